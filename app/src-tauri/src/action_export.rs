@@ -0,0 +1,355 @@
+//! Action item export
+//!
+//! Serializes action items as an iCalendar file of VTODOs, a markdown checklist
+//! or CSV so they can be imported into task managers. Free-form deadlines
+//! ("next Friday", "2024-03-15") are resolved relative to when the item was
+//! created; ones that can't be parsed are kept as text.
+
+use crate::knowledge_base::month_number;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Output format for `KnowledgeBase::export_action_items`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionExportFormat {
+    ICal,
+    Markdown,
+    Csv,
+}
+
+impl ActionExportFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.trim().to_lowercase().as_str() {
+            "ical" | "ics" | "vtodo" => Ok(Self::ICal),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "csv" => Ok(Self::Csv),
+            other => Err(format!("Unknown action item export format '{}' (expected ical, markdown or csv)", other)),
+        }
+    }
+}
+
+/// An action item with its meeting title, as exported
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportActionItem {
+    pub id: String,
+    pub text: String,
+    pub assignee: Option<String>,
+    pub deadline: Option<String>,
+    pub status: String,
+    pub meeting_title: Option<String>,
+    pub created_at: u64,
+}
+
+impl ExportActionItem {
+    /// Concrete due date, resolving relative deadlines against the creation date
+    pub fn due_date(&self) -> Option<NaiveDate> {
+        let deadline = self.deadline.as_deref()?;
+        let created = chrono::DateTime::from_timestamp_millis(self.created_at as i64)?.date_naive();
+        parse_deadline(deadline, created)
+    }
+
+    fn is_done(&self) -> bool {
+        self.status == "done"
+    }
+}
+
+/// Resolve a free-form deadline to a date, relative to `reference` (the day it was said)
+///
+/// Handles ISO and US dates, "today"/"tomorrow", weekdays ("Friday", "next Friday"),
+/// "next week", "in 3 days", "end of week/month" and "March 15" / "15 March".
+pub fn parse_deadline(deadline: &str, reference: NaiveDate) -> Option<NaiveDate> {
+    let text = deadline.trim().to_lowercase();
+    let text = text.trim_end_matches('.');
+    let text = ["by ", "before ", "on ", "due "].iter()
+        .find_map(|prefix| text.strip_prefix(prefix))
+        .unwrap_or(text)
+        .trim();
+
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Some(date);
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(text, "%m/%d/%Y") {
+        return Some(date);
+    }
+
+    match text {
+        "today" | "eod" | "end of day" => return Some(reference),
+        "tomorrow" => return Some(reference + Duration::days(1)),
+        "next week" => return Some(reference + Duration::days(7)),
+        "end of week" | "end of the week" | "eow" | "this week" => {
+            return Some(next_weekday(reference, Weekday::Fri, true));
+        }
+        "end of month" | "end of the month" | "eom" | "this month" => {
+            return last_day_of_month(reference.year(), reference.month());
+        }
+        _ => {}
+    }
+
+    let words: Vec<&str> = text.split_whitespace().collect();
+    match words.as_slice() {
+        ["in", n, unit] | [n, unit, "from", "now"] => {
+            let n: i64 = n.parse().ok()?;
+            let days = match unit.trim_end_matches('s') {
+                "day" => n,
+                "week" => n * 7,
+                _ => return None,
+            };
+            Some(reference + Duration::days(days))
+        }
+        [day] => day.parse::<Weekday>().ok().map(|d| next_weekday(reference, d, true)),
+        ["this", day] => day.parse::<Weekday>().ok().map(|d| next_weekday(reference, d, true)),
+        ["next", day] => day.parse::<Weekday>().ok().map(|d| next_weekday(reference, d, false)),
+        [a, b] => month_day(a, b, reference).or_else(|| month_day(b, a, reference)),
+        _ => None,
+    }
+}
+
+/// The next `day` on or after `reference` (`include_today`) or strictly after it
+fn next_weekday(reference: NaiveDate, day: Weekday, include_today: bool) -> NaiveDate {
+    let current = reference.weekday().num_days_from_monday() as i64;
+    let target = day.num_days_from_monday() as i64;
+    let mut ahead = (target - current).rem_euclid(7);
+    if ahead == 0 && !include_today {
+        ahead = 7;
+    }
+    reference + Duration::days(ahead)
+}
+
+fn last_day_of_month(year: i32, month: u32) -> Option<NaiveDate> {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1).map(|d| d - Duration::days(1))
+}
+
+/// "march" + "15th" -> the next March 15 on or after `reference`
+fn month_day(month: &str, day: &str, reference: NaiveDate) -> Option<NaiveDate> {
+    let month = month_number(month.trim_end_matches(['.', ',']))?;
+    let day: u32 = day.trim_end_matches(|c: char| c.is_alphabetic() || c == ',').parse().ok()?;
+    let date = NaiveDate::from_ymd_opt(reference.year(), month, day)?;
+    if date < reference {
+        NaiveDate::from_ymd_opt(reference.year() + 1, month, day)
+    } else {
+        Some(date)
+    }
+}
+
+/// Serialize action items in the requested format
+pub fn render(items: &[ExportActionItem], format: ActionExportFormat) -> String {
+    match format {
+        ActionExportFormat::ICal => to_ical(items),
+        ActionExportFormat::Markdown => to_markdown(items),
+        ActionExportFormat::Csv => to_csv(items),
+    }
+}
+
+pub fn to_ical(items: &[ExportActionItem]) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Second Brain//Action Items//EN".to_string(),
+    ];
+
+    for item in items {
+        let created = chrono::DateTime::from_timestamp_millis(item.created_at as i64).unwrap_or_default();
+        lines.push("BEGIN:VTODO".to_string());
+        lines.push(format!("UID:{}@second-brain", ical_escape(&item.id)));
+        lines.push(format!("DTSTAMP:{}", created.format("%Y%m%dT%H%M%SZ")));
+        lines.push(format!("SUMMARY:{}", ical_escape(&item.text)));
+        let status = match item.status.as_str() {
+            "done" => "COMPLETED",
+            "in_progress" => "IN-PROCESS",
+            _ => "NEEDS-ACTION",
+        };
+        lines.push(format!("STATUS:{}", status));
+
+        let mut notes = Vec::new();
+        match (item.due_date(), item.deadline.as_deref()) {
+            (Some(due), _) => lines.push(format!("DUE;VALUE=DATE:{}", due.format("%Y%m%d"))),
+            (None, Some(deadline)) => notes.push(format!("Deadline: {}", deadline)),
+            (None, None) => {}
+        }
+        if let Some(assignee) = item.assignee.as_deref().filter(|a| !a.trim().is_empty()) {
+            // Assignees have no email address; invalid:nomail is the conventional placeholder
+            lines.push(format!("ATTENDEE;CN={};ROLE=REQ-PARTICIPANT:invalid:nomail", ical_param(assignee)));
+        }
+        if let Some(title) = &item.meeting_title {
+            notes.push(format!("From meeting: {}", title));
+        }
+        if !notes.is_empty() {
+            lines.push(format!("DESCRIPTION:{}", ical_escape(&notes.join("\n"))));
+        }
+        lines.push("END:VTODO".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines.iter().map(|l| fold_ical_line(l)).collect::<Vec<_>>().join("\r\n") + "\r\n"
+}
+
+pub fn to_markdown(items: &[ExportActionItem]) -> String {
+    let mut out = String::from("# Action Items\n\n");
+    for item in items {
+        let mut details = Vec::new();
+        if let Some(assignee) = item.assignee.as_deref().filter(|a| !a.trim().is_empty()) {
+            details.push(format!("@{}", assignee));
+        }
+        match (item.due_date(), item.deadline.as_deref()) {
+            (Some(due), _) => details.push(format!("due {}", due)),
+            (None, Some(deadline)) => details.push(format!("due: {}", deadline)),
+            (None, None) => {}
+        }
+        if let Some(title) = &item.meeting_title {
+            details.push(format!("from _{}_", title));
+        }
+
+        let check = if item.is_done() { "x" } else { " " };
+        if details.is_empty() {
+            out.push_str(&format!("- [{}] {}\n", check, item.text));
+        } else {
+            out.push_str(&format!("- [{}] {} ({})\n", check, item.text, details.join(", ")));
+        }
+    }
+    out
+}
+
+pub fn to_csv(items: &[ExportActionItem]) -> String {
+    let mut out = String::from("text,assignee,deadline,due_date,status,meeting\n");
+    for item in items {
+        let due = item.due_date().map(|d| d.to_string()).unwrap_or_default();
+        let fields = [
+            item.text.as_str(),
+            item.assignee.as_deref().unwrap_or(""),
+            item.deadline.as_deref().unwrap_or(""),
+            due.as_str(),
+            item.status.as_str(),
+            item.meeting_title.as_deref().unwrap_or(""),
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escape a TEXT property value (RFC 5545 3.3.11)
+fn ical_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Quote a parameter value when it contains characters that end a parameter
+fn ical_param(value: &str) -> String {
+    let value = value.replace('"', "'");
+    if value.contains([':', ';', ',']) {
+        format!("\"{}\"", value)
+    } else {
+        value
+    }
+}
+
+/// Fold lines longer than 75 octets, continuing with a leading space
+fn fold_ical_line(line: &str) -> String {
+    let mut out = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        let len = c.len_utf8();
+        if width + len > 75 {
+            out.push_str("\r\n ");
+            width = 1;
+        }
+        out.push(c);
+        width += len;
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn item(text: &str, assignee: Option<&str>, deadline: Option<&str>, status: &str) -> ExportActionItem {
+        ExportActionItem {
+            id: "abc".to_string(),
+            text: text.to_string(),
+            assignee: assignee.map(|a| a.to_string()),
+            deadline: deadline.map(|d| d.to_string()),
+            status: status.to_string(),
+            meeting_title: Some("Weekly sync".to_string()),
+            // 2024-03-13 (a Wednesday) 12:00 UTC
+            created_at: 1_710_331_200_000,
+        }
+    }
+
+    #[test]
+    fn test_parse_deadline_forms() {
+        let wed = date(2024, 3, 13);
+        assert_eq!(parse_deadline("2024-03-15", wed), Some(date(2024, 3, 15)));
+        assert_eq!(parse_deadline("3/15/2024", wed), Some(date(2024, 3, 15)));
+        assert_eq!(parse_deadline("Tomorrow", wed), Some(date(2024, 3, 14)));
+        assert_eq!(parse_deadline("by Friday", wed), Some(date(2024, 3, 15)));
+        assert_eq!(parse_deadline("next Wednesday", wed), Some(date(2024, 3, 20)));
+        assert_eq!(parse_deadline("in 2 weeks", wed), Some(date(2024, 3, 27)));
+        assert_eq!(parse_deadline("end of month", wed), Some(date(2024, 3, 31)));
+        assert_eq!(parse_deadline("March 20th", wed), Some(date(2024, 3, 20)));
+        assert_eq!(parse_deadline("1 Feb", wed), Some(date(2025, 2, 1)));
+        assert_eq!(parse_deadline("after the launch", wed), None);
+    }
+
+    #[test]
+    fn test_ical_vtodo_with_due_date_and_attendee() {
+        let ics = to_ical(&[item("Send deck, v2", Some("Alice"), Some("next Friday"), "open")]);
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ics.contains("SUMMARY:Send deck\\, v2\r\n"));
+        assert!(ics.contains("DUE;VALUE=DATE:20240315\r\n"));
+        assert!(ics.contains("ATTENDEE;CN=Alice;ROLE=REQ-PARTICIPANT:invalid:nomail\r\n"));
+        assert!(ics.contains("STATUS:NEEDS-ACTION\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_unparseable_deadline_kept_as_note() {
+        let ics = to_ical(&[item("Ship it", None, Some("after the launch"), "done")]);
+        assert!(!ics.contains("DUE"));
+        assert!(ics.contains("DESCRIPTION:Deadline: after the launch\\nFrom meeting: Weekly sync"));
+        assert!(ics.contains("STATUS:COMPLETED"));
+
+        let md = to_markdown(&[item("Ship it", None, Some("after the launch"), "done")]);
+        assert!(md.contains("- [x] Ship it (due: after the launch, from _Weekly sync_)"));
+    }
+
+    #[test]
+    fn test_csv_quotes_fields() {
+        let csv = to_csv(&[item("Review \"PR\", then merge", Some("Bob"), Some("2024-03-15"), "open")]);
+        assert_eq!(
+            csv.lines().nth(1),
+            Some("\"Review \"\"PR\"\", then merge\",Bob,2024-03-15,2024-03-15,open,Weekly sync")
+        );
+    }
+
+    #[test]
+    fn test_long_ical_lines_fold() {
+        let folded = fold_ical_line(&format!("SUMMARY:{}", "x".repeat(100)));
+        assert!(folded.split("\r\n").all(|l| l.len() <= 75));
+        assert_eq!(folded.replace("\r\n ", ""), format!("SUMMARY:{}", "x".repeat(100)));
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(ActionExportFormat::parse("ICS").unwrap(), ActionExportFormat::ICal);
+        assert_eq!(ActionExportFormat::parse("markdown").unwrap(), ActionExportFormat::Markdown);
+        assert!(ActionExportFormat::parse("xlsx").is_err());
+    }
+}
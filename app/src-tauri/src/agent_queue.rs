@@ -9,6 +9,7 @@
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock, Mutex};
 use serde::{Deserialize, Serialize};
+use crate::llm_agent::Source;
 
 /// Job types that agents can process
 #[derive(Debug, Clone)]
@@ -58,7 +59,7 @@ pub struct RealtimeSuggestionResult {
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AnswerResult {
     pub answer: String,
-    pub sources: Vec<String>,
+    pub sources: Vec<Source>,
     pub error: Option<String>,
 }
 
@@ -109,6 +110,7 @@ pub struct QueueStats {
     pub processed_jobs: u64,
     pub failed_jobs: u64,
     pub workers_active: usize,
+    pub draining: bool,      // Shutdown requested; no new jobs are accepted
 }
 
 /// The main job queue that distributes work to agent workers
@@ -130,6 +132,9 @@ impl AgentQueue {
     pub async fn submit(&self, job: AgentJob) -> Result<(), String> {
         {
             let mut stats = self.stats.write().await;
+            if stats.draining {
+                return Err("Agent queue is shutting down".to_string());
+            }
             stats.pending_jobs += 1;
         }
 
@@ -142,6 +147,31 @@ impl AgentQueue {
         self.stats.read().await.clone()
     }
 
+    /// Stop accepting jobs and queue one `Shutdown` per worker
+    ///
+    /// Shutdown jobs sit behind anything already queued, so workers finish
+    /// the backlog before they exit.
+    pub async fn begin_shutdown(&self, num_workers: usize) -> Result<(), String> {
+        {
+            let mut stats = self.stats.write().await;
+            if stats.draining {
+                return Ok(());
+            }
+            stats.draining = true;
+        }
+
+        for _ in 0..num_workers {
+            self.job_tx.send(AgentJob::Shutdown).await
+                .map_err(|e| format!("Failed to send shutdown signal: {}", e))?;
+        }
+        Ok(())
+    }
+
+    /// Whether `begin_shutdown` has been called
+    pub async fn is_draining(&self) -> bool {
+        self.stats.read().await.draining
+    }
+
     /// Mark a job as completed
     pub async fn mark_completed(&self) {
         let mut stats = self.stats.write().await;
@@ -164,7 +194,6 @@ impl AgentQueue {
 /// Worker pool that processes jobs from the queue
 pub struct WorkerPool {
     handles: Vec<tokio::task::JoinHandle<()>>,
-    shutdown_tx: Option<mpsc::Sender<()>>,
 }
 
 impl WorkerPool {
@@ -180,7 +209,6 @@ impl WorkerPool {
         Fut: std::future::Future<Output = ()> + Send,
     {
         let job_rx = Arc::new(Mutex::new(job_rx));
-        let (shutdown_tx, _shutdown_rx) = mpsc::channel::<()>(1);
         let mut handles = Vec::with_capacity(num_workers);
 
         for worker_id in 0..num_workers {
@@ -228,16 +256,24 @@ impl WorkerPool {
             handles.push(handle);
         }
 
-        Self {
-            handles,
-            shutdown_tx: Some(shutdown_tx),
-        }
+        Self { handles }
     }
 
-    /// Shutdown all workers gracefully
-    pub async fn shutdown(mut self) {
-        drop(self.shutdown_tx.take());
+    /// Number of workers in the pool
+    pub fn worker_count(&self) -> usize {
+        self.handles.len()
+    }
 
+    /// Shutdown all workers gracefully: broadcast `Shutdown` through the queue
+    /// and wait for each worker to finish its current job
+    pub async fn shutdown(self, queue: &AgentQueue) -> Result<(), String> {
+        queue.begin_shutdown(self.handles.len()).await?;
+        self.join().await;
+        Ok(())
+    }
+
+    /// Wait for every worker to stop
+    pub async fn join(self) {
         for handle in self.handles {
             let _ = handle.await;
         }
@@ -271,4 +307,36 @@ mod tests {
         let job = rx.recv().await;
         assert!(matches!(job, Some(AgentJob::AnswerQuestion { .. })));
     }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_queued_jobs() {
+        let (queue, rx) = AgentQueue::new(10);
+        let queue = Arc::new(queue);
+        let stats = Arc::new(RwLock::new(QueueStats::default()));
+
+        let (resp_tx, _resp_rx) = response_channel();
+        queue.submit(AgentJob::AnswerQuestion {
+            question: "Queued before shutdown".to_string(),
+            context: None,
+            response_tx: resp_tx,
+        }).await.unwrap();
+
+        let pool = WorkerPool::start(2, rx, stats.clone(), |_job, stats| async move {
+            stats.write().await.processed_jobs += 1;
+        });
+        assert_eq!(pool.worker_count(), 2);
+
+        pool.shutdown(&queue).await.unwrap();
+
+        assert_eq!(stats.read().await.processed_jobs, 1);
+        assert!(queue.is_draining().await);
+
+        let (resp_tx, _resp_rx) = response_channel();
+        let rejected = queue.submit(AgentJob::AnswerQuestion {
+            question: "Too late".to_string(),
+            context: None,
+            response_tx: resp_tx,
+        }).await;
+        assert!(rejected.is_err());
+    }
 }
@@ -129,7 +129,7 @@ async fn process_realtime_suggestions(
             error: None,
         },
         Err(e) => RealtimeSuggestionResult {
-            error: Some(e),
+            error: Some(e.to_string()),
             ..Default::default()
         },
     }
@@ -165,14 +165,14 @@ async fn process_answer_question(
         None => question.to_string(),
     };
 
-    match assistant.ask(&full_context, kb_arc).await {
-        Ok(answer) => AnswerResult {
-            answer,
-            sources: vec![], // TODO: Track sources from KB lookups
+    match assistant.ask_with_sources(&full_context, kb_arc).await {
+        Ok(result) => AnswerResult {
+            answer: result.answer,
+            sources: result.sources,
             error: None,
         },
         Err(e) => AnswerResult {
-            error: Some(e),
+            error: Some(e.to_string()),
             ..Default::default()
         },
     }
@@ -272,7 +272,7 @@ async fn process_meeting_highlights(
             error: None,
         },
         Err(e) => HighlightsResult {
-            error: Some(e),
+            error: Some(e.to_string()),
             ..Default::default()
         },
     }
@@ -0,0 +1,227 @@
+//! Keyword alerts on live transcripts
+//!
+//! The `meeting_alerts` setting is a JSON list whose entries are either a plain
+//! keyword (`"budget"`) or a `MeetingAlert` object. Every alert compiles to one
+//! regex, so checking a final transcript is a handful of regex scans. Alerts
+//! tied to an entity label (e.g. a person's name) only run the entity engine
+//! when one of the name's words already appears in the text.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::user_store::default_true;
+
+/// A configured alert
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeetingAlert {
+    /// Word or phrase to listen for (a regex when `regex` is set)
+    pub keyword: String,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default = "default_true")]
+    pub whole_word: bool,
+    /// Only fire for an entity with this label (e.g. "person"); any single word of
+    /// the keyword matches, so "Dana" fires for a "Dana Whitfield" alert
+    #[serde(default)]
+    pub entity: Option<String>,
+}
+
+/// Setting entries may be bare keywords or full alerts
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AlertEntry {
+    Keyword(String),
+    Alert(MeetingAlert),
+}
+
+impl From<AlertEntry> for MeetingAlert {
+    fn from(entry: AlertEntry) -> Self {
+        match entry {
+            AlertEntry::Keyword(keyword) => MeetingAlert {
+                keyword,
+                regex: false,
+                case_sensitive: false,
+                whole_word: true,
+                entity: None,
+            },
+            AlertEntry::Alert(alert) => alert,
+        }
+    }
+}
+
+/// An alert that fired on a transcript
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlertMatch {
+    pub keyword: String,
+    pub matched_text: String,
+    pub entity_label: Option<String>,
+}
+
+struct CompiledAlert {
+    keyword: String,
+    regex: Regex,
+    entity: Option<String>,
+}
+
+impl CompiledAlert {
+    fn new(alert: &MeetingAlert) -> Result<Self, String> {
+        let keyword = alert.keyword.trim();
+        if keyword.is_empty() {
+            return Err("Alert keyword cannot be empty".to_string());
+        }
+
+        let pattern = if alert.regex {
+            keyword.to_string()
+        } else if alert.entity.is_some() {
+            keyword.split_whitespace()
+                .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+                .filter(|word| !word.is_empty())
+                .map(regex::escape)
+                .collect::<Vec<_>>()
+                .join("|")
+        } else {
+            regex::escape(keyword)
+        };
+        if pattern.is_empty() {
+            return Err(format!("Alert '{}' has no words to match", keyword));
+        }
+
+        let pattern = if alert.whole_word { format!(r"\b(?:{})\b", pattern) } else { format!("(?:{})", pattern) };
+        let pattern = if alert.case_sensitive { pattern } else { format!("(?i){}", pattern) };
+        let regex = Regex::new(&pattern)
+            .map_err(|e| format!("Invalid alert pattern '{}': {}", keyword, e))?;
+
+        Ok(Self {
+            keyword: keyword.to_string(),
+            regex,
+            entity: alert.entity.as_ref()
+                .map(|label| label.trim().to_lowercase())
+                .filter(|label| !label.is_empty()),
+        })
+    }
+}
+
+/// Compiled `meeting_alerts`
+#[derive(Default)]
+pub struct AlertMatcher {
+    alerts: Vec<CompiledAlert>,
+}
+
+impl AlertMatcher {
+    pub fn new(alerts: &[MeetingAlert]) -> Result<Self, String> {
+        let alerts = alerts.iter()
+            .map(CompiledAlert::new)
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { alerts })
+    }
+
+    /// Build from the `meeting_alerts` setting (JSON list; empty = no alerts)
+    pub fn from_config(alerts_json: &str) -> Result<Self, String> {
+        if alerts_json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let entries: Vec<AlertEntry> = serde_json::from_str(alerts_json)
+            .map_err(|e| format!("Invalid meeting_alerts setting: {}", e))?;
+        let alerts: Vec<MeetingAlert> = entries.into_iter().map(MeetingAlert::from).collect();
+        Self::new(&alerts)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.alerts.is_empty()
+    }
+
+    /// Keyword alerts that fire on `text`; never needs the entity engine
+    pub fn keyword_matches(&self, text: &str) -> Vec<AlertMatch> {
+        self.alerts.iter()
+            .filter(|alert| alert.entity.is_none())
+            .filter_map(|alert| {
+                alert.regex.find(text).map(|found| AlertMatch {
+                    keyword: alert.keyword.clone(),
+                    matched_text: found.as_str().to_string(),
+                    entity_label: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Whether an entity alert's words appear in `text`, so entities are worth extracting
+    pub fn wants_entities(&self, text: &str) -> bool {
+        self.alerts.iter().any(|alert| alert.entity.is_some() && alert.regex.is_match(text))
+    }
+
+    /// Entity alerts that fire on `text`, given the `(label, text)` entities found in it
+    pub fn entity_matches(&self, text: &str, entities: &[(String, String)]) -> Vec<AlertMatch> {
+        let mut matches = Vec::new();
+        for alert in &self.alerts {
+            let Some(label) = &alert.entity else {
+                continue;
+            };
+            if !alert.regex.is_match(text) {
+                continue;
+            }
+            let entity = entities.iter()
+                .find(|(entity_label, name)| entity_label.eq_ignore_ascii_case(label) && alert.regex.is_match(name));
+            if let Some((_, name)) = entity {
+                matches.push(AlertMatch {
+                    keyword: alert.keyword.clone(),
+                    matched_text: name.clone(),
+                    entity_label: Some(label.clone()),
+                });
+            }
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_alerts() {
+        let matcher = AlertMatcher::from_config(
+            r#"["budget", {"keyword": "Q[34] plan(s)?", "regex": true}, {"keyword": "API", "case_sensitive": true, "whole_word": false}]"#,
+        ).unwrap();
+
+        let hits = matcher.keyword_matches("The Budget for the q3 plans is tight");
+        let keywords: Vec<&str> = hits.iter().map(|m| m.keyword.as_str()).collect();
+        assert_eq!(keywords, vec!["budget", "Q[34] plan(s)?"]);
+        assert_eq!(hits[0].matched_text, "Budget");
+
+        assert!(matcher.keyword_matches("budgets were discussed").is_empty());
+        assert_eq!(matcher.keyword_matches("the RAPIDS team").len(), 1);
+        assert!(matcher.keyword_matches("an api change").is_empty());
+        assert!(!matcher.wants_entities("The Budget for the q3 plans is tight"));
+
+        assert!(AlertMatcher::from_config("").unwrap().is_empty());
+        assert!(AlertMatcher::from_config(r#"[""]"#).is_err());
+        assert!(AlertMatcher::from_config(r#"[{"keyword": "(", "regex": true}]"#).is_err());
+    }
+
+    #[test]
+    fn test_entity_alerts() {
+        let matcher = AlertMatcher::from_config(r#"[{"keyword": "Dana Whitfield", "entity": "person"}]"#).unwrap();
+
+        // No name word in the text: the entity engine is never asked
+        assert!(!matcher.wants_entities("let's review the roadmap"));
+        assert!(matcher.keyword_matches("dana said the launch slips").is_empty());
+
+        assert!(matcher.wants_entities("dana said the launch slips"));
+        let hits = matcher.entity_matches("dana said the launch slips", &[
+            ("person".to_string(), "Dana".to_string()),
+        ]);
+        assert_eq!(hits, vec![AlertMatch {
+            keyword: "Dana Whitfield".to_string(),
+            matched_text: "Dana".to_string(),
+            entity_label: Some("person".to_string()),
+        }]);
+
+        // The word appears but not as a person
+        let hits = matcher.entity_matches("Whitfield Street office", &[
+            ("location".to_string(), "Whitfield Street".to_string()),
+        ]);
+        assert!(hits.is_empty());
+    }
+}
@@ -27,6 +27,22 @@ impl Default for Emotion {
     }
 }
 
+impl Emotion {
+    /// Lowercase name stored on segments; None when the recognizer couldn't tell
+    pub fn label(&self) -> Option<&'static str> {
+        match self {
+            Emotion::Neutral => Some("neutral"),
+            Emotion::Happy => Some("happy"),
+            Emotion::Sad => Some("sad"),
+            Emotion::Angry => Some("angry"),
+            Emotion::Fearful => Some("fearful"),
+            Emotion::Disgusted => Some("disgusted"),
+            Emotion::Surprised => Some("surprised"),
+            Emotion::Unknown => None,
+        }
+    }
+}
+
 /// Detected audio events from SenseVoice
 #[derive(Debug, Clone, serde::Serialize, PartialEq)]
 pub enum AudioEvent {
@@ -38,12 +54,17 @@ pub enum AudioEvent {
     Other(String),
 }
 
+/// A word with its start/end in ms, relative to the start of the speech segment
+pub type WordTiming = (String, u64, u64);
+
 /// Transcription result with emotion and events
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct TranscriptionResult {
     pub text: String,
     pub source: String,           // "microphone" or "system"
     pub timestamp_ms: u64,
+    pub duration_ms: u64,         // Length of the transcribed speech audio
+    pub word_timings: Vec<WordTiming>, // Per-word offsets from the segment start
     pub is_final: bool,
     pub language: String,         // Detected language (zh/en/ja/ko/yue)
     pub emotion: Emotion,         // Detected emotion
@@ -52,10 +73,14 @@ pub struct TranscriptionResult {
     pub turn_confidence: f32,     // Confidence of turn completion (0-1)
 }
 
+/// How much new speech accumulates between interim hypotheses
+pub const INTERIM_INTERVAL_MS: u64 = 1000;
+
 /// ASR configuration
 pub struct AsrConfig {
     pub models_dir: PathBuf,
     pub sample_rate: u32,
+    pub interim_results: bool,    // Emit `is_final: false` hypotheses while speech is ongoing
 }
 
 impl Default for AsrConfig {
@@ -68,6 +93,7 @@ impl Default for AsrConfig {
         Self {
             models_dir,
             sample_rate: 16000,
+            interim_results: true,
         }
     }
 }
@@ -79,6 +105,51 @@ pub struct AsrEngine {
     mic_vad: Option<SileroVad>,
     system_vad: Option<SileroVad>,
     recognizer: Option<SenseVoiceRecognizer>,
+    // Per-source state carried between chunks
+    mic_stream: StreamState,
+    system_stream: StreamState,
+}
+
+/// Per-source state carried between audio chunks
+#[derive(Default)]
+struct StreamState {
+    // Audio of the speech segment still in progress
+    interim: InterimBuffer,
+    // Chunk overlap heard just before the VAD last went quiet -> speech
+    pending_pre_roll: Vec<f32>,
+    // Pre-roll for the speech segment in progress, prepended when it is transcribed
+    segment_pre_roll: Option<Vec<f32>>,
+    // Last final text, to drop words the pre-roll made SenseVoice hear twice
+    last_text: String,
+}
+
+/// Speech heard since the VAD last reported speech start, for interim hypotheses
+#[derive(Default)]
+pub struct InterimBuffer {
+    samples: Vec<f32>,
+    emitted_len: usize,
+}
+
+impl InterimBuffer {
+    /// Add audio heard while the VAD says speech is ongoing
+    /// Returns true once `interval` samples have arrived since the last hypothesis.
+    pub fn push(&mut self, samples: &[f32], interval: usize) -> bool {
+        self.samples.extend_from_slice(samples);
+        if self.samples.len() - self.emitted_len >= interval {
+            self.emitted_len = self.samples.len();
+            return true;
+        }
+        false
+    }
+
+    pub fn samples(&self) -> &[f32] {
+        &self.samples
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.emitted_len = 0;
+    }
 }
 
 impl AsrEngine {
@@ -89,6 +160,8 @@ impl AsrEngine {
             mic_vad: None,
             system_vad: None,
             recognizer: None,
+            mic_stream: StreamState::default(),
+            system_stream: StreamState::default(),
         }
     }
 
@@ -161,34 +234,50 @@ impl AsrEngine {
     }
 
     /// Process audio from microphone
-    pub fn process_microphone(&mut self, samples: &[f32], sample_rate: u32) -> Option<TranscriptionResult> {
-        self.process_audio(samples, sample_rate, "microphone")
+    /// `overlap` is the tail of the previous chunk (see `ChunkOverlap`); it is not fed to the VAD again.
+    pub fn process_microphone(&mut self, samples: &[f32], overlap: &[f32], sample_rate: u32) -> Option<TranscriptionResult> {
+        self.process_audio(samples, overlap, sample_rate, "microphone")
     }
 
     /// Process audio from system (guests)
-    pub fn process_system(&mut self, samples: &[f32], sample_rate: u32) -> Option<TranscriptionResult> {
-        self.process_audio(samples, sample_rate, "system")
+    pub fn process_system(&mut self, samples: &[f32], overlap: &[f32], sample_rate: u32) -> Option<TranscriptionResult> {
+        self.process_audio(samples, overlap, sample_rate, "system")
     }
 
     /// Process audio and return transcription when speech segment ends
-    fn process_audio(&mut self, samples: &[f32], sample_rate: u32, source: &str) -> Option<TranscriptionResult> {
+    /// While speech is still ongoing an interim (`is_final: false`) hypothesis of the
+    /// segment so far is returned every `INTERIM_INTERVAL_MS`; the final one replaces it.
+    ///
+    /// The VAD only reports speech once it is sure, so the first word of a segment can
+    /// lose its onset. The overlap of the chunk in which speech started is prepended as
+    /// pre-roll, and words it repeats from the previous segment are dropped.
+    fn process_audio(&mut self, samples: &[f32], overlap: &[f32], sample_rate: u32, source: &str) -> Option<TranscriptionResult> {
         // Get the appropriate VAD based on source
-        let vad = if source == "microphone" {
-            self.mic_vad.as_mut()?
+        let (vad, stream) = if source == "microphone" {
+            (self.mic_vad.as_mut()?, &mut self.mic_stream)
         } else {
-            self.system_vad.as_mut()?
+            (self.system_vad.as_mut()?, &mut self.system_stream)
         };
         let recognizer = self.recognizer.as_mut()?;
+        let asr_rate = self.config.sample_rate;
 
         // Resample if needed (silent - this runs on every audio chunk)
-        let resampled = if sample_rate != self.config.sample_rate {
-            resample(samples, sample_rate, self.config.sample_rate)
+        let (resampled, overlap) = if sample_rate != asr_rate {
+            (resample(samples, sample_rate, asr_rate), resample(overlap, sample_rate, asr_rate))
         } else {
-            samples.to_vec()
+            (samples.to_vec(), overlap.to_vec())
         };
 
+        if !vad.is_speech() {
+            stream.pending_pre_roll = overlap;
+        }
+
         // Feed samples to VAD
-        vad.accept_waveform(resampled);
+        vad.accept_waveform(resampled.clone());
+
+        if stream.segment_pre_roll.is_none() && (vad.is_speech() || !vad.is_empty()) {
+            stream.segment_pre_roll = Some(std::mem::take(&mut stream.pending_pre_roll));
+        }
 
         // Check for completed speech segments
         let mut result: Option<TranscriptionResult> = None;
@@ -203,35 +292,36 @@ impl AsrEngine {
             let segment = vad.front();
             let speech_samples = segment.samples.clone();
             vad.pop();
+            stream.interim.clear();
+            let pre_roll = stream.segment_pre_roll.take().unwrap_or_default();
 
             // Only transcribe if segment has enough audio (> 250ms)
-            if speech_samples.len() > self.config.sample_rate as usize / 4 {
-                // Transcribe with SenseVoice
-                let sensevoice_result = recognizer.transcribe(self.config.sample_rate, &speech_samples);
-
-                // Parse the raw text to extract emotion, events, and clean text
-                let parsed = parse_sensevoice_output(&sensevoice_result.text);
-
-                if !parsed.text.trim().is_empty() {
-                    result = Some(TranscriptionResult {
-                        text: parsed.text,
-                        source: source.to_string(),
-                        timestamp_ms: std::time::SystemTime::now()
-                            .duration_since(std::time::UNIX_EPOCH)
-                            .unwrap_or_default()
-                            .as_millis() as u64,
-                        is_final: true,
-                        language: sensevoice_result.lang.clone(),
-                        emotion: parsed.emotion,
-                        audio_events: parsed.events,
-                        is_turn_complete: false,  // Will be set by Smart Turn
-                        turn_confidence: 0.0,
-                    });
+            if speech_samples.len() > asr_rate as usize / 4 {
+                let audio = [pre_roll.as_slice(), speech_samples.as_slice()].concat();
+                if let Some(mut transcription) = recognize(recognizer, asr_rate, &audio, source, true) {
+                    if !pre_roll.is_empty() && !drop_repeated_words(&mut transcription, &stream.last_text) {
+                        continue;
+                    }
+                    stream.last_text = transcription.text.clone();
+                    result = Some(transcription);
                 }
             }
         }
 
-        result
+        if result.is_some() || !self.config.interim_results {
+            return result;
+        }
+
+        if !vad.is_speech() {
+            stream.interim.clear();
+            return None;
+        }
+
+        let interval = (asr_rate as u64 * INTERIM_INTERVAL_MS / 1000) as usize;
+        if stream.interim.push(&resampled, interval) {
+            return recognize(recognizer, asr_rate, stream.interim.samples(), source, false);
+        }
+        None
     }
 
     /// Reset the engine state
@@ -242,9 +332,123 @@ impl AsrEngine {
         if let Some(vad) = self.system_vad.as_mut() {
             vad.clear();
         }
+        self.mic_stream = StreamState::default();
+        self.system_stream = StreamState::default();
+    }
+}
+
+/// Most words de-duplicated between a segment's start and the previous segment's end
+const MAX_OVERLAP_WORDS: usize = 4;
+/// Fewest repeated words treated as pre-roll; a single shared word ("the", "so") is usually said twice
+const MIN_OVERLAP_WORDS: usize = 2;
+
+/// Lowercased word without surrounding punctuation, for overlap matching
+fn normalize_word(word: &str) -> String {
+    word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase()
+}
+
+/// How many leading words of `current` repeat the trailing words of `previous`
+pub fn overlapping_words(previous: &str, current: &str) -> usize {
+    let previous: Vec<String> = previous.split_whitespace().map(normalize_word).collect();
+    let current: Vec<String> = current.split_whitespace().map(normalize_word).collect();
+    let max = MAX_OVERLAP_WORDS.min(previous.len()).min(current.len());
+    (1..=max).rev()
+        .find(|&n| previous[previous.len() - n..] == current[..n])
+        .unwrap_or(0)
+}
+
+/// Strip words the pre-roll repeated from `previous`; false if nothing new is left
+/// Overlaps shorter than `MIN_OVERLAP_WORDS` are kept as spoken.
+fn drop_repeated_words(transcription: &mut TranscriptionResult, previous: &str) -> bool {
+    let repeated = overlapping_words(previous, &transcription.text);
+    if repeated < MIN_OVERLAP_WORDS {
+        return true;
+    }
+    let words: Vec<&str> = transcription.text.split_whitespace().collect();
+    if repeated >= words.len() {
+        return false;
+    }
+    transcription.text = words[repeated..].join(" ");
+    if transcription.word_timings.len() == words.len() {
+        transcription.word_timings.drain(..repeated);
+    }
+    true
+}
+
+/// Carries the last few ms of each emitted chunk into the next one
+/// The capture thread clears its buffer on every emit; the tail kept here is sent
+/// alongside the next chunk so the ASR engine can recover a word cut at the boundary.
+#[derive(Default)]
+pub struct ChunkOverlap {
+    tail: Vec<f32>,
+    sample_rate: u32,
+}
+
+impl ChunkOverlap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the overlap to send with `chunk`, then keep the last `overlap_ms` of audio
+    pub fn next(&mut self, chunk: &[f32], sample_rate: u32, overlap_ms: u64) -> Vec<f32> {
+        if sample_rate != self.sample_rate {
+            self.tail.clear();
+            self.sample_rate = sample_rate;
+        }
+        let overlap = std::mem::take(&mut self.tail);
+
+        let keep = (sample_rate as u64 * overlap_ms / 1000) as usize;
+        if keep > 0 {
+            let mut joined = [overlap.as_slice(), chunk].concat();
+            let start = joined.len().saturating_sub(keep);
+            self.tail = joined.split_off(start);
+        }
+        overlap
     }
 }
 
+/// Transcribe one run of speech, or None if SenseVoice heard no words
+fn recognize(
+    recognizer: &mut SenseVoiceRecognizer,
+    sample_rate: u32,
+    speech_samples: &[f32],
+    source: &str,
+    is_final: bool,
+) -> Option<TranscriptionResult> {
+    // Transcribe with SenseVoice
+    let sensevoice_result = recognizer.transcribe(sample_rate, speech_samples);
+
+    // Parse the raw text to extract emotion, events, and clean text
+    let parsed = parse_sensevoice_output(&sensevoice_result.text);
+    if parsed.text.trim().is_empty() {
+        return None;
+    }
+
+    let duration_ms = samples_to_ms(speech_samples.len() as u64, sample_rate);
+    let word_timings = word_timings_from_tokens(
+        &sensevoice_result.tokens,
+        &sensevoice_result.timestamps,
+        duration_ms,
+    ).unwrap_or_else(|| interpolate_word_timings(&parsed.text, duration_ms));
+
+    Some(TranscriptionResult {
+        text: parsed.text,
+        source: source.to_string(),
+        timestamp_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64,
+        duration_ms,
+        word_timings,
+        is_final,
+        language: sensevoice_result.lang.clone(),
+        emotion: parsed.emotion,
+        audio_events: parsed.events,
+        is_turn_complete: false,  // Will be set by Smart Turn
+        turn_confidence: 0.0,
+    })
+}
+
 /// Parsed output from SenseVoice
 struct ParsedSenseVoiceOutput {
     text: String,
@@ -341,8 +545,109 @@ fn parse_sensevoice_output(raw_text: &str) -> ParsedSenseVoiceOutput {
     }
 }
 
+/// Duration of `samples` mono samples at `sample_rate`, in milliseconds
+pub fn samples_to_ms(samples: u64, sample_rate: u32) -> u64 {
+    if sample_rate == 0 {
+        return 0;
+    }
+    samples * 1000 / sample_rate as u64
+}
+
+/// Tracks elapsed audio time for one source from the samples fed through it
+///
+/// Chunk durations are derived from the running sample total, so they always
+/// add up to the total audio processed instead of drifting by per-chunk rounding.
+#[derive(Debug, Clone, Default)]
+pub struct AudioClock {
+    sample_rate: u32,
+    samples_at_rate: u64,  // Samples since the last sample rate change
+    base_ms: u64,          // Elapsed time when the current sample rate started
+    elapsed_ms: u64,
+}
+
+impl AudioClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Advance by a chunk of `samples` mono samples; returns the chunk's duration in ms
+    pub fn advance(&mut self, samples: usize, sample_rate: u32) -> u64 {
+        if sample_rate != self.sample_rate {
+            self.base_ms = self.elapsed_ms;
+            self.samples_at_rate = 0;
+            self.sample_rate = sample_rate;
+        }
+        self.samples_at_rate += samples as u64;
+
+        let elapsed = self.base_ms + samples_to_ms(self.samples_at_rate, sample_rate);
+        let duration = elapsed - self.elapsed_ms;
+        self.elapsed_ms = elapsed;
+        duration
+    }
+
+    /// Total audio time seen so far
+    pub fn elapsed_ms(&self) -> u64 {
+        self.elapsed_ms
+    }
+}
+
+/// Build word timings from SenseVoice tokens and their start times (in seconds)
+///
+/// Tokens are sentencepiece pieces where "▁" marks the start of a word; special
+/// `<|...|>` tokens are skipped. Each word ends where the next one starts, the last
+/// one at `duration_ms`. Returns None when the model gave no usable timestamps.
+/// Words come from the raw tokens, so they can differ from the ITN-normalized text
+/// (e.g. "twenty" vs "20").
+pub fn word_timings_from_tokens(tokens: &[String], timestamps: &[f32], duration_ms: u64) -> Option<Vec<WordTiming>> {
+    if tokens.is_empty() || tokens.len() != timestamps.len() {
+        return None;
+    }
+
+    let mut words: Vec<(String, u64)> = Vec::new();
+    for (token, &start) in tokens.iter().zip(timestamps) {
+        if token.starts_with("<|") {
+            continue;
+        }
+        let start_ms = ((start.max(0.0) * 1000.0) as u64).min(duration_ms);
+        let starts_word = token.starts_with('▁') || token.starts_with(' ');
+        let piece = token.trim_start_matches(['▁', ' ']);
+
+        match words.last_mut() {
+            Some((word, _)) if !starts_word => word.push_str(piece),
+            _ if !piece.is_empty() => words.push((piece.to_string(), start_ms)),
+            _ => {}
+        }
+    }
+
+    if words.is_empty() {
+        return None;
+    }
+
+    let ends: Vec<u64> = words.iter().skip(1).map(|(_, start)| *start).chain([duration_ms]).collect();
+    Some(words.into_iter()
+        .zip(ends)
+        .map(|((word, start), end)| (word, start, end.max(start)))
+        .collect())
+}
+
+/// Fallback when the recognizer has no token timings: spread the words evenly over the segment
+pub fn interpolate_word_timings(text: &str, duration_ms: u64) -> Vec<WordTiming> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let count = words.len() as u64;
+    words.into_iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let i = i as u64;
+            (word.to_string(), duration_ms * i / count, duration_ms * (i + 1) / count)
+        })
+        .collect()
+}
+
 /// Simple linear resampling
-fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+pub fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     if from_rate == to_rate {
         return samples.to_vec();
     }
@@ -364,6 +669,53 @@ fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
     result
 }
 
+/// Linear resampler for a continuous stream delivered in arbitrary packets
+/// Carries the interpolation position and last sample across calls, so the output
+/// length tracks `input * to / from` exactly instead of losing a fraction per packet
+/// (which would make buffered audio, and diarization timestamps, drift over a meeting).
+pub struct StreamResampler {
+    to_rate: u32,
+    from_rate: u32,
+    position: f64,       // Next output position, in input samples from `last`
+    last: Option<f32>,   // Final sample of the previous packet
+}
+
+impl StreamResampler {
+    pub fn new(to_rate: u32) -> Self {
+        Self { to_rate, from_rate: to_rate, position: 0.0, last: None }
+    }
+
+    /// Resample one packet of mono audio captured at `from_rate`
+    /// A rate change (e.g. the device was switched) restarts the stream.
+    pub fn process(&mut self, samples: &[f32], from_rate: u32) -> Vec<f32> {
+        if from_rate != self.from_rate {
+            self.from_rate = from_rate;
+            self.position = 0.0;
+            self.last = None;
+        }
+        if from_rate == self.to_rate || samples.is_empty() {
+            return samples.to_vec();
+        }
+
+        let input: Vec<f32> = self.last.into_iter().chain(samples.iter().copied()).collect();
+        let step = from_rate as f64 / self.to_rate as f64;
+        let end = (input.len() - 1) as f64;
+        let mut result = Vec::with_capacity((samples.len() as f64 / step) as usize + 1);
+
+        while self.position <= end {
+            let i = self.position.floor() as usize;
+            let t = self.position - i as f64;
+            let next = input[(i + 1).min(input.len() - 1)];
+            result.push((input[i] as f64 * (1.0 - t) + next as f64 * t) as f32);
+            self.position += step;
+        }
+
+        self.position -= end;
+        self.last = input.last().copied();
+        result
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -388,4 +740,172 @@ mod tests {
         assert_eq!(parsed3.text, "Just plain text");
         assert_eq!(parsed3.emotion, Emotion::Neutral);
     }
+
+    #[test]
+    fn test_word_timings_from_tokens() {
+        let tokens: Vec<String> = ["<|en|>", "▁hel", "lo", "▁there", "▁bob"]
+            .iter().map(|t| t.to_string()).collect();
+        let timestamps = [0.0, 0.12, 0.3, 0.56, 0.9];
+        let timings = word_timings_from_tokens(&tokens, &timestamps, 1200).unwrap();
+
+        assert_eq!(timings, vec![
+            ("hello".to_string(), 120, 560),
+            ("there".to_string(), 560, 900),
+            ("bob".to_string(), 900, 1200),
+        ]);
+
+        assert!(word_timings_from_tokens(&tokens, &[0.0], 1200).is_none());
+        assert!(word_timings_from_tokens(&[], &[], 1200).is_none());
+    }
+
+    #[test]
+    fn test_interpolate_word_timings() {
+        let timings = interpolate_word_timings("ship it Friday", 900);
+        assert_eq!(timings, vec![
+            ("ship".to_string(), 0, 300),
+            ("it".to_string(), 300, 600),
+            ("Friday".to_string(), 600, 900),
+        ]);
+        assert!(interpolate_word_timings("  ", 900).is_empty());
+    }
+
+    #[test]
+    fn test_interim_buffer_fires_every_interval() {
+        let mut buffer = InterimBuffer::default();
+        assert!(!buffer.push(&[0.0; 600], 1000));
+        assert!(buffer.push(&[0.0; 600], 1000));
+        assert!(!buffer.push(&[0.0; 600], 1000));
+        assert!(buffer.push(&[0.0; 600], 1000));
+        assert_eq!(buffer.samples().len(), 2400);
+
+        buffer.clear();
+        assert!(buffer.samples().is_empty());
+        assert!(!buffer.push(&[0.0; 600], 1000));
+    }
+
+    #[test]
+    fn test_chunk_overlap_carries_tail_into_next_chunk() {
+        // A "phrase" of 4000 samples split unevenly across three chunks at 16kHz
+        let phrase: Vec<f32> = (0..4000).map(|i| i as f32).collect();
+        let mut overlap = ChunkOverlap::new();
+
+        // 50ms = 800 samples
+        assert!(overlap.next(&phrase[..1000], 16000, 50).is_empty());
+        assert_eq!(overlap.next(&phrase[1000..1300], 16000, 50), &phrase[200..1000]);
+        // A chunk shorter than the overlap keeps part of the previous tail
+        assert_eq!(overlap.next(&phrase[1300..], 16000, 50), &phrase[500..1300]);
+
+        // Changing sample rate drops the tail; 0ms disables overlap
+        assert!(overlap.next(&phrase, 48000, 0).is_empty());
+        assert!(overlap.next(&phrase, 48000, 0).is_empty());
+    }
+
+    #[test]
+    fn test_boundary_phrase_transcribes_once() {
+        // The second segment re-heard "the beta" through the pre-roll
+        let previous = "We should ship the beta";
+        let current = "the beta on Friday.";
+        let repeated = overlapping_words(previous, current);
+        assert_eq!(repeated, 2);
+
+        let mut transcription = TranscriptionResult {
+            text: current.to_string(),
+            source: "microphone".to_string(),
+            timestamp_ms: 0,
+            duration_ms: 1200,
+            word_timings: interpolate_word_timings(current, 1200),
+            is_final: true,
+            language: "en".to_string(),
+            emotion: Emotion::Neutral,
+            audio_events: Vec::new(),
+            is_turn_complete: false,
+            turn_confidence: 0.0,
+        };
+        assert!(drop_repeated_words(&mut transcription, previous));
+        assert_eq!(format!("{} {}", previous, transcription.text), "We should ship the beta on Friday.");
+        assert_eq!(transcription.word_timings.len(), 2);
+
+        assert_eq!(overlapping_words("Thanks, everyone.", "Next item"), 0);
+        transcription.text = "the Beta.".to_string();
+        assert!(!drop_repeated_words(&mut transcription, previous));
+    }
+
+    #[test]
+    fn test_single_repeated_word_is_kept() {
+        let previous = "Let's talk about the";
+        let mut transcription = TranscriptionResult {
+            text: "The plan is ready.".to_string(),
+            source: "microphone".to_string(),
+            timestamp_ms: 0,
+            duration_ms: 1000,
+            word_timings: interpolate_word_timings("The plan is ready.", 1000),
+            is_final: true,
+            language: "en".to_string(),
+            emotion: Emotion::Neutral,
+            audio_events: Vec::new(),
+            is_turn_complete: false,
+            turn_confidence: 0.0,
+        };
+        assert_eq!(overlapping_words(previous, &transcription.text), 1);
+        assert!(drop_repeated_words(&mut transcription, previous));
+        assert_eq!(transcription.text, "The plan is ready.");
+        assert_eq!(transcription.word_timings.len(), 4);
+    }
+
+    #[test]
+    fn test_samples_to_ms() {
+        assert_eq!(samples_to_ms(16000, 16000), 1000);
+        assert_eq!(samples_to_ms(800, 16000), 50);
+        assert_eq!(samples_to_ms(4410, 44100), 100);
+        assert_eq!(samples_to_ms(100, 0), 0);
+    }
+
+    #[test]
+    fn test_audio_clock_chunk_durations_sum_to_total() {
+        let mut clock = AudioClock::new();
+        // Odd chunk sizes that don't divide evenly into milliseconds
+        let chunks = [801usize, 4000, 333, 1237, 800, 15, 4001, 999];
+        let sum: u64 = chunks.iter().map(|&n| clock.advance(n, 16000)).sum();
+        let total_samples: usize = chunks.iter().sum();
+
+        assert_eq!(sum, samples_to_ms(total_samples as u64, 16000));
+        assert_eq!(sum, clock.elapsed_ms());
+    }
+
+    #[test]
+    fn test_audio_clock_handles_sample_rate_change() {
+        let mut clock = AudioClock::new();
+        let first: u64 = (0..10).map(|_| clock.advance(4411, 44100)).sum();
+        let second: u64 = (0..10).map(|_| clock.advance(4801, 48000)).sum();
+
+        assert_eq!(first, samples_to_ms(44110, 44100));
+        assert_eq!(second, samples_to_ms(48010, 48000));
+        assert_eq!(clock.elapsed_ms(), first + second);
+    }
+
+    #[test]
+    fn test_stream_resampler_keeps_length_across_packets() {
+        // One second of a 48kHz ramp delivered in uneven packets
+        let input: Vec<f32> = (0..48000).map(|i| i as f32 / 48000.0).collect();
+        let mut resampler = StreamResampler::new(16000);
+        let mut output = Vec::new();
+        for packet in input.chunks(441) {
+            output.extend(resampler.process(packet, 48000));
+        }
+
+        assert!((output.len() as i64 - 16000).abs() <= 1, "got {} samples", output.len());
+        // Every third input sample, with no seams at packet boundaries
+        for (i, &value) in output.iter().enumerate().take(15999) {
+            assert!((value - input[i * 3]).abs() < 1e-4, "sample {} = {}", i, value);
+        }
+
+        // 44.1kHz goes through the same path
+        let mut resampler = StreamResampler::new(16000);
+        let total: usize = (0..100).map(|_| resampler.process(&[0.0; 441], 44100).len()).sum();
+        assert!((total as i64 - 16000).abs() <= 1);
+
+        // Already at the target rate: passthrough
+        let mut resampler = StreamResampler::new(16000);
+        assert_eq!(resampler.process(&[0.1, 0.2], 16000), vec![0.1, 0.2]);
+    }
 }
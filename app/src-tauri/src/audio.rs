@@ -3,6 +3,11 @@ use std::sync::Arc;
 use tokio::sync::mpsc;
 use serde::{Serialize, Deserialize};
 
+/// Rate everything downstream of capture runs at (chunking, ASR, diarization, WAV export)
+/// Devices are opened at this rate when they support it; otherwise the capture
+/// thread resamples (most built-in devices only offer 44.1kHz or 48kHz).
+pub const TARGET_SAMPLE_RATE: u32 = 16_000;
+
 /// Audio capture mode
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum AudioCaptureMode {
@@ -218,6 +223,47 @@ impl Drop for AudioCapture {
     }
 }
 
+/// Write mono 16kHz f32 samples to a 16-bit PCM WAV file
+pub fn write_wav_16k_mono(path: &std::path::Path, samples: &[f32]) -> Result<(), String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: TARGET_SAMPLE_RATE,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+
+    let mut writer = hound::WavWriter::create(path, spec)
+        .map_err(|e| format!("Failed to create WAV file: {}", e))?;
+
+    for &sample in samples {
+        let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        writer.write_sample(value)
+            .map_err(|e| format!("Failed to write WAV sample: {}", e))?;
+    }
+
+    writer.finalize()
+        .map_err(|e| format!("Failed to finalize WAV file: {}", e))
+}
+
+/// Input config for `device`: an f32 config at `TARGET_SAMPLE_RATE` if the device offers
+/// one (no resampling needed), else its default config
+fn preferred_input_config(device: &cpal::Device) -> Result<cpal::SupportedStreamConfig, String> {
+    use cpal::traits::DeviceTrait;
+
+    let target = cpal::SampleRate(TARGET_SAMPLE_RATE);
+    let native = device.supported_input_configs().ok().and_then(|mut ranges| {
+        ranges.find(|r| {
+            r.sample_format() == cpal::SampleFormat::F32
+                && r.min_sample_rate() <= target
+                && r.max_sample_rate() >= target
+        })
+    });
+    match native {
+        Some(range) => Ok(range.with_sample_rate(target)),
+        None => device.default_input_config().map_err(|e| e.to_string()),
+    }
+}
+
 /// Capture microphone audio using cpal
 fn capture_microphone(
     sender: mpsc::UnboundedSender<AudioSample>,
@@ -229,8 +275,7 @@ fn capture_microphone(
     let device = host.default_input_device()
         .ok_or("No input device available")?;
 
-    let config = device.default_input_config()
-        .map_err(|e| e.to_string())?;
+    let config = preferred_input_config(&device)?;
 
     let sample_rate = config.sample_rate().0;
     let channels = config.channels();
@@ -325,7 +370,7 @@ fn capture_system_audio_macos(
         }
     };
 
-    let config = device.default_input_config()
+    let config = preferred_input_config(&device)
         .map_err(|e| format!("Failed to get config: {}", e))?;
 
     let sample_rate = config.sample_rate().0;
@@ -0,0 +1,154 @@
+//! Streaming decode of recorded audio files for import
+//!
+//! Files are decoded one packet at a time with symphonia and handed out as
+//! 16kHz mono, so an hour-long recording never sits in memory at its source
+//! rate and channel count. Packets that fail to decode are skipped rather than
+//! failing the whole import.
+
+use std::path::Path;
+
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+use crate::asr::StreamResampler;
+use crate::audio::TARGET_SAMPLE_RATE;
+use crate::stereo_to_mono;
+
+/// Extensions offered in the import file picker
+pub const SUPPORTED_EXTENSIONS: &[&str] = &["wav", "mp3", "m4a", "aac", "flac", "ogg"];
+
+/// Consecutive undecodable packets after which the file is treated as corrupt
+const MAX_DECODE_ERRORS: usize = 50;
+
+/// Packet-by-packet decoder producing 16kHz mono
+pub struct AudioFileDecoder {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    source_rate: u32,
+    total_frames: Option<u64>,
+    frames_decoded: u64,
+    resampler: StreamResampler,
+    decode_errors: usize,
+}
+
+impl AudioFileDecoder {
+    /// Probe `path` and open its first audio track
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let file = std::fs::File::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        let stream = MediaSourceStream::new(Box::new(file), Default::default());
+
+        let mut hint = Hint::new();
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            hint.with_extension(ext);
+        }
+
+        let probed = symphonia::default::get_probe()
+            .format(&hint, stream, &FormatOptions::default(), &MetadataOptions::default())
+            .map_err(|e| format!("Unsupported audio file {}: {}", path.display(), e))?;
+        let format = probed.format;
+
+        let track = format.tracks().iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or("No audio track found in file")?;
+        let track_id = track.id;
+        let source_rate = track.codec_params.sample_rate.unwrap_or(TARGET_SAMPLE_RATE);
+        let total_frames = track.codec_params.n_frames;
+
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())
+            .map_err(|e| format!("Unsupported audio codec: {}", e))?;
+
+        Ok(Self {
+            format,
+            decoder,
+            track_id,
+            source_rate,
+            total_frames,
+            frames_decoded: 0,
+            resampler: StreamResampler::new(TARGET_SAMPLE_RATE),
+            decode_errors: 0,
+        })
+    }
+
+    /// Length of the file, when the container reports it
+    pub fn duration_ms(&self) -> Option<u64> {
+        self.total_frames.map(|frames| frames * 1000 / self.source_rate.max(1) as u64)
+    }
+
+    /// Fraction of the file decoded so far (0.0-1.0), when the length is known
+    pub fn progress(&self) -> Option<f32> {
+        self.total_frames
+            .filter(|&total| total > 0)
+            .map(|total| (self.frames_decoded as f32 / total as f32).min(1.0))
+    }
+
+    /// Next packet as 16kHz mono, or `None` at the end of the file
+    pub fn next_chunk(&mut self) -> Result<Option<Vec<f32>>, String> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+                // Chained streams (e.g. a new Ogg logical stream) aren't followed
+                Err(SymphoniaError::ResetRequired) => return Ok(None),
+                Err(e) => return Err(format!("Failed to read audio: {}", e)),
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                Err(SymphoniaError::DecodeError(e)) => {
+                    self.decode_errors += 1;
+                    if self.decode_errors > MAX_DECODE_ERRORS {
+                        return Err(format!("Too many undecodable packets ({})", e));
+                    }
+                    eprintln!("[Import] Skipping undecodable packet: {}", e);
+                    continue;
+                }
+                Err(e) => return Err(format!("Failed to decode audio: {}", e)),
+            };
+            self.decode_errors = 0;
+
+            let spec = *decoded.spec();
+            let frames = decoded.frames();
+            if frames == 0 {
+                continue;
+            }
+            let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            buffer.copy_interleaved_ref(decoded);
+
+            self.frames_decoded += frames as u64;
+            let mono = stereo_to_mono(buffer.samples(), spec.channels.count() as u16);
+            return Ok(Some(self.resampler.process(&mono, spec.rate)));
+        }
+    }
+}
+
+/// Meeting title for an imported file: its name without the extension
+pub fn title_from_path(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|stem| stem.to_str())
+        .map(|stem| stem.replace(['_', '-'], " ").trim().to_string())
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| "Imported recording".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_title_from_path() {
+        assert_eq!(title_from_path(Path::new("/calls/weekly_sync-2026.mp3")), "weekly sync 2026");
+        assert_eq!(title_from_path(Path::new("interview.wav")), "interview");
+        assert_eq!(title_from_path(Path::new("/calls/___.m4a")), "Imported recording");
+    }
+}
@@ -0,0 +1,233 @@
+//! Knowledge base backup and restore
+//!
+//! Packs the RocksDB-backed SurrealDB directory and the SQLite user store into a
+//! single zip with a manifest. Restores are staged next to the live data and
+//! swapped in on the next app start, since both databases hold open file locks.
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Bump when the on-disk layout of the knowledge base or user store changes
+pub const BACKUP_SCHEMA_VERSION: u32 = 1;
+
+const MANIFEST_NAME: &str = "manifest.json";
+const KB_DIR_NAME: &str = "knowledge.db";
+const USER_STORE_NAME: &str = "user_store.db";
+const STAGING_DIR_NAME: &str = "restore_staging";
+
+/// Metadata written alongside the backed-up data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub schema_version: u32,
+    pub app_version: String,
+    pub created_at: u64,
+    pub file_count: usize,
+}
+
+/// Write a backup zip of the knowledge base directory and a user store snapshot
+///
+/// `kb_db_dir` must not be open by SurrealDB while this runs.
+/// `user_store_snapshot` should be a consistent copy (e.g. from `VACUUM INTO`).
+pub fn create_backup(
+    kb_db_dir: &Path,
+    user_store_snapshot: Option<&Path>,
+    dest_path: &Path,
+) -> Result<BackupManifest, String> {
+    if !kb_db_dir.is_dir() {
+        return Err(format!("Knowledge base directory not found: {:?}", kb_db_dir));
+    }
+
+    let file = File::create(dest_path)
+        .map_err(|e| format!("Failed to create backup file: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut file_count = 0;
+    for path in walk_files(kb_db_dir)? {
+        let relative = path.strip_prefix(kb_db_dir)
+            .map_err(|e| format!("Invalid backup path: {}", e))?;
+        // RocksDB's LOCK file belongs to the running process, not the data
+        if relative == Path::new("LOCK") {
+            continue;
+        }
+        let name = format!("{}/{}", KB_DIR_NAME, relative.to_string_lossy().replace('\\', "/"));
+        add_file(&mut zip, &name, &path, options)?;
+        file_count += 1;
+    }
+
+    if let Some(snapshot) = user_store_snapshot {
+        add_file(&mut zip, USER_STORE_NAME, snapshot, options)?;
+        file_count += 1;
+    }
+
+    let manifest = BackupManifest {
+        schema_version: BACKUP_SCHEMA_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        created_at: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64,
+        file_count,
+    };
+
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+    zip.start_file(MANIFEST_NAME, options)
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+    zip.write_all(manifest_json.as_bytes())
+        .map_err(|e| format!("Failed to write manifest: {}", e))?;
+
+    zip.finish().map_err(|e| format!("Failed to finalize backup: {}", e))?;
+
+    println!("[Backup] Wrote {} files to {:?}", file_count, dest_path);
+    Ok(manifest)
+}
+
+/// Validate a backup and extract it into the staging area under `kb_data_dir`
+///
+/// Nothing live is touched; `apply_pending_restore` swaps the data in at startup.
+pub fn stage_restore(src_path: &Path, kb_data_dir: &Path) -> Result<BackupManifest, String> {
+    let file = File::open(src_path)
+        .map_err(|e| format!("Failed to open backup: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Not a valid backup archive: {}", e))?;
+
+    let manifest: BackupManifest = {
+        let mut entry = archive.by_name(MANIFEST_NAME)
+            .map_err(|_| "Backup is missing its manifest".to_string())?;
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents)
+            .map_err(|e| format!("Failed to read manifest: {}", e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid backup manifest: {}", e))?
+    };
+
+    if manifest.schema_version != BACKUP_SCHEMA_VERSION {
+        return Err(format!(
+            "Backup schema version {} is not compatible with this app (expects {})",
+            manifest.schema_version, BACKUP_SCHEMA_VERSION
+        ));
+    }
+
+    let has_kb = (0..archive.len()).any(|i| {
+        archive.by_index(i)
+            .map(|f| f.name().starts_with(&format!("{}/", KB_DIR_NAME)))
+            .unwrap_or(false)
+    });
+    if !has_kb {
+        return Err("Backup does not contain a knowledge base".to_string());
+    }
+
+    let staging = kb_data_dir.join(STAGING_DIR_NAME);
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)
+            .map_err(|e| format!("Failed to clear previous staged restore: {}", e))?;
+    }
+    std::fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create staging directory: {}", e))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)
+            .map_err(|e| format!("Failed to read backup entry: {}", e))?;
+        // enclosed_name rejects absolute paths and `..` traversal
+        let relative = entry.enclosed_name()
+            .ok_or_else(|| format!("Unsafe path in backup: {}", entry.name()))?;
+        let out_path = staging.join(relative);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&out_path)
+                .map_err(|e| format!("Failed to create {:?}: {}", out_path, e))?;
+            continue;
+        }
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        let mut out = File::create(&out_path)
+            .map_err(|e| format!("Failed to create {:?}: {}", out_path, e))?;
+        std::io::copy(&mut entry, &mut out)
+            .map_err(|e| format!("Failed to extract {:?}: {}", out_path, e))?;
+    }
+
+    println!("[Backup] Staged restore from {:?}; restart to apply", src_path);
+    Ok(manifest)
+}
+
+/// Swap a staged restore into place. Must run before either database is opened.
+/// The replaced data is kept with a `.pre-restore` suffix.
+pub fn apply_pending_restore(kb_data_dir: &Path, user_store_dir: &Path) -> Result<bool, String> {
+    let staging = kb_data_dir.join(STAGING_DIR_NAME);
+    if !staging.join(MANIFEST_NAME).exists() {
+        return Ok(false);
+    }
+
+    swap_in(&staging.join(KB_DIR_NAME), &kb_data_dir.join(KB_DIR_NAME))?;
+
+    let staged_store = staging.join(USER_STORE_NAME);
+    if staged_store.exists() {
+        swap_in(&staged_store, &user_store_dir.join(USER_STORE_NAME))?;
+    }
+
+    std::fs::remove_dir_all(&staging)
+        .map_err(|e| format!("Failed to remove staging directory: {}", e))?;
+
+    println!("[Backup] Applied staged restore");
+    Ok(true)
+}
+
+/// Move `staged` to `live`, keeping the previous `live` as `<live>.pre-restore`
+fn swap_in(staged: &Path, live: &Path) -> Result<(), String> {
+    let previous = PathBuf::from(format!("{}.pre-restore", live.to_string_lossy()));
+    if previous.is_dir() {
+        let _ = std::fs::remove_dir_all(&previous);
+    } else if previous.exists() {
+        let _ = std::fs::remove_file(&previous);
+    }
+
+    if live.exists() {
+        std::fs::rename(live, &previous)
+            .map_err(|e| format!("Failed to move aside {:?}: {}", live, e))?;
+    }
+    if let Some(parent) = live.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+    std::fs::rename(staged, live)
+        .map_err(|e| format!("Failed to move {:?} into place: {}", staged, e))
+}
+
+fn add_file(
+    zip: &mut zip::ZipWriter<File>,
+    name: &str,
+    path: &Path,
+    options: zip::write::SimpleFileOptions,
+) -> Result<(), String> {
+    zip.start_file(name, options)
+        .map_err(|e| format!("Failed to add {} to backup: {}", name, e))?;
+    let mut source = File::open(path)
+        .map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+    std::io::copy(&mut source, zip)
+        .map_err(|e| format!("Failed to add {} to backup: {}", name, e))?;
+    Ok(())
+}
+
+fn walk_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        let entries = std::fs::read_dir(&current)
+            .map_err(|e| format!("Failed to read {:?}: {}", current, e))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    Ok(files)
+}
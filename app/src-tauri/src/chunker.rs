@@ -1,26 +1,72 @@
 //! Text chunking module for semantic document splitting.
 //!
 //! Uses text-splitter crate for markdown-aware chunking that preserves
-//! semantic boundaries (paragraphs, sentences, headings).
+//! semantic boundaries (paragraphs, sentences, headings). Documents can also be
+//! split per heading section, or semantically: sentences are embedded and a new
+//! chunk starts where a sentence stops resembling the ones just before it.
 
+use crate::embeddings::EmbeddingEngine;
 use serde::{Deserialize, Serialize};
 use text_splitter::{Characters, MarkdownSplitter};
 
+/// How documents are split into chunks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChunkStrategy {
+    /// Pack text up to `chunk_size`, breaking at the largest markdown boundary that fits
+    #[default]
+    FixedSize,
+    /// One chunk per heading section; sections over `chunk_size` are packed like `FixedSize`
+    ByHeading,
+    /// Break where consecutive sentences' embeddings stop being similar (needs an embedder)
+    Semantic,
+}
+
+impl ChunkStrategy {
+    pub fn parse(strategy: &str) -> Result<Self, String> {
+        match strategy.trim().to_lowercase().replace('-', "_").as_str() {
+            "fixed_size" | "fixed" => Ok(Self::FixedSize),
+            "by_heading" | "heading" => Ok(Self::ByHeading),
+            "semantic" => Ok(Self::Semantic),
+            other => Err(format!("Unknown chunk strategy '{}' (expected fixed_size, by_heading or semantic)", other)),
+        }
+    }
+}
+
 /// Configuration for the document chunker
 #[derive(Debug, Clone)]
 pub struct ChunkerConfig {
     /// Target chunk size in characters
     pub chunk_size: usize,
+    pub strategy: ChunkStrategy,
+    /// Semantic: start a new chunk when a sentence's similarity to the window drops below this
+    pub similarity_threshold: f32,
+    /// Semantic: how many of the chunk's latest sentences a new sentence is compared against
+    pub window_sentences: usize,
 }
 
 impl Default for ChunkerConfig {
     fn default() -> Self {
         Self {
             chunk_size: 1000,    // ~250 tokens at 4 chars/token
+            strategy: ChunkStrategy::default(),
+            similarity_threshold: 0.4,
+            window_sentences: 3,
         }
     }
 }
 
+/// Embeds sentences for `ChunkStrategy::Semantic`
+pub trait SentenceEmbedder {
+    fn embed_sentences(&self, sentences: &[&str]) -> Result<Vec<Vec<f32>>, String>;
+}
+
+impl SentenceEmbedder for EmbeddingEngine {
+    fn embed_sentences(&self, sentences: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+        self.embed_batch(sentences)
+    }
+}
+
 /// A chunk of text with position metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Chunk {
@@ -41,12 +87,13 @@ pub struct ChunkWithMeta {
 }
 
 /// Document chunker for splitting text into semantic chunks
-pub struct DocumentChunker {
+pub struct DocumentChunker<'a> {
     config: ChunkerConfig,
     splitter: MarkdownSplitter<Characters>,
+    embedder: Option<&'a dyn SentenceEmbedder>,
 }
 
-impl DocumentChunker {
+impl<'a> DocumentChunker<'a> {
     /// Create a new document chunker with default config
     pub fn new() -> Self {
         Self::with_config(ChunkerConfig::default())
@@ -57,35 +104,101 @@ impl DocumentChunker {
         // Create splitter with target chunk size in characters
         let splitter = MarkdownSplitter::new(config.chunk_size);
 
-        Self { config, splitter }
+        Self { config, splitter, embedder: None }
+    }
+
+    /// Use `embedder` for semantic chunking; without one `Semantic` falls back to `FixedSize`
+    pub fn with_embedder(mut self, embedder: &'a dyn SentenceEmbedder) -> Self {
+        self.embedder = Some(embedder);
+        self
     }
 
-    /// Chunk markdown content into semantic pieces
+    /// Chunk markdown content into pieces using the configured strategy
     pub fn chunk_markdown(&self, content: &str) -> Vec<Chunk> {
-        let chunks: Vec<_> = self.splitter.chunks(content).collect();
+        let spans = match self.config.strategy {
+            ChunkStrategy::FixedSize => self.packed_spans(content, 0),
+            ChunkStrategy::ByHeading => self.heading_spans(content),
+            ChunkStrategy::Semantic => self.semantic_spans(content).unwrap_or_else(|e| {
+                eprintln!("[Chunker] Semantic chunking unavailable, packing to size instead: {}", e);
+                self.packed_spans(content, 0)
+            }),
+        };
+
+        spans.into_iter()
+            .enumerate()
+            .map(|(index, (start, end))| Chunk {
+                text: content[start..end].to_string(),
+                start_char: start,
+                end_char: end,
+                chunk_index: index,
+            })
+            .collect()
+    }
 
-        let mut result = Vec::with_capacity(chunks.len());
-        let mut current_pos = 0;
+    /// Byte spans of `text` packed up to the chunk size, shifted by `offset`
+    fn packed_spans(&self, text: &str, offset: usize) -> Vec<(usize, usize)> {
+        self.splitter.chunk_indices(text)
+            .map(|(start, chunk)| (offset + start, offset + start + chunk.len()))
+            .collect()
+    }
 
-        for (index, chunk_text) in chunks.into_iter().enumerate() {
-            // Find the actual position in the original content
-            let start = content[current_pos..]
-                .find(chunk_text)
-                .map(|pos| current_pos + pos)
-                .unwrap_or(current_pos);
+    /// One span per heading section, packing sections that are too long
+    fn heading_spans(&self, content: &str) -> Vec<(usize, usize)> {
+        let mut bounds = heading_starts(content);
+        bounds.push(content.len());
+
+        let mut spans = Vec::new();
+        let mut section_start = 0;
+        for section_end in bounds {
+            let Some((start, end)) = trim_span(content, section_start, section_end) else {
+                section_start = section_end;
+                continue;
+            };
+            if end - start <= self.config.chunk_size {
+                spans.push((start, end));
+            } else {
+                spans.extend(self.packed_spans(&content[start..end], start));
+            }
+            section_start = section_end;
+        }
+        spans
+    }
 
-            let end = start + chunk_text.len();
-            current_pos = end;
+    /// Sentences grouped into chunks at similarity drops, capped at the chunk size
+    fn semantic_spans(&self, content: &str) -> Result<Vec<(usize, usize)>, String> {
+        let embedder = self.embedder.ok_or("no embedding engine")?;
+        let sentences: Vec<(usize, usize)> = sentence_spans(content)
+            .into_iter()
+            .flat_map(|(start, end)| {
+                if end - start > self.config.chunk_size {
+                    self.packed_spans(&content[start..end], start)
+                } else {
+                    vec![(start, end)]
+                }
+            })
+            .collect();
+        if sentences.len() < 2 {
+            return Ok(sentences);
+        }
 
-            result.push(Chunk {
-                text: chunk_text.to_string(),
-                start_char: start,
-                end_char: end,
-                chunk_index: index,
-            });
+        let texts: Vec<&str> = sentences.iter().map(|&(start, end)| &content[start..end]).collect();
+        let embeddings = embedder.embed_sentences(&texts)?;
+        if embeddings.len() != sentences.len() {
+            return Err(format!("Expected {} sentence embeddings, got {}", sentences.len(), embeddings.len()));
         }
 
-        result
+        let mut spans = Vec::new();
+        let mut first = 0;
+        for i in 1..sentences.len() {
+            let window = &embeddings[first.max(i.saturating_sub(self.config.window_sentences))..i];
+            let too_long = sentences[i].1 - sentences[first].0 > self.config.chunk_size;
+            if too_long || window_similarity(window, &embeddings[i]) < self.config.similarity_threshold {
+                spans.push((sentences[first].0, sentences[i - 1].1));
+                first = i;
+            }
+        }
+        spans.push((sentences[first].0, sentences[sentences.len() - 1].1));
+        Ok(spans)
     }
 
     /// Chunk content with source metadata attached
@@ -123,12 +236,74 @@ impl DocumentChunker {
     }
 }
 
-impl Default for DocumentChunker {
+impl Default for DocumentChunker<'_> {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// Byte offsets of ATX heading lines (`# Title`), skipping fenced code blocks
+fn heading_starts(content: &str) -> Vec<usize> {
+    let mut starts = Vec::new();
+    let mut in_fence = false;
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_fence = !in_fence;
+        } else if !in_fence && offset > 0 {
+            let level = trimmed.chars().take_while(|&c| c == '#').count();
+            if (1..=6).contains(&level) && trimmed[level..].starts_with([' ', '\t']) {
+                starts.push(offset);
+            }
+        }
+        offset += line.len();
+    }
+    starts
+}
+
+/// Sentence spans: text up to `.`, `!` or `?` followed by whitespace, or up to a line break
+fn sentence_spans(content: &str) -> Vec<(usize, usize)> {
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut chars = content.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let next_is_space = chars.peek().map(|&(_, n)| n.is_whitespace()).unwrap_or(true);
+        let end = match c {
+            '\n' => i,
+            '.' | '!' | '?' if next_is_space => i + c.len_utf8(),
+            _ => continue,
+        };
+        spans.extend(trim_span(content, start, end));
+        start = end;
+    }
+    spans.extend(trim_span(content, start, content.len()));
+    spans
+}
+
+/// `[start, end)` without surrounding whitespace; None if nothing is left
+fn trim_span(content: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let text = &content[start..end];
+    let trimmed = text.trim_start();
+    let start = start + (text.len() - trimmed.len());
+    let end = start + trimmed.trim_end().len();
+    (end > start).then_some((start, end))
+}
+
+/// Cosine similarity between `embedding` and the mean of `window`
+fn window_similarity(window: &[Vec<f32>], embedding: &[f32]) -> f32 {
+    let mut mean = vec![0.0f32; embedding.len()];
+    for vector in window {
+        for (m, v) in mean.iter_mut().zip(vector) {
+            *m += v;
+        }
+    }
+    let dot: f32 = mean.iter().zip(embedding).map(|(a, b)| a * b).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(&mean) * norm(embedding);
+    if denominator == 0.0 { 0.0 } else { dot / denominator }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,4 +352,100 @@ Final paragraph here.
         assert_eq!(chunks[0].source_title, "Test Page");
         assert_eq!(chunks[0].total_chunks, chunks.len());
     }
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!(ChunkStrategy::parse("Semantic").unwrap(), ChunkStrategy::Semantic);
+        assert_eq!(ChunkStrategy::parse("by-heading").unwrap(), ChunkStrategy::ByHeading);
+        assert_eq!(ChunkStrategy::parse("fixed_size").unwrap(), ChunkStrategy::FixedSize);
+        assert!(ChunkStrategy::parse("paragraph").is_err());
+    }
+
+    #[test]
+    fn test_by_heading_keeps_sections_apart() {
+        let config = ChunkerConfig { strategy: ChunkStrategy::ByHeading, ..Default::default() };
+        let chunker = DocumentChunker::with_config(config);
+        let content = "# Setup\n\nInstall it.\n\n```\n# not a heading\n```\n\n## Usage\n\nRun it.\n";
+
+        let chunks = chunker.chunk_markdown(content);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].text.starts_with("# Setup") && chunks[0].text.contains("# not a heading"));
+        assert_eq!(chunks[1].text, "## Usage\n\nRun it.");
+        assert_eq!(&content[chunks[1].start_char..chunks[1].end_char], chunks[1].text);
+    }
+
+    #[test]
+    fn test_semantic_without_embedder_falls_back_to_fixed_size() {
+        let config = ChunkerConfig { strategy: ChunkStrategy::Semantic, ..Default::default() };
+        let content = "One sentence. Another one.";
+        let chunks = DocumentChunker::with_config(config).chunk_markdown(content);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].text, content);
+    }
+
+    /// Bag-of-words embedder over two topics, enough to tell the topics apart
+    struct TopicEmbedder;
+
+    const COOKING: &[&str] = &["pasta", "sauce", "garlic", "boil", "oven", "dough", "simmer", "basil"];
+    const ASTRONOMY: &[&str] = &["planet", "orbit", "telescope", "star", "moon", "comet", "galaxy", "nebula"];
+
+    fn topic_vector(text: &str) -> Vec<f32> {
+        let words: Vec<String> = text.split(|c: char| !c.is_alphanumeric()).map(|w| w.to_lowercase()).collect();
+        let count = |vocab: &[&str]| words.iter().filter(|w| vocab.contains(&w.as_str())).count() as f32;
+        vec![count(COOKING), count(ASTRONOMY), 0.1]
+    }
+
+    impl SentenceEmbedder for TopicEmbedder {
+        fn embed_sentences(&self, sentences: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+            Ok(sentences.iter().map(|s| topic_vector(s)).collect())
+        }
+    }
+
+    /// Share of the query topic's sentences found in the single best-matching chunk
+    fn top_chunk_recall(chunks: &[Chunk], query: &str, relevant: &[&str]) -> f32 {
+        let query = topic_vector(query);
+        let best = chunks.iter()
+            .max_by(|a, b| {
+                window_similarity(&[topic_vector(&a.text)], &query)
+                    .total_cmp(&window_similarity(&[topic_vector(&b.text)], &query))
+            })
+            .unwrap();
+        relevant.iter().filter(|s| best.text.contains(*s)).count() as f32 / relevant.len() as f32
+    }
+
+    #[test]
+    fn test_semantic_chunks_retrieve_better_than_fixed_size() {
+        let cooking = [
+            "Boil the pasta in salted water until tender.",
+            "Simmer the garlic sauce while the pasta cooks.",
+            "Tear fresh basil over the sauce at the end.",
+            "Bake the dough in a hot oven for ten minutes.",
+        ];
+        let astronomy = [
+            "A telescope shows the moon in sharp detail.",
+            "Each planet follows an orbit around its star.",
+            "A comet brightens as its orbit nears the star.",
+            "The galaxy holds a nebula where stars form.",
+        ];
+        let content = format!("{} {}", cooking.join(" "), astronomy.join(" "));
+        let chunk_size = 240;
+
+        let fixed = DocumentChunker::with_config(ChunkerConfig { chunk_size, ..Default::default() })
+            .chunk_markdown(&content);
+        let semantic_config = ChunkerConfig { chunk_size, strategy: ChunkStrategy::Semantic, ..Default::default() };
+        let semantic = DocumentChunker::with_config(semantic_config)
+            .with_embedder(&TopicEmbedder)
+            .chunk_markdown(&content);
+
+        // Size-packed chunks straddle the topic change; semantic ones break right at it
+        assert_eq!(semantic.len(), 2);
+        assert_eq!(semantic[0].text, cooking.join(" "));
+        assert!(fixed.iter().any(|c| c.text.contains(cooking[3]) && c.text.contains(astronomy[0])));
+
+        let query = "which planet has a moon in its orbit";
+        let fixed_recall = top_chunk_recall(&fixed, query, &astronomy);
+        let semantic_recall = top_chunk_recall(&semantic, query, &astronomy);
+        assert_eq!(semantic_recall, 1.0);
+        assert!(semantic_recall > fixed_recall);
+    }
 }
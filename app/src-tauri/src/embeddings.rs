@@ -1,12 +1,59 @@
+use lru::LruCache;
 use ndarray::{Array1, Array2, Axis};
 use ort::session::{builder::GraphOptimizationLevel, Session};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use tokenizers::Tokenizer;
 
+use crate::inference_gate::InferenceGate;
+
 /// Embedding dimension for EmbeddingGemma-300M (768-dim)
 pub const EMBEDDING_DIM: usize = 768;
 
+/// Default number of embeddings kept in the in-memory LRU cache
+pub const DEFAULT_EMBEDDING_CACHE_CAPACITY: usize = 2048;
+
+/// Largest number of texts sent through the model in one forward pass (bounds padding memory)
+pub const MAX_EMBEDDING_BATCH_SIZE: usize = 16;
+
+/// Model loaded when no other model is configured; the id is also part of cache
+/// keys so a model swap never serves stale vectors
+pub const DEFAULT_EMBEDDING_MODEL: &str = "embeddinggemma-300m-q4";
+
+/// Known embedding models as (model id, ONNX filename in the models directory)
+const EMBEDDING_MODEL_FILES: &[(&str, &str)] = &[
+    ("embeddinggemma-300m-q4", "model_q4.onnx"),
+    ("embeddinggemma-300m-fp16", "model_fp16.onnx"),
+    ("embeddinggemma-300m", "model.onnx"),
+];
+
+/// ONNX filename for a model id; unknown ids are looked up as `<id>.onnx`
+pub fn embedding_model_file(model_id: &str) -> String {
+    EMBEDDING_MODEL_FILES.iter()
+        .find(|(id, _)| *id == model_id)
+        .map(|(_, file)| file.to_string())
+        .unwrap_or_else(|| format!("{}.onnx", model_id))
+}
+
+/// Embedding cache counters for tuning the capacity
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+    pub hit_rate: f32,
+}
+
+/// LRU cache of text -> embedding
+struct EmbeddingCache {
+    entries: LruCache<String, Vec<f32>>,
+    hits: u64,
+    misses: u64,
+}
+
 /// Result of embedding a text
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingResult {
@@ -18,16 +65,26 @@ pub struct EmbeddingResult {
 pub struct EmbeddingEngine {
     session: Session,
     tokenizer: Tokenizer,
+    model_id: String,
+    dimension: usize,
+    // The engine is shared via Arc across commands and workers, so the cache needs its own lock
+    cache: Mutex<EmbeddingCache>,
+    gate: InferenceGate,  // Bounds concurrent forward passes through `session`
 }
 
 impl EmbeddingEngine {
-    /// Create a new embedding engine
+    /// Create a new embedding engine with the default model
     ///
     /// # Arguments
-    /// * `models_dir` - Directory containing embedding-model.onnx and embedding-tokenizer.json
+    /// * `models_dir` - Directory containing the model .onnx file and embedding-tokenizer.json
     pub fn new(models_dir: &PathBuf) -> Result<Self, String> {
+        Self::with_model(models_dir, DEFAULT_EMBEDDING_MODEL)
+    }
+
+    /// Create an embedding engine for `model_id` (see `embedding_model_file`)
+    pub fn with_model(models_dir: &PathBuf, model_id: &str) -> Result<Self, String> {
         // Use original filename - .onnx file references .onnx_data by name internally
-        let model_path = models_dir.join("model_q4.onnx");
+        let model_path = models_dir.join(embedding_model_file(model_id));
         let tokenizer_path = models_dir.join("embedding-tokenizer.json");
 
         if !model_path.exists() {
@@ -51,8 +108,40 @@ impl EmbeddingEngine {
         let tokenizer = Tokenizer::from_file(&tokenizer_path)
             .map_err(|e| format!("Failed to load tokenizer: {}", e))?;
 
-        println!("Embedding engine initialized (EmbeddingGemma-300M)");
-        Ok(Self { session, tokenizer })
+        let capacity = NonZeroUsize::new(DEFAULT_EMBEDDING_CACHE_CAPACITY).unwrap_or(NonZeroUsize::MIN);
+
+        let mut engine = Self {
+            session,
+            tokenizer,
+            model_id: model_id.to_string(),
+            dimension: 0,
+            cache: Mutex::new(EmbeddingCache {
+                entries: LruCache::new(capacity),
+                hits: 0,
+                misses: 0,
+            }),
+            gate: InferenceGate::default(),
+        };
+
+        // Vectors from different models can't be compared, so record what this one produces
+        engine.dimension = engine.run_model(&["dimension probe"])?
+            .first()
+            .map(|e| e.len())
+            .filter(|dim| *dim > 0)
+            .ok_or("Embedding model produced no output")?;
+
+        println!("Embedding engine initialized ({}, {}-dim)", engine.model_id, engine.dimension);
+        Ok(engine)
+    }
+
+    /// Id of the loaded model
+    pub fn model_id(&self) -> &str {
+        &self.model_id
+    }
+
+    /// Length of the vectors this model produces
+    pub fn dimension(&self) -> usize {
+        self.dimension
     }
 
     /// Generate embedding for a single text
@@ -62,11 +151,83 @@ impl EmbeddingEngine {
     }
 
     /// Generate embeddings for multiple texts (batched)
+    /// Cached texts are served from the LRU; only the misses go through the model.
     pub fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
         if texts.is_empty() {
             return Ok(vec![]);
         }
 
+        let mut results: Vec<Option<Vec<f32>>> = vec![None; texts.len()];
+        let mut missing: Vec<&str> = Vec::new();
+        {
+            let mut cache = self.cache.lock();
+            for (i, text) in texts.iter().enumerate() {
+                if let Some(embedding) = cache.entries.get(&self.cache_key(text)) {
+                    results[i] = Some(embedding.clone());
+                    cache.hits += 1;
+                } else {
+                    cache.misses += 1;
+                    if !missing.contains(text) {
+                        missing.push(text);
+                    }
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            // The lock is released during inference so other callers can still hit the cache
+            let mut computed = Vec::with_capacity(missing.len());
+            for batch in missing.chunks(MAX_EMBEDDING_BATCH_SIZE) {
+                computed.extend(self.run_model(batch)?);
+            }
+            let mut cache = self.cache.lock();
+            for (text, embedding) in missing.iter().zip(computed) {
+                for (i, t) in texts.iter().enumerate() {
+                    if t == text {
+                        results[i] = Some(embedding.clone());
+                    }
+                }
+                cache.entries.put(self.cache_key(text), embedding);
+            }
+        }
+
+        Ok(results.into_iter().map(|r| r.unwrap_or_default()).collect())
+    }
+
+    fn cache_key(&self, text: &str) -> String {
+        format!("{}\u{0}{}", self.model_id, text)
+    }
+
+    /// Current cache counters
+    pub fn cache_stats(&self) -> EmbeddingCacheStats {
+        let cache = self.cache.lock();
+        let lookups = cache.hits + cache.misses;
+        EmbeddingCacheStats {
+            hits: cache.hits,
+            misses: cache.misses,
+            entries: cache.entries.len(),
+            capacity: cache.entries.cap().get(),
+            hit_rate: if lookups == 0 { 0.0 } else { cache.hits as f32 / lookups as f32 },
+        }
+    }
+
+    /// Resize the cache (evicting least recently used entries if shrinking)
+    pub fn set_cache_capacity(&self, capacity: usize) {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN);
+        self.cache.lock().entries.resize(capacity);
+    }
+
+    /// Allow up to `limit` forward passes at once; callers beyond that queue
+    pub fn set_max_concurrency(&self, limit: usize) {
+        self.gate.set_limit(limit);
+    }
+
+    /// Run one batched forward pass through the model (no caching)
+    fn run_model(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, String> {
+        if texts.is_empty() {
+            return Ok(vec![]);
+        }
+
         // Tokenize all texts
         let encodings = self.tokenizer
             .encode_batch(texts.to_vec(), true)
@@ -115,7 +276,8 @@ impl EmbeddingEngine {
             attention_mask
         ).map_err(|e| format!("Failed to create attention_mask array: {}", e))?;
 
-        // Run inference
+        // Run inference, waiting for a free slot if other callers are mid-pass
+        let _permit = self.gate.acquire();
         let outputs = self.session
             .run(ort::inputs![
                 "input_ids" => input_ids_array.view(),
@@ -224,6 +386,13 @@ mod tests {
         assert!((cosine_similarity(&a, &d) - (-1.0)).abs() < 0.001);
     }
 
+    #[test]
+    fn test_embedding_model_file() {
+        assert_eq!(embedding_model_file(DEFAULT_EMBEDDING_MODEL), "model_q4.onnx");
+        assert_eq!(embedding_model_file("embeddinggemma-300m"), "model.onnx");
+        assert_eq!(embedding_model_file("bge-small-en"), "bge-small-en.onnx");
+    }
+
     #[test]
     fn test_find_similar() {
         let query = vec![1.0, 0.0, 0.0];
@@ -7,9 +7,13 @@ use gliner::model::output::decoded::SpanOutput;
 use gliner::model::output::relation::RelationOutput;
 use orp::model::Model;
 use orp::params::RuntimeParameters;
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+use crate::inference_gate::InferenceGate;
+
 /// Entity types we extract from meeting transcripts
 /// Expanded labels for technical interviews and general conversations
 pub const ENTITY_LABELS: &[&str] = &[
@@ -41,6 +45,170 @@ pub const ENTITY_LABELS: &[&str] = &[
     "location",
 ];
 
+/// Spans GLiNER scores below this are dropped unless configured otherwise
+pub const DEFAULT_MIN_CONFIDENCE: f32 = 0.5;
+
+/// How the knowledge base files an entity label in the graph
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Person,
+    Topic,
+    ActionItem,
+    Decision,
+}
+
+/// Default label -> graph kind mapping; other labels are extracted but not stored as nodes
+pub fn default_label_kinds() -> HashMap<String, EntityKind> {
+    [
+        ("person", EntityKind::Person),
+        ("topic", EntityKind::Topic),
+        ("project", EntityKind::Topic),
+        ("product", EntityKind::Topic),
+        ("action_item", EntityKind::ActionItem),
+        ("decision", EntityKind::Decision),
+    ]
+    .into_iter()
+    .map(|(label, kind)| (label.to_string(), kind))
+    .collect()
+}
+
+/// Entity extraction settings: the GLiNER label prompt, span threshold and graph mapping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityConfig {
+    pub min_confidence: f32,
+    pub labels: Vec<String>,
+    #[serde(default = "default_label_kinds")]
+    pub label_kinds: HashMap<String, EntityKind>,
+}
+
+impl Default for EntityConfig {
+    fn default() -> Self {
+        Self {
+            min_confidence: DEFAULT_MIN_CONFIDENCE,
+            labels: ENTITY_LABELS.iter().map(|l| l.to_string()).collect(),
+            label_kinds: default_label_kinds(),
+        }
+    }
+}
+
+impl EntityConfig {
+    /// Build a config from labels plus extra label kinds (merged over the defaults)
+    ///
+    /// Labels are trimmed, lowercased and deduplicated; an empty list is rejected
+    /// since GLiNER needs at least one label to prompt with.
+    pub fn new(
+        min_confidence: f32,
+        labels: &[String],
+        extra_kinds: HashMap<String, EntityKind>,
+    ) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&min_confidence) {
+            return Err(format!("Entity confidence must be between 0 and 1, got {}", min_confidence));
+        }
+
+        let mut normalized: Vec<String> = Vec::new();
+        for label in labels {
+            let label = normalize_label(label);
+            if !label.is_empty() && !normalized.contains(&label) {
+                normalized.push(label);
+            }
+        }
+        if normalized.is_empty() {
+            return Err("At least one entity label is required".to_string());
+        }
+
+        let mut label_kinds = default_label_kinds();
+        for (label, kind) in extra_kinds {
+            label_kinds.insert(normalize_label(&label), kind);
+        }
+
+        Ok(Self { min_confidence, labels: normalized, label_kinds })
+    }
+
+    /// Build from the `entity_*` settings (JSON label list and kind map; "" = defaults)
+    pub fn from_settings(min_confidence: f32, labels_json: &str, kinds_json: &str) -> Result<Self, String> {
+        let labels: Vec<String> = if labels_json.trim().is_empty() {
+            ENTITY_LABELS.iter().map(|l| l.to_string()).collect()
+        } else {
+            serde_json::from_str(labels_json)
+                .map_err(|e| format!("Invalid entity_labels setting: {}", e))?
+        };
+        let kinds: HashMap<String, EntityKind> = if kinds_json.trim().is_empty() {
+            HashMap::new()
+        } else {
+            serde_json::from_str(kinds_json)
+                .map_err(|e| format!("Invalid entity_label_kinds setting: {}", e))?
+        };
+        Self::new(min_confidence, &labels, kinds)
+    }
+
+    /// Graph kind for an entity found in a meeting transcript
+    pub fn kind_of(&self, label: &str) -> Option<EntityKind> {
+        self.label_kinds.get(label).copied()
+    }
+
+    /// Graph kind for an entity found in a knowledge source
+    /// Sources also file organizations as topics so documents about a company link up.
+    pub fn source_kind_of(&self, label: &str) -> Option<EntityKind> {
+        self.kind_of(label).or((label == "organization").then_some(EntityKind::Topic))
+    }
+}
+
+/// Which extracted relationships get stored: a confidence floor and an optional
+/// whitelist of relation types (empty = keep every type GLiNER emits)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationConfig {
+    pub min_confidence: f32,
+    #[serde(default)]
+    pub allowed_relations: Vec<String>,
+}
+
+impl Default for RelationConfig {
+    fn default() -> Self {
+        Self { min_confidence: DEFAULT_MIN_CONFIDENCE, allowed_relations: Vec::new() }
+    }
+}
+
+impl RelationConfig {
+    /// Relation types are normalized like labels ("Works On" -> "works_on") and deduplicated
+    pub fn new(min_confidence: f32, allowed_relations: &[String]) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&min_confidence) {
+            return Err(format!("Relation confidence must be between 0 and 1, got {}", min_confidence));
+        }
+
+        let mut normalized: Vec<String> = Vec::new();
+        for relation in allowed_relations {
+            let relation = normalize_label(relation);
+            if !relation.is_empty() && !normalized.contains(&relation) {
+                normalized.push(relation);
+            }
+        }
+        Ok(Self { min_confidence, allowed_relations: normalized })
+    }
+
+    /// Build from the `relation_*` settings (JSON list of relation types; "" = all)
+    pub fn from_settings(min_confidence: f32, whitelist_json: &str) -> Result<Self, String> {
+        let relations: Vec<String> = if whitelist_json.trim().is_empty() {
+            Vec::new()
+        } else {
+            serde_json::from_str(whitelist_json)
+                .map_err(|e| format!("Invalid relation_whitelist setting: {}", e))?
+        };
+        Self::new(min_confidence, &relations)
+    }
+
+    /// Whether a relationship clears the threshold and is a whitelisted type
+    pub fn allows(&self, rel: &Relationship) -> bool {
+        rel.confidence >= self.min_confidence
+            && (self.allowed_relations.is_empty()
+                || self.allowed_relations.contains(&normalize_label(&rel.relation)))
+    }
+}
+
+fn normalize_label(label: &str) -> String {
+    label.trim().to_lowercase().split_whitespace().collect::<Vec<_>>().join("_")
+}
+
 /// An extracted entity from text
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entity {
@@ -89,6 +257,8 @@ pub struct EntityEngine {
     model: Model,
     params: Parameters,
     tokenizer_path: String,
+    config: RwLock<EntityConfig>,
+    gate: InferenceGate,  // Bounds concurrent GLiNER passes
 }
 
 /// Build relationship schema for meeting-related relations
@@ -150,29 +320,64 @@ impl EntityEngine {
             model,
             params: Parameters::default(),
             tokenizer_path: tokenizer_str,
+            config: RwLock::new(EntityConfig::default()),
+            gate: InferenceGate::default(),
         })
     }
 
+    /// Current extraction config
+    pub fn config(&self) -> EntityConfig {
+        self.config.read().clone()
+    }
+
+    /// Replace the label prompt, threshold and graph mapping used by later extractions
+    pub fn set_config(&self, config: EntityConfig) {
+        println!("[Entities] {} labels, min confidence {:.2}", config.labels.len(), config.min_confidence);
+        *self.config.write() = config;
+    }
+
+    /// Allow up to `limit` model passes at once; callers beyond that queue
+    pub fn set_max_concurrency(&self, limit: usize) {
+        self.gate.set_limit(limit);
+    }
+
+    /// Graph kind for a meeting entity label under the current config
+    pub fn kind_of(&self, label: &str) -> Option<EntityKind> {
+        self.config.read().kind_of(label)
+    }
+
+    /// Graph kind for a knowledge source entity label under the current config
+    pub fn source_kind_of(&self, label: &str) -> Option<EntityKind> {
+        self.config.read().source_kind_of(label)
+    }
+
     /// Extract entities from a single text
     pub fn extract(&self, text: &str) -> Result<Vec<Entity>, String> {
         if text.trim().is_empty() {
             return Ok(vec![]);
         }
 
-        let input = TextInput::from_str(&[text], ENTITY_LABELS)
+        let config = self.config();
+        let labels: Vec<&str> = config.labels.iter().map(String::as_str).collect();
+        let input = TextInput::from_str(&[text], &labels)
             .map_err(|e| format!("Failed to create input: {}", e))?;
 
         let token_pipeline = TokenPipeline::new(&self.tokenizer_path)
             .map_err(|e| format!("Failed to create token pipeline: {}", e))?;
 
-        let output: SpanOutput = self.model.inference(input, &token_pipeline, &self.params)
-            .map_err(|e| format!("Inference failed: {}", e))?;
+        let output: SpanOutput = {
+            let _permit = self.gate.acquire();
+            self.model.inference(input, &token_pipeline, &self.params)
+        }.map_err(|e| format!("Inference failed: {}", e))?;
 
         let mut entities = Vec::new();
 
         // Process first (and only) text result - output.spans is Vec<Vec<Span>>
         if let Some(text_spans) = output.spans.into_iter().next() {
             for span in text_spans {
+                if span.probability() < config.min_confidence {
+                    continue;
+                }
                 entities.push(Entity {
                     text: span.text().to_string(),
                     label: span.class().to_string(),
@@ -195,20 +400,27 @@ impl EntityEngine {
             return Ok((vec![], vec![]));
         }
 
-        let input = TextInput::from_str(&[text], ENTITY_LABELS)
+        let config = self.config();
+        let labels: Vec<&str> = config.labels.iter().map(String::as_str).collect();
+        let input = TextInput::from_str(&[text], &labels)
             .map_err(|e| format!("Failed to create input: {}", e))?;
 
         // First pass: Entity extraction with TokenPipeline
         let token_pipeline = TokenPipeline::new(&self.tokenizer_path)
             .map_err(|e| format!("Failed to create token pipeline: {}", e))?;
 
-        let entity_output: SpanOutput = self.model.inference(input, &token_pipeline, &self.params)
-            .map_err(|e| format!("Entity inference failed: {}", e))?;
+        let entity_output: SpanOutput = {
+            let _permit = self.gate.acquire();
+            self.model.inference(input, &token_pipeline, &self.params)
+        }.map_err(|e| format!("Entity inference failed: {}", e))?;
 
         // Collect entities
         let mut entities = Vec::new();
         if let Some(text_spans) = entity_output.spans.iter().next() {
             for span in text_spans {
+                if span.probability() < config.min_confidence {
+                    continue;
+                }
                 entities.push(Entity {
                     text: span.text().to_string(),
                     label: span.class().to_string(),
@@ -235,17 +447,93 @@ impl EntityEngine {
         Ok((entities, relationships))
     }
 
+    /// Extract entities AND relationships from several texts in one inference pass each
+    /// Results line up with `texts`; empty texts get empty results.
+    pub fn extract_batch_with_relations(&self, texts: &[&str]) -> Result<Vec<(Vec<Entity>, Vec<Relationship>)>, String> {
+        let mut results: Vec<(Vec<Entity>, Vec<Relationship>)> = vec![(vec![], vec![]); texts.len()];
+        let indices: Vec<usize> = (0..texts.len()).filter(|&i| !texts[i].trim().is_empty()).collect();
+        if indices.is_empty() {
+            return Ok(results);
+        }
+        let batch: Vec<&str> = indices.iter().map(|&i| texts[i]).collect();
+
+        let config = self.config();
+        let labels: Vec<&str> = config.labels.iter().map(String::as_str).collect();
+        let input = TextInput::from_str(&batch, &labels)
+            .map_err(|e| format!("Failed to create batch input: {}", e))?;
+
+        let token_pipeline = TokenPipeline::new(&self.tokenizer_path)
+            .map_err(|e| format!("Failed to create token pipeline: {}", e))?;
+
+        let entity_output: SpanOutput = {
+            let _permit = self.gate.acquire();
+            self.model.inference(input, &token_pipeline, &self.params)
+        }.map_err(|e| format!("Batch entity inference failed: {}", e))?;
+
+        let per_text: Vec<Vec<Entity>> = entity_output.spans.iter()
+            .map(|text_spans| {
+                let mut entities: Vec<Entity> = text_spans.iter()
+                    .filter(|span| span.probability() >= config.min_confidence)
+                    .map(|span| Entity {
+                        text: span.text().to_string(),
+                        label: span.class().to_string(),
+                        sequence: span.sequence(),
+                        confidence: span.probability(),
+                    })
+                    .collect();
+                entities.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+                entities
+            })
+            .collect();
+
+        // Relations are optional, as in extract_with_relations: a failed batch pass only
+        // drops them, and texts without entities never get any
+        let relationships = if per_text.iter().any(|e| !e.is_empty()) {
+            self.try_extract_relationships_batch(entity_output, &per_text).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+
+        for (n, entities) in per_text.into_iter().enumerate() {
+            let Some(&i) = indices.get(n) else { break };
+            let rels = if entities.is_empty() {
+                Vec::new()
+            } else {
+                relationships.get(n).cloned().unwrap_or_default()
+            };
+            results[i] = (entities, rels);
+        }
+        Ok(results)
+    }
+
     /// Try to extract relationships from entity output (helper that can fail gracefully)
     fn try_extract_relationships(&self, entity_output: SpanOutput, entities: &[Entity]) -> Result<Vec<Relationship>, String> {
+        let per_text = [entities.to_vec()];
+        Ok(self.try_extract_relationships_batch(entity_output, &per_text)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// Relationships per input text, typed from that text's entities
+    fn try_extract_relationships_batch(
+        &self,
+        entity_output: SpanOutput,
+        per_text: &[Vec<Entity>],
+    ) -> Result<Vec<Vec<Relationship>>, String> {
         let relation_schema = build_relation_schema();
         let relation_pipeline = RelationPipeline::default(&self.tokenizer_path, &relation_schema)
             .map_err(|e| format!("Failed to create relation pipeline: {}", e))?;
 
-        let relation_output: RelationOutput = self.model.inference(entity_output, &relation_pipeline, &self.params)
-            .map_err(|e| format!("Relation inference failed: {}", e))?;
+        let relation_output: RelationOutput = {
+            let _permit = self.gate.acquire();
+            self.model.inference(entity_output, &relation_pipeline, &self.params)
+        }.map_err(|e| format!("Relation inference failed: {}", e))?;
 
-        let mut relationships = Vec::new();
-        for seq_relations in relation_output.relations {
+        let mut per_text_relationships = Vec::with_capacity(per_text.len());
+        for (seq, seq_relations) in relation_output.relations.into_iter().enumerate() {
+            let entities = per_text.get(seq).map(Vec::as_slice).unwrap_or(&[]);
+            let mut relationships = Vec::new();
             for rel in seq_relations {
                 let source_type = entities.iter()
                     .find(|e| e.text == rel.subject())
@@ -265,10 +553,11 @@ impl EntityEngine {
                     confidence: rel.probability(),
                 });
             }
+            relationships.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
+            per_text_relationships.push(relationships);
         }
 
-        relationships.sort_by(|a, b| b.confidence.partial_cmp(&a.confidence).unwrap_or(std::cmp::Ordering::Equal));
-        Ok(relationships)
+        Ok(per_text_relationships)
     }
 
     /// Extract entities from multiple texts (batched for efficiency)
@@ -277,20 +566,25 @@ impl EntityEngine {
             return Ok(vec![]);
         }
 
-        let input = TextInput::from_str(texts, ENTITY_LABELS)
+        let config = self.config();
+        let labels: Vec<&str> = config.labels.iter().map(String::as_str).collect();
+        let input = TextInput::from_str(texts, &labels)
             .map_err(|e| format!("Failed to create batch input: {}", e))?;
 
         let token_pipeline = TokenPipeline::new(&self.tokenizer_path)
             .map_err(|e| format!("Failed to create token pipeline: {}", e))?;
 
-        let output: SpanOutput = self.model.inference(input, &token_pipeline, &self.params)
-            .map_err(|e| format!("Batch inference failed: {}", e))?;
+        let output: SpanOutput = {
+            let _permit = self.gate.acquire();
+            self.model.inference(input, &token_pipeline, &self.params)
+        }.map_err(|e| format!("Batch inference failed: {}", e))?;
 
         let mut results = Vec::new();
 
         for text_spans in output.spans {
             let mut entities: Vec<Entity> = text_spans
                 .into_iter()
+                .filter(|span| span.probability() >= config.min_confidence)
                 .map(|span| Entity {
                     text: span.text().to_string(),
                     label: span.class().to_string(),
@@ -393,4 +687,61 @@ mod tests {
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].text, "High");
     }
+
+    #[test]
+    fn test_default_config_keeps_graph_mapping() {
+        let config = EntityConfig::default();
+
+        assert_eq!(config.labels.len(), ENTITY_LABELS.len());
+        assert_eq!(config.kind_of("person"), Some(EntityKind::Person));
+        assert_eq!(config.kind_of("project"), Some(EntityKind::Topic));
+        assert_eq!(config.kind_of("action_item"), Some(EntityKind::ActionItem));
+        assert_eq!(config.kind_of("organization"), None);
+        assert_eq!(config.source_kind_of("organization"), Some(EntityKind::Topic));
+        assert_eq!(config.kind_of("metric"), None);
+    }
+
+    #[test]
+    fn test_custom_labels_from_settings() {
+        let config = EntityConfig::from_settings(
+            0.7,
+            r#"["person", " Ticket ID ", "repo", "person"]"#,
+            r#"{"repo": "topic"}"#,
+        ).unwrap();
+
+        assert_eq!(config.labels, vec!["person", "ticket_id", "repo"]);
+        assert_eq!(config.kind_of("repo"), Some(EntityKind::Topic));
+        assert_eq!(config.kind_of("ticket_id"), None);
+        assert_eq!(config.kind_of("decision"), Some(EntityKind::Decision));
+
+        assert!(EntityConfig::from_settings(0.5, "[]", "").is_err());
+        assert!(EntityConfig::from_settings(1.5, "", "").is_err());
+        assert!(EntityConfig::from_settings(0.5, "", r#"{"repo": "widget"}"#).is_err());
+    }
+
+    #[test]
+    fn test_relation_config_threshold_and_whitelist() {
+        let rel = |relation: &str, confidence: f32| Relationship {
+            source: "Alice".to_string(),
+            source_type: "person".to_string(),
+            relation: relation.to_string(),
+            target: "Atlas".to_string(),
+            target_type: "project".to_string(),
+            confidence,
+        };
+
+        let default = RelationConfig::default();
+        assert!(default.allows(&rel("related_to", 0.5)));
+        assert!(!default.allows(&rel("works_on", 0.49)));
+
+        let config = RelationConfig::from_settings(0.6, r#"["Works On", "reports_to", "works_on"]"#).unwrap();
+        assert_eq!(config.allowed_relations, vec!["works_on", "reports_to"]);
+        assert!(config.allows(&rel("works_on", 0.7)));
+        assert!(config.allows(&rel("Works On", 0.7)));
+        assert!(!config.allows(&rel("related_to", 0.9)));
+        assert!(!config.allows(&rel("reports_to", 0.55)));
+
+        assert!(RelationConfig::from_settings(-0.1, "").is_err());
+        assert!(RelationConfig::from_settings(0.5, "not json").is_err());
+    }
 }
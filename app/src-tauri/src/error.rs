@@ -0,0 +1,110 @@
+//! Typed errors for the knowledge base and LLM layers
+//!
+//! Errors serialize as `{ "kind": "not_initialized", "message": "..." }`, so the
+//! frontend can branch on `kind` (offer a model download, retry a network call,
+//! ...) instead of matching message text. Commands that still return `String`
+//! convert with `?` through `From<SecondBrainError> for String`, and errors from
+//! modules not migrated yet come in as `Other`.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "snake_case")]
+pub enum SecondBrainError {
+    /// A subsystem (knowledge base, LLM, engine) hasn't been set up yet
+    NotInitialized(String),
+    /// A model file is missing or doesn't match what the data was built with
+    ModelMissing(String),
+    /// Couldn't reach a remote service
+    Network(String),
+    /// The database rejected or failed a query
+    Db(String),
+    /// The LLM answered with an error or something unusable
+    Llm(String),
+    /// The request itself was bad (empty name, malformed setting, ...)
+    InvalidInput(String),
+    /// The record asked for doesn't exist
+    NotFound(String),
+    /// Not classified yet
+    Other(String),
+}
+
+impl SecondBrainError {
+    pub fn message(&self) -> &str {
+        match self {
+            Self::NotInitialized(message)
+            | Self::ModelMissing(message)
+            | Self::Network(message)
+            | Self::Db(message)
+            | Self::Llm(message)
+            | Self::InvalidInput(message)
+            | Self::NotFound(message)
+            | Self::Other(message) => message,
+        }
+    }
+
+    pub fn not_initialized(what: &str) -> Self {
+        Self::NotInitialized(format!("{} not initialized", what))
+    }
+
+    /// Prefix the message with what was being attempted, keeping the kind
+    pub fn context(self, context: impl std::fmt::Display) -> Self {
+        let wrap = |message: String| format!("{}: {}", context, message);
+        match self {
+            Self::NotInitialized(message) => Self::NotInitialized(wrap(message)),
+            Self::ModelMissing(message) => Self::ModelMissing(wrap(message)),
+            Self::Network(message) => Self::Network(wrap(message)),
+            Self::Db(message) => Self::Db(wrap(message)),
+            Self::Llm(message) => Self::Llm(wrap(message)),
+            Self::InvalidInput(message) => Self::InvalidInput(wrap(message)),
+            Self::NotFound(message) => Self::NotFound(wrap(message)),
+            Self::Other(message) => Self::Other(wrap(message)),
+        }
+    }
+}
+
+impl std::fmt::Display for SecondBrainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for SecondBrainError {}
+
+impl From<SecondBrainError> for String {
+    fn from(error: SecondBrainError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<String> for SecondBrainError {
+    fn from(message: String) -> Self {
+        Self::Other(message)
+    }
+}
+
+impl From<&str> for SecondBrainError {
+    fn from(message: &str) -> Self {
+        Self::Other(message.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serializes_kind_and_message() {
+        let error = SecondBrainError::NotInitialized("Knowledge base not initialized".to_string());
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "kind": "not_initialized", "message": "Knowledge base not initialized" }),
+        );
+        assert_eq!(String::from(error), "Knowledge base not initialized");
+        assert_eq!(SecondBrainError::from("boom"), SecondBrainError::Other("boom".to_string()));
+        assert_eq!(
+            SecondBrainError::Network("connection refused".to_string()).context("Failed to get response"),
+            SecondBrainError::Network("Failed to get response: connection refused".to_string()),
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Knowledge graph export
+//!
+//! Flattens people, topics and extracted entity relations into a plain
+//! nodes + edges graph that external tools (Gephi, Obsidian, yEd) can open,
+//! either as JSON or GraphML.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Output format for `KnowledgeBase::export_graph`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphExportFormat {
+    Json,
+    GraphML,
+}
+
+impl GraphExportFormat {
+    pub fn parse(format: &str) -> Result<Self, String> {
+        match format.trim().to_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "graphml" | "xml" => Ok(Self::GraphML),
+            other => Err(format!("Unknown graph export format '{}' (expected json or graphml)", other)),
+        }
+    }
+}
+
+/// A person, topic or other extracted entity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,          // "<type>:<lowercased name>", stable across exports
+    pub label: String,
+    pub node_type: String,
+    pub mention_count: u32,
+}
+
+/// A relation between two nodes; repeated relations are collapsed into one edge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub relation: String,
+    pub weight: u32,         // Number of times the relation was extracted
+    pub confidence: f32,     // Highest confidence among the collapsed relations
+}
+
+/// Nodes + edges snapshot of the knowledge graph
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphExport {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+/// Incrementally builds a `GraphExport`, merging duplicate nodes and edges
+#[derive(Default)]
+pub struct GraphBuilder {
+    nodes: Vec<GraphNode>,
+    node_index: HashMap<String, usize>,
+    edges: Vec<GraphEdge>,
+    edge_index: HashMap<(String, String, String), usize>,
+}
+
+pub fn node_id(node_type: &str, name: &str) -> String {
+    format!("{}:{}", node_type.trim().to_lowercase(), name.trim().to_lowercase())
+}
+
+impl GraphBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a node or add `mention_count` to an existing one; returns the node id
+    pub fn add_node(&mut self, node_type: &str, name: &str, mention_count: u32) -> String {
+        let id = node_id(node_type, name);
+        match self.node_index.get(&id) {
+            Some(&idx) => self.nodes[idx].mention_count += mention_count,
+            None => {
+                self.node_index.insert(id.clone(), self.nodes.len());
+                self.nodes.push(GraphNode {
+                    id: id.clone(),
+                    label: name.trim().to_string(),
+                    node_type: node_type.trim().to_lowercase(),
+                    mention_count,
+                });
+            }
+        }
+        id
+    }
+
+    /// Add a relation, creating missing endpoint nodes with zero mentions
+    pub fn add_relation(
+        &mut self,
+        source_type: &str,
+        source: &str,
+        relation: &str,
+        target_type: &str,
+        target: &str,
+        confidence: f32,
+    ) {
+        let source_id = self.add_node(source_type, source, 0);
+        let target_id = self.add_node(target_type, target, 0);
+        let relation = relation.trim().to_lowercase();
+        let key = (source_id.clone(), target_id.clone(), relation.clone());
+
+        match self.edge_index.get(&key) {
+            Some(&idx) => {
+                let edge = &mut self.edges[idx];
+                edge.weight += 1;
+                edge.confidence = edge.confidence.max(confidence);
+            }
+            None => {
+                self.edge_index.insert(key, self.edges.len());
+                self.edges.push(GraphEdge {
+                    source: source_id,
+                    target: target_id,
+                    relation,
+                    weight: 1,
+                    confidence,
+                });
+            }
+        }
+    }
+
+    pub fn build(self) -> GraphExport {
+        GraphExport { nodes: self.nodes, edges: self.edges }
+    }
+}
+
+impl GraphExport {
+    /// Serialize in the requested format
+    pub fn render(&self, format: GraphExportFormat) -> Result<String, String> {
+        match format {
+            GraphExportFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(|e| format!("Failed to serialize graph: {}", e)),
+            GraphExportFormat::GraphML => Ok(self.to_graphml()),
+        }
+    }
+
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+        out.push_str("  <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n");
+        out.push_str("  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n");
+        out.push_str("  <key id=\"mentions\" for=\"node\" attr.name=\"mention_count\" attr.type=\"int\"/>\n");
+        out.push_str("  <key id=\"relation\" for=\"edge\" attr.name=\"relation\" attr.type=\"string\"/>\n");
+        out.push_str("  <key id=\"weight\" for=\"edge\" attr.name=\"weight\" attr.type=\"double\"/>\n");
+        out.push_str("  <key id=\"confidence\" for=\"edge\" attr.name=\"confidence\" attr.type=\"double\"/>\n");
+        out.push_str("  <graph id=\"second-brain\" edgedefault=\"directed\">\n");
+
+        for node in &self.nodes {
+            out.push_str(&format!("    <node id=\"{}\">\n", xml_escape(&node.id)));
+            out.push_str(&format!("      <data key=\"label\">{}</data>\n", xml_escape(&node.label)));
+            out.push_str(&format!("      <data key=\"type\">{}</data>\n", xml_escape(&node.node_type)));
+            out.push_str(&format!("      <data key=\"mentions\">{}</data>\n", node.mention_count));
+            out.push_str("    </node>\n");
+        }
+
+        for (i, edge) in self.edges.iter().enumerate() {
+            out.push_str(&format!(
+                "    <edge id=\"e{}\" source=\"{}\" target=\"{}\">\n",
+                i, xml_escape(&edge.source), xml_escape(&edge.target)
+            ));
+            out.push_str(&format!("      <data key=\"relation\">{}</data>\n", xml_escape(&edge.relation)));
+            out.push_str(&format!("      <data key=\"weight\">{}</data>\n", edge.weight));
+            out.push_str(&format!("      <data key=\"confidence\">{:.3}</data>\n", edge.confidence));
+            out.push_str("    </edge>\n");
+        }
+
+        out.push_str("  </graph>\n");
+        out.push_str("</graphml>\n");
+        out
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_relations_collapse_with_weight() {
+        let mut builder = GraphBuilder::new();
+        builder.add_node("person", "Alice", 3);
+        builder.add_relation("person", "Alice", "works_on", "project", "Atlas", 0.6);
+        builder.add_relation("person", "alice ", "Works_On", "project", "atlas", 0.9);
+        builder.add_relation("person", "Alice", "manages", "project", "Atlas", 0.5);
+        let graph = builder.build();
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.nodes[0].mention_count, 3);
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.edges[0].weight, 2);
+        assert!((graph.edges[0].confidence - 0.9).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_graphml_escapes_labels() {
+        let mut builder = GraphBuilder::new();
+        builder.add_relation("organization", "R&D <Labs>", "part_of", "organization", "Acme", 0.8);
+        let graphml = builder.build().to_graphml();
+
+        assert!(graphml.contains("R&amp;D &lt;Labs&gt;"));
+        assert!(graphml.contains("source=\"organization:r&amp;d &lt;labs&gt;\""));
+        assert!(!graphml.contains("<Labs>"));
+    }
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(GraphExportFormat::parse("JSON").unwrap(), GraphExportFormat::Json);
+        assert_eq!(GraphExportFormat::parse("graphml").unwrap(), GraphExportFormat::GraphML);
+        assert!(GraphExportFormat::parse("csv").is_err());
+    }
+}
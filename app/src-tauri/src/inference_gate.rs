@@ -0,0 +1,118 @@
+//! Bounded concurrency for ONNX inference
+//!
+//! The embedding and entity engines are shared through `Arc` by commands, Graph-RAG's
+//! parallel lookups and the agent worker pool, so several callers can reach the same
+//! ONNX session at once. Each engine owns an `InferenceGate`: a counting semaphore that
+//! lets at most `limit` model passes run together and queues the rest. Inference is
+//! synchronous, so waiting blocks the calling thread like the pass itself would.
+
+use parking_lot::{Condvar, Mutex};
+
+/// Model passes allowed to run at once per engine when nothing is configured
+pub const DEFAULT_INFERENCE_CONCURRENCY: usize = 1;
+
+/// Upper bound for the `inference_concurrency` setting
+pub const MAX_INFERENCE_CONCURRENCY: usize = 8;
+
+struct GateState {
+    limit: usize,
+    running: usize,
+}
+
+/// Counting semaphore around an engine's model passes
+pub struct InferenceGate {
+    state: Mutex<GateState>,
+    freed: Condvar,
+}
+
+/// A running model pass; frees its slot when dropped
+pub struct InferencePermit<'a> {
+    gate: &'a InferenceGate,
+}
+
+impl InferenceGate {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            state: Mutex::new(GateState { limit: clamp_limit(limit), running: 0 }),
+            freed: Condvar::new(),
+        }
+    }
+
+    pub fn limit(&self) -> usize {
+        self.state.lock().limit
+    }
+
+    /// Change the limit; passes already running finish, queued ones see the new limit
+    pub fn set_limit(&self, limit: usize) {
+        self.state.lock().limit = clamp_limit(limit);
+        self.freed.notify_all();
+    }
+
+    /// Wait for a free slot
+    pub fn acquire(&self) -> InferencePermit<'_> {
+        let mut state = self.state.lock();
+        while state.running >= state.limit {
+            self.freed.wait(&mut state);
+        }
+        state.running += 1;
+        InferencePermit { gate: self }
+    }
+}
+
+impl Default for InferenceGate {
+    fn default() -> Self {
+        Self::new(DEFAULT_INFERENCE_CONCURRENCY)
+    }
+}
+
+impl Drop for InferencePermit<'_> {
+    fn drop(&mut self) {
+        self.gate.state.lock().running -= 1;
+        self.gate.freed.notify_one();
+    }
+}
+
+fn clamp_limit(limit: usize) -> usize {
+    limit.clamp(1, MAX_INFERENCE_CONCURRENCY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_gate_never_exceeds_limit() {
+        let gate = Arc::new(InferenceGate::new(2));
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let (gate, running, peak) = (gate.clone(), running.clone(), peak.clone());
+                std::thread::spawn(move || {
+                    let _permit = gate.acquire();
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    std::thread::sleep(std::time::Duration::from_millis(10));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(gate.state.lock().running, 0);
+    }
+
+    #[test]
+    fn test_limit_is_clamped() {
+        let gate = InferenceGate::new(0);
+        assert_eq!(gate.limit(), 1);
+        gate.set_limit(100);
+        assert_eq!(gate.limit(), MAX_INFERENCE_CONCURRENCY);
+    }
+}
@@ -1,12 +1,202 @@
+use crate::action_export::{ActionExportFormat, ExportActionItem};
+use crate::asr::{interpolate_word_timings, WordTiming};
+use crate::chunker::{ChunkStrategy, ChunkerConfig, DocumentChunker};
 use crate::embeddings::EmbeddingEngine;
-use crate::entities::{Entity, EntityEngine, Relationship};
+use crate::entities::{Entity, EntityEngine, EntityKind, RelationConfig, Relationship};
+use crate::error::SecondBrainError;
+use crate::graph_export::{GraphBuilder, GraphExport, GraphExportFormat};
+use crate::redaction::Redactor;
+use crate::sqlite_export::KbSnapshot;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Months, NaiveDate, TimeZone};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, LazyLock};
 use surrealdb::engine::local::{Db, RocksDb};
 use surrealdb::sql::Thing;
 use surrealdb::Surreal;
 
+/// Default similarity a knowledge source needs to be auto-linked to a meeting
+pub const DEFAULT_AUTO_LINK_THRESHOLD: f32 = 0.6;
+
+/// Transcript prefix embedded when matching a meeting against knowledge sources
+const AUTO_LINK_MAX_CHARS: usize = 8000;
+
+/// Number of chunks considered when auto-linking knowledge sources
+const AUTO_LINK_CHUNK_LIMIT: usize = 50;
+
+/// Chunks embedded per forward pass during ingestion; each batch is stored before the next
+const INGEST_BATCH_SIZE: usize = 16;
+
+/// Names of the `person` entities, for redaction
+fn person_names(entities: &[Entity]) -> Vec<String> {
+    entities.iter()
+        .filter(|e| e.label == "person")
+        .map(|e| e.text.clone())
+        .collect()
+}
+
+/// Evenly respread timings over redacted text, keeping the original overall span
+fn redacted_word_timings(redacted_text: &str, timings: &[WordTiming]) -> Vec<WordTiming> {
+    let span_start = timings.first().map(|t| t.1).unwrap_or(0);
+    let span_end = timings.last().map(|t| t.2).unwrap_or(0);
+    if span_end <= span_start {
+        return Vec::new();
+    }
+    interpolate_word_timings(redacted_text, span_end - span_start)
+        .into_iter()
+        .map(|(word, start, end)| (word, start + span_start, end + span_start))
+        .collect()
+}
+
+/// Case-insensitive Levenshtein distance between two names
+pub fn name_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.trim().to_lowercase().chars().collect();
+    let b: Vec<char> = b.trim().to_lowercase().chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut row = Vec::with_capacity(b.len() + 1);
+        row.push(i + 1);
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev[j] + usize::from(ca != cb);
+            row.push(substitute.min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// Index of the entity whose name forms (canonical name first, then aliases) best match `query`
+/// An exact match on any form, ignoring case, wins. Otherwise the closest form within
+/// `max_distance` edits matches, allowing at most one edit per three characters of the
+/// query so short names aren't matched to unrelated ones. Ties go to the earlier entity.
+pub fn best_name_match(query: &str, entities: &[Vec<&str>], max_distance: usize) -> Option<usize> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+    let exact = entities.iter().position(|forms| {
+        forms.iter().any(|form| form.trim().to_lowercase() == query.to_lowercase())
+    });
+    if exact.is_some() {
+        return exact;
+    }
+
+    let allowed = max_distance.min(query.chars().count() / 3);
+    if allowed == 0 {
+        return None;
+    }
+    entities.iter()
+        .enumerate()
+        .filter_map(|(i, forms)| forms.iter().map(|form| name_distance(query, form)).min().map(|d| (d, i)))
+        .filter(|(d, _)| *d <= allowed)
+        .min()
+        .map(|(_, i)| i)
+}
+
+/// Word overlap above which a new action item is treated as a repeat of a stored one
+const ACTION_DUPLICATE_THRESHOLD: f32 = 0.6;
+
+/// Word-set (Jaccard) overlap between two action item texts, ignoring case and short words
+pub fn action_text_similarity(a: &str, b: &str) -> f32 {
+    fn words(text: &str) -> std::collections::HashSet<String> {
+        text.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() > 2)
+            .map(|w| w.to_string())
+            .collect()
+    }
+
+    let (a, b) = (words(a), words(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f32 / a.union(&b).count() as f32
+}
+
+/// Strip a `table:` prefix (and SurrealDB's ⟨⟩ id quoting) so ids are stored and
+/// compared in one bare form regardless of how the caller passed them
+pub fn normalize_record_id(table: &str, id: &str) -> String {
+    let id = id.trim();
+    let bare = id
+        .strip_prefix(table)
+        .and_then(|rest| rest.strip_prefix(':'))
+        .unwrap_or(id);
+    bare.strip_prefix('⟨')
+        .and_then(|rest| rest.strip_suffix('⟩'))
+        .unwrap_or(bare)
+        .to_string()
+}
+
+/// Trim an optional text field, treating a blank value as absent
+fn blank_to_none(value: Option<&str>) -> Option<String> {
+    value.map(str::trim).filter(|v| !v.is_empty()).map(str::to_string)
+}
+
+/// Whether an action item's assignee names one of `names`, as whole words ignoring case
+/// Unassigned items belong to no one, and "Al" doesn't match "also".
+fn is_assigned_to(assignee: Option<&str>, names: &[String]) -> bool {
+    let words = |text: &str| -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    };
+    let Some(assignee) = assignee.map(words).filter(|w| !w.is_empty()) else {
+        return false;
+    };
+    names.iter()
+        .map(|n| words(n))
+        .filter(|name| !name.is_empty())
+        .any(|name| assignee.windows(name.len()).any(|w| w == name.as_slice()))
+}
+
+/// A meeting id in bare form, accepted from the frontend as either `meeting:abc` or `abc`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(transparent)]
+pub struct MeetingId(String);
+
+impl MeetingId {
+    pub fn new(id: &str) -> Self {
+        Self(normalize_record_id("meeting", id))
+    }
+
+    /// The bare id, as stored in `meeting_id` fields
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The full record id (`meeting:abc`)
+    pub fn thing_string(&self) -> String {
+        format!("meeting:{}", self.0)
+    }
+}
+
+impl std::fmt::Display for MeetingId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for MeetingId {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+impl From<String> for MeetingId {
+    fn from(id: String) -> Self {
+        Self::new(&id)
+    }
+}
+
+impl<'de> Deserialize<'de> for MeetingId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Ok(Self::new(&raw))
+    }
+}
+
 /// A meeting record in the knowledge base
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Meeting {
@@ -16,8 +206,35 @@ pub struct Meeting {
     pub end_time: Option<u64>,
     pub participants: Vec<String>,
     pub summary: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub favorite: bool,
+    /// Kept out of global search and Graph-RAG; only reachable through the meeting itself
+    #[serde(default)]
+    pub private: bool,
+    /// Language most segments were spoken in, set when the meeting ends; None if mixed or unknown
+    #[serde(default)]
+    pub dominant_language: Option<String>,
+    /// When the meeting was moved to the trash; hidden everywhere until restored or purged
+    #[serde(default)]
+    pub deleted_at: Option<u64>,
+}
+
+/// Tag that exempts a meeting from the retention policy
+pub const RETENTION_KEEP_TAG: &str = "keep";
+
+/// Whether the retention policy may delete `meeting` (ended before `cutoff`, not favorited or tagged "keep")
+fn past_retention(meeting: &Meeting, cutoff: u64) -> bool {
+    meeting.end_time.is_some()
+        && meeting.start_time < cutoff
+        && !meeting.favorite
+        && !meeting.tags.iter().any(|t| t.trim().eq_ignore_ascii_case(RETENTION_KEEP_TAG))
 }
 
+/// Diarized span as (start_ms, end_ms, speaker_id, speaker_label, confidence)
+pub type DiarizationTuple = (u64, u64, i32, String, Option<f32>);
+
 /// A transcript segment from a meeting
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TranscriptSegment {
@@ -28,6 +245,24 @@ pub struct TranscriptSegment {
     pub start_ms: u64,
     pub end_ms: u64,
     pub embedding: Vec<f32>,
+    /// Per-word (word, start, end) offsets from `start_ms`; empty for older segments
+    #[serde(default)]
+    pub word_timings: Vec<WordTiming>,
+    /// How sure diarization was about `speaker` (0-1); None if never diarized
+    #[serde(default)]
+    pub speaker_confidence: Option<f32>,
+    /// Diarization heard another speaker talking over this segment (crosstalk)
+    #[serde(default)]
+    pub overlapped: bool,
+    /// Language the recognizer detected (e.g. "en"); None for older segments
+    #[serde(default)]
+    pub language: Option<String>,
+    /// Emotion the recognizer heard (e.g. "happy"); None for older segments
+    #[serde(default)]
+    pub emotion: Option<String>,
+    /// Stored without an embedding because the model failed; filled by `backfill_missing_embeddings`
+    #[serde(default)]
+    pub needs_embedding: bool,
 }
 
 /// An action item extracted from meetings
@@ -83,6 +318,130 @@ pub struct KnowledgeSource {
     pub tags: Vec<String>,
     pub created_at: u64,
     pub last_updated: u64,
+    #[serde(default)]
+    pub last_accessed: Option<u64>,  // Last time a knowledge search returned one of its chunks
+    #[serde(default)]
+    pub pinned: bool,  // Always included in Graph-RAG context, ahead of similarity results
+    #[serde(default)]
+    pub refresh_interval_hours: Option<u32>,  // Re-crawl this often (web pages only); None = manual
+    #[serde(default)]
+    pub last_refreshed: Option<u64>,  // Last time the page was re-crawled, changed or not
+}
+
+impl KnowledgeSource {
+    /// Only crawled web pages can be fetched again
+    pub fn is_refreshable(&self) -> bool {
+        self.url.starts_with("http://") || self.url.starts_with("https://")
+    }
+
+    /// Auto-refresh is on and its interval has passed since the last check
+    pub fn refresh_due(&self, now: u64) -> bool {
+        match self.refresh_interval_hours {
+            Some(hours) if hours > 0 && self.is_refreshable() => {
+                let checked = self.last_refreshed.unwrap_or(self.last_updated);
+                now.saturating_sub(checked) >= hours as u64 * 60 * 60 * 1000
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Outcome of re-crawling a knowledge source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRefresh {
+    pub source_id: String,
+    pub changed: bool,        // The page differed from the stored content and was re-embedded
+    pub chunk_count: usize,   // Chunks stored for the source now
+}
+
+/// Outcome of `bulk_delete_sources`: sources removed and the ids that failed (with why)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BulkDeleteResult {
+    pub deleted: usize,
+    pub failed: Vec<String>,
+}
+
+/// Rows returned per statement by `run_raw_query`
+pub const RAW_QUERY_ROW_LIMIT: usize = 500;
+
+/// Keywords that change data or schema; raw queries containing them need `allow_writes`
+const RAW_QUERY_WRITE_KEYWORDS: &[&str] = &[
+    "REMOVE", "DEFINE", "DELETE", "UPDATE", "UPSERT", "CREATE", "INSERT", "RELATE", "ALTER",
+];
+
+/// Reject raw SurrealQL that would modify the database or switch it unless writes are allowed
+/// Every statement must be a SELECT, and write keywords are refused anywhere (subqueries
+/// included). Both checks are textual, so a `;` or keyword inside a string literal is
+/// refused too; that errs on the side of not touching the DB.
+pub fn check_raw_query(surql: &str, allow_writes: bool) -> Result<(), String> {
+    if surql.trim().is_empty() {
+        return Err("Query is empty".to_string());
+    }
+    if allow_writes {
+        return Ok(());
+    }
+    let upper = surql.to_uppercase();
+    for statement in upper.split(';').map(str::trim).filter(|s| !s.is_empty()) {
+        let first = statement.split_whitespace().next().unwrap_or_default();
+        if first != "SELECT" {
+            return Err(format!("Only SELECT statements run read-only, found {}; pass allow_writes to run other statements", first));
+        }
+    }
+    let found = upper
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .find(|word| RAW_QUERY_WRITE_KEYWORDS.contains(word));
+    match found {
+        Some(keyword) => Err(format!("Query contains {}; pass allow_writes to run statements that modify the database", keyword)),
+        None => Ok(()),
+    }
+}
+
+/// How many knowledge sources and chunks carry a tag
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagStats {
+    pub tag: String,
+    pub source_count: usize,
+    pub chunk_count: usize,
+}
+
+/// Per-tag totals from each source's tags and chunk count, most used tags first
+pub fn tag_stats(sources: &[(Vec<String>, usize)]) -> Vec<TagStats> {
+    let mut by_tag: std::collections::HashMap<&str, TagStats> = std::collections::HashMap::new();
+    for (tags, chunk_count) in sources {
+        let unique: std::collections::HashSet<&str> = tags.iter().map(|t| t.as_str()).collect();
+        for tag in unique {
+            let stats = by_tag.entry(tag).or_insert_with(|| TagStats {
+                tag: tag.to_string(),
+                source_count: 0,
+                chunk_count: 0,
+            });
+            stats.source_count += 1;
+            stats.chunk_count += chunk_count;
+        }
+    }
+
+    let mut stats: Vec<TagStats> = by_tag.into_values().collect();
+    stats.sort_by(|a, b| b.source_count.cmp(&a.source_count).then_with(|| a.tag.cmp(&b.tag)));
+    stats
+}
+
+/// Replace `old` with `new` in a tag list (None drops it), keeping order and removing duplicates
+fn retag(tags: &[String], old: &str, new: Option<&str>) -> Vec<String> {
+    let mut result: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = if tag == old {
+            match new {
+                Some(new) => new,
+                None => continue,
+            }
+        } else {
+            tag.as_str()
+        };
+        if !result.iter().any(|t| t == tag) {
+            result.push(tag.to_string());
+        }
+    }
+    result
 }
 
 /// A chunk from a knowledge source with embedding
@@ -93,6 +452,104 @@ pub struct KnowledgeChunk {
     pub text: String,
     pub chunk_index: i32,
     pub embedding: Vec<f32>,
+    #[serde(default)]
+    pub needs_embedding: bool,  // Stored without an embedding; see `backfill_missing_embeddings`
+}
+
+/// Chunk embedding progress while a knowledge source is ingested
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestionProgress {
+    pub source_id: String,
+    pub title: String,
+    pub embedded_chunks: usize,   // Chunks stored so far, including ones kept from earlier attempts
+    pub total_chunks: usize,
+    pub resumed_chunks: usize,    // Chunks an earlier, interrupted attempt already stored
+}
+
+/// Embedding model the stored vectors were produced with (`kb_meta:embedding`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingMeta {
+    pub model_id: String,
+    pub dimension: usize,
+}
+
+/// Loaded embedding model vs the one the stored vectors came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingStatus {
+    pub model_id: String,
+    pub dimension: usize,
+    pub stored: Option<EmbeddingMeta>,
+    pub mismatch: Option<String>,
+}
+
+/// Re-embedding progress for one table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReembedProgress {
+    pub table: String,
+    pub done: usize,
+    pub total: usize,
+}
+
+/// Entity reprocessing progress: segments of the current meeting, and meetings overall
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityReprocessProgress {
+    pub meeting_id: String,
+    pub segments_done: usize,
+    pub segments_total: usize,
+    pub meetings_done: usize,
+    pub meetings_total: usize,
+}
+
+/// What an entity reprocessing run extracted
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntityReprocessStats {
+    pub meetings: usize,
+    pub segments: usize,
+    pub entities: usize,
+    pub relationships: usize,
+}
+
+/// Segments run through entity extraction per forward pass when reprocessing
+const REPROCESS_BATCH_SIZE: usize = 16;
+
+/// Tables holding embeddings, with the field each embedding is computed from
+const EMBEDDED_TABLES: &[(&str, &str)] = &[
+    ("segment", "text"),
+    ("knowledge_chunk", "text"),
+    ("topic", "name"),
+];
+
+/// Rows re-embedded per page by `reembed_all` and `backfill_missing_embeddings`
+const REEMBED_PAGE_SIZE: usize = 64;
+
+/// Embedded tables whose rows can be stored unembedded and flagged `needs_embedding`
+const DEFERRABLE_TABLES: &[(&str, &str)] = &[
+    ("segment", "text"),
+    ("knowledge_chunk", "text"),
+];
+
+/// A stored row whose embedding is empty or has the wrong dimension
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenEmbedding {
+    pub table: String,
+    pub id: String,
+    pub text: String,
+    pub dimension: usize,
+    pub expected: usize,
+}
+
+/// Check that an embedding is non-empty and has the expected dimension
+pub fn check_embedding(embedding: &[f32], expected: usize) -> Result<(), String> {
+    if embedding.is_empty() {
+        return Err("Embedding engine returned an empty embedding".to_string());
+    }
+    if embedding.len() != expected {
+        return Err(format!(
+            "Embedding has {} dimensions but the loaded model produces {}",
+            embedding.len(), expected
+        ));
+    }
+    Ok(())
 }
 
 /// Link between a meeting and a knowledge source
@@ -105,6 +562,37 @@ pub struct MeetingKnowledge {
     pub assigned_by: String, // "user" or "auto"
 }
 
+/// Relations allowed between meetings; links point from the later meeting to the earlier one
+pub const MEETING_RELATIONS: &[&str] = &["follow_up", "continues", "references"];
+
+/// An explicit link between two meetings (`from_meeting` is a follow-up to `to_meeting`, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingLink {
+    pub id: Option<Thing>,
+    pub from_meeting: String,
+    pub to_meeting: String,
+    pub relation: String,
+    pub created_at: u64,
+}
+
+/// A meeting linked to the one being looked at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelatedMeeting {
+    pub meeting: Meeting,
+    pub relation: String,
+    pub outgoing: bool,      // true if the looked-at meeting is the `from` side
+}
+
+/// A moment flagged during recording to revisit later
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingBookmark {
+    pub id: Option<Thing>,
+    pub meeting_id: String,
+    pub offset_ms: u64,      // Since recording started
+    pub timestamp_ms: u64,   // Wall clock, comparable with segment start_ms
+    pub note: Option<String>,
+}
+
 /// Search result from knowledge chunks
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KnowledgeSearchResult {
@@ -118,6 +606,76 @@ pub struct KnowledgeSearchResult {
 // Graph-RAG Types
 // ============================================================================
 
+/// Context length the default retrieval counts are sized for
+const REFERENCE_CONTEXT_TOKENS: usize = 8192;
+
+/// How much Graph-RAG retrieves per question, from the `retrieval_params` setting
+///
+/// The setting is a JSON object with any of these fields, e.g.
+/// `{"max_meetings": 5, "max_chunks": 8}`; missing fields keep their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetrievalConfig {
+    pub max_meetings: usize,
+    pub segments_per_meeting: usize,
+    pub max_chunks: usize,
+    pub max_people: usize,
+    pub max_topics: usize,
+    /// Top chunks taken from each pinned knowledge source
+    pub pinned_chunks_per_source: usize,
+    /// Model context length in tokens; None = ask the LLM server
+    pub context_length: Option<usize>,
+    /// Most typos a person or topic name in a question may have and still match (0 = exact only)
+    pub name_max_distance: usize,
+}
+
+impl Default for RetrievalConfig {
+    fn default() -> Self {
+        Self {
+            max_meetings: 3,
+            segments_per_meeting: 2,
+            max_chunks: 5,
+            max_people: 5,
+            max_topics: 5,
+            pinned_chunks_per_source: 2,
+            context_length: None,
+            name_max_distance: 2,
+        }
+    }
+}
+
+impl RetrievalConfig {
+    pub fn from_config(params_json: &str) -> Result<Self, String> {
+        if params_json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let config: Self = serde_json::from_str(params_json)
+            .map_err(|e| format!("Invalid retrieval_params setting: {}", e))?;
+        if config.context_length.is_some_and(|n| n < 512) {
+            return Err("retrieval_params context_length must be at least 512 tokens".to_string());
+        }
+        Ok(config)
+    }
+
+    /// Shrink the counts to fit a model's context window
+    /// The configured counts are a ceiling sized for 8k tokens; smaller windows get fewer
+    /// items (at least one of each enabled kind), larger ones keep them. Zero keeps a kind disabled.
+    pub fn fit_to_context(&self, context_tokens: usize) -> Self {
+        let factor = (context_tokens as f64 / REFERENCE_CONTEXT_TOKENS as f64).clamp(0.25, 1.0);
+        let scale = |n: usize| if n == 0 { 0 } else { ((n as f64 * factor).round() as usize).max(1) };
+        Self {
+            max_meetings: scale(self.max_meetings),
+            segments_per_meeting: scale(self.segments_per_meeting),
+            max_chunks: scale(self.max_chunks),
+            max_people: scale(self.max_people),
+            max_topics: scale(self.max_topics),
+            pinned_chunks_per_source: scale(self.pinned_chunks_per_source),
+            context_length: self.context_length,
+            name_max_distance: self.name_max_distance,
+        }
+    }
+}
+
 /// Context retrieved via Graph-RAG
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphRAGContext {
@@ -133,12 +691,32 @@ pub struct GraphRAGContext {
     pub open_actions: Vec<ActionItem>,
     /// Recent decisions (temporal)
     pub recent_decisions: Vec<Decision>,
-    /// Vector-similar chunks
+    /// Top chunks of pinned sources, included regardless of similarity
+    pub pinned_chunks: Vec<KnowledgeSearchResult>,
+    /// Vector-similar chunks (excluding those already in `pinned_chunks`)
     pub similar_chunks: Vec<KnowledgeSearchResult>,
     /// Temporal info
     pub temporal_context: Option<TemporalContext>,
 }
 
+impl GraphRAGContext {
+    /// Drop everything that came from a private or trashed meeting (`private` holds bare meeting ids)
+    /// The queries already skip those meetings; this also catches actions and decisions,
+    /// which are fetched without a meeting filter.
+    pub fn exclude_private(&mut self, private: &std::collections::HashSet<String>) {
+        let is_private = |meeting_id: &str| private.contains(&normalize_record_id("meeting", meeting_id));
+        self.related_meetings.retain(|ctx| {
+            let id = ctx.meeting.id.as_ref().map(|t| t.to_string()).unwrap_or_default();
+            !ctx.meeting.private && ctx.meeting.deleted_at.is_none() && !is_private(&id)
+        });
+        for ctx in &mut self.related_meetings {
+            ctx.relevant_segments.retain(|seg| !is_private(&seg.meeting_id));
+        }
+        self.open_actions.retain(|a| !is_private(&a.meeting_id));
+        self.recent_decisions.retain(|d| !is_private(&d.meeting_id));
+    }
+}
+
 /// Meeting with temporal context
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MeetingContext {
@@ -156,6 +734,36 @@ pub struct PersonContext {
     pub recent_topics: Vec<String>,
 }
 
+/// A relation involving a person, with the meeting it was extracted from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonRelation {
+    pub relation: String,
+    pub other: String,
+    pub other_type: String,
+    pub outgoing: bool,               // The person is the relation's source
+    pub confidence: f32,
+    pub meeting_id: Option<String>,
+}
+
+/// Everything known about one person, for person-scoped questions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersonDossier {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub segments: Vec<TranscriptSegment>,  // Spoken by or mentioning the person, newest first
+    pub relations: Vec<PersonRelation>,
+    pub topics: Vec<String>,
+    pub open_actions: Vec<ActionItem>,
+    pub related_people: Vec<String>,
+    pub meetings: Vec<Meeting>,            // Meetings the material above came from, newest first
+}
+
+impl PersonDossier {
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty() && self.relations.is_empty() && self.open_actions.is_empty()
+    }
+}
+
 /// Topic with temporal info
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TopicContext {
@@ -173,6 +781,300 @@ pub struct TemporalContext {
     pub end_timestamp: Option<u64>,
 }
 
+/// Month names and abbreviations recognized in temporal expressions
+const MONTH_NAMES: &[(&str, u32)] = &[
+    ("january", 1), ("february", 2), ("march", 3), ("april", 4), ("may", 5), ("june", 6),
+    ("july", 7), ("august", 8), ("september", 9), ("october", 10), ("november", 11), ("december", 12),
+    ("jan", 1), ("feb", 2), ("mar", 3), ("apr", 4), ("jun", 6), ("jul", 7), ("aug", 8),
+    ("sept", 9), ("sep", 9), ("oct", 10), ("nov", 11), ("dec", 12),
+];
+
+const TEMPORAL_COUNT: &str = r"(\d+|an?|one|two|three|four|five|six|seven|eight|nine|ten|eleven|twelve)";
+const TEMPORAL_DATE: &str = r"(\d{4})-(\d{1,2})-(\d{1,2})";
+
+fn temporal_regex(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("valid temporal pattern")
+}
+
+/// "between 2024-03-01 and 2024-03-15", "from 2024-03-01 to 2024-03-15"
+static DATE_RANGE_RE: LazyLock<Regex> = LazyLock::new(|| temporal_regex(&format!(
+    r"\b(?:between|from)\s+{d}\s+(?:and|to|until|through)\s+{d}\b", d = TEMPORAL_DATE
+)));
+/// "between March and April", "from jan 2024 to mar 2024"
+static MONTH_RANGE_RE: LazyLock<Regex> = LazyLock::new(|| temporal_regex(&format!(
+    r"\b(?:between|from)\s+{m}(?:\s+(\d{{4}}))?\s+(?:and|to|until|through)\s+{m}(?:\s+(\d{{4}}))?\b",
+    m = month_alternation()
+)));
+/// "on 2024-03-15"
+static DATE_RE: LazyLock<Regex> = LazyLock::new(|| temporal_regex(&format!(r"\b{}\b", TEMPORAL_DATE)));
+/// "in Q2", "q3 2023"
+static QUARTER_RE: LazyLock<Regex> = LazyLock::new(|| temporal_regex(r"\bq([1-4])(?:\s+(\d{4}))?\b"));
+/// "last 3 months", "past two weeks"
+static ROLLING_RE: LazyLock<Regex> = LazyLock::new(|| temporal_regex(&format!(
+    r"\b(?:last|past)\s+{}\s+(day|week|month|year)s?\b", TEMPORAL_COUNT
+)));
+/// "3 weeks ago", "two months ago"
+static AGO_RE: LazyLock<Regex> = LazyLock::new(|| temporal_regex(&format!(
+    r"\b{}\s+(day|week|month|year)s?\s+ago\b", TEMPORAL_COUNT
+)));
+static TODAY_RE: LazyLock<Regex> = LazyLock::new(|| temporal_regex(r"\btoday\b"));
+static YESTERDAY_RE: LazyLock<Regex> = LazyLock::new(|| temporal_regex(r"\byesterday\b"));
+/// "this week", "last month", "past year"
+static PERIOD_RE: LazyLock<Regex> = LazyLock::new(|| temporal_regex(r"\b(this|last|past)\s+(week|month|year)\b"));
+/// "in March", "since april", "december 2023"
+static MONTH_RE: LazyLock<Regex> = LazyLock::new(|| temporal_regex(&format!(
+    r"\b(?:(in|during|of|from|since|last|this)\s+)?{}(?:\s+(\d{{4}}))?\b", month_alternation()
+)));
+
+/// Parse temporal references in a query relative to `now`
+///
+/// Handles explicit ISO dates and date ranges, month names and month ranges,
+/// quarters, "N days/weeks/months/years ago", "last N <unit>", "this/last/past
+/// week/month/year", "today", "yesterday" and parts of today ("this morning").
+/// Calendar periods resolve to their most recent occurrence that has started.
+pub fn parse_temporal_expression<Tz: TimeZone>(query: &str, now: &DateTime<Tz>) -> Option<TemporalContext> {
+    let query = query.to_lowercase();
+    let tz = now.timezone();
+    let now_ms = now.timestamp_millis().max(0) as u64;
+    let today = now.date_naive();
+
+    let context = |reference: &str, start: u64, end: u64| Some(TemporalContext {
+        time_reference: reference.to_string(),
+        start_timestamp: Some(start),
+        end_timestamp: Some(end.max(start)),
+    });
+
+    if let Some(caps) = DATE_RANGE_RE.captures(&query) {
+        if let (Some(a), Some(b)) = (date_from_captures(&caps, 1), date_from_captures(&caps, 4)) {
+            let (first, last) = if a <= b { (a, b) } else { (b, a) };
+            return context(&caps[0], day_start_ms(&tz, first), day_start_ms(&tz, next_day(last)).saturating_sub(1));
+        }
+    }
+
+    if let Some(caps) = MONTH_RANGE_RE.captures(&query) {
+        let first_month = month_number(&caps[1]);
+        let last_month = month_number(&caps[3]);
+        if let (Some(first_month), Some(last_month)) = (first_month, last_month) {
+            let first_year = caps.get(2).and_then(|y| y.as_str().parse().ok())
+                .unwrap_or_else(|| most_recent_year(today, first_month));
+            let last_year = caps.get(4).and_then(|y| y.as_str().parse().ok())
+                .unwrap_or(if last_month >= first_month { first_year } else { first_year + 1 });
+            let (next_year, next_month) = shift_month(last_year, last_month, 1);
+            return context(
+                &caps[0],
+                month_start_ms(&tz, first_year, first_month),
+                month_start_ms(&tz, next_year, next_month).saturating_sub(1),
+            );
+        }
+    }
+
+    if let Some(caps) = DATE_RE.captures(&query) {
+        if let Some(date) = date_from_captures(&caps, 1) {
+            return context(&caps[0], day_start_ms(&tz, date), day_start_ms(&tz, next_day(date)).saturating_sub(1));
+        }
+    }
+
+    if let Some(caps) = QUARTER_RE.captures(&query) {
+        let quarter: u32 = caps[1].parse().unwrap_or(1);
+        let first_month = (quarter - 1) * 3 + 1;
+        let year = caps.get(2).and_then(|y| y.as_str().parse().ok())
+            .unwrap_or_else(|| most_recent_year(today, first_month));
+        let (next_year, next_month) = shift_month(year, first_month, 3);
+        return context(
+            &caps[0],
+            month_start_ms(&tz, year, first_month),
+            month_start_ms(&tz, next_year, next_month).saturating_sub(1),
+        );
+    }
+
+    // "last 3 months", "past two weeks" - a rolling window ending now
+    if let Some(caps) = ROLLING_RE.captures(&query) {
+        if let Some(count) = parse_temporal_count(&caps[1]) {
+            let start = match &caps[2] {
+                "day" => now.clone().checked_sub_signed(ChronoDuration::try_days(count as i64)?)?,
+                "week" => now.clone().checked_sub_signed(ChronoDuration::try_weeks(count as i64)?)?,
+                "month" => now.clone().checked_sub_months(Months::new(count))?,
+                _ => now.clone().checked_sub_months(Months::new(count.checked_mul(12)?))?,
+            };
+            return context(&caps[0], start.timestamp_millis().max(0) as u64, now_ms);
+        }
+    }
+
+    // "3 weeks ago", "two months ago" - the calendar period that far back
+    if let Some(caps) = AGO_RE.captures(&query) {
+        if let Some(count) = parse_temporal_count(&caps[1]) {
+            let (start, end) = match &caps[2] {
+                "day" => {
+                    let date = today.checked_sub_signed(ChronoDuration::try_days(count as i64)?)?;
+                    (day_start_ms(&tz, date), day_start_ms(&tz, next_day(date)))
+                }
+                "week" => {
+                    let monday = week_start(today).checked_sub_signed(ChronoDuration::try_weeks(count as i64)?)?;
+                    (day_start_ms(&tz, monday), day_start_ms(&tz, monday + ChronoDuration::weeks(1)))
+                }
+                "month" => {
+                    let (year, month) = shift_month(today.year(), today.month(), -i32::try_from(count).ok()?);
+                    let (next_year, next_month) = shift_month(year, month, 1);
+                    (month_start_ms(&tz, year, month), month_start_ms(&tz, next_year, next_month))
+                }
+                _ => {
+                    let year = today.year().checked_sub(i32::try_from(count).ok()?)?;
+                    (month_start_ms(&tz, year, 1), month_start_ms(&tz, year + 1, 1))
+                }
+            };
+            return context(&caps[0], start, end.saturating_sub(1));
+        }
+    }
+
+    // Parts of today
+    for (phrase, from_hour, to_hour) in [
+        ("this morning", 0, 12),
+        ("this afternoon", 12, 18),
+        ("this evening", 17, 24),
+        ("tonight", 17, 24),
+    ] {
+        if query.contains(phrase) {
+            let end = if to_hour == 24 {
+                day_start_ms(&tz, next_day(today))
+            } else {
+                local_ms(&tz, today, to_hour)
+            };
+            return context(phrase, local_ms(&tz, today, from_hour), end.saturating_sub(1));
+        }
+    }
+
+    if TODAY_RE.is_match(&query) {
+        return context("today", day_start_ms(&tz, today), now_ms);
+    }
+
+    if YESTERDAY_RE.is_match(&query) {
+        let date = today - ChronoDuration::days(1);
+        return context("yesterday", day_start_ms(&tz, date), day_start_ms(&tz, today).saturating_sub(1));
+    }
+
+    // "this week" (calendar, to now), "last week" (previous calendar week), "past week" (rolling)
+    if let Some(caps) = PERIOD_RE.captures(&query) {
+        let (period_start, previous_start) = match &caps[2] {
+            "week" => {
+                let monday = week_start(today);
+                (day_start_ms(&tz, monday), day_start_ms(&tz, monday - ChronoDuration::weeks(1)))
+            }
+            "month" => {
+                let (year, month) = shift_month(today.year(), today.month(), -1);
+                (month_start_ms(&tz, today.year(), today.month()), month_start_ms(&tz, year, month))
+            }
+            _ => (month_start_ms(&tz, today.year(), 1), month_start_ms(&tz, today.year() - 1, 1)),
+        };
+        return match &caps[1] {
+            "this" => context(&caps[0], period_start, now_ms),
+            "last" => context(&caps[0], previous_start, period_start.saturating_sub(1)),
+            _ => {
+                let start = match &caps[2] {
+                    "week" => now.clone() - ChronoDuration::weeks(1),
+                    "month" => now.clone().checked_sub_months(Months::new(1))?,
+                    _ => now.clone().checked_sub_months(Months::new(12))?,
+                };
+                context(&caps[0], start.timestamp_millis().max(0) as u64, now_ms)
+            }
+        };
+    }
+
+    // "in March", "since april", "december 2023"
+    for caps in MONTH_RE.captures_iter(&query) {
+        let name = &caps[2];
+        // "may" is far more often a verb than a month without a preposition or year
+        if name == "may" && caps.get(1).is_none() && caps.get(3).is_none() {
+            continue;
+        }
+        let Some(month) = month_number(name) else { continue };
+        let year = caps.get(3).and_then(|y| y.as_str().parse().ok())
+            .unwrap_or_else(|| most_recent_year(today, month));
+        let start = month_start_ms(&tz, year, month);
+        if caps.get(1).map(|p| p.as_str()) == Some("since") {
+            return context(&caps[0], start, now_ms);
+        }
+        let (next_year, next_month) = shift_month(year, month, 1);
+        return context(&caps[0], start, month_start_ms(&tz, next_year, next_month).saturating_sub(1));
+    }
+
+    None
+}
+
+fn month_alternation() -> String {
+    let names: Vec<&str> = MONTH_NAMES.iter().map(|(name, _)| *name).collect();
+    format!("({})", names.join("|"))
+}
+
+pub(crate) fn month_number(name: &str) -> Option<u32> {
+    MONTH_NAMES.iter().find(|(n, _)| *n == name).map(|(_, m)| *m)
+}
+
+/// Largest count read from "last N days" / "N years ago"; anything bigger isn't a real time reference
+const MAX_TEMPORAL_COUNT: u32 = 10_000;
+
+fn parse_temporal_count(word: &str) -> Option<u32> {
+    match word {
+        "a" | "an" | "one" => Some(1),
+        "two" => Some(2),
+        "three" => Some(3),
+        "four" => Some(4),
+        "five" => Some(5),
+        "six" => Some(6),
+        "seven" => Some(7),
+        "eight" => Some(8),
+        "nine" => Some(9),
+        "ten" => Some(10),
+        "eleven" => Some(11),
+        "twelve" => Some(12),
+        digits => digits.parse().ok().filter(|&n| n <= MAX_TEMPORAL_COUNT),
+    }
+}
+
+fn date_from_captures(caps: &regex::Captures, first_group: usize) -> Option<NaiveDate> {
+    let year = caps.get(first_group)?.as_str().parse().ok()?;
+    let month = caps.get(first_group + 1)?.as_str().parse().ok()?;
+    let day = caps.get(first_group + 2)?.as_str().parse().ok()?;
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+/// Year in which `month` most recently started (this year unless it is still ahead)
+fn most_recent_year(today: NaiveDate, month: u32) -> i32 {
+    if month <= today.month() { today.year() } else { today.year() - 1 }
+}
+
+fn shift_month(year: i32, month: u32, delta: i32) -> (i32, u32) {
+    let total = year * 12 + month as i32 - 1 + delta;
+    (total.div_euclid(12), total.rem_euclid(12) as u32 + 1)
+}
+
+fn next_day(date: NaiveDate) -> NaiveDate {
+    date + ChronoDuration::days(1)
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - ChronoDuration::days(date.weekday().num_days_from_monday() as i64)
+}
+
+fn month_start_ms<Tz: TimeZone>(tz: &Tz, year: i32, month: u32) -> u64 {
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .map(|date| day_start_ms(tz, date))
+        .unwrap_or(0)
+}
+
+fn day_start_ms<Tz: TimeZone>(tz: &Tz, date: NaiveDate) -> u64 {
+    local_ms(tz, date, 0)
+}
+
+/// Epoch millis of `hour:00` local time on `date` (UTC if the local time doesn't exist)
+fn local_ms<Tz: TimeZone>(tz: &Tz, date: NaiveDate, hour: u32) -> u64 {
+    let naive = date.and_hms_opt(hour, 0, 0).expect("valid hour");
+    let millis = tz.from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.timestamp_millis())
+        .unwrap_or_else(|| naive.and_utc().timestamp_millis());
+    millis.max(0) as u64
+}
+
 /// Internal struct for deserializing chunk with similarity from query
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct ChunkWithSimilarity {
@@ -184,17 +1086,182 @@ struct ChunkWithSimilarity {
     pub similarity: f32,
 }
 
-/// Search result from the knowledge base
+/// Internal struct for deserializing a transcript segment with similarity from query
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SearchResult {
-    pub segment: TranscriptSegment,
-    pub meeting_title: String,
+struct SegmentWithSimilarity {
+    pub id: Option<Thing>,
+    pub meeting_id: String,
+    pub speaker: String,
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub embedding: Vec<f32>,
+    #[serde(default)]
+    pub word_timings: Vec<WordTiming>,
+    #[serde(default)]
+    pub speaker_confidence: Option<f32>,
+    #[serde(default)]
+    pub overlapped: bool,
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub emotion: Option<String>,
     pub similarity: f32,
 }
 
-/// Meeting statistics
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MeetingStats {
+impl SegmentWithSimilarity {
+    fn into_segment(self) -> TranscriptSegment {
+        TranscriptSegment {
+            id: self.id,
+            meeting_id: self.meeting_id,
+            speaker: self.speaker,
+            text: self.text,
+            start_ms: self.start_ms,
+            end_ms: self.end_ms,
+            embedding: self.embedding,
+            word_timings: self.word_timings,
+            speaker_confidence: self.speaker_confidence,
+            overlapped: self.overlapped,
+            language: self.language,
+            emotion: self.emotion,
+            needs_embedding: false,  // Vector queries only return embedded rows
+        }
+    }
+}
+
+/// Drop matches below `min_similarity` and order the rest best-first
+fn rank_segment_matches(
+    mut matches: Vec<SegmentWithSimilarity>,
+    min_similarity: Option<f32>,
+) -> Vec<SegmentWithSimilarity> {
+    if let Some(min) = min_similarity {
+        matches.retain(|m| m.similarity >= min);
+    }
+    matches.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
+    matches
+}
+
+/// Search result from the knowledge base
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub segment: TranscriptSegment,
+    pub meeting_title: String,
+    pub similarity: f32,
+}
+
+/// A segment matched by a search scoped to one meeting
+/// `score` is cosine similarity for semantic search and the number of occurrences for text search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentMatch {
+    pub segment: TranscriptSegment,
+    pub score: f32,
+}
+
+/// Case-insensitive occurrences of `needle` (already lowercased) in `text`
+fn text_match_count(text: &str, needle: &str) -> usize {
+    if needle.is_empty() {
+        return 0;
+    }
+    text.to_lowercase().matches(needle).count()
+}
+
+/// Text matches ordered by occurrences, ties in transcript order
+fn rank_text_matches(segments: Vec<TranscriptSegment>, needle: &str, limit: usize) -> Vec<SegmentMatch> {
+    let mut matches: Vec<SegmentMatch> = segments.into_iter()
+        .filter_map(|segment| {
+            let count = text_match_count(&segment.text, needle);
+            (count > 0).then_some(SegmentMatch { segment, score: count as f32 })
+        })
+        .collect();
+    matches.sort_by(|a, b| {
+        b.score.partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.segment.start_ms.cmp(&b.segment.start_ms))
+    });
+    matches.truncate(limit);
+    matches
+}
+
+/// A meeting ranked by how well its content matches a query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingSearchResult {
+    pub meeting: Meeting,
+    pub excerpt: String,         // Best-matching segment (or action/decision) text
+    pub score: f32,
+    pub matched_segments: usize,
+}
+
+/// Score added when the query appears in the meeting title
+const MEETING_TITLE_BOOST: f32 = 0.3;
+/// Score added when the query appears in one of the meeting's action items or decisions
+const MEETING_ITEM_BOOST: f32 = 0.15;
+/// Score added per matching segment beyond the best one, capped at `MEETING_EXTRA_HITS_CAP`
+const MEETING_EXTRA_HIT_WEIGHT: f32 = 0.05;
+const MEETING_EXTRA_HITS_CAP: usize = 5;
+/// Segment hits fetched per requested meeting before grouping
+const MEETING_SEGMENT_FANOUT: usize = 10;
+/// Segment, title, action item and decision matches for `search_meetings`, minus `$hidden` meetings
+const MEETING_SEARCH_QUERY: &str = r#"
+    SELECT meeting_id, text, vector::similarity::cosine(embedding, $embedding) AS similarity
+    FROM segment
+    WHERE meeting_id NOTINSIDE $hidden AND needs_embedding != true
+    ORDER BY similarity DESC
+    LIMIT $hits;
+    SELECT * FROM meeting WHERE string::contains(string::lowercase(title), $needle) AND deleted_at IS NONE AND private != true;
+    SELECT meeting_id, text FROM action_item WHERE meeting_id NOTINSIDE $hidden AND string::contains(string::lowercase(text), $needle);
+    SELECT meeting_id, text FROM decision WHERE meeting_id NOTINSIDE $hidden AND string::contains(string::lowercase(text), $needle);
+"#;
+
+/// Per-meeting accumulator for `search_meetings`
+#[derive(Debug, Default)]
+struct MeetingScore {
+    best_similarity: f32,
+    segment_hits: usize,
+    excerpt: Option<String>,
+    title_match: bool,
+    action_match: bool,
+    decision_match: bool,
+}
+
+impl MeetingScore {
+    fn add_segment(&mut self, similarity: f32, text: &str) {
+        if self.segment_hits == 0 || similarity > self.best_similarity {
+            self.best_similarity = similarity;
+            self.excerpt = Some(text.to_string());
+        }
+        self.segment_hits += 1;
+    }
+
+    fn add_item(&mut self, text: &str, is_action: bool) {
+        if is_action {
+            self.action_match = true;
+        } else {
+            self.decision_match = true;
+        }
+        if self.excerpt.is_none() {
+            self.excerpt = Some(text.to_string());
+        }
+    }
+
+    fn score(&self) -> f32 {
+        let extra_hits = self.segment_hits.saturating_sub(1).min(MEETING_EXTRA_HITS_CAP);
+        let mut score = self.best_similarity.max(0.0) + extra_hits as f32 * MEETING_EXTRA_HIT_WEIGHT;
+        if self.title_match {
+            score += MEETING_TITLE_BOOST;
+        }
+        if self.action_match {
+            score += MEETING_ITEM_BOOST;
+        }
+        if self.decision_match {
+            score += MEETING_ITEM_BOOST;
+        }
+        score
+    }
+}
+
+/// Meeting statistics
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingStats {
     pub segment_count: usize,
     pub action_count: usize,
     pub decision_count: usize,
@@ -204,11 +1271,794 @@ pub struct MeetingStats {
     pub total_words: usize,
 }
 
+/// Speaker switches that start within this gap of the previous segment count as interruptions
+pub const INTERRUPTION_GAP_MS: u64 = 500;
+
+/// Talk-time breakdown for one speaker in a meeting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpeakerStats {
+    pub speaker: String,
+    pub segment_count: usize,
+    pub word_count: usize,
+    pub talk_duration_ms: u64,
+    pub talk_percentage: f32,      // Share of total talk time across all speakers (0-100)
+    pub interruptions: usize,      // Times this speaker cut in on someone else
+    pub crosstalk_segments: usize, // Segments flagged as overlapped by diarization
+}
+
+/// Per-speaker talk time for a meeting, busiest speaker first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingSpeakerStats {
+    pub meeting_id: String,
+    pub duration_ms: u64,
+    pub total_talk_ms: u64,
+    pub total_words: usize,
+    pub speaker_switches: usize,
+    pub crosstalk_segments: usize,
+    pub crosstalk_ms: u64,         // Talk time in segments flagged as crosstalk
+    pub speakers: Vec<SpeakerStats>,
+}
+
+impl MeetingSpeakerStats {
+    /// Build from segments ordered by `start_ms`
+    pub fn from_segments(meeting_id: &str, segments: &[TranscriptSegment], interruption_gap_ms: u64) -> Self {
+        let mut speakers: Vec<SpeakerStats> = Vec::new();
+        let mut speaker_switches = 0;
+        let mut crosstalk_segments = 0;
+        let mut crosstalk_ms = 0;
+        let mut previous: Option<&TranscriptSegment> = None;
+
+        for segment in segments {
+            let idx = match speakers.iter().position(|s| s.speaker == segment.speaker) {
+                Some(idx) => idx,
+                None => {
+                    speakers.push(SpeakerStats {
+                        speaker: segment.speaker.clone(),
+                        segment_count: 0,
+                        word_count: 0,
+                        talk_duration_ms: 0,
+                        talk_percentage: 0.0,
+                        interruptions: 0,
+                        crosstalk_segments: 0,
+                    });
+                    speakers.len() - 1
+                }
+            };
+
+            let stats = &mut speakers[idx];
+            stats.segment_count += 1;
+            stats.word_count += segment.text.split_whitespace().count();
+            stats.talk_duration_ms += segment.end_ms.saturating_sub(segment.start_ms);
+            if segment.overlapped {
+                stats.crosstalk_segments += 1;
+                crosstalk_segments += 1;
+                crosstalk_ms += segment.end_ms.saturating_sub(segment.start_ms);
+            }
+
+            if let Some(prev) = previous {
+                if prev.speaker != segment.speaker {
+                    speaker_switches += 1;
+                    // Overlapping starts give a zero gap, so they count too
+                    if segment.start_ms.saturating_sub(prev.end_ms) < interruption_gap_ms {
+                        stats.interruptions += 1;
+                    }
+                }
+            }
+            previous = Some(segment);
+        }
+
+        let total_talk_ms: u64 = speakers.iter().map(|s| s.talk_duration_ms).sum();
+        let total_words = speakers.iter().map(|s| s.word_count).sum();
+        if total_talk_ms > 0 {
+            for stats in &mut speakers {
+                stats.talk_percentage = stats.talk_duration_ms as f32 * 100.0 / total_talk_ms as f32;
+            }
+        }
+        speakers.sort_by(|a, b| b.talk_duration_ms.cmp(&a.talk_duration_ms));
+
+        let duration_ms = match (segments.first(), segments.iter().map(|s| s.end_ms).max()) {
+            (Some(first), Some(last_end)) => last_end.saturating_sub(first.start_ms),
+            _ => 0,
+        };
+
+        Self {
+            meeting_id: meeting_id.to_string(),
+            duration_ms,
+            total_talk_ms,
+            total_words,
+            speaker_switches,
+            crosstalk_segments,
+            crosstalk_ms,
+            speakers,
+        }
+    }
+}
+
+/// How `export_meeting_markdown` lays out the transcript
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TranscriptExportMode {
+    /// Segments in order, speakers interleaved
+    #[default]
+    Chronological,
+    /// One block per speaker, busiest speaker first
+    BySpeaker,
+    /// Consecutive segments merged into turns; Q/A pairs when two speakers alternate
+    Dialogue,
+}
+
+impl TranscriptExportMode {
+    pub fn parse(mode: &str) -> Result<Self, String> {
+        match mode.trim().to_lowercase().as_str() {
+            "" | "chronological" => Ok(Self::Chronological),
+            "by_speaker" | "speaker" => Ok(Self::BySpeaker),
+            "dialogue" | "qa" => Ok(Self::Dialogue),
+            other => Err(format!(
+                "Unknown transcript export mode '{}' (expected chronological, by_speaker or dialogue)",
+                other
+            )),
+        }
+    }
+}
+
+/// Consecutive segments from one speaker, joined
+#[derive(Debug, Clone, PartialEq)]
+pub struct DialogueTurn {
+    pub speaker: String,
+    pub start_ms: u64,
+    pub text: String,
+}
+
+/// Merge runs of same-speaker segments (ordered by `start_ms`) into turns
+pub fn dialogue_turns(segments: &[TranscriptSegment]) -> Vec<DialogueTurn> {
+    let mut turns: Vec<DialogueTurn> = Vec::new();
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match turns.last_mut() {
+            Some(turn) if turn.speaker == segment.speaker => {
+                turn.text.push(' ');
+                turn.text.push_str(text);
+            }
+            _ => turns.push(DialogueTurn {
+                speaker: segment.speaker.clone(),
+                start_ms: segment.start_ms,
+                text: text.to_string(),
+            }),
+        }
+    }
+    turns
+}
+
+/// A page of results plus the total count, for building pagers in the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PagedResult<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+}
+
+/// Controls merging of near-identical consecutive segments from the same speaker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentDedupConfig {
+    pub enabled: bool,
+    /// Word-overlap similarity (0.0-1.0) above which two segments count as duplicates
+    pub similarity_threshold: f32,
+    /// Max gap between the previous segment's end and the new segment's start
+    pub window_ms: u64,
+}
+
+impl Default for SegmentDedupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            similarity_threshold: 0.85,
+            window_ms: 5000,
+        }
+    }
+}
+
+impl SegmentDedupConfig {
+    /// Whether `text` starting at `start_ms` repeats `last_text` (which ended at `last_end_ms`)
+    ///
+    /// Only near-identical wordings count: a short phrase that merely starts the next
+    /// sentence ("yes" then "yes, ship it") is a separate segment. Blank text never matches.
+    fn is_duplicate(&self, last_text: &str, last_end_ms: u64, text: &str, start_ms: u64) -> bool {
+        if start_ms.saturating_sub(last_end_ms) > self.window_ms {
+            return false;
+        }
+        let new_norm = normalize_segment_text(text);
+        let last_norm = normalize_segment_text(last_text);
+        if new_norm.is_empty() || last_norm.is_empty() {
+            return false;
+        }
+        new_norm == last_norm || word_similarity(&new_norm, &last_norm) >= self.similarity_threshold
+    }
+}
+
+/// A final transcript waiting to be written by `add_segments_batch`
+#[derive(Debug, Clone)]
+pub struct PendingSegment {
+    pub meeting_id: String,
+    pub speaker: String,
+    pub text: String,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub word_timings: Vec<WordTiming>,
+    pub language: Option<String>,
+    pub emotion: Option<String>,
+}
+
+/// Why `add_segments_batch` stopped, and the segments it hadn't written yet
+///
+/// Merges into stored segments and the multi-row insert each land on their own, so a
+/// retry should cover `unsaved` only; anything else would store segments twice.
+#[derive(Debug)]
+pub struct BatchWriteError {
+    pub error: SecondBrainError,
+    pub unsaved: Vec<PendingSegment>,
+}
+
+impl From<BatchWriteError> for SecondBrainError {
+    fn from(e: BatchWriteError) -> Self {
+        e.error
+    }
+}
+
+/// Fold same-speaker near-duplicates inside a batch into the earlier segment, the way
+/// `merge_duplicate_segment` does against stored segments; `T` rides along with each one
+fn collapse_batch_duplicates<T>(config: &SegmentDedupConfig, batch: Vec<(PendingSegment, T)>) -> Vec<(PendingSegment, T)> {
+    if !config.enabled {
+        return batch;
+    }
+    let mut kept: Vec<(PendingSegment, T)> = Vec::with_capacity(batch.len());
+    for (segment, extra) in batch {
+        let last = kept.iter_mut()
+            .rev()
+            .find(|(s, _)| s.meeting_id == segment.meeting_id && s.speaker == segment.speaker);
+        match last {
+            Some((last, last_extra)) if config.is_duplicate(&last.text, last.end_ms, &segment.text, segment.start_ms) => {
+                // Keep whichever wording carries more content
+                if normalize_segment_text(&segment.text).len() > normalize_segment_text(&last.text).len() {
+                    let shift = segment.start_ms.saturating_sub(last.start_ms);
+                    last.word_timings = segment.word_timings.iter()
+                        .map(|(word, start, end)| (word.clone(), start + shift, end + shift))
+                        .collect();
+                    last.text = segment.text;
+                    *last_extra = extra;
+                }
+                last.end_ms = last.end_ms.max(segment.end_ms);
+            }
+            _ => kept.push((segment, extra)),
+        }
+    }
+    kept
+}
+
+/// Lowercase, drop punctuation and collapse whitespace for duplicate comparison
+fn normalize_segment_text(text: &str) -> String {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c.is_whitespace() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Jaccard similarity over word sets of two normalized texts
+fn word_similarity(a: &str, b: &str) -> f32 {
+    let a_words: std::collections::HashSet<&str> = a.split_whitespace().collect();
+    let b_words: std::collections::HashSet<&str> = b.split_whitespace().collect();
+    if a_words.is_empty() && b_words.is_empty() {
+        return 1.0;
+    }
+    let intersection = a_words.intersection(&b_words).count();
+    let union = a_words.union(&b_words).count();
+    intersection as f32 / union as f32
+}
+
+/// Share of language-tagged segments the top language needs to be a meeting's dominant language
+const DOMINANT_LANGUAGE_MIN_SHARE: f32 = 0.5;
+
+/// Share a second language needs before a meeting counts as code-switched
+const CODE_SWITCHING_MIN_SHARE: f32 = 0.2;
+
+/// Segments spoken in one language
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageShare {
+    pub language: String,
+    pub segments: usize,
+    /// Percent of the meeting's language-tagged segments (0-100)
+    pub percentage: f32,
+}
+
+/// Which languages a meeting was held in
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LanguageBreakdown {
+    pub meeting_id: String,
+    /// Most segments first
+    pub languages: Vec<LanguageShare>,
+    /// Segments with no detected language (stored before languages were recorded)
+    pub untagged_segments: usize,
+    /// Set when one language holds at least half the tagged segments and outnumbers the rest
+    pub dominant_language: Option<String>,
+    /// More than one language holds a meaningful share of the meeting
+    pub code_switching: bool,
+}
+
+/// Clean a recognizer language tag ("<|en|>" -> "en"); None for "auto", "nospeech" and the like
+pub fn normalize_language(raw: &str) -> Option<String> {
+    let code = raw.trim()
+        .trim_start_matches("<|")
+        .trim_end_matches("|>")
+        .trim()
+        .to_lowercase();
+    match code.as_str() {
+        "" | "auto" | "nospeech" | "unknown" | "unk" | "none" => None,
+        _ => Some(code),
+    }
+}
+
+/// Tally per-segment languages into a breakdown
+fn language_breakdown(meeting_id: &str, languages: &[Option<String>]) -> LanguageBreakdown {
+    let mut counts: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    let mut untagged_segments = 0;
+    for language in languages {
+        match language.as_deref().and_then(normalize_language) {
+            Some(code) => *counts.entry(code).or_insert(0) += 1,
+            None => untagged_segments += 1,
+        }
+    }
+
+    let tagged: usize = counts.values().sum();
+    let mut languages: Vec<LanguageShare> = counts.into_iter()
+        .map(|(language, segments)| LanguageShare {
+            language,
+            segments,
+            percentage: (segments as f32 * 1000.0 / tagged as f32).round() / 10.0,
+        })
+        .collect();
+    languages.sort_by(|a, b| b.segments.cmp(&a.segments).then_with(|| a.language.cmp(&b.language)));
+
+    let share_of = |share: &LanguageShare| share.segments as f32 / tagged.max(1) as f32;
+    let dominant_language = match languages.as_slice() {
+        [top, rest @ ..] if share_of(top) >= DOMINANT_LANGUAGE_MIN_SHARE
+            && rest.first().map(|next| next.segments < top.segments).unwrap_or(true) => Some(top.language.clone()),
+        _ => None,
+    };
+    let code_switching = languages.get(1).map(|second| share_of(second) >= CODE_SWITCHING_MIN_SHARE).unwrap_or(false);
+
+    LanguageBreakdown {
+        meeting_id: meeting_id.to_string(),
+        languages,
+        untagged_segments,
+        dominant_language,
+        code_switching,
+    }
+}
+
+/// Default width of a sentiment timeline bucket
+pub const DEFAULT_SENTIMENT_BUCKET_MS: u64 = 60_000;
+
+/// Buckets are widened so a long meeting never yields more than this many
+const MAX_SENTIMENT_BUCKETS: u64 = 500;
+
+/// Clean a recognizer emotion ("Happy", "<|HAPPY|>" -> "happy"); None when it couldn't tell
+pub fn normalize_emotion(raw: &str) -> Option<String> {
+    let emotion = raw.trim()
+        .trim_start_matches("<|")
+        .trim_end_matches("|>")
+        .trim()
+        .to_lowercase();
+    match emotion.as_str() {
+        "" | "unknown" | "emo_unknown" | "none" => None,
+        _ => Some(emotion),
+    }
+}
+
+/// How positive an emotion is, from -1 (angry, disgusted) to 1 (happy); 0 for neutral
+fn emotion_valence(emotion: &str) -> f32 {
+    match emotion {
+        "happy" => 1.0,
+        "surprised" => 0.5,
+        "sad" | "fearful" => -0.5,
+        "angry" | "disgusted" => -1.0,
+        _ => 0.0,
+    }
+}
+
+/// Segments heard with one emotion
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmotionShare {
+    pub emotion: String,
+    pub segments: usize,
+    /// Percent of the emotion-tagged segments counted alongside it (0-100)
+    pub percentage: f32,
+}
+
+/// Emotion mix over one slice of a meeting
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SentimentBucket {
+    /// Offsets from the meeting start
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Emotion-tagged segments starting in the bucket
+    pub segments: usize,
+    /// Mean valence (-1 to 1); None for a bucket nobody spoke in
+    pub score: Option<f32>,
+    pub dominant_emotion: Option<String>,
+    /// Most segments first
+    pub emotions: Vec<EmotionShare>,
+}
+
+/// Emotion mix of one speaker across a meeting
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SpeakerSentiment {
+    pub speaker: String,
+    pub segments: usize,
+    pub score: Option<f32>,
+    pub emotions: Vec<EmotionShare>,
+}
+
+/// Emotion timeline and per-speaker emotions for a meeting
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MeetingSentiment {
+    pub meeting_id: String,
+    /// Width of each timeline bucket (may exceed the requested width for long meetings)
+    pub bucket_ms: u64,
+    /// Consecutive buckets from the meeting start to its last segment
+    pub timeline: Vec<SentimentBucket>,
+    /// Most emotion-tagged segments first
+    pub speakers: Vec<SpeakerSentiment>,
+    pub score: Option<f32>,
+    /// Segments with no detected emotion (stored before emotions were recorded)
+    pub untagged_segments: usize,
+}
+
+/// Shares, mean valence and top emotion of a set of emotion counts
+fn summarize_emotions(counts: std::collections::HashMap<String, usize>) -> (usize, Option<f32>, Vec<EmotionShare>) {
+    let total: usize = counts.values().sum();
+    if total == 0 {
+        return (0, None, Vec::new());
+    }
+    let valence: f32 = counts.iter().map(|(emotion, n)| emotion_valence(emotion) * *n as f32).sum();
+    let mut emotions: Vec<EmotionShare> = counts.into_iter()
+        .map(|(emotion, segments)| EmotionShare {
+            emotion,
+            segments,
+            percentage: (segments as f32 * 1000.0 / total as f32).round() / 10.0,
+        })
+        .collect();
+    emotions.sort_by(|a, b| b.segments.cmp(&a.segments).then_with(|| a.emotion.cmp(&b.emotion)));
+    (total, Some((valence * 100.0 / total as f32).round() / 100.0), emotions)
+}
+
+impl MeetingSentiment {
+    /// Build from segments ordered by `start_ms`; offsets count from `meeting_start`
+    pub fn from_segments(meeting_id: &str, meeting_start: u64, segments: &[TranscriptSegment], bucket_ms: u64) -> Self {
+        let last_offset = segments.iter().map(|s| s.end_ms.saturating_sub(meeting_start)).max().unwrap_or(0);
+        let bucket_ms = bucket_ms.max(1).max(last_offset.div_ceil(MAX_SENTIMENT_BUCKETS));
+        let bucket_count = if segments.is_empty() { 0 } else { (last_offset / bucket_ms + 1) as usize };
+
+        let mut buckets = vec![std::collections::HashMap::new(); bucket_count];
+        let mut speakers: Vec<(String, std::collections::HashMap<String, usize>)> = Vec::new();
+        let mut overall = std::collections::HashMap::new();
+        let mut untagged_segments = 0;
+        for segment in segments {
+            let Some(emotion) = segment.emotion.as_deref().and_then(normalize_emotion) else {
+                untagged_segments += 1;
+                continue;
+            };
+            let bucket = (segment.start_ms.saturating_sub(meeting_start) / bucket_ms) as usize;
+            *buckets[bucket.min(bucket_count - 1)].entry(emotion.clone()).or_insert(0) += 1;
+            let idx = match speakers.iter().position(|(speaker, _)| *speaker == segment.speaker) {
+                Some(idx) => idx,
+                None => {
+                    speakers.push((segment.speaker.clone(), std::collections::HashMap::new()));
+                    speakers.len() - 1
+                }
+            };
+            *speakers[idx].1.entry(emotion.clone()).or_insert(0) += 1;
+            *overall.entry(emotion).or_insert(0) += 1;
+        }
+
+        let timeline = buckets.into_iter()
+            .enumerate()
+            .map(|(i, counts)| {
+                let (segments, score, emotions) = summarize_emotions(counts);
+                SentimentBucket {
+                    start_ms: i as u64 * bucket_ms,
+                    end_ms: (i as u64 + 1) * bucket_ms,
+                    segments,
+                    score,
+                    dominant_emotion: emotions.first().map(|e| e.emotion.clone()),
+                    emotions,
+                }
+            })
+            .collect();
+
+        let mut speakers: Vec<SpeakerSentiment> = speakers.into_iter()
+            .map(|(speaker, counts)| {
+                let (segments, score, emotions) = summarize_emotions(counts);
+                SpeakerSentiment { speaker, segments, score, emotions }
+            })
+            .collect();
+        speakers.sort_by(|a, b| b.segments.cmp(&a.segments));
+
+        Self {
+            meeting_id: meeting_id.to_string(),
+            bucket_ms,
+            timeline,
+            speakers,
+            score: summarize_emotions(overall).1,
+            untagged_segments,
+        }
+    }
+}
+
+/// Topics and people listed in `MeetingAnalytics`
+pub const ANALYTICS_TOP_N: usize = 10;
+
+/// A topic or person ranked by how often it came up in a date range
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MentionCount {
+    pub name: String,
+    pub mentions: usize,   // Extracted mentions in the range
+    pub meetings: usize,   // Distinct meetings it was mentioned in
+}
+
+/// Meetings held on one local calendar day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnalyticsDay {
+    pub date: String,      // YYYY-MM-DD
+    pub meetings: usize,
+    pub duration_ms: u64,
+}
+
+/// Aggregate meeting stats for meetings started in `[start, end)`, for charting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingAnalytics {
+    pub start: u64,
+    pub end: u64,
+    pub meeting_count: usize,
+    pub total_duration_ms: u64,
+    pub average_duration_ms: u64,
+    pub estimated_durations: usize,   // Meetings without an end_time, timed by their last segment
+    pub daily: Vec<AnalyticsDay>,
+    pub top_topics: Vec<MentionCount>,
+    pub top_people: Vec<MentionCount>,
+    pub action_items_open: usize,
+    pub action_items_in_progress: usize,
+    pub action_items_done: usize,
+    pub action_done_ratio: f32,       // Done share of all action items (0-1); 0 when there are none
+}
+
+/// How long a meeting ran, and whether that was estimated
+/// Meetings still in progress (or never properly ended) have no `end_time`, so
+/// they're timed up to the end of their last segment, or 0 if they have none.
+fn meeting_duration_ms(meeting: &Meeting, last_segment_end: Option<u64>) -> (u64, bool) {
+    match meeting.end_time {
+        Some(end) => (end.saturating_sub(meeting.start_time), false),
+        None => (last_segment_end.map(|end| end.saturating_sub(meeting.start_time)).unwrap_or(0), true),
+    }
+}
+
+/// Rank `(name, meeting_id)` mention pairs by mentions, then distinct meetings, then name
+fn rank_mentions(pairs: &[(String, String)], limit: usize) -> Vec<MentionCount> {
+    let mut by_name: std::collections::HashMap<&str, (usize, std::collections::HashSet<&str>)> =
+        std::collections::HashMap::new();
+    for (name, meeting) in pairs {
+        let entry = by_name.entry(name.as_str()).or_default();
+        entry.0 += 1;
+        entry.1.insert(meeting.as_str());
+    }
+
+    let mut ranked: Vec<MentionCount> = by_name.into_iter()
+        .map(|(name, (mentions, meetings))| MentionCount {
+            name: name.to_string(),
+            mentions,
+            meetings: meetings.len(),
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.mentions.cmp(&a.mentions)
+            .then_with(|| b.meetings.cmp(&a.meetings))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+/// Meeting count and duration per calendar day in `tz`, by start time, oldest first
+fn daily_totals<Tz: TimeZone>(meetings: &[(u64, u64)], tz: &Tz) -> Vec<AnalyticsDay> {
+    let mut days: std::collections::BTreeMap<NaiveDate, (usize, u64)> = std::collections::BTreeMap::new();
+    for &(start, duration) in meetings {
+        let Some(started) = tz.timestamp_millis_opt(start as i64).single() else {
+            continue;
+        };
+        let day = days.entry(started.date_naive()).or_default();
+        day.0 += 1;
+        day.1 += duration;
+    }
+    days.into_iter()
+        .map(|(date, (meetings, duration_ms))| AnalyticsDay {
+            date: date.format("%Y-%m-%d").to_string(),
+            meetings,
+            duration_ms,
+        })
+        .collect()
+}
+
+/// WHERE clause shared by meeting listing and counting (binds `$tags`, `$since`, `$language`)
+fn meeting_filter_clause(tags: &Option<Vec<String>>, favorites_only: bool, since: Option<u64>, language: &Option<String>) -> String {
+    let mut conditions = vec!["deleted_at IS NONE"];
+    if tags.as_ref().map(|t| !t.is_empty()).unwrap_or(false) {
+        conditions.push("tags CONTAINSANY $tags");
+    }
+    if favorites_only {
+        conditions.push("favorite = true");
+    }
+    if since.is_some() {
+        conditions.push("start_time >= $since");
+    }
+    if language.is_some() {
+        conditions.push("dominant_language = $language");
+    }
+
+    format!(" WHERE {}", conditions.join(" AND "))
+}
+
+/// Meetings kept out of global search and Graph-RAG
+const HIDDEN_MEETINGS: &str = "private = true OR deleted_at IS NOT NONE";
+/// Meetings in the trash
+const TRASHED_MEETINGS: &str = "deleted_at IS NOT NONE";
+
+/// Moves a meeting into (`$deleted_at` set) or out of (NONE) the trash
+const SET_DELETED_AT_QUERY: &str = "UPDATE type::thing('meeting', $id) SET deleted_at = $deleted_at";
+/// Open action items outside the `$deleted` meetings
+const OPEN_ACTIONS_QUERY: &str = "SELECT * FROM action_item WHERE status = 'open' AND meeting_id NOTINSIDE $deleted ORDER BY created_at DESC";
+/// Newest decisions outside the `$deleted` meetings
+const RECENT_DECISIONS_QUERY: &str = "SELECT * FROM decision WHERE meeting_id NOTINSIDE $deleted ORDER BY created_at DESC LIMIT $limit";
+
+/// Clears a meeting's extracted graph data, taking its mentions back off each topic's count
+const CLEAR_MEETING_ENTITIES_QUERY: &str = r#"
+    FOR $mention IN (SELECT in, count() AS n FROM discussed_in WHERE out = type::thing('meeting', $id) GROUP BY in) {
+        UPDATE $mention.in SET mention_count = IF mention_count > $mention.n THEN mention_count - $mention.n ELSE 0 END;
+    };
+    DELETE FROM entity_relation WHERE meeting_id = $id;
+    DELETE FROM mentioned_in WHERE out = type::thing('meeting', $id);
+    DELETE FROM discussed_in WHERE out = type::thing('meeting', $id);
+"#;
+/// Records a person seen at `$seen_at`, never moving `first_seen` later or `last_seen` earlier
+const UPSERT_PERSON_QUERY: &str = r#"
+    UPSERT person SET
+        name = $name,
+        aliases = array::union(aliases, []),
+        first_seen = IF first_seen IS NONE OR first_seen > $seen_at THEN $seen_at ELSE first_seen END,
+        last_seen = IF last_seen > $seen_at THEN last_seen ELSE $seen_at END
+    WHERE name = $name
+"#;
+/// Counts one more mention of a topic at `$seen_at`
+const UPSERT_TOPIC_QUERY: &str = r#"
+    UPSERT topic SET
+        name = $name,
+        embedding = $embedding,
+        mention_count = mention_count + 1,
+        last_mentioned = IF last_mentioned > $seen_at THEN last_mentioned ELSE $seen_at END
+    WHERE name = $name
+"#;
+
+/// Takes one of `$name`'s mentions in a meeting back off its topic count and edges
+const FORGET_TOPIC_MENTION_QUERY: &str = r#"
+    LET $edge = (SELECT VALUE id FROM discussed_in WHERE in.name = $name AND out = type::thing('meeting', $meeting_id) LIMIT 1);
+    IF array::len($edge) > 0 {
+        UPDATE topic SET mention_count = IF mention_count > 0 THEN mention_count - 1 ELSE 0 END WHERE name = $name;
+    };
+    DELETE $edge;
+"#;
+/// Removes one of `$name`'s person mentions in a meeting
+const FORGET_PERSON_MENTION_QUERY: &str = r#"
+    LET $edge = (SELECT VALUE id FROM mentioned_in WHERE in.name = $name AND out = type::thing('meeting', $meeting_id) LIMIT 1);
+    DELETE $edge;
+"#;
+
+/// Entities an edit adds and removes, compared by case-insensitive text and label
+fn entity_changes(old: &[Entity], new: Vec<Entity>) -> (Vec<Entity>, Vec<Entity>) {
+    let key = |e: &Entity| (e.text.to_lowercase(), e.label.clone());
+    let old_keys: std::collections::HashSet<_> = old.iter().map(key).collect();
+    let new_keys: std::collections::HashSet<_> = new.iter().map(key).collect();
+    let removed = old.iter().filter(|e| !new_keys.contains(&key(e))).cloned().collect();
+    let added = new.into_iter().filter(|e| !old_keys.contains(&key(e))).collect();
+    (added, removed)
+}
+
+/// People whose stored name or an alias is exactly `$name` (indexed)
+const PERSON_BY_NAME_QUERY: &str = "SELECT name, aliases, last_seen FROM person WHERE name = $name OR aliases CONTAINS $name LIMIT 1";
+/// Candidates for fuzzy person matching, most recently seen first so they win ties
+const PERSON_CANDIDATES_QUERY: &str = "SELECT name, aliases, last_seen FROM person ORDER BY last_seen DESC LIMIT $limit";
+/// The topic stored exactly as `$name` (indexed)
+const TOPIC_BY_NAME_QUERY: &str = "SELECT name, mention_count, last_mentioned FROM topic WHERE name = $name LIMIT 1";
+/// Candidates for fuzzy topic matching, most mentioned first so they win ties
+const TOPIC_CANDIDATES_QUERY: &str = "SELECT name, mention_count, last_mentioned FROM topic ORDER BY mention_count DESC LIMIT $limit";
+/// Topics and projects any of `$names` relates to outside the `$hidden` meetings
+const PERSON_TOPICS_QUERY: &str = r#"
+    SELECT target_entity FROM entity_relation
+    WHERE source_entity IN $names AND source_type = 'person'
+    AND (target_type = 'topic' OR target_type = 'project')
+    AND meeting_id NOTINSIDE $hidden
+    LIMIT 5
+"#;
+/// People relating to any of the topic's `$names` outside the `$hidden` meetings
+const TOPIC_PEOPLE_QUERY: &str = r#"
+    SELECT source_entity FROM entity_relation
+    WHERE target_entity IN $names AND source_type = 'person'
+    AND meeting_id NOTINSIDE $hidden
+    LIMIT 5
+"#;
+/// Relations to or from any of a person's `$names` outside the `$hidden` meetings, strongest first
+const PERSON_RELATIONS_QUERY: &str = "SELECT * FROM entity_relation WHERE (source_entity IN $names OR target_entity IN $names) AND meeting_id NOTINSIDE $hidden ORDER BY confidence DESC LIMIT 50";
+/// Meetings `$person` is linked to by `mentioned_in`, leaving out private and trashed ones
+const PERSON_MEETINGS_QUERY: &str = "SELECT * FROM meeting WHERE id IN (SELECT VALUE out FROM mentioned_in WHERE in = $person) AND private != true AND deleted_at IS NONE";
+/// Names of people mentioned in the same meetings as `$name`, private and trashed ones aside
+const RELATED_PEOPLE_QUERY: &str = r#"
+    SELECT VALUE name FROM person
+    WHERE id IN (
+        SELECT VALUE in FROM mentioned_in
+        WHERE out IN (
+            SELECT VALUE out FROM mentioned_in
+            WHERE in IN (SELECT VALUE id FROM person WHERE name = $name)
+            AND out.private != true AND out.deleted_at IS NONE
+        )
+    )
+    AND name != $name
+"#;
+/// How many people or topics a name that misses the exact lookup is fuzzily compared against
+const FUZZY_NAME_CANDIDATES: usize = 500;
+
+/// Largest timestamp bound into a query (SurrealDB integers are i64)
+const MAX_QUERY_TIMESTAMP_MS: u64 = i64::MAX as u64;
+/// A meeting's segments in order; `$meeting_id` is the bare id, as in the per-meeting queries below
+const MEETING_SEGMENTS_QUERY: &str = "SELECT * FROM segment WHERE meeting_id = $meeting_id ORDER BY start_ms ASC";
+/// A meeting's action items, newest first
+const MEETING_ACTION_ITEMS_QUERY: &str = "SELECT * FROM action_item WHERE meeting_id = $meeting_id ORDER BY created_at DESC";
+/// A meeting's decisions, newest first
+const MEETING_DECISIONS_QUERY: &str = "SELECT * FROM decision WHERE meeting_id = $meeting_id ORDER BY created_at DESC";
+/// Topics linked to a meeting by `discussed_in`
+const MEETING_TOPICS_QUERY: &str = "SELECT * FROM topic WHERE id IN (SELECT VALUE in FROM discussed_in WHERE out = type::thing('meeting', $meeting_id))";
+/// People linked to a meeting by `mentioned_in`
+const MEETING_PEOPLE_QUERY: &str = "SELECT * FROM person WHERE id IN (SELECT VALUE in FROM mentioned_in WHERE out = type::thing('meeting', $meeting_id))";
+/// A meeting's bookmarks in playback order
+const MEETING_BOOKMARKS_QUERY: &str = "SELECT * FROM meeting_bookmark WHERE meeting_id = $meeting_id ORDER BY timestamp_ms ASC";
+
+/// Bare ids of the meetings matching `condition`
+async fn meeting_ids_where(db: &Surreal<Db>, condition: &str) -> Result<std::collections::HashSet<String>, SecondBrainError> {
+    let ids: Vec<Thing> = db
+        .query(format!("SELECT VALUE id FROM meeting WHERE {}", condition))
+        .await
+        .map_err(|e| SecondBrainError::Db(format!("Failed to query meeting ids: {}", e)))?
+        .take(0)
+        .map_err(|e| SecondBrainError::Db(format!("Failed to extract meeting ids: {}", e)))?;
+    Ok(ids.iter().map(|t| normalize_record_id("meeting", &t.to_string())).collect())
+}
+
+/// Meeting ids in both forms a `meeting_id` field may hold, for `NOTINSIDE` filters
+fn meeting_id_forms(ids: std::collections::HashSet<String>) -> Vec<String> {
+    ids.into_iter()
+        .flat_map(|id| [format!("meeting:{}", id), id])
+        .collect()
+}
+
 /// The main knowledge base powered by SurrealDB
 pub struct KnowledgeBase {
     db: Surreal<Db>,
-    embedding_engine: Arc<EmbeddingEngine>,
+    embedding_engine: parking_lot::RwLock<Arc<EmbeddingEngine>>,  // Swapped by `reembed_all`
     entity_engine: Arc<EntityEngine>,
+    dedup_config: SegmentDedupConfig,
+    redactor: parking_lot::RwLock<Option<Arc<Redactor>>>,  // Set when redact_on_store is enabled
+    embedding_mismatch: parking_lot::RwLock<Option<String>>,  // Why vector search is refused, if it is
+    relation_config: parking_lot::RwLock<RelationConfig>,     // Which extracted relations get stored
+    chunk_strategy: parking_lot::RwLock<ChunkStrategy>,       // How ingested knowledge sources are split
 }
 
 impl KnowledgeBase {
@@ -217,35 +2067,41 @@ impl KnowledgeBase {
         data_dir: &PathBuf,
         embedding_engine: Arc<EmbeddingEngine>,
         entity_engine: Arc<EntityEngine>,
-    ) -> Result<Self, String> {
+    ) -> Result<Self, SecondBrainError> {
         let db_path = data_dir.join("knowledge.db");
 
         // Connect to embedded SurrealDB with RocksDB backend
         let db = Surreal::new::<RocksDb>(db_path.to_str().unwrap())
             .await
-            .map_err(|e| format!("Failed to open database: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to open database: {}", e)))?;
 
         // Select namespace and database
         db.use_ns("second_brain")
             .use_db("knowledge")
             .await
-            .map_err(|e| format!("Failed to select namespace: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to select namespace: {}", e)))?;
 
         let kb = Self {
             db,
-            embedding_engine,
+            embedding_engine: parking_lot::RwLock::new(embedding_engine),
             entity_engine,
+            dedup_config: SegmentDedupConfig::default(),
+            redactor: parking_lot::RwLock::new(None),
+            embedding_mismatch: parking_lot::RwLock::new(None),
+            relation_config: parking_lot::RwLock::new(RelationConfig::default()),
+            chunk_strategy: parking_lot::RwLock::new(ChunkStrategy::default()),
         };
 
         // Initialize schema
         kb.init_schema().await?;
+        kb.check_embedding_model().await?;
 
         println!("Knowledge base initialized at {:?}", data_dir);
         Ok(kb)
     }
 
     /// Initialize database schema
-    async fn init_schema(&self) -> Result<(), String> {
+    async fn init_schema(&self) -> Result<(), SecondBrainError> {
         // Define tables with indexes
         let schema = r#"
             -- Meetings table
@@ -255,7 +2111,14 @@ impl KnowledgeBase {
             DEFINE FIELD end_time ON meeting TYPE option<int>;
             DEFINE FIELD participants ON meeting TYPE array<string>;
             DEFINE FIELD summary ON meeting TYPE option<string>;
+            DEFINE FIELD tags ON meeting TYPE array<string> DEFAULT [];
+            DEFINE FIELD favorite ON meeting TYPE bool DEFAULT false;
+            DEFINE FIELD private ON meeting TYPE bool DEFAULT false;
+            DEFINE FIELD dominant_language ON meeting TYPE option<string>;
+            DEFINE FIELD deleted_at ON meeting TYPE option<int>;
             DEFINE INDEX idx_meeting_time ON meeting FIELDS start_time;
+            DEFINE INDEX idx_meeting_tags ON meeting FIELDS tags;
+            DEFINE INDEX idx_meeting_favorite ON meeting FIELDS favorite;
 
             -- Transcript segments with vector embeddings
             DEFINE TABLE segment SCHEMAFULL;
@@ -265,8 +2128,15 @@ impl KnowledgeBase {
             DEFINE FIELD start_ms ON segment TYPE int;
             DEFINE FIELD end_ms ON segment TYPE int;
             DEFINE FIELD embedding ON segment TYPE array<float>;
+            DEFINE FIELD word_timings ON segment TYPE array DEFAULT [];
+            DEFINE FIELD speaker_confidence ON segment TYPE option<float>;
+            DEFINE FIELD overlapped ON segment TYPE bool DEFAULT false;
+            DEFINE FIELD language ON segment TYPE option<string>;
+            DEFINE FIELD emotion ON segment TYPE option<string>;
+            DEFINE FIELD needs_embedding ON segment TYPE bool DEFAULT false;
             DEFINE INDEX idx_segment_meeting ON segment FIELDS meeting_id;
             DEFINE INDEX idx_segment_speaker ON segment FIELDS speaker;
+            DEFINE INDEX idx_segment_meeting_time ON segment FIELDS meeting_id, start_ms;
 
             -- Action items
             DEFINE TABLE action_item SCHEMAFULL;
@@ -293,6 +2163,7 @@ impl KnowledgeBase {
             DEFINE FIELD first_seen ON person TYPE int;
             DEFINE FIELD last_seen ON person TYPE int;
             DEFINE INDEX idx_person_name ON person FIELDS name UNIQUE;
+            DEFINE INDEX idx_person_aliases ON person FIELDS aliases;
 
             -- Topics
             DEFINE TABLE topic SCHEMAFULL;
@@ -318,10 +2189,12 @@ impl KnowledgeBase {
             DEFINE FIELD confidence ON entity_relation TYPE float;
             DEFINE FIELD meeting_id ON entity_relation TYPE option<string>;
             DEFINE FIELD knowledge_source_id ON entity_relation TYPE option<string>;
+            DEFINE FIELD segment_id ON entity_relation TYPE option<string>;
             DEFINE FIELD created_at ON entity_relation TYPE int;
             DEFINE INDEX idx_relation_source ON entity_relation FIELDS source_entity;
             DEFINE INDEX idx_relation_target ON entity_relation FIELDS target_entity;
             DEFINE INDEX idx_relation_type ON entity_relation FIELDS relation;
+            DEFINE INDEX idx_relation_segment ON entity_relation FIELDS segment_id;
 
             -- Knowledge sources (crawled URLs, documents)
             DEFINE TABLE knowledge_source SCHEMAFULL;
@@ -332,6 +2205,10 @@ impl KnowledgeBase {
             DEFINE FIELD tags ON knowledge_source TYPE array<string>;
             DEFINE FIELD created_at ON knowledge_source TYPE int;
             DEFINE FIELD last_updated ON knowledge_source TYPE int;
+            DEFINE FIELD last_accessed ON knowledge_source TYPE option<int>;
+            DEFINE FIELD pinned ON knowledge_source TYPE bool DEFAULT false;
+            DEFINE FIELD refresh_interval_hours ON knowledge_source TYPE option<int>;
+            DEFINE FIELD last_refreshed ON knowledge_source TYPE option<int>;
             DEFINE INDEX idx_source_url ON knowledge_source FIELDS url UNIQUE;
             DEFINE INDEX idx_source_tags ON knowledge_source FIELDS tags;
 
@@ -341,6 +2218,7 @@ impl KnowledgeBase {
             DEFINE FIELD text ON knowledge_chunk TYPE string;
             DEFINE FIELD chunk_index ON knowledge_chunk TYPE int;
             DEFINE FIELD embedding ON knowledge_chunk TYPE array<float>;
+            DEFINE FIELD needs_embedding ON knowledge_chunk TYPE bool DEFAULT false;
             DEFINE INDEX idx_chunk_source ON knowledge_chunk FIELDS source_id;
 
             -- Meeting-knowledge links
@@ -351,75 +2229,656 @@ impl KnowledgeBase {
             DEFINE FIELD assigned_by ON meeting_knowledge TYPE string;
             DEFINE INDEX idx_mk_meeting ON meeting_knowledge FIELDS meeting_id;
             DEFINE INDEX idx_mk_source ON meeting_knowledge FIELDS source_id;
+
+            -- Bookmarks flagged during recording
+            DEFINE TABLE meeting_bookmark SCHEMAFULL;
+            DEFINE FIELD meeting_id ON meeting_bookmark TYPE string;
+            DEFINE FIELD offset_ms ON meeting_bookmark TYPE int;
+            DEFINE FIELD timestamp_ms ON meeting_bookmark TYPE int;
+            DEFINE FIELD note ON meeting_bookmark TYPE option<string>;
+            DEFINE INDEX idx_bookmark_meeting ON meeting_bookmark FIELDS meeting_id;
+
+            -- Links between meetings (follow-ups, continuations, references)
+            DEFINE TABLE related_meeting SCHEMAFULL;
+            DEFINE FIELD from_meeting ON related_meeting TYPE string;
+            DEFINE FIELD to_meeting ON related_meeting TYPE string;
+            DEFINE FIELD relation ON related_meeting TYPE string;
+            DEFINE FIELD created_at ON related_meeting TYPE int;
+            DEFINE INDEX idx_related_from ON related_meeting FIELDS from_meeting;
+            DEFINE INDEX idx_related_to ON related_meeting FIELDS to_meeting;
+
+            -- Knowledge base metadata (embedding model, ...)
+            DEFINE TABLE kb_meta SCHEMALESS;
         "#;
 
         self.db
             .query(schema)
             .await
-            .map_err(|e| format!("Failed to create schema: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to create schema: {}", e)))?;
+
+        // Migration: backfill organizational fields on meetings created before they existed
+        self.db
+            .query("UPDATE meeting SET tags = [] WHERE tags IS NONE; UPDATE meeting SET favorite = false WHERE favorite IS NONE; UPDATE meeting SET private = false WHERE private IS NONE; UPDATE segment SET word_timings = [] WHERE word_timings IS NONE; UPDATE knowledge_source SET pinned = false WHERE pinned IS NONE;")
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to migrate meetings: {}", e)))?;
+
+        // Migration: older rows stored `meeting:xyz`; meeting_id fields are now always bare
+        self.db
+            .query(r#"
+                UPDATE segment SET meeting_id = string::replace(meeting_id, 'meeting:', '') WHERE string::starts_with(meeting_id, 'meeting:');
+                UPDATE action_item SET meeting_id = string::replace(meeting_id, 'meeting:', '') WHERE string::starts_with(meeting_id, 'meeting:');
+                UPDATE decision SET meeting_id = string::replace(meeting_id, 'meeting:', '') WHERE string::starts_with(meeting_id, 'meeting:');
+                UPDATE entity_relation SET meeting_id = string::replace(meeting_id, 'meeting:', '') WHERE meeting_id IS NOT NONE AND string::starts_with(meeting_id, 'meeting:');
+                UPDATE meeting_knowledge SET meeting_id = string::replace(meeting_id, 'meeting:', '') WHERE string::starts_with(meeting_id, 'meeting:');
+            "#)
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to migrate meeting ids: {}", e)))?;
 
         Ok(())
     }
 
-    /// Create a new meeting
-    pub async fn create_meeting(&self, title: &str, participants: Vec<String>) -> Result<String, String> {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
+    /// Current embedding engine (replaced wholesale by `reembed_all`)
+    fn embedder(&self) -> Arc<EmbeddingEngine> {
+        self.embedding_engine.read().clone()
+    }
 
-        let meeting = Meeting {
-            id: None,
-            title: title.to_string(),
-            start_time: now,
-            end_time: None,
-            participants,
-            summary: None,
+    /// Embed `text`, refusing empty or wrongly sized vectors so they never reach the DB
+    fn embed_checked(&self, text: &str) -> Result<Vec<f32>, SecondBrainError> {
+        let engine = self.embedder();
+        let embedding = engine.embed(text)?;
+        check_embedding(&embedding, engine.dimension())?;
+        Ok(embedding)
+    }
+
+    /// Embed `text` for storage, or defer it: when the model fails the row is stored with an
+    /// empty embedding and `needs_embedding` set, so a transcript is never lost to an embedding error
+    fn embed_or_defer(&self, text: &str) -> (Vec<f32>, bool) {
+        match self.embed_checked(text) {
+            Ok(embedding) => (embedding, false),
+            Err(e) => {
+                eprintln!("[KB] Embedding failed, storing without one for backfill: {}", e);
+                (Vec::new(), true)
+            }
+        }
+    }
+
+    async fn stored_embedding_meta(&self) -> Result<Option<EmbeddingMeta>, SecondBrainError> {
+        let meta: Option<EmbeddingMeta> = self.db
+            .select(("kb_meta", "embedding"))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to read embedding metadata: {}", e)))?;
+        Ok(meta)
+    }
+
+    async fn store_embedding_meta(&self, meta: EmbeddingMeta) -> Result<(), SecondBrainError> {
+        self.db
+            .query("UPSERT kb_meta:embedding CONTENT $meta")
+            .bind(("meta", meta))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to store embedding metadata: {}", e)))?;
+        Ok(())
+    }
+
+    /// Compare the loaded embedding model with the one stored vectors came from
+    ///
+    /// Databases from before the metadata existed are adopted if a stored vector has
+    /// the loaded model's dimension. On a mismatch vector search is refused until
+    /// `reembed_all` runs, since cosine similarity across models is meaningless.
+    async fn check_embedding_model(&self) -> Result<(), SecondBrainError> {
+        let engine = self.embedder();
+        let current = EmbeddingMeta {
+            model_id: engine.model_id().to_string(),
+            dimension: engine.dimension(),
+        };
+
+        let stored = match self.stored_embedding_meta().await? {
+            Some(meta) => meta,
+            None => {
+                let dims: Vec<usize> = self.db
+                    .query("SELECT VALUE array::len(embedding) FROM segment WHERE array::len(embedding) > 0 LIMIT 1; SELECT VALUE array::len(embedding) FROM knowledge_chunk WHERE array::len(embedding) > 0 LIMIT 1;")
+                    .await
+                    .map_err(|e| SecondBrainError::Db(format!("Failed to inspect stored embeddings: {}", e)))
+                    .map(|mut r| {
+                        let mut dims: Vec<usize> = r.take(0).unwrap_or_default();
+                        dims.extend(r.take::<Vec<usize>>(1).unwrap_or_default());
+                        dims
+                    })?;
+                match dims.first() {
+                    Some(&dim) if dim != current.dimension => EmbeddingMeta {
+                        model_id: "unknown".to_string(),
+                        dimension: dim,
+                    },
+                    _ => {
+                        self.store_embedding_meta(current).await?;
+                        return Ok(());
+                    }
+                }
+            }
+        };
+
+        if stored.model_id != current.model_id || stored.dimension != current.dimension {
+            let message = format!(
+                "Stored embeddings come from {} ({}-dim) but {} ({}-dim) is loaded. Run reembed_all to re-embed the knowledge base before searching.",
+                stored.model_id, stored.dimension, current.model_id, current.dimension
+            );
+            eprintln!("[KB] {}", message);
+            *self.embedding_mismatch.write() = Some(message);
+        } else {
+            *self.embedding_mismatch.write() = None;
+        }
+        Ok(())
+    }
+
+    /// Error out of vector search while stored embeddings don't match the loaded model
+    fn ensure_embeddings_compatible(&self) -> Result<(), SecondBrainError> {
+        match self.embedding_mismatch.read().as_ref() {
+            Some(message) => Err(SecondBrainError::ModelMissing(message.clone())),
+            None => Ok(()),
+        }
+    }
+
+    /// Loaded and stored embedding model, and whether search is blocked
+    pub async fn embedding_status(&self) -> Result<EmbeddingStatus, SecondBrainError> {
+        let engine = self.embedder();
+        Ok(EmbeddingStatus {
+            model_id: engine.model_id().to_string(),
+            dimension: engine.dimension(),
+            stored: self.stored_embedding_meta().await?,
+            mismatch: self.embedding_mismatch.read().clone(),
+        })
+    }
+
+    /// Cheap round-trip to check the database is still answering queries
+    pub async fn ping(&self) -> Result<(), SecondBrainError> {
+        self.db.query("RETURN 1")
+            .await
+            .and_then(|mut r| r.take::<Option<i64>>(0))
+            .map(|_| ())
+            .map_err(|e| SecondBrainError::Db(format!("Database not reachable: {}", e)))
+    }
+
+    /// Rows whose embedding is empty or doesn't match the loaded model's dimension
+    /// These need `reembed_all` (or re-ingesting) before they show up in search.
+    pub async fn find_broken_embeddings(&self) -> Result<Vec<BrokenEmbedding>, SecondBrainError> {
+        #[derive(Deserialize)]
+        struct Row {
+            id: Thing,
+            text: Option<String>,
+            dimension: usize,
+        }
+
+        let expected = self.embedder().dimension();
+        let mut broken = Vec::new();
+        for (table, field) in EMBEDDED_TABLES {
+            let rows: Vec<Row> = self.db
+                .query(format!(
+                    "SELECT id, {} AS text, array::len(embedding ?? []) AS dimension FROM {} WHERE array::len(embedding ?? []) != $dim",
+                    field, table
+                ))
+                .bind(("dim", expected))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to scan {} embeddings: {}", table, e)))?
+                .take(0)
+                .map_err(|e| SecondBrainError::Db(format!("Failed to extract {} embeddings: {}", table, e)))?;
+
+            broken.extend(rows.into_iter().map(|row| BrokenEmbedding {
+                table: table.to_string(),
+                id: normalize_record_id(table, &row.id.to_string()),
+                text: row.text.unwrap_or_default(),
+                dimension: row.dimension,
+                expected,
+            }));
+        }
+
+        if !broken.is_empty() {
+            eprintln!("[KB] Found {} rows with broken embeddings (expected {} dims)", broken.len(), expected);
+        }
+        Ok(broken)
+    }
+
+    /// Re-embed every segment, knowledge chunk and topic with `engine`, then switch to it
+    /// Returns the number of rows re-embedded.
+    pub async fn reembed_all(
+        &self,
+        engine: Arc<EmbeddingEngine>,
+        on_progress: &(dyn Fn(&ReembedProgress) + Sync),
+    ) -> Result<usize, SecondBrainError> {
+        #[derive(Deserialize)]
+        struct Row {
+            id: Thing,
+            text: Option<String>,
+        }
+
+        let mut reembedded = 0;
+        for (table, field) in EMBEDDED_TABLES {
+            let counts: Vec<serde_json::Value> = self.db
+                .query(format!("SELECT count() AS count FROM {} GROUP ALL", table))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to count {} rows: {}", table, e)))?
+                .take(0)
+                .unwrap_or_default();
+            let total = counts
+                .first()
+                .and_then(|v| v.get("count").and_then(|c| c.as_u64()))
+                .unwrap_or(0) as usize;
+
+            let mut progress = ReembedProgress { table: table.to_string(), done: 0, total };
+            on_progress(&progress);
+
+            while progress.done < total {
+                let rows: Vec<Row> = self.db
+                    .query(format!("SELECT id, {} AS text FROM {} ORDER BY id LIMIT $limit START $start", field, table))
+                    .bind(("limit", REEMBED_PAGE_SIZE))
+                    .bind(("start", progress.done))
+                    .await
+                    .map_err(|e| SecondBrainError::Db(format!("Failed to read {} rows: {}", table, e)))?
+                    .take(0)
+                    .map_err(|e| SecondBrainError::Db(format!("Failed to extract {} rows: {}", table, e)))?;
+                if rows.is_empty() {
+                    break;
+                }
+
+                let texts: Vec<&str> = rows.iter().map(|r| r.text.as_deref().unwrap_or("")).collect();
+                let embeddings = engine.embed_batch(&texts)?;
+
+                for (row, embedding) in rows.iter().zip(embeddings) {
+                    if let Err(e) = check_embedding(&embedding, engine.dimension()) {
+                        eprintln!("[KB] Not re-embedding {} {}: {}", table, row.id, e);
+                        continue;
+                    }
+                    let deferrable = DEFERRABLE_TABLES.iter().any(|(t, _)| t == table);
+                    self.db
+                        .query(if deferrable {
+                            "UPDATE $id SET embedding = $embedding, needs_embedding = false"
+                        } else {
+                            "UPDATE $id SET embedding = $embedding"
+                        })
+                        .bind(("id", row.id.clone()))
+                        .bind(("embedding", embedding))
+                        .await
+                        .map_err(|e| SecondBrainError::Db(format!("Failed to update {} embedding: {}", table, e)))?;
+                }
+
+                progress.done += rows.len();
+                reembedded += rows.len();
+                on_progress(&progress);
+            }
+
+            println!("[KB] Re-embedded {} {} rows with {}", progress.done, table, engine.model_id());
+        }
+
+        self.store_embedding_meta(EmbeddingMeta {
+            model_id: engine.model_id().to_string(),
+            dimension: engine.dimension(),
+        }).await?;
+        *self.embedding_engine.write() = engine;
+        *self.embedding_mismatch.write() = None;
+
+        Ok(reembedded)
+    }
+
+    /// Embed rows stored with `needs_embedding` because the model failed at ingest time
+    ///
+    /// Rows that still fail are left flagged and skipped for the rest of the run, so a
+    /// later backfill can retry them. Returns how many rows were embedded.
+    pub async fn backfill_missing_embeddings(
+        &self,
+        on_progress: &(dyn Fn(&ReembedProgress) + Sync),
+    ) -> Result<usize, SecondBrainError> {
+        #[derive(Deserialize)]
+        struct Row {
+            id: Thing,
+            text: Option<String>,
+        }
+
+        let engine = self.embedder();
+        let mut backfilled = 0;
+        for (table, field) in DEFERRABLE_TABLES {
+            let counts: Vec<serde_json::Value> = self.db
+                .query(format!("SELECT count() AS count FROM {} WHERE needs_embedding = true GROUP ALL", table))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to count unembedded {} rows: {}", table, e)))?
+                .take(0)
+                .unwrap_or_default();
+            let total = counts
+                .first()
+                .and_then(|v| v.get("count").and_then(|c| c.as_u64()))
+                .unwrap_or(0) as usize;
+            if total == 0 {
+                continue;
+            }
+
+            let mut progress = ReembedProgress { table: table.to_string(), done: 0, total };
+            on_progress(&progress);
+
+            // Embedded rows drop out of the filter, so each page starts from the top
+            let mut failed: Vec<Thing> = Vec::new();
+            while progress.done < total {
+                let rows: Vec<Row> = self.db
+                    .query(format!(
+                        "SELECT id, {} AS text FROM {} WHERE needs_embedding = true AND id NOTINSIDE $failed ORDER BY id LIMIT $limit",
+                        field, table
+                    ))
+                    .bind(("failed", failed.clone()))
+                    .bind(("limit", REEMBED_PAGE_SIZE))
+                    .await
+                    .map_err(|e| SecondBrainError::Db(format!("Failed to read unembedded {} rows: {}", table, e)))?
+                    .take(0)
+                    .map_err(|e| SecondBrainError::Db(format!("Failed to extract unembedded {} rows: {}", table, e)))?;
+                if rows.is_empty() {
+                    break;
+                }
+
+                let texts: Vec<&str> = rows.iter().map(|r| r.text.as_deref().unwrap_or("")).collect();
+                let embeddings = engine.embed_batch(&texts)?;
+
+                for (row, embedding) in rows.iter().zip(embeddings) {
+                    if let Err(e) = check_embedding(&embedding, engine.dimension()) {
+                        eprintln!("[KB] Could not backfill {} {}: {}", table, row.id, e);
+                        failed.push(row.id.clone());
+                        continue;
+                    }
+                    self.db
+                        .query("UPDATE $id SET embedding = $embedding, needs_embedding = false")
+                        .bind(("id", row.id.clone()))
+                        .bind(("embedding", embedding))
+                        .await
+                        .map_err(|e| SecondBrainError::Db(format!("Failed to update {} embedding: {}", table, e)))?;
+                    backfilled += 1;
+                }
+
+                progress.done += rows.len();
+                on_progress(&progress);
+            }
+
+            println!("[KB] Backfilled {} {} rows, {} still missing", progress.done - failed.len(), table, failed.len());
+        }
+
+        Ok(backfilled)
+    }
+
+    /// Re-run entity extraction over a meeting's stored segments and rebuild its graph data
+    /// The meeting's people/topic edges, relations and topic mention counts are cleared first so nothing doubles up.
+    /// Action items and decisions are left alone: they may have been edited or LLM-extracted.
+    pub async fn reprocess_meeting_entities(
+        &self,
+        meeting_id: &str,
+        on_progress: &(dyn Fn(&EntityReprocessProgress) + Sync),
+    ) -> Result<EntityReprocessStats, SecondBrainError> {
+        let id_part = normalize_record_id("meeting", meeting_id);
+        let segments = self.get_meeting_segments(&id_part).await?;
+
+        self.db
+            .query(CLEAR_MEETING_ENTITIES_QUERY)
+            .bind(("id", id_part.clone()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to clear entity data for meeting {}: {}", id_part, e)))?;
+
+        let mut progress = EntityReprocessProgress {
+            meeting_id: id_part.clone(),
+            segments_done: 0,
+            segments_total: segments.len(),
+            meetings_done: 0,
+            meetings_total: 1,
+        };
+        on_progress(&progress);
+
+        let mut stats = EntityReprocessStats { meetings: 1, ..Default::default() };
+        for batch in segments.chunks(REPROCESS_BATCH_SIZE) {
+            let texts: Vec<&str> = batch.iter().map(|s| s.text.as_str()).collect();
+            let extracted = self.entity_engine.extract_batch_with_relations(&texts)?;
+
+            for (segment, (entities, relationships)) in batch.iter().zip(extracted) {
+                let graph_entities: Vec<Entity> = entities.into_iter()
+                    .filter(|e| matches!(self.entity_engine.kind_of(&e.label), Some(EntityKind::Person | EntityKind::Topic)))
+                    .collect();
+                let segment_id = segment.id.as_ref().map(|t| normalize_record_id("segment", &t.to_string()));
+
+                self.process_entities(&id_part, &graph_entities, segment.end_ms).await?;
+                self.process_relationships(&id_part, segment_id.as_deref(), &relationships).await?;
+                stats.entities += graph_entities.len();
+                stats.relationships += relationships.len();
+            }
+
+            progress.segments_done += batch.len();
+            stats.segments += batch.len();
+            on_progress(&progress);
+        }
+
+        println!("[KB] Reprocessed meeting {}: {} segments, {} entities, {} relationships",
+            id_part, stats.segments, stats.entities, stats.relationships);
+        Ok(stats)
+    }
+
+    /// Re-run entity extraction over every stored meeting (e.g. after a model or label change)
+    pub async fn reprocess_all_entities(
+        &self,
+        on_progress: &(dyn Fn(&EntityReprocessProgress) + Sync),
+    ) -> Result<EntityReprocessStats, SecondBrainError> {
+        let meeting_ids: Vec<Thing> = self.db
+            .query("SELECT VALUE id FROM meeting ORDER BY start_time ASC")
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to list meetings: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meeting ids: {}", e)))?;
+
+        let meetings_total = meeting_ids.len();
+        let mut stats = EntityReprocessStats::default();
+        for (meetings_done, id) in meeting_ids.iter().enumerate() {
+            let meeting_id = normalize_record_id("meeting", &id.to_string());
+            let meeting_stats = self.reprocess_meeting_entities(&meeting_id, &|progress| {
+                on_progress(&EntityReprocessProgress {
+                    meetings_done,
+                    meetings_total,
+                    ..progress.clone()
+                });
+            }).await?;
+
+            stats.meetings += 1;
+            stats.segments += meeting_stats.segments;
+            stats.entities += meeting_stats.entities;
+            stats.relationships += meeting_stats.relationships;
+        }
+
+        on_progress(&EntityReprocessProgress {
+            meeting_id: String::new(),
+            segments_done: 0,
+            segments_total: 0,
+            meetings_done: meetings_total,
+            meetings_total,
+        });
+        println!("[KB] Reprocessed entities for {} meetings", stats.meetings);
+        Ok(stats)
+    }
+
+    /// Create a new meeting
+    pub async fn create_meeting(&self, title: &str, participants: Vec<String>) -> Result<String, SecondBrainError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        let meeting = Meeting {
+            id: None,
+            title: title.to_string(),
+            start_time: now,
+            end_time: None,
+            participants,
+            summary: None,
+            tags: Vec::new(),
+            favorite: false,
+            private: false,
+            dominant_language: None,
+            deleted_at: None,
         };
 
         let created: Option<Meeting> = self.db
             .create("meeting")
             .content(meeting)
             .await
-            .map_err(|e| format!("Failed to create meeting: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to create meeting: {}", e)))?;
 
         match created {
-            Some(m) => Ok(m.id.map(|t| t.to_string()).unwrap_or_default()),
-            None => Err("Failed to create meeting".to_string()),
+            Some(m) => Ok(m.id.map(|t| normalize_record_id("meeting", &t.to_string())).unwrap_or_default()),
+            None => Err(SecondBrainError::Db("Failed to create meeting".to_string())),
+        }
+    }
+
+    /// Link `from_id` to an earlier meeting `to_id` (e.g. as its follow-up); linking twice is a no-op
+    pub async fn link_meetings(&self, from_id: &str, to_id: &str, relation: &str) -> Result<MeetingLink, SecondBrainError> {
+        let from_meeting = normalize_record_id("meeting", from_id);
+        let to_meeting = normalize_record_id("meeting", to_id);
+        let relation = relation.trim().to_lowercase();
+        if !MEETING_RELATIONS.contains(&relation.as_str()) {
+            return Err(SecondBrainError::InvalidInput(format!("Unknown meeting relation '{}' (expected one of: {})",
+                relation, MEETING_RELATIONS.join(", "))));
+        }
+        if from_meeting == to_meeting {
+            return Err(SecondBrainError::InvalidInput("A meeting cannot be linked to itself".to_string()));
+        }
+        for id in [&from_meeting, &to_meeting] {
+            if self.get_meeting(id).await?.is_none() {
+                return Err(SecondBrainError::NotFound(format!("Meeting not found: {}", id)));
+            }
+        }
+
+        let existing: Vec<MeetingLink> = self.db
+            .query("SELECT * FROM related_meeting WHERE from_meeting = $from AND to_meeting = $to AND relation = $relation")
+            .bind(("from", from_meeting.clone()))
+            .bind(("to", to_meeting.clone()))
+            .bind(("relation", relation.clone()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query meeting links: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meeting links: {}", e)))?;
+        if let Some(link) = existing.into_iter().next() {
+            return Ok(link);
+        }
+
+        let link = MeetingLink {
+            id: None,
+            from_meeting,
+            to_meeting,
+            relation,
+            created_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+        };
+        let created: Option<MeetingLink> = self.db
+            .create("related_meeting")
+            .content(link)
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to link meetings: {}", e)))?;
+
+        created.ok_or_else(|| SecondBrainError::Db("Failed to link meetings".to_string()))
+    }
+
+    /// Meetings linked to `meeting_id` in either direction, most recent first
+    pub async fn get_related_meetings(&self, meeting_id: &str) -> Result<Vec<RelatedMeeting>, SecondBrainError> {
+        let id_part = normalize_record_id("meeting", meeting_id);
+        let links: Vec<MeetingLink> = self.db
+            .query("SELECT * FROM related_meeting WHERE from_meeting = $id OR to_meeting = $id")
+            .bind(("id", id_part.clone()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query meeting links: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meeting links: {}", e)))?;
+
+        let mut related = Vec::with_capacity(links.len());
+        for link in links {
+            let outgoing = link.from_meeting == id_part;
+            let other = if outgoing { &link.to_meeting } else { &link.from_meeting };
+            if let Some(meeting) = self.get_meeting(other).await? {
+                related.push(RelatedMeeting { meeting, relation: link.relation, outgoing });
+            }
         }
+        related.sort_by(|a, b| b.meeting.start_time.cmp(&a.meeting.start_time));
+        Ok(related)
+    }
+
+    /// Most recent earlier meeting with the same title (the previous instance of a recurring meeting)
+    pub async fn find_previous_instance(&self, meeting_id: &str) -> Result<Option<Meeting>, SecondBrainError> {
+        let Some(meeting) = self.get_meeting(meeting_id).await? else {
+            return Ok(None);
+        };
+        let previous: Vec<Meeting> = self.db
+            .query(r#"
+                SELECT * FROM meeting
+                WHERE string::lowercase(title) = $title AND start_time < $start_time AND deleted_at IS NONE
+                ORDER BY start_time DESC
+                LIMIT 1
+            "#)
+            .bind(("title", meeting.title.trim().to_lowercase()))
+            .bind(("start_time", meeting.start_time))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query previous meeting: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract previous meeting: {}", e)))?;
+
+        Ok(previous.into_iter().next())
     }
 
     /// End a meeting and set summary
-    pub async fn end_meeting(&self, meeting_id: &str, summary: Option<String>) -> Result<(), String> {
+    pub async fn end_meeting(&self, meeting_id: &str, summary: Option<String>) -> Result<(), SecondBrainError> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_millis() as u64;
+        self.end_meeting_at(meeting_id, summary, now).await
+    }
 
+    /// End a meeting at a given time, e.g. an imported recording's start plus its length
+    pub async fn end_meeting_at(&self, meeting_id: &str, summary: Option<String>, end_time: u64) -> Result<(), SecondBrainError> {
         // Normalize meeting_id - strip prefix if present
-        let id_part = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
-        } else {
-            meeting_id
-        };
+        let id_part = normalize_record_id("meeting", meeting_id);
 
         println!("[KB] Ending meeting: {} (normalized: {})", meeting_id, id_part);
 
         self.db
             .query("UPDATE type::thing('meeting', $id) SET end_time = $end_time, summary = $summary")
             .bind(("id", id_part.to_string()))
-            .bind(("end_time", now))
+            .bind(("end_time", end_time))
             .bind(("summary", summary))
             .await
-            .map_err(|e| format!("Failed to end meeting: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to end meeting: {}", e)))?;
+
+        // A missing language only weakens filtering and summaries; never fail the meeting over it
+        match self.get_meeting_language_breakdown(&id_part).await {
+            Ok(breakdown) => {
+                if let Err(e) = self.db
+                    .query("UPDATE type::thing('meeting', $id) SET dominant_language = $language")
+                    .bind(("id", id_part.to_string()))
+                    .bind(("language", breakdown.dominant_language.clone()))
+                    .await
+                {
+                    eprintln!("[KB] Failed to store dominant language: {}", e);
+                }
+            }
+            Err(e) => eprintln!("[KB] Language breakdown failed: {}", e),
+        }
+
+        println!("[KB] Meeting ended successfully with end_time: {}", end_time);
+        Ok(())
+    }
+
+    /// Clear a meeting's end_time so new segments can be appended to it
+    pub async fn reopen_meeting(&self, meeting_id: &str) -> Result<(), SecondBrainError> {
+        let id_part = normalize_record_id("meeting", meeting_id);
+
+        self.db
+            .query("UPDATE type::thing('meeting', $id) SET end_time = NONE")
+            .bind(("id", id_part.to_string()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to reopen meeting: {}", e)))?;
 
-        println!("[KB] Meeting ended successfully with end_time: {}", now);
+        println!("[KB] Reopened meeting: {}", id_part);
         Ok(())
     }
 
     /// Auto-end stale meetings (meetings without end_time older than max_age_hours)
     /// Returns the number of meetings that were auto-ended
-    pub async fn auto_end_stale_meetings(&self, max_age_hours: u64) -> Result<usize, String> {
+    pub async fn auto_end_stale_meetings(&self, max_age_hours: u64) -> Result<usize, SecondBrainError> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -433,7 +2892,7 @@ impl KnowledgeBase {
             .query("SELECT id, title, start_time FROM meeting WHERE end_time IS NONE AND start_time < $cutoff")
             .bind(("cutoff", cutoff_time))
             .await
-            .map_err(|e| format!("Failed to query stale meetings: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query stale meetings: {}", e)))?;
 
         #[derive(serde::Deserialize)]
         struct StaleMeeting {
@@ -443,7 +2902,7 @@ impl KnowledgeBase {
         }
 
         let stale_meetings: Vec<StaleMeeting> = result.take(0)
-            .map_err(|e| format!("Failed to parse stale meetings: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to parse stale meetings: {}", e)))?;
 
         if stale_meetings.is_empty() {
             return Ok(0);
@@ -465,12 +2924,32 @@ impl KnowledgeBase {
                 .bind(("id", meeting_id.clone()))
                 .bind(("end_time", estimated_end))
                 .await
-                .map_err(|e| format!("Failed to auto-end meeting {}: {}", meeting_id, e))?;
+                .map_err(|e| SecondBrainError::Db(format!("Failed to auto-end meeting {}: {}", meeting_id, e)))?;
         }
 
         Ok(stale_meetings.len())
     }
 
+    /// Ended meetings that started more than `retention_days` ago, oldest first
+    /// Favorites and meetings tagged "keep" are never returned.
+    pub async fn get_meetings_past_retention(&self, retention_days: u64) -> Result<Vec<Meeting>, SecondBrainError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let cutoff = now.saturating_sub(retention_days.saturating_mul(24 * 60 * 60 * 1000));
+
+        let meetings: Vec<Meeting> = self.db
+            .query("SELECT * FROM meeting WHERE end_time IS NOT NONE AND start_time < $cutoff ORDER BY start_time ASC")
+            .bind(("cutoff", cutoff))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query old meetings: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract old meetings: {}", e)))?;
+
+        Ok(meetings.into_iter().filter(|m| past_retention(m, cutoff)).collect())
+    }
+
     /// Add a transcript segment
     pub async fn add_segment(
         &self,
@@ -479,13 +2958,59 @@ impl KnowledgeBase {
         text: &str,
         start_ms: u64,
         end_ms: u64,
-    ) -> Result<String, String> {
+    ) -> Result<String, SecondBrainError> {
+        self.add_segment_with_timings(meeting_id, speaker, text, start_ms, end_ms, Vec::new()).await
+    }
+
+    /// Add a transcript segment along with per-word timings relative to `start_ms`
+    pub async fn add_segment_with_timings(
+        &self,
+        meeting_id: &str,
+        speaker: &str,
+        text: &str,
+        start_ms: u64,
+        end_ms: u64,
+        word_timings: Vec<WordTiming>,
+    ) -> Result<String, SecondBrainError> {
         println!("[KB::add_segment] Starting for meeting={}, speaker={}, text_len={}",
             meeting_id, speaker, text.len());
+        let meeting_id = normalize_record_id("meeting", meeting_id);
+        let meeting_id = meeting_id.as_str();
+
+        // Scrub PII before anything is stored or embedded; entity extraction runs first
+        // so detected person names can be redacted too, then again on what is actually
+        // stored so scrubbed names never reach the graph
+        let redactor = self.redactor.read().clone();
+        let mut word_timings = word_timings;
+        let (text, extracted) = match redactor {
+            Some(redactor) => {
+                let extracted = self.entity_engine.extract_with_relations(text)?;
+                let redacted = redactor.redact(text, &person_names(&extracted.0));
+                let extracted = if redacted.count > 0 {
+                    println!("[KB::add_segment] Redacted {} span(s)", redacted.count);
+                    // The raw words would leak what was scrubbed; respread over the redacted text
+                    if !word_timings.is_empty() {
+                        word_timings = redacted_word_timings(&redacted.text, &word_timings);
+                    }
+                    self.entity_engine.extract_with_relations(&redacted.text)?
+                } else {
+                    extracted
+                };
+                (redacted.text, Some(extracted))
+            }
+            None => (text.to_string(), None),
+        };
+        let text = text.as_str();
+
+        // Merge into the previous segment if this is a near-duplicate from overlapping chunks
+        if let Some(existing_id) = self.merge_duplicate_segment(meeting_id, speaker, text, start_ms, end_ms, &word_timings).await? {
+            println!("[KB::add_segment] Merged duplicate into existing segment: {}", existing_id);
+            return Ok(existing_id);
+        }
 
         // Generate embedding for the text
         println!("[KB::add_segment] Generating embedding...");
-        let embedding = self.embedding_engine.embed(text)?;
+        let (embedding, needs_embedding) = self.embed_or_defer(text);
         println!("[KB::add_segment] Embedding generated, dim={}", embedding.len());
 
         let segment = TranscriptSegment {
@@ -496,6 +3021,12 @@ impl KnowledgeBase {
             start_ms,
             end_ms,
             embedding,
+            word_timings,
+            speaker_confidence: None,
+            overlapped: false,
+            language: None,
+            emotion: None,
+            needs_embedding,
         };
 
         println!("[KB::add_segment] Creating segment in DB...");
@@ -503,16 +3034,22 @@ impl KnowledgeBase {
             .create("segment")
             .content(segment)
             .await
-            .map_err(|e| format!("Failed to create segment: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to create segment: {}", e)))?;
         println!("[KB::add_segment] Segment created in DB");
 
         // Extract entities and relationships using GLiNER multitask
         println!("[KB::add_segment] Extracting entities...");
-        let (entities, relationships) = self.entity_engine.extract_with_relations(text)?;
+        let (entities, relationships) = match extracted {
+            Some(extracted) => extracted,
+            None => self.entity_engine.extract_with_relations(text)?,
+        };
         println!("[KB::add_segment] Found {} entities, {} relationships", entities.len(), relationships.len());
 
-        self.process_entities(meeting_id, &entities).await?;
-        self.process_relationships(meeting_id, &relationships).await?;
+        let segment_id = created.as_ref()
+            .and_then(|s| s.id.as_ref())
+            .map(|t| normalize_record_id("segment", &t.to_string()));
+        self.process_entities(meeting_id, &entities, end_ms).await?;
+        self.process_relationships(meeting_id, segment_id.as_deref(), &relationships).await?;
         println!("[KB::add_segment] Entities and relationships processed");
 
         match created {
@@ -521,70 +3058,644 @@ impl KnowledgeBase {
                 println!("[KB::add_segment] Success! Segment ID: {}", id);
                 Ok(id)
             }
-            None => Err("Failed to create segment".to_string()),
+            None => Err(SecondBrainError::Db("Failed to create segment".to_string())),
         }
     }
 
-    /// Process extracted entities and create graph relations
-    async fn process_entities(&self, meeting_id: &str, entities: &[Entity]) -> Result<(), String> {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
+    /// Write several final transcripts at once: one entity-extraction pass, one embedding
+    /// batch and one multi-row insert instead of a round of each per segment
+    ///
+    /// Redaction and duplicate merging match `add_segment_with_timings`. Returns the id each
+    /// segment was stored (or merged) under; on failure, the segments still to be written.
+    pub async fn add_segments_batch(&self, batch: Vec<PendingSegment>) -> Result<Vec<String>, BatchWriteError> {
+        if batch.is_empty() {
+            return Ok(Vec::new());
+        }
+        println!("[KB::add_segments_batch] Writing {} segment(s)", batch.len());
 
-        // Extract just the ID part for use with type::thing()
-        let meeting_id_part = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
-        } else {
-            meeting_id
+        let texts: Vec<&str> = batch.iter().map(|s| s.text.as_str()).collect();
+        let extracted = match self.entity_engine.extract_batch_with_relations(&texts) {
+            Ok(extracted) => extracted,
+            Err(error) => return Err(BatchWriteError { error: error.into(), unsaved: batch }),
         };
-        let meeting_id_owned = meeting_id_part.to_string();
-
-        for entity in entities {
-            let entity_text = entity.text.clone();
-            let meeting_id_clone = meeting_id_owned.clone();
-
-            match entity.label.as_str() {
-                "person" => {
-                    // Upsert person
-                    self.db
-                        .query(r#"
-                            UPSERT person SET
-                                name = $name,
-                                aliases = array::union(aliases, []),
-                                first_seen = math::min(first_seen, $now),
-                                last_seen = $now
-                            WHERE name = $name
-                        "#)
-                        .bind(("name", entity_text.clone()))
-                        .bind(("now", now))
-                        .await
-                        .ok();
 
-                    // Create relation
-                    self.db
-                        .query("RELATE (SELECT * FROM person WHERE name = $name) -> mentioned_in -> type::thing('meeting', $meeting_id)")
-                        .bind(("name", entity_text))
-                        .bind(("meeting_id", meeting_id_clone))
-                        .await
-                        .ok();
+        let redactor = self.redactor.read().clone();
+        let mut staged = Vec::with_capacity(batch.len());
+        let mut redacted_at = Vec::new();
+        for (mut segment, extracted) in batch.into_iter().zip(extracted) {
+            segment.meeting_id = normalize_record_id("meeting", &segment.meeting_id);
+            segment.language = segment.language.as_deref().and_then(normalize_language);
+            segment.emotion = segment.emotion.as_deref().and_then(normalize_emotion);
+            if let Some(redactor) = &redactor {
+                let redacted = redactor.redact(&segment.text, &person_names(&extracted.0));
+                if redacted.count > 0 {
+                    if !segment.word_timings.is_empty() {
+                        segment.word_timings = redacted_word_timings(&redacted.text, &segment.word_timings);
+                    }
+                    segment.text = redacted.text;
+                    redacted_at.push(staged.len());
                 }
-                "topic" | "project" | "product" => {
-                    // Upsert topic
-                    let topic_embedding = self.embedding_engine.embed(&entity.text).unwrap_or_default();
+            }
+            staged.push((segment, extracted));
+        }
+        // Entities of redacted segments come from the stored text, so scrubbed names stay out of the graph
+        if !redacted_at.is_empty() {
+            let texts: Vec<&str> = redacted_at.iter().map(|&i| staged[i].0.text.as_str()).collect();
+            let reextracted = match self.entity_engine.extract_batch_with_relations(&texts) {
+                Ok(reextracted) => reextracted,
+                Err(error) => return Err(BatchWriteError {
+                    error: error.into(),
+                    unsaved: staged.into_iter().map(|(s, _)| s).collect(),
+                }),
+            };
+            for (i, extracted) in redacted_at.into_iter().zip(reextracted) {
+                staged[i].1 = extracted;
+            }
+        }
+        let staged = collapse_batch_duplicates(&self.dedup_config, staged);
+
+        // Only a speaker's first segment in the batch can continue one already stored
+        let mut ids = Vec::with_capacity(staged.len());
+        let mut first_of_speaker = std::collections::HashSet::new();
+        let mut to_insert: Vec<(PendingSegment, (Vec<Entity>, Vec<Relationship>))> = Vec::with_capacity(staged.len());
+        let mut staged = staged.into_iter();
+        while let Some((segment, extracted)) = staged.next() {
+            if first_of_speaker.insert((segment.meeting_id.clone(), segment.speaker.clone())) {
+                let merged = self.merge_duplicate_segment(
+                    &segment.meeting_id,
+                    &segment.speaker,
+                    &segment.text,
+                    segment.start_ms,
+                    segment.end_ms,
+                    &segment.word_timings,
+                ).await;
+                match merged {
+                    Ok(Some(existing_id)) => {
+                        ids.push(existing_id);
+                        continue;
+                    }
+                    Ok(None) => {}
+                    Err(error) => {
+                        // Earlier merges are stored; this segment and everything after it isn't
+                        let unsaved = to_insert.into_iter().map(|(s, _)| s)
+                            .chain(std::iter::once(segment))
+                            .chain(staged.map(|(s, _)| s))
+                            .collect();
+                        return Err(BatchWriteError { error, unsaved });
+                    }
+                }
+            }
+            to_insert.push((segment, extracted));
+        }
+        if to_insert.is_empty() {
+            return Ok(ids);
+        }
 
-                    self.db
-                        .query(r#"
-                            UPSERT topic SET
-                                name = $name,
-                                embedding = $embedding,
-                                mention_count = mention_count + 1,
-                                last_mentioned = $now
-                            WHERE name = $name
-                        "#)
+        // A failed or short embedding batch defers the affected segments instead of dropping them
+        let engine = self.embedder();
+        let texts: Vec<&str> = to_insert.iter().map(|(s, _)| s.text.as_str()).collect();
+        let embeddings = match engine.embed_batch(&texts) {
+            Ok(embeddings) if embeddings.len() == to_insert.len() => embeddings,
+            Ok(embeddings) => {
+                eprintln!("[KB::add_segments_batch] Expected {} embeddings, got {}; deferring them", to_insert.len(), embeddings.len());
+                vec![Vec::new(); to_insert.len()]
+            }
+            Err(e) => {
+                eprintln!("[KB::add_segments_batch] Embedding failed, deferring {} segment(s): {}", to_insert.len(), e);
+                vec![Vec::new(); to_insert.len()]
+            }
+        };
+        let rows: Vec<TranscriptSegment> = to_insert.iter()
+            .zip(embeddings)
+            .map(|((segment, _), embedding)| {
+                let needs_embedding = check_embedding(&embedding, engine.dimension()).is_err();
+                let embedding = if needs_embedding { Vec::new() } else { embedding };
+                TranscriptSegment {
+                    id: None,
+                    meeting_id: segment.meeting_id.clone(),
+                    speaker: segment.speaker.clone(),
+                    text: segment.text.clone(),
+                    start_ms: segment.start_ms,
+                    end_ms: segment.end_ms,
+                    embedding,
+                    word_timings: segment.word_timings.clone(),
+                    speaker_confidence: None,
+                    overlapped: false,
+                    language: segment.language.clone(),
+                    emotion: segment.emotion.clone(),
+                    needs_embedding,
+                }
+            })
+            .collect();
+
+        // One INSERT statement, so either every row is written or none is
+        let created: Vec<TranscriptSegment> = match self.db.insert("segment").content(rows).await {
+            Ok(created) => created,
+            Err(e) => return Err(BatchWriteError {
+                error: SecondBrainError::Db(format!("Failed to create segments: {}", e)),
+                unsaved: to_insert.into_iter().map(|(s, _)| s).collect(),
+            }),
+        };
+        if created.len() != to_insert.len() {
+            return Err(BatchWriteError {
+                error: SecondBrainError::Db(format!("Expected {} segments to be created, got {}", to_insert.len(), created.len())),
+                unsaved: Vec::new(),
+            });
+        }
+
+        // The transcript is stored from here on; entity errors mustn't trigger a rewrite
+        let stored = |error: SecondBrainError| BatchWriteError { error, unsaved: Vec::new() };
+        for ((segment, (entities, relationships)), row) in to_insert.iter().zip(&created) {
+            let segment_id = row.id.as_ref().map(|t| normalize_record_id("segment", &t.to_string()));
+            self.process_entities(&segment.meeting_id, entities, segment.end_ms).await.map_err(stored)?;
+            self.process_relationships(&segment.meeting_id, segment_id.as_deref(), relationships).await.map_err(stored)?;
+            ids.push(row.id.as_ref().map(|t| t.to_string()).unwrap_or_default());
+        }
+
+        println!("[KB::add_segments_batch] Stored {} new segment(s), {} merged",
+            created.len(), ids.len() - created.len());
+        Ok(ids)
+    }
+
+    /// Replace a segment's text (e.g. to fix an ASR mistake)
+    ///
+    /// Re-embeds the segment so vector search matches the new wording, and swaps the
+    /// entity mentions and relations extracted from the old text for those found in the new text.
+    /// Store-time redaction applies to the edit like any new segment.
+    pub async fn edit_segment(&self, segment_id: &str, new_text: &str) -> Result<TranscriptSegment, SecondBrainError> {
+        let id_part = normalize_record_id("segment", segment_id);
+        let new_text = new_text.trim();
+        if new_text.is_empty() {
+            return Err(SecondBrainError::InvalidInput("Segment text cannot be empty".to_string()));
+        }
+
+        let existing: Option<TranscriptSegment> = self.db
+            .select(("segment", id_part.as_str()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to get segment: {}", e)))?;
+        let existing = existing.ok_or_else(|| SecondBrainError::NotFound(format!("Segment not found: {}", segment_id)))?;
+
+        let (mut entities, mut relationships) = self.entity_engine.extract_with_relations(new_text)?;
+        let redactor = self.redactor.read().clone();
+        let text = match redactor {
+            Some(redactor) => redactor.redact(new_text, &person_names(&entities)).text,
+            None => new_text.to_string(),
+        };
+        if text == existing.text {
+            return Ok(existing);
+        }
+        // Scrubbed names must not reach the graph through the edit either
+        if text != new_text {
+            (entities, relationships) = self.entity_engine.extract_with_relations(&text)?;
+        }
+
+        let embedding = self.embed_checked(&text)?;
+        let word_timings = redacted_word_timings(&text, &existing.word_timings);
+        let updated: Vec<TranscriptSegment> = self.db
+            .query("UPDATE type::thing('segment', $id) SET text = $text, embedding = $embedding, needs_embedding = false, word_timings = $word_timings")
+            .bind(("id", id_part.clone()))
+            .bind(("text", text.clone()))
+            .bind(("embedding", embedding))
+            .bind(("word_timings", word_timings))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to update segment: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract updated segment: {}", e)))?;
+        let updated = updated.into_iter().next()
+            .ok_or_else(|| SecondBrainError::NotFound(format!("Segment not found: {}", segment_id)))?;
+
+        // Drop the relations the old text produced; rows stored before relations
+        // carried a segment_id are matched one-for-one by their triple instead
+        let (old_entities, old_relationships) = self.entity_engine.extract_with_relations(&existing.text)?;
+        self.db
+            .query("DELETE entity_relation WHERE segment_id = $segment_id")
+            .bind(("segment_id", id_part.clone()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to remove old relations: {}", e)))?;
+        for rel in old_relationships.iter().filter(|r| r.confidence >= 0.5) {
+            self.db
+                .query(r#"
+                    LET $legacy = (SELECT VALUE id FROM entity_relation
+                        WHERE meeting_id = $meeting_id AND segment_id IS NONE
+                        AND source_entity = $source AND relation = $relation AND target_entity = $target
+                        LIMIT 1);
+                    DELETE $legacy;
+                "#)
+                .bind(("meeting_id", existing.meeting_id.clone()))
+                .bind(("source", rel.source.clone()))
+                .bind(("relation", rel.relation.clone()))
+                .bind(("target", rel.target.clone()))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to remove old relation: {}", e)))?;
+        }
+
+        // Only entities the edit adds or removes change the graph, so mention counts don't double up
+        let (new_entities, removed_entities) = entity_changes(&old_entities, entities);
+        self.forget_entities(&existing.meeting_id, &removed_entities).await?;
+        self.process_entities(&existing.meeting_id, &new_entities, existing.end_ms).await?;
+        self.process_relationships(&existing.meeting_id, Some(&id_part), &relationships).await?;
+
+        println!("[KB] Edited segment {} ({} new entities, {} removed, {} relations)",
+            id_part, new_entities.len(), removed_entities.len(), relationships.len());
+        Ok(updated)
+    }
+
+    /// Set the confidence threshold and relation whitelist used for new relations
+    pub fn set_relation_config(&self, config: RelationConfig) {
+        *self.relation_config.write() = config;
+    }
+
+    pub fn relation_config(&self) -> RelationConfig {
+        self.relation_config.read().clone()
+    }
+
+    /// Set how knowledge sources are chunked from now on; stored chunks keep their old split
+    pub fn set_chunk_strategy(&self, strategy: ChunkStrategy) {
+        *self.chunk_strategy.write() = strategy;
+    }
+
+    /// Enable (Some) or disable (None) PII redaction of newly stored segments
+    pub fn set_redactor(&self, redactor: Option<Redactor>) {
+        *self.redactor.write() = redactor.map(Arc::new);
+    }
+
+    /// Redact a meeting's stored segments in place, re-embedding any that changed
+    /// Returns the number of spans redacted.
+    pub async fn redact_meeting(&self, meeting_id: &str, redactor: &Redactor) -> Result<usize, SecondBrainError> {
+        let segments = self.get_meeting_segments(meeting_id).await?;
+        let meeting_names = self.meeting_person_names(meeting_id).await?;
+        let mut total = 0;
+        let mut changed_segments = 0;
+
+        for segment in segments {
+            let Some(segment_id) = segment.id.clone() else { continue };
+            let mut names = person_names(&self.entity_engine.extract(&segment.text)?);
+            names.extend(meeting_names.iter().cloned());
+            let redacted = redactor.redact(&segment.text, &names);
+            if redacted.count == 0 {
+                continue;
+            }
+
+            let embedding = self.embed_checked(&redacted.text)?;
+            let word_timings = redacted_word_timings(&redacted.text, &segment.word_timings);
+            self.db
+                .query("UPDATE $id SET text = $text, embedding = $embedding, word_timings = $word_timings")
+                .bind(("id", segment_id))
+                .bind(("text", redacted.text))
+                .bind(("embedding", embedding))
+                .bind(("word_timings", word_timings))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to update redacted segment: {}", e)))?;
+
+            total += redacted.count;
+            changed_segments += 1;
+        }
+
+        println!("[KB] Redacted {} span(s) across {} segment(s) in meeting {}",
+            total, changed_segments, meeting_id);
+        Ok(total)
+    }
+
+    /// People linked to a meeting plus its listed participants
+    async fn meeting_person_names(&self, meeting_id: &str) -> Result<Vec<String>, SecondBrainError> {
+        let mut names: Vec<String> = self.get_meeting_people(meeting_id).await?
+            .into_iter()
+            .flat_map(|p| std::iter::once(p.name).chain(p.aliases))
+            .collect();
+        if let Some(meeting) = self.get_meeting(meeting_id).await? {
+            names.extend(meeting.participants);
+        }
+        Ok(names)
+    }
+
+    /// Render a meeting (summary, action items, decisions, transcript) as Markdown
+    /// With a redactor, every exported text is scrubbed, whatever was stored.
+    /// `mode` only changes the transcript section; bookmarks are interleaved in chronological mode.
+    pub async fn export_meeting_markdown(
+        &self,
+        meeting_id: &str,
+        redactor: Option<&Redactor>,
+        mode: TranscriptExportMode,
+    ) -> Result<String, SecondBrainError> {
+        let meeting = self.get_meeting(meeting_id).await?
+            .ok_or_else(|| SecondBrainError::NotFound(format!("Meeting not found: {}", meeting_id)))?;
+        let segments = self.get_meeting_segments(meeting_id).await?;
+        let actions = self.get_meeting_action_items(meeting_id).await?;
+        let decisions = self.get_meeting_decisions(meeting_id).await?;
+        let bookmarks = self.get_meeting_bookmarks(meeting_id).await?;
+
+        let names = match redactor {
+            Some(_) => self.meeting_person_names(meeting_id).await?,
+            None => Vec::new(),
+        };
+        let mut redactions = 0;
+        let mut scrub = |text: &str| -> String {
+            match redactor {
+                Some(r) => {
+                    let redacted = r.redact(text, &names);
+                    redactions += redacted.count;
+                    redacted.text
+                }
+                None => text.to_string(),
+            }
+        };
+
+        let mut md = format!("# {}\n\n", meeting.title);
+        if let Some(started) = chrono::DateTime::from_timestamp_millis(meeting.start_time as i64) {
+            md.push_str(&format!("**Date:** {}\n", started.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")));
+        }
+        if let Some(end) = meeting.end_time {
+            md.push_str(&format!("**Duration:** {} min\n", end.saturating_sub(meeting.start_time) / 60_000));
+        }
+        if !meeting.participants.is_empty() {
+            let participants = meeting.participants.join(", ");
+            md.push_str(&format!("**Participants:** {}\n", scrub(&participants)));
+        }
+        if !meeting.tags.is_empty() {
+            md.push_str(&format!("**Tags:** {}\n", meeting.tags.join(", ")));
+        }
+
+        if let Some(summary) = meeting.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+            md.push_str(&format!("\n## Summary\n\n{}\n", scrub(summary)));
+        }
+
+        if !actions.is_empty() {
+            md.push_str("\n## Action Items\n\n");
+            for action in &actions {
+                let done = if action.status == "done" { "x" } else { " " };
+                let mut line = format!("- [{}] {}", done, scrub(&action.text));
+                if let Some(assignee) = &action.assignee {
+                    line.push_str(&format!(" (@{})", scrub(assignee)));
+                }
+                if let Some(deadline) = &action.deadline {
+                    line.push_str(&format!(" - due {}", deadline));
+                }
+                md.push_str(&line);
+                md.push('\n');
+            }
+        }
+
+        if !decisions.is_empty() {
+            md.push_str("\n## Decisions\n\n");
+            for decision in &decisions {
+                md.push_str(&format!("- {}\n", scrub(&decision.text)));
+            }
+        }
+
+        let clock = |timestamp_ms: u64| {
+            let offset_secs = timestamp_ms.saturating_sub(meeting.start_time) / 1000;
+            format!("{:02}:{:02}", offset_secs / 60, offset_secs % 60)
+        };
+        let mut bookmark_notes = Vec::with_capacity(bookmarks.len());
+        for bookmark in &bookmarks {
+            bookmark_notes.push(bookmark.note.as_deref().map(&mut scrub));
+        }
+
+        if !bookmarks.is_empty() {
+            md.push_str("\n## Bookmarks\n\n");
+            for (bookmark, note) in bookmarks.iter().zip(&bookmark_notes) {
+                match note {
+                    Some(note) => md.push_str(&format!("- [{}] {}\n", clock(bookmark.timestamp_ms), note)),
+                    None => md.push_str(&format!("- [{}]\n", clock(bookmark.timestamp_ms))),
+                }
+            }
+        }
+
+        if !segments.is_empty() && mode == TranscriptExportMode::BySpeaker {
+            md.push_str("\n## Transcript by Speaker\n\n");
+            // Busiest speaker first, so relabelled meetings with many minor speakers stay readable
+            let stats = MeetingSpeakerStats::from_segments(meeting_id, &segments, INTERRUPTION_GAP_MS);
+            for speaker in &stats.speakers {
+                let talk_secs = speaker.talk_duration_ms / 1000;
+                md.push_str(&format!(
+                    "### {} ({}:{:02} talk time)\n\n",
+                    scrub(&speaker.speaker),
+                    talk_secs / 60,
+                    talk_secs % 60
+                ));
+                for segment in segments.iter().filter(|s| s.speaker == speaker.speaker) {
+                    md.push_str(&format!("[{}] {}\n\n", clock(segment.start_ms), scrub(&segment.text)));
+                }
+            }
+        } else if !segments.is_empty() && mode == TranscriptExportMode::Dialogue {
+            md.push_str("\n## Dialogue\n\n");
+            let turns = dialogue_turns(&segments);
+            let mut speakers: Vec<&str> = Vec::new();
+            for turn in &turns {
+                if !speakers.contains(&turn.speaker.as_str()) {
+                    speakers.push(&turn.speaker);
+                }
+            }
+            if speakers.len() == 2 {
+                // Whoever speaks first asks; each answer closes a Q/A pair
+                for turn in &turns {
+                    let is_question = turn.speaker == speakers[0];
+                    md.push_str(&format!(
+                        "**{}** ({}) [{}]: {}\n",
+                        if is_question { "Q" } else { "A" },
+                        scrub(&turn.speaker),
+                        clock(turn.start_ms),
+                        scrub(&turn.text)
+                    ));
+                    if !is_question {
+                        md.push('\n');
+                    }
+                }
+                if !md.ends_with("\n\n") {
+                    md.push('\n');
+                }
+            } else {
+                for turn in &turns {
+                    md.push_str(&format!(
+                        "**{}** [{}]: {}\n\n",
+                        scrub(&turn.speaker),
+                        clock(turn.start_ms),
+                        scrub(&turn.text)
+                    ));
+                }
+            }
+        } else if !segments.is_empty() {
+            md.push_str("\n## Transcript\n\n");
+            // Bookmarks are interleaved before the first segment that starts after them
+            let mut pending = bookmarks.iter().zip(&bookmark_notes).peekable();
+            let bookmark_line = |bookmark: &MeetingBookmark, note: &Option<String>| match note {
+                Some(note) => format!("> **Bookmark** [{}]: {}\n\n", clock(bookmark.timestamp_ms), note),
+                None => format!("> **Bookmark** [{}]\n\n", clock(bookmark.timestamp_ms)),
+            };
+            for segment in &segments {
+                while let Some((bookmark, note)) = pending.next_if(|(b, _)| b.timestamp_ms <= segment.start_ms) {
+                    md.push_str(&bookmark_line(bookmark, note));
+                }
+                let offset_secs = segment.start_ms.saturating_sub(meeting.start_time) / 1000;
+                md.push_str(&format!(
+                    "**{}** [{:02}:{:02}]: {}\n\n",
+                    scrub(&segment.speaker),
+                    offset_secs / 60,
+                    offset_secs % 60,
+                    scrub(&segment.text)
+                ));
+            }
+            for (bookmark, note) in pending {
+                md.push_str(&bookmark_line(bookmark, note));
+            }
+        }
+
+        if redactor.is_some() {
+            println!("[KB] Exported meeting {} with {} redaction(s)", meeting_id, redactions);
+        }
+        Ok(md)
+    }
+
+    /// Replace the segment dedup settings
+    pub fn set_dedup_config(&mut self, config: SegmentDedupConfig) {
+        self.dedup_config = config;
+    }
+
+    pub fn dedup_config(&self) -> &SegmentDedupConfig {
+        &self.dedup_config
+    }
+
+    /// If the speaker's latest segment is a near-duplicate of `text` within the dedup window,
+    /// extend it (end time and, if longer, text) and return its id
+    async fn merge_duplicate_segment(
+        &self,
+        meeting_id: &str,
+        speaker: &str,
+        text: &str,
+        start_ms: u64,
+        end_ms: u64,
+        word_timings: &[WordTiming],
+    ) -> Result<Option<String>, SecondBrainError> {
+        if !self.dedup_config.enabled {
+            return Ok(None);
+        }
+
+        let previous: Vec<TranscriptSegment> = self.db
+            .query("SELECT * FROM segment WHERE meeting_id = $meeting_id AND speaker = $speaker ORDER BY end_ms DESC LIMIT 1")
+            .bind(("meeting_id", meeting_id.to_string()))
+            .bind(("speaker", speaker.to_string()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query previous segment: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract previous segment: {}", e)))?;
+
+        let Some(last) = previous.into_iter().next() else {
+            return Ok(None);
+        };
+        let Some(last_id) = last.id.clone() else {
+            return Ok(None);
+        };
+
+        if !self.dedup_config.is_duplicate(&last.text, last.end_ms, text, start_ms) {
+            return Ok(None);
+        }
+
+        // Keep whichever wording carries more content
+        let merged_text = if normalize_segment_text(text).len() > normalize_segment_text(&last.text).len() {
+            text.to_string()
+        } else {
+            last.text.clone()
+        };
+        let merged_end = end_ms.max(last.end_ms);
+
+        if merged_text != last.text {
+            // The new wording wins, so its word timings replace the old ones (shifted onto
+            // the kept segment's start)
+            let shift = start_ms.saturating_sub(last.start_ms);
+            let merged_timings: Vec<WordTiming> = word_timings.iter()
+                .map(|(word, start, end)| (word.clone(), start + shift, end + shift))
+                .collect();
+            let (embedding, needs_embedding) = self.embed_or_defer(&merged_text);
+            self.db
+                .query("UPDATE $id SET text = $text, end_ms = $end_ms, embedding = $embedding, needs_embedding = $needs_embedding, word_timings = $word_timings")
+                .bind(("id", last_id.clone()))
+                .bind(("text", merged_text))
+                .bind(("end_ms", merged_end))
+                .bind(("embedding", embedding))
+                .bind(("needs_embedding", needs_embedding))
+                .bind(("word_timings", merged_timings))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to merge segment: {}", e)))?;
+        } else {
+            self.db
+                .query("UPDATE $id SET end_ms = $end_ms")
+                .bind(("id", last_id.clone()))
+                .bind(("end_ms", merged_end))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to merge segment: {}", e)))?;
+        }
+
+        Ok(Some(last_id.to_string()))
+    }
+
+    /// Undo one mention per entity in a meeting: its graph edge and, for topics, its count
+    async fn forget_entities(&self, meeting_id: &str, entities: &[Entity]) -> Result<(), SecondBrainError> {
+        let meeting_id = normalize_record_id("meeting", meeting_id);
+        for entity in entities {
+            let query = match self.entity_engine.kind_of(&entity.label) {
+                Some(EntityKind::Person) => FORGET_PERSON_MENTION_QUERY,
+                Some(EntityKind::Topic) => FORGET_TOPIC_MENTION_QUERY,
+                _ => continue,
+            };
+            self.db
+                .query(query)
+                .bind(("name", entity.text.clone()))
+                .bind(("meeting_id", meeting_id.clone()))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to remove mention of {}: {}", entity.text, e)))?;
+        }
+        Ok(())
+    }
+
+    /// Process extracted entities and create graph relations
+    /// `seen_at` is when the entities were said (the segment's end), not when they were processed.
+    async fn process_entities(&self, meeting_id: &str, entities: &[Entity], seen_at: u64) -> Result<(), SecondBrainError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        // Extract just the ID part for use with type::thing()
+        let meeting_id_part = normalize_record_id("meeting", meeting_id);
+        let meeting_id_owned = meeting_id_part.to_string();
+
+        for entity in entities {
+            let entity_text = entity.text.clone();
+            let meeting_id_clone = meeting_id_owned.clone();
+
+            match self.entity_engine.kind_of(&entity.label) {
+                Some(EntityKind::Person) => {
+                    // Upsert person
+                    self.db
+                        .query(UPSERT_PERSON_QUERY)
+                        .bind(("name", entity_text.clone()))
+                        .bind(("seen_at", seen_at))
+                        .await
+                        .ok();
+
+                    // Create relation
+                    self.db
+                        .query("RELATE (SELECT * FROM person WHERE name = $name) -> mentioned_in -> type::thing('meeting', $meeting_id)")
+                        .bind(("name", entity_text))
+                        .bind(("meeting_id", meeting_id_clone))
+                        .await
+                        .ok();
+                }
+                Some(EntityKind::Topic) => {
+                    // Upsert topic
+                    let topic_embedding = match self.embed_checked(&entity.text) {
+                        Ok(embedding) => embedding,
+                        Err(e) => {
+                            eprintln!("[KB] Skipping topic '{}': {}", entity_text, e);
+                            continue;
+                        }
+                    };
+
+                    self.db
+                        .query(UPSERT_TOPIC_QUERY)
                         .bind(("name", entity_text.clone()))
                         .bind(("embedding", topic_embedding))
-                        .bind(("now", now))
+                        .bind(("seen_at", seen_at))
                         .await
                         .ok();
 
@@ -596,7 +3707,7 @@ impl KnowledgeBase {
                         .await
                         .ok();
                 }
-                "action_item" => {
+                Some(EntityKind::ActionItem) => {
                     let action = ActionItem {
                         id: None,
                         meeting_id: meeting_id_clone,
@@ -613,7 +3724,7 @@ impl KnowledgeBase {
                         .await
                         .ok();
                 }
-                "decision" => {
+                Some(EntityKind::Decision) => {
                     let decision = Decision {
                         id: None,
                         meeting_id: meeting_id_clone,
@@ -636,15 +3747,22 @@ impl KnowledgeBase {
     }
 
     /// Process extracted relationships and store in graph
-    async fn process_relationships(&self, meeting_id: &str, relationships: &[Relationship]) -> Result<(), String> {
+    /// `segment_id` ties each relation to the segment it came from so edits can replace them.
+    async fn process_relationships(
+        &self,
+        meeting_id: &str,
+        segment_id: Option<&str>,
+        relationships: &[Relationship],
+    ) -> Result<(), SecondBrainError> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_millis() as u64;
 
+        let config = self.relation_config();
         for rel in relationships {
-            // Only store relationships with reasonable confidence
-            if rel.confidence < 0.5 {
+            // Only store confident relationships of a whitelisted type
+            if !config.allows(rel) {
                 continue;
             }
 
@@ -657,6 +3775,7 @@ impl KnowledgeBase {
                 target_type: String,
                 confidence: f32,
                 meeting_id: Option<String>,
+                segment_id: Option<String>,
                 created_at: u64,
             }
 
@@ -667,7 +3786,8 @@ impl KnowledgeBase {
                 target_entity: rel.target.clone(),
                 target_type: rel.target_type.clone(),
                 confidence: rel.confidence,
-                meeting_id: Some(meeting_id.to_string()),
+                meeting_id: Some(normalize_record_id("meeting", meeting_id)),
+                segment_id: segment_id.map(|id| id.to_string()),
                 created_at: now,
             };
 
@@ -686,7 +3806,7 @@ impl KnowledgeBase {
     }
 
     /// Process entities from a knowledge source (not a meeting)
-    async fn process_entities_for_source(&self, _source_id: &str, entities: &[Entity]) -> Result<(), String> {
+    async fn process_entities_for_source(&self, _source_id: &str, entities: &[Entity]) -> Result<(), SecondBrainError> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -695,8 +3815,8 @@ impl KnowledgeBase {
         for entity in entities {
             let entity_text = entity.text.clone();
 
-            match entity.label.as_str() {
-                "person" => {
+            match self.entity_engine.source_kind_of(&entity.label) {
+                Some(EntityKind::Person) => {
                     // Upsert person
                     self.db
                         .query(r#"
@@ -712,9 +3832,15 @@ impl KnowledgeBase {
                         .await
                         .ok();
                 }
-                "topic" | "project" | "product" | "organization" => {
+                Some(EntityKind::Topic) => {
                     // Upsert topic
-                    let topic_embedding = self.embedding_engine.embed(&entity.text).unwrap_or_default();
+                    let topic_embedding = match self.embed_checked(&entity.text) {
+                        Ok(embedding) => embedding,
+                        Err(e) => {
+                            eprintln!("[KB] Skipping topic '{}': {}", entity_text, e);
+                            continue;
+                        }
+                    };
 
                     self.db
                         .query(r#"
@@ -739,14 +3865,15 @@ impl KnowledgeBase {
     }
 
     /// Process relationships from a knowledge source (not a meeting)
-    async fn process_relationships_for_source(&self, source_id: &str, relationships: &[Relationship]) -> Result<(), String> {
+    async fn process_relationships_for_source(&self, source_id: &str, relationships: &[Relationship]) -> Result<(), SecondBrainError> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_millis() as u64;
 
+        let config = self.relation_config();
         for rel in relationships {
-            if rel.confidence < 0.5 {
+            if !config.allows(rel) {
                 continue;
             }
 
@@ -790,186 +3917,762 @@ impl KnowledgeBase {
         &self,
         query: &str,
         limit: usize,
-    ) -> Result<Vec<SearchResult>, String> {
-        let query_embedding = self.embedding_engine.embed(query)?;
-
-        // SurrealDB vector search
-        let results: Vec<TranscriptSegment> = self.db
+        min_similarity: Option<f32>,
+    ) -> Result<Vec<SearchResult>, SecondBrainError> {
+        self.ensure_embeddings_compatible()?;
+        let query_embedding = self.embedder().embed(query)?;
+        let hidden = self.hidden_meeting_filter().await?;
+
+        // SurrealDB vector search, skipping private and trashed meetings
+        let results: Vec<SegmentWithSimilarity> = self.db
             .query(r#"
                 SELECT *, vector::similarity::cosine(embedding, $embedding) AS similarity
                 FROM segment
+                WHERE meeting_id NOTINSIDE $hidden AND needs_embedding != true
                 ORDER BY similarity DESC
                 LIMIT $limit
             "#)
             .bind(("embedding", query_embedding))
+            .bind(("hidden", hidden))
             .bind(("limit", limit))
             .await
-            .map_err(|e| format!("Search failed: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Search failed: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract results: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract results: {}", e)))?;
 
         // Get meeting titles
         let mut search_results = Vec::new();
-        for segment in results {
-            let meeting_title = self.get_meeting_title(&segment.meeting_id).await?;
+        for matched in rank_segment_matches(results, min_similarity) {
+            let meeting_title = self.get_meeting_title(&matched.meeting_id).await?;
+            let similarity = matched.similarity;
             search_results.push(SearchResult {
-                segment,
+                segment: matched.into_segment(),
                 meeting_title,
-                similarity: 0.0, // Will be filled by the query
+                similarity,
             });
         }
 
         Ok(search_results)
     }
 
-    /// Get meeting title by ID
-    async fn get_meeting_title(&self, meeting_id: &str) -> Result<String, String> {
-        let meeting: Option<Meeting> = self.db
-            .select(("meeting", meeting_id))
-            .await
-            .map_err(|e| format!("Failed to get meeting: {}", e))?;
-
-        Ok(meeting.map(|m| m.title).unwrap_or_else(|| "Unknown".to_string()))
-    }
-
-    /// Get all open action items
-    pub async fn get_open_actions(&self) -> Result<Vec<ActionItem>, String> {
-        let actions: Vec<ActionItem> = self.db
-            .query("SELECT * FROM action_item WHERE status = 'open' ORDER BY created_at DESC")
-            .await
-            .map_err(|e| format!("Query failed: {}", e))?
-            .take(0)
-            .map_err(|e| format!("Failed to extract actions: {}", e))?;
-
-        Ok(actions)
-    }
+    /// Semantic search over one meeting's segments, best match first
+    /// A cosine scan filtered to the meeting (segments have no vector index to narrow it further).
+    pub async fn search_within_meeting(
+        &self,
+        meeting_id: &str,
+        query: &str,
+        limit: usize,
+        min_similarity: Option<f32>,
+    ) -> Result<Vec<SegmentMatch>, SecondBrainError> {
+        if query.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        self.ensure_embeddings_compatible()?;
+        let query_embedding = self.embedder().embed(query)?;
 
-    /// Get recent decisions
-    pub async fn get_recent_decisions(&self, limit: usize) -> Result<Vec<Decision>, String> {
-        let decisions: Vec<Decision> = self.db
-            .query("SELECT * FROM decision ORDER BY created_at DESC LIMIT $limit")
+        let results: Vec<SegmentWithSimilarity> = self.db
+            .query(r#"
+                SELECT *, vector::similarity::cosine(embedding, $embedding) AS similarity
+                FROM segment
+                WHERE meeting_id = $meeting_id AND needs_embedding != true
+                ORDER BY similarity DESC
+                LIMIT $limit
+            "#)
+            .bind(("embedding", query_embedding))
+            .bind(("meeting_id", normalize_record_id("meeting", meeting_id)))
             .bind(("limit", limit))
             .await
-            .map_err(|e| format!("Query failed: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Search failed: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract decisions: {}", e))?;
-
-        Ok(decisions)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract results: {}", e)))?;
+
+        Ok(rank_segment_matches(results, min_similarity)
+            .into_iter()
+            .map(|matched| {
+                let score = matched.similarity;
+                SegmentMatch { segment: matched.into_segment(), score }
+            })
+            .collect())
     }
 
-    /// Get people mentioned with a person
-    pub async fn get_related_people(&self, person_name: &str) -> Result<Vec<String>, String> {
-        let name_owned = person_name.to_string();
+    /// Case-insensitive text search over one meeting's segments, most occurrences first
+    pub async fn search_text_within_meeting(
+        &self,
+        meeting_id: &str,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SegmentMatch>, SecondBrainError> {
+        let needle = query.trim().to_lowercase();
+        if needle.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        let people: Vec<Person> = self.db
-            .query(r#"
-                SELECT DISTINCT person.name FROM person
-                WHERE id IN (
-                    SELECT in FROM mentioned_in
-                    WHERE out IN (
-                        SELECT out FROM mentioned_in
-                        WHERE in = (SELECT id FROM person WHERE name = $name)
-                    )
-                )
-                AND name != $name
-            "#)
-            .bind(("name", name_owned))
+        let segments: Vec<TranscriptSegment> = self.db
+            .query("SELECT * FROM segment WHERE meeting_id = $meeting_id AND string::contains(string::lowercase(text), $needle)")
+            .bind(("meeting_id", normalize_record_id("meeting", meeting_id)))
+            .bind(("needle", needle.clone()))
             .await
-            .map_err(|e| format!("Query failed: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Search failed: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract people: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))?;
 
-        Ok(people.into_iter().map(|p| p.name).collect())
+        Ok(rank_text_matches(segments, &needle, limit))
     }
 
-    /// Full-text search in transcripts
-    pub async fn search_text(&self, query: &str, limit: usize) -> Result<Vec<TranscriptSegment>, String> {
-        let query_owned = query.to_string();
+    /// Find meetings by content: vector-matched segments grouped per meeting, boosted by
+    /// title, action item and decision substring matches
+    /// Segment similarity is a full cosine scan, like `search_similar`.
+    pub async fn search_meetings(&self, query: &str, limit: usize) -> Result<Vec<MeetingSearchResult>, SecondBrainError> {
+        #[derive(Deserialize)]
+        struct SegmentHit {
+            meeting_id: String,
+            text: String,
+            similarity: f32,
+        }
 
-        let segments: Vec<TranscriptSegment> = self.db
-            .query("SELECT * FROM segment WHERE text CONTAINS $query LIMIT $limit")
-            .bind(("query", query_owned))
-            .bind(("limit", limit))
+        #[derive(Deserialize)]
+        struct ItemHit {
+            meeting_id: String,
+            text: String,
+        }
+
+        self.ensure_embeddings_compatible()?;
+        let query_embedding = self.embedder().embed(query)?;
+        let needle = query.trim().to_lowercase();
+        let hidden = self.hidden_meeting_filter().await?;
+
+        let mut response = self.db
+            .query(MEETING_SEARCH_QUERY)
+            .bind(("embedding", query_embedding))
+            .bind(("hits", limit.max(1) * MEETING_SEGMENT_FANOUT))
+            .bind(("hidden", hidden))
+            .bind(("needle", needle))
             .await
-            .map_err(|e| format!("Search failed: {}", e))?
-            .take(0)
-            .map_err(|e| format!("Failed to extract segments: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Meeting search failed: {}", e)))?;
 
-        Ok(segments)
-    }
+        let segment_hits: Vec<SegmentHit> = response.take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segment matches: {}", e)))?;
+        let title_matches: Vec<Meeting> = response.take(1).unwrap_or_default();
+        let action_hits: Vec<ItemHit> = response.take(2).unwrap_or_default();
+        let decision_hits: Vec<ItemHit> = response.take(3).unwrap_or_default();
 
-    // ==================== Knowledge Source Methods ====================
+        let mut scores: std::collections::HashMap<String, MeetingScore> = std::collections::HashMap::new();
+        let mut meetings: std::collections::HashMap<String, Meeting> = std::collections::HashMap::new();
 
-    /// Add a knowledge source (URL, document) and chunk it
-    pub async fn add_knowledge_source(
-        &self,
-        url: &str,
-        title: &str,
+        for hit in &segment_hits {
+            scores.entry(normalize_record_id("meeting", &hit.meeting_id))
+                .or_default()
+                .add_segment(hit.similarity, &hit.text);
+        }
+        for meeting in title_matches {
+            let id = meeting.id.as_ref()
+                .map(|t| normalize_record_id("meeting", &t.to_string()))
+                .unwrap_or_default();
+            scores.entry(id.clone()).or_default().title_match = true;
+            meetings.insert(id, meeting);
+        }
+        for (hits, is_action) in [(&action_hits, true), (&decision_hits, false)] {
+            for hit in hits {
+                scores.entry(normalize_record_id("meeting", &hit.meeting_id))
+                    .or_default()
+                    .add_item(&hit.text, is_action);
+            }
+        }
+
+        let mut ranked: Vec<(String, MeetingScore)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.score().partial_cmp(&a.1.score()).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut results = Vec::new();
+        for (meeting_id, score) in ranked {
+            if results.len() >= limit {
+                break;
+            }
+            let meeting = match meetings.remove(&meeting_id) {
+                Some(meeting) => meeting,
+                // Segments can outlive a deleted meeting; skip them
+                None => match self.get_meeting(&meeting_id).await? {
+                    Some(meeting) => meeting,
+                    None => continue,
+                },
+            };
+            if meeting.deleted_at.is_some() || meeting.private {
+                continue;
+            }
+            results.push(MeetingSearchResult {
+                meeting,
+                excerpt: score.excerpt.clone().unwrap_or_default(),
+                score: score.score(),
+                matched_segments: score.segment_hits,
+            });
+        }
+
+        println!("[KB] Meeting search '{}': {} meetings from {} segment hits",
+            query, results.len(), segment_hits.len());
+        Ok(results)
+    }
+
+    /// Get meeting title by ID
+    async fn get_meeting_title(&self, meeting_id: &str) -> Result<String, SecondBrainError> {
+        let meeting: Option<Meeting> = self.db
+            .select(("meeting", normalize_record_id("meeting", meeting_id)))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to get meeting: {}", e)))?;
+
+        Ok(meeting.map(|m| m.title).unwrap_or_else(|| "Unknown".to_string()))
+    }
+
+    /// Get all open action items, skipping trashed meetings
+    pub async fn get_open_actions(&self) -> Result<Vec<ActionItem>, SecondBrainError> {
+        let deleted = self.deleted_meeting_filter().await?;
+        self.open_actions_outside(deleted).await
+    }
+
+    /// Open action items outside the `excluded` meetings (ids in both forms)
+    async fn open_actions_outside(&self, excluded: Vec<String>) -> Result<Vec<ActionItem>, SecondBrainError> {
+        let actions: Vec<ActionItem> = self.db
+            .query(OPEN_ACTIONS_QUERY)
+            .bind(("deleted", excluded))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Query failed: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract actions: {}", e)))?;
+
+        Ok(actions)
+    }
+
+    /// Get recent decisions, skipping trashed meetings
+    pub async fn get_recent_decisions(&self, limit: usize) -> Result<Vec<Decision>, SecondBrainError> {
+        let deleted = self.deleted_meeting_filter().await?;
+        let decisions: Vec<Decision> = self.db
+            .query(RECENT_DECISIONS_QUERY)
+            .bind(("deleted", deleted))
+            .bind(("limit", limit))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Query failed: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract decisions: {}", e)))?;
+
+        Ok(decisions)
+    }
+
+    /// Get people mentioned with a person, outside private and trashed meetings
+    pub async fn get_related_people(&self, person_name: &str) -> Result<Vec<String>, SecondBrainError> {
+        let names: Vec<String> = self.db
+            .query(RELATED_PEOPLE_QUERY)
+            .bind(("name", person_name.to_string()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Query failed: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract people: {}", e)))?;
+
+        Ok(names)
+    }
+
+    /// Collect a person's segments, relations, topics and open actions across all meetings
+    /// `name` may be the person's name or any of their merged aliases.
+    pub async fn get_person_dossier(&self, name: &str, segment_limit: usize) -> Result<PersonDossier, SecondBrainError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(SecondBrainError::InvalidInput("Person name is required".to_string()));
+        }
+
+        let people: Vec<Person> = self.db
+            .query("SELECT * FROM person WHERE name = $name OR aliases CONTAINS $name OR string::lowercase(name) = $lower LIMIT 1")
+            .bind(("name", name.to_string()))
+            .bind(("lower", name.to_lowercase()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query person: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract person: {}", e)))?;
+        let person = people.into_iter().next();
+
+        let (canonical, aliases) = match &person {
+            Some(p) => (p.name.clone(), p.aliases.clone()),
+            None => (name.to_string(), Vec::new()),
+        };
+        let mut names: Vec<String> = std::iter::once(canonical.clone()).chain(aliases.iter().cloned()).collect();
+        names.dedup();
+
+        // Segments spoken by the person, then segments mentioning any of their names
+        // (private and trashed meetings stay out of the dossier)
+        let hidden_ids = self.hidden_meeting_ids().await?;
+        let hidden = meeting_id_forms(hidden_ids.clone());
+        let mut segments: Vec<TranscriptSegment> = self.db
+            .query("SELECT * FROM segment WHERE speaker IN $names AND meeting_id NOTINSIDE $hidden ORDER BY start_ms DESC LIMIT $limit")
+            .bind(("names", names.clone()))
+            .bind(("hidden", hidden.clone()))
+            .bind(("limit", segment_limit))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query segments: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))?;
+        for n in &names {
+            let mentions: Vec<TranscriptSegment> = self.db
+                .query("SELECT * FROM segment WHERE string::contains(string::lowercase(text), $needle) AND meeting_id NOTINSIDE $hidden ORDER BY start_ms DESC LIMIT $limit")
+                .bind(("needle", n.to_lowercase()))
+                .bind(("hidden", hidden.clone()))
+                .bind(("limit", segment_limit))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to query mentions: {}", e)))?
+                .take(0)
+                .map_err(|e| SecondBrainError::Db(format!("Failed to extract mentions: {}", e)))?;
+            segments.extend(mentions);
+        }
+        let mut seen_segments = std::collections::HashSet::new();
+        segments.retain(|s| seen_segments.insert(s.id.as_ref().map(|t| t.to_string()).unwrap_or_default()));
+        segments.sort_by(|a, b| b.start_ms.cmp(&a.start_ms));
+        segments.truncate(segment_limit);
+
+        #[derive(Deserialize)]
+        struct StoredRelation {
+            source_entity: String,
+            relation: String,
+            target_entity: String,
+            source_type: String,
+            target_type: String,
+            confidence: f32,
+            meeting_id: Option<String>,
+        }
+
+        let stored: Vec<StoredRelation> = self.db
+            .query(PERSON_RELATIONS_QUERY)
+            .bind(("names", names.clone()))
+            .bind(("hidden", hidden.clone()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query relations: {}", e)))?
+            .take(0)
+            .unwrap_or_default();
+
+        let relations: Vec<PersonRelation> = stored.into_iter().map(|r| {
+            let outgoing = names.contains(&r.source_entity);
+            let (other, other_type) = if outgoing {
+                (r.target_entity, r.target_type)
+            } else {
+                (r.source_entity, r.source_type)
+            };
+            PersonRelation {
+                relation: r.relation,
+                other,
+                other_type,
+                outgoing,
+                confidence: r.confidence,
+                meeting_id: r.meeting_id,
+            }
+        }).collect();
+
+        let mut topics: Vec<String> = Vec::new();
+        for r in &relations {
+            if matches!(r.other_type.as_str(), "topic" | "project" | "product") && !topics.contains(&r.other) {
+                topics.push(r.other.clone());
+            }
+        }
+
+        let open_actions: Vec<ActionItem> = self.open_actions_outside(hidden).await?
+            .into_iter()
+            .filter(|a| is_assigned_to(a.assignee.as_deref(), &names))
+            .collect();
+
+        let related_people = self.get_related_people(&canonical).await.unwrap_or_default();
+
+        // Meetings the person is linked to, plus any the material above cites
+        let mut meetings: Vec<Meeting> = match &person {
+            Some(Person { id: Some(person_id), .. }) => self.db
+                .query(PERSON_MEETINGS_QUERY)
+                .bind(("person", person_id.clone()))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to query meetings: {}", e)))?
+                .take(0)
+                .unwrap_or_default(),
+            _ => Vec::new(),
+        };
+        let mut meeting_ids: std::collections::HashSet<String> = meetings.iter()
+            .filter_map(|m| m.id.as_ref().map(|t| normalize_record_id("meeting", &t.to_string())))
+            .collect();
+        let cited = segments.iter().map(|s| s.meeting_id.clone())
+            .chain(relations.iter().filter_map(|r| r.meeting_id.clone()))
+            .chain(open_actions.iter().map(|a| a.meeting_id.clone()));
+        for meeting_id in cited {
+            let id_part = normalize_record_id("meeting", &meeting_id);
+            if !hidden_ids.contains(&id_part) && meeting_ids.insert(id_part.clone()) {
+                if let Some(meeting) = self.get_meeting(&id_part).await? {
+                    meetings.push(meeting);
+                }
+            }
+        }
+        meetings.sort_by(|a, b| b.start_time.cmp(&a.start_time));
+
+        println!("[KB] Dossier for {}: {} segments, {} relations, {} open actions across {} meetings",
+            canonical, segments.len(), relations.len(), open_actions.len(), meetings.len());
+
+        Ok(PersonDossier {
+            name: canonical,
+            aliases,
+            segments,
+            relations,
+            topics,
+            open_actions,
+            related_people,
+            meetings,
+        })
+    }
+
+    /// Full-text search in transcripts
+    pub async fn search_text(&self, query: &str, limit: usize) -> Result<Vec<TranscriptSegment>, SecondBrainError> {
+        let query_owned = query.to_string();
+
+        let hidden = self.hidden_meeting_filter().await?;
+
+        let segments: Vec<TranscriptSegment> = self.db
+            .query("SELECT * FROM segment WHERE text CONTAINS $query AND meeting_id NOTINSIDE $hidden LIMIT $limit")
+            .bind(("query", query_owned))
+            .bind(("hidden", hidden))
+            .bind(("limit", limit))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Search failed: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))?;
+
+        Ok(segments)
+    }
+
+    // ==================== Knowledge Source Methods ====================
+
+    /// Add a knowledge source (URL, document) and chunk it
+    pub async fn add_knowledge_source(
+        &self,
+        url: &str,
+        title: &str,
         content: &str,
         source_type: &str,
         tags: Vec<String>,
-    ) -> Result<String, String> {
-        use crate::chunker::DocumentChunker;
+    ) -> Result<String, SecondBrainError> {
+        self.add_knowledge_source_with_progress(url, title, content, source_type, tags, &|_| {}).await
+    }
 
+    /// Add a knowledge source, reporting chunk embedding progress
+    ///
+    /// Re-adding a URL whose earlier ingestion was interrupted resumes it: chunks
+    /// that were already stored are kept and only the missing ones are embedded.
+    /// If the content changed since then, the old chunks are rebuilt instead.
+    pub async fn add_knowledge_source_with_progress(
+        &self,
+        url: &str,
+        title: &str,
+        content: &str,
+        source_type: &str,
+        tags: Vec<String>,
+        on_progress: &(dyn Fn(&IngestionProgress) + Sync),
+    ) -> Result<String, SecondBrainError> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
             .as_millis() as u64;
 
-        // Create the knowledge source
-        let source = KnowledgeSource {
-            id: None,
-            url: url.to_string(),
-            title: title.to_string(),
-            source_type: source_type.to_string(),
-            raw_content: content.to_string(),
-            tags,
-            created_at: now,
-            last_updated: now,
+        let existing: Vec<KnowledgeSource> = self.db
+            .query("SELECT * FROM knowledge_source WHERE url = $url LIMIT 1")
+            .bind(("url", url.to_string()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to look up knowledge source: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract knowledge source: {}", e)))?;
+
+        let source_id = match existing.into_iter().next() {
+            Some(source) => {
+                let source_id = source.id.as_ref().map(|t| t.to_string()).unwrap_or_default();
+                let content_changed = source.raw_content != content;
+                println!("[KB Ingest] Source {} already exists ({}), resuming", source_id,
+                    if content_changed { "content changed" } else { "same content" });
+
+                let id_part = source_id.strip_prefix("knowledge_source:").unwrap_or(&source_id).to_string();
+                self.db
+                    .query("UPDATE type::thing('knowledge_source', $id) SET title = $title, raw_content = $content, tags = $tags, last_updated = $now")
+                    .bind(("id", id_part))
+                    .bind(("title", title.to_string()))
+                    .bind(("content", content.to_string()))
+                    .bind(("tags", tags))
+                    .bind(("now", now))
+                    .await
+                    .map_err(|e| SecondBrainError::Db(format!("Failed to update knowledge source: {}", e)))?;
+
+                if content_changed {
+                    self.clear_source_chunks(&source_id).await?;
+                }
+                source_id
+            }
+            None => {
+                let source = KnowledgeSource {
+                    id: None,
+                    url: url.to_string(),
+                    title: title.to_string(),
+                    source_type: source_type.to_string(),
+                    raw_content: content.to_string(),
+                    tags,
+                    created_at: now,
+                    last_updated: now,
+                    last_accessed: None,
+                    pinned: false,
+                    refresh_interval_hours: None,
+                    last_refreshed: None,
+                };
+
+                let created: Option<KnowledgeSource> = self.db
+                    .create("knowledge_source")
+                    .content(source)
+                    .await
+                    .map_err(|e| SecondBrainError::Db(format!("Failed to create knowledge source: {}", e)))?;
+
+                match created {
+                    Some(s) => s.id.map(|t| t.to_string()).unwrap_or_default(),
+                    None => return Err(SecondBrainError::Db("Failed to create knowledge source".to_string())),
+                }
+            }
         };
 
-        let created: Option<KnowledgeSource> = self.db
-            .create("knowledge_source")
-            .content(source)
+        let embedded = self.embed_missing_chunks(&source_id, title, content, on_progress).await?;
+
+        // Entities were already extracted if an earlier attempt stored every chunk
+        if embedded > 0 {
+            self.extract_source_entities(&source_id, content).await;
+        }
+
+        Ok(source_id)
+    }
+
+    /// Rebuild a knowledge source's chunks and embeddings from its stored raw content
+    /// Useful after switching embedding models. Returns the number of chunks stored.
+    pub async fn reindex_knowledge_source(
+        &self,
+        source_id: &str,
+        on_progress: &(dyn Fn(&IngestionProgress) + Sync),
+    ) -> Result<usize, SecondBrainError> {
+        let source = self.get_knowledge_source(source_id).await?
+            .ok_or_else(|| SecondBrainError::NotFound(format!("Knowledge source not found: {}", source_id)))?;
+        let full_source_id = source.id.as_ref().map(|t| t.to_string())
+            .unwrap_or_else(|| source_id.to_string());
+
+        self.clear_source_chunks(&full_source_id).await?;
+        let count = self.embed_missing_chunks(&full_source_id, &source.title, &source.raw_content, on_progress).await?;
+        self.extract_source_entities(&full_source_id, &source.raw_content).await;
+
+        println!("[KB Ingest] Reindexed {} with {} chunks", full_source_id, count);
+        Ok(count)
+    }
+
+    /// Store freshly fetched content for a source
+    /// Changed content replaces the old chunks and is re-embedded; unchanged content only
+    /// records the check.
+    pub async fn refresh_knowledge_source(
+        &self,
+        source_id: &str,
+        title: &str,
+        content: &str,
+        on_progress: &(dyn Fn(&IngestionProgress) + Sync),
+    ) -> Result<SourceRefresh, SecondBrainError> {
+        let source = self.get_knowledge_source(source_id).await?
+            .ok_or_else(|| SecondBrainError::NotFound(format!("Knowledge source not found: {}", source_id)))?;
+        let full_source_id = source.id.as_ref().map(|t| t.to_string())
+            .unwrap_or_else(|| source_id.to_string());
+
+        if source.raw_content == content {
+            self.mark_source_refreshed(&full_source_id).await?;
+            let chunk_count = self.count_source_chunks(&full_source_id).await?;
+            println!("[KB Ingest] {} unchanged since last refresh", full_source_id);
+            return Ok(SourceRefresh { source_id: full_source_id, changed: false, chunk_count });
+        }
+
+        self.clear_source_chunks(&full_source_id).await?;
+        let chunk_count = self.embed_missing_chunks(&full_source_id, title, content, on_progress).await?;
+
+        // Only now that the chunks match the new content; if the rebuild failed, the old
+        // raw_content makes the next refresh see the change and try again
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        self.db
+            .query("UPDATE type::thing('knowledge_source', $id) SET title = $title, raw_content = $content, last_updated = $now, last_refreshed = $now")
+            .bind(("id", normalize_record_id("knowledge_source", &full_source_id)))
+            .bind(("title", title.to_string()))
+            .bind(("content", content.to_string()))
+            .bind(("now", now))
             .await
-            .map_err(|e| format!("Failed to create knowledge source: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to update knowledge source: {}", e)))?;
+        self.extract_source_entities(&full_source_id, content).await;
 
-        let source_id = match created {
-            Some(s) => s.id.map(|t| t.to_string()).unwrap_or_default(),
-            None => return Err("Failed to create knowledge source".to_string()),
-        };
+        println!("[KB Ingest] Refreshed {}: content changed, {} chunks", full_source_id, chunk_count);
+        Ok(SourceRefresh { source_id: full_source_id, changed: true, chunk_count })
+    }
+
+    /// Record a refresh check without touching the content (also after a failed fetch, so
+    /// auto-refresh waits a full interval before trying again)
+    pub async fn mark_source_refreshed(&self, source_id: &str) -> Result<(), SecondBrainError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        self.db
+            .query("UPDATE type::thing('knowledge_source', $id) SET last_refreshed = $now")
+            .bind(("id", normalize_record_id("knowledge_source", source_id)))
+            .bind(("now", now))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to record refresh: {}", e)))?;
+        Ok(())
+    }
 
-        // Chunk the content
-        let chunker = DocumentChunker::new();
-        let chunks = chunker.chunk_markdown(content);
+    /// Re-crawl a web source every `hours` hours; None turns auto-refresh off
+    pub async fn set_source_refresh_interval(&self, source_id: &str, hours: Option<u32>) -> Result<(), SecondBrainError> {
+        let source = self.get_knowledge_source(source_id).await?
+            .ok_or_else(|| SecondBrainError::NotFound(format!("Knowledge source not found: {}", source_id)))?;
+        let hours = hours.filter(|&h| h > 0);
+        if hours.is_some() && !source.is_refreshable() {
+            return Err(SecondBrainError::InvalidInput(format!("{} is not a web page and can't be refreshed", source.url)));
+        }
+        self.db
+            .query("UPDATE type::thing('knowledge_source', $id) SET refresh_interval_hours = $hours")
+            .bind(("id", normalize_record_id("knowledge_source", source_id)))
+            .bind(("hours", hours))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to set refresh interval: {}", e)))?;
+        Ok(())
+    }
 
-        println!("Chunking content: {} chars -> {} chunks", content.len(), chunks.len());
+    /// Sources with auto-refresh on whose interval has passed
+    pub async fn sources_due_for_refresh(&self) -> Result<Vec<KnowledgeSource>, SecondBrainError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let sources: Vec<KnowledgeSource> = self.db
+            .query("SELECT * FROM knowledge_source WHERE refresh_interval_hours > 0")
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query refreshable sources: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract refreshable sources: {}", e)))?;
+        Ok(sources.into_iter().filter(|s| s.refresh_due(now)).collect())
+    }
 
-        // Create chunks with embeddings
-        let mut chunk_count = 0;
-        for chunk in chunks {
-            let embedding = self.embedding_engine.embed(&chunk.text)?;
+    /// Count the chunks stored for a source without loading them
+    async fn count_source_chunks(&self, full_source_id: &str) -> Result<usize, SecondBrainError> {
+        let id_part = full_source_id.strip_prefix("knowledge_source:").unwrap_or(full_source_id).to_string();
 
-            let kb_chunk = KnowledgeChunk {
-                id: None,
-                source_id: source_id.clone(),
-                text: chunk.text,
-                chunk_index: chunk.chunk_index as i32,
-                embedding,
-            };
+        let counts: Vec<serde_json::Value> = self.db
+            .query("SELECT count() AS count FROM knowledge_chunk WHERE source_id = $full_id OR source_id = $short_id GROUP ALL")
+            .bind(("full_id", full_source_id.to_string()))
+            .bind(("short_id", id_part))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to count chunks: {}", e)))?
+            .take(0)
+            .unwrap_or_default();
 
-            self.db
-                .create::<Option<KnowledgeChunk>>("knowledge_chunk")
-                .content(kb_chunk)
-                .await
-                .map_err(|e| format!("Failed to create chunk: {}", e))?;
+        Ok(counts
+            .first()
+            .and_then(|v| v.get("count").and_then(|c| c.as_u64()))
+            .unwrap_or(0) as usize)
+    }
+
+    /// Delete a source's chunks and the relations extracted from it
+    async fn clear_source_chunks(&self, full_source_id: &str) -> Result<(), SecondBrainError> {
+        let id_part = full_source_id.strip_prefix("knowledge_source:").unwrap_or(full_source_id).to_string();
+
+        self.db
+            .query("DELETE FROM knowledge_chunk WHERE source_id = $full_id OR source_id = $short_id; DELETE FROM entity_relation WHERE knowledge_source_id = $full_id OR knowledge_source_id = $short_id;")
+            .bind(("full_id", full_source_id.to_string()))
+            .bind(("short_id", id_part))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to clear chunks: {}", e)))?;
+        Ok(())
+    }
+
+    /// Embed and store the chunks of `content` not yet stored for the source, in batches
+    /// Returns how many chunks were embedded by this call.
+    async fn embed_missing_chunks(
+        &self,
+        full_source_id: &str,
+        title: &str,
+        content: &str,
+        on_progress: &(dyn Fn(&IngestionProgress) + Sync),
+    ) -> Result<usize, SecondBrainError> {
+        let id_part = full_source_id.strip_prefix("knowledge_source:").unwrap_or(full_source_id).to_string();
+
+        let stored: Vec<i64> = self.db
+            .query("SELECT VALUE chunk_index FROM knowledge_chunk WHERE source_id = $full_id OR source_id = $short_id")
+            .bind(("full_id", full_source_id.to_string()))
+            .bind(("short_id", id_part))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query stored chunks: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract stored chunks: {}", e)))?;
+        let stored: std::collections::HashSet<i64> = stored.into_iter().collect();
+
+        let engine = self.embedder();
+        let config = ChunkerConfig { strategy: *self.chunk_strategy.read(), ..Default::default() };
+        let chunks = DocumentChunker::with_config(config)
+            .with_embedder(engine.as_ref())
+            .chunk_markdown(content);
+        let total_chunks = chunks.len();
+        let missing: Vec<_> = chunks.into_iter()
+            .filter(|c| !stored.contains(&(c.chunk_index as i64)))
+            .collect();
+        let resumed_chunks = total_chunks - missing.len();
 
-            chunk_count += 1;
+        println!("[KB Ingest] {}: {} chars -> {} chunks ({} already stored)",
+            title, content.len(), total_chunks, resumed_chunks);
+
+        let mut progress = IngestionProgress {
+            source_id: full_source_id.to_string(),
+            title: title.to_string(),
+            embedded_chunks: resumed_chunks,
+            total_chunks,
+            resumed_chunks,
+        };
+        on_progress(&progress);
+
+        let mut embedded = 0;
+        for batch in missing.chunks(INGEST_BATCH_SIZE) {
+            let texts: Vec<&str> = batch.iter().map(|c| c.text.as_str()).collect();
+            // If the model fails the chunks are stored unembedded and backfilled later
+            let embeddings = self.embedder().embed_batch(&texts).unwrap_or_else(|e| {
+                eprintln!("[KB Ingest] Embedding failed, deferring {} chunk(s) of {}: {}", batch.len(), title, e);
+                vec![Vec::new(); batch.len()]
+            });
+
+            let dimension = self.embedder().dimension();
+            for (chunk, embedding) in batch.iter().zip(embeddings) {
+                // Skipped chunks stay missing and are retried on the next ingest of this source
+                if !embedding.is_empty() {
+                    if let Err(e) = check_embedding(&embedding, dimension) {
+                        eprintln!("[KB Ingest] Skipping chunk {} of {}: {}", chunk.chunk_index, title, e);
+                        continue;
+                    }
+                }
+                let needs_embedding = embedding.is_empty();
+                let kb_chunk = KnowledgeChunk {
+                    id: None,
+                    source_id: full_source_id.to_string(),
+                    text: chunk.text.clone(),
+                    chunk_index: chunk.chunk_index as i32,
+                    embedding,
+                    needs_embedding,
+                };
+
+                self.db
+                    .create::<Option<KnowledgeChunk>>("knowledge_chunk")
+                    .content(kb_chunk)
+                    .await
+                    .map_err(|e| SecondBrainError::Db(format!("Failed to create chunk: {}", e)))?;
+                embedded += 1;
+            }
+
+            progress.embedded_chunks = resumed_chunks + embedded;
+            on_progress(&progress);
         }
 
-        println!("Added knowledge source: {} (id={}) with {} chunks", title, source_id, chunk_count);
+        println!("Added knowledge source: {} (id={}) with {} chunks", title, full_source_id, total_chunks);
+        Ok(embedded)
+    }
 
-        // Extract entities and relationships from the content for Graph-RAG
+    /// Extract entities and relationships from a source's content for Graph-RAG
+    async fn extract_source_entities(&self, source_id: &str, content: &str) {
         // Process in chunks to avoid overwhelming the model with huge texts
         let text_chunks: Vec<&str> = content.split("\n\n").filter(|s| s.len() > 50).take(20).collect();
         let mut total_entities = 0;
@@ -979,8 +4682,8 @@ impl KnowledgeBase {
             match self.entity_engine.extract_with_relations(text_chunk) {
                 Ok((entities, relationships)) => {
                     // Store entities (without meeting_id since this is a knowledge source)
-                    self.process_entities_for_source(&source_id, &entities).await.ok();
-                    self.process_relationships_for_source(&source_id, &relationships).await.ok();
+                    self.process_entities_for_source(source_id, &entities).await.ok();
+                    self.process_relationships_for_source(source_id, &relationships).await.ok();
                     total_entities += entities.len();
                     total_relationships += relationships.len();
                 }
@@ -991,29 +4694,28 @@ impl KnowledgeBase {
         }
 
         println!("Extracted {} entities and {} relationships from knowledge source", total_entities, total_relationships);
-        Ok(source_id)
     }
 
     /// Get all knowledge sources, optionally filtered by tags
     pub async fn get_knowledge_sources(
         &self,
         tags: Option<Vec<String>>,
-    ) -> Result<Vec<KnowledgeSource>, String> {
+    ) -> Result<Vec<KnowledgeSource>, SecondBrainError> {
         let sources: Vec<KnowledgeSource> = if let Some(tag_list) = tags {
             self.db
                 .query("SELECT * FROM knowledge_source WHERE tags CONTAINSANY $tags ORDER BY last_updated DESC")
                 .bind(("tags", tag_list))
                 .await
-                .map_err(|e| format!("Query failed: {}", e))?
+                .map_err(|e| SecondBrainError::Db(format!("Query failed: {}", e)))?
                 .take(0)
-                .map_err(|e| format!("Failed to extract sources: {}", e))?
+                .map_err(|e| SecondBrainError::Db(format!("Failed to extract sources: {}", e)))?
         } else {
             self.db
                 .query("SELECT * FROM knowledge_source ORDER BY last_updated DESC")
                 .await
-                .map_err(|e| format!("Query failed: {}", e))?
+                .map_err(|e| SecondBrainError::Db(format!("Query failed: {}", e)))?
                 .take(0)
-                .map_err(|e| format!("Failed to extract sources: {}", e))?
+                .map_err(|e| SecondBrainError::Db(format!("Failed to extract sources: {}", e)))?
         };
 
         Ok(sources)
@@ -1021,7 +4723,7 @@ impl KnowledgeBase {
 
     /// Get a single knowledge source by ID
     /// Accepts either full Thing string (knowledge_source:id) or just the ID part
-    pub async fn get_knowledge_source(&self, source_id: &str) -> Result<Option<KnowledgeSource>, String> {
+    pub async fn get_knowledge_source(&self, source_id: &str) -> Result<Option<KnowledgeSource>, SecondBrainError> {
         // Extract just the ID part if full Thing string is passed
         let id_part = if source_id.starts_with("knowledge_source:") {
             source_id.strip_prefix("knowledge_source:").unwrap_or(source_id)
@@ -1033,7 +4735,7 @@ impl KnowledgeBase {
         let source: Option<KnowledgeSource> = self.db
             .select(("knowledge_source", id_part))
             .await
-            .map_err(|e| format!("Failed to get source: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to get source: {}", e)))?;
 
         // If select didn't find it, try a query with the full source_id
         if source.is_none() {
@@ -1043,9 +4745,9 @@ impl KnowledgeBase {
                 .query("SELECT * FROM knowledge_source WHERE id = $id")
                 .bind(("id", source_id_owned))
                 .await
-                .map_err(|e| format!("Query failed: {}", e))?
+                .map_err(|e| SecondBrainError::Db(format!("Query failed: {}", e)))?
                 .take(0)
-                .map_err(|e| format!("Failed to extract source: {}", e))?;
+                .map_err(|e| SecondBrainError::Db(format!("Failed to extract source: {}", e)))?;
 
             if let Some(s) = query_result.into_iter().next() {
                 return Ok(Some(s));
@@ -1056,7 +4758,7 @@ impl KnowledgeBase {
     }
 
     /// Delete a knowledge source and its chunks
-    pub async fn delete_knowledge_source(&self, source_id: &str) -> Result<(), String> {
+    pub async fn delete_knowledge_source(&self, source_id: &str) -> Result<(), SecondBrainError> {
         // Chunks store source_id as the full Thing string (knowledge_source:xyz)
         // But frontend may pass just the ID part (xyz)
         // We need to try both formats for deletion
@@ -1081,7 +4783,7 @@ impl KnowledgeBase {
             .bind(("full_id", full_source_id.clone()))
             .bind(("short_id", id_part.clone()))
             .await
-            .map_err(|e| format!("Failed to delete chunks: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete chunks: {}", e)))?;
 
         println!("[KB Delete] Chunk delete result: {:?}", delete_result.num_statements());
 
@@ -1091,13 +4793,13 @@ impl KnowledgeBase {
             .bind(("full_id", full_source_id.clone()))
             .bind(("short_id", id_part.clone()))
             .await
-            .map_err(|e| format!("Failed to delete meeting links: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete meeting links: {}", e)))?;
 
         // Delete the source itself
         self.db
             .delete::<Option<KnowledgeSource>>(("knowledge_source", id_part.as_str()))
             .await
-            .map_err(|e| format!("Failed to delete source: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete source: {}", e)))?;
 
         println!("[KB Delete] Source deleted successfully");
         Ok(())
@@ -1108,7 +4810,7 @@ impl KnowledgeBase {
         &self,
         source_id: &str,
         tags: Vec<String>,
-    ) -> Result<(), String> {
+    ) -> Result<(), SecondBrainError> {
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -1122,54 +4824,173 @@ impl KnowledgeBase {
             .bind(("tags", tags))
             .bind(("now", now))
             .await
-            .map_err(|e| format!("Failed to update tags: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to update tags: {}", e)))?;
 
         Ok(())
     }
 
-    /// Search knowledge chunks using vector similarity
-    pub async fn search_knowledge(
-        &self,
-        query: &str,
-        limit: usize,
-        tags: Option<Vec<String>>,
-    ) -> Result<Vec<KnowledgeSearchResult>, String> {
-        let query_embedding = self.embedding_engine.embed(query)?;
+    /// Run ad-hoc SurrealQL for debugging, one JSON result per statement
+    /// Each statement's result is capped at `RAW_QUERY_ROW_LIMIT` rows. Write statements are
+    /// refused unless `allow_writes` is set; see `check_raw_query`.
+    pub async fn run_raw_query(&self, surql: &str, allow_writes: bool) -> Result<serde_json::Value, SecondBrainError> {
+        check_raw_query(surql, allow_writes)?;
 
-        // Search with optional tag filtering using ChunkWithSimilarity to capture similarity
-        let chunks_with_sim: Vec<ChunkWithSimilarity> = if let Some(tag_list) = tags {
-            self.db
-                .query(r#"
-                    SELECT *, vector::similarity::cosine(embedding, $embedding) AS similarity
-                    FROM knowledge_chunk
-                    WHERE source_id IN (
-                        SELECT VALUE id FROM knowledge_source WHERE tags CONTAINSANY $tags
-                    )
-                    ORDER BY similarity DESC
-                    LIMIT $limit
-                "#)
-                .bind(("embedding", query_embedding.clone()))
-                .bind(("tags", tag_list))
-                .bind(("limit", limit))
-                .await
-                .map_err(|e| format!("Search failed: {}", e))?
-                .take(0)
-                .map_err(|e| format!("Failed to extract chunks: {}", e))?
-        } else {
-            self.db
-                .query(r#"
-                    SELECT *, vector::similarity::cosine(embedding, $embedding) AS similarity
-                    FROM knowledge_chunk
-                    ORDER BY similarity DESC
-                    LIMIT $limit
-                "#)
-                .bind(("embedding", query_embedding.clone()))
-                .bind(("limit", limit))
-                .await
-                .map_err(|e| format!("Search failed: {}", e))?
-                .take(0)
-                .map_err(|e| format!("Failed to extract chunks: {}", e))?
-        };
+        let mut response = self.db
+            .query(surql)
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Query failed: {}", e)))?;
+
+        let mut results = Vec::new();
+        for index in 0..response.num_statements() {
+            let value: surrealdb::Value = response.take(index)
+                .map_err(|e| SecondBrainError::Db(format!("Statement {} failed: {}", index + 1, e)))?;
+            let value = match value.into_inner().into_json() {
+                serde_json::Value::Array(mut rows) if rows.len() > RAW_QUERY_ROW_LIMIT => {
+                    rows.truncate(RAW_QUERY_ROW_LIMIT);
+                    serde_json::Value::Array(rows)
+                }
+                other => other,
+            };
+            results.push(value);
+        }
+
+        println!("[KB] Ran raw query ({} statements, writes {})", results.len(),
+            if allow_writes { "allowed" } else { "blocked" });
+        Ok(serde_json::Value::Array(results))
+    }
+
+    /// Source and chunk counts for every tag used by knowledge sources
+    pub async fn get_tag_stats(&self) -> Result<Vec<TagStats>, SecondBrainError> {
+        #[derive(Deserialize)]
+        struct SourceRow {
+            id: Thing,
+            tags: Vec<String>,
+        }
+        #[derive(Deserialize)]
+        struct ChunkCount {
+            source_id: String,
+            count: usize,
+        }
+
+        let mut response = self.db
+            .query("SELECT id, tags FROM knowledge_source; SELECT source_id, count() AS count FROM knowledge_chunk GROUP BY source_id;")
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query tag stats: {}", e)))?;
+        let sources: Vec<SourceRow> = response.take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract sources: {}", e)))?;
+        let chunk_counts: Vec<ChunkCount> = response.take(1)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract chunk counts: {}", e)))?;
+
+        // Chunks store source_id as the full Thing string (knowledge_source:xyz)
+        let chunks_by_source: std::collections::HashMap<String, usize> = chunk_counts.into_iter()
+            .map(|c| (c.source_id, c.count))
+            .collect();
+        let sources: Vec<(Vec<String>, usize)> = sources.into_iter()
+            .map(|s| {
+                let chunks = chunks_by_source.get(&s.id.to_string()).copied().unwrap_or(0);
+                (s.tags, chunks)
+            })
+            .collect();
+
+        Ok(tag_stats(&sources))
+    }
+
+    /// Rename a tag on every knowledge source; returns the number of sources changed
+    pub async fn rename_tag(&self, old: &str, new: &str) -> Result<usize, SecondBrainError> {
+        let new = new.trim();
+        if new.is_empty() {
+            return Err(SecondBrainError::InvalidInput("New tag name cannot be empty".to_string()));
+        }
+        self.retag_sources(old, Some(new)).await
+    }
+
+    /// Remove a tag from every knowledge source; returns the number of sources changed
+    pub async fn delete_tag(&self, tag: &str) -> Result<usize, SecondBrainError> {
+        self.retag_sources(tag, None).await
+    }
+
+    async fn retag_sources(&self, old: &str, new: Option<&str>) -> Result<usize, SecondBrainError> {
+        #[derive(Deserialize)]
+        struct SourceRow {
+            id: Thing,
+            tags: Vec<String>,
+        }
+
+        let old = old.trim().to_string();
+        if new == Some(old.as_str()) {
+            return Ok(0);
+        }
+
+        let sources: Vec<SourceRow> = self.db
+            .query("SELECT id, tags FROM knowledge_source WHERE tags CONTAINS $tag")
+            .bind(("tag", old.clone()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to find tagged sources: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract tagged sources: {}", e)))?;
+
+        // Updating through the record keeps idx_source_tags in step
+        for source in &sources {
+            self.db
+                .query("UPDATE $id SET tags = $tags")
+                .bind(("id", source.id.clone()))
+                .bind(("tags", retag(&source.tags, &old, new)))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to update tags: {}", e)))?;
+        }
+
+        match new {
+            Some(new) => println!("[KB] Renamed tag '{}' to '{}' on {} sources", old, new, sources.len()),
+            None => println!("[KB] Removed tag '{}' from {} sources", old, sources.len()),
+        }
+        Ok(sources.len())
+    }
+
+    /// Search knowledge chunks using vector similarity
+    pub async fn search_knowledge(
+        &self,
+        query: &str,
+        limit: usize,
+        tags: Option<Vec<String>>,
+    ) -> Result<Vec<KnowledgeSearchResult>, SecondBrainError> {
+        self.ensure_embeddings_compatible()?;
+        let query_embedding = self.embedder().embed(query)?;
+
+        // Search with optional tag filtering using ChunkWithSimilarity to capture similarity
+        let chunks_with_sim: Vec<ChunkWithSimilarity> = if let Some(tag_list) = tags {
+            self.db
+                .query(r#"
+                    SELECT *, vector::similarity::cosine(embedding, $embedding) AS similarity
+                    FROM knowledge_chunk
+                    WHERE needs_embedding != true AND source_id IN (
+                        SELECT VALUE id FROM knowledge_source WHERE tags CONTAINSANY $tags
+                    )
+                    ORDER BY similarity DESC
+                    LIMIT $limit
+                "#)
+                .bind(("embedding", query_embedding.clone()))
+                .bind(("tags", tag_list))
+                .bind(("limit", limit))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Search failed: {}", e)))?
+                .take(0)
+                .map_err(|e| SecondBrainError::Db(format!("Failed to extract chunks: {}", e)))?
+        } else {
+            self.db
+                .query(r#"
+                    SELECT *, vector::similarity::cosine(embedding, $embedding) AS similarity
+                    FROM knowledge_chunk
+                    WHERE needs_embedding != true
+                    ORDER BY similarity DESC
+                    LIMIT $limit
+                "#)
+                .bind(("embedding", query_embedding.clone()))
+                .bind(("limit", limit))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Search failed: {}", e)))?
+                .take(0)
+                .map_err(|e| SecondBrainError::Db(format!("Failed to extract chunks: {}", e)))?
+        };
 
         println!("Found {} chunks with similarity", chunks_with_sim.len());
 
@@ -1192,6 +5013,7 @@ impl KnowledgeBase {
                 text: chunk_sim.text,
                 chunk_index: chunk_sim.chunk_index,
                 embedding: chunk_sim.embedding,
+                needs_embedding: false,
             };
 
             // Try to get source info, but still include the chunk even if source lookup fails
@@ -1216,20 +5038,175 @@ impl KnowledgeBase {
             });
         }
 
+        self.touch_sources(results.iter().map(|r| r.chunk.source_id.as_str())).await;
+
         println!("Returning {} search results", results.len());
         Ok(results)
     }
 
+    /// The `per_source` chunks of each pinned source most similar to `query`
+    pub async fn search_pinned_knowledge(
+        &self,
+        query: &str,
+        per_source: usize,
+    ) -> Result<Vec<KnowledgeSearchResult>, SecondBrainError> {
+        if per_source == 0 {
+            return Ok(Vec::new());
+        }
+        let pinned: Vec<KnowledgeSource> = self.db
+            .query("SELECT * FROM knowledge_source WHERE pinned = true ORDER BY created_at ASC")
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query pinned sources: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract pinned sources: {}", e)))?;
+        if pinned.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.ensure_embeddings_compatible()?;
+        let query_embedding = self.embedder().embed(query)?;
+
+        let mut results = Vec::new();
+        for source in pinned {
+            let Some(full_id) = source.id.as_ref().map(|t| t.to_string()) else {
+                continue;
+            };
+            let id_part = full_id.strip_prefix("knowledge_source:").unwrap_or(&full_id).to_string();
+            let chunks: Vec<ChunkWithSimilarity> = self.db
+                .query(r#"
+                    SELECT *, vector::similarity::cosine(embedding, $embedding) AS similarity
+                    FROM knowledge_chunk
+                    WHERE (source_id = $full_id OR source_id = $short_id) AND needs_embedding != true
+                    ORDER BY similarity DESC
+                    LIMIT $limit
+                "#)
+                .bind(("embedding", query_embedding.clone()))
+                .bind(("full_id", full_id.clone()))
+                .bind(("short_id", id_part))
+                .bind(("limit", per_source))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Pinned search failed: {}", e)))?
+                .take(0)
+                .map_err(|e| SecondBrainError::Db(format!("Failed to extract pinned chunks: {}", e)))?;
+
+            results.extend(chunks.into_iter().map(|c| KnowledgeSearchResult {
+                chunk: KnowledgeChunk {
+                    id: c.id,
+                    source_id: c.source_id,
+                    text: c.text,
+                    chunk_index: c.chunk_index,
+                    embedding: c.embedding,
+                    needs_embedding: false,
+                },
+                source_title: source.title.clone(),
+                source_url: source.url.clone(),
+                similarity: c.similarity,
+            }));
+        }
+
+        self.touch_sources(results.iter().map(|r| r.chunk.source_id.as_str())).await;
+        Ok(results)
+    }
+
+    /// Flip a knowledge source's pinned flag, returning the new value
+    pub async fn toggle_source_pinned(&self, source_id: &str) -> Result<bool, SecondBrainError> {
+        let id_part = normalize_record_id("knowledge_source", source_id);
+
+        let updated: Vec<KnowledgeSource> = self.db
+            .query("UPDATE type::thing('knowledge_source', $id) SET pinned = !(pinned ?? false)")
+            .bind(("id", id_part))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to toggle pinned: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract knowledge source: {}", e)))?;
+
+        updated
+            .first()
+            .map(|s| s.pinned)
+            .ok_or_else(|| SecondBrainError::NotFound("Knowledge source not found".to_string()))
+    }
+
+    /// Record that these sources just showed up in a search (feeds `get_stale_sources`)
+    /// Best effort: a failed update never fails the search.
+    async fn touch_sources<'a>(&self, source_ids: impl Iterator<Item = &'a str>) {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let ids: std::collections::HashSet<String> = source_ids
+            .map(|id| normalize_record_id("knowledge_source", id))
+            .collect();
+        for id in ids {
+            if let Err(e) = self.db
+                .query("UPDATE type::thing('knowledge_source', $id) SET last_accessed = $now")
+                .bind(("id", id.clone()))
+                .bind(("now", now))
+                .await
+            {
+                eprintln!("[KB] Failed to record access for source {}: {}", id, e);
+            }
+        }
+    }
+
+    /// Sources not linked to any meeting and not returned by a search in the last `days`
+    /// Sources never searched count from when they were added, so new ones aren't stale.
+    pub async fn get_stale_sources(&self, days: u64) -> Result<Vec<KnowledgeSource>, SecondBrainError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        let cutoff = now.saturating_sub(days.saturating_mul(24 * 60 * 60 * 1000));
+
+        let mut response = self.db
+            .query("SELECT * FROM knowledge_source WHERE (last_accessed ?? created_at) < $cutoff ORDER BY created_at ASC")
+            .query("SELECT VALUE source_id FROM meeting_knowledge")
+            .bind(("cutoff", cutoff))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query stale sources: {}", e)))?;
+        let candidates: Vec<KnowledgeSource> = response.take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract stale sources: {}", e)))?;
+        let linked: std::collections::HashSet<String> = response.take::<Vec<String>>(1)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meeting links: {}", e)))?
+            .into_iter()
+            .map(|id| normalize_record_id("knowledge_source", &id))
+            .collect();
+
+        let stale: Vec<KnowledgeSource> = candidates.into_iter()
+            .filter(|source| {
+                let id = source.id.as_ref()
+                    .map(|t| normalize_record_id("knowledge_source", &t.to_string()))
+                    .unwrap_or_default();
+                !linked.contains(&id)
+            })
+            .collect();
+        println!("[KB] {} sources unused for {} days", stale.len(), days);
+        Ok(stale)
+    }
+
+    /// Delete several knowledge sources (and their chunks and meeting links)
+    /// Keeps going past failures so one bad id doesn't block the rest.
+    pub async fn bulk_delete_sources(&self, source_ids: &[String]) -> BulkDeleteResult {
+        let mut result = BulkDeleteResult::default();
+        for id in source_ids {
+            match self.delete_knowledge_source(id).await {
+                Ok(()) => result.deleted += 1,
+                Err(e) => result.failed.push(format!("{}: {}", id, e)),
+            }
+        }
+        println!("[KB] Bulk deleted {} sources ({} failed)", result.deleted, result.failed.len());
+        result
+    }
+
     /// Link a knowledge source to a meeting
     pub async fn link_knowledge_to_meeting(
         &self,
         meeting_id: &str,
         source_id: &str,
         assigned_by: &str,
-    ) -> Result<(), String> {
+    ) -> Result<(), SecondBrainError> {
         let link = MeetingKnowledge {
             id: None,
-            meeting_id: meeting_id.to_string(),
+            meeting_id: normalize_record_id("meeting", meeting_id),
             source_id: source_id.to_string(),
             relevance_score: 1.0,
             assigned_by: assigned_by.to_string(),
@@ -1239,23 +5216,91 @@ impl KnowledgeBase {
             .create::<Option<MeetingKnowledge>>("meeting_knowledge")
             .content(link)
             .await
-            .map_err(|e| format!("Failed to link knowledge: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to link knowledge: {}", e)))?;
 
         Ok(())
     }
 
+    /// Link knowledge sources whose chunks resemble the meeting transcript
+    /// Chunk similarities are aggregated per source (best matching chunk wins);
+    /// sources that are already linked, manually or automatically, are skipped.
+    pub async fn auto_link_knowledge(
+        &self,
+        meeting_id: &str,
+        threshold: f32,
+    ) -> Result<Vec<MeetingKnowledge>, SecondBrainError> {
+        let segments = self.get_meeting_segments(meeting_id).await?;
+        if segments.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let combined = segments
+            .iter()
+            .map(|s| s.text.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let query: String = combined.chars().take(AUTO_LINK_MAX_CHARS).collect();
+
+        let results = self.search_knowledge(&query, AUTO_LINK_CHUNK_LIMIT, None).await?;
+
+        let mut best_by_source: std::collections::HashMap<String, f32> = std::collections::HashMap::new();
+        for result in &results {
+            let entry = best_by_source.entry(result.chunk.source_id.clone()).or_insert(0.0);
+            if result.similarity > *entry {
+                *entry = result.similarity;
+            }
+        }
+
+        let meeting_id_owned = normalize_record_id("meeting", meeting_id);
+        let existing: Vec<MeetingKnowledge> = self.db
+            .query("SELECT * FROM meeting_knowledge WHERE meeting_id = $meeting_id")
+            .bind(("meeting_id", meeting_id_owned))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Query failed: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract links: {}", e)))?;
+
+        let mut created = Vec::new();
+        for (source_id, score) in best_by_source {
+            if score < threshold || existing.iter().any(|l| l.source_id == source_id) {
+                continue;
+            }
+
+            let link = MeetingKnowledge {
+                id: None,
+                meeting_id: meeting_id_owned.clone(),
+                source_id,
+                relevance_score: score,
+                assigned_by: "auto".to_string(),
+            };
+
+            let saved: Option<MeetingKnowledge> = self.db
+                .create("meeting_knowledge")
+                .content(link)
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to link knowledge: {}", e)))?;
+
+            if let Some(saved) = saved {
+                created.push(saved);
+            }
+        }
+
+        println!("[KB] Auto-linked {} knowledge sources to {}", created.len(), meeting_id);
+        Ok(created)
+    }
+
     /// Get knowledge sources linked to a meeting
-    pub async fn get_meeting_knowledge(&self, meeting_id: &str) -> Result<Vec<KnowledgeSource>, String> {
-        let meeting_id_owned = meeting_id.to_string();
+    pub async fn get_meeting_knowledge(&self, meeting_id: &str) -> Result<Vec<KnowledgeSource>, SecondBrainError> {
+        let meeting_id_owned = normalize_record_id("meeting", meeting_id);
 
         // Get linked source IDs
         let links: Vec<MeetingKnowledge> = self.db
             .query("SELECT * FROM meeting_knowledge WHERE meeting_id = $meeting_id")
             .bind(("meeting_id", meeting_id_owned))
             .await
-            .map_err(|e| format!("Query failed: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Query failed: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract links: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract links: {}", e)))?;
 
         // Get the actual sources
         let mut sources = Vec::new();
@@ -1269,16 +5314,16 @@ impl KnowledgeBase {
     }
 
     /// Get chunk count for a source
-    pub async fn get_source_chunk_count(&self, source_id: &str) -> Result<usize, String> {
+    pub async fn get_source_chunk_count(&self, source_id: &str) -> Result<usize, SecondBrainError> {
         let source_id_owned = source_id.to_string();
 
         let chunks: Vec<KnowledgeChunk> = self.db
             .query("SELECT * FROM knowledge_chunk WHERE source_id = $source_id")
             .bind(("source_id", source_id_owned))
             .await
-            .map_err(|e| format!("Query failed: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Query failed: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to count chunks: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to count chunks: {}", e)))?;
 
         Ok(chunks.len())
     }
@@ -1289,8 +5334,8 @@ impl KnowledgeBase {
     pub async fn graph_rag_query(
         &self,
         query: &str,
-        limit: usize,
-    ) -> Result<GraphRAGContext, String> {
+        retrieval: &RetrievalConfig,
+    ) -> Result<GraphRAGContext, SecondBrainError> {
         let start = std::time::Instant::now();
 
         // 1. Extract entities from the query (sync, fast)
@@ -1310,13 +5355,15 @@ impl KnowledgeBase {
             actions_result,
             decisions_result,
             chunks_result,
+            pinned_result,
         ) = tokio::join!(
-            self.get_meetings_for_entities(&query_entities, &temporal_context),
-            self.get_people_context(&query_entities),
-            self.get_topic_context(&query_entities),
+            self.get_meetings_for_entities(&query_entities, &temporal_context, retrieval),
+            self.get_people_context(&query_entities, retrieval.max_people, retrieval.name_max_distance),
+            self.get_topic_context(&query_entities, retrieval.max_topics, retrieval.name_max_distance),
             self.get_open_actions(),
             self.get_recent_decisions(10),
-            self.search_knowledge(query, limit, None),
+            self.search_knowledge(query, retrieval.max_chunks, None),
+            self.search_pinned_knowledge(query, retrieval.pinned_chunks_per_source),
         );
 
         // Unwrap results (use empty defaults on error to avoid blocking)
@@ -1325,94 +5372,51 @@ impl KnowledgeBase {
         let related_topics = topics_result.unwrap_or_default();
         let open_actions = actions_result.unwrap_or_default();
         let recent_decisions = decisions_result.unwrap_or_default();
-        let similar_chunks = chunks_result.unwrap_or_default();
-
-        println!("[Graph-RAG] Parallel queries completed in {:?}: {} meetings, {} people, {} topics, {} chunks",
-            start.elapsed(),
-            related_meetings.len(),
-            related_people.len(),
-            related_topics.len(),
-            similar_chunks.len());
+        let pinned_chunks = pinned_result.unwrap_or_else(|e| {
+            eprintln!("[Graph-RAG] Pinned sources unavailable: {}", e);
+            Vec::new()
+        });
+        let similar_chunks: Vec<KnowledgeSearchResult> = chunks_result.unwrap_or_default()
+            .into_iter()
+            .filter(|r| !pinned_chunks.iter().any(|p| p.chunk.id == r.chunk.id))
+            .collect();
 
-        Ok(GraphRAGContext {
+        let mut context = GraphRAGContext {
             query_entities,
             related_meetings,
             related_people,
             related_topics,
             open_actions,
             recent_decisions,
+            pinned_chunks,
             similar_chunks,
             temporal_context,
-        })
-    }
-
-    /// Parse temporal references from query (e.g., "3 weeks ago", "last month")
-    fn parse_temporal_context(&self, query: &str) -> Option<TemporalContext> {
-        let query_lower = query.to_lowercase();
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis() as u64;
-
-        let day_ms: u64 = 24 * 60 * 60 * 1000;
-        let week_ms: u64 = 7 * day_ms;
-
-        // Parse common temporal patterns
-        if let Some(caps) = regex::Regex::new(r"(\d+)\s*weeks?\s*ago")
-            .ok()
-            .and_then(|re| re.captures(&query_lower))
-        {
-            if let Some(weeks) = caps.get(1).and_then(|m| m.as_str().parse::<u64>().ok()) {
-                let start = now - (weeks * week_ms) - week_ms; // Start of that week
-                let end = now - (weeks * week_ms) + week_ms;   // End of that week
-                return Some(TemporalContext {
-                    time_reference: format!("{} weeks ago", weeks),
-                    start_timestamp: Some(start),
-                    end_timestamp: Some(end),
-                });
-            }
-        }
-
-        if let Some(caps) = regex::Regex::new(r"(\d+)\s*days?\s*ago")
-            .ok()
-            .and_then(|re| re.captures(&query_lower))
-        {
-            if let Some(days) = caps.get(1).and_then(|m| m.as_str().parse::<u64>().ok()) {
-                let start = now - (days * day_ms) - day_ms;
-                let end = now - (days * day_ms) + day_ms;
-                return Some(TemporalContext {
-                    time_reference: format!("{} days ago", days),
-                    start_timestamp: Some(start),
-                    end_timestamp: Some(end),
-                });
+        };
+        // Fail closed: without the hidden list nothing meeting-derived goes into the prompt
+        match self.hidden_meeting_ids().await {
+            Ok(hidden) => context.exclude_private(&hidden),
+            Err(e) => {
+                eprintln!("[Graph-RAG] {}; leaving meetings out of the context", e);
+                context.related_meetings.clear();
+                context.open_actions.clear();
+                context.recent_decisions.clear();
             }
         }
 
-        if query_lower.contains("last week") {
-            return Some(TemporalContext {
-                time_reference: "last week".to_string(),
-                start_timestamp: Some(now - (2 * week_ms)),
-                end_timestamp: Some(now - week_ms),
-            });
-        }
-
-        if query_lower.contains("last month") {
-            return Some(TemporalContext {
-                time_reference: "last month".to_string(),
-                start_timestamp: Some(now - (30 * day_ms)),
-                end_timestamp: Some(now),
-            });
-        }
+        println!("[Graph-RAG] Parallel queries completed in {:?}: {} meetings, {} people, {} topics, {} pinned + {} chunks",
+            start.elapsed(),
+            context.related_meetings.len(),
+            context.related_people.len(),
+            context.related_topics.len(),
+            context.pinned_chunks.len(),
+            context.similar_chunks.len());
 
-        if query_lower.contains("yesterday") {
-            return Some(TemporalContext {
-                time_reference: "yesterday".to_string(),
-                start_timestamp: Some(now - (2 * day_ms)),
-                end_timestamp: Some(now - day_ms),
-            });
-        }
+        Ok(context)
+    }
 
-        None
+    /// Parse temporal references from query (e.g., "3 weeks ago", "in Q2", "on 2024-03-15")
+    fn parse_temporal_context(&self, query: &str) -> Option<TemporalContext> {
+        parse_temporal_expression(query, &chrono::Local::now())
     }
 
     /// Get meetings related to extracted entities
@@ -1420,7 +5424,12 @@ impl KnowledgeBase {
         &self,
         entities: &[Entity],
         temporal: &Option<TemporalContext>,
-    ) -> Result<Vec<MeetingContext>, String> {
+        retrieval: &RetrievalConfig,
+    ) -> Result<Vec<MeetingContext>, SecondBrainError> {
+        if retrieval.max_meetings == 0 {
+            return Ok(Vec::new());
+        }
+
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -1443,37 +5452,40 @@ impl KnowledgeBase {
             .map(|e| e.text.clone())
             .collect();
 
-        // Query for meetings involving these entities
+        // Query for meetings involving these entities (never private or trashed ones)
         let base_query = if let Some(temp) = temporal {
             if let (Some(start), Some(end)) = (temp.start_timestamp, temp.end_timestamp) {
                 format!(
-                    "SELECT * FROM meeting WHERE start_time >= {} AND start_time <= {} ORDER BY start_time DESC LIMIT 20",
-                    start, end
+                    "SELECT * FROM meeting WHERE private != true AND deleted_at IS NONE AND start_time >= {} AND start_time <= {} ORDER BY start_time DESC LIMIT {}",
+                    start, end, retrieval.max_meetings
                 )
             } else {
-                "SELECT * FROM meeting ORDER BY start_time DESC LIMIT 20".to_string()
+                format!("SELECT * FROM meeting WHERE private != true AND deleted_at IS NONE ORDER BY start_time DESC LIMIT {}", retrieval.max_meetings)
             }
         } else {
-            "SELECT * FROM meeting ORDER BY start_time DESC LIMIT 20".to_string()
+            format!("SELECT * FROM meeting WHERE private != true AND deleted_at IS NONE ORDER BY start_time DESC LIMIT {}", retrieval.max_meetings)
         };
 
         let meetings: Vec<Meeting> = self.db
             .query(&base_query)
             .await
-            .map_err(|e| format!("Failed to query meetings: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query meetings: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract meetings: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meetings: {}", e)))?;
 
         for meeting in meetings {
-            let meeting_id = meeting.id.as_ref().map(|t| t.to_string()).unwrap_or_default();
+            let meeting_id = meeting.id.as_ref()
+                .map(|t| normalize_record_id("meeting", &t.to_string()))
+                .unwrap_or_default();
             let days_ago = (now as i64 - meeting.start_time as i64) / day_ms;
 
             // Get relevant segments from this meeting
             let segments: Vec<TranscriptSegment> = self.db
-                .query("SELECT * FROM segment WHERE meeting_id = $meeting_id LIMIT 5")
+                .query("SELECT * FROM segment WHERE meeting_id = $meeting_id LIMIT $limit")
                 .bind(("meeting_id", meeting_id.clone()))
+                .bind(("limit", retrieval.segments_per_meeting))
                 .await
-                .map_err(|e| format!("Failed to get segments: {}", e))?
+                .map_err(|e| SecondBrainError::Db(format!("Failed to get segments: {}", e)))?
                 .take(0)
                 .unwrap_or_default();
 
@@ -1488,7 +5500,18 @@ impl KnowledgeBase {
     }
 
     /// Get context about people mentioned in query
-    async fn get_people_context(&self, entities: &[Entity]) -> Result<Vec<PersonContext>, String> {
+    /// Names are looked up exactly by name or alias first; a miss is matched against the
+    /// `FUZZY_NAME_CANDIDATES` most recently seen people, tolerating up to `max_distance`
+    /// typos, so "Jon" finds "John". The context carries the stored name.
+    async fn get_people_context(&self, entities: &[Entity], limit: usize, max_distance: usize) -> Result<Vec<PersonContext>, SecondBrainError> {
+        #[derive(Clone, Deserialize)]
+        struct KnownPerson {
+            name: String,
+            #[serde(default)]
+            aliases: Vec<String>,
+            last_seen: u64,
+        }
+
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -1502,54 +5525,98 @@ impl KnowledgeBase {
             .iter()
             .filter(|e| e.label == "person")
             .map(|e| e.text.as_str())
+            .take(limit)
             .collect();
+        if person_names.is_empty() {
+            return Ok(people_contexts);
+        }
+
+        // Relations from private and trashed meetings stay out of the context
+        let hidden = self.hidden_meeting_filter().await?;
 
+        // Loaded on the first name the exact lookup misses
+        let mut candidates: Option<Vec<KnownPerson>> = None;
+        let mut matched = std::collections::HashSet::new();
         for name in person_names {
-            // Get person record
-            let people: Vec<Person> = self.db
-                .query("SELECT * FROM person WHERE name = $name")
+            let exact: Option<KnownPerson> = self.db
+                .query(PERSON_BY_NAME_QUERY)
                 .bind(("name", name.to_string()))
                 .await
-                .map_err(|e| format!("Failed to query person: {}", e))?
+                .map_err(|e| SecondBrainError::Db(format!("Failed to look up person: {}", e)))?
                 .take(0)
                 .unwrap_or_default();
+            let person = match exact {
+                Some(person) => person,
+                None => {
+                    if candidates.is_none() {
+                        let people: Vec<KnownPerson> = self.db
+                            .query(PERSON_CANDIDATES_QUERY)
+                            .bind(("limit", FUZZY_NAME_CANDIDATES))
+                            .await
+                            .map_err(|e| SecondBrainError::Db(format!("Failed to query people: {}", e)))?
+                            .take(0)
+                            .unwrap_or_default();
+                        candidates = Some(people);
+                    }
+                    let people = candidates.as_deref().unwrap_or_default();
+                    let forms: Vec<Vec<&str>> = people.iter()
+                        .map(|p| std::iter::once(p.name.as_str()).chain(p.aliases.iter().map(String::as_str)).collect())
+                        .collect();
+                    match best_name_match(name, &forms, max_distance) {
+                        Some(index) => people[index].clone(),
+                        None => continue,
+                    }
+                }
+            };
+            if !matched.insert(person.name.clone()) {
+                continue;
+            }
+            let last_seen_days_ago = (now as i64 - person.last_seen as i64) / day_ms;
 
-            if let Some(person) = people.into_iter().next() {
-                let last_seen_days_ago = (now as i64 - person.last_seen as i64) / day_ms;
-
-                // Get topics this person has discussed
-                let topics: Vec<serde_json::Value> = self.db
-                    .query(r#"
-                        SELECT target_entity FROM entity_relation
-                        WHERE source_entity = $name AND source_type = 'person'
-                        AND (target_type = 'topic' OR target_type = 'project')
-                        LIMIT 5
-                    "#)
-                    .bind(("name", name.to_string()))
-                    .await
-                    .map_err(|e| format!("Failed to query topics: {}", e))?
-                    .take(0)
-                    .unwrap_or_default();
+            // Get topics this person has discussed, under any of their names
+            let mut names: Vec<String> = std::iter::once(person.name.clone())
+                .chain(person.aliases.iter().cloned())
+                .collect();
+            names.push(name.to_string());
+            let topics: Vec<serde_json::Value> = self.db
+                .query(PERSON_TOPICS_QUERY)
+                .bind(("names", names))
+                .bind(("hidden", hidden.clone()))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to query topics: {}", e)))?
+                .take(0)
+                .unwrap_or_default();
 
-                let recent_topics: Vec<String> = topics
-                    .iter()
-                    .filter_map(|v| v.get("target_entity").and_then(|t| t.as_str()).map(|s| s.to_string()))
-                    .collect();
+            let recent_topics: Vec<String> = topics
+                .iter()
+                .filter_map(|v| v.get("target_entity").and_then(|t| t.as_str()).map(|s| s.to_string()))
+                .collect();
 
-                people_contexts.push(PersonContext {
-                    name: person.name,
-                    last_seen_days_ago,
-                    meeting_count: 0, // Would need a separate query
-                    recent_topics,
-                });
+            if person.name != name {
+                println!("[Graph-RAG] Matched person \"{}\" to \"{}\"", name, person.name);
             }
+            people_contexts.push(PersonContext {
+                name: person.name,
+                last_seen_days_ago,
+                meeting_count: 0, // Would need a separate query
+                recent_topics,
+            });
         }
 
         Ok(people_contexts)
     }
 
     /// Get context about topics mentioned in query
-    async fn get_topic_context(&self, entities: &[Entity]) -> Result<Vec<TopicContext>, String> {
+    /// Names are matched like people's (see `get_people_context`), against the most mentioned
+    /// topics on a miss; the context carries the stored name.
+    async fn get_topic_context(&self, entities: &[Entity], limit: usize, max_distance: usize) -> Result<Vec<TopicContext>, SecondBrainError> {
+        #[derive(Clone, Deserialize)]
+        struct KnownTopic {
+            name: String,
+            mention_count: u32,
+            last_mentioned: u64,
+        }
+
         let now = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap()
@@ -1563,48 +5630,76 @@ impl KnowledgeBase {
             .iter()
             .filter(|e| e.label == "topic" || e.label == "project" || e.label == "product")
             .map(|e| e.text.as_str())
+            .take(limit)
             .collect();
+        if topic_names.is_empty() {
+            return Ok(topic_contexts);
+        }
 
+        // Relations from private and trashed meetings stay out of the context
+        let hidden = self.hidden_meeting_filter().await?;
+
+        // Loaded on the first name the exact lookup misses
+        let mut candidates: Option<Vec<KnownTopic>> = None;
+        let mut matched = std::collections::HashSet::new();
         for name in topic_names {
-            // Get topic record
-            let topics: Vec<serde_json::Value> = self.db
-                .query("SELECT * FROM topic WHERE name = $name")
+            let exact: Option<KnownTopic> = self.db
+                .query(TOPIC_BY_NAME_QUERY)
                 .bind(("name", name.to_string()))
                 .await
-                .map_err(|e| format!("Failed to query topic: {}", e))?
+                .map_err(|e| SecondBrainError::Db(format!("Failed to look up topic: {}", e)))?
                 .take(0)
                 .unwrap_or_default();
+            let topic = match exact {
+                Some(topic) => topic,
+                None => {
+                    if candidates.is_none() {
+                        let topics: Vec<KnownTopic> = self.db
+                            .query(TOPIC_CANDIDATES_QUERY)
+                            .bind(("limit", FUZZY_NAME_CANDIDATES))
+                            .await
+                            .map_err(|e| SecondBrainError::Db(format!("Failed to query topics: {}", e)))?
+                            .take(0)
+                            .unwrap_or_default();
+                        candidates = Some(topics);
+                    }
+                    let topics = candidates.as_deref().unwrap_or_default();
+                    let forms: Vec<Vec<&str>> = topics.iter().map(|t| vec![t.name.as_str()]).collect();
+                    match best_name_match(name, &forms, max_distance) {
+                        Some(index) => topics[index].clone(),
+                        None => continue,
+                    }
+                }
+            };
+            if !matched.insert(topic.name.clone()) {
+                continue;
+            }
+            let last_mentioned_days_ago = (now as i64 - topic.last_mentioned as i64) / day_ms;
 
-            if let Some(topic) = topics.into_iter().next() {
-                let last_mentioned = topic.get("last_mentioned").and_then(|v| v.as_u64()).unwrap_or(0);
-                let mention_count = topic.get("mention_count").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
-                let last_mentioned_days_ago = (now as i64 - last_mentioned as i64) / day_ms;
-
-                // Get people who discussed this topic
-                let people: Vec<serde_json::Value> = self.db
-                    .query(r#"
-                        SELECT source_entity FROM entity_relation
-                        WHERE target_entity = $name AND source_type = 'person'
-                        LIMIT 5
-                    "#)
-                    .bind(("name", name.to_string()))
-                    .await
-                    .map_err(|e| format!("Failed to query people: {}", e))?
-                    .take(0)
-                    .unwrap_or_default();
+            // Get people who discussed this topic
+            let people: Vec<serde_json::Value> = self.db
+                .query(TOPIC_PEOPLE_QUERY)
+                .bind(("names", vec![topic.name.clone(), name.to_string()]))
+                .bind(("hidden", hidden.clone()))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to query people: {}", e)))?
+                .take(0)
+                .unwrap_or_default();
 
-                let related_people: Vec<String> = people
-                    .iter()
-                    .filter_map(|v| v.get("source_entity").and_then(|t| t.as_str()).map(|s| s.to_string()))
-                    .collect();
+            let related_people: Vec<String> = people
+                .iter()
+                .filter_map(|v| v.get("source_entity").and_then(|t| t.as_str()).map(|s| s.to_string()))
+                .collect();
 
-                topic_contexts.push(TopicContext {
-                    name: name.to_string(),
-                    last_mentioned_days_ago,
-                    mention_count,
-                    related_people,
-                });
+            if topic.name != name {
+                println!("[Graph-RAG] Matched topic \"{}\" to \"{}\"", name, topic.name);
             }
+            topic_contexts.push(TopicContext {
+                name: topic.name,
+                last_mentioned_days_ago,
+                mention_count: topic.mention_count,
+                related_people,
+            });
         }
 
         Ok(topic_contexts)
@@ -1615,7 +5710,7 @@ impl KnowledgeBase {
         &self,
         entity_name: &str,
         limit: usize,
-    ) -> Result<Vec<Relationship>, String> {
+    ) -> Result<Vec<Relationship>, SecondBrainError> {
         #[derive(Deserialize)]
         struct StoredRelation {
             source_entity: String,
@@ -1636,7 +5731,7 @@ impl KnowledgeBase {
             .bind(("name", entity_name.to_string()))
             .bind(("limit", limit))
             .await
-            .map_err(|e| format!("Failed to query relations: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query relations: {}", e)))?
             .take(0)
             .unwrap_or_default();
 
@@ -1650,103 +5745,493 @@ impl KnowledgeBase {
         }).collect())
     }
 
-    // ==================== Meeting Query Methods ====================
-
-    /// Get all meetings, ordered by start time descending
-    pub async fn get_meetings(&self, limit: Option<usize>) -> Result<Vec<Meeting>, String> {
-        let query_limit = limit.unwrap_or(50);
-
-        let meetings: Vec<Meeting> = self.db
-            .query("SELECT * FROM meeting ORDER BY start_time DESC LIMIT $limit")
-            .bind(("limit", query_limit))
-            .await
-            .map_err(|e| format!("Failed to query meetings: {}", e))?
-            .take(0)
-            .map_err(|e| format!("Failed to extract meetings: {}", e))?;
+    /// Export the entity graph as JSON (nodes + edges) or GraphML
+    /// Optionally scoped to the people, topics and relations of a single meeting.
+    pub async fn export_graph(
+        &self,
+        format: GraphExportFormat,
+        meeting_id: Option<&str>,
+    ) -> Result<String, SecondBrainError> {
+        let graph = self.build_graph(meeting_id).await?;
+        println!("[KB] Exported graph: {} nodes, {} edges", graph.nodes.len(), graph.edges.len());
+        Ok(graph.render(format)?)
+    }
 
-        Ok(meetings)
+    /// Relationship subgraph of one meeting: its people and topics plus the relations
+    /// extracted from its transcript, with confidence on each edge
+    pub async fn get_meeting_graph(&self, meeting_id: &str) -> Result<GraphExport, SecondBrainError> {
+        let graph = self.build_graph(Some(meeting_id)).await?;
+        println!("[KB] Meeting graph for {}: {} nodes, {} edges",
+            normalize_record_id("meeting", meeting_id), graph.nodes.len(), graph.edges.len());
+        Ok(graph)
     }
 
-    /// Get a single meeting by ID
-    pub async fn get_meeting(&self, meeting_id: &str) -> Result<Option<Meeting>, String> {
-        // Extract just the ID part if full Thing string is passed
-        let id_part = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
-        } else {
-            meeting_id
+    /// Collect people, topics and de-duplicated entity relations into a graph
+    pub async fn build_graph(&self, meeting_id: Option<&str>) -> Result<GraphExport, SecondBrainError> {
+        #[derive(Deserialize)]
+        struct NodeCount {
+            name: Option<String>,
+            #[serde(default)]
+            mentions: u32,
+        }
+
+        #[derive(Deserialize)]
+        struct StoredRelation {
+            source_entity: String,
+            source_type: String,
+            relation: String,
+            target_entity: String,
+            target_type: String,
+            confidence: f32,
+        }
+
+        let meeting_id = meeting_id.map(|id| normalize_record_id("meeting", id));
+        let meeting_filter = if meeting_id.is_some() {
+            "WHERE out = type::thing('meeting', $meeting_id)"
+        } else {
+            ""
+        };
+
+        let (people, topics): (Vec<NodeCount>, Vec<NodeCount>) = {
+            let mut response = self.db
+                .query(format!(
+                    "SELECT in.name AS name, count() AS mentions FROM mentioned_in {} GROUP BY name;",
+                    meeting_filter
+                ))
+                .query(if meeting_id.is_some() {
+                    format!("SELECT in.name AS name, count() AS mentions FROM discussed_in {} GROUP BY name;", meeting_filter)
+                } else {
+                    "SELECT name, mention_count AS mentions FROM topic;".to_string()
+                })
+                .bind(("meeting_id", meeting_id.clone().unwrap_or_default()))
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to query graph nodes: {}", e)))?;
+            (
+                response.take(0).unwrap_or_default(),
+                response.take(1).unwrap_or_default(),
+            )
         };
 
+        let relations: Vec<StoredRelation> = self.db
+            .query(if meeting_id.is_some() {
+                // Older rows may carry the prefixed `meeting:` form
+                "SELECT * FROM entity_relation WHERE meeting_id = $meeting_id OR meeting_id = $meeting_thing"
+            } else {
+                "SELECT * FROM entity_relation"
+            })
+            .bind(("meeting_id", meeting_id.clone().unwrap_or_default()))
+            .bind(("meeting_thing", format!("meeting:{}", meeting_id.clone().unwrap_or_default())))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query relations: {}", e)))?
+            .take(0)
+            .unwrap_or_default();
+
+        let mut builder = GraphBuilder::new();
+        for person in people {
+            if let Some(name) = person.name {
+                builder.add_node("person", &name, person.mentions);
+            }
+        }
+        for topic in topics {
+            if let Some(name) = topic.name {
+                builder.add_node("topic", &name, topic.mentions);
+            }
+        }
+
+        // People never linked to a meeting still belong in the full graph
+        if meeting_id.is_none() {
+            let names: Vec<String> = self.db
+                .query("SELECT VALUE name FROM person")
+                .await
+                .map_err(|e| SecondBrainError::Db(format!("Failed to query people: {}", e)))?
+                .take(0)
+                .unwrap_or_default();
+            for name in names {
+                builder.add_node("person", &name, 0);
+            }
+        }
+
+        for r in relations {
+            builder.add_relation(
+                &r.source_type,
+                &r.source_entity,
+                &r.relation,
+                &r.target_type,
+                &r.target_entity,
+                r.confidence,
+            );
+        }
+
+        Ok(builder.build())
+    }
+
+    // ==================== Meeting Query Methods ====================
+
+    /// Get meetings, ordered by start time descending
+    /// Optionally filtered to meetings tagged with any of `tags`, favorites and/or a dominant language
+    pub async fn get_meetings(
+        &self,
+        limit: Option<usize>,
+        offset: Option<usize>,
+        tags: Option<Vec<String>>,
+        favorites_only: bool,
+        since: Option<u64>,
+        language: Option<String>,
+    ) -> Result<Vec<Meeting>, SecondBrainError> {
+        let query_limit = limit.unwrap_or(50);
+        let query_offset = offset.unwrap_or(0);
+
+        let query = format!(
+            "SELECT * FROM meeting{} ORDER BY start_time DESC LIMIT $limit START $offset",
+            meeting_filter_clause(&tags, favorites_only, since, &language)
+        );
+
+        let meetings: Vec<Meeting> = self.db
+            .query(query)
+            .bind(("limit", query_limit))
+            .bind(("offset", query_offset))
+            .bind(("tags", tags.unwrap_or_default()))
+            .bind(("since", since.unwrap_or(0)))
+            .bind(("language", language.unwrap_or_default()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query meetings: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meetings: {}", e)))?;
+
+        Ok(meetings)
+    }
+
+    /// Count meetings matching the same filters as `get_meetings`
+    pub async fn count_meetings(
+        &self,
+        tags: Option<Vec<String>>,
+        favorites_only: bool,
+        since: Option<u64>,
+        language: Option<String>,
+    ) -> Result<usize, SecondBrainError> {
+        let query = format!(
+            "SELECT count() AS count FROM meeting{} GROUP ALL",
+            meeting_filter_clause(&tags, favorites_only, since, &language)
+        );
+
+        let counts: Vec<serde_json::Value> = self.db
+            .query(query)
+            .bind(("tags", tags.unwrap_or_default()))
+            .bind(("since", since.unwrap_or(0)))
+            .bind(("language", language.unwrap_or_default()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to count meetings: {}", e)))?
+            .take(0)
+            .unwrap_or_default();
+
+        Ok(counts
+            .first()
+            .and_then(|v| v.get("count").and_then(|c| c.as_u64()))
+            .unwrap_or(0) as usize)
+    }
+
+    /// Replace a meeting's tags (trimmed, de-duplicated)
+    pub async fn set_meeting_tags(&self, meeting_id: &str, tags: Vec<String>) -> Result<Vec<String>, SecondBrainError> {
+        let id_part = normalize_record_id("meeting", meeting_id);
+
+        let mut cleaned: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = tag.trim().to_string();
+            if !tag.is_empty() && !cleaned.contains(&tag) {
+                cleaned.push(tag);
+            }
+        }
+
+        self.db
+            .query("UPDATE type::thing('meeting', $id) SET tags = $tags")
+            .bind(("id", id_part))
+            .bind(("tags", cleaned.clone()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to update meeting tags: {}", e)))?;
+
+        Ok(cleaned)
+    }
+
+    /// Flip a meeting's favorite flag, returning the new value
+    pub async fn toggle_meeting_favorite(&self, meeting_id: &str) -> Result<bool, SecondBrainError> {
+        let id_part = normalize_record_id("meeting", meeting_id);
+
+        let updated: Vec<Meeting> = self.db
+            .query("UPDATE type::thing('meeting', $id) SET favorite = !(favorite ?? false)")
+            .bind(("id", id_part))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to toggle favorite: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meeting: {}", e)))?;
+
+        updated
+            .first()
+            .map(|m| m.favorite)
+            .ok_or_else(|| SecondBrainError::NotFound("Meeting not found".to_string()))
+    }
+
+    /// Mark a meeting private (kept out of global search and Graph-RAG) or public again
+    pub async fn set_meeting_private(&self, meeting_id: &str, private: bool) -> Result<(), SecondBrainError> {
+        let id_part = normalize_record_id("meeting", meeting_id);
+
+        let updated: Vec<Meeting> = self.db
+            .query("UPDATE type::thing('meeting', $id) SET private = $private")
+            .bind(("id", id_part))
+            .bind(("private", private))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to update meeting privacy: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meeting: {}", e)))?;
+
+        if updated.is_empty() {
+            return Err(SecondBrainError::NotFound("Meeting not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Bare ids of meetings kept out of global search and Graph-RAG: private and trashed ones
+    pub async fn hidden_meeting_ids(&self) -> Result<std::collections::HashSet<String>, SecondBrainError> {
+        meeting_ids_where(&self.db, HIDDEN_MEETINGS).await
+    }
+
+    /// Hidden meeting ids in every form a segment's `meeting_id` may hold, for `NOTINSIDE` filters
+    async fn hidden_meeting_filter(&self) -> Result<Vec<String>, SecondBrainError> {
+        Ok(meeting_id_forms(self.hidden_meeting_ids().await?))
+    }
+
+    /// Trashed meeting ids in both forms, for keeping their items out of listings
+    async fn deleted_meeting_filter(&self) -> Result<Vec<String>, SecondBrainError> {
+        Ok(meeting_id_forms(meeting_ids_where(&self.db, TRASHED_MEETINGS).await?))
+    }
+
+    /// All distinct tags currently used by meetings
+    /// Derived from live meeting records, so deleted meetings never leave stale tags behind
+    pub async fn get_all_meeting_tags(&self) -> Result<Vec<String>, SecondBrainError> {
+        let tags: Vec<String> = self.db
+            .query("array::sort(array::distinct(array::flatten(SELECT VALUE tags FROM meeting WHERE deleted_at IS NONE)))")
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query meeting tags: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meeting tags: {}", e)))?;
+
+        Ok(tags)
+    }
+
+    /// Get a single meeting by ID
+    pub async fn get_meeting(&self, meeting_id: &str) -> Result<Option<Meeting>, SecondBrainError> {
+        // Extract just the ID part if full Thing string is passed
+        let id_part = normalize_record_id("meeting", meeting_id);
+
         let meeting: Option<Meeting> = self.db
             .select(("meeting", id_part))
             .await
-            .map_err(|e| format!("Failed to get meeting: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to get meeting: {}", e)))?;
 
         Ok(meeting)
     }
 
     /// Get all transcript segments for a meeting
-    pub async fn get_meeting_segments(&self, meeting_id: &str) -> Result<Vec<TranscriptSegment>, String> {
-        let meeting_id_owned = meeting_id.to_string();
+    pub async fn get_meeting_segments(&self, meeting_id: &str) -> Result<Vec<TranscriptSegment>, SecondBrainError> {
+        let meeting_id_owned = normalize_record_id("meeting", meeting_id);
 
         let segments: Vec<TranscriptSegment> = self.db
-            .query("SELECT * FROM segment WHERE meeting_id = $meeting_id ORDER BY start_ms ASC")
+            .query(MEETING_SEGMENTS_QUERY)
             .bind(("meeting_id", meeting_id_owned))
             .await
-            .map_err(|e| format!("Failed to query segments: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query segments: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract segments: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))?;
 
         Ok(segments)
     }
 
-    /// Get action items for a specific meeting
-    pub async fn get_meeting_action_items(&self, meeting_id: &str) -> Result<Vec<ActionItem>, String> {
-        // Normalize meeting_id - strip prefix if present
-        let normalized_id = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
+    /// Detected languages across a meeting's segments, most used first
+    pub async fn get_meeting_language_breakdown(&self, meeting_id: &str) -> Result<LanguageBreakdown, SecondBrainError> {
+        let meeting_id_owned = normalize_record_id("meeting", meeting_id);
+
+        let languages: Vec<Option<String>> = self.db
+            .query("SELECT VALUE language FROM segment WHERE meeting_id = $meeting_id")
+            .bind(("meeting_id", meeting_id_owned.clone()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query segment languages: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segment languages: {}", e)))?;
+
+        Ok(language_breakdown(&meeting_id_owned, &languages))
+    }
+
+    /// Segments overlapping `[start_offset_ms, end_offset_ms]`, in ms since the meeting started
+    /// (no end = through the end of the meeting)
+    pub async fn get_segments_in_range(
+        &self,
+        meeting_id: &str,
+        start_offset_ms: u64,
+        end_offset_ms: Option<u64>,
+    ) -> Result<Vec<TranscriptSegment>, SecondBrainError> {
+        if let Some(end_offset_ms) = end_offset_ms.filter(|&end| end < start_offset_ms) {
+            return Err(SecondBrainError::InvalidInput(format!("Invalid range: end {}ms is before start {}ms", end_offset_ms, start_offset_ms)));
+        }
+        let (meeting_id_owned, start) = self.meeting_timestamp(meeting_id, start_offset_ms).await?;
+        let end = match end_offset_ms {
+            Some(end_offset_ms) => start.saturating_add(end_offset_ms - start_offset_ms).min(MAX_QUERY_TIMESTAMP_MS),
+            None => MAX_QUERY_TIMESTAMP_MS,
+        };
+
+        self.db
+            .query("SELECT * FROM segment WHERE meeting_id = $meeting_id AND start_ms <= $end AND end_ms >= $start ORDER BY start_ms ASC")
+            .bind(("meeting_id", meeting_id_owned))
+            .bind(("start", start))
+            .bind(("end", end))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query segments: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))
+    }
+
+    /// The given segments of a meeting in transcript order; ids from other meetings are ignored
+    pub async fn get_segments_by_ids(&self, meeting_id: &str, segment_ids: &[String]) -> Result<Vec<TranscriptSegment>, SecondBrainError> {
+        let ids: Vec<String> = segment_ids.iter().map(|id| normalize_record_id("segment", id)).collect();
+
+        self.db
+            .query("SELECT * FROM segment WHERE meeting_id = $meeting_id AND record::id(id) IN $ids ORDER BY start_ms ASC")
+            .bind(("meeting_id", normalize_record_id("meeting", meeting_id)))
+            .bind(("ids", ids))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query segments: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))
+    }
+
+    /// Absolute timestamp of `offset_ms` into a meeting, with the normalized meeting id
+    async fn meeting_timestamp(&self, meeting_id: &str, offset_ms: u64) -> Result<(String, u64), SecondBrainError> {
+        let meeting = self.get_meeting(meeting_id).await?
+            .ok_or_else(|| SecondBrainError::NotFound(format!("Meeting {} not found", meeting_id)))?;
+        let at = meeting.start_time.saturating_add(offset_ms).min(MAX_QUERY_TIMESTAMP_MS);
+        Ok((normalize_record_id("meeting", meeting_id), at))
+    }
+
+    /// Segment being spoken `offset_ms` after the meeting started, if any
+    /// Overlapping segments resolve to the one that started last.
+    pub async fn get_segment_at_time(&self, meeting_id: &str, offset_ms: u64) -> Result<Option<TranscriptSegment>, SecondBrainError> {
+        let (meeting_id_owned, at) = self.meeting_timestamp(meeting_id, offset_ms).await?;
+
+        let segments: Vec<TranscriptSegment> = self.db
+            .query("SELECT * FROM segment WHERE meeting_id = $meeting_id AND start_ms <= $at AND end_ms >= $at ORDER BY start_ms DESC LIMIT 1")
+            .bind(("meeting_id", meeting_id_owned))
+            .bind(("at", at))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query segment: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segment: {}", e)))?;
+
+        Ok(segments.into_iter().next())
+    }
+
+    /// First segment starting after `offset_ms` into the meeting, if any
+    pub async fn get_next_segment(&self, meeting_id: &str, offset_ms: u64) -> Result<Option<TranscriptSegment>, SecondBrainError> {
+        let (meeting_id_owned, at) = self.meeting_timestamp(meeting_id, offset_ms).await?;
+
+        let segments: Vec<TranscriptSegment> = self.db
+            .query("SELECT * FROM segment WHERE meeting_id = $meeting_id AND start_ms > $at ORDER BY start_ms ASC LIMIT 1")
+            .bind(("meeting_id", meeting_id_owned))
+            .bind(("at", at))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query segment: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segment: {}", e)))?;
+
+        Ok(segments.into_iter().next())
+    }
+
+    /// Get a window of transcript segments for a meeting (all remaining if no limit)
+    pub async fn get_meeting_segments_page(
+        &self,
+        meeting_id: &str,
+        limit: Option<usize>,
+        offset: Option<usize>,
+    ) -> Result<Vec<TranscriptSegment>, SecondBrainError> {
+        let meeting_id_owned = normalize_record_id("meeting", meeting_id);
+        let query_offset = offset.unwrap_or(0);
+
+        let query = if limit.is_some() {
+            "SELECT * FROM segment WHERE meeting_id = $meeting_id ORDER BY start_ms ASC LIMIT $limit START $offset"
         } else {
-            meeting_id
+            "SELECT * FROM segment WHERE meeting_id = $meeting_id ORDER BY start_ms ASC START $offset"
         };
 
+        let segments: Vec<TranscriptSegment> = self.db
+            .query(query)
+            .bind(("meeting_id", meeting_id_owned))
+            .bind(("limit", limit.unwrap_or(0)))
+            .bind(("offset", query_offset))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query segments: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))?;
+
+        Ok(segments)
+    }
+
+    /// Count transcript segments for a meeting without loading them
+    pub async fn count_meeting_segments(&self, meeting_id: &str) -> Result<usize, SecondBrainError> {
+        let meeting_id_owned = normalize_record_id("meeting", meeting_id);
+
+        let counts: Vec<serde_json::Value> = self.db
+            .query("SELECT count() AS count FROM segment WHERE meeting_id = $meeting_id GROUP ALL")
+            .bind(("meeting_id", meeting_id_owned))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to count segments: {}", e)))?
+            .take(0)
+            .unwrap_or_default();
+
+        Ok(counts
+            .first()
+            .and_then(|v| v.get("count").and_then(|c| c.as_u64()))
+            .unwrap_or(0) as usize)
+    }
+
+    /// Get action items for a specific meeting
+    pub async fn get_meeting_action_items(&self, meeting_id: &str) -> Result<Vec<ActionItem>, SecondBrainError> {
+        // Normalize meeting_id - strip prefix if present
+        let normalized_id = normalize_record_id("meeting", meeting_id);
+
         println!("[KB] Getting action items for meeting: {} (normalized: {})", meeting_id, normalized_id);
 
         let actions: Vec<ActionItem> = self.db
-            .query("SELECT * FROM action_item WHERE meeting_id = $meeting_id ORDER BY created_at DESC")
+            .query(MEETING_ACTION_ITEMS_QUERY)
             .bind(("meeting_id", normalized_id.to_string()))
             .await
-            .map_err(|e| format!("Failed to query action items: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query action items: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract action items: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract action items: {}", e)))?;
 
         println!("[KB] Found {} action items", actions.len());
         Ok(actions)
     }
 
     /// Get decisions for a specific meeting
-    pub async fn get_meeting_decisions(&self, meeting_id: &str) -> Result<Vec<Decision>, String> {
+    pub async fn get_meeting_decisions(&self, meeting_id: &str) -> Result<Vec<Decision>, SecondBrainError> {
         // Normalize meeting_id - strip prefix if present
-        let normalized_id = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
-        } else {
-            meeting_id
-        };
+        let normalized_id = normalize_record_id("meeting", meeting_id);
 
         println!("[KB] Getting decisions for meeting: {} (normalized: {})", meeting_id, normalized_id);
 
         let decisions: Vec<Decision> = self.db
-            .query("SELECT * FROM decision WHERE meeting_id = $meeting_id ORDER BY created_at DESC")
+            .query(MEETING_DECISIONS_QUERY)
             .bind(("meeting_id", normalized_id.to_string()))
             .await
-            .map_err(|e| format!("Failed to query decisions: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query decisions: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract decisions: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract decisions: {}", e)))?;
 
         println!("[KB] Found {} decisions", decisions.len());
         Ok(decisions)
     }
 
     /// Get ALL action items across all meetings with meeting title
-    pub async fn get_all_action_items(&self, limit: usize) -> Result<Vec<serde_json::Value>, String> {
+    pub async fn get_all_action_items(&self, limit: usize) -> Result<Vec<serde_json::Value>, SecondBrainError> {
+        // Items from trashed meetings stay hidden until the meeting is restored
+        let deleted = self.deleted_meeting_filter().await?;
         let results: Vec<serde_json::Value> = self.db
             .query(r#"
                 SELECT
@@ -1756,38 +6241,160 @@ impl KnowledgeBase {
                     deadline,
                     status,
                     meeting_id,
-                    (SELECT title FROM meeting WHERE id = $parent.meeting_id)[0].title AS meeting_title,
+                    (SELECT title FROM meeting WHERE id = type::thing('meeting', $parent.meeting_id))[0].title AS meeting_title,
                     created_at
                 FROM action_item
+                WHERE meeting_id NOTINSIDE $deleted
+                ORDER BY created_at DESC
+                LIMIT $limit
+            "#)
+            .bind(("deleted", deleted))
+            .bind(("limit", limit))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query all action items: {}", e)))?
+            .take(0)
+            .unwrap_or_default();
+
+        Ok(results)
+    }
+
+    /// Action items with no assignee, or whose meeting no longer exists, newest first
+    pub async fn get_unassigned_action_items(&self, limit: usize) -> Result<Vec<serde_json::Value>, SecondBrainError> {
+        let deleted = self.deleted_meeting_filter().await?;
+        let results: Vec<serde_json::Value> = self.db
+            .query(r#"
+                SELECT * FROM (
+                    SELECT
+                        id,
+                        text,
+                        assignee,
+                        deadline,
+                        status,
+                        meeting_id,
+                        (SELECT title FROM meeting WHERE id = type::thing('meeting', $parent.meeting_id))[0].title AS meeting_title,
+                        created_at
+                    FROM action_item
+                    WHERE meeting_id NOTINSIDE $deleted
+                )
+                WHERE string::trim(assignee ?? '') = '' OR meeting_title IS NONE
                 ORDER BY created_at DESC
                 LIMIT $limit
             "#)
+            .bind(("deleted", deleted))
             .bind(("limit", limit))
             .await
-            .map_err(|e| format!("Failed to query all action items: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query unassigned action items: {}", e)))?
             .take(0)
             .unwrap_or_default();
 
         Ok(results)
     }
 
+    /// Export every action item as iCal VTODOs, a markdown checklist or CSV
+    pub async fn export_action_items(&self, format: ActionExportFormat) -> Result<String, SecondBrainError> {
+        #[derive(Deserialize)]
+        struct Row {
+            id: Thing,
+            text: String,
+            assignee: Option<String>,
+            deadline: Option<String>,
+            status: String,
+            meeting_title: Option<String>,
+            created_at: u64,
+        }
+
+        let deleted = self.deleted_meeting_filter().await?;
+        let rows: Vec<Row> = self.db
+            .query(r#"
+                SELECT
+                    id,
+                    text,
+                    assignee,
+                    deadline,
+                    status,
+                    (SELECT title FROM meeting WHERE id = type::thing('meeting', $parent.meeting_id))[0].title AS meeting_title,
+                    created_at
+                FROM action_item
+                WHERE meeting_id NOTINSIDE $deleted
+                ORDER BY created_at DESC
+            "#)
+            .bind(("deleted", deleted))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query action items: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract action items: {}", e)))?;
+
+        let items: Vec<ExportActionItem> = rows.into_iter()
+            .map(|r| ExportActionItem {
+                id: normalize_record_id("action_item", &r.id.to_string()),
+                text: r.text,
+                assignee: r.assignee,
+                deadline: r.deadline,
+                status: r.status,
+                meeting_title: r.meeting_title,
+                created_at: r.created_at,
+            })
+            .collect();
+
+        println!("[KB] Exported {} action items as {:?}", items.len(), format);
+        Ok(crate::action_export::render(&items, format))
+    }
+
+    /// Every live record for a portable SQLite export (see `sqlite_export`)
+    /// Private meetings are included since the export is the user's own copy; trashed ones are not.
+    pub async fn export_snapshot(&self) -> Result<KbSnapshot, SecondBrainError> {
+        let deleted = self.deleted_meeting_filter().await?;
+        let mut response = self.db
+            .query(r#"
+                SELECT * FROM meeting WHERE deleted_at IS NONE ORDER BY start_time;
+                SELECT * FROM segment WHERE meeting_id NOTINSIDE $deleted ORDER BY meeting_id, start_ms;
+                SELECT * FROM action_item WHERE meeting_id NOTINSIDE $deleted ORDER BY created_at;
+                SELECT * FROM decision WHERE meeting_id NOTINSIDE $deleted ORDER BY created_at;
+                SELECT * FROM person ORDER BY name;
+                SELECT * FROM topic ORDER BY name;
+                SELECT * FROM knowledge_source ORDER BY created_at;
+                SELECT * FROM knowledge_chunk ORDER BY source_id, chunk_index;
+            "#)
+            .bind(("deleted", deleted))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to read knowledge base for export: {}", e)))?;
+
+        let snapshot = KbSnapshot {
+            meetings: response.take(0).map_err(|e| SecondBrainError::Db(format!("Failed to extract meetings: {}", e)))?,
+            segments: response.take(1).map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))?,
+            action_items: response.take(2).map_err(|e| SecondBrainError::Db(format!("Failed to extract action items: {}", e)))?,
+            decisions: response.take(3).map_err(|e| SecondBrainError::Db(format!("Failed to extract decisions: {}", e)))?,
+            people: response.take(4).map_err(|e| SecondBrainError::Db(format!("Failed to extract people: {}", e)))?,
+            topics: response.take(5).map_err(|e| SecondBrainError::Db(format!("Failed to extract topics: {}", e)))?,
+            sources: response.take(6).map_err(|e| SecondBrainError::Db(format!("Failed to extract knowledge sources: {}", e)))?,
+            chunks: response.take(7).map_err(|e| SecondBrainError::Db(format!("Failed to extract knowledge chunks: {}", e)))?,
+        };
+        println!("[KB] Snapshot for export: {} meetings, {} segments, {} sources",
+            snapshot.meetings.len(), snapshot.segments.len(), snapshot.sources.len());
+        Ok(snapshot)
+    }
+
     /// Get ALL decisions across all meetings with meeting title
-    pub async fn get_all_decisions(&self, limit: usize) -> Result<Vec<serde_json::Value>, String> {
+    pub async fn get_all_decisions(&self, limit: usize) -> Result<Vec<serde_json::Value>, SecondBrainError> {
+        // Items from trashed meetings stay hidden until the meeting is restored
+        let deleted = self.deleted_meeting_filter().await?;
         let results: Vec<serde_json::Value> = self.db
             .query(r#"
                 SELECT
                     id,
                     text,
                     meeting_id,
-                    (SELECT title FROM meeting WHERE id = $parent.meeting_id)[0].title AS meeting_title,
+                    (SELECT title FROM meeting WHERE id = type::thing('meeting', $parent.meeting_id))[0].title AS meeting_title,
                     created_at
                 FROM decision
+                WHERE meeting_id NOTINSIDE $deleted
                 ORDER BY created_at DESC
                 LIMIT $limit
             "#)
+            .bind(("deleted", deleted))
             .bind(("limit", limit))
             .await
-            .map_err(|e| format!("Failed to query all decisions: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query all decisions: {}", e)))?
             .take(0)
             .unwrap_or_default();
 
@@ -1795,12 +6402,12 @@ impl KnowledgeBase {
     }
 
     /// Get global knowledge base statistics
-    pub async fn get_global_stats(&self) -> Result<serde_json::Value, String> {
+    pub async fn get_global_stats(&self) -> Result<serde_json::Value, SecondBrainError> {
         // Count total segments
         let segment_counts: Vec<serde_json::Value> = self.db
             .query("SELECT count() AS count FROM transcript_segment GROUP ALL")
             .await
-            .map_err(|e| format!("Failed to count segments: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to count segments: {}", e)))?
             .take(0)
             .unwrap_or_default();
 
@@ -1819,7 +6426,7 @@ impl KnowledgeBase {
                 LIMIT 10
             "#)
             .await
-            .map_err(|e| format!("Failed to count entities: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to count entities: {}", e)))?
             .take(0)
             .unwrap_or_default();
 
@@ -1830,25 +6437,17 @@ impl KnowledgeBase {
     }
 
     /// Get topics discussed in a meeting
-    pub async fn get_meeting_topics(&self, meeting_id: &str) -> Result<Vec<Topic>, String> {
+    pub async fn get_meeting_topics(&self, meeting_id: &str) -> Result<Vec<Topic>, SecondBrainError> {
         // Extract just the ID part for use with type::thing()
-        let meeting_id_part = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
-        } else {
-            meeting_id
-        };
+        let meeting_id_part = normalize_record_id("meeting", meeting_id);
         let meeting_id_owned = meeting_id_part.to_string();
 
         // Query topics that are linked to this meeting via discussed_in relation
         let topics: Vec<Topic> = self.db
-            .query(r#"
-                SELECT * FROM topic WHERE id IN (
-                    SELECT in FROM discussed_in WHERE out = type::thing('meeting', $meeting_id)
-                )
-            "#)
+            .query(MEETING_TOPICS_QUERY)
             .bind(("meeting_id", meeting_id_owned))
             .await
-            .map_err(|e| format!("Failed to query topics: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query topics: {}", e)))?
             .take(0)
             .unwrap_or_default();
 
@@ -1856,25 +6455,17 @@ impl KnowledgeBase {
     }
 
     /// Get people mentioned in a meeting
-    pub async fn get_meeting_people(&self, meeting_id: &str) -> Result<Vec<Person>, String> {
+    pub async fn get_meeting_people(&self, meeting_id: &str) -> Result<Vec<Person>, SecondBrainError> {
         // Extract just the ID part for use with type::thing()
-        let meeting_id_part = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
-        } else {
-            meeting_id
-        };
+        let meeting_id_part = normalize_record_id("meeting", meeting_id);
         let meeting_id_owned = meeting_id_part.to_string();
 
         // Query people that are linked to this meeting via mentioned_in relation
         let people: Vec<Person> = self.db
-            .query(r#"
-                SELECT * FROM person WHERE id IN (
-                    SELECT in FROM mentioned_in WHERE out = type::thing('meeting', $meeting_id)
-                )
-            "#)
+            .query(MEETING_PEOPLE_QUERY)
             .bind(("meeting_id", meeting_id_owned))
             .await
-            .map_err(|e| format!("Failed to query people: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query people: {}", e)))?
             .take(0)
             .unwrap_or_default();
 
@@ -1882,7 +6473,7 @@ impl KnowledgeBase {
     }
 
     /// Update action item status
-    pub async fn update_action_item_status(&self, action_id: &str, status: &str) -> Result<(), String> {
+    pub async fn update_action_item_status(&self, action_id: &str, status: &str) -> Result<(), SecondBrainError> {
         let id_part = if action_id.starts_with("action_item:") {
             action_id.strip_prefix("action_item:").unwrap_or(action_id)
         } else {
@@ -1894,11 +6485,77 @@ impl KnowledgeBase {
             .bind(("id", id_part.to_string()))
             .bind(("status", status.to_string()))
             .await
-            .map_err(|e| format!("Failed to update action item: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to update action item: {}", e)))?;
 
         Ok(())
     }
 
+    /// Set or clear (with `None` or a blank string) who owns an action item
+    pub async fn set_action_item_assignee(&self, action_id: &str, assignee: Option<&str>) -> Result<ActionItem, SecondBrainError> {
+        self.update_action_item_fields(action_id, "assignee = $assignee", vec![("assignee", blank_to_none(assignee))]).await
+    }
+
+    /// Set or clear (with `None` or a blank string) when an action item is due
+    pub async fn set_action_item_deadline(&self, action_id: &str, deadline: Option<&str>) -> Result<ActionItem, SecondBrainError> {
+        self.update_action_item_fields(action_id, "deadline = $deadline", vec![("deadline", blank_to_none(deadline))]).await
+    }
+
+    /// Move an action item to another meeting, optionally setting its assignee and deadline.
+    /// `None` keeps the current value; a blank string clears it.
+    pub async fn reassign_action_item(
+        &self,
+        action_id: &str,
+        meeting_id: &str,
+        assignee: Option<&str>,
+        deadline: Option<&str>,
+    ) -> Result<ActionItem, SecondBrainError> {
+        let meeting_id = normalize_record_id("meeting", meeting_id);
+        if self.get_meeting(&meeting_id).await?.is_none() {
+            return Err(SecondBrainError::NotFound(format!("Meeting not found: {}", meeting_id)));
+        }
+
+        let mut assignments = vec!["meeting_id = $meeting_id"];
+        if assignee.is_some() {
+            assignments.push("assignee = $assignee");
+        }
+        if deadline.is_some() {
+            assignments.push("deadline = $deadline");
+        }
+
+        self.update_action_item_fields(
+            action_id,
+            &assignments.join(", "),
+            vec![
+                ("meeting_id", Some(meeting_id)),
+                ("assignee", blank_to_none(assignee)),
+                ("deadline", blank_to_none(deadline)),
+            ],
+        )
+        .await
+    }
+
+    async fn update_action_item_fields(
+        &self,
+        action_id: &str,
+        assignments: &str,
+        fields: Vec<(&'static str, Option<String>)>,
+    ) -> Result<ActionItem, SecondBrainError> {
+        let mut query = self.db
+            .query(format!("UPDATE type::thing('action_item', $id) SET {}", assignments))
+            .bind(("id", normalize_record_id("action_item", action_id)));
+        for field in fields {
+            query = query.bind(field);
+        }
+
+        let updated: Option<ActionItem> = query
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to update action item: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract action item: {}", e)))?;
+
+        updated.ok_or_else(|| SecondBrainError::NotFound(format!("Action item not found: {}", action_id)))
+    }
+
     /// Add an action item to a meeting
     pub async fn add_action_item(
         &self,
@@ -1906,13 +6563,9 @@ impl KnowledgeBase {
         text: &str,
         assignee: Option<&str>,
         deadline: Option<&str>,
-    ) -> Result<String, String> {
+    ) -> Result<String, SecondBrainError> {
         // Normalize meeting_id - strip prefix if present
-        let normalized_id = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
-        } else {
-            meeting_id
-        };
+        let normalized_id = normalize_record_id("meeting", meeting_id);
 
         println!("[KB] Adding action item for meeting: {} (normalized: {})", meeting_id, normalized_id);
 
@@ -1923,34 +6576,50 @@ impl KnowledgeBase {
             .bind(("assignee", assignee.map(|s| s.to_string())))
             .bind(("deadline", deadline.map(|s| s.to_string())))
             .await
-            .map_err(|e| format!("Failed to create action item: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to create action item: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract action item: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract action item: {}", e)))?;
 
         let id = action.and_then(|a| a.id).map(|id| id.to_string()).unwrap_or_default();
         println!("[KB] Created action item: {}", id);
         Ok(id)
     }
 
-    /// Add a decision to a meeting
-    pub async fn add_decision(&self, meeting_id: &str, text: &str) -> Result<String, String> {
-        // Normalize meeting_id - strip prefix if present
-        let normalized_id = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
-        } else {
-            meeting_id
-        };
-
-        println!("[KB] Adding decision for meeting: {} (normalized: {})", meeting_id, normalized_id);
-
-        let decision: Option<Decision> = self.db
+    /// Add an action item unless the meeting already has one with (nearly) the same text
+    /// Returns the new item's id, or None if it was a duplicate.
+    pub async fn add_action_item_if_new(
+        &self,
+        meeting_id: &str,
+        text: &str,
+        assignee: Option<&str>,
+        deadline: Option<&str>,
+    ) -> Result<Option<String>, SecondBrainError> {
+        let existing = self.get_meeting_action_items(meeting_id).await?;
+        if let Some(duplicate) = existing.iter()
+            .find(|item| action_text_similarity(&item.text, text) >= ACTION_DUPLICATE_THRESHOLD)
+        {
+            println!("[KB] Skipping duplicate action item '{}' (matches '{}')", text, duplicate.text);
+            return Ok(None);
+        }
+
+        self.add_action_item(meeting_id, text, assignee, deadline).await.map(Some)
+    }
+
+    /// Add a decision to a meeting
+    pub async fn add_decision(&self, meeting_id: &str, text: &str) -> Result<String, SecondBrainError> {
+        // Normalize meeting_id - strip prefix if present
+        let normalized_id = normalize_record_id("meeting", meeting_id);
+
+        println!("[KB] Adding decision for meeting: {} (normalized: {})", meeting_id, normalized_id);
+
+        let decision: Option<Decision> = self.db
             .query("CREATE decision SET meeting_id = $meeting_id, text = $text, created_at = time::now()")
             .bind(("meeting_id", normalized_id.to_string()))
             .bind(("text", text.to_string()))
             .await
-            .map_err(|e| format!("Failed to create decision: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to create decision: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract decision: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract decision: {}", e)))?;
 
         let id = decision.and_then(|d| d.id).map(|id| id.to_string()).unwrap_or_default();
         println!("[KB] Created decision: {}", id);
@@ -1958,13 +6627,9 @@ impl KnowledgeBase {
     }
 
     /// Update meeting summary
-    pub async fn update_meeting_summary(&self, meeting_id: &str, summary: &str) -> Result<(), String> {
+    pub async fn update_meeting_summary(&self, meeting_id: &str, summary: &str) -> Result<(), SecondBrainError> {
         // Normalize meeting_id - strip prefix if present
-        let id_part = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
-        } else {
-            meeting_id
-        };
+        let id_part = normalize_record_id("meeting", meeting_id);
 
         println!("[KB] Updating summary for meeting: {} (id_part: {})", meeting_id, id_part);
 
@@ -1973,13 +6638,51 @@ impl KnowledgeBase {
             .bind(("id", id_part.to_string()))
             .bind(("summary", summary.to_string()))
             .await
-            .map_err(|e| format!("Failed to update meeting summary: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to update meeting summary: {}", e)))?;
 
         Ok(())
     }
 
+    /// Flag a moment in a meeting
+    pub async fn add_bookmark(
+        &self,
+        meeting_id: &str,
+        offset_ms: u64,
+        timestamp_ms: u64,
+        note: Option<String>,
+    ) -> Result<MeetingBookmark, SecondBrainError> {
+        let bookmark = MeetingBookmark {
+            id: None,
+            meeting_id: normalize_record_id("meeting", meeting_id),
+            offset_ms,
+            timestamp_ms,
+            note: note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+        };
+
+        let created: Option<MeetingBookmark> = self.db
+            .create("meeting_bookmark")
+            .content(bookmark)
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to create bookmark: {}", e)))?;
+
+        created.ok_or_else(|| SecondBrainError::Db("Failed to create bookmark".to_string()))
+    }
+
+    /// Get a meeting's bookmarks in the order they were made
+    pub async fn get_meeting_bookmarks(&self, meeting_id: &str) -> Result<Vec<MeetingBookmark>, SecondBrainError> {
+        let bookmarks: Vec<MeetingBookmark> = self.db
+            .query(MEETING_BOOKMARKS_QUERY)
+            .bind(("meeting_id", normalize_record_id("meeting", meeting_id)))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query bookmarks: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract bookmarks: {}", e)))?;
+
+        Ok(bookmarks)
+    }
+
     /// Get meeting statistics
-    pub async fn get_meeting_stats(&self, meeting_id: &str) -> Result<MeetingStats, String> {
+    pub async fn get_meeting_stats(&self, meeting_id: &str) -> Result<MeetingStats, SecondBrainError> {
         let segments = self.get_meeting_segments(meeting_id).await?;
         let actions = self.get_meeting_action_items(meeting_id).await?;
         let decisions = self.get_meeting_decisions(meeting_id).await?;
@@ -2010,58 +6713,239 @@ impl KnowledgeBase {
         })
     }
 
-    /// Delete a meeting and all associated data
-    pub async fn delete_meeting(&self, meeting_id: &str) -> Result<(), String> {
-        // Extract just the ID part if full Thing string is passed
-        let id_part = if meeting_id.starts_with("meeting:") {
-            meeting_id.strip_prefix("meeting:").unwrap_or(meeting_id)
-        } else {
-            meeting_id
+    /// Get per-speaker word counts, talk time and interruptions for a meeting
+    pub async fn get_meeting_speaker_stats(&self, meeting_id: &str) -> Result<MeetingSpeakerStats, SecondBrainError> {
+        let segments = self.get_meeting_segments(meeting_id).await?;
+        Ok(MeetingSpeakerStats::from_segments(
+            &normalize_record_id("meeting", meeting_id),
+            &segments,
+            INTERRUPTION_GAP_MS,
+        ))
+    }
+
+    /// Emotion timeline in `bucket_ms` slices (default one minute) and per-speaker emotions
+    pub async fn get_meeting_sentiment(&self, meeting_id: &str, bucket_ms: Option<u64>) -> Result<MeetingSentiment, SecondBrainError> {
+        let meeting = self.get_meeting(meeting_id).await?
+            .ok_or_else(|| SecondBrainError::NotFound(format!("Meeting {} not found", meeting_id)))?;
+        let segments = self.get_meeting_segments(meeting_id).await?;
+        Ok(MeetingSentiment::from_segments(
+            &normalize_record_id("meeting", meeting_id),
+            meeting.start_time,
+            &segments,
+            bucket_ms.unwrap_or(DEFAULT_SENTIMENT_BUCKET_MS),
+        ))
+    }
+
+    /// Meeting count and time, busiest topics and people, and action item progress for
+    /// meetings started in `[start, end)`. Trashed meetings are left out.
+    pub async fn get_analytics(&self, start: u64, end: u64) -> Result<MeetingAnalytics, SecondBrainError> {
+        #[derive(Deserialize)]
+        struct LastSegment {
+            meeting_id: String,
+            last_end: Option<u64>,
+        }
+
+        #[derive(Deserialize)]
+        struct Mention {
+            name: Option<String>,
+            meeting: Thing,
+        }
+
+        #[derive(Deserialize)]
+        struct StatusCount {
+            status: String,
+            count: usize,
+        }
+
+        if start >= end {
+            return Err(SecondBrainError::InvalidInput("Analytics range start must be before its end".to_string()));
+        }
+
+        let meetings: Vec<Meeting> = self.db
+            .query("SELECT * FROM meeting WHERE deleted_at IS NONE AND start_time >= $start AND start_time < $end ORDER BY start_time")
+            .bind(("start", start))
+            .bind(("end", end))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query meetings: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meetings: {}", e)))?;
+
+        let meeting_things: Vec<Thing> = meetings.iter().filter_map(|m| m.id.clone()).collect();
+        let meeting_ids = meeting_id_forms(
+            meeting_things.iter().map(|t| normalize_record_id("meeting", &t.to_string())).collect()
+        );
+
+        let mut response = self.db
+            .query(r#"
+                SELECT meeting_id, math::max(end_ms) AS last_end FROM segment
+                    WHERE meeting_id INSIDE $ids GROUP BY meeting_id;
+                SELECT in.name AS name, out AS meeting FROM discussed_in WHERE out INSIDE $meetings;
+                SELECT in.name AS name, out AS meeting FROM mentioned_in WHERE out INSIDE $meetings;
+                SELECT status, count() AS count FROM action_item WHERE meeting_id INSIDE $ids GROUP BY status;
+            "#)
+            .bind(("ids", meeting_ids))
+            .bind(("meetings", meeting_things))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query analytics: {}", e)))?;
+        let last_segments: Vec<LastSegment> = response.take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segment ends: {}", e)))?;
+        let topic_mentions: Vec<Mention> = response.take(1)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract topic mentions: {}", e)))?;
+        let person_mentions: Vec<Mention> = response.take(2)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract person mentions: {}", e)))?;
+        let statuses: Vec<StatusCount> = response.take(3)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract action item counts: {}", e)))?;
+
+        let last_end: std::collections::HashMap<String, u64> = last_segments.into_iter()
+            .filter_map(|row| row.last_end.map(|end| (normalize_record_id("meeting", &row.meeting_id), end)))
+            .collect();
+        let mut timed = Vec::with_capacity(meetings.len());
+        let mut estimated_durations = 0;
+        for meeting in &meetings {
+            let id = meeting.id.as_ref().map(|t| normalize_record_id("meeting", &t.to_string())).unwrap_or_default();
+            let (duration, estimated) = meeting_duration_ms(meeting, last_end.get(&id).copied());
+            if estimated {
+                estimated_durations += 1;
+            }
+            timed.push((meeting.start_time, duration));
+        }
+        let total_duration_ms: u64 = timed.iter().map(|(_, duration)| duration).sum();
+
+        let pairs = |mentions: Vec<Mention>| -> Vec<(String, String)> {
+            mentions.into_iter()
+                .filter_map(|m| m.name.map(|name| (name, m.meeting.to_string())))
+                .collect()
         };
 
-        let full_meeting_id = format!("meeting:{}", id_part);
+        let count_of = |status: &str| statuses.iter().filter(|s| s.status == status).map(|s| s.count).sum::<usize>();
+        let action_items_done = count_of("done");
+        let action_total: usize = statuses.iter().map(|s| s.count).sum();
+
+        Ok(MeetingAnalytics {
+            start,
+            end,
+            meeting_count: meetings.len(),
+            total_duration_ms,
+            average_duration_ms: if meetings.is_empty() { 0 } else { total_duration_ms / meetings.len() as u64 },
+            estimated_durations,
+            daily: daily_totals(&timed, &chrono::Local),
+            top_topics: rank_mentions(&pairs(topic_mentions), ANALYTICS_TOP_N),
+            top_people: rank_mentions(&pairs(person_mentions), ANALYTICS_TOP_N),
+            action_items_open: count_of("open"),
+            action_items_in_progress: count_of("in_progress"),
+            action_items_done,
+            action_done_ratio: if action_total == 0 { 0.0 } else { action_items_done as f32 / action_total as f32 },
+        })
+    }
+
+    /// Move a meeting to the trash: it disappears from listings and search but keeps all its data
+    pub async fn trash_meeting(&self, meeting_id: &str) -> Result<(), SecondBrainError> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        self.set_meeting_deleted_at(meeting_id, Some(now)).await
+    }
+
+    /// Take a meeting back out of the trash
+    pub async fn restore_meeting(&self, meeting_id: &str) -> Result<(), SecondBrainError> {
+        self.set_meeting_deleted_at(meeting_id, None).await
+    }
+
+    async fn set_meeting_deleted_at(&self, meeting_id: &str, deleted_at: Option<u64>) -> Result<(), SecondBrainError> {
+        let updated: Vec<Meeting> = self.db
+            .query(SET_DELETED_AT_QUERY)
+            .bind(("id", normalize_record_id("meeting", meeting_id)))
+            .bind(("deleted_at", deleted_at))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to update meeting: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract meeting: {}", e)))?;
+
+        if updated.is_empty() {
+            return Err(SecondBrainError::NotFound("Meeting not found".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Meetings in the trash, most recently deleted first
+    pub async fn get_trashed_meetings(&self) -> Result<Vec<Meeting>, SecondBrainError> {
+        let meetings: Vec<Meeting> = self.db
+            .query("SELECT * FROM meeting WHERE deleted_at IS NOT NONE ORDER BY deleted_at DESC")
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query trashed meetings: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract trashed meetings: {}", e)))?;
+        Ok(meetings)
+    }
+
+    /// Trashed meetings deleted before `cutoff` (ms), due to be purged
+    pub async fn get_meetings_trashed_before(&self, cutoff: u64) -> Result<Vec<Meeting>, SecondBrainError> {
+        let meetings: Vec<Meeting> = self.db
+            .query("SELECT * FROM meeting WHERE deleted_at IS NOT NONE AND deleted_at < $cutoff")
+            .bind(("cutoff", cutoff))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to query trashed meetings: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract trashed meetings: {}", e)))?;
+        Ok(meetings)
+    }
+
+    /// Permanently delete a meeting and all associated data
+    pub async fn purge_meeting(&self, meeting_id: &str) -> Result<(), SecondBrainError> {
+        // Extract just the ID part if full Thing string is passed
+        let id_part = normalize_record_id("meeting", meeting_id);
 
-        println!("[KB Delete Meeting] Deleting meeting: id_part={}, full={}", id_part, full_meeting_id);
+        println!("[KB Purge Meeting] Purging meeting: {}", id_part);
 
         // Delete all segments for this meeting
         self.db
-            .query("DELETE FROM segment WHERE meeting_id = $meeting_id OR meeting_id = $full_id")
+            .query("DELETE FROM segment WHERE meeting_id = $meeting_id")
             .bind(("meeting_id", id_part.to_string()))
-            .bind(("full_id", full_meeting_id.clone()))
             .await
-            .map_err(|e| format!("Failed to delete segments: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete segments: {}", e)))?;
 
         // Delete all action items for this meeting
         self.db
-            .query("DELETE FROM action_item WHERE meeting_id = $meeting_id OR meeting_id = $full_id")
+            .query("DELETE FROM action_item WHERE meeting_id = $meeting_id")
             .bind(("meeting_id", id_part.to_string()))
-            .bind(("full_id", full_meeting_id.clone()))
             .await
-            .map_err(|e| format!("Failed to delete action items: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete action items: {}", e)))?;
 
         // Delete all decisions for this meeting
         self.db
-            .query("DELETE FROM decision WHERE meeting_id = $meeting_id OR meeting_id = $full_id")
+            .query("DELETE FROM decision WHERE meeting_id = $meeting_id")
             .bind(("meeting_id", id_part.to_string()))
-            .bind(("full_id", full_meeting_id.clone()))
             .await
-            .map_err(|e| format!("Failed to delete decisions: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete decisions: {}", e)))?;
 
         // Delete entity relations for this meeting
         self.db
-            .query("DELETE FROM entity_relation WHERE meeting_id = $meeting_id OR meeting_id = $full_id")
+            .query("DELETE FROM entity_relation WHERE meeting_id = $meeting_id")
+            .bind(("meeting_id", id_part.to_string()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete entity relations: {}", e)))?;
+
+        // Delete links to and from other meetings
+        self.db
+            .query("DELETE FROM related_meeting WHERE from_meeting = $meeting_id OR to_meeting = $meeting_id")
+            .bind(("meeting_id", id_part.to_string()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete meeting links: {}", e)))?;
+
+        // Delete bookmarks
+        self.db
+            .query("DELETE FROM meeting_bookmark WHERE meeting_id = $meeting_id")
             .bind(("meeting_id", id_part.to_string()))
-            .bind(("full_id", full_meeting_id.clone()))
             .await
-            .map_err(|e| format!("Failed to delete entity relations: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete bookmarks: {}", e)))?;
 
         // Delete meeting-knowledge links
         self.db
-            .query("DELETE FROM meeting_knowledge WHERE meeting_id = $meeting_id OR meeting_id = $full_id")
+            .query("DELETE FROM meeting_knowledge WHERE meeting_id = $meeting_id")
             .bind(("meeting_id", id_part.to_string()))
-            .bind(("full_id", full_meeting_id.clone()))
             .await
-            .map_err(|e| format!("Failed to delete meeting links: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete meeting links: {}", e)))?;
 
         // Delete graph relations (mentioned_in, discussed_in edges pointing to this meeting)
         self.db
@@ -2078,23 +6962,23 @@ impl KnowledgeBase {
 
         // Finally, delete the meeting itself
         self.db
-            .delete::<Option<Meeting>>(("meeting", id_part))
+            .delete::<Option<Meeting>>(("meeting", id_part.as_str()))
             .await
-            .map_err(|e| format!("Failed to delete meeting: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to delete meeting: {}", e)))?;
 
-        println!("[KB Delete Meeting] Meeting deleted successfully: {}", meeting_id);
+        println!("[KB Purge Meeting] Meeting purged: {}", meeting_id);
         Ok(())
     }
 
     /// Clean up orphaned chunks (chunks whose source no longer exists)
-    pub async fn cleanup_orphaned_chunks(&self) -> Result<usize, String> {
+    pub async fn cleanup_orphaned_chunks(&self) -> Result<usize, SecondBrainError> {
         // Get all unique source_ids from chunks using GROUP BY (SurrealDB syntax)
         let chunk_source_ids: Vec<serde_json::Value> = self.db
             .query("SELECT source_id FROM knowledge_chunk GROUP BY source_id")
             .await
-            .map_err(|e| format!("Failed to get chunk source_ids: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to get chunk source_ids: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract source_ids: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract source_ids: {}", e)))?;
 
         println!("[KB Cleanup] Found {} unique source_ids in chunks", chunk_source_ids.len());
 
@@ -2111,7 +6995,7 @@ impl KnowledgeBase {
                         .query("DELETE FROM knowledge_chunk WHERE source_id = $source_id")
                         .bind(("source_id", source_id.to_string()))
                         .await
-                        .map_err(|e| format!("Failed to delete orphaned chunks: {}", e))?;
+                        .map_err(|e| SecondBrainError::Db(format!("Failed to delete orphaned chunks: {}", e)))?;
 
                     deleted_count += 1;
                 }
@@ -2123,21 +7007,23 @@ impl KnowledgeBase {
     }
 
     /// Relabel speakers in a meeting based on diarization results
-    /// Updates "Guest" segments to have proper speaker labels (Speaker 1, Speaker 2, etc.)
+    /// Updates segments labeled `remote_label` (e.g. "Guest") to proper speaker labels (Speaker 1, Speaker 2, etc.)
     pub async fn relabel_speakers(
         &self,
         meeting_id: &str,
-        diarization: &[(u64, u64, i32, String)],  // (start_ms, end_ms, speaker_id, speaker_label)
-    ) -> Result<usize, String> {
-        // Get all segments for this meeting that have "Guest" as speaker
-        let meeting_id_owned = meeting_id.to_string();
+        diarization: &[DiarizationTuple],
+        remote_label: &str,
+    ) -> Result<usize, SecondBrainError> {
+        // Get all segments for this meeting that still carry the remote label
+        let meeting_id_owned = normalize_record_id("meeting", meeting_id);
         let segments: Vec<TranscriptSegment> = self.db
-            .query("SELECT * FROM segment WHERE meeting_id = $meeting_id AND speaker = 'Guest'")
+            .query("SELECT * FROM segment WHERE meeting_id = $meeting_id AND speaker = $remote_label")
             .bind(("meeting_id", meeting_id_owned))
+            .bind(("remote_label", remote_label.to_string()))
             .await
-            .map_err(|e| format!("Failed to get segments: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to get segments: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract segments: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))?;
 
         let mut relabeled_count = 0;
 
@@ -2145,17 +7031,18 @@ impl KnowledgeBase {
             let segment_mid = (segment.start_ms + segment.end_ms) / 2;
 
             // Find overlapping diarization segment
-            if let Some((_, _, _, speaker_label)) = diarization.iter().find(|(start, end, _, _)| {
+            if let Some((_, _, _, speaker_label, confidence)) = diarization.iter().find(|(start, end, _, _, _)| {
                 segment_mid >= *start && segment_mid <= *end
             }) {
                 // Update the speaker label
                 if let Some(ref id) = segment.id {
                     self.db
-                        .query("UPDATE $id SET speaker = $speaker")
+                        .query("UPDATE $id SET speaker = $speaker, speaker_confidence = $confidence")
                         .bind(("id", id.clone()))
                         .bind(("speaker", speaker_label.clone()))
+                        .bind(("confidence", *confidence))
                         .await
-                        .map_err(|e| format!("Failed to update segment speaker: {}", e))?;
+                        .map_err(|e| SecondBrainError::Db(format!("Failed to update segment speaker: {}", e)))?;
 
                     relabeled_count += 1;
                 }
@@ -2171,22 +7058,22 @@ impl KnowledgeBase {
     pub async fn relabel_all_speakers(
         &self,
         meeting_id: &str,
-        diarization: &[(u64, u64, i32, String)],  // (start_ms, end_ms, speaker_id, speaker_label)
-    ) -> Result<usize, String> {
+        diarization: &[DiarizationTuple],
+    ) -> Result<usize, SecondBrainError> {
         if diarization.is_empty() {
             println!("[KB] No diarization results to apply");
             return Ok(0);
         }
 
         // Get ALL segments for this meeting (regardless of current speaker label)
-        let meeting_id_owned = meeting_id.to_string();
+        let meeting_id_owned = normalize_record_id("meeting", meeting_id);
         let segments: Vec<TranscriptSegment> = self.db
             .query("SELECT * FROM segment WHERE meeting_id = $meeting_id ORDER BY start_ms")
             .bind(("meeting_id", meeting_id_owned))
             .await
-            .map_err(|e| format!("Failed to get segments: {}", e))?
+            .map_err(|e| SecondBrainError::Db(format!("Failed to get segments: {}", e)))?
             .take(0)
-            .map_err(|e| format!("Failed to extract segments: {}", e))?;
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))?;
 
         println!("[KB] Found {} segments to potentially relabel", segments.len());
 
@@ -2197,22 +7084,23 @@ impl KnowledgeBase {
 
             // Find overlapping diarization segment by timestamp
             // Use a tolerance window since ASR and diarization timestamps may not align perfectly
-            if let Some((_, _, _, speaker_label)) = diarization.iter().find(|(start, end, _, _)| {
+            if let Some((_, _, _, speaker_label, confidence)) = diarization.iter().find(|(start, end, _, _, _)| {
                 // Check if segment midpoint falls within diarization window
                 // Or if there's any overlap
                 let overlap = segment.start_ms <= *end && segment.end_ms >= *start;
                 let midpoint_in_range = segment_mid >= *start && segment_mid <= *end;
                 overlap || midpoint_in_range
             }) {
-                // Only update if the label is different
-                if segment.speaker != *speaker_label {
+                // Only update if the label or its confidence changed
+                if segment.speaker != *speaker_label || segment.speaker_confidence != *confidence {
                     if let Some(ref id) = segment.id {
                         self.db
-                            .query("UPDATE $id SET speaker = $speaker")
+                            .query("UPDATE $id SET speaker = $speaker, speaker_confidence = $confidence")
                             .bind(("id", id.clone()))
                             .bind(("speaker", speaker_label.clone()))
+                            .bind(("confidence", *confidence))
                             .await
-                            .map_err(|e| format!("Failed to update segment speaker: {}", e))?;
+                            .map_err(|e| SecondBrainError::Db(format!("Failed to update segment speaker: {}", e)))?;
 
                         relabeled_count += 1;
                     }
@@ -2223,4 +7111,1172 @@ impl KnowledgeBase {
         println!("[KB] Relabeled {} segments with diarization results", relabeled_count);
         Ok(relabeled_count)
     }
+
+    /// Flag segments that intersect diarization's crosstalk regions (wall-clock ms)
+    /// Clears the flag on all other segments of the meeting, so re-diarizing replaces old results
+    pub async fn mark_overlapped_segments(
+        &self,
+        meeting_id: &str,
+        regions: &[(u64, u64)],
+    ) -> Result<usize, SecondBrainError> {
+        let meeting_id_owned = normalize_record_id("meeting", meeting_id);
+        let segments: Vec<TranscriptSegment> = self.db
+            .query("SELECT * FROM segment WHERE meeting_id = $meeting_id")
+            .bind(("meeting_id", meeting_id_owned.clone()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to get segments: {}", e)))?
+            .take(0)
+            .map_err(|e| SecondBrainError::Db(format!("Failed to extract segments: {}", e)))?;
+
+        let overlapped_ids: Vec<Thing> = segments
+            .into_iter()
+            .filter(|s| regions.iter().any(|(start, end)| s.start_ms < *end && s.end_ms > *start))
+            .filter_map(|s| s.id)
+            .collect();
+        let count = overlapped_ids.len();
+
+        self.db
+            .query("UPDATE segment SET overlapped = (id IN $ids) WHERE meeting_id = $meeting_id")
+            .bind(("ids", overlapped_ids))
+            .bind(("meeting_id", meeting_id_owned))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to flag overlapped segments: {}", e)))?;
+
+        Ok(count)
+    }
+
+    /// Rename a speaker label across a meeting (segments, relations, assignees, person links)
+    /// Renaming onto an existing label merges the two; all updates run in one transaction
+    pub async fn rename_speaker(
+        &self,
+        meeting_id: &str,
+        old_label: &str,
+        new_label: &str,
+    ) -> Result<usize, SecondBrainError> {
+        let new_label = new_label.trim();
+        if new_label.is_empty() {
+            return Err(SecondBrainError::InvalidInput("New speaker name cannot be empty".to_string()));
+        }
+        if old_label == new_label {
+            return Ok(0);
+        }
+
+        let id_part = normalize_record_id("meeting", meeting_id);
+
+        let counts: Vec<serde_json::Value> = self.db
+            .query("SELECT count() AS count FROM segment WHERE meeting_id = $meeting_id AND speaker = $old_label GROUP ALL")
+            .bind(("meeting_id", id_part.clone()))
+            .bind(("old_label", old_label.to_string()))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to count segments: {}", e)))?
+            .take(0)
+            .unwrap_or_default();
+        let segment_count = counts
+            .first()
+            .and_then(|v| v.get("count").and_then(|c| c.as_u64()))
+            .unwrap_or(0) as usize;
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+
+        self.db
+            .query(r#"
+                BEGIN TRANSACTION;
+
+                UPDATE segment SET speaker = $new_label
+                    WHERE meeting_id = $meeting_id AND speaker = $old_label;
+
+                UPDATE entity_relation SET source_entity = $new_label
+                    WHERE meeting_id = $meeting_id AND source_entity = $old_label;
+                UPDATE entity_relation SET target_entity = $new_label
+                    WHERE meeting_id = $meeting_id AND target_entity = $old_label;
+
+                UPDATE action_item SET assignee = $new_label
+                    WHERE meeting_id = $meeting_id AND assignee = $old_label;
+
+                UPDATE type::thing('meeting', $meeting_id)
+                    SET participants = array::distinct(array::append(array::complement(participants, [$old_label]), $new_label))
+                    WHERE participants CONTAINS $old_label;
+
+                -- Move this meeting's person link from the old label to the (possibly existing) new person
+                LET $old_people = (SELECT VALUE in FROM mentioned_in
+                    WHERE out = type::thing('meeting', $meeting_id) AND in.name = $old_label);
+                IF array::len($old_people) > 0 {
+                    UPSERT person SET
+                        name = $new_label,
+                        aliases = array::union(aliases ?? [], [$old_label]),
+                        first_seen = math::min([first_seen ?? $now, $now]),
+                        last_seen = $now
+                    WHERE name = $new_label;
+                    RELATE (SELECT * FROM person WHERE name = $new_label) -> mentioned_in -> type::thing('meeting', $meeting_id);
+                    DELETE mentioned_in WHERE out = type::thing('meeting', $meeting_id) AND in IN $old_people;
+                };
+
+                COMMIT TRANSACTION;
+            "#)
+            .bind(("meeting_id", id_part))
+            .bind(("old_label", old_label.to_string()))
+            .bind(("new_label", new_label.to_string()))
+            .bind(("now", now))
+            .await
+            .map_err(|e| SecondBrainError::Db(format!("Failed to rename speaker: {}", e)))?
+            .check()
+            .map_err(|e| SecondBrainError::Db(format!("Failed to rename speaker: {}", e)))?;
+
+        println!("[KB] Renamed speaker '{}' -> '{}' on {} segments", old_label, new_label, segment_count);
+        Ok(segment_count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_record_id_accepts_both_forms() {
+        assert_eq!(normalize_record_id("meeting", "meeting:abc"), "abc");
+        assert_eq!(normalize_record_id("meeting", "abc"), "abc");
+        assert_eq!(normalize_record_id("meeting", " meeting:abc "), "abc");
+        assert_eq!(normalize_record_id("meeting", "meeting:⟨a-b-c⟩"), "a-b-c");
+    }
+
+    #[test]
+    fn test_normalize_record_id_only_strips_own_table() {
+        assert_eq!(normalize_record_id("meeting", "segment:abc"), "segment:abc");
+        assert_eq!(normalize_record_id("meeting", "meetingabc"), "meetingabc");
+        assert_eq!(normalize_record_id("action_item", "action_item:xyz"), "xyz");
+    }
+
+    #[test]
+    fn test_blank_to_none_trims_and_clears() {
+        assert_eq!(blank_to_none(Some("  Alice ")), Some("Alice".to_string()));
+        assert_eq!(blank_to_none(Some("   ")), None);
+        assert_eq!(blank_to_none(None), None);
+    }
+
+    #[test]
+    fn test_is_assigned_to_matches_whole_names() {
+        let names = vec!["Dana Lee".to_string(), "Al".to_string()];
+        assert!(is_assigned_to(Some("dana lee"), &names));
+        assert!(is_assigned_to(Some("AL"), &names));
+        assert!(is_assigned_to(Some("Sam and Dana Lee"), &names));
+        assert!(!is_assigned_to(Some("also Sam"), &names));
+        assert!(!is_assigned_to(Some("Dana"), &names));
+        assert!(!is_assigned_to(Some("  "), &names));
+        assert!(!is_assigned_to(None, &names));
+    }
+
+    #[test]
+    fn test_meeting_id_forms_are_identical() {
+        let prefixed = MeetingId::new("meeting:abc");
+        let bare = MeetingId::new("abc");
+        assert_eq!(prefixed, bare);
+        assert_eq!(prefixed.as_str(), "abc");
+        assert_eq!(prefixed.thing_string(), "meeting:abc");
+        assert_eq!(bare.to_string(), "abc");
+    }
+
+    fn segment_match(text: &str, similarity: f32) -> SegmentWithSimilarity {
+        SegmentWithSimilarity {
+            id: None,
+            meeting_id: "abc".to_string(),
+            speaker: "You".to_string(),
+            text: text.to_string(),
+            start_ms: 0,
+            end_ms: 1000,
+            embedding: Vec::new(),
+            word_timings: Vec::new(),
+            speaker_confidence: None,
+            overlapped: false,
+            similarity,
+        }
+    }
+
+    #[test]
+    fn test_segment_matches_sorted_by_descending_similarity() {
+        let ranked = rank_segment_matches(
+            vec![segment_match("b", 0.42), segment_match("a", 0.91), segment_match("c", 0.17)],
+            None,
+        );
+        let scores: Vec<f32> = ranked.iter().map(|m| m.similarity).collect();
+        assert_eq!(scores, vec![0.91, 0.42, 0.17]);
+    }
+
+    #[test]
+    fn test_text_matches_ranked_by_occurrences_then_time() {
+        let segment = |start_ms: u64, text: &str| spoken("Dana", text, start_ms, start_ms + 1000);
+        let matches = rank_text_matches(vec![
+            segment(0, "The budget is tight"),
+            segment(1000, "Nothing here"),
+            segment(2000, "Budget, budget, BUDGET"),
+            segment(3000, "Revisit the budget"),
+        ], "budget", 10);
+
+        let starts: Vec<u64> = matches.iter().map(|m| m.segment.start_ms).collect();
+        assert_eq!(starts, vec![2000, 0, 3000]);
+        assert_eq!(matches[0].score, 3.0);
+        assert_eq!(rank_text_matches(vec![segment(0, "budget")], "budget", 0).len(), 0);
+    }
+
+    #[test]
+    fn test_segment_matches_below_min_similarity_dropped() {
+        let ranked = rank_segment_matches(
+            vec![segment_match("b", 0.42), segment_match("a", 0.91), segment_match("c", 0.17)],
+            Some(0.4),
+        );
+        let texts: Vec<&str> = ranked.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_action_text_similarity_catches_rephrased_task() {
+        let stored = "Send the pricing deck to Alice by Friday";
+        assert!(action_text_similarity(stored, "send pricing deck to alice by friday") >= ACTION_DUPLICATE_THRESHOLD);
+        assert!(action_text_similarity(stored, "Book the venue for the offsite") < ACTION_DUPLICATE_THRESHOLD);
+        assert_eq!(action_text_similarity(stored, ""), 0.0);
+    }
+
+    #[test]
+    fn test_meeting_score_boosts_title_and_items() {
+        let mut segment_only = MeetingScore::default();
+        segment_only.add_segment(0.5, "we talked about pricing");
+
+        let mut with_title = MeetingScore::default();
+        with_title.add_segment(0.5, "we talked about pricing");
+        with_title.title_match = true;
+
+        let mut with_decision = MeetingScore::default();
+        with_decision.add_segment(0.5, "we talked about pricing");
+        with_decision.add_item("Raise pricing in Q3", false);
+
+        assert!(with_title.score() > with_decision.score());
+        assert!(with_decision.score() > segment_only.score());
+    }
+
+    #[test]
+    fn test_meeting_score_keeps_best_segment_excerpt() {
+        let mut score = MeetingScore::default();
+        score.add_item("Send pricing deck", true);
+        score.add_segment(0.4, "weaker match");
+        score.add_segment(0.8, "best match");
+        score.add_segment(0.6, "middle match");
+
+        assert_eq!(score.excerpt.as_deref(), Some("best match"));
+        assert_eq!(score.segment_hits, 3);
+        let expected = 0.8 + 2.0 * MEETING_EXTRA_HIT_WEIGHT + MEETING_ITEM_BOOST;
+        assert!((score.score() - expected).abs() < 1e-6);
+    }
+
+    use chrono::Utc;
+
+    /// Wednesday 2024-06-12 15:30 UTC
+    fn fixed_now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 6, 12, 15, 30, 0).unwrap()
+    }
+
+    fn ms(year: i32, month: u32, day: u32, hour: u32) -> u64 {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap().timestamp_millis() as u64
+    }
+
+    fn range(query: &str) -> (u64, u64) {
+        let ctx = parse_temporal_expression(query, &fixed_now()).expect("temporal context");
+        (ctx.start_timestamp.unwrap(), ctx.end_timestamp.unwrap())
+    }
+
+    #[test]
+    fn test_temporal_this_morning() {
+        assert_eq!(range("what did we discuss this morning?"), (ms(2024, 6, 12, 0), ms(2024, 6, 12, 12) - 1));
+    }
+
+    #[test]
+    fn test_temporal_iso_date() {
+        assert_eq!(range("notes from the call on 2024-03-15"), (ms(2024, 3, 15, 0), ms(2024, 3, 16, 0) - 1));
+    }
+
+    #[test]
+    fn test_temporal_iso_date_range_in_either_order() {
+        let expected = (ms(2024, 3, 1, 0), ms(2024, 3, 21, 0) - 1);
+        assert_eq!(range("between 2024-03-01 and 2024-03-20"), expected);
+        assert_eq!(range("from 2024-03-20 to 2024-03-01"), expected);
+    }
+
+    #[test]
+    fn test_temporal_quarter() {
+        assert_eq!(range("roadmap decisions in Q2"), (ms(2024, 4, 1, 0), ms(2024, 7, 1, 0) - 1));
+        // Q3 hasn't started yet, so it means last year's
+        assert_eq!(range("q3 planning"), (ms(2023, 7, 1, 0), ms(2023, 10, 1, 0) - 1));
+        assert_eq!(range("q4 2022"), (ms(2022, 10, 1, 0), ms(2023, 1, 1, 0) - 1));
+    }
+
+    #[test]
+    fn test_temporal_month_range() {
+        assert_eq!(range("between March and April"), (ms(2024, 3, 1, 0), ms(2024, 5, 1, 0) - 1));
+        assert_eq!(range("from november to january"), (ms(2023, 11, 1, 0), ms(2024, 2, 1, 0) - 1));
+    }
+
+    #[test]
+    fn test_temporal_month_name() {
+        assert_eq!(range("what happened in march"), (ms(2024, 3, 1, 0), ms(2024, 4, 1, 0) - 1));
+        assert_eq!(range("the december offsite"), (ms(2023, 12, 1, 0), ms(2024, 1, 1, 0) - 1));
+        assert_eq!(range("since april"), (ms(2024, 4, 1, 0), fixed_now().timestamp_millis() as u64));
+        assert!(parse_temporal_expression("what may we have missed", &fixed_now()).is_none());
+    }
+
+    #[test]
+    fn test_temporal_months_ago() {
+        assert_eq!(range("two months ago"), (ms(2024, 4, 1, 0), ms(2024, 5, 1, 0) - 1));
+        assert_eq!(range("1 month ago"), (ms(2024, 5, 1, 0), ms(2024, 6, 1, 0) - 1));
+    }
+
+    #[test]
+    fn test_temporal_weeks_and_days_ago() {
+        assert_eq!(range("3 weeks ago"), (ms(2024, 5, 20, 0), ms(2024, 5, 27, 0) - 1));
+        assert_eq!(range("2 days ago"), (ms(2024, 6, 10, 0), ms(2024, 6, 11, 0) - 1));
+    }
+
+    #[test]
+    fn test_temporal_last_n_months() {
+        let now = fixed_now().timestamp_millis() as u64;
+        assert_eq!(range("last 3 months"), (Utc.with_ymd_and_hms(2024, 3, 12, 15, 30, 0).unwrap().timestamp_millis() as u64, now));
+        assert_eq!(range("past two weeks"), (Utc.with_ymd_and_hms(2024, 5, 29, 15, 30, 0).unwrap().timestamp_millis() as u64, now));
+    }
+
+    #[test]
+    fn test_temporal_calendar_weeks() {
+        let now = fixed_now().timestamp_millis() as u64;
+        assert_eq!(range("this week"), (ms(2024, 6, 10, 0), now));
+        assert_eq!(range("last week"), (ms(2024, 6, 3, 0), ms(2024, 6, 10, 0) - 1));
+        assert_eq!(range("last month"), (ms(2024, 5, 1, 0), ms(2024, 6, 1, 0) - 1));
+    }
+
+    #[test]
+    fn test_temporal_today_and_yesterday() {
+        assert_eq!(range("today"), (ms(2024, 6, 12, 0), fixed_now().timestamp_millis() as u64));
+        assert_eq!(range("yesterday's standup"), (ms(2024, 6, 11, 0), ms(2024, 6, 12, 0) - 1));
+    }
+
+    #[test]
+    fn test_temporal_oversized_counts_are_ignored() {
+        for query in [
+            "last 99999999 days",
+            "past 4000000000 years",
+            "2147483648 years ago",
+            "99999999999 weeks ago",
+            "3000000000 months ago",
+        ] {
+            assert!(parse_temporal_expression(query, &fixed_now()).is_none(), "{}", query);
+        }
+        assert_eq!(range("100 years ago"), (ms(1924, 1, 1, 0), ms(1925, 1, 1, 0) - 1));
+    }
+
+    #[test]
+    fn test_temporal_none_without_reference() {
+        assert!(parse_temporal_expression("what did Alice say about the launch", &fixed_now()).is_none());
+    }
+
+    fn spoken(speaker: &str, text: &str, start_ms: u64, end_ms: u64) -> TranscriptSegment {
+        TranscriptSegment {
+            id: None,
+            meeting_id: "abc".to_string(),
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            embedding: Vec::new(),
+            word_timings: Vec::new(),
+            speaker_confidence: None,
+            overlapped: false,
+            language: None,
+            emotion: None,
+            needs_embedding: false,
+        }
+    }
+
+    fn titled(title: &str) -> Meeting {
+        Meeting {
+            id: None,
+            title: title.to_string(),
+            start_time: 0,
+            end_time: None,
+            participants: Vec::new(),
+            summary: None,
+            tags: Vec::new(),
+            favorite: false,
+            private: false,
+            dominant_language: None,
+            deleted_at: None,
+        }
+    }
+
+    #[test]
+    fn test_speaker_stats_talk_time_and_words() {
+        let segments = vec![
+            spoken("You", "let's get started then", 0, 3000),
+            spoken("Speaker 1", "sure", 5000, 6000),
+            spoken("You", "first item is the launch", 8000, 14000),
+        ];
+        let stats = MeetingSpeakerStats::from_segments("abc", &segments, INTERRUPTION_GAP_MS);
+
+        assert_eq!(stats.duration_ms, 14000);
+        assert_eq!(stats.total_talk_ms, 10000);
+        assert_eq!(stats.total_words, 10);
+        assert_eq!(stats.speaker_switches, 2);
+        assert_eq!(stats.speakers[0].speaker, "You");
+        assert_eq!(stats.speakers[0].word_count, 9);
+        assert_eq!(stats.speakers[0].talk_duration_ms, 9000);
+        assert!((stats.speakers[0].talk_percentage - 90.0).abs() < 0.01);
+        assert_eq!(stats.speakers[1].segment_count, 1);
+    }
+
+    #[test]
+    fn test_speaker_stats_counts_quick_switches_as_interruptions() {
+        let segments = vec![
+            spoken("You", "so the plan is", 0, 2000),
+            spoken("Speaker 1", "wait", 1800, 2500),          // Overlaps
+            spoken("You", "let me finish", 2700, 4000),       // 200ms gap
+            spoken("You", "as I was saying", 4100, 5000),     // Same speaker
+            spoken("Speaker 1", "okay", 8000, 8500),          // Long pause
+        ];
+        let stats = MeetingSpeakerStats::from_segments("abc", &segments, INTERRUPTION_GAP_MS);
+        let by_name = |name: &str| stats.speakers.iter().find(|s| s.speaker == name).unwrap();
+
+        assert_eq!(stats.speaker_switches, 3);
+        assert_eq!(by_name("Speaker 1").interruptions, 1);
+        assert_eq!(by_name("You").interruptions, 1);
+    }
+
+    #[test]
+    fn test_speaker_stats_counts_crosstalk() {
+        let crosstalk = |mut segment: TranscriptSegment| {
+            segment.overlapped = true;
+            segment
+        };
+        let segments = vec![
+            crosstalk(spoken("You", "so the plan is", 0, 2000)),
+            crosstalk(spoken("Speaker 1", "wait", 1800, 2500)),
+            spoken("You", "let me finish", 2700, 4000),
+        ];
+        let stats = MeetingSpeakerStats::from_segments("abc", &segments, INTERRUPTION_GAP_MS);
+
+        assert_eq!(stats.crosstalk_segments, 2);
+        assert_eq!(stats.crosstalk_ms, 2700);
+        let you = stats.speakers.iter().find(|s| s.speaker == "You").unwrap();
+        assert_eq!(you.crosstalk_segments, 1);
+    }
+
+    fn pending(speaker: &str, text: &str, start_ms: u64, end_ms: u64) -> (PendingSegment, ()) {
+        let segment = PendingSegment {
+            meeting_id: "abc".to_string(),
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            word_timings: Vec::new(),
+            language: None,
+            emotion: None,
+        };
+        (segment, ())
+    }
+
+    #[test]
+    fn test_collapse_batch_duplicates_merges_overlapping_chunks() {
+        let config = SegmentDedupConfig::default();
+        let batch = vec![
+            pending("You", "let's review the quarterly budget now", 0, 2000),
+            pending("Guest", "sure", 1500, 2500),
+            pending("You", "Let's review the quarterly budget now, okay?", 1800, 4000),  // Overlapping chunk, longer
+            pending("You", "next topic is hiring", 12000, 14000),     // Outside the window
+        ];
+        let kept = collapse_batch_duplicates(&config, batch);
+
+        assert_eq!(kept.len(), 3);
+        assert_eq!(kept[0].0.text, "Let's review the quarterly budget now, okay?");
+        assert_eq!(kept[0].0.end_ms, 4000);
+        assert_eq!(kept[1].0.speaker, "Guest");
+        assert_eq!(kept[2].0.start_ms, 12000);
+
+        let disabled = SegmentDedupConfig { enabled: false, ..Default::default() };
+        let batch = vec![pending("You", "hello", 0, 1000), pending("You", "hello", 1000, 2000)];
+        assert_eq!(collapse_batch_duplicates(&disabled, batch).len(), 2);
+    }
+
+    #[test]
+    fn test_is_duplicate_needs_near_identical_text() {
+        let config = SegmentDedupConfig::default();
+        assert!(config.is_duplicate("Ship it on Friday.", 1000, "ship it on friday", 1500));
+        // A prefix is the start of a new sentence, not a repeat
+        assert!(!config.is_duplicate("yes", 1000, "yes, ship it on Friday", 1500));
+        assert!(!config.is_duplicate("let's review the", 1000, "let's review the hiring plan", 1500));
+        // Blank text never merges
+        assert!(!config.is_duplicate("  ", 1000, "...", 1500));
+        assert!(!config.is_duplicate("ship it", 1000, " ", 1500));
+        // Outside the window
+        assert!(!config.is_duplicate("ship it", 1000, "ship it", 1000 + config.window_ms + 1));
+    }
+
+    #[test]
+    fn test_dialogue_turns_merge_consecutive_speaker_segments() {
+        let segments = vec![
+            spoken("You", "how did the launch go", 0, 2000),
+            spoken("You", "from your side?", 2100, 3000),
+            spoken("Speaker 1", "pretty well", 3500, 4500),
+            spoken("Speaker 1", "  ", 4600, 4700),
+            spoken("You", "great", 5000, 5500),
+        ];
+        let turns = dialogue_turns(&segments);
+
+        assert_eq!(turns.len(), 3);
+        assert_eq!(turns[0].text, "how did the launch go from your side?");
+        assert_eq!(turns[0].start_ms, 0);
+        assert_eq!(turns[1].speaker, "Speaker 1");
+        assert_eq!(turns[1].text, "pretty well");
+        assert_eq!(turns[2].start_ms, 5000);
+    }
+
+    #[test]
+    fn test_transcript_export_mode_parse() {
+        assert_eq!(TranscriptExportMode::parse("").unwrap(), TranscriptExportMode::Chronological);
+        assert_eq!(TranscriptExportMode::parse("By_Speaker").unwrap(), TranscriptExportMode::BySpeaker);
+        assert_eq!(TranscriptExportMode::parse("dialogue").unwrap(), TranscriptExportMode::Dialogue);
+        assert!(TranscriptExportMode::parse("columns").is_err());
+    }
+
+    #[test]
+    fn test_speaker_stats_empty_meeting() {
+        let stats = MeetingSpeakerStats::from_segments("abc", &[], INTERRUPTION_GAP_MS);
+        assert_eq!(stats.duration_ms, 0);
+        assert!(stats.speakers.is_empty());
+    }
+
+    #[test]
+    fn test_meeting_id_deserializes_both_forms() {
+        let prefixed: MeetingId = serde_json::from_str("\"meeting:abc\"").unwrap();
+        let bare: MeetingId = serde_json::from_str("\"abc\"").unwrap();
+        assert_eq!(prefixed, bare);
+        assert_eq!(serde_json::to_string(&prefixed).unwrap(), "\"abc\"");
+    }
+
+    #[test]
+    fn test_check_embedding_rejects_empty_and_wrong_dimension() {
+        assert!(check_embedding(&[0.1, 0.2, 0.3], 3).is_ok());
+        assert!(check_embedding(&[], 3).is_err());
+        assert!(check_embedding(&[0.1, 0.2], 3).is_err());
+    }
+
+    #[test]
+    fn test_tag_stats_counts_sources_and_chunks() {
+        let tags = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let stats = tag_stats(&[
+            (tags(&["rust", "docs"]), 10),
+            (tags(&["rust", "rust"]), 4),
+            (tags(&[]), 7),
+        ]);
+
+        assert_eq!(stats, vec![
+            TagStats { tag: "rust".to_string(), source_count: 2, chunk_count: 14 },
+            TagStats { tag: "docs".to_string(), source_count: 1, chunk_count: 10 },
+        ]);
+    }
+
+    #[test]
+    fn test_source_refresh_due_after_interval() {
+        const HOUR: u64 = 60 * 60 * 1000;
+        let mut source = KnowledgeSource {
+            id: None,
+            url: "https://example.com/docs".to_string(),
+            title: "Docs".to_string(),
+            source_type: "url".to_string(),
+            raw_content: String::new(),
+            tags: vec![],
+            created_at: 0,
+            last_updated: 10 * HOUR,
+            last_accessed: None,
+            pinned: false,
+            refresh_interval_hours: None,
+            last_refreshed: None,
+        };
+        assert!(!source.refresh_due(100 * HOUR));
+
+        source.refresh_interval_hours = Some(24);
+        assert!(!source.refresh_due(20 * HOUR));
+        assert!(source.refresh_due(34 * HOUR));
+
+        source.last_refreshed = Some(30 * HOUR);
+        assert!(!source.refresh_due(34 * HOUR));
+
+        source.url = "file:///tmp/notes.md".to_string();
+        assert!(!source.refresh_due(100 * HOUR));
+    }
+
+    #[test]
+    fn test_retag_renames_merges_and_deletes() {
+        let tags: Vec<String> = ["ml", "ai", "docs"].iter().map(|t| t.to_string()).collect();
+        assert_eq!(retag(&tags, "ml", Some("ai")), vec!["ai", "docs"]);
+        assert_eq!(retag(&tags, "ml", Some("machine-learning")), vec!["machine-learning", "ai", "docs"]);
+        assert_eq!(retag(&tags, "ai", None), vec!["ml", "docs"]);
+    }
+
+    #[test]
+    fn test_check_raw_query_blocks_writes() {
+        assert!(check_raw_query("SELECT * FROM meeting LIMIT 5", false).is_ok());
+        assert!(check_raw_query("SELECT count() FROM segment GROUP ALL; select * FROM topic;", false).is_ok());
+        assert!(check_raw_query("SELECT * FROM meeting; USE NS other DB other", false).is_err());
+        assert!(check_raw_query("INFO FOR DB", false).is_err());
+        assert!(check_raw_query("SELECT * FROM (DELETE meeting RETURN BEFORE)", false).is_err());
+        assert!(check_raw_query("select * from meeting; delete segment", false).is_err());
+        assert!(check_raw_query("REMOVE TABLE topic", false).is_err());
+        assert!(check_raw_query("DEFINE FIELD x ON meeting TYPE string", true).is_ok());
+        assert!(check_raw_query("SELECT * FROM deleted_meeting", false).is_ok());
+        assert!(check_raw_query("   ", true).is_err());
+    }
+
+    #[test]
+    fn test_past_retention_spares_favorites_keep_tag_and_open_meetings() {
+        let meeting = |start_time: u64, ended: bool, favorite: bool, tags: &[&str]| Meeting {
+            id: None,
+            title: "Standup".to_string(),
+            start_time,
+            end_time: ended.then_some(start_time + 1000),
+            participants: Vec::new(),
+            summary: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            favorite,
+            private: false,
+            dominant_language: None,
+            deleted_at: None,
+        };
+        let cutoff = 10_000;
+
+        assert!(past_retention(&meeting(5_000, true, false, &["weekly"]), cutoff));
+        assert!(!past_retention(&meeting(20_000, true, false, &[]), cutoff));
+        assert!(!past_retention(&meeting(5_000, false, false, &[]), cutoff));
+        assert!(!past_retention(&meeting(5_000, true, true, &[]), cutoff));
+        assert!(!past_retention(&meeting(5_000, true, false, &["Keep"]), cutoff));
+    }
+
+    #[test]
+    fn test_meeting_duration_estimated_from_last_segment() {
+        let meeting = |end_time: Option<u64>| Meeting { start_time: 1_000, end_time, ..titled("Standup") };
+
+        assert_eq!(meeting_duration_ms(&meeting(Some(61_000)), Some(90_000)), (60_000, false));
+        assert_eq!(meeting_duration_ms(&meeting(None), Some(31_000)), (30_000, true));
+        assert_eq!(meeting_duration_ms(&meeting(None), None), (0, true));
+    }
+
+    #[test]
+    fn test_mentions_ranked_by_count_then_meetings() {
+        let pair = |name: &str, meeting: &str| (name.to_string(), meeting.to_string());
+        let pairs = vec![
+            pair("launch", "m1"), pair("launch", "m1"), pair("launch", "m2"),
+            pair("hiring", "m1"), pair("hiring", "m3"),
+            pair("budget", "m2"), pair("budget", "m2"),
+            pair("offsite", "m3"),
+        ];
+        let ranked = rank_mentions(&pairs, 3);
+
+        let names: Vec<&str> = ranked.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["launch", "hiring", "budget"]);
+        assert_eq!(ranked[0], MentionCount { name: "launch".to_string(), mentions: 3, meetings: 2 });
+        assert_eq!(ranked[2].meetings, 1);
+    }
+
+    #[test]
+    fn test_daily_totals_group_by_start_day() {
+        let day = 86_400_000;
+        let days = daily_totals(&[(day + 1_000, 60_000), (day + 5_000, 30_000), (3 * day, 10_000)], &chrono::Utc);
+        assert_eq!(days, vec![
+            AnalyticsDay { date: "1970-01-02".to_string(), meetings: 2, duration_ms: 90_000 },
+            AnalyticsDay { date: "1970-01-04".to_string(), meetings: 1, duration_ms: 10_000 },
+        ]);
+    }
+
+
+    #[test]
+    fn test_private_meetings_stay_out_of_graph_rag_context() {
+        let meeting = |title: &str, private: bool| Meeting {
+            summary: Some(format!("{} summary", title)),
+            private,
+            ..titled(title)
+        };
+        let segment = |meeting_id: &str, text: &str| TranscriptSegment {
+            meeting_id: meeting_id.to_string(),
+            ..spoken("Dana", text, 0, 1000)
+        };
+        let action = |meeting_id: &str, text: &str| ActionItem {
+            id: None,
+            meeting_id: meeting_id.to_string(),
+            text: text.to_string(),
+            assignee: None,
+            deadline: None,
+            status: "open".to_string(),
+            created_at: 0,
+        };
+        let decision = |meeting_id: &str, text: &str| Decision {
+            id: None,
+            meeting_id: meeting_id.to_string(),
+            text: text.to_string(),
+            participants: Vec::new(),
+            created_at: 0,
+        };
+
+        let mut context = GraphRAGContext {
+            query_entities: Vec::new(),
+            related_meetings: vec![
+                MeetingContext { meeting: meeting("Roadmap", false), days_ago: 1, relevant_segments: vec![
+                    segment("meeting:roadmap", "ship in May"),
+                    segment("meeting:⟨secret⟩", "layoffs next quarter"),
+                ] },
+                MeetingContext { meeting: meeting("HR review", true), days_ago: 2, relevant_segments: vec![
+                    segment("secret", "layoffs next quarter"),
+                ] },
+            ],
+            related_people: Vec::new(),
+            related_topics: Vec::new(),
+            open_actions: vec![action("roadmap", "Draft plan"), action("meeting:secret", "Prepare layoffs list")],
+            recent_decisions: vec![decision("meeting:roadmap", "Ship in May"), decision("secret", "Cut two teams")],
+            pinned_chunks: Vec::new(),
+            similar_chunks: Vec::new(),
+            temporal_context: None,
+        };
+        let private = std::collections::HashSet::from(["secret".to_string()]);
+        context.exclude_private(&private);
+
+        let rendered = serde_json::to_string(&context).unwrap();
+        for leaked in ["HR review", "layoffs", "Cut two teams"] {
+            assert!(!rendered.contains(leaked), "private content leaked: {}", leaked);
+        }
+        assert_eq!(context.related_meetings.len(), 1);
+        assert_eq!(context.related_meetings[0].relevant_segments.len(), 1);
+        assert_eq!(context.open_actions.len(), 1);
+        assert_eq!(context.recent_decisions.len(), 1);
+    }
+
+    #[test]
+    fn test_language_breakdown() {
+        let tags = |langs: &[&str]| langs.iter()
+            .map(|l| if l.is_empty() { None } else { Some(l.to_string()) })
+            .collect::<Vec<_>>();
+
+        let breakdown = language_breakdown("abc", &tags(&["<|en|>", "en", "en", "de", ""]));
+        assert_eq!(breakdown.dominant_language.as_deref(), Some("en"));
+        assert_eq!(breakdown.untagged_segments, 1);
+        assert_eq!(breakdown.languages[0], LanguageShare { language: "en".to_string(), segments: 3, percentage: 75.0 });
+        assert!(breakdown.code_switching);
+
+        // Genuine code-switching: no language dominates
+        let mixed = language_breakdown("abc", &tags(&["en", "zh", "en", "zh", "ja"]));
+        assert_eq!(mixed.dominant_language, None);
+        assert!(mixed.code_switching);
+
+        let old = language_breakdown("abc", &tags(&["", "auto"]));
+        assert!(old.languages.is_empty());
+        assert_eq!(old.dominant_language, None);
+        assert!(!old.code_switching);
+    }
+
+    #[test]
+    fn test_meeting_sentiment_timeline_and_speakers() {
+        let felt = |speaker: &str, start_ms: u64, emotion: Option<&str>| TranscriptSegment {
+            emotion: emotion.map(|e| e.to_string()),
+            ..spoken(speaker, "words", start_ms, start_ms + 5_000)
+        };
+        let start = 1_000_000;
+        let segments = vec![
+            felt("You", start, Some("Happy")),
+            felt("Guest", start + 10_000, Some("<|NEUTRAL|>")),
+            felt("Guest", start + 70_000, Some("angry")),
+            felt("You", start + 75_000, None),
+            felt("Guest", start + 190_000, Some("angry")),
+        ];
+
+        let sentiment = MeetingSentiment::from_segments("abc", start, &segments, 60_000);
+        assert_eq!(sentiment.untagged_segments, 1);
+        let scores: Vec<Option<f32>> = sentiment.timeline.iter().map(|b| b.score).collect();
+        assert_eq!(scores, vec![Some(0.5), Some(-1.0), None, Some(-1.0)]);
+        assert_eq!(sentiment.timeline[3].start_ms, 180_000);
+        assert_eq!(sentiment.timeline[1].dominant_emotion.as_deref(), Some("angry"));
+
+        assert_eq!(sentiment.speakers[0].speaker, "Guest");
+        assert_eq!(sentiment.speakers[0].emotions[0], EmotionShare { emotion: "angry".to_string(), segments: 2, percentage: 66.7 });
+        assert_eq!(sentiment.speakers[1].score, Some(1.0));
+
+        // A long meeting is split into at most MAX_SENTIMENT_BUCKETS buckets
+        let long = MeetingSentiment::from_segments("abc", start, &[felt("You", start + 10 * 3_600_000, Some("sad"))], 1_000);
+        assert!(long.timeline.len() as u64 <= MAX_SENTIMENT_BUCKETS + 1);
+        assert_eq!(normalize_emotion("EMO_UNKNOWN"), None);
+    }
+
+    #[test]
+    fn test_retrieval_config_from_settings() {
+        assert_eq!(RetrievalConfig::from_config("").unwrap(), RetrievalConfig::default());
+        let config = RetrievalConfig::from_config(r#"{"max_meetings": 8, "context_length": 32768}"#).unwrap();
+        assert_eq!(config.max_meetings, 8);
+        assert_eq!(config.max_chunks, RetrievalConfig::default().max_chunks);
+        assert_eq!(config.context_length, Some(32768));
+
+        assert!(RetrievalConfig::from_config(r#"{"max_meeting": 8}"#).is_err());
+        assert!(RetrievalConfig::from_config(r#"{"context_length": 100}"#).is_err());
+    }
+
+    #[test]
+    fn test_retrieval_config_fits_context() {
+        let config = RetrievalConfig { max_topics: 0, ..RetrievalConfig::default() };
+        assert_eq!(config.fit_to_context(8192), config);
+
+        let small = config.fit_to_context(2048);
+        assert_eq!(small.max_meetings, 1);
+        assert_eq!(small.max_chunks, 1);
+        assert_eq!(small.max_topics, 0);
+
+        // The configured counts are a ceiling, however large the window
+        assert_eq!(config.fit_to_context(1_000_000), config);
+    }
+
+    #[test]
+    fn test_name_distance() {
+        assert_eq!(name_distance("Jon", "John"), 1);
+        assert_eq!(name_distance("priya", "Priya"), 0);
+        assert_eq!(name_distance("Kubernetes", "Kubernetis"), 1);
+        assert_eq!(name_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_best_name_match_prefers_exact_and_aliases() {
+        let entities = vec![
+            vec!["John Smith", "John"],
+            vec!["Joan"],
+            vec!["Priya Raman", "Priya"],
+        ];
+        // "Jon" is one edit from both "John" and "Joan": the earlier entity wins
+        assert_eq!(best_name_match("Jon", &entities, 2), Some(0));
+        // An exact match beats an earlier fuzzy one, and aliases count as names
+        assert_eq!(best_name_match("joan", &entities, 2), Some(1));
+        assert_eq!(best_name_match("priya", &entities, 2), Some(2));
+        assert_eq!(best_name_match("Pria", &entities, 2), Some(2));
+        assert_eq!(best_name_match("Jon", &entities, 0), None);
+        // Short names only match exactly
+        assert_eq!(best_name_match("Al", &[vec!["Bo"]], 2), None);
+        assert_eq!(best_name_match("Zachary", &entities, 2), None);
+    }
+
+    /// Empty on-disk database for running the knowledge base's queries directly
+    async fn scratch_db(name: &str) -> Surreal<Db> {
+        let dir = std::env::temp_dir().join(format!("second-brain-test-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let db = Surreal::new::<RocksDb>(dir.to_str().unwrap()).await.unwrap();
+        db.use_ns("second_brain").use_db("knowledge").await.unwrap();
+        db
+    }
+
+    #[derive(Deserialize)]
+    struct MeetingHit {
+        meeting_id: String,
+    }
+
+    #[tokio::test]
+    async fn test_meeting_search_leaves_out_private_meetings() {
+        let db = scratch_db("meeting-search").await;
+        db.query(r#"
+            CREATE meeting:public SET title = 'Launch sync', start_time = 0, participants = [], private = false;
+            CREATE meeting:secret SET title = 'Launch HR review', start_time = 0, participants = [], private = true;
+            CREATE segment SET meeting_id = 'public', text = 'launch moves to May', embedding = [1.0, 0.0];
+            CREATE segment SET meeting_id = 'meeting:secret', text = 'launch layoffs after', embedding = [1.0, 0.0];
+            CREATE action_item SET meeting_id = 'public', text = 'Launch checklist';
+            CREATE action_item SET meeting_id = 'secret', text = 'Launch severance letters';
+            CREATE decision SET meeting_id = 'meeting:public', text = 'Launch in May';
+            CREATE decision SET meeting_id = 'secret', text = 'Launch with two teams cut';
+        "#).await.unwrap().check().unwrap();
+
+        let hidden = meeting_id_forms(meeting_ids_where(&db, HIDDEN_MEETINGS).await.unwrap());
+        let mut response = db.query(MEETING_SEARCH_QUERY)
+            .bind(("embedding", vec![1.0f32, 0.0]))
+            .bind(("hits", 10))
+            .bind(("hidden", hidden))
+            .bind(("needle", "launch"))
+            .await
+            .unwrap();
+        let segments: Vec<MeetingHit> = response.take(0).unwrap();
+        let titles: Vec<Meeting> = response.take(1).unwrap();
+        let actions: Vec<MeetingHit> = response.take(2).unwrap();
+        let decisions: Vec<MeetingHit> = response.take(3).unwrap();
+
+        let title_ids: Vec<String> = titles.iter()
+            .map(|m| normalize_record_id("meeting", &m.id.as_ref().unwrap().to_string()))
+            .collect();
+        assert_eq!(title_ids, vec!["public"]);
+        for hits in [&segments, &actions, &decisions] {
+            let ids: Vec<String> = hits.iter().map(|h| normalize_record_id("meeting", &h.meeting_id)).collect();
+            assert_eq!(ids, vec!["public"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_person_dossier_leaves_out_private_meetings() {
+        let db = scratch_db("dossier-private").await;
+        db.query(r#"
+            CREATE meeting:public SET title = 'Roadmap sync', start_time = 0, participants = [], private = false;
+            CREATE meeting:secret SET title = 'Ana performance review', start_time = 0, participants = [], private = true;
+            CREATE person:ana SET name = 'Ana';
+            CREATE person:ben SET name = 'Ben';
+            CREATE person:hr SET name = 'Hana';
+            RELATE person:ana -> mentioned_in -> meeting:public;
+            RELATE person:ben -> mentioned_in -> meeting:public;
+            RELATE person:ana -> mentioned_in -> meeting:secret;
+            RELATE person:hr -> mentioned_in -> meeting:secret;
+            CREATE entity_relation SET meeting_id = 'public', source_entity = 'Ana', source_type = 'person', relation = 'works_on', target_entity = 'roadmap', target_type = 'project', confidence = 0.9;
+            CREATE entity_relation SET meeting_id = 'meeting:secret', source_entity = 'Ana', source_type = 'person', relation = 'works_on', target_entity = 'exit plan', target_type = 'project', confidence = 0.9;
+            CREATE action_item SET meeting_id = 'public', text = 'Ana drafts the roadmap', assignee = 'Ana', status = 'open', created_at = 1;
+            CREATE action_item SET meeting_id = 'secret', text = 'Ana signs the warning', assignee = 'Ana', status = 'open', created_at = 2;
+        "#).await.unwrap().check().unwrap();
+        let hidden = meeting_id_forms(meeting_ids_where(&db, HIDDEN_MEETINGS).await.unwrap());
+        let names = vec!["Ana".to_string()];
+
+        let relations: Vec<MeetingHit> = db.query(PERSON_RELATIONS_QUERY)
+            .bind(("names", names.clone()))
+            .bind(("hidden", hidden.clone()))
+            .await.unwrap().take(0).unwrap();
+        let relation_ids: Vec<String> = relations.iter().map(|r| normalize_record_id("meeting", &r.meeting_id)).collect();
+        assert_eq!(relation_ids, vec!["public"]);
+
+        let meetings: Vec<Meeting> = db.query(PERSON_MEETINGS_QUERY)
+            .bind(("person", Thing::from(("person", "ana"))))
+            .await.unwrap().take(0).unwrap();
+        let titles: Vec<&str> = meetings.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(titles, vec!["Roadmap sync"]);
+
+        let actions: Vec<MeetingHit> = db.query(OPEN_ACTIONS_QUERY)
+            .bind(("deleted", hidden.clone()))
+            .await.unwrap().take(0).unwrap();
+        let action_ids: Vec<String> = actions.iter().map(|a| normalize_record_id("meeting", &a.meeting_id)).collect();
+        assert_eq!(action_ids, vec!["public"]);
+
+        let related: Vec<String> = db.query(RELATED_PEOPLE_QUERY)
+            .bind(("name", "Ana"))
+            .await.unwrap().take(0).unwrap();
+        assert_eq!(related, vec!["Ben"]);
+
+        // The Graph-RAG people and topic context read the same relations
+        let topics: Vec<serde_json::Value> = db.query(PERSON_TOPICS_QUERY)
+            .bind(("names", names))
+            .bind(("hidden", hidden.clone()))
+            .await.unwrap().take(0).unwrap();
+        assert_eq!(topics, vec![serde_json::json!({ "target_entity": "roadmap" })]);
+        let people: Vec<serde_json::Value> = db.query(TOPIC_PEOPLE_QUERY)
+            .bind(("names", vec!["exit plan".to_string()]))
+            .bind(("hidden", hidden))
+            .await.unwrap().take(0).unwrap();
+        assert!(people.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_trashed_meetings_leave_listings_until_restored() {
+        let db = scratch_db("trash").await;
+        db.query(r#"
+            CREATE meeting:weekly SET title = 'Weekly', start_time = 0, participants = [];
+            CREATE meeting:other SET title = 'Other', start_time = 0, participants = [];
+            CREATE action_item SET meeting_id = 'weekly', text = 'Send notes', status = 'open', created_at = 2;
+            CREATE action_item SET meeting_id = 'meeting:other', text = 'Book room', status = 'open', created_at = 1;
+            CREATE decision SET meeting_id = 'meeting:weekly', text = 'Ship Friday', participants = [], created_at = 2;
+            CREATE decision SET meeting_id = 'other', text = 'Keep the room', participants = [], created_at = 1;
+        "#).await.unwrap().check().unwrap();
+
+        // (meetings, open actions, decisions) as the listings show them
+        async fn listed(db: &Surreal<Db>) -> (Vec<String>, Vec<String>, Vec<String>) {
+            let deleted = meeting_id_forms(meeting_ids_where(db, TRASHED_MEETINGS).await.unwrap());
+            let meetings: Vec<Meeting> = db
+                .query(format!("SELECT * FROM meeting{} ORDER BY title", meeting_filter_clause(&None, false, None, &None)))
+                .await.unwrap().take(0).unwrap();
+            let actions: Vec<ActionItem> = db.query(OPEN_ACTIONS_QUERY)
+                .bind(("deleted", deleted.clone()))
+                .await.unwrap().take(0).unwrap();
+            let decisions: Vec<Decision> = db.query(RECENT_DECISIONS_QUERY)
+                .bind(("deleted", deleted))
+                .bind(("limit", 10))
+                .await.unwrap().take(0).unwrap();
+            (
+                meetings.into_iter().map(|m| m.title).collect(),
+                actions.into_iter().map(|a| a.text).collect(),
+                decisions.into_iter().map(|d| d.text).collect(),
+            )
+        }
+        async fn set_deleted_at(db: &Surreal<Db>, deleted_at: Option<u64>) {
+            db.query(SET_DELETED_AT_QUERY)
+                .bind(("id", "weekly"))
+                .bind(("deleted_at", deleted_at))
+                .await.unwrap().check().unwrap();
+        }
+
+        let everything = (
+            vec!["Other".to_string(), "Weekly".to_string()],
+            vec!["Send notes".to_string(), "Book room".to_string()],
+            vec!["Ship Friday".to_string(), "Keep the room".to_string()],
+        );
+        assert_eq!(listed(&db).await, everything);
+
+        set_deleted_at(&db, Some(1_000)).await;
+        assert_eq!(listed(&db).await, (
+            vec!["Other".to_string()],
+            vec!["Book room".to_string()],
+            vec!["Keep the room".to_string()],
+        ));
+
+        set_deleted_at(&db, None).await;
+        assert_eq!(listed(&db).await, everything);
+    }
+
+    #[tokio::test]
+    async fn test_clearing_a_meeting_takes_back_its_topic_mentions() {
+        let db = scratch_db("clear-entities").await;
+        db.query(r#"
+            CREATE topic:budget SET name = 'budget', embedding = [], mention_count = 3, last_mentioned = 0;
+            RELATE topic:budget -> discussed_in -> meeting:weekly;
+            RELATE topic:budget -> discussed_in -> meeting:weekly;
+            RELATE topic:budget -> discussed_in -> meeting:other;
+            CREATE entity_relation SET meeting_id = 'weekly', source_entity = 'Ana', relation = 'works_on', target_entity = 'budget';
+        "#).await.unwrap().check().unwrap();
+
+        db.query(CLEAR_MEETING_ENTITIES_QUERY).bind(("id", "weekly")).await.unwrap().check().unwrap();
+
+        let counts: Vec<u32> = db.query("SELECT VALUE mention_count FROM topic:budget").await.unwrap().take(0).unwrap();
+        assert_eq!(counts, vec![1]);
+        let edges: Vec<Thing> = db.query("SELECT VALUE out FROM discussed_in").await.unwrap().take(0).unwrap();
+        assert_eq!(edges.len(), 1);
+        let relations: Vec<Thing> = db.query("SELECT VALUE id FROM entity_relation").await.unwrap().take(0).unwrap();
+        assert!(relations.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_person_last_seen_follows_the_segment_time() {
+        let db = scratch_db("person-seen").await;
+        db.query("CREATE person SET name = 'Ana', aliases = [], first_seen = 5_000, last_seen = 9_000")
+            .await.unwrap().check().unwrap();
+
+        // Reprocessing an older meeting neither moves last_seen back nor to "now"
+        for seen_at in [2_000u64, 7_000] {
+            db.query(UPSERT_PERSON_QUERY)
+                .bind(("name", "Ana"))
+                .bind(("seen_at", seen_at))
+                .await.unwrap().check().unwrap();
+        }
+
+        let people: Vec<Person> = db.query("SELECT * FROM person").await.unwrap().take(0).unwrap();
+        assert_eq!(people.len(), 1);
+        assert_eq!((people[0].first_seen, people[0].last_seen), (2_000, 9_000));
+    }
+
+    #[tokio::test]
+    async fn test_person_lookup_finds_exact_names_and_aliases() {
+        let db = scratch_db("person-lookup").await;
+        db.query(r#"
+            DEFINE INDEX idx_person_name ON person FIELDS name UNIQUE;
+            DEFINE INDEX idx_person_aliases ON person FIELDS aliases;
+            CREATE person SET name = 'Robert Smith', aliases = ['Bob'], first_seen = 0, last_seen = 0;
+            CREATE person SET name = 'Alice', aliases = [], first_seen = 0, last_seen = 0;
+        "#).await.unwrap().check().unwrap();
+
+        #[derive(Deserialize)]
+        struct Found {
+            name: String,
+        }
+        async fn lookup(db: &Surreal<Db>, name: &str) -> Vec<String> {
+            db.query(PERSON_BY_NAME_QUERY)
+                .bind(("name", name.to_string()))
+                .await.unwrap().take::<Vec<Found>>(0).unwrap()
+                .into_iter().map(|p| p.name).collect()
+        }
+
+        assert_eq!(lookup(&db, "Bob").await, vec!["Robert Smith".to_string()]);
+        assert_eq!(lookup(&db, "Alice").await, vec!["Alice".to_string()]);
+        // Typos fall through to the fuzzy match over the candidates
+        assert!(lookup(&db, "Alise").await.is_empty());
+    }
+
+    fn entity(text: &str, label: &str) -> Entity {
+        Entity { text: text.to_string(), label: label.to_string(), sequence: 0, confidence: 0.9 }
+    }
+
+    #[test]
+    fn test_entity_changes_of_an_edit() {
+        let old = vec![entity("Budget", "topic"), entity("Ana", "person")];
+        let new = vec![entity("budget", "topic"), entity("Ben", "person")];
+        let (added, removed) = entity_changes(&old, new);
+        assert_eq!(added.iter().map(|e| e.text.as_str()).collect::<Vec<_>>(), vec!["Ben"]);
+        assert_eq!(removed.iter().map(|e| e.text.as_str()).collect::<Vec<_>>(), vec!["Ana"]);
+    }
+
+    #[tokio::test]
+    async fn test_editing_out_a_mention_takes_it_back() {
+        let db = scratch_db("forget-mention").await;
+        db.query(r#"
+            CREATE topic:budget SET name = 'budget', embedding = [], mention_count = 2, last_mentioned = 0;
+            CREATE person:ana SET name = 'Ana', aliases = [], first_seen = 0, last_seen = 0;
+            RELATE topic:budget -> discussed_in -> meeting:weekly;
+            RELATE topic:budget -> discussed_in -> meeting:other;
+            RELATE person:ana -> mentioned_in -> meeting:weekly;
+        "#).await.unwrap().check().unwrap();
+
+        for (query, name) in [(FORGET_TOPIC_MENTION_QUERY, "budget"), (FORGET_PERSON_MENTION_QUERY, "Ana")] {
+            db.query(query)
+                .bind(("name", name))
+                .bind(("meeting_id", "weekly"))
+                .await.unwrap().check().unwrap();
+        }
+        // A meeting without the mention leaves the count alone
+        db.query(FORGET_TOPIC_MENTION_QUERY)
+            .bind(("name", "budget"))
+            .bind(("meeting_id", "planning"))
+            .await.unwrap().check().unwrap();
+
+        let counts: Vec<u32> = db.query("SELECT VALUE mention_count FROM topic:budget").await.unwrap().take(0).unwrap();
+        assert_eq!(counts, vec![1]);
+        let topic_edges: Vec<Thing> = db.query("SELECT VALUE out FROM discussed_in").await.unwrap().take(0).unwrap();
+        assert_eq!(topic_edges, vec![Thing::from(("meeting", "other"))]);
+        let person_edges: Vec<Thing> = db.query("SELECT VALUE id FROM mentioned_in").await.unwrap().take(0).unwrap();
+        assert!(person_edges.is_empty());
+    }
+
+    #[derive(Deserialize)]
+    struct IdRow {
+        id: Thing,
+    }
+
+    #[tokio::test]
+    async fn test_meeting_getters_accept_both_id_forms() {
+        let db = scratch_db("meeting-id-forms").await;
+        db.query(r#"
+            CREATE meeting:abc SET title = 'Standup', start_time = 0, participants = [];
+            CREATE segment:s1 SET meeting_id = 'abc', speaker = 'Dana', text = 'ship it', start_ms = 0, end_ms = 1000;
+            CREATE action_item:a1 SET meeting_id = 'abc', text = 'Send notes', status = 'open', created_at = 1;
+            CREATE decision:d1 SET meeting_id = 'abc', text = 'Ship Friday', participants = [], created_at = 1;
+            CREATE meeting_bookmark:b1 SET meeting_id = 'abc', timestamp_ms = 500;
+            CREATE topic:launch SET name = 'launch';
+            CREATE person:dana SET name = 'Dana';
+            RELATE topic:launch->discussed_in->meeting:abc;
+            RELATE person:dana->mentioned_in->meeting:abc;
+        "#).await.unwrap().check().unwrap();
+
+        for query in [
+            MEETING_SEGMENTS_QUERY,
+            MEETING_ACTION_ITEMS_QUERY,
+            MEETING_DECISIONS_QUERY,
+            MEETING_BOOKMARKS_QUERY,
+            MEETING_TOPICS_QUERY,
+            MEETING_PEOPLE_QUERY,
+        ] {
+            let mut found = Vec::new();
+            for form in ["meeting:abc", "abc"] {
+                let rows: Vec<IdRow> = db.query(query)
+                    .bind(("meeting_id", normalize_record_id("meeting", form)))
+                    .await
+                    .unwrap()
+                    .take(0)
+                    .unwrap();
+                found.push(rows.into_iter().map(|row| row.id.to_string()).collect::<Vec<_>>());
+            }
+            assert_eq!(found[0].len(), 1, "{}", query);
+            assert_eq!(found[0], found[1], "{}", query);
+        }
+    }
 }
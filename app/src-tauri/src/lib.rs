@@ -8,18 +8,26 @@ use tauri::{
 // - RwLock for read-heavy engines (initialized once, read many times)
 // - Mutex for write-heavy state (audio buffers, etc.)
 use parking_lot::{Mutex, RwLock};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 
 // ============================================================================
 // Adaptive Audio Chunking Configuration
 // ============================================================================
 
+/// Audio at the start of each recording used to estimate a source's noise floor
+const CALIBRATION_MS: u64 = 1000;
+/// Silence threshold as a multiple of the measured noise floor
+const NOISE_FLOOR_MARGIN: f32 = 1.5;
+/// Bounds for calibrated silence thresholds (digital silence / very noisy rooms)
+const MIN_SILENCE_THRESHOLD: f32 = 0.0003;
+const MAX_SILENCE_THRESHOLD: f32 = 0.03;
+
 /// Configuration for adaptive audio chunking based on energy levels
-#[derive(Clone)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct AdaptiveChunkConfig {
-    /// Minimum chunk size in samples (during active speech) - ~50ms at 16kHz
+    /// Minimum chunk size in samples (during active speech) - ~50ms at `TARGET_SAMPLE_RATE`
     pub min_chunk_samples: usize,
-    /// Maximum chunk size in samples (during silence) - ~250ms at 16kHz
+    /// Maximum chunk size in samples (during silence) - ~250ms at `TARGET_SAMPLE_RATE`
     pub max_chunk_samples: usize,
     /// RMS threshold to detect speech activity (typical speech: 0.02-0.1)
     pub speech_threshold: f32,
@@ -29,8 +37,21 @@ pub struct AdaptiveChunkConfig {
     pub silence_holdoff_chunks: u32,
     /// Minimum time between emissions in ms (to prevent too frequent updates)
     pub min_emit_interval_ms: u64,
+    /// Re-derive the thresholds from the first second of audio of each recording
+    #[serde(default = "default_true")]
+    pub auto_calibrate: bool,
+    /// Trailing audio (ms) of each chunk re-sent with the next one so boundary words aren't cut
+    #[serde(default = "default_overlap_ms")]
+    pub overlap_ms: u64,
+}
+
+fn default_overlap_ms() -> u64 {
+    150
 }
 
+/// Longest chunk overlap accepted by `set_audio_chunk_config`
+const MAX_CHUNK_OVERLAP_MS: u64 = 1000;
+
 impl Default for AdaptiveChunkConfig {
     fn default() -> Self {
         Self {
@@ -40,10 +61,102 @@ impl Default for AdaptiveChunkConfig {
             silence_threshold: 0.003,   // RMS level indicating silence
             silence_holdoff_chunks: 3,  // Wait 3 silent chunks before switching
             min_emit_interval_ms: 40,   // At least 40ms between emissions
+            auto_calibrate: true,
+            overlap_ms: default_overlap_ms(),
+        }
+    }
+}
+
+impl AdaptiveChunkConfig {
+    /// Copy with thresholds set from a measured ambient noise RMS
+    /// Silence sits just above the noise floor; speech keeps the configured speech/silence ratio.
+    pub fn calibrated(&self, noise_rms: f32) -> Self {
+        let ratio = (self.speech_threshold / self.silence_threshold.max(f32::EPSILON)).max(2.0);
+        let silence_threshold = (noise_rms * NOISE_FLOOR_MARGIN)
+            .clamp(MIN_SILENCE_THRESHOLD, MAX_SILENCE_THRESHOLD);
+        Self {
+            silence_threshold,
+            speech_threshold: silence_threshold * ratio,
+            ..self.clone()
+        }
+    }
+}
+
+/// Separate chunking configs per source: a close mic and loopback audio have very different noise floors
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct SourceChunkConfigs {
+    pub microphone: AdaptiveChunkConfig,
+    pub system: AdaptiveChunkConfig,
+}
+
+impl SourceChunkConfigs {
+    pub fn for_source(&self, source: &AudioSource) -> &AdaptiveChunkConfig {
+        match source {
+            AudioSource::Microphone => &self.microphone,
+            AudioSource::SystemAudio => &self.system,
+        }
+    }
+
+    pub fn set(&mut self, source: &AudioSource, config: AdaptiveChunkConfig) {
+        match source {
+            AudioSource::Microphone => self.microphone = config,
+            AudioSource::SystemAudio => self.system = config,
+        }
+    }
+}
+
+/// Parse a "microphone" / "system" source name from the frontend
+fn parse_audio_source(source: &str) -> Result<AudioSource, String> {
+    match source {
+        "microphone" | "mic" => Ok(AudioSource::Microphone),
+        "system" | "system_audio" => Ok(AudioSource::SystemAudio),
+        other => Err(format!("Unknown audio source '{}' (expected microphone or system)", other)),
+    }
+}
+
+/// Estimates a source's noise floor from the quiet part of its audio
+/// Uses a low percentile of per-packet RMS so speech during calibration doesn't skew it.
+#[derive(Default)]
+struct NoiseFloorEstimator {
+    packet_rms: Vec<f32>,
+    samples: u64,
+}
+
+impl NoiseFloorEstimator {
+    /// Record one packet of mono samples
+    fn observe(&mut self, samples: &[f32]) {
+        if !samples.is_empty() {
+            self.packet_rms.push(AdaptiveChunkState::calculate_rms(samples));
+            self.samples += samples.len() as u64;
+        }
+    }
+
+    fn elapsed_ms(&self, sample_rate: u32) -> u64 {
+        asr::samples_to_ms(self.samples, sample_rate)
+    }
+
+    /// 20th percentile packet RMS, or None before any audio
+    fn noise_floor(&self) -> Option<f32> {
+        if self.packet_rms.is_empty() {
+            return None;
         }
+        let mut sorted = self.packet_rms.clone();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+        Some(sorted[sorted.len() / 5])
     }
 }
 
+/// Average interleaved channels down to mono
+fn stereo_to_mono(samples: &[f32], channels: u16) -> Vec<f32> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    let channels = channels as usize;
+    samples.chunks_exact(channels)
+        .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+        .collect()
+}
+
 /// State for adaptive chunking
 struct AdaptiveChunkState {
     /// Current target chunk size
@@ -56,6 +169,8 @@ struct AdaptiveChunkState {
     last_emit: std::time::Instant,
     /// Config
     config: AdaptiveChunkConfig,
+    /// Noise floor estimation, until the first second of audio has been seen
+    calibration: Option<NoiseFloorEstimator>,
 }
 
 impl AdaptiveChunkState {
@@ -65,10 +180,26 @@ impl AdaptiveChunkState {
             silent_chunk_count: 0,
             in_speech: false,
             last_emit: std::time::Instant::now(),
+            calibration: config.auto_calibrate.then(NoiseFloorEstimator::default),
             config,
         }
     }
 
+    /// Feed incoming mono audio to auto-calibration
+    /// Returns the measured noise floor once, when calibration completes.
+    fn observe(&mut self, samples: &[f32], sample_rate: u32) -> Option<f32> {
+        let estimator = self.calibration.as_mut()?;
+        estimator.observe(samples);
+        if estimator.elapsed_ms(sample_rate) < CALIBRATION_MS {
+            return None;
+        }
+        let noise_floor = estimator.noise_floor();
+        self.calibration = None;
+        let noise_floor = noise_floor?;
+        self.config = self.config.calibrated(noise_floor);
+        Some(noise_floor)
+    }
+
     /// Calculate RMS (Root Mean Square) energy of samples
     fn calculate_rms(samples: &[f32]) -> f32 {
         if samples.is_empty() {
@@ -110,6 +241,181 @@ impl AdaptiveChunkState {
     }
 }
 
+/// Time between the auto-stop warning and actually stopping, so the user can cancel
+const AUTO_STOP_GRACE_MS: u64 = 60_000;
+
+/// What the silence auto-stop wants done after the latest audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SilenceEvent {
+    /// Silence passed the limit; warn and start the grace period
+    Warn,
+    /// Grace period ran out without speech or a cancel
+    Stop,
+    /// Speech (or a cancel) ended a warned silence
+    Reset,
+}
+
+/// Longest `auto_stop_silence_minutes` accepted (a day)
+const AUTO_STOP_SILENCE_MINUTES_LIMIT: u64 = 24 * 60;
+
+fn validate_auto_stop_silence_minutes(minutes: u64) -> Result<(), String> {
+    if !(1..=AUTO_STOP_SILENCE_MINUTES_LIMIT).contains(&minutes) {
+        return Err(format!("auto_stop_silence_minutes must be between 1 and {}", AUTO_STOP_SILENCE_MINUTES_LIMIT));
+    }
+    Ok(())
+}
+
+/// Tracks how long all sources have been silent for the optional auto-stop
+struct SilenceWatch {
+    limit_ms: Option<u64>,  // None = auto-stop disabled
+    silent_since: std::time::Instant,
+    warned_at: Option<std::time::Instant>,
+}
+
+impl SilenceWatch {
+    fn new(limit_ms: Option<u64>) -> Self {
+        Self {
+            limit_ms,
+            silent_since: std::time::Instant::now(),
+            warned_at: None,
+        }
+    }
+
+    /// Feed the current speech state; `cancelled` restarts the timer as if speech was heard
+    fn update(&mut self, speaking: bool, cancelled: bool) -> Option<SilenceEvent> {
+        let limit_ms = self.limit_ms?;
+        if speaking || cancelled {
+            self.silent_since = std::time::Instant::now();
+            return self.warned_at.take().map(|_| SilenceEvent::Reset);
+        }
+        match self.warned_at {
+            None if self.silent_since.elapsed().as_millis() as u64 >= limit_ms => {
+                self.warned_at = Some(std::time::Instant::now());
+                Some(SilenceEvent::Warn)
+            }
+            Some(warned) if warned.elapsed().as_millis() as u64 >= AUTO_STOP_GRACE_MS => {
+                self.warned_at = None;
+                Some(SilenceEvent::Stop)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Longest `max_recording_minutes` accepted (a week); 0 turns the limit off
+const MAX_RECORDING_MINUTES_LIMIT: u64 = 7 * 24 * 60;
+
+fn validate_max_recording_minutes(minutes: u64) -> Result<(), String> {
+    if minutes > MAX_RECORDING_MINUTES_LIMIT {
+        return Err(format!("max_recording_minutes must be between 0 and {}", MAX_RECORDING_MINUTES_LIMIT));
+    }
+    Ok(())
+}
+
+/// Active recording time for the `max_recording_minutes` safeguard
+/// Counts from `recording_start_time` minus time spent paused, plus `carried_ms` a resumed
+/// meeting already recorded before it ended. The gap between ending and resuming isn't counted.
+struct DurationWatch {
+    limit_ms: Option<u64>,  // None = no limit
+    started_at: u64,
+    carried_ms: u64,
+    paused_ms: u64,
+    paused_since: Option<u64>,
+}
+
+impl DurationWatch {
+    fn new(limit_ms: Option<u64>, started_at: u64, carried_ms: u64) -> Self {
+        Self {
+            limit_ms,
+            started_at,
+            carried_ms,
+            paused_ms: 0,
+            paused_since: None,
+        }
+    }
+
+    /// Feed the pause state at `now` (ms since epoch); true once active time reaches the limit
+    fn exceeded(&mut self, paused: bool, now: u64) -> bool {
+        let Some(limit_ms) = self.limit_ms else {
+            return false;
+        };
+        match (paused, self.paused_since) {
+            (true, None) => self.paused_since = Some(now),
+            (false, Some(since)) => {
+                self.paused_ms += now.saturating_sub(since);
+                self.paused_since = None;
+            }
+            _ => {}
+        }
+        let paused_ms = self.paused_ms + self.paused_since.map_or(0, |since| now.saturating_sub(since));
+        let active_ms = now.saturating_sub(self.started_at).saturating_sub(paused_ms);
+        active_ms.saturating_add(self.carried_ms) >= limit_ms
+    }
+}
+
+/// Mic RMS at or below this is digital silence (an OS-level mute), well under any real noise floor
+const MIC_MUTE_RMS: f32 = 1e-4;
+/// How long the mic must stay silent, with system audio playing, before it counts as muted
+const MIC_MUTE_AFTER_MS: u64 = 3_000;
+
+enum MuteEvent {
+    Muted,
+    Unmuted,
+}
+
+/// Detects an OS-muted mic: digital silence on the mic while system audio keeps playing
+/// (a quiet room still has a noise floor, and silence on both sides is just silence)
+struct MuteWatch {
+    mic_silent_since: Option<std::time::Instant>,
+    system_active_at: Option<std::time::Instant>,
+    muted: bool,
+}
+
+impl MuteWatch {
+    fn new() -> Self {
+        Self {
+            mic_silent_since: None,
+            system_active_at: None,
+            muted: false,
+        }
+    }
+
+    /// Feed one packet's RMS for its source, heard at `now`
+    fn observe(&mut self, source: &AudioSource, rms: f32, now: std::time::Instant) -> Option<MuteEvent> {
+        if *source == AudioSource::SystemAudio {
+            if rms > MIC_MUTE_RMS {
+                self.system_active_at = Some(now);
+            }
+            return None;
+        }
+
+        if rms > MIC_MUTE_RMS {
+            self.mic_silent_since = None;
+            return std::mem::take(&mut self.muted).then_some(MuteEvent::Unmuted);
+        }
+        let silent_since = *self.mic_silent_since.get_or_insert(now);
+        let system_playing = self.system_active_at
+            .is_some_and(|t| now.duration_since(t).as_millis() as u64 <= MIC_MUTE_AFTER_MS);
+        if !self.muted && system_playing && now.duration_since(silent_since).as_millis() as u64 >= MIC_MUTE_AFTER_MS {
+            self.muted = true;
+            return Some(MuteEvent::Muted);
+        }
+        None
+    }
+}
+
+/// What the recorder is doing, for the UI's status indicator
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordingState {
+    Idle,
+    Recording,
+    /// Paused by the user: audio is captured for timing but nothing is transcribed or stored
+    Paused,
+    /// Mic muted at the OS level: system audio is still transcribed, the mic is not
+    Muted,
+}
+
 // ============================================================================
 // Tauri Channel Events for Streaming
 // ============================================================================
@@ -130,6 +436,7 @@ pub enum TranscriptionEvent {
         audio_events: Vec<String>,
         is_turn_complete: bool,
         turn_confidence: f32,
+        word_timings: Vec<WordTiming>,  // (word, start, end) ms offsets from timestamp_ms
     },
     /// Audio level update (for visualization)
     #[serde(rename_all = "camelCase")]
@@ -153,7 +460,34 @@ pub enum TranscriptionEvent {
     },
 }
 
+/// Progress of a long-running operation, sent via the channel registered with `subscribe_progress`
+/// `operation` is one of "download_models", "audio_import", "ingestion", "reprocess_entities",
+/// "reembed" or "backfill_embeddings"; the older per-operation events are still emitted alongside.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProgressEvent {
+    pub operation: String,
+    pub current: u64,
+    pub total: u64,
+    pub message: Option<String>,
+}
+
+impl ProgressEvent {
+    pub fn new(operation: &str, current: u64, total: u64, message: Option<String>) -> Self {
+        Self { operation: operation.to_string(), current, total, message }
+    }
+}
+
+/// Send a progress update to the subscribed channel, if any
+pub(crate) fn send_progress(app: &tauri::AppHandle, event: ProgressEvent) {
+    let state = app.state::<AppState>();
+    let channel_guard = state.progress_channel.lock();
+    if let Some(channel) = channel_guard.as_ref() {
+        let _ = channel.send(event);
+    }
+}
+
 mod audio;
+mod audio_import;
 mod asr;
 mod chunker;
 mod embeddings;
@@ -168,30 +502,170 @@ mod web_crawler;
 mod agent_queue;
 mod agent_workers;
 mod screenshot;
-
-use audio::{AudioCapture, AudioSample, AudioSource, AudioCapabilities, AudioCaptureMode, check_audio_capabilities};
-use asr::{AsrEngine, AsrConfig};
-use embeddings::EmbeddingEngine;
-use entities::{EntityEngine, Entity, ExtractionResult};
-use knowledge_base::{KnowledgeBase, SearchResult, ActionItem, Decision, KnowledgeSource, KnowledgeSearchResult, Meeting, TranscriptSegment, Topic, Person, MeetingStats};
-use llm_agent::{MeetingAssistant, RealtimeSuggestion, MeetingHighlights};
-use models::{ModelStatus, get_models_status, all_models_installed, download_all_models, get_models_dir};
+mod ocr;
+mod backup;
+mod graph_export;
+mod action_export;
+mod redaction;
+mod wake_word;
+mod pdf_text;
+mod profiles;
+mod usage;
+mod alerts;
+mod error;
+mod inference_gate;
+mod sqlite_export;
+
+use audio::{AudioCapture, AudioSample, AudioSource, AudioCapabilities, AudioCaptureMode, check_audio_capabilities, TARGET_SAMPLE_RATE};
+use asr::{AsrEngine, AsrConfig, AudioClock, ChunkOverlap, StreamResampler, WordTiming};
+use embeddings::{EmbeddingEngine, EmbeddingCacheStats, DEFAULT_EMBEDDING_MODEL};
+use entities::{EntityEngine, EntityConfig, EntityKind, Entity, ExtractionResult, RelationConfig};
+use knowledge_base::{RetrievalConfig, EntityReprocessStats, EntityReprocessProgress, KnowledgeBase, SearchResult, SegmentMatch, MeetingSearchResult, ActionItem, Decision, KnowledgeSource, SourceRefresh, KnowledgeSearchResult, Meeting, TranscriptSegment, Topic, Person, MeetingStats, MeetingSpeakerStats, MeetingSentiment, MeetingAnalytics, MeetingBookmark, MeetingLink, RelatedMeeting, MeetingId, MeetingKnowledge, PagedResult, SegmentDedupConfig, DiarizationTuple, IngestionProgress, EmbeddingStatus, BrokenEmbedding, TagStats, BulkDeleteResult, TranscriptExportMode, PendingSegment, LanguageBreakdown, DEFAULT_AUTO_LINK_THRESHOLD, normalize_language, normalize_record_id};
+use llm_agent::{LlmProvider, LlmEndpoint, EndpointHealth, MeetingAssistant, GenerationSettings, SuggestionCadence, is_local_url, RealtimeSuggestion, MeetingHighlights, AnswerWithSources, CatchUpItem, CatchUpSummary, MeetingDigest, MeetingDiff, MeetingSummary, is_assigned_to_me, run_cancellable, validate_prompt_template, UsageCallback};
+use tokio_util::sync::CancellationToken;
+use models::{ModelStatus, ModelsDiskUsage, get_models_status, all_models_installed, download_all_models, get_models_dir, download_model, find_model, is_model_installed, OCR_MODEL_IDS, WAKE_WORD_MODEL_ID};
 use smart_turn::{SmartTurnEngine, SmartTurnConfig};
-use speaker_diarization::{SpeakerDiarizationEngine, SpeakerDiarizationConfig};
-use user_store::{UserStore, UserSettings, Note, Integration, SavedSearch};
-use web_crawler::{WebCrawler, SearchResult as WebSearchResult, CrawledPage};
-use screenshot::{capture_screen, ScreenshotResult};
+use wake_word::{WakeWordConfig, WakeWordEngine};
+use speaker_diarization::{SpeakerDiarizationEngine, SpeakerDiarizationConfig, DiarizationQuality, SpeakerLabels};
+use user_store::{UserStore, UserSettings, Note, Integration, SavedSearch, PromptTemplate, default_true};
+use web_crawler::{WebCrawler, CrawlerConfig, CrawlPoliteness, SearchBackend, SearchResult as WebSearchResult, CrawledPage};
+use screenshot::{capture_screen, capture_screen_pixels, ScreenshotResult};
+use ocr::ScreenOcr;
 use agent_queue::{AgentQueue, QueueStats};
+use backup::BackupManifest;
+use sqlite_export::SqliteExportStats;
+use graph_export::{GraphExport, GraphExportFormat};
+use chunker::ChunkStrategy;
+use action_export::ActionExportFormat;
+use redaction::Redactor;
+use usage::{LlmUsage, ModelPricing, UsageStats, UsageTotals};
+use alerts::AlertMatcher;
+use error::SecondBrainError;
 use std::sync::Arc;
 // Note: We use parking_lot::RwLock (imported above) for sync access
 // and tokio::sync::RwLock only for KnowledgeBase (async access)
 
+/// Queued final transcripts are written once this many pile up...
+const SEGMENT_BATCH_SIZE: usize = 8;
+/// ...or once the oldest has waited this long
+const SEGMENT_BATCH_MAX_AGE: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// An open speaker turn is stored once it spans this long (ms) even if Smart Turn never calls it complete...
+const TURN_MERGE_MAX_MS: u64 = 20_000;
+/// ...or once its speaker has been quiet this long
+const TURN_MERGE_IDLE_MS: u64 = 3_000;
+
+/// Final transcripts waiting for `KnowledgeBase::add_segments_batch`
+/// With `merge_turns` on, consecutive same-speaker chunks are held as one open turn
+/// until Smart Turn reports it complete, so a sentence isn't split across rows.
+#[derive(Default)]
+pub struct SegmentBatch {
+    segments: Vec<PendingSegment>,
+    oldest: Option<std::time::Instant>,
+    merge_turns: bool,
+    open_turns: Vec<PendingSegment>,  // At most one per meeting and speaker
+}
+
+impl SegmentBatch {
+    fn push(&mut self, segment: PendingSegment) {
+        if self.segments.is_empty() {
+            self.oldest = Some(std::time::Instant::now());
+        }
+        self.segments.push(segment);
+    }
+
+    /// Queue a chunk's transcript as part of its speaker's current turn
+    fn push_turn(&mut self, segment: PendingSegment, turn_complete: bool) {
+        if !self.merge_turns {
+            self.push(segment);
+            return;
+        }
+        self.close_stale_turns(segment.start_ms);
+
+        let index = match self.open_turns.iter()
+            .position(|t| t.meeting_id == segment.meeting_id && t.speaker == segment.speaker)
+        {
+            Some(index) => {
+                merge_turn_segment(&mut self.open_turns[index], segment);
+                index
+            }
+            None => {
+                self.open_turns.push(segment);
+                self.open_turns.len() - 1
+            }
+        };
+        if turn_complete {
+            let turn = self.open_turns.remove(index);
+            self.push(turn);
+        }
+    }
+
+    /// Queue turns that ran past `TURN_MERGE_MAX_MS` or went quiet, as of `now_ms` (wall clock)
+    fn close_stale_turns(&mut self, now_ms: u64) {
+        let (stale, open): (Vec<_>, Vec<_>) = std::mem::take(&mut self.open_turns).into_iter()
+            .partition(|t| now_ms.saturating_sub(t.start_ms) >= TURN_MERGE_MAX_MS
+                || now_ms.saturating_sub(t.end_ms) >= TURN_MERGE_IDLE_MS);
+        self.open_turns = open;
+        for turn in stale {
+            self.push(turn);
+        }
+    }
+
+    /// Queue every open turn (recording stopped or the meeting is ending)
+    fn close_turns(&mut self) {
+        for turn in std::mem::take(&mut self.open_turns) {
+            self.push(turn);
+        }
+    }
+
+    fn is_due(&self) -> bool {
+        self.segments.len() >= SEGMENT_BATCH_SIZE
+            || self.oldest.is_some_and(|t| t.elapsed() >= SEGMENT_BATCH_MAX_AGE)
+    }
+
+    fn take(&mut self) -> Vec<PendingSegment> {
+        self.oldest = None;
+        std::mem::take(&mut self.segments)
+    }
+}
+
+/// Append the next chunk of a speaker turn, shifting its word timings onto the turn's start
+fn merge_turn_segment(turn: &mut PendingSegment, next: PendingSegment) {
+    let shift = next.start_ms.saturating_sub(turn.start_ms);
+    turn.word_timings.extend(next.word_timings.into_iter()
+        .map(|(word, start, end)| (word, start + shift, end + shift)));
+    turn.text = format!("{} {}", turn.text.trim_end(), next.text.trim_start());
+    turn.end_ms = turn.end_ms.max(next.end_ms);
+    if turn.language.is_none() {
+        turn.language = next.language;
+    }
+    // A turn that becomes emotional partway through takes that emotion
+    if next.emotion.is_some() && turn.emotion.as_deref().map_or(true, |e| e == "neutral") {
+        turn.emotion = next.emotion;
+    }
+}
+
+/// Mic capture + spotting thread that listens for the wake word between recordings
+pub struct WakeWordListener {
+    stop: Arc<std::sync::atomic::AtomicBool>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+/// Background agent worker thread, kept so shutdown can wait for it
+pub struct AgentWorkers {
+    worker_count: usize,
+    thread: std::thread::JoinHandle<()>,
+}
+
 // App state
 // Uses parking_lot primitives for high-performance synchronization:
 // - RwLock for engines (initialized once, read many times during processing)
 // - Mutex for frequently-changing state (audio buffers, etc.)
 pub struct AppState {
     pub is_recording: std::sync::atomic::AtomicBool,
+    pub recording_paused: std::sync::atomic::AtomicBool,  // ASR/KB writes skipped until resumed
+    pub mic_muted: std::sync::atomic::AtomicBool,  // Mic transcription skipped until audio returns
+    pub importing_audio: std::sync::atomic::AtomicBool,  // An audio file import owns the ASR engine
     // Audio capture - Mutex (write-heavy, single writer)
     pub audio_capture: Mutex<AudioCapture>,
     pub audio_sender: Mutex<Option<mpsc::UnboundedSender<AudioSample>>>,
@@ -201,32 +675,59 @@ pub struct AppState {
     pub entity_engine: RwLock<Option<Arc<EntityEngine>>>,
     pub embedding_engine: RwLock<Option<Arc<EmbeddingEngine>>>,
     pub diarization_engine: RwLock<Option<SpeakerDiarizationEngine>>,
+    pub wake_word_engine: Mutex<Option<WakeWordEngine>>,  // Set while wake word listening is enabled
+    pub last_diarization: Mutex<Option<DiarizationQuality>>,  // Quality of the last diarized meeting
     pub llm_assistant: RwLock<Option<Arc<MeetingAssistant>>>,
+    pub llm_session_usage: Mutex<UsageTotals>,  // LLM tokens used since the app started
+    pub meeting_alerts: RwLock<Arc<AlertMatcher>>,  // Compiled `meeting_alerts`, checked on every final transcript
+    pub ocr_engine: RwLock<Option<Arc<ScreenOcr>>>,  // Loaded on first OCR request
     // UserStore uses rusqlite::Connection which is not Sync, so it must use Mutex
     pub user_store: Mutex<Option<UserStore>>,
     // Knowledge base - already uses tokio::RwLock for async access
     pub knowledge_base: Arc<tokio::sync::RwLock<Option<KnowledgeBase>>>,
     // Frequently-changing state - Mutex (write-heavy)
     pub current_meeting_id: Mutex<Option<String>>,
+    pub active_session: Mutex<Option<String>>,  // Session whose meeting current_meeting_id belongs to
+    pub paused_sessions: Mutex<std::collections::HashMap<String, MeetingSession>>,  // Parked meetings, by session id
     pub recording_start_time: Mutex<Option<u64>>,  // Timestamp when recording started
     pub mic_audio_buffer: Mutex<Vec<f32>>,     // Buffer microphone for diarization
     pub system_audio_buffer: Mutex<Vec<f32>>,  // Buffer system audio for diarization
     pub current_audio_chunk: Mutex<Vec<f32>>,  // Buffer for Smart Turn analysis
     pub recent_transcripts: Mutex<Vec<String>>,  // Recent transcripts for LLM suggestions (max 10)
+    pub suggestion_cadence: RwLock<SuggestionCadence>,  // When the recording loop asks for realtime suggestions
+    pub speaker_labels: RwLock<SpeakerLabels>,  // Speaker names for the live meeting's mic and system audio
+    pub pending_segments: Mutex<SegmentBatch>,   // Final transcripts not yet written to the KB
+    pub segment_flush: tokio::sync::Mutex<()>,   // Held while a batch is written, so flushes don't overlap
     pub current_meeting_context: Mutex<Option<String>>,  // Context/agenda for current meeting
     pub transcription_channel: Mutex<Option<Channel<TranscriptionEvent>>>,  // Channel for streaming
+    pub progress_channel: Mutex<Option<Channel<ProgressEvent>>>,  // Channel for long-running operation progress
+    pub assistant_request: Mutex<Option<CancellationToken>>,   // In-flight ask_assistant call
+    pub suggestion_request: Mutex<Option<CancellationToken>>,  // In-flight realtime suggestion
+    pub auto_stop_cancel: std::sync::atomic::AtomicBool,  // Set by cancel_auto_stop, read by the capture thread
+    pub resumed_active_ms: std::sync::atomic::AtomicU64,  // Time a resumed meeting already recorded, taken by start_recording
+    pub screen_share_protection: std::sync::atomic::AtomicBool,  // Applied to PROTECTED_WINDOWS as they open
+    pub wake_word_listener: Mutex<Option<WakeWordListener>>,  // Running only while enabled and not recording
     // Agent queue - RwLock (initialized once, submit is async)
     pub agent_queue: RwLock<Option<Arc<AgentQueue>>>,
-    // Config - immutable after init
-    pub adaptive_chunk_config: AdaptiveChunkConfig,
+    // Adaptive chunking thresholds per audio source (read when recording starts)
+    pub adaptive_chunk_configs: RwLock<SourceChunkConfigs>,
     // Worker pool handle for graceful shutdown
-    pub worker_pool: Mutex<Option<Arc<tokio::sync::Mutex<Option<agent_queue::WorkerPool>>>>>,
+    pub worker_pool: Mutex<Option<AgentWorkers>>,
+    // Robots.txt cache and per-host rate limiter shared by all crawls
+    pub crawl_politeness: Arc<CrawlPoliteness>,
+    // Set once the background knowledge source refresh loop is running
+    pub source_refresh_started: std::sync::atomic::AtomicBool,
+    // Set once the background trash purge loop is running
+    pub trash_purge_started: std::sync::atomic::AtomicBool,
 }
 
 impl Default for AppState {
     fn default() -> Self {
         Self {
             is_recording: std::sync::atomic::AtomicBool::new(false),
+            recording_paused: std::sync::atomic::AtomicBool::new(false),
+            mic_muted: std::sync::atomic::AtomicBool::new(false),
+            importing_audio: std::sync::atomic::AtomicBool::new(false),
             // Audio (Mutex - write heavy)
             audio_capture: Mutex::new(AudioCapture::new()),
             audio_sender: Mutex::new(None),
@@ -236,25 +737,46 @@ impl Default for AppState {
             entity_engine: RwLock::new(None),
             embedding_engine: RwLock::new(None),
             diarization_engine: RwLock::new(None),
+            wake_word_engine: Mutex::new(None),
+            last_diarization: Mutex::new(None),
             llm_assistant: RwLock::new(None),
+            llm_session_usage: Mutex::new(UsageTotals::default()),
+            meeting_alerts: RwLock::new(Arc::new(AlertMatcher::default())),
+            ocr_engine: RwLock::new(None),
             user_store: Mutex::new(None),
             // Knowledge base (tokio RwLock for async)
             knowledge_base: Arc::new(tokio::sync::RwLock::new(None)),
             // Frequently-changing state (Mutex)
             current_meeting_id: Mutex::new(None),
+            active_session: Mutex::new(None),
+            paused_sessions: Mutex::new(std::collections::HashMap::new()),
             recording_start_time: Mutex::new(None),
             mic_audio_buffer: Mutex::new(Vec::new()),      // Buffer for microphone diarization
             system_audio_buffer: Mutex::new(Vec::new()),   // Buffer for system audio diarization
             current_audio_chunk: Mutex::new(Vec::new()),
             recent_transcripts: Mutex::new(Vec::new()),
+            suggestion_cadence: RwLock::new(SuggestionCadence::default()),
+            speaker_labels: RwLock::new(SpeakerLabels::default()),
+            pending_segments: Mutex::new(SegmentBatch::default()),
+            segment_flush: tokio::sync::Mutex::new(()),
             current_meeting_context: Mutex::new(None),
             transcription_channel: Mutex::new(None),
+            progress_channel: Mutex::new(None),
+            assistant_request: Mutex::new(None),
+            auto_stop_cancel: std::sync::atomic::AtomicBool::new(false),
+            resumed_active_ms: std::sync::atomic::AtomicU64::new(0),
+            screen_share_protection: std::sync::atomic::AtomicBool::new(user_store::default_screen_share_protection()),
+            suggestion_request: Mutex::new(None),
+            wake_word_listener: Mutex::new(None),
             // Agent queue (RwLock)
             agent_queue: RwLock::new(None),
             // Config
-            adaptive_chunk_config: AdaptiveChunkConfig::default(),
+            adaptive_chunk_configs: RwLock::new(SourceChunkConfigs::default()),
             // Worker pool
             worker_pool: Mutex::new(None),
+            crawl_politeness: Arc::new(CrawlPoliteness::new()),
+            source_refresh_started: std::sync::atomic::AtomicBool::new(false),
+            trash_purge_started: std::sync::atomic::AtomicBool::new(false),
         }
     }
 }
@@ -308,6 +830,11 @@ fn initialize_entities(state: tauri::State<AppState>) -> Result<(), String> {
 
     let models_dir = get_models_dir();
     let engine = EntityEngine::new(&models_dir)?;
+    engine.set_max_concurrency(configured_inference_concurrency(&state));
+    match configured_entity_config(&state) {
+        Ok(config) => engine.set_config(config),
+        Err(e) => eprintln!("[Entities] Using default labels, invalid settings: {}", e),
+    }
 
     *entity_guard = Some(Arc::new(engine));
     println!("Entity extraction engine initialized");
@@ -324,13 +851,195 @@ fn initialize_embeddings(state: tauri::State<AppState>) -> Result<(), String> {
     }
 
     let models_dir = get_models_dir();
-    let engine = EmbeddingEngine::new(&models_dir)?;
+    let engine = EmbeddingEngine::with_model(&models_dir, &configured_embedding_model(&state))?;
+    engine.set_max_concurrency(configured_inference_concurrency(&state));
 
     *embed_guard = Some(Arc::new(engine));
     println!("Embedding engine initialized");
     Ok(())
 }
 
+/// Embedding model from the `embedding_model` setting (built-in default if unset)
+fn configured_embedding_model(state: &AppState) -> String {
+    let store_guard = state.user_store.lock();
+    store_guard.as_ref()
+        .and_then(|store| store.get_settings().ok())
+        .map(|settings| settings.embedding_model)
+        .filter(|model| !model.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string())
+}
+
+// Loaded vs stored embedding model; `mismatch` is set while vector search is refused
+#[tauri::command]
+async fn get_embedding_status(state: tauri::State<'_, AppState>) -> Result<EmbeddingStatus, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+    kb.embedding_status().await
+}
+
+// Run raw SurrealQL against the knowledge base (developer mode only)
+// Results are capped per statement; anything other than SELECT needs `allow_writes`.
+// This bypasses every invariant the KB methods maintain, so it is strictly a debugging aid.
+#[tauri::command]
+async fn run_kb_query(
+    state: tauri::State<'_, AppState>,
+    surql: String,
+    allow_writes: Option<bool>,
+) -> Result<serde_json::Value, SecondBrainError> {
+    let developer_mode = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| s.developer_mode)
+            .unwrap_or(false)
+    };
+    if !developer_mode {
+        return Err(SecondBrainError::InvalidInput("Raw queries require developer mode to be enabled in settings".to_string()));
+    }
+
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+    kb.run_raw_query(&surql, allow_writes.unwrap_or(false)).await
+}
+
+// Segments, chunks and topics whose stored embedding is empty or has the wrong dimension
+#[tauri::command]
+async fn find_broken_embeddings(state: tauri::State<'_, AppState>) -> Result<Vec<BrokenEmbedding>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+    kb.find_broken_embeddings().await
+}
+
+// Switch embedding models (default: the configured one) and re-embed everything stored
+// Emits `reembed-progress` events per table
+#[tauri::command]
+async fn reembed_all(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    new_model: Option<String>,
+) -> Result<usize, String> {
+    let model_id = new_model
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .unwrap_or_else(|| configured_embedding_model(&state));
+
+    let models_dir = get_models_dir();
+    let load_id = model_id.clone();
+    let engine = tokio::task::spawn_blocking(move || EmbeddingEngine::with_model(&models_dir, &load_id))
+        .await
+        .map_err(|e| format!("Failed to load embedding model: {}", e))??;
+    engine.set_max_concurrency(configured_inference_concurrency(&state));
+    let engine = Arc::new(engine);
+
+    let count = {
+        let kb_guard = state.knowledge_base.read().await;
+        let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+        kb.reembed_all(engine.clone(), &|progress| {
+            let _ = app.emit("reembed-progress", progress);
+            send_progress(&app, ProgressEvent::new("reembed", progress.done as u64, progress.total as u64, Some(progress.table.clone())));
+        }).await?
+    };
+
+    *state.embedding_engine.write() = Some(engine);
+    {
+        let store_guard = state.user_store.lock();
+        if let Some(store) = store_guard.as_ref() {
+            store.set_setting("embedding_model", &model_id)?;
+        }
+    }
+
+    println!("[Embeddings] Re-embedded {} rows with {}", count, model_id);
+    Ok(count)
+}
+
+// Embed segments and chunks that were stored without an embedding after a model failure
+// Emits `backfill-progress` events per table
+#[tauri::command]
+async fn backfill_missing_embeddings(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<usize, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+    kb.backfill_missing_embeddings(&|progress| {
+        let _ = app.emit("backfill-progress", progress);
+        send_progress(&app, ProgressEvent::new("backfill_embeddings", progress.done as u64, progress.total as u64, Some(progress.table.clone())));
+    }).await
+}
+
+// Re-run entity extraction over one meeting's transcript and rebuild its graph data
+// Emits `entity-reprocess-progress` events
+#[tauri::command]
+async fn reprocess_entities(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    meeting_id: String,
+) -> Result<EntityReprocessStats, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+    kb.reprocess_meeting_entities(&meeting_id, &|progress| {
+        emit_entity_reprocess_progress(&app, progress);
+    }).await
+}
+
+// Re-run entity extraction over every meeting (after a GLiNER model or label set change)
+// Emits `entity-reprocess-progress` events
+#[tauri::command]
+async fn reprocess_all_entities(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<EntityReprocessStats, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+    kb.reprocess_all_entities(&|progress| {
+        emit_entity_reprocess_progress(&app, progress);
+    }).await
+}
+
+/// Forward entity reprocessing progress as `entity-reprocess-progress` and to the progress channel
+/// A run over every meeting reports meetings done; a single meeting reports its segments.
+fn emit_entity_reprocess_progress(app: &tauri::AppHandle, progress: &EntityReprocessProgress) {
+    let _ = app.emit("entity-reprocess-progress", progress);
+    let (current, total) = if progress.meetings_total > 1 {
+        (progress.meetings_done, progress.meetings_total)
+    } else {
+        (progress.segments_done, progress.segments_total)
+    };
+    send_progress(app, ProgressEvent::new("reprocess_entities", current as u64, total as u64, Some(progress.meeting_id.clone())));
+}
+
+// Embed multiple texts in batched forward passes
+#[tauri::command]
+fn embed_texts_batch(
+    state: tauri::State<AppState>,
+    texts: Vec<String>,
+) -> Result<Vec<Vec<f32>>, String> {
+    let embed_guard = state.embedding_engine.read();
+
+    let engine = embed_guard.as_ref()
+        .ok_or("Embedding engine not initialized. Call initialize_embeddings first.")?;
+
+    let text_refs: Vec<&str> = texts.iter().map(|s| s.as_str()).collect();
+    engine.embed_batch(&text_refs)
+}
+
+// Get embedding cache hit/miss counters
+#[tauri::command]
+fn get_embedding_cache_stats(state: tauri::State<AppState>) -> Result<EmbeddingCacheStats, String> {
+    let embed_guard = state.embedding_engine.read();
+    let engine = embed_guard.as_ref().ok_or("Embedding engine not initialized")?;
+    Ok(engine.cache_stats())
+}
+
+// Resize the embedding cache
+#[tauri::command]
+fn set_embedding_cache_capacity(state: tauri::State<AppState>, capacity: usize) -> Result<EmbeddingCacheStats, String> {
+    let embed_guard = state.embedding_engine.read();
+    let engine = embed_guard.as_ref().ok_or("Embedding engine not initialized")?;
+    engine.set_cache_capacity(capacity);
+    Ok(engine.cache_stats())
+}
+
 // Initialize Speaker Diarization engine
 #[tauri::command]
 fn initialize_diarization(state: tauri::State<AppState>) -> Result<(), String> {
@@ -358,9 +1067,19 @@ fn initialize_diarization(state: tauri::State<AppState>) -> Result<(), String> {
     Ok(())
 }
 
+/// Directory holding the active profile's SurrealDB knowledge base
+fn knowledge_base_dir() -> std::path::PathBuf {
+    profiles::load().knowledge_base_dir()
+}
+
+/// Directory holding the active profile's SQLite user store
+fn user_store_dir() -> Result<std::path::PathBuf, String> {
+    profiles::load().user_store_dir()
+}
+
 // Initialize Knowledge Base (requires entities and embeddings first)
 #[tauri::command]
-async fn initialize_knowledge_base(state: tauri::State<'_, AppState>) -> Result<(), String> {
+async fn initialize_knowledge_base(state: tauri::State<'_, AppState>, app: tauri::AppHandle) -> Result<(), String> {
     {
         let kb_guard = state.knowledge_base.read().await;
         if kb_guard.is_some() {
@@ -378,9 +1097,7 @@ async fn initialize_knowledge_base(state: tauri::State<'_, AppState>) -> Result<
         guard.clone().ok_or("Embedding engine not initialized. Call initialize_embeddings first.")?
     };
 
-    let data_dir = dirs::data_local_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join("second-brain");
+    let data_dir = knowledge_base_dir();
 
     std::fs::create_dir_all(&data_dir).ok();
 
@@ -398,11 +1115,30 @@ async fn initialize_knowledge_base(state: tauri::State<'_, AppState>) -> Result<
         }
     }
 
+    // Apply the meeting retention policy
+    match purge_expired_meetings(&state, &kb).await {
+        Ok(summary) if !summary.purged.is_empty() || !summary.failed.is_empty() => {
+            println!("[Startup] Retention purged {} meeting(s) older than {} days ({} failed)",
+                summary.purged.len(), summary.retention_days, summary.failed.len());
+            let _ = app.emit("retention-purged", summary);
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("[Startup] Warning: Failed to apply retention policy: {}", e);
+        }
+    }
+
     {
         let mut kb_guard = state.knowledge_base.write().await;
         *kb_guard = Some(kb);
     }
 
+    apply_redaction_settings(&state).await;
+    apply_relation_settings(&state).await;
+    apply_chunking_settings(&state).await;
+    start_source_refresh_loop(&state, &app);
+    start_trash_purge_loop(&state, &app);
+
     println!("Knowledge base initialized");
     Ok(())
 }
@@ -447,37 +1183,271 @@ fn extract_entities_batch(
     engine.extract_batch(&text_refs)
 }
 
+/// Session used when callers don't name one, so single-meeting flows work unchanged
+const DEFAULT_SESSION_ID: &str = "default";
+
+/// A meeting parked while another one records
+///
+/// Only one session is live at a time (there is one mic and one system stream); its
+/// state lives in the regular AppState fields. Pausing moves that state in here and
+/// resuming moves it back, so ASR and KB writes always follow the live meeting.
+/// Buffered audio isn't parked: diarization only covers a meeting since it was last resumed.
+pub struct MeetingSession {
+    pub meeting_id: String,
+    pub paused_at: u64,
+    pub recent_transcripts: Vec<String>,
+    pub context: Option<String>,
+    pub speaker_labels: SpeakerLabels,
+}
+
+/// A recording session for `list_sessions`
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct SessionInfo {
+    pub session_id: String,
+    pub meeting_id: String,
+    pub active: bool,
+    pub paused_at: Option<u64>,
+}
+
+fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Drop the buffered diarization audio and restart the clock it is aligned to
+/// Called whenever the live meeting changes, so the next meeting's buffers start empty at now.
+fn restart_audio_buffers(state: &AppState) {
+    state.mic_audio_buffer.lock().clear();
+    state.system_audio_buffer.lock().clear();
+    let recording = state.is_recording.load(std::sync::atomic::Ordering::SeqCst);
+    *state.recording_start_time.lock() = recording.then(now_ms);
+}
+
+/// Park the live session's meeting; returns its session id, or None if idle
+/// Its buffered audio is dropped rather than parked (see `MeetingSession`).
+fn park_active_session(state: &AppState) -> Option<String> {
+    let meeting_id = state.current_meeting_id.lock().take()?;
+    let session_id = state.active_session.lock().take()
+        .unwrap_or_else(|| DEFAULT_SESSION_ID.to_string());
+
+    // Write the parked meeting's tail before the next meeting's segments queue up
+    state.pending_segments.lock().close_turns();
+    restart_audio_buffers(state);
+
+    let session = MeetingSession {
+        meeting_id: meeting_id.clone(),
+        paused_at: now_ms(),
+        recent_transcripts: std::mem::take(&mut *state.recent_transcripts.lock()),
+        context: state.current_meeting_context.lock().take(),
+        speaker_labels: state.speaker_labels.read().clone(),
+    };
+    state.paused_sessions.lock().insert(session_id.clone(), session);
+    println!("[MEETING] Paused session '{}' (meeting {})", session_id, meeting_id);
+    Some(session_id)
+}
+
+/// Make a parked session live again, parking whichever one is live now
+fn activate_session(state: &AppState, session_id: &str) -> Result<String, String> {
+    if state.active_session.lock().as_deref() == Some(session_id) {
+        return state.current_meeting_id.lock().clone().ok_or_else(|| "No meeting in progress".to_string());
+    }
+    if !state.paused_sessions.lock().contains_key(session_id) {
+        return Err(format!("No paused session '{}'", session_id));
+    }
+    park_active_session(state);
+
+    let session = state.paused_sessions.lock().remove(session_id)
+        .ok_or_else(|| format!("No paused session '{}'", session_id))?;
+
+    // Diarization offsets count from the resume, not from when the meeting first started
+    restart_audio_buffers(state);
+    *state.recent_transcripts.lock() = session.recent_transcripts;
+    *state.current_meeting_context.lock() = session.context;
+    *state.speaker_labels.write() = session.speaker_labels;
+    *state.current_meeting_id.lock() = Some(session.meeting_id.clone());
+    *state.active_session.lock() = Some(session_id.to_string());
+
+    println!("[MEETING] Resumed session '{}' (meeting {})", session_id, session.meeting_id);
+    Ok(session.meeting_id)
+}
+
+/// Whether a meeting is live or parked, i.e. segments could still be written for one
+fn has_any_session(state: &AppState) -> bool {
+    state.current_meeting_id.lock().is_some() || !state.paused_sessions.lock().is_empty()
+}
+
 // Start a new meeting
+// With a `session_id` other than the live one, the live meeting is paused (not ended)
+// so it can be picked up again with `resume_session`.
 #[tauri::command]
 async fn start_meeting(
     state: tauri::State<'_, AppState>,
     title: String,
     participants: Vec<String>,
+    follow_up_to: Option<MeetingId>,
+    session_id: Option<String>,
+    speaker_name: Option<String>,
 ) -> Result<String, String> {
+    let session_id = session_id.unwrap_or_else(|| DEFAULT_SESSION_ID.to_string());
+    // The user's name, if given, labels the mic for this meeting instead of the configured label
+    let mut speaker_labels = configured_speaker_labels(&state);
+    if let Some(name) = speaker_name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        speaker_labels = SpeakerLabels::new(name, &speaker_labels.remote)?;
+    }
+    if state.paused_sessions.lock().contains_key(&session_id) {
+        return Err(format!("Session '{}' already has a paused meeting; resume or end it first", session_id));
+    }
+
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     let meeting_id = kb.create_meeting(&title, participants).await?;
     println!("[MEETING] Created meeting with ID: {}", meeting_id);
 
+    // Link to the given meeting, or to the previous instance of a recurring meeting
+    let previous = match follow_up_to {
+        Some(id) => Some(id.as_str().to_string()),
+        None => kb.find_previous_instance(&meeting_id).await?
+            .and_then(|m| m.id)
+            .map(|t| normalize_record_id("meeting", &t.to_string())),
+    };
+    if let Some(previous) = previous {
+        match kb.link_meetings(&meeting_id, &previous, "follow_up").await {
+            Ok(_) => println!("[MEETING] Linked as follow-up to {}", previous),
+            Err(e) => eprintln!("[MEETING] Failed to link to {}: {}", previous, e),
+        }
+    }
+
+    let live_session = state.active_session.lock().clone()
+        .unwrap_or_else(|| DEFAULT_SESSION_ID.to_string());
+    if live_session != session_id {
+        park_active_session(&state);
+    }
+
     {
         let mut current = state.current_meeting_id.lock();
         *current = Some(meeting_id.clone());
         println!("[MEETING] Set current_meeting_id to: {:?}", *current);
     }
+    *state.speaker_labels.write() = speaker_labels;
+    *state.active_session.lock() = Some(session_id);
 
     println!("[MEETING] Started meeting: {} (ID: {})", title, meeting_id);
     Ok(meeting_id)
 }
 
-// End the current meeting
+// Pause the live meeting without ending it; audio is ignored until a session is resumed
 #[tauri::command]
-async fn end_meeting(
-    state: tauri::State<'_, AppState>,
-    app: tauri::AppHandle,
-    summary: Option<String>,
-) -> Result<(), String> {
+fn pause_session(state: tauri::State<AppState>) -> Result<String, String> {
+    park_active_session(&state).ok_or_else(|| "No meeting in progress".to_string())
+}
+
+// Resume a paused session, pausing the live one; returns the resumed meeting id
+#[tauri::command]
+fn resume_session(state: tauri::State<AppState>, session_id: String) -> Result<String, String> {
+    activate_session(&state, &session_id)
+}
+
+// The live session (if any) and every paused one
+#[tauri::command]
+fn list_sessions(state: tauri::State<AppState>) -> Vec<SessionInfo> {
+    let mut sessions = Vec::new();
+    if let Some(meeting_id) = state.current_meeting_id.lock().clone() {
+        sessions.push(SessionInfo {
+            session_id: state.active_session.lock().clone().unwrap_or_else(|| DEFAULT_SESSION_ID.to_string()),
+            meeting_id,
+            active: true,
+            paused_at: None,
+        });
+    }
+    let paused = state.paused_sessions.lock();
+    let mut parked: Vec<SessionInfo> = paused.iter()
+        .map(|(id, session)| SessionInfo {
+            session_id: id.clone(),
+            meeting_id: session.meeting_id.clone(),
+            active: false,
+            paused_at: Some(session.paused_at),
+        })
+        .collect();
+    parked.sort_by_key(|s| s.paused_at);
+    sessions.extend(parked);
+    sessions
+}
+
+// End the current meeting, or the meeting of `session_id`
+// Ending a paused session briefly makes it live; the previously live one is resumed after.
+#[tauri::command]
+async fn end_meeting(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    summary: Option<String>,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    let restore = match session_id {
+        Some(ref id) if state.active_session.lock().as_deref() != Some(id.as_str()) => {
+            let live = state.active_session.lock().clone()
+                .filter(|_| state.current_meeting_id.lock().is_some());
+            activate_session(&state, id)?;
+            live
+        }
+        _ => None,
+    };
+
+    let result = end_active_meeting(&state, &app, summary).await;
+
+    if let Some(live) = restore {
+        if let Err(e) = activate_session(&state, &live) {
+            eprintln!("[MEETING] Failed to resume session '{}': {}", live, e);
+        }
+    }
+    result
+}
+
+/// Write all queued transcript segments to the knowledge base
+/// Segments the batch write didn't get to are retried one by one, so one bad segment doesn't lose the rest.
+/// Flushes run one at a time, so a caller returns only once everything queued before it is stored.
+async fn flush_pending_segments(state: &AppState) {
+    let _flushing = state.segment_flush.lock().await;
+    let batch = state.pending_segments.lock().take();
+    if batch.is_empty() {
+        return;
+    }
+
+    let kb_guard = state.knowledge_base.read().await;
+    let Some(kb) = kb_guard.as_ref() else {
+        eprintln!("[KB] Knowledge base not available, dropping {} queued segment(s)", batch.len());
+        return;
+    };
+
+    let count = batch.len();
+    match kb.add_segments_batch(batch).await {
+        Ok(ids) => println!("[KB] Saved {} queued segment(s) into {} row(s)", count, ids.len()),
+        Err(e) => {
+            eprintln!("[KB] Batch save failed ({}), saving {} unsaved segment(s) individually", e.error, e.unsaved.len());
+            for segment in e.unsaved {
+                if let Err(e) = kb.add_segment_with_timings(
+                    &segment.meeting_id,
+                    &segment.speaker,
+                    &segment.text,
+                    segment.start_ms,
+                    segment.end_ms,
+                    segment.word_timings,
+                ).await {
+                    eprintln!("[KB] ERROR saving segment: {}", e);
+                }
+            }
+        }
+    }
+}
+
+async fn end_active_meeting(
+    state: &AppState,
+    app: &tauri::AppHandle,
+    summary: Option<String>,
+) -> Result<(), String> {
     // Emit recording-stopped event to close overlay window
     let _ = app.emit("recording-stopped", ());
 
@@ -488,6 +1458,11 @@ async fn end_meeting(
         *current = None; // Clear immediately to prevent duplicate calls
         id
     };
+    *state.active_session.lock() = None;
+
+    // Write the tail of the transcript before diarization and summaries read it
+    state.pending_segments.lock().close_turns();
+    flush_pending_segments(state).await;
 
     // Get recording start time for timestamp alignment
     let recording_start_time = {
@@ -500,6 +1475,27 @@ async fn end_meeting(
     let audio_caps = check_audio_capabilities();
     let is_combined_mode = audio_caps.capture_mode == AudioCaptureMode::Combined;
 
+    let auto_save_recording = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|store| store.get_settings().ok())
+            .map(|settings| settings.auto_save_recording)
+            .unwrap_or(false)
+    };
+
+    // A known participant count caps the number of diarized speakers
+    let participant_count = {
+        let kb_guard = state.knowledge_base.read().await;
+        match kb_guard.as_ref() {
+            Some(kb) => kb.get_meeting(&meeting_id).await
+                .ok()
+                .flatten()
+                .map(|m| m.participants.len())
+                .filter(|n| *n > 0),
+            None => None,
+        }
+    };
+
     // Run speaker diarization based on audio capture mode
     let diarization_results = {
         let mic_audio = {
@@ -515,6 +1511,13 @@ async fn end_meeting(
             audio
         };
 
+        if auto_save_recording {
+            match write_meeting_recordings(&meeting_id, &mic_audio, &system_audio, is_combined_mode) {
+                Ok(paths) => println!("[Recording] Saved {} file(s): {:?}", paths.len(), paths),
+                Err(e) => eprintln!("[Recording] Failed to save recording: {}", e),
+            }
+        }
+
         // Determine which audio to diarize based on mode
         let (audio_to_diarize, mode_description) = if is_combined_mode {
             // Combined mode: mic contains BOTH user and system audio
@@ -542,26 +1545,36 @@ async fn end_meeting(
             println!("[Diarization] Processing {} samples from {} source...", audio_to_diarize.len(), mode_description);
             let mut diar_guard = state.diarization_engine.write();
             if let Some(ref mut diar_engine) = *diar_guard {
-                match diar_engine.process(audio_to_diarize, 16000) {
-                    Ok(segments) => {
-                        let speaker_count = segments.iter()
+                // Manual bounds win; otherwise clamp to the meeting's participant count
+                let (min_speakers, max_speakers) = diar_engine.speaker_bounds();
+                let max_speakers = max_speakers.or(participant_count.map(|n| n as i32));
+                match diar_engine.process(audio_to_diarize, TARGET_SAMPLE_RATE, min_speakers, max_speakers) {
+                    Ok(output) => {
+                        let speaker_count = output.segments.iter()
                             .map(|s| s.speaker_id)
                             .collect::<std::collections::HashSet<_>>()
                             .len();
-                        println!("[Diarization] Found {} segments from {} unique speakers", segments.len(), speaker_count);
+                        println!("[Diarization] Found {} segments from {} unique speakers", output.segments.len(), speaker_count);
 
-                        // Convert diarization timestamps to wall clock
-                        let labeled_segments: Vec<_> = if let Some(start_ts) = recording_start_time {
-                            segments.into_iter().map(|mut seg| {
-                                seg.start_ms += start_ts;
-                                seg.end_ms += start_ts;
-                                seg
-                            }).collect()
-                        } else {
-                            segments
-                        };
+                        let quality = DiarizationQuality::from_output(&meeting_id, &output);
+                        if let Some(avg) = quality.average_confidence {
+                            println!("[Diarization] Average speaker confidence {:.2} ({} low-confidence segments)",
+                                avg, quality.low_confidence_segments);
+                        }
+                        *state.last_diarization.lock() = Some(quality);
 
-                        Some((labeled_segments, is_combined_mode))
+                        // Convert diarization timestamps to wall clock
+                        let offset = recording_start_time.unwrap_or(0);
+                        let labeled_segments: Vec<_> = output.segments.into_iter().map(|mut seg| {
+                            seg.start_ms += offset;
+                            seg.end_ms += offset;
+                            seg
+                        }).collect();
+                        let crosstalk: Vec<(u64, u64)> = output.overlap_regions.iter()
+                            .map(|r| (r.start_ms + offset, r.end_ms + offset))
+                            .collect();
+
+                        Some((labeled_segments, crosstalk, is_combined_mode))
                     }
                     Err(e) => {
                         eprintln!("[Diarization] Error processing audio: {}", e);
@@ -581,12 +1594,12 @@ async fn end_meeting(
     // Apply diarization results to knowledge base
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    if let Some((ref segments, combined_mode)) = diarization_results {
-        let diar_tuples: Vec<(u64, u64, i32, String)> = segments
+    if let Some((ref segments, ref crosstalk, combined_mode)) = diarization_results {
+        let diar_tuples: Vec<DiarizationTuple> = segments
             .iter()
-            .map(|s| (s.start_ms, s.end_ms, s.speaker_id, s.speaker_label.clone()))
+            .map(|s| (s.start_ms, s.end_ms, s.speaker_id, s.speaker_label.clone(), s.confidence))
             .collect();
 
         if combined_mode {
@@ -596,12 +1609,18 @@ async fn end_meeting(
                 Err(e) => eprintln!("[Diarization] Relabeling failed: {}", e),
             }
         } else {
-            // Separate mode: only relabel "Guest" segments, keep "You" as is
-            match kb.relabel_speakers(&meeting_id, &diar_tuples).await {
-                Ok(count) => println!("[Diarization] Relabeled {} 'Guest' segments to unique speakers", count),
+            // Separate mode: only relabel the remote segments, keep the mic speaker as is
+            let remote_label = state.speaker_labels.read().remote.clone();
+            match kb.relabel_speakers(&meeting_id, &diar_tuples, &remote_label).await {
+                Ok(count) => println!("[Diarization] Relabeled {} '{}' segments to unique speakers", count, remote_label),
                 Err(e) => eprintln!("[Diarization] Relabeling failed: {}", e),
             }
         }
+
+        match kb.mark_overlapped_segments(&meeting_id, crosstalk).await {
+            Ok(count) => println!("[Diarization] Flagged {} segments with crosstalk", count),
+            Err(e) => eprintln!("[Diarization] Crosstalk flagging failed: {}", e),
+        }
     }
 
     kb.end_meeting(&meeting_id, summary).await?;
@@ -616,7 +1635,462 @@ async fn end_meeting(
     Ok(())
 }
 
-// Add transcript segment to current meeting
+/// Audio fed to ASR per step when importing a file (100ms at 16kHz)
+const IMPORT_CHUNK_SAMPLES: usize = TARGET_SAMPLE_RATE as usize / 10;
+/// Transcripts written to the KB per batch while importing
+const IMPORT_SEGMENT_BATCH: usize = 25;
+/// Longest stretch of an imported file that is diarized (1 hour at 16kHz, ~230MB of samples);
+/// speech past it keeps the "Guest" label
+const MAX_IMPORT_DIARIZATION_SAMPLES: usize = 60 * 60 * TARGET_SAMPLE_RATE as usize;
+
+/// Progress of an audio file import, emitted as `audio-import-progress`
+#[derive(Debug, Clone, serde::Serialize)]
+struct AudioImportProgress {
+    meeting_id: String,
+    stage: String,          // "transcribing", "diarizing", "saving", "done", "failed"
+    progress_percent: f32,
+    segments: usize,
+    message: Option<String>,
+}
+
+fn emit_import_progress(app: &tauri::AppHandle, meeting_id: &str, stage: &str, progress_percent: f32, segments: usize, message: Option<String>) {
+    let detail = match &message {
+        Some(message) => format!("{}: {}", stage, message),
+        None => stage.to_string(),
+    };
+    send_progress(app, ProgressEvent::new("audio_import", progress_percent.clamp(0.0, 100.0).round() as u64, 100, Some(detail)));
+    let _ = app.emit("audio-import-progress", AudioImportProgress {
+        meeting_id: meeting_id.to_string(),
+        stage: stage.to_string(),
+        progress_percent,
+        segments,
+        message,
+    });
+}
+
+/// Outcome of `import_audio_file`
+#[derive(Debug, Clone, serde::Serialize)]
+struct AudioImportResult {
+    meeting_id: String,
+    duration_ms: u64,
+    segments: usize,
+    speakers: Option<usize>,  // None when diarization was unavailable or failed
+}
+
+/// Transcribed and diarized file, before the speaker labels are applied
+struct TranscribedImport {
+    duration_ms: u64,
+    segments: usize,
+    diarization: Option<speaker_diarization::DiarizationOutput>,
+}
+
+/// Decode, transcribe and diarize an imported file on a blocking thread
+/// Transcripts are written to the KB in batches as they come in; only the 16kHz
+/// mono copy needed for diarization is kept in memory.
+fn transcribe_imported_audio(
+    app: &tauri::AppHandle,
+    meeting_id: &str,
+    meeting_start: u64,
+    mut decoder: audio_import::AudioFileDecoder,
+) -> Result<TranscribedImport, String> {
+    let state: tauri::State<AppState> = app.state();
+    let overlap_ms = state.adaptive_chunk_configs.read().system.overlap_ms;
+    let remote_label = configured_speaker_labels(&state).remote;
+
+    state.asr_engine.write().as_mut().ok_or("ASR engine not initialized")?.reset();
+
+    let mut clock = AudioClock::new();
+    let mut overlap = ChunkOverlap::new();
+    let mut pending: Vec<f32> = Vec::with_capacity(TARGET_SAMPLE_RATE as usize);
+    let mut diarization_audio: Vec<f32> = Vec::new();
+    let mut batch: Vec<PendingSegment> = Vec::new();
+    let mut saved = 0;
+    let mut last_emit = std::time::Instant::now();
+    let mut finished = false;
+
+    let save_batch = |batch: Vec<PendingSegment>| -> Result<usize, String> {
+        tauri::async_runtime::block_on(async {
+            let kb_guard = state.knowledge_base.read().await;
+            let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+            let count = batch.len();
+            kb.add_segments_batch(batch).await.map_err(SecondBrainError::from)?;
+            Ok(count)
+        })
+    };
+
+    while !finished {
+        match decoder.next_chunk()? {
+            Some(mono) => {
+                let room = MAX_IMPORT_DIARIZATION_SAMPLES.saturating_sub(diarization_audio.len());
+                diarization_audio.extend_from_slice(&mono[..mono.len().min(room)]);
+                pending.extend_from_slice(&mono);
+            }
+            None => {
+                // A second of trailing silence lets the VAD close the last speech segment
+                pending.resize(pending.len() + TARGET_SAMPLE_RATE as usize, 0.0);
+                finished = true;
+            }
+        }
+
+        while pending.len() >= IMPORT_CHUNK_SAMPLES || (finished && !pending.is_empty()) {
+            let take = pending.len().min(IMPORT_CHUNK_SAMPLES);
+            let chunk: Vec<f32> = pending.drain(..take).collect();
+            clock.advance(chunk.len(), TARGET_SAMPLE_RATE);
+            let tail = overlap.next(&chunk, TARGET_SAMPLE_RATE, overlap_ms);
+
+            let transcription = state.asr_engine.write().as_mut()
+                .and_then(|asr| asr.process_system(&chunk, &tail, TARGET_SAMPLE_RATE));
+            let Some(transcription) = transcription.filter(|t| t.is_final && !t.text.trim().is_empty()) else {
+                continue;
+            };
+
+            // The VAD reports a segment once it has ended, so it finishes about here in the file
+            let end_ms = meeting_start + clock.elapsed_ms();
+            batch.push(PendingSegment {
+                meeting_id: meeting_id.to_string(),
+                speaker: remote_label.clone(),
+                text: transcription.text,
+                start_ms: end_ms.saturating_sub(transcription.duration_ms).max(meeting_start),
+                end_ms,
+                word_timings: transcription.word_timings,
+                language: Some(transcription.language),
+                emotion: transcription.emotion.label().map(str::to_string),
+            });
+            if batch.len() >= IMPORT_SEGMENT_BATCH {
+                saved += save_batch(std::mem::take(&mut batch))?;
+            }
+        }
+
+        if last_emit.elapsed().as_millis() >= 500 {
+            let percent = decoder.progress().map(|p| p * 90.0).unwrap_or(0.0);
+            emit_import_progress(app, meeting_id, "transcribing", percent, saved + batch.len(), None);
+            last_emit = std::time::Instant::now();
+        }
+    }
+    saved += save_batch(batch)?;
+    if let Some(asr) = state.asr_engine.write().as_mut() {
+        asr.reset();
+    }
+
+    let duration_ms = decoder.duration_ms().unwrap_or_else(|| clock.elapsed_ms().saturating_sub(1000));
+    println!("[Import] Transcribed {}ms of audio into {} segment(s)", duration_ms, saved);
+
+    if diarization_audio.is_empty() {
+        return Ok(TranscribedImport { duration_ms, segments: saved, diarization: None });
+    }
+
+    emit_import_progress(app, meeting_id, "diarizing", 90.0, saved, None);
+    let mut diar_guard = state.diarization_engine.write();
+    let Some(ref mut diar_engine) = *diar_guard else {
+        println!("[Import] Diarization engine not initialized - keeping '{}' labels", remote_label);
+        return Ok(TranscribedImport { duration_ms, segments: saved, diarization: None });
+    };
+    let (min_speakers, max_speakers) = diar_engine.speaker_bounds();
+    let diarization = match diar_engine.process(diarization_audio, TARGET_SAMPLE_RATE, min_speakers, max_speakers) {
+        Ok(output) => {
+            *state.last_diarization.lock() = Some(DiarizationQuality::from_output(meeting_id, &output));
+            Some(output)
+        }
+        Err(e) => {
+            eprintln!("[Import] Diarization failed: {}", e);
+            None
+        }
+    };
+
+    Ok(TranscribedImport { duration_ms, segments: saved, diarization })
+}
+
+// Import a recorded call: decode it to 16kHz mono, transcribe it into a new meeting,
+// then diarize and relabel its speakers. Emits `audio-import-progress` events
+#[tauri::command]
+async fn import_audio_file(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    path: String,
+    title: Option<String>,
+) -> Result<AudioImportResult, String> {
+    if state.is_recording.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err("Stop recording before importing an audio file".to_string());
+    }
+    if state.asr_engine.read().is_none() {
+        return Err("ASR engine not initialized".to_string());
+    }
+    if state.importing_audio.swap(true, std::sync::atomic::Ordering::SeqCst) {
+        return Err("An audio file import is already in progress".to_string());
+    }
+
+    let result = import_audio_file_inner(&state, &app, std::path::PathBuf::from(path), title).await;
+    state.importing_audio.store(false, std::sync::atomic::Ordering::SeqCst);
+    result
+}
+
+async fn import_audio_file_inner(
+    state: &AppState,
+    app: &tauri::AppHandle,
+    path: std::path::PathBuf,
+    title: Option<String>,
+) -> Result<AudioImportResult, String> {
+    // Open the file first so an unreadable one doesn't leave an empty meeting behind
+    let decoder = {
+        let path = path.clone();
+        tokio::task::spawn_blocking(move || audio_import::AudioFileDecoder::open(&path))
+            .await
+            .map_err(|e| format!("Failed to open audio file: {}", e))??
+    };
+    let title = title
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty())
+        .unwrap_or_else(|| audio_import::title_from_path(&path));
+
+    let meeting_id = {
+        let kb_guard = state.knowledge_base.read().await;
+        let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+        kb.create_meeting(&title, Vec::new()).await?
+    };
+    // Segments are placed from the meeting's start as if it were recorded just now
+    let meeting_start = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    println!("[Import] Importing {} into meeting {}", path.display(), meeting_id);
+    emit_import_progress(app, &meeting_id, "transcribing", 0.0, 0, None);
+
+    let transcribed = {
+        let app = app.clone();
+        let meeting_id = meeting_id.clone();
+        tokio::task::spawn_blocking(move || transcribe_imported_audio(&app, &meeting_id, meeting_start, decoder))
+            .await
+            .map_err(|e| format!("Audio import failed: {}", e))
+            .and_then(|r| r)
+    };
+    let transcribed = match transcribed {
+        Ok(transcribed) => transcribed,
+        Err(e) => {
+            // Keep whatever was transcribed before the failure
+            if let Some(kb) = state.knowledge_base.read().await.as_ref() {
+                let _ = kb.end_meeting(&meeting_id, None).await;
+            }
+            emit_import_progress(app, &meeting_id, "failed", 100.0, 0, Some(e.clone()));
+            return Err(e);
+        }
+    };
+
+    emit_import_progress(app, &meeting_id, "saving", 95.0, transcribed.segments, None);
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    let mut speakers = None;
+    if let Some(output) = transcribed.diarization {
+        // Diarization times are offsets into the file; segments are stored from the meeting start
+        let diar_tuples: Vec<DiarizationTuple> = output.segments.iter()
+            .map(|s| (s.start_ms + meeting_start, s.end_ms + meeting_start, s.speaker_id, s.speaker_label.clone(), s.confidence))
+            .collect();
+        let crosstalk: Vec<(u64, u64)> = output.overlap_regions.iter()
+            .map(|r| (r.start_ms + meeting_start, r.end_ms + meeting_start))
+            .collect();
+        speakers = Some(output.segments.iter()
+            .map(|s| s.speaker_id)
+            .collect::<std::collections::HashSet<_>>()
+            .len());
+
+        match kb.relabel_all_speakers(&meeting_id, &diar_tuples).await {
+            Ok(count) => println!("[Import] Relabeled {} segments across {} speaker(s)", count, speakers.unwrap_or(0)),
+            Err(e) => eprintln!("[Import] Relabeling failed: {}", e),
+        }
+        if let Err(e) = kb.mark_overlapped_segments(&meeting_id, &crosstalk).await {
+            eprintln!("[Import] Crosstalk flagging failed: {}", e);
+        }
+    }
+
+    // The meeting lasts as long as the recording, not until the import finished
+    kb.end_meeting_at(&meeting_id, None, meeting_start + transcribed.duration_ms).await?;
+    emit_import_progress(app, &meeting_id, "done", 100.0, transcribed.segments, None);
+
+    Ok(AudioImportResult {
+        meeting_id,
+        duration_ms: transcribed.duration_ms,
+        segments: transcribed.segments,
+        speakers,
+    })
+}
+
+// Reopen a recently ended meeting and resume recording into it
+// Segments keep wall-clock timestamps, so they line up after the original start_time
+#[tauri::command]
+async fn resume_meeting(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    meeting_id: MeetingId,
+) -> Result<(), String> {
+    if state.current_meeting_id.lock().is_some() {
+        return Err("A meeting is already in progress".to_string());
+    }
+
+    let window_minutes = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|store| store.get_settings().ok())
+            .map(|settings| settings.resume_window_minutes)
+            .unwrap_or(60)
+    };
+
+    let id_part = normalize_record_id("meeting", meeting_id.as_str());
+    let (summary, recorded_ms) = {
+        let kb_guard = state.knowledge_base.read().await;
+        let kb = kb_guard.as_ref()
+            .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+        let meeting = kb.get_meeting(&id_part).await?
+            .ok_or_else(|| format!("Meeting not found: {}", id_part))?;
+
+        if let Some(end_time) = meeting.end_time {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            let ended_minutes_ago = now.saturating_sub(end_time) / 60_000;
+            if ended_minutes_ago > window_minutes {
+                return Err(format!(
+                    "Meeting ended {} minutes ago; only meetings ended within {} minutes can be resumed",
+                    ended_minutes_ago, window_minutes
+                ));
+            }
+        }
+
+        kb.reopen_meeting(&id_part).await?;
+        // The earlier recording (its start-to-end span) counts toward max_recording_minutes
+        let recorded_ms = meeting.end_time.map_or(0, |end| end.saturating_sub(meeting.start_time));
+        (meeting.summary, recorded_ms)
+    };
+
+    *state.speaker_labels.write() = configured_speaker_labels(&state);
+    *state.current_meeting_id.lock() = Some(id_part.clone());
+
+    if !state.is_recording.load(std::sync::atomic::Ordering::SeqCst) {
+        state.resumed_active_ms.store(recorded_ms, std::sync::atomic::Ordering::SeqCst);
+        if let Err(e) = start_recording(state.clone(), app) {
+            // Put the meeting back the way we found it
+            *state.current_meeting_id.lock() = None;
+            state.resumed_active_ms.store(0, std::sync::atomic::Ordering::SeqCst);
+            let kb_guard = state.knowledge_base.read().await;
+            if let Some(kb) = kb_guard.as_ref() {
+                let _ = kb.end_meeting(&id_part, summary).await;
+            }
+            return Err(format!("Failed to resume recording: {}", e));
+        }
+    }
+
+    println!("[MEETING] Resumed meeting: {}", id_part);
+    Ok(())
+}
+
+/// Directory for exported meeting recordings (next to the knowledge base data)
+fn recordings_dir() -> std::path::PathBuf {
+    knowledge_base_dir().join("recordings")
+}
+
+/// Recording suffixes written by `write_meeting_recordings`
+const RECORDING_SUFFIXES: [&str; 3] = ["combined", "mic", "system"];
+
+/// Filesystem-safe stem shared by a meeting's WAV files
+fn recording_file_stem(meeting_id: &str) -> String {
+    normalize_record_id("meeting", meeting_id)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect()
+}
+
+/// WAV files saved for a meeting that exist on disk
+fn meeting_recording_paths(meeting_id: &str) -> Vec<std::path::PathBuf> {
+    let dir = recordings_dir();
+    let file_stem = recording_file_stem(meeting_id);
+    RECORDING_SUFFIXES.iter()
+        .map(|suffix| dir.join(format!("{}_{}.wav", file_stem, suffix)))
+        .filter(|path| path.exists())
+        .collect()
+}
+
+/// Permanently delete a meeting from the knowledge base along with its WAV recordings and note links
+/// Linked knowledge sources are kept; only the meeting's links to them are removed.
+async fn remove_meeting(state: &AppState, kb: &KnowledgeBase, meeting_id: &str) -> Result<usize, String> {
+    kb.purge_meeting(meeting_id).await?;
+
+    let mut recordings_deleted = 0;
+    for path in meeting_recording_paths(meeting_id) {
+        match std::fs::remove_file(&path) {
+            Ok(()) => recordings_deleted += 1,
+            Err(e) => eprintln!("[Recording] Failed to delete {}: {}", path.display(), e),
+        }
+    }
+
+    let store_guard = state.user_store.lock();
+    if let Some(store) = store_guard.as_ref() {
+        if let Err(e) = store.unlink_meeting_notes(&normalize_record_id("meeting", meeting_id)) {
+            eprintln!("[Notes] {}", e);
+        }
+    }
+    Ok(recordings_deleted)
+}
+
+/// Write buffered 16kHz meeting audio to WAV files, returning the written paths
+/// In combined mode the mic stream already contains every speaker, so a single file is written
+fn write_meeting_recordings(
+    meeting_id: &str,
+    mic_audio: &[f32],
+    system_audio: &[f32],
+    combined_mode: bool,
+) -> Result<Vec<String>, String> {
+    let dir = recordings_dir();
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create recordings directory: {}", e))?;
+
+    let file_stem = recording_file_stem(meeting_id);
+
+    let outputs: Vec<(&str, &[f32])> = if combined_mode {
+        vec![("combined", mic_audio)]
+    } else {
+        vec![("mic", mic_audio), ("system", system_audio)]
+    };
+
+    let mut paths = Vec::new();
+    for (suffix, samples) in outputs {
+        if samples.is_empty() {
+            continue;
+        }
+        let path = dir.join(format!("{}_{}.wav", file_stem, suffix));
+        audio::write_wav_16k_mono(&path, samples)?;
+        paths.push(path.to_string_lossy().to_string());
+    }
+
+    if paths.is_empty() {
+        return Err("No recorded audio to save".to_string());
+    }
+
+    Ok(paths)
+}
+
+// Save the current meeting's buffered audio as WAV files
+#[tauri::command]
+fn save_recording(
+    state: tauri::State<AppState>,
+    meeting_id: MeetingId,
+) -> Result<Vec<String>, String> {
+    {
+        let current = state.current_meeting_id.lock();
+        match current.as_ref() {
+            Some(id) if *id == meeting_id.as_str() => {}
+            _ => return Err("Recorded audio is only kept in memory for the meeting in progress".to_string()),
+        }
+    }
+
+    let mic_audio = state.mic_audio_buffer.lock().clone();
+    let system_audio = state.system_audio_buffer.lock().clone();
+    let is_combined_mode = check_audio_capabilities().capture_mode == AudioCaptureMode::Combined;
+
+    write_meeting_recordings(meeting_id.as_str(), &mic_audio, &system_audio, is_combined_mode)
+}
+
+// Add transcript segment to current meeting (or to a paused session's meeting)
 #[tauri::command]
 async fn add_transcript_segment(
     state: tauri::State<'_, AppState>,
@@ -624,41 +2098,137 @@ async fn add_transcript_segment(
     text: String,
     start_ms: u64,
     end_ms: u64,
-) -> Result<String, String> {
-    let meeting_id = {
-        let current = state.current_meeting_id.lock();
-        current.clone().ok_or("No meeting in progress")?
+    session_id: Option<String>,
+) -> Result<String, SecondBrainError> {
+    let paused_meeting = session_id.as_ref()
+        .and_then(|id| state.paused_sessions.lock().get(id).map(|s| s.meeting_id.clone()));
+    let meeting_id = match paused_meeting {
+        Some(id) => id,
+        None => {
+            if let Some(ref id) = session_id {
+                if state.active_session.lock().as_deref().unwrap_or(DEFAULT_SESSION_ID) != id.as_str() {
+                    return Err(SecondBrainError::NotFound(format!("No session '{}'", id)));
+                }
+            }
+            let current = state.current_meeting_id.lock();
+            current.clone().ok_or("No meeting in progress")?
+        }
     };
 
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.add_segment(&meeting_id, &speaker, &text, start_ms, end_ms).await
 }
 
+// Configure merging of near-identical consecutive segments
+#[tauri::command]
+async fn set_segment_dedup_config(
+    state: tauri::State<'_, AppState>,
+    enabled: Option<bool>,
+    similarity_threshold: Option<f32>,
+    window_ms: Option<u64>,
+) -> Result<SegmentDedupConfig, String> {
+    if let Some(t) = similarity_threshold {
+        if !(0.0..=1.0).contains(&t) {
+            return Err("similarity_threshold must be between 0.0 and 1.0".to_string());
+        }
+    }
+
+    let mut kb_guard = state.knowledge_base.write().await;
+    let kb = kb_guard.as_mut()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    let mut config = kb.dedup_config().clone();
+    if let Some(e) = enabled {
+        config.enabled = e;
+    }
+    if let Some(t) = similarity_threshold {
+        config.similarity_threshold = t;
+    }
+    if let Some(w) = window_ms {
+        config.window_ms = w;
+    }
+    kb.set_dedup_config(config.clone());
+
+    Ok(config)
+}
+
+// Rename a speaker label within a meeting (merges if the new name already exists)
+#[tauri::command]
+async fn rename_meeting_speaker(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+    old_label: String,
+    new_label: String,
+) -> Result<usize, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.rename_speaker(meeting_id.as_str(), &old_label, &new_label).await
+}
+
 // Search knowledge base
 #[tauri::command]
 async fn search_knowledge(
     state: tauri::State<'_, AppState>,
     query: String,
     limit: Option<usize>,
-) -> Result<Vec<SearchResult>, String> {
+    min_similarity: Option<f32>,
+) -> Result<Vec<SearchResult>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.search_similar(&query, limit.unwrap_or(10), min_similarity).await
+}
+
+// Find meetings by what was discussed in them
+#[tauri::command]
+async fn search_meetings(
+    state: tauri::State<'_, AppState>,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<MeetingSearchResult>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.search_meetings(&query, limit.unwrap_or(10)).await
+}
+
+// Find in one meeting: semantic by default, or plain text with `text_only`
+#[tauri::command]
+async fn search_within_meeting(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+    query: String,
+    limit: Option<usize>,
+    min_similarity: Option<f32>,
+    text_only: Option<bool>,
+) -> Result<Vec<SegmentMatch>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.search_similar(&query, limit.unwrap_or(10)).await
+    let limit = limit.unwrap_or(20);
+    if text_only.unwrap_or(false) {
+        kb.search_text_within_meeting(meeting_id.as_str(), &query, limit).await
+    } else {
+        kb.search_within_meeting(meeting_id.as_str(), &query, limit, min_similarity).await
+    }
 }
 
 // Get open action items
 #[tauri::command]
 async fn get_action_items(
     state: tauri::State<'_, AppState>,
-) -> Result<Vec<ActionItem>, String> {
+) -> Result<Vec<ActionItem>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.get_open_actions().await
 }
@@ -668,10 +2238,10 @@ async fn get_action_items(
 async fn get_decisions(
     state: tauri::State<'_, AppState>,
     limit: Option<usize>,
-) -> Result<Vec<Decision>, String> {
+) -> Result<Vec<Decision>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.get_recent_decisions(limit.unwrap_or(10)).await
 }
@@ -683,116 +2253,471 @@ async fn get_decisions(
 async fn get_meetings(
     state: tauri::State<'_, AppState>,
     limit: Option<usize>,
-) -> Result<Vec<Meeting>, String> {
+    offset: Option<usize>,
+    tags: Option<Vec<String>>,
+    favorites_only: Option<bool>,
+    since: Option<u64>,
+    language: Option<String>,
+) -> Result<PagedResult<Meeting>, String> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.get_meetings(limit).await
+    let favorites_only = favorites_only.unwrap_or(false);
+    let language = language.as_deref().and_then(normalize_language);
+    let items = kb.get_meetings(limit, offset, tags.clone(), favorites_only, since, language.clone()).await?;
+    let total = kb.count_meetings(tags, favorites_only, since, language).await?;
+    Ok(PagedResult { items, total })
 }
 
-// Get a single meeting by ID
+// Replace the tags on a meeting
 #[tauri::command]
-async fn get_meeting(
+async fn set_meeting_tags(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
-) -> Result<Option<Meeting>, String> {
+    meeting_id: MeetingId,
+    tags: Vec<String>,
+) -> Result<Vec<String>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.get_meeting(&meeting_id).await
+    kb.set_meeting_tags(meeting_id.as_str(), tags).await
 }
 
-// Get transcript segments for a meeting
+// Toggle a meeting's favorite flag
 #[tauri::command]
-async fn get_meeting_segments(
+async fn toggle_meeting_favorite(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
-) -> Result<Vec<TranscriptSegment>, String> {
+    meeting_id: MeetingId,
+) -> Result<bool, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.get_meeting_segments(&meeting_id).await
+    kb.toggle_meeting_favorite(meeting_id.as_str()).await
 }
 
-// Get action items for a meeting
+// Mark a meeting private: it stays in the meeting list and ask_about_meeting,
+// but never feeds global search or Graph-RAG answers
 #[tauri::command]
-async fn get_meeting_action_items(
+async fn set_meeting_private(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
-) -> Result<Vec<ActionItem>, String> {
+    meeting_id: MeetingId,
+    private: bool,
+) -> Result<(), SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.get_meeting_action_items(&meeting_id).await
+    kb.set_meeting_private(meeting_id.as_str(), private).await
 }
 
-// Get decisions for a meeting
+// Get all tags used across meetings (for filter dropdowns)
+#[tauri::command]
+async fn get_all_meeting_tags(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<String>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_all_meeting_tags().await
+}
+
+// Get a single meeting by ID
+#[tauri::command]
+async fn get_meeting(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+) -> Result<Option<Meeting>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_meeting(meeting_id.as_str()).await
+}
+
+// Get transcript segments for a meeting
+#[tauri::command]
+async fn get_meeting_segments(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<PagedResult<TranscriptSegment>, String> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    let items = kb.get_meeting_segments_page(meeting_id.as_str(), limit, offset).await?;
+    let total = kb.count_meeting_segments(meeting_id.as_str()).await?;
+    Ok(PagedResult { items, total })
+}
+
+// Segment playing at `ms` into the meeting (for transcript follow during playback)
+#[tauri::command]
+async fn get_segment_at_time(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+    ms: u64,
+) -> Result<Option<TranscriptSegment>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_segment_at_time(meeting_id.as_str(), ms).await
+}
+
+// First segment starting after `ms` into the meeting
+#[tauri::command]
+async fn get_next_segment(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+    ms: u64,
+) -> Result<Option<TranscriptSegment>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_next_segment(meeting_id.as_str(), ms).await
+}
+
+// Get action items for a meeting
+#[tauri::command]
+async fn get_meeting_action_items(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+) -> Result<Vec<ActionItem>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_meeting_action_items(meeting_id.as_str()).await
+}
+
+// Get decisions for a meeting
 #[tauri::command]
 async fn get_meeting_decisions(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
-) -> Result<Vec<Decision>, String> {
+    meeting_id: MeetingId,
+) -> Result<Vec<Decision>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.get_meeting_decisions(&meeting_id).await
+    kb.get_meeting_decisions(meeting_id.as_str()).await
 }
 
 // Get topics discussed in a meeting
 #[tauri::command]
 async fn get_meeting_topics(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
-) -> Result<Vec<Topic>, String> {
+    meeting_id: MeetingId,
+) -> Result<Vec<Topic>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_meeting_topics(meeting_id.as_str()).await
+}
+
+// Get a meeting's people, topics and the relations between them as nodes + edges
+#[tauri::command]
+async fn get_meeting_graph(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+) -> Result<GraphExport, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_meeting_graph(meeting_id.as_str()).await
+}
+
+// Export the knowledge graph as "json" or "graphml", optionally for a single meeting
+#[tauri::command]
+async fn export_graph(
+    state: tauri::State<'_, AppState>,
+    format: String,
+    meeting_id: Option<MeetingId>,
+) -> Result<String, SecondBrainError> {
+    let format = GraphExportFormat::parse(&format)?;
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.get_meeting_topics(&meeting_id).await
+    kb.export_graph(format, meeting_id.as_ref().map(|id| id.as_str())).await
 }
 
 // Get people mentioned in a meeting
 #[tauri::command]
 async fn get_meeting_people(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
-) -> Result<Vec<Person>, String> {
+    meeting_id: MeetingId,
+) -> Result<Vec<Person>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.get_meeting_people(&meeting_id).await
+    kb.get_meeting_people(meeting_id.as_str()).await
 }
 
 // Get meeting statistics
 #[tauri::command]
 async fn get_meeting_stats(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
-) -> Result<MeetingStats, String> {
+    meeting_id: MeetingId,
+) -> Result<MeetingStats, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_meeting_stats(meeting_id.as_str()).await
+}
+
+// Fix the text of a stored transcript segment
+#[tauri::command]
+async fn edit_transcript_segment(
+    state: tauri::State<'_, AppState>,
+    segment_id: String,
+    new_text: String,
+) -> Result<TranscriptSegment, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.edit_segment(&segment_id, &new_text).await
+}
+
+// Get a meeting's emotion timeline and per-speaker emotions, for a sentiment graph
+// `bucket_secs` sets the timeline resolution (default one minute)
+#[tauri::command]
+async fn get_meeting_sentiment(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+    bucket_secs: Option<u64>,
+) -> Result<MeetingSentiment, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_meeting_sentiment(meeting_id.as_str(), bucket_secs.map(|secs| secs.saturating_mul(1000))).await
+}
+
+// Aggregate stats for meetings started between `start` and `end` (epoch ms): count and
+// time, top topics and people, and open vs done action items
+#[tauri::command]
+async fn get_analytics(
+    state: tauri::State<'_, AppState>,
+    start: u64,
+    end: u64,
+) -> Result<MeetingAnalytics, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_analytics(start, end).await
+}
+
+// Get per-speaker talk time for a meeting
+#[tauri::command]
+async fn get_meeting_speaker_stats(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+) -> Result<MeetingSpeakerStats, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.get_meeting_stats(&meeting_id).await
+    kb.get_meeting_speaker_stats(meeting_id.as_str()).await
 }
 
-// Delete a meeting and all associated data
+// Move a meeting to the trash; it's purged for good after TRASH_GRACE_DAYS unless restored
 #[tauri::command]
 async fn delete_meeting(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
+    meeting_id: MeetingId,
+) -> Result<(), SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.trash_meeting(meeting_id.as_str()).await
+}
+
+// Take a meeting back out of the trash
+#[tauri::command]
+async fn restore_meeting(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+) -> Result<(), SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.restore_meeting(meeting_id.as_str()).await
+}
+
+// Permanently delete a meeting and all associated data, including saved recordings
+#[tauri::command]
+async fn purge_meeting(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
 ) -> Result<(), String> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    remove_meeting(&state, kb, meeting_id.as_str()).await.map(|_| ())
+}
+
+// List meetings in the trash, most recently deleted first
+#[tauri::command]
+async fn get_trashed_meetings(
+    state: tauri::State<'_, AppState>,
+) -> Result<Vec<Meeting>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_trashed_meetings().await
+}
+
+/// Days a trashed meeting can still be restored before it's purged
+const TRASH_GRACE_DAYS: u64 = 30;
+
+/// How often the trash is checked for meetings past the grace period
+const TRASH_PURGE_POLL: std::time::Duration = std::time::Duration::from_secs(6 * 60 * 60);
+
+/// Purge every meeting that has been in the trash longer than TRASH_GRACE_DAYS
+/// Returns how many were purged; failures are logged and retried next round.
+async fn purge_expired_trash(state: &AppState, kb: &KnowledgeBase) -> Result<usize, String> {
+    let cutoff = now_ms().saturating_sub(TRASH_GRACE_DAYS * 24 * 60 * 60 * 1000);
+    let mut purged = 0;
+    for meeting in kb.get_meetings_trashed_before(cutoff).await? {
+        let Some(meeting_id) = meeting.id.as_ref().map(|t| normalize_record_id("meeting", &t.to_string())) else {
+            continue;
+        };
+        match remove_meeting(state, kb, &meeting_id).await {
+            Ok(_) => purged += 1,
+            Err(e) => eprintln!("[Trash] Failed to purge {}: {}", meeting_id, e),
+        }
+    }
+    Ok(purged)
+}
+
+/// Start the background loop that empties expired meetings out of the trash
+/// Runs once right away (startup), then every TRASH_PURGE_POLL.
+fn start_trash_purge_loop(state: &AppState, app: &tauri::AppHandle) {
+    if state.trash_purge_started.swap(true, std::sync::atomic::Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let state = app.state::<AppState>();
+            {
+                let kb_guard = state.knowledge_base.read().await;
+                if let Some(kb) = kb_guard.as_ref() {
+                    match purge_expired_trash(&state, kb).await {
+                        Ok(count) if count > 0 => println!("[Trash] Purged {} meeting(s) deleted over {} days ago", count, TRASH_GRACE_DAYS),
+                        Ok(_) => {}
+                        Err(e) => eprintln!("[Trash] Purge failed: {}", e),
+                    }
+                }
+            }
+            tokio::time::sleep(TRASH_PURGE_POLL).await;
+        }
+    });
+}
+
+/// A meeting the retention policy would delete
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RetentionCandidate {
+    pub meeting_id: String,
+    pub title: String,
+    pub start_time: u64,
+    pub recordings: Vec<String>,  // WAV files deleted with it
+}
+
+/// What a retention purge deleted
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct RetentionPurgeSummary {
+    pub retention_days: u64,
+    pub purged: Vec<RetentionCandidate>,
+    pub recordings_deleted: usize,
+    pub failed: Vec<String>,  // "<meeting id>: <error>"
+}
+
+/// Longest `retention_days` accepted (about a century); 0 keeps meetings forever
+const RETENTION_DAYS_LIMIT: u64 = 36_500;
+
+fn validate_retention_days(days: u64) -> Result<(), String> {
+    if days > RETENTION_DAYS_LIMIT {
+        return Err(format!("retention_days must be between 0 and {}", RETENTION_DAYS_LIMIT));
+    }
+    Ok(())
+}
+
+/// Saved `retention_days` (0 = keep meetings forever)
+fn configured_retention_days(state: &AppState) -> u64 {
+    let store_guard = state.user_store.lock();
+    store_guard.as_ref()
+        .and_then(|s| s.get_settings().ok())
+        .map(|s| s.retention_days)
+        .unwrap_or(0)
+}
+
+async fn retention_candidates(kb: &KnowledgeBase, retention_days: u64) -> Result<Vec<RetentionCandidate>, String> {
+    let meetings = kb.get_meetings_past_retention(retention_days).await?;
+    Ok(meetings.into_iter()
+        .filter_map(|m| {
+            let meeting_id = normalize_record_id("meeting", &m.id.as_ref()?.to_string());
+            let recordings = meeting_recording_paths(&meeting_id).iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect();
+            Some(RetentionCandidate { meeting_id, title: m.title, start_time: m.start_time, recordings })
+        })
+        .collect())
+}
+
+/// Delete every meeting past the retention policy (no-op when it's off)
+async fn purge_expired_meetings(state: &AppState, kb: &KnowledgeBase) -> Result<RetentionPurgeSummary, String> {
+    let retention_days = configured_retention_days(state);
+    let mut summary = RetentionPurgeSummary { retention_days, ..Default::default() };
+    if retention_days == 0 {
+        return Ok(summary);
+    }
+
+    for candidate in retention_candidates(kb, retention_days).await? {
+        match remove_meeting(state, kb, &candidate.meeting_id).await {
+            Ok(deleted) => {
+                summary.recordings_deleted += deleted;
+                summary.purged.push(candidate);
+            }
+            Err(e) => summary.failed.push(format!("{}: {}", candidate.meeting_id, e)),
+        }
+    }
+    Ok(summary)
+}
+
+// List the meetings a retention purge would delete, without deleting anything
+// `retention_days` defaults to the saved setting, so a policy can be reviewed before enabling it
+#[tauri::command]
+async fn preview_retention_purge(
+    state: tauri::State<'_, AppState>,
+    retention_days: Option<u64>,
+) -> Result<Vec<RetentionCandidate>, String> {
+    let retention_days = match retention_days.unwrap_or_else(|| configured_retention_days(&state)) {
+        0 => return Err("Retention is off; pass retention_days to preview a policy".to_string()),
+        days => days,
+    };
+    validate_retention_days(retention_days)?;
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.delete_meeting(&meeting_id).await
+    retention_candidates(kb, retention_days).await
 }
 
 // Get ALL action items across all meetings
@@ -800,23 +2725,37 @@ async fn delete_meeting(
 async fn get_all_action_items(
     state: tauri::State<'_, AppState>,
     limit: Option<usize>,
-) -> Result<Vec<serde_json::Value>, String> {
+) -> Result<Vec<serde_json::Value>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.get_all_action_items(limit.unwrap_or(50)).await
 }
 
+// Export all action items as "ical" (VTODO), "markdown" (checklist) or "csv"
+#[tauri::command]
+async fn export_action_items(
+    state: tauri::State<'_, AppState>,
+    format: String,
+) -> Result<String, SecondBrainError> {
+    let format = ActionExportFormat::parse(&format)?;
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.export_action_items(format).await
+}
+
 // Get ALL decisions across all meetings
 #[tauri::command]
 async fn get_all_decisions(
     state: tauri::State<'_, AppState>,
     limit: Option<usize>,
-) -> Result<Vec<serde_json::Value>, String> {
+) -> Result<Vec<serde_json::Value>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.get_all_decisions(limit.unwrap_or(20)).await
 }
@@ -825,10 +2764,10 @@ async fn get_all_decisions(
 #[tauri::command]
 async fn get_knowledge_stats(
     state: tauri::State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
+) -> Result<serde_json::Value, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.get_global_stats().await
 }
@@ -839,14 +2778,71 @@ async fn update_action_item_status(
     state: tauri::State<'_, AppState>,
     action_id: String,
     status: String,
-) -> Result<(), String> {
+) -> Result<(), SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.update_action_item_status(&action_id, &status).await
 }
 
+// Set or clear who owns an action item
+#[tauri::command]
+async fn set_action_item_assignee(
+    state: tauri::State<'_, AppState>,
+    action_id: String,
+    assignee: Option<String>,
+) -> Result<ActionItem, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.set_action_item_assignee(&action_id, assignee.as_deref()).await
+}
+
+// Set or clear an action item's deadline
+#[tauri::command]
+async fn set_action_item_deadline(
+    state: tauri::State<'_, AppState>,
+    action_id: String,
+    deadline: Option<String>,
+) -> Result<ActionItem, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.set_action_item_deadline(&action_id, deadline.as_deref()).await
+}
+
+// List action items nobody owns, or that point at a meeting that no longer exists
+#[tauri::command]
+async fn get_unassigned_action_items(
+    state: tauri::State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<Vec<serde_json::Value>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_unassigned_action_items(limit.unwrap_or(50)).await
+}
+
+// Move an action item to another meeting; omitted assignee/deadline are left unchanged
+#[tauri::command]
+async fn reassign_action_item(
+    state: tauri::State<'_, AppState>,
+    action_id: String,
+    meeting_id: MeetingId,
+    assignee: Option<String>,
+    deadline: Option<String>,
+) -> Result<ActionItem, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.reassign_action_item(&action_id, meeting_id.as_str(), assignee.as_deref(), deadline.as_deref()).await
+}
+
 // Get current meeting ID
 #[tauri::command]
 fn get_current_meeting_id(state: tauri::State<AppState>) -> Option<String> {
@@ -857,38 +2853,58 @@ fn get_current_meeting_id(state: tauri::State<AppState>) -> Option<String> {
 #[tauri::command]
 fn initialize_llm(
     state: tauri::State<AppState>,
+    app: tauri::AppHandle,
     api_url: Option<String>,
     model: Option<String>,
     api_key: Option<String>,
+    provider: Option<String>,
+    fallbacks: Option<Vec<LlmEndpoint>>,
 ) -> Result<(), String> {
     let mut llm_guard = state.llm_assistant.write();
 
     // Get settings from user store
-    let (stored_url, stored_model, stored_api_key) = {
+    let (stored_url, stored_model, stored_api_key, stored_provider, stored_fallbacks) = {
         let store_guard = state.user_store.lock();
         if let Some(ref store) = *store_guard {
             if let Ok(settings) = store.get_settings() {
-                (settings.llm_url.clone(), settings.llm_model.clone(), settings.llm_api_key.clone())
+                (settings.llm_url.clone(), settings.llm_model.clone(), settings.llm_api_key.clone(), settings.llm_provider.clone(), settings.llm_fallbacks.clone())
             } else {
-                (String::new(), String::new(), String::new())
+                (String::new(), String::new(), String::new(), String::new(), String::new())
             }
         } else {
-            (String::new(), String::new(), String::new())
+            (String::new(), String::new(), String::new(), String::new(), String::new())
         }
     };
 
-    // Get URL from param or user settings
+    // Get provider from param or user settings (defaults to OpenAI-compatible)
+    let provider = match provider {
+        Some(p) if !p.trim().is_empty() => LlmProvider::parse(&p),
+        _ => LlmProvider::parse(&stored_provider),
+    };
+
+    // Get URL from param or user settings, falling back to the provider default
     let url = match api_url {
         Some(u) if !u.trim().is_empty() => u,
         _ => {
             if !stored_url.trim().is_empty() {
                 stored_url
+            } else if let Some(default_url) = provider.default_url() {
+                default_url.to_string()
             } else {
                 return Err("LLM URL not configured. Please configure in settings.".to_string());
             }
         }
     };
 
+    // Offline mode only allows an endpoint on this machine
+    let (provider, url) = if offline_mode(&state) && !is_local_url(&url) {
+        let local = LlmProvider::Ollama;
+        println!("[Offline] Remote LLM endpoint {} replaced with local {}", url, local.as_str());
+        (local, local.default_url().unwrap_or_default().to_string())
+    } else {
+        (provider, url)
+    };
+
     // Get model from param or user settings
     let model_name = match model {
         Some(m) if !m.trim().is_empty() => m,
@@ -907,74 +2923,396 @@ fn initialize_llm(
         _ => stored_api_key,
     };
 
+    // Fallback endpoints from param or user settings; offline mode drops remote ones
+    let fallbacks = match fallbacks {
+        Some(list) => list,
+        None => LlmEndpoint::list_from_config(&stored_fallbacks)?,
+    };
+    let fallbacks: Vec<LlmEndpoint> = if offline_mode(&state) {
+        fallbacks.into_iter()
+            .filter(|e| is_local_url(if e.url.trim().is_empty() { e.provider.default_url().unwrap_or_default() } else { &e.url }))
+            .collect()
+    } else {
+        fallbacks
+    };
+
     // Re-initialize even if already initialized (allows changing settings)
-    let assistant = Arc::new(MeetingAssistant::new(&url, &model_name, &key));
-    *llm_guard = Some(assistant);
+    let assistant = MeetingAssistant::new(provider, &url, &model_name, &key)
+        .with_fallbacks(&fallbacks)
+        .with_generation_settings(configured_generation_settings(&state))
+        .with_prompt_template(configured_prompt_template(&state))
+        .with_retrieval_config(configured_retrieval_config(&state))
+        .with_usage_callback(usage_recorder(&app));
+    *llm_guard = Some(Arc::new(assistant));
+
+    println!("LLM assistant initialized with provider: {}, URL: {} and model: {} ({} fallback endpoint(s))",
+        provider.as_str(), url, model_name, fallbacks.len());
+    Ok(())
+}
+
+/// Error returned by anything that would reach the network while offline mode is on
+const OFFLINE_MODE_ERROR: &str = "Offline mode enabled: network access is disabled";
+
+/// Whether the user turned on offline mode
+fn offline_mode(state: &AppState) -> bool {
+    let store_guard = state.user_store.lock();
+    store_guard.as_ref()
+        .and_then(|s| s.get_settings().ok())
+        .map(|s| s.offline_mode)
+        .unwrap_or(false)
+}
 
-    println!("LLM assistant initialized with URL: {} and model: {}", url, model_name);
+/// Refuse network access in offline mode
+fn ensure_online(state: &AppState) -> Result<(), String> {
+    if offline_mode(state) {
+        return Err(OFFLINE_MODE_ERROR.to_string());
+    }
     Ok(())
 }
 
-// Ask the LLM assistant a question
-#[tauri::command]
-async fn ask_assistant(
-    state: tauri::State<'_, AppState>,
-    question: String,
-) -> Result<String, String> {
-    let assistant = {
-        let guard = state.llm_assistant.read();
-        guard.as_ref()
-            .ok_or("LLM assistant not initialized. Call initialize_llm first.")?
-            .clone()
-    };
+/// Refuse remote LLM endpoints in offline mode; local ones are always allowed
+fn ensure_llm_endpoint_allowed(state: &AppState, api_url: &str) -> Result<(), String> {
+    if offline_mode(state) && !is_local_url(api_url) {
+        return Err(format!("{} (LLM endpoint must be local)", OFFLINE_MODE_ERROR));
+    }
+    Ok(())
+}
 
-    let kb = state.knowledge_base.clone();
-    assistant.ask(&question, kb).await
+/// Re-initialize the assistant on a local endpoint if offline mode was just turned on
+fn apply_offline_mode(state: tauri::State<'_, AppState>, app: tauri::AppHandle) {
+    let remote = state.llm_assistant.read().as_ref()
+        .map(|a| a.api_urls().iter().any(|url| !is_local_url(url)))
+        .unwrap_or(false);
+    if remote && offline_mode(&state) {
+        if let Err(e) = initialize_llm(state, app, None, None, None, None, None) {
+            eprintln!("[Offline] Failed to switch LLM to a local endpoint: {}", e);
+        }
+    }
 }
 
-// Summarize a meeting
-#[tauri::command]
-async fn summarize_meeting(
-    state: tauri::State<'_, AppState>,
-    segments: Vec<String>,
-) -> Result<String, String> {
-    let assistant = {
-        let guard = state.llm_assistant.read();
-        guard.as_ref()
-            .ok_or("LLM assistant not initialized")?
-            .clone()
+/// Network-related state for the UI's offline badge
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct NetworkStatus {
+    pub offline_mode: bool,
+    pub llm_url: Option<String>,
+    pub llm_local: bool,
+}
+
+/// Per-task generation params from the saved `llm_generation_params` (built-in if unset)
+fn configured_generation_settings(state: &AppState) -> GenerationSettings {
+    let params = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| s.llm_generation_params)
+            .unwrap_or_default()
     };
+    GenerationSettings::from_config(&params).unwrap_or_else(|e| {
+        eprintln!("[LLM] {}, using defaults", e);
+        GenerationSettings::default()
+    })
+}
 
-    assistant.summarize_meeting(&segments).await
+/// Swap the saved generation params into the running assistant
+fn apply_generation_settings(state: &AppState) {
+    let generation = configured_generation_settings(state);
+    let mut llm_guard = state.llm_assistant.write();
+    if let Some(assistant) = llm_guard.as_mut() {
+        let updated = assistant.as_ref().clone().with_generation_settings(generation);
+        *assistant = Arc::new(updated);
+    }
 }
 
-// Get suggested questions
-#[tauri::command]
-async fn suggest_questions(
+/// Graph-RAG retrieval counts from the saved `retrieval_params` (defaults if unset)
+fn configured_retrieval_config(state: &AppState) -> RetrievalConfig {
+    let params = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| s.retrieval_params)
+            .unwrap_or_default()
+    };
+    RetrievalConfig::from_config(&params).unwrap_or_else(|e| {
+        eprintln!("[LLM] {}, using defaults", e);
+        RetrievalConfig::default()
+    })
+}
+
+/// Swap the saved retrieval counts into the running assistant
+fn apply_retrieval_config(state: &AppState) {
+    let retrieval = configured_retrieval_config(state);
+    let mut llm_guard = state.llm_assistant.write();
+    if let Some(assistant) = llm_guard.as_mut() {
+        let updated = assistant.as_ref().clone().with_retrieval_config(retrieval);
+        *assistant = Arc::new(updated);
+    }
+}
+
+/// Text of the active prompt template, or None for the built-in prompts
+/// A missing or invalid template falls back to the built-ins rather than breaking every prompt.
+fn configured_prompt_template(state: &AppState) -> Option<String> {
+    let store_guard = state.user_store.lock();
+    let store = store_guard.as_ref()?;
+    let name = store.get_settings().ok()?.active_prompt_template;
+    if name.trim().is_empty() {
+        return None;
+    }
+    match store.get_prompt_template(&name) {
+        Ok(Some(t)) => match validate_prompt_template(&t.template) {
+            Ok(()) => Some(t.template),
+            Err(e) => {
+                eprintln!("[LLM] Prompt template '{}' is invalid ({}), using built-in prompts", name, e);
+                None
+            }
+        },
+        Ok(None) => {
+            eprintln!("[LLM] Prompt template '{}' not found, using built-in prompts", name);
+            None
+        }
+        Err(e) => {
+            eprintln!("[LLM] {}, using built-in prompts", e);
+            None
+        }
+    }
+}
+
+/// Per-model prices from the saved `llm_pricing` (no prices if unset)
+fn configured_model_pricing(state: &AppState) -> ModelPricing {
+    let pricing = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| s.llm_pricing)
+            .unwrap_or_default()
+    };
+    ModelPricing::from_config(&pricing).unwrap_or_else(|e| {
+        eprintln!("[LLM] {}, costs not estimated", e);
+        ModelPricing::default()
+    })
+}
+
+/// Usage callback that stores each completion's tokens and emits the running session total
+fn usage_recorder(app: &tauri::AppHandle) -> UsageCallback {
+    let app = app.clone();
+    Arc::new(move |usage: LlmUsage| {
+        let state: tauri::State<AppState> = app.state();
+        let cost = configured_model_pricing(&state).cost(&usage.model, usage.usage);
+        {
+            let store_guard = state.user_store.lock();
+            if let Some(store) = store_guard.as_ref() {
+                if let Err(e) = store.record_usage(&usage) {
+                    eprintln!("[LLM] {}", e);
+                }
+            }
+        }
+        let session = {
+            let mut totals = state.llm_session_usage.lock();
+            totals.add(usage.usage, cost);
+            totals.clone()
+        };
+        let _ = app.emit("llm-usage", serde_json::json!({
+            "task": usage.task,
+            "model": usage.model,
+            "prompt_tokens": usage.usage.prompt_tokens,
+            "completion_tokens": usage.usage.completion_tokens,
+            "estimated_cost": cost,
+            "session": session,
+        }));
+    })
+}
+
+// LLM token usage and estimated cost since a date (YYYY-MM-DD; None = all time)
+#[tauri::command]
+fn get_usage_stats(state: tauri::State<AppState>, since: Option<String>) -> Result<UsageStats, String> {
+    let since = since.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    if let Some(day) = since.as_deref() {
+        chrono::NaiveDate::parse_from_str(day, "%Y-%m-%d")
+            .map_err(|_| format!("Invalid date '{}' (expected YYYY-MM-DD)", day))?;
+    }
+    let rows = {
+        let store_guard = state.user_store.lock();
+        let store = store_guard.as_ref().ok_or("User store not initialized")?;
+        store.get_usage(since.as_deref())?
+    };
+    let mut stats = UsageStats::from_rows(since, &rows, &configured_model_pricing(&state));
+    stats.session = state.llm_session_usage.lock().clone();
+    Ok(stats)
+}
+
+/// Swap the active prompt template into the running assistant
+fn apply_prompt_template(state: &AppState) {
+    let template = configured_prompt_template(state);
+    let mut llm_guard = state.llm_assistant.write();
+    if let Some(assistant) = llm_guard.as_mut() {
+        let updated = assistant.as_ref().clone().with_prompt_template(template);
+        *assistant = Arc::new(updated);
+    }
+}
+
+/// Saved prompt templates and which one is active (None = built-in prompts)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PromptTemplates {
+    pub active: Option<String>,
+    pub templates: Vec<PromptTemplate>,
+}
+
+// List prompt templates and the active one
+#[tauri::command]
+fn get_prompt_templates(state: tauri::State<AppState>) -> Result<PromptTemplates, String> {
+    let store_guard = state.user_store.lock();
+    let store = store_guard.as_ref().ok_or("User store not initialized")?;
+    let active = store.get_settings()?.active_prompt_template;
+    Ok(PromptTemplates {
+        active: Some(active).filter(|n| !n.trim().is_empty()),
+        templates: store.get_prompt_templates()?,
+    })
+}
+
+// Create or replace a named prompt template, e.g. "Be terse. Answer in Spanish.\n\n{context}"
+// `{context}` (required) is the built-in prompt; `{question}` is the user's question.
+#[tauri::command]
+fn set_prompt_template(
+    state: tauri::State<AppState>,
+    name: String,
+    template: String,
+    activate: Option<bool>,
+) -> Result<PromptTemplate, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Prompt template name is required".to_string());
+    }
+    validate_prompt_template(&template)?;
+
+    let saved = {
+        let store_guard = state.user_store.lock();
+        let store = store_guard.as_ref().ok_or("User store not initialized")?;
+        let saved = store.save_prompt_template(&name, &template)?;
+        if activate.unwrap_or(false) {
+            store.set_setting("active_prompt_template", &name)?;
+        }
+        saved
+    };
+    apply_prompt_template(&state);
+    Ok(saved)
+}
+
+// Choose the active prompt template (None = built-in prompts)
+#[tauri::command]
+fn set_active_prompt_template(state: tauri::State<AppState>, name: Option<String>) -> Result<(), String> {
+    {
+        let store_guard = state.user_store.lock();
+        let store = store_guard.as_ref().ok_or("User store not initialized")?;
+        let name = name.map(|n| n.trim().to_string()).unwrap_or_default();
+        if !name.is_empty() && store.get_prompt_template(&name)?.is_none() {
+            return Err(format!("Prompt template not found: {}", name));
+        }
+        store.set_setting("active_prompt_template", &name)?;
+    }
+    apply_prompt_template(&state);
+    Ok(())
+}
+
+// Delete a prompt template; deleting the active one switches back to the built-in prompts
+#[tauri::command]
+fn delete_prompt_template(state: tauri::State<AppState>, name: String) -> Result<(), String> {
+    {
+        let store_guard = state.user_store.lock();
+        let store = store_guard.as_ref().ok_or("User store not initialized")?;
+        store.delete_prompt_template(&name)?;
+        if store.get_settings()?.active_prompt_template == name {
+            store.set_setting("active_prompt_template", "")?;
+        }
+    }
+    apply_prompt_template(&state);
+    Ok(())
+}
+
+// Ping an LLM endpoint/model and any fallbacks with one-token completions before saving settings
+// Reports each endpoint's health rather than failing on the first unreachable one
+#[tauri::command]
+async fn test_llm_connection(
     state: tauri::State<'_, AppState>,
-    current_topic: String,
-) -> Result<Vec<String>, String> {
+    provider: String,
+    api_url: String,
+    model: String,
+    api_key: Option<String>,
+    fallbacks: Option<Vec<LlmEndpoint>>,
+) -> Result<Vec<EndpointHealth>, String> {
+    ensure_llm_endpoint_allowed(&state, &api_url)?;
+    let fallbacks = fallbacks.unwrap_or_default();
+    for endpoint in &fallbacks {
+        ensure_llm_endpoint_allowed(&state, &endpoint.url)?;
+    }
+    let assistant = MeetingAssistant::new(
+        LlmProvider::parse(&provider),
+        &api_url,
+        &model,
+        &api_key.unwrap_or_default(),
+    ).with_fallbacks(&fallbacks);
+    Ok(assistant.check_endpoints().await)
+}
+
+// List models available from an LLM endpoint
+#[tauri::command]
+async fn list_llm_models(
+    state: tauri::State<'_, AppState>,
+    provider: String,
+    api_url: String,
+    api_key: Option<String>,
+) -> Result<Vec<String>, SecondBrainError> {
+    ensure_llm_endpoint_allowed(&state, &api_url)?;
+    let assistant = MeetingAssistant::new(
+        LlmProvider::parse(&provider),
+        &api_url,
+        "",
+        &api_key.unwrap_or_default(),
+    );
+    assistant.list_models().await
+}
+
+// Context length (tokens) of the active model, as used to size Graph-RAG retrieval
+#[tauri::command]
+async fn get_model_context_length(state: tauri::State<'_, AppState>) -> Result<usize, String> {
+    let assistant = state.llm_assistant.read().clone()
+        .ok_or("LLM assistant not initialized")?;
+    Ok(assistant.context_length().await)
+}
+
+/// Cancel whatever request holds `slot` and register a fresh token for the caller
+fn replace_request_token(slot: &Mutex<Option<CancellationToken>>) -> CancellationToken {
+    let token = CancellationToken::new();
+    if let Some(previous) = slot.lock().replace(token.clone()) {
+        previous.cancel();
+    }
+    token
+}
+
+// Ask the LLM assistant a question
+// A new question cancels the previous one so its late answer can't overwrite this one
+#[tauri::command]
+async fn ask_assistant(
+    state: tauri::State<'_, AppState>,
+    question: String,
+) -> Result<String, SecondBrainError> {
     let assistant = {
         let guard = state.llm_assistant.read();
         guard.as_ref()
-            .ok_or("LLM assistant not initialized")?
+            .ok_or("LLM assistant not initialized. Call initialize_llm first.")?
             .clone()
     };
 
+    let token = replace_request_token(&state.assistant_request);
     let kb = state.knowledge_base.clone();
-    assistant.suggest_questions(&current_topic, kb).await
+    run_cancellable(&token, assistant.ask(&question, kb)).await
 }
 
-// Ask a question about a specific meeting
+// Ask the LLM assistant a question, returning the meetings and documents it drew on
+// Shares ask_assistant's cancellation slot, so either kind of question supersedes the other
 #[tauri::command]
-async fn ask_meeting_question(
+async fn ask_assistant_with_sources(
     state: tauri::State<'_, AppState>,
     question: String,
-    meeting_title: String,
-    transcript: Vec<String>,
-    action_items: Vec<String>,
-    decisions: Vec<String>,
-) -> Result<String, String> {
+) -> Result<AnswerWithSources, SecondBrainError> {
     let assistant = {
         let guard = state.llm_assistant.read();
         guard.as_ref()
@@ -982,114 +3320,454 @@ async fn ask_meeting_question(
             .clone()
     };
 
-    assistant.ask_about_meeting(&question, &meeting_title, &transcript, &action_items, &decisions).await
+    let token = replace_request_token(&state.assistant_request);
+    let kb = state.knowledge_base.clone();
+    run_cancellable(&token, assistant.ask_with_sources(&question, kb)).await
 }
 
-// Get real-time suggestions based on recent transcript
+/// Most recent segments gathered for a person-scoped question
+const PERSON_SEGMENT_LIMIT: usize = 40;
+
+// Ask about one person across all meetings (name or any merged alias), citing meetings
 #[tauri::command]
-async fn get_realtime_suggestions(
+async fn ask_about_person(
     state: tauri::State<'_, AppState>,
-    meeting_context: Option<String>,
-) -> Result<RealtimeSuggestion, String> {
+    name: String,
+    question: String,
+) -> Result<AnswerWithSources, SecondBrainError> {
     let assistant = {
         let guard = state.llm_assistant.read();
         guard.as_ref()
-            .ok_or("LLM assistant not initialized")?
+            .ok_or("LLM assistant not initialized. Call initialize_llm first.")?
             .clone()
     };
 
-    let recent = {
-        let guard = state.recent_transcripts.lock();
-        guard.clone()
+    let dossier = {
+        let kb_guard = state.knowledge_base.read().await;
+        let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+        kb.get_person_dossier(&name, PERSON_SEGMENT_LIMIT).await?
     };
 
-    if recent.is_empty() {
-        return Ok(RealtimeSuggestion::default());
-    }
+    let token = replace_request_token(&state.assistant_request);
+    run_cancellable(&token, assistant.ask_about_person(&question, &dossier)).await
+}
 
-    let kb = state.knowledge_base.clone();
-    assistant.generate_realtime_suggestions(&recent, meeting_context.as_deref(), kb).await
+// Cancel the in-flight ask_assistant request, returning whether one was running
+#[tauri::command]
+fn cancel_assistant_request(state: tauri::State<AppState>) -> bool {
+    match state.assistant_request.lock().take() {
+        Some(token) if !token.is_cancelled() => {
+            token.cancel();
+            println!("[LLM] Cancelled in-flight assistant request");
+            true
+        }
+        _ => false,
+    }
 }
 
-// Clear recent transcripts (call when meeting ends)
+// Summarize a meeting
+// With `meeting_id`, the summary is written in the meeting's dominant language.
 #[tauri::command]
-fn clear_recent_transcripts(state: tauri::State<AppState>) {
-    let mut guard = state.recent_transcripts.lock();
-    guard.clear();
+async fn summarize_meeting(
+    state: tauri::State<'_, AppState>,
+    segments: Vec<String>,
+    meeting_id: Option<MeetingId>,
+) -> Result<String, SecondBrainError> {
+    Ok(summarize_meeting_sections(state, segments, meeting_id).await?.summary)
 }
 
-// Set meeting context (agenda, notes, linked doc summaries)
+// Summarize a meeting, also returning the per-part summaries of transcripts too long for one prompt
 #[tauri::command]
-fn set_meeting_context(state: tauri::State<AppState>, context: Option<String>) {
-    let mut guard = state.current_meeting_context.lock();
-    *guard = context;
-    println!("[Meeting] Context set: {} chars", guard.as_ref().map(|c| c.len()).unwrap_or(0));
+async fn summarize_meeting_sections(
+    state: tauri::State<'_, AppState>,
+    segments: Vec<String>,
+    meeting_id: Option<MeetingId>,
+) -> Result<MeetingSummary, SecondBrainError> {
+    let assistant = {
+        let guard = state.llm_assistant.read();
+        guard.as_ref()
+            .ok_or("LLM assistant not initialized")?
+            .clone()
+    };
+
+    let language = match meeting_id {
+        Some(meeting_id) => meeting_language(&state, meeting_id.as_str()).await,
+        None => None,
+    };
+    assistant.summarize_meeting(&segments, language.as_deref()).await
 }
 
-// Get meeting context
+/// A meeting's dominant language: the stored one, or worked out from its segments while it's still running
+async fn meeting_language(state: &AppState, meeting_id: &str) -> Option<String> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()?;
+    if let Ok(Some(meeting)) = kb.get_meeting(meeting_id).await {
+        if meeting.end_time.is_some() {
+            return meeting.dominant_language;
+        }
+    }
+    kb.get_meeting_language_breakdown(meeting_id).await.ok()?.dominant_language
+}
+
+// Languages detected in a meeting, with segment counts and the dominant one
 #[tauri::command]
-fn get_meeting_context(state: tauri::State<AppState>) -> Option<String> {
-    let guard = state.current_meeting_context.lock();
-    guard.clone()
+async fn get_meeting_language_breakdown(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+) -> Result<LanguageBreakdown, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_meeting_language_breakdown(meeting_id.as_str()).await
 }
 
-// Initialize agent queue with background worker pool
+// Summarize part of a meeting: segments between two offsets (ms since start), or an explicit selection
+// `segment_ids` takes precedence over the time range when given.
 #[tauri::command]
-fn initialize_agent_queue(
-    state: tauri::State<AppState>,
-    num_workers: Option<usize>,
-) -> Result<(), String> {
-    // Check if already initialized
-    {
-        let queue_guard = state.agent_queue.read();
-        if queue_guard.is_some() {
-            return Ok(()); // Already initialized
+async fn summarize_segment_range(
+    state: tauri::State<'_, AppState>,
+    meeting_id: String,
+    start_ms: Option<u64>,
+    end_ms: Option<u64>,
+    segment_ids: Option<Vec<String>>,
+) -> Result<String, SecondBrainError> {
+    let assistant = {
+        let guard = state.llm_assistant.read();
+        guard.as_ref()
+            .ok_or("LLM assistant not initialized")?
+            .clone()
+    };
+
+    let segments = {
+        let kb_guard = state.knowledge_base.read().await;
+        let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+        match segment_ids.filter(|ids| !ids.is_empty()) {
+            Some(ids) => kb.get_segments_by_ids(&meeting_id, &ids).await?,
+            None => kb.get_segments_in_range(&meeting_id, start_ms.unwrap_or(0), end_ms).await?,
         }
+    };
+
+    let transcript: Vec<String> = segments.iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .map(|s| format!("{}: {}", s.speaker, s.text))
+        .collect();
+    if transcript.is_empty() {
+        return Ok("Nothing was said in the selected part of the meeting.".to_string());
     }
 
-    // Get dependencies for workers
-    let llm = {
+    println!("[Summary] Summarizing {} segment(s) of meeting {}", transcript.len(), meeting_id);
+    let language = meeting_language(&state, &meeting_id).await;
+    Ok(assistant.summarize_meeting(&transcript, language.as_deref()).await?.summary)
+}
+
+// Get suggested questions
+#[tauri::command]
+async fn suggest_questions(
+    state: tauri::State<'_, AppState>,
+    current_topic: String,
+) -> Result<Vec<String>, SecondBrainError> {
+    let assistant = {
         let guard = state.llm_assistant.read();
-        guard.clone()
+        guard.as_ref()
+            .ok_or("LLM assistant not initialized")?
+            .clone()
     };
-    // Note: Entity engine requires type refactoring to work with workers
-    // Currently uses Option<Arc<EntityEngine>> but workers need Arc<RwLock<Option<EntityEngine>>>
-    // TODO: Refactor entity engine storage for worker compatibility
-    let entity_engine = None::<Arc<parking_lot::RwLock<Option<EntityEngine>>>>;
-    let kb = Some(state.knowledge_base.clone());
 
-    // Create queue and get receiver
-    let (queue, job_rx) = AgentQueue::new(100);
-    let queue = Arc::new(queue);
-    let queue_stats = Arc::new(tokio::sync::RwLock::new(QueueStats::default()));
+    let kb = state.knowledge_base.clone();
+    assistant.suggest_questions(&current_topic, kb).await
+}
 
-    // Create worker dependencies
-    let deps = agent_workers::WorkerDependencies {
-        llm,
-        kb,
-        entity_engine,
+// Ask a question about a specific meeting
+#[tauri::command]
+async fn ask_meeting_question(
+    state: tauri::State<'_, AppState>,
+    question: String,
+    meeting_title: String,
+    transcript: Vec<String>,
+    action_items: Vec<String>,
+    decisions: Vec<String>,
+    meeting_id: Option<MeetingId>,
+    include_related: Option<bool>,
+) -> Result<String, SecondBrainError> {
+    let assistant = {
+        let guard = state.llm_assistant.read();
+        guard.as_ref()
+            .ok_or("LLM assistant not initialized. Call initialize_llm first.")?
+            .clone()
     };
 
-    // Determine worker count (default to CPU count / 2, min 2, max 8)
-    let worker_count = num_workers.unwrap_or_else(|| {
-        std::thread::available_parallelism()
-            .map(|p| (p.get() / 2).clamp(2, 8))
-            .unwrap_or(2)
-    });
+    let linked = match meeting_id {
+        Some(id) if include_related.unwrap_or(false) => {
+            let kb_guard = state.knowledge_base.read().await;
+            let kb = kb_guard.as_ref()
+                .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+            linked_meeting_context(kb, id.as_str()).await?
+        }
+        _ => Vec::new(),
+    };
 
-    // Start worker pool in a separate thread with its own tokio runtime
-    let job_rx_arc = Arc::new(tokio::sync::Mutex::new(job_rx));
-    let queue_stats_clone = queue_stats.clone();
+    assistant.ask_about_meeting(&question, &meeting_title, &transcript, &action_items, &decisions, &linked).await
+}
 
-    std::thread::spawn(move || {
-        let rt = tokio::runtime::Builder::new_multi_thread()
-            .worker_threads(worker_count)
-            .enable_all()
-            .build()
-            .expect("Failed to create tokio runtime for agent workers");
+/// Prior meetings pulled into ask_meeting_question when related context is requested
+const LINKED_MEETING_LIMIT: usize = 3;
 
-        rt.block_on(async move {
-            println!("[AgentQueue] Starting {} workers", worker_count);
+/// One line per earlier meeting this one links to: title, summary, decisions and open actions
+async fn linked_meeting_context(kb: &KnowledgeBase, meeting_id: &str) -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+    for related in kb.get_related_meetings(meeting_id).await?.into_iter()
+        .filter(|r| r.outgoing)
+        .take(LINKED_MEETING_LIMIT)
+    {
+        let Some(id) = related.meeting.id.as_ref().map(|t| t.to_string()) else { continue };
+        let decisions = kb.get_meeting_decisions(&id).await?;
+        let open_actions: Vec<String> = kb.get_meeting_action_items(&id).await?
+            .into_iter()
+            .filter(|a| a.status != "done")
+            .map(|a| a.text)
+            .collect();
+
+        let mut line = format!("{} ({})", related.meeting.title, related.relation.replace('_', " "));
+        if let Some(summary) = related.meeting.summary.as_deref().filter(|s| !s.trim().is_empty()) {
+            line.push_str(&format!(": {}", summary.trim()));
+        }
+        if !decisions.is_empty() {
+            let texts: Vec<&str> = decisions.iter().map(|d| d.text.as_str()).collect();
+            line.push_str(&format!(" Decisions: {}.", texts.join("; ")));
+        }
+        if !open_actions.is_empty() {
+            line.push_str(&format!(" Open actions: {}.", open_actions.join("; ")));
+        }
+        lines.push(line);
+    }
+    Ok(lines)
+}
+
+/// Material for one side of compare_meetings
+/// Meetings that were never post-processed get their decisions and topics from a
+/// fresh (unsaved) highlights pass over the transcript.
+async fn meeting_digest(kb: &KnowledgeBase, assistant: &MeetingAssistant, meeting_id: &str) -> Result<MeetingDigest, String> {
+    let meeting = kb.get_meeting(meeting_id).await?
+        .ok_or_else(|| format!("Meeting not found: {}", meeting_id))?;
+    let transcript: Vec<String> = kb.get_meeting_segments(meeting_id).await?
+        .iter()
+        .map(|s| format!("{}: {}", s.speaker, s.text))
+        .collect();
+
+    let mut digest = MeetingDigest {
+        meeting_id: meeting_id.to_string(),
+        title: meeting.title.clone(),
+        start_time: meeting.start_time,
+        summary: meeting.summary.clone().filter(|s| !s.trim().is_empty()),
+        decisions: kb.get_meeting_decisions(meeting_id).await?.into_iter().map(|d| d.text).collect(),
+        action_items: kb.get_meeting_action_items(meeting_id).await?
+            .into_iter()
+            .map(|a| format!("{} ({}, {})", a.text, a.assignee.as_deref().unwrap_or("unassigned"), a.status))
+            .collect(),
+        key_topics: kb.get_meeting_topics(meeting_id).await?.into_iter().map(|t| t.name).collect(),
+        transcript,
+    };
+
+    if digest.summary.is_none() && digest.decisions.is_empty() && !digest.transcript.is_empty() {
+        println!("[Compare] {} has no highlights yet, extracting them", meeting.title);
+        let highlights = assistant.process_meeting_end(&digest.transcript, &meeting.title).await?;
+        digest.summary = highlights.summary;
+        digest.decisions = highlights.decisions;
+        if digest.action_items.is_empty() {
+            digest.action_items = highlights.action_items.into_iter()
+                .map(|a| format!("{} ({}, open)", a.task, a.assignee.as_deref().unwrap_or("unassigned")))
+                .collect();
+        }
+        if digest.key_topics.is_empty() {
+            digest.key_topics = highlights.key_topics;
+        }
+    }
+    Ok(digest)
+}
+
+// Structured "what changed" diff between two meetings on the same topic
+// Without `meeting_b`, compares to the meeting `meeting_a` follows up on (or the previous
+// instance of a recurring meeting). The earlier of the two is always the baseline.
+#[tauri::command]
+async fn compare_meetings(
+    state: tauri::State<'_, AppState>,
+    meeting_a: MeetingId,
+    meeting_b: Option<MeetingId>,
+) -> Result<MeetingDiff, SecondBrainError> {
+    let assistant = {
+        let guard = state.llm_assistant.read();
+        guard.as_ref()
+            .ok_or("LLM assistant not initialized")?
+            .clone()
+    };
+
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    let other = match meeting_b {
+        Some(id) => id.to_string(),
+        None => {
+            let linked = kb.get_related_meetings(meeting_a.as_str()).await?
+                .into_iter()
+                .find(|r| r.outgoing)
+                .and_then(|r| r.meeting.id.map(|t| normalize_record_id("meeting", &t.to_string())));
+            match linked {
+                Some(id) => id,
+                None => kb.find_previous_instance(meeting_a.as_str()).await?
+                    .and_then(|m| m.id.map(|t| normalize_record_id("meeting", &t.to_string())))
+                    .ok_or("No earlier meeting to compare with")?,
+            }
+        }
+    };
+
+    if other == meeting_a.as_str() {
+        return Err(SecondBrainError::InvalidInput("Cannot compare a meeting with itself".to_string()));
+    }
+
+    let a = meeting_digest(kb, &assistant, meeting_a.as_str()).await?;
+    let b = meeting_digest(kb, &assistant, &other).await?;
+    let (earlier, later) = if a.start_time <= b.start_time { (a, b) } else { (b, a) };
+
+    println!("[Compare] {} -> {}", earlier.title, later.title);
+    assistant.compare_meetings(&earlier, &later).await
+}
+
+// Link one meeting to an earlier one ("follow_up", "continues" or "references")
+#[tauri::command]
+async fn link_meetings(
+    state: tauri::State<'_, AppState>,
+    from_id: MeetingId,
+    to_id: MeetingId,
+    relation: String,
+) -> Result<MeetingLink, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.link_meetings(from_id.as_str(), to_id.as_str(), &relation).await
+}
+
+// Get meetings linked to a meeting in either direction
+#[tauri::command]
+async fn get_related_meetings(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+) -> Result<Vec<RelatedMeeting>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_related_meetings(meeting_id.as_str()).await
+}
+
+// Get real-time suggestions based on recent transcript
+#[tauri::command]
+async fn get_realtime_suggestions(
+    state: tauri::State<'_, AppState>,
+    meeting_context: Option<String>,
+) -> Result<RealtimeSuggestion, SecondBrainError> {
+    let assistant = {
+        let guard = state.llm_assistant.read();
+        guard.as_ref()
+            .ok_or("LLM assistant not initialized")?
+            .clone()
+    };
+
+    let recent = {
+        let guard = state.recent_transcripts.lock();
+        guard.clone()
+    };
+
+    if recent.is_empty() {
+        return Ok(RealtimeSuggestion::default());
+    }
+
+    let token = replace_request_token(&state.suggestion_request);
+    let kb = state.knowledge_base.clone();
+    run_cancellable(&token, assistant.generate_realtime_suggestions(&recent, meeting_context.as_deref(), kb)).await
+}
+
+// Clear recent transcripts (call when meeting ends)
+#[tauri::command]
+fn clear_recent_transcripts(state: tauri::State<AppState>) {
+    let mut guard = state.recent_transcripts.lock();
+    guard.clear();
+}
+
+// Set meeting context (agenda, notes, linked doc summaries)
+#[tauri::command]
+fn set_meeting_context(state: tauri::State<AppState>, context: Option<String>) {
+    let mut guard = state.current_meeting_context.lock();
+    *guard = context;
+    println!("[Meeting] Context set: {} chars", guard.as_ref().map(|c| c.len()).unwrap_or(0));
+}
+
+// Get meeting context
+#[tauri::command]
+fn get_meeting_context(state: tauri::State<AppState>) -> Option<String> {
+    let guard = state.current_meeting_context.lock();
+    guard.clone()
+}
+
+// Initialize agent queue with background worker pool
+#[tauri::command]
+fn initialize_agent_queue(
+    state: tauri::State<AppState>,
+    num_workers: Option<usize>,
+) -> Result<(), String> {
+    // Check if already initialized
+    {
+        let queue_guard = state.agent_queue.read();
+        if queue_guard.is_some() {
+            return Ok(()); // Already initialized
+        }
+    }
+
+    // Get dependencies for workers
+    let llm = {
+        let guard = state.llm_assistant.read();
+        guard.clone()
+    };
+    // Note: Entity engine requires type refactoring to work with workers
+    // Currently uses Option<Arc<EntityEngine>> but workers need Arc<RwLock<Option<EntityEngine>>>
+    // TODO: Refactor entity engine storage for worker compatibility
+    let entity_engine = None::<Arc<parking_lot::RwLock<Option<EntityEngine>>>>;
+    let kb = Some(state.knowledge_base.clone());
+
+    // Create queue and get receiver
+    let (queue, job_rx) = AgentQueue::new(100);
+    let queue = Arc::new(queue);
+    let queue_stats = Arc::new(tokio::sync::RwLock::new(QueueStats::default()));
+
+    // Create worker dependencies
+    let deps = agent_workers::WorkerDependencies {
+        llm,
+        kb,
+        entity_engine,
+    };
+
+    // Determine worker count (default to CPU count / 2, min 2, max 8)
+    let worker_count = num_workers.unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|p| (p.get() / 2).clamp(2, 8))
+            .unwrap_or(2)
+    });
+
+    // Start worker pool in a separate thread with its own tokio runtime
+    let job_rx_arc = Arc::new(tokio::sync::Mutex::new(job_rx));
+    let queue_stats_clone = queue_stats.clone();
+
+    let thread = std::thread::spawn(move || {
+        let rt = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(worker_count)
+            .enable_all()
+            .build()
+            .expect("Failed to create tokio runtime for agent workers");
+
+        rt.block_on(async move {
+            println!("[AgentQueue] Starting {} workers", worker_count);
 
             // Create worker tasks
             let mut handles = Vec::with_capacity(worker_count);
@@ -1166,11 +3844,12 @@ fn initialize_agent_queue(
         });
     });
 
-    // Store the queue
+    // Store the queue and the worker thread
     {
         let mut queue_guard = state.agent_queue.write();
         *queue_guard = Some(queue);
     }
+    *state.worker_pool.lock() = Some(AgentWorkers { worker_count, thread });
 
     println!("[AgentQueue] Initialized with {} background workers", worker_count);
     Ok(())
@@ -1189,6 +3868,129 @@ async fn get_queue_stats(state: tauri::State<'_, AppState>) -> Result<QueueStats
     }
 }
 
+/// Broadcast `Shutdown` to the agent workers, wait for them to finish the
+/// jobs already queued, then let in-flight knowledge base writes complete
+async fn drain_agent_queue(state: &AppState) -> Result<QueueStats, String> {
+    let queue = state.agent_queue.read().clone();
+    let Some(queue) = queue else {
+        return Ok(QueueStats::default());
+    };
+
+    let workers = state.worker_pool.lock().take();
+    if let Some(workers) = workers {
+        println!("[AgentQueue] Draining {} workers", workers.worker_count);
+        queue.begin_shutdown(workers.worker_count).await?;
+        tokio::task::spawn_blocking(move || workers.thread.join())
+            .await
+            .map_err(|e| format!("Failed to wait for agent workers: {}", e))?
+            .map_err(|_| "Agent worker thread panicked".to_string())?;
+    }
+
+    // Writers hold the knowledge base lock, so acquiring it waits them out
+    drop(state.knowledge_base.write().await);
+
+    let stats = queue.get_stats().await;
+    *state.agent_queue.write() = None;
+    println!("[AgentQueue] Shutdown complete ({} processed, {} failed)", stats.processed_jobs, stats.failed_jobs);
+    Ok(stats)
+}
+
+// Stop the background agent workers once queued jobs are done (stats show `draining` meanwhile)
+#[tauri::command]
+async fn shutdown_agent_queue(state: tauri::State<'_, AppState>) -> Result<QueueStats, String> {
+    drain_agent_queue(&state).await
+}
+
+/// How many meetings/actions/decisions a catch-up briefing considers
+const CATCH_UP_LIMIT: usize = 200;
+
+// Digest of meetings, decisions and open action items since `since_timestamp` (ms)
+// `my_names` are extra names action items may be assigned to the user under
+#[tauri::command]
+async fn catch_up_summary(
+    state: tauri::State<'_, AppState>,
+    since_timestamp: u64,
+    my_names: Option<Vec<String>>,
+) -> Result<CatchUpSummary, SecondBrainError> {
+    let my_names = my_names.unwrap_or_default();
+    let assistant = {
+        let guard = state.llm_assistant.read();
+        guard.as_ref()
+            .ok_or("LLM assistant not initialized")?
+            .clone()
+    };
+
+    let material = {
+        let kb_guard = state.knowledge_base.read().await;
+        let kb = kb_guard.as_ref()
+            .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+        let meetings = kb.get_meetings(Some(CATCH_UP_LIMIT), None, None, false, Some(since_timestamp), None).await?;
+        let actions = kb.get_all_action_items(CATCH_UP_LIMIT).await?;
+        let decisions = kb.get_all_decisions(CATCH_UP_LIMIT).await?;
+
+        let created_since = |v: &serde_json::Value| {
+            v.get("created_at").and_then(|c| c.as_u64()).unwrap_or(0) >= since_timestamp
+        };
+        let str_field = |v: &serde_json::Value, key: &str| {
+            v.get(key).and_then(|f| f.as_str()).filter(|f| !f.is_empty()).map(|f| f.to_string())
+        };
+
+        let new_decisions = decisions.iter()
+            .filter(|d| created_since(d))
+            .filter_map(|d| Some(CatchUpItem {
+                text: str_field(d, "text")?,
+                meeting_title: str_field(d, "meeting_title"),
+                detail: None,
+            }))
+            .collect();
+
+        let pending_actions = actions.iter()
+            .filter(|a| created_since(a))
+            .filter(|a| str_field(a, "status").as_deref() != Some("done"))
+            .filter(|a| is_assigned_to_me(str_field(a, "assignee").as_deref(), &my_names))
+            .filter_map(|a| {
+                let detail = match (str_field(a, "assignee"), str_field(a, "deadline")) {
+                    (Some(who), Some(when)) => Some(format!("{}, due {}", who, when)),
+                    (Some(who), None) => Some(who),
+                    (None, Some(when)) => Some(format!("due {}", when)),
+                    (None, None) => None,
+                };
+                Some(CatchUpItem {
+                    text: str_field(a, "text")?,
+                    meeting_title: str_field(a, "meeting_title"),
+                    detail,
+                })
+            })
+            .collect();
+
+        let notable_discussions = meetings.iter()
+            .map(|m| CatchUpItem {
+                text: m.summary.clone().unwrap_or_else(|| "No summary yet".to_string()),
+                meeting_title: Some(m.title.clone()),
+                detail: None,
+            })
+            .collect();
+
+        CatchUpSummary {
+            since: since_timestamp,
+            meeting_count: meetings.len(),
+            overview: None,
+            new_decisions,
+            pending_actions,
+            notable_discussions,
+        }
+    };
+
+    println!("[CatchUp] Since {}: {} meetings, {} decisions, {} pending actions",
+        since_timestamp,
+        material.meeting_count,
+        material.new_decisions.len(),
+        material.pending_actions.len());
+
+    assistant.catch_up_summary(material).await
+}
+
 // Submit a question to the agent queue (async processing)
 // Note: For now, processes inline since workers need complex async setup
 #[tauri::command]
@@ -1210,16 +4012,16 @@ async fn queue_ask_question(
         None => question.clone(),
     };
 
-    match assistant.ask(&full_context, kb).await {
-        Ok(answer) => Ok(agent_queue::AnswerResult {
-            answer,
-            sources: vec![],
+    match assistant.ask_with_sources(&full_context, kb).await {
+        Ok(result) => Ok(agent_queue::AnswerResult {
+            answer: result.answer,
+            sources: result.sources,
             error: None,
         }),
         Err(e) => Ok(agent_queue::AnswerResult {
             answer: String::new(),
             sources: vec![],
-            error: Some(e),
+            error: Some(e.to_string()),
         }),
     }
 }
@@ -1242,9 +4044,10 @@ async fn queue_realtime_suggestions(
         return Ok(agent_queue::RealtimeSuggestionResult::default());
     }
 
+    let token = replace_request_token(&state.suggestion_request);
     let kb = state.knowledge_base.clone();
 
-    match assistant.generate_realtime_suggestions(&recent_transcripts, meeting_context.as_deref(), kb).await {
+    match run_cancellable(&token, assistant.generate_realtime_suggestions(&recent_transcripts, meeting_context.as_deref(), kb)).await {
         Ok(suggestion) => Ok(agent_queue::RealtimeSuggestionResult {
             insight: suggestion.insight,
             question: suggestion.question,
@@ -1252,7 +4055,7 @@ async fn queue_realtime_suggestions(
             error: None,
         }),
         Err(e) => Ok(agent_queue::RealtimeSuggestionResult {
-            error: Some(e),
+            error: Some(e.to_string()),
             ..Default::default()
         }),
     }
@@ -1263,7 +4066,7 @@ async fn queue_realtime_suggestions(
 #[tauri::command]
 async fn queue_meeting_highlights(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
+    meeting_id: MeetingId,
 ) -> Result<agent_queue::HighlightsResult, String> {
     let assistant = {
         let guard = state.llm_assistant.read();
@@ -1272,9 +4075,9 @@ async fn queue_meeting_highlights(
 
     // Get meeting segments
     let kb_guard = state.knowledge_base.read().await;
-    let kb = kb_guard.as_ref().ok_or("Knowledge base not initialized")?;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    let segments = kb.get_meeting_segments(&meeting_id).await
+    let segments = kb.get_meeting_segments(meeting_id.as_str()).await
         .map_err(|e| format!("Failed to get segments: {}", e))?;
 
     if segments.is_empty() {
@@ -1290,7 +4093,7 @@ async fn queue_meeting_highlights(
         .map(|s| format!("{}: {}", s.speaker, s.text))
         .collect();
 
-    let meeting = kb.get_meeting(&meeting_id).await
+    let meeting = kb.get_meeting(meeting_id.as_str()).await
         .map_err(|e| format!("Failed to get meeting: {}", e))?
         .ok_or("Meeting not found")?;
 
@@ -1312,7 +4115,7 @@ async fn queue_meeting_highlights(
             error: None,
         }),
         Err(e) => Ok(agent_queue::HighlightsResult {
-            error: Some(e),
+            error: Some(e.to_string()),
             ..Default::default()
         }),
     }
@@ -1352,14 +4155,47 @@ async fn queue_entity_extraction(
     }
 }
 
+/// Progress of post-meeting highlight processing, emitted as `highlights-progress`
+#[derive(Debug, Clone, serde::Serialize)]
+struct HighlightsProgress {
+    meeting_id: String,
+    stage: String,          // "loading", "summarizing", "extracting action items", "saving", "linking", "done", "failed"
+    progress_percent: f32,
+    message: Option<String>,
+}
+
+fn emit_highlights_progress(app: &tauri::AppHandle, meeting_id: &MeetingId, stage: &str, progress_percent: f32, message: Option<String>) {
+    let _ = app.emit("highlights-progress", HighlightsProgress {
+        meeting_id: meeting_id.as_str().to_string(),
+        stage: stage.to_string(),
+        progress_percent,
+        message,
+    });
+}
+
 // Process meeting after it ends - extract highlights via LLM
 #[tauri::command]
 async fn process_meeting_highlights(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
+    app: tauri::AppHandle,
+    meeting_id: MeetingId,
+) -> Result<MeetingHighlights, String> {
+    let result = run_meeting_highlights(&state, &app, &meeting_id).await;
+    match &result {
+        Ok(_) => emit_highlights_progress(&app, &meeting_id, "done", 100.0, None),
+        Err(e) => emit_highlights_progress(&app, &meeting_id, "failed", 100.0, Some(e.clone())),
+    }
+    result
+}
+
+async fn run_meeting_highlights(
+    state: &AppState,
+    app: &tauri::AppHandle,
+    meeting_id: &MeetingId,
 ) -> Result<MeetingHighlights, String> {
     println!("[Highlights] Starting post-meeting processing for: {}", meeting_id);
     let start = std::time::Instant::now();
+    emit_highlights_progress(app, meeting_id, "loading", 0.0, None);
 
     let assistant = {
         let guard = state.llm_assistant.read();
@@ -1371,13 +4207,13 @@ async fn process_meeting_highlights(
     // Get meeting and segments
     let kb_guard = state.knowledge_base.read().await;
     let kb = kb_guard.as_ref()
-        .ok_or("Knowledge base not initialized")?;
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    let meeting = kb.get_meeting(&meeting_id).await?
+    let meeting = kb.get_meeting(meeting_id.as_str()).await?
         .ok_or("Meeting not found")?;
     println!("[Highlights] Found meeting: {}", meeting.title);
 
-    let segments = kb.get_meeting_segments(&meeting_id).await?;
+    let segments = kb.get_meeting_segments(meeting_id.as_str()).await?;
     println!("[Highlights] Found {} transcript segments", segments.len());
 
     if segments.is_empty() {
@@ -1391,26 +4227,39 @@ async fn process_meeting_highlights(
         .map(|s| format!("{}: {}", s.speaker, s.text))
         .collect();
 
-    // Process with LLM
+    // Process with LLM (the slow step)
+    emit_highlights_progress(app, meeting_id, "summarizing", 10.0,
+        Some(format!("Summarizing {} transcript segments", segments.len())));
     let highlights = assistant.process_meeting_end(&formatted, &meeting.title).await?;
 
     // Store extracted action items and decisions in KB
+    emit_highlights_progress(app, meeting_id, "extracting action items", 75.0,
+        Some(format!("{} action items, {} decisions", highlights.action_items.len(), highlights.decisions.len())));
+    // Items already caught live during the meeting aren't stored again
     for action in &highlights.action_items {
-        let _ = kb.add_action_item(
-            &meeting_id,
+        let _ = kb.add_action_item_if_new(
+            meeting_id.as_str(),
             &action.task,
             action.assignee.as_deref(),
             action.deadline.as_deref(),
         ).await;
     }
 
+    emit_highlights_progress(app, meeting_id, "saving", 85.0, None);
     for decision in &highlights.decisions {
-        let _ = kb.add_decision(&meeting_id, decision).await;
+        let _ = kb.add_decision(meeting_id.as_str(), decision).await;
     }
 
     // Update meeting summary if we got one
     if let Some(ref summary) = highlights.summary {
-        let _ = kb.update_meeting_summary(&meeting_id, summary).await;
+        let _ = kb.update_meeting_summary(meeting_id.as_str(), summary).await;
+    }
+
+    // Auto-link knowledge sources that resemble this meeting
+    emit_highlights_progress(app, meeting_id, "linking", 92.0, None);
+    match kb.auto_link_knowledge(meeting_id.as_str(), DEFAULT_AUTO_LINK_THRESHOLD).await {
+        Ok(links) => println!("[Highlights] Auto-linked {} knowledge sources", links.len()),
+        Err(e) => eprintln!("[Highlights] Auto-linking knowledge failed: {}", e),
     }
 
     println!("[Highlights] Post-processing complete in {:?}: {} action items, {} decisions, {} key topics, summary: {}",
@@ -1447,74 +4296,345 @@ fn unsubscribe_transcription(state: tauri::State<AppState>) -> Result<(), String
     Ok(())
 }
 
+/// Subscribe to progress of downloads, imports, ingestion, reprocessing and re-embedding
 #[tauri::command]
-fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Result<(), String> {
-    if state.is_recording.load(std::sync::atomic::Ordering::SeqCst) {
-        return Err("Already recording".to_string());
-    }
+fn subscribe_progress(
+    state: tauri::State<AppState>,
+    on_event: Channel<ProgressEvent>,
+) -> Result<(), String> {
+    let mut channel_guard = state.progress_channel.lock();
+    *channel_guard = Some(on_event);
+    println!("[Channel] Progress channel subscribed");
+    Ok(())
+}
 
-    // Track when recording started (for timestamp alignment with diarization)
-    let start_time = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_millis() as u64;
-    *state.recording_start_time.lock() = Some(start_time);
+/// Unsubscribe from progress channel
+#[tauri::command]
+fn unsubscribe_progress(state: tauri::State<AppState>) -> Result<(), String> {
+    let mut channel_guard = state.progress_channel.lock();
+    *channel_guard = None;
+    println!("[Channel] Progress channel unsubscribed");
+    Ok(())
+}
 
-    // Create channel for audio samples
-    let (tokio_tx, mut tokio_rx) = mpsc::unbounded_channel::<AudioSample>();
-    *state.audio_sender.lock() = Some(tokio_tx.clone());
+/// How long the suggestion worker waits for a burst of transcripts to settle
+const SUGGESTION_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(750);
+/// A suggestion is stale once this many newer transcripts arrived while it was generated
+const SUGGESTION_MAX_LAG: u64 = 3;
+
+/// Transcript window a realtime suggestion is generated from
+#[derive(Clone, Default)]
+struct SuggestionWindow {
+    seq: u64,  // Sequence number of the newest final transcript in the window
+    last_suggest_seq: u64,  // Sequence number of the latest transcript that asked for a suggestion
+    last_turn_seq: u64,  // Sequence number of the latest transcript that completed a turn
+    transcripts: Vec<String>,
+}
 
-    // Start audio capture
-    let mut capture = state.audio_capture.lock();
-    capture.start(tokio_tx)?;
+/// Store commitments detected in the latest window as action items for the current meeting
+async fn detect_live_action_items(
+    app: &tauri::AppHandle,
+    assistant: &MeetingAssistant,
+    transcripts: &[String],
+) -> Result<(), String> {
+    let state: tauri::State<AppState> = app.state();
+    let Some(meeting_id) = state.current_meeting_id.lock().clone() else {
+        return Ok(());
+    };
 
-    state.is_recording.store(true, std::sync::atomic::Ordering::SeqCst);
+    let known_tasks: Vec<String> = {
+        let kb_guard = state.knowledge_base.read().await;
+        let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+        kb.get_meeting_action_items(&meeting_id).await?
+            .into_iter()
+            .map(|item| item.text)
+            .collect()
+    };
 
-    // Channel for ASR processing
-    let (asr_tx, asr_rx) = std::sync::mpsc::channel::<(Vec<f32>, u32, String)>();
+    // Don't hold the KB lock across the LLM call
+    let detected = assistant.detect_commitments(transcripts, &known_tasks).await?;
+    if detected.is_empty() {
+        return Ok(());
+    }
 
-    // Spawn thread to bridge tokio channel to std channel and process audio
-    let app_handle = app.clone();
-    let asr_tx_clone = asr_tx.clone();
-    std::thread::spawn(move || {
-        // Create a small tokio runtime just for receiving from the channel
-        let rt = tokio::runtime::Builder::new_current_thread()
-            .enable_all()
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    for item in detected {
+        let stored = kb.add_action_item_if_new(
+            &meeting_id,
+            &item.task,
+            item.assignee.as_deref(),
+            item.deadline.as_deref(),
+        ).await?;
+
+        if let Some(action_id) = stored {
+            let _ = app.emit("realtime-action-item", serde_json::json!({
+                "id": action_id,
+                "meeting_id": meeting_id,
+                "task": item.task,
+                "assignee": item.assignee,
+                "deadline": item.deadline,
+            }));
+            println!("[ActionItems] Detected live action item: {}", item.task);
+        }
+    }
+
+    Ok(())
+}
+
+/// How long the alert worker waits for more segments before running entity extraction
+const ALERT_ENTITY_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(500);
+/// Most segments checked by one entity extraction
+const ALERT_ENTITY_BATCH_MAX: usize = 8;
+
+/// Final transcript an alert fired on
+struct AlertSegment {
+    meeting_id: Option<String>,
+    speaker: String,
+    text: String,
+    timestamp_ms: u64,
+}
+
+fn emit_alerts(app: &tauri::AppHandle, segment: &AlertSegment, hits: Vec<alerts::AlertMatch>) {
+    for hit in hits {
+        println!("[Alerts] '{}' matched \"{}\"", hit.keyword, hit.matched_text);
+        let _ = app.emit("alert-triggered", serde_json::json!({
+            "meeting_id": segment.meeting_id,
+            "keyword": hit.keyword,
+            "matched_text": hit.matched_text,
+            "entity_label": hit.entity_label,
+            "segment": {
+                "speaker": segment.speaker,
+                "text": segment.text,
+                "timestamp_ms": segment.timestamp_ms,
+            },
+        }));
+    }
+}
+
+/// Spawn the worker that checks entity alerts, keeping GLiNER off the ASR thread
+///
+/// Segments arriving within `ALERT_ENTITY_DEBOUNCE` of each other share one extraction.
+/// The worker exits when the sender is dropped.
+fn spawn_alert_worker(app: tauri::AppHandle, segments: std::sync::mpsc::Receiver<AlertSegment>) {
+    std::thread::spawn(move || {
+        while let Ok(first) = segments.recv() {
+            let mut batch = vec![first];
+            while batch.len() < ALERT_ENTITY_BATCH_MAX {
+                match segments.recv_timeout(ALERT_ENTITY_DEBOUNCE) {
+                    Ok(segment) => batch.push(segment),
+                    Err(_) => break,
+                }
+            }
+
+            let state: tauri::State<AppState> = app.state();
+            let matcher = state.meeting_alerts.read().clone();
+            let Some(engine) = state.entity_engine.read().clone() else {
+                continue;
+            };
+            let text = batch.iter().map(|segment| segment.text.as_str()).collect::<Vec<_>>().join("\n");
+            let entities: Vec<(String, String)> = match engine.extract(&text) {
+                Ok(entities) => entities.into_iter().map(|e| (e.label, e.text)).collect(),
+                Err(e) => {
+                    eprintln!("[Alerts] Entity extraction failed: {}", e);
+                    continue;
+                }
+            };
+
+            for segment in &batch {
+                // Only the entities found in this segment's own text
+                let lower = segment.text.to_lowercase();
+                let found: Vec<(String, String)> = entities.iter()
+                    .filter(|(_, name)| lower.contains(&name.to_lowercase()))
+                    .cloned()
+                    .collect();
+                emit_alerts(&app, segment, matcher.entity_matches(&segment.text, &found));
+            }
+        }
+    });
+}
+
+/// Spawn the single worker that turns transcript windows into realtime suggestions
+///
+/// Triggers only overwrite the latest window, so anything sent while a generation is
+/// in flight collapses into one follow-up run. The worker exits when the sender is dropped.
+fn spawn_suggestion_worker(app: tauri::AppHandle, mut windows: watch::Receiver<SuggestionWindow>) {
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to create tokio runtime for suggestions");
+
+        rt.block_on(async move {
+            let mut suggested_seq = 0u64;
+            let mut checked_turn_seq = 0u64;
+            while windows.changed().await.is_ok() {
+                tokio::time::sleep(SUGGESTION_DEBOUNCE).await;
+                let window = windows.borrow_and_update().clone();
+                if window.transcripts.is_empty() {
+                    continue;
+                }
+
+                let state: tauri::State<AppState> = app.state();
+                let Some(assistant) = state.llm_assistant.read().clone() else {
+                    continue;
+                };
+                let token = replace_request_token(&state.suggestion_request);
+
+                if window.last_suggest_seq > suggested_seq {
+                    suggested_seq = window.last_suggest_seq;
+                    let meeting_context = state.current_meeting_context.lock().clone();
+                    let kb = state.knowledge_base.clone();
+                    let use_agent = {
+                        let store_guard = state.user_store.lock();
+                        store_guard.as_ref()
+                            .and_then(|s| s.get_settings().ok())
+                            .map(|s| s.agent_suggestions)
+                            .unwrap_or(false)
+                    };
+                    let response = if use_agent {
+                        let request = assistant.generate_agent_suggestions(&window.transcripts, meeting_context.as_deref(), kb, offline_mode(&state));
+                        run_cancellable(&token, request).await
+                    } else {
+                        let request = assistant.generate_realtime_suggestions(&window.transcripts, meeting_context.as_deref(), kb);
+                        run_cancellable(&token, request).await
+                    };
+                    match response {
+                        Ok(suggestion) => {
+                            let latest_seq = windows.borrow().seq;
+                            if latest_seq.saturating_sub(window.seq) > SUGGESTION_MAX_LAG {
+                                println!("[Suggestions] Dropped stale suggestion ({} transcripts behind)",
+                                    latest_seq - window.seq);
+                            } else if suggestion.insight.is_some() || suggestion.question.is_some() || suggestion.related_info.is_some() {
+                                // Only emit if there's actual content
+                                let _ = app.emit("realtime-suggestion", serde_json::json!({
+                                    "insight": suggestion.insight,
+                                    "question": suggestion.question,
+                                    "related_info": suggestion.related_info,
+                                }));
+                                println!("[Suggestions] Emitted real-time suggestion");
+                            }
+                        }
+                        Err(e) if token.is_cancelled() => {
+                            println!("[Suggestions] Dropped stale suggestion ({})", e);
+                        }
+                        Err(e) => {
+                            eprintln!("[Suggestions] Error generating: {}", e);
+                        }
+                    }
+                }
+
+                // Look for commitments once per completed turn, on the same worker so
+                // the local model only ever serves one request at a time
+                if window.last_turn_seq > checked_turn_seq && !token.is_cancelled() {
+                    checked_turn_seq = window.last_turn_seq;
+                    if let Err(e) = run_cancellable(&token, detect_live_action_items(&app, &assistant, &window.transcripts)).await {
+                        eprintln!("[ActionItems] Live detection failed: {}", e);
+                    }
+                }
+            }
+            println!("[Suggestions] Worker stopped");
+        });
+    });
+}
+
+#[tauri::command]
+fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Result<(), String> {
+    if state.is_recording.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err("Already recording".to_string());
+    }
+    if state.importing_audio.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err("An audio file import is in progress".to_string());
+    }
+
+    // The recording capture takes over the mic; the wake word keeps running on it
+    stop_wake_word_listener(&state);
+
+    // Track when recording started (for timestamp alignment with diarization)
+    let start_time = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    *state.recording_start_time.lock() = Some(start_time);
+
+    // Create channel for audio samples
+    let (tokio_tx, mut tokio_rx) = mpsc::unbounded_channel::<AudioSample>();
+    *state.audio_sender.lock() = Some(tokio_tx.clone());
+
+    // Start audio capture
+    let mut capture = state.audio_capture.lock();
+    capture.start(tokio_tx)?;
+
+    state.is_recording.store(true, std::sync::atomic::Ordering::SeqCst);
+    state.recording_paused.store(false, std::sync::atomic::Ordering::SeqCst);
+    state.mic_muted.store(false, std::sync::atomic::Ordering::SeqCst);
+
+    // Channel for ASR processing: (mono samples, sample rate, source, chunk duration ms)
+    let (asr_tx, asr_rx) = std::sync::mpsc::channel::<(Vec<f32>, Vec<f32>, u32, String, u64)>();
+
+    // Spawn thread to bridge tokio channel to std channel and process audio
+    let app_handle = app.clone();
+    let asr_tx_clone = asr_tx.clone();
+    let chunk_configs = state.adaptive_chunk_configs.read().clone();
+    let auto_stop_limit_ms = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .filter(|s| s.auto_stop_enabled)
+            .map(|s| s.auto_stop_silence_minutes.saturating_mul(60_000))
+    };
+    let max_duration_ms = {
+        let store_guard = state.user_store.lock();
+        let minutes = store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| s.max_recording_minutes)
+            .unwrap_or(720);
+        (minutes > 0).then_some(minutes.saturating_mul(60_000))
+    };
+    let carried_ms = state.resumed_active_ms.swap(0, std::sync::atomic::Ordering::SeqCst);
+    let merge_turns = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| s.merge_turns)
+            .unwrap_or(false)
+    };
+    state.pending_segments.lock().merge_turns = merge_turns;
+    apply_alert_settings(&state);
+    apply_suggestion_settings(&state);
+    state.auto_stop_cancel.store(false, std::sync::atomic::Ordering::SeqCst);
+    std::thread::spawn(move || {
+        // Create a small tokio runtime just for receiving from the channel
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
             .build()
             .expect("Failed to create tokio runtime");
 
         rt.block_on(async move {
-            let mut mic_buffer: Vec<f32> = Vec::with_capacity(16000);
-            let mut system_buffer: Vec<f32> = Vec::with_capacity(16000);
-            let mut mic_channels: u16 = 1;
-            let mut system_channels: u16 = 1;
+            // Buffers hold mono audio at TARGET_SAMPLE_RATE so the sample-count chunk
+            // thresholds mean the same duration whatever rate the device captures at
+            let mut mic_buffer: Vec<f32> = Vec::with_capacity(TARGET_SAMPLE_RATE as usize);
+            let mut system_buffer: Vec<f32> = Vec::with_capacity(TARGET_SAMPLE_RATE as usize);
+            let mut mic_resampler = StreamResampler::new(TARGET_SAMPLE_RATE);
+            let mut system_resampler = StreamResampler::new(TARGET_SAMPLE_RATE);
+            let mut mic_clock = AudioClock::new();
+            let mut system_clock = AudioClock::new();
+            let mut mic_overlap = ChunkOverlap::new();
+            let mut system_overlap = ChunkOverlap::new();
 
             // Adaptive chunking state for each audio source
-            let adaptive_config = AdaptiveChunkConfig::default();
-            let mut mic_chunk_state = AdaptiveChunkState::new(adaptive_config.clone());
-            let mut system_chunk_state = AdaptiveChunkState::new(adaptive_config);
+            let mut mic_chunk_state = AdaptiveChunkState::new(chunk_configs.microphone);
+            let mut system_chunk_state = AdaptiveChunkState::new(chunk_configs.system);
 
             // Audio level emission throttle (send at most every 100ms for visualization)
             let mut last_level_emit = std::time::Instant::now();
 
-            // Helper to convert stereo to mono
-            fn stereo_to_mono(samples: &[f32], channels: u16) -> Vec<f32> {
-                if channels <= 1 {
-                    return samples.to_vec();
-                }
-                // Average all channels together
-                let channels = channels as usize;
-                let num_frames = samples.len() / channels;
-                let mut mono = Vec::with_capacity(num_frames);
-                for frame in 0..num_frames {
-                    let mut sum = 0.0f32;
-                    for ch in 0..channels {
-                        sum += samples[frame * channels + ch];
-                    }
-                    mono.push(sum / channels as f32);
-                }
-                mono
-            }
+            // Optional auto-stop after a long stretch of silence on every source
+            let mut silence_watch = SilenceWatch::new(auto_stop_limit_ms);
+            let mut mute_watch = MuteWatch::new();
+            // Hard cap on active recording time, whatever is being said
+            let mut duration_watch = DurationWatch::new(max_duration_ms, start_time, carried_ms);
 
             while let Some(sample) = tokio_rx.recv().await {
                 let source_str = match sample.source {
@@ -1522,19 +4642,47 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
                     AudioSource::SystemAudio => "system",
                 };
 
-                // Track channel count and add to appropriate buffer
-                // Note: We store raw data and convert to mono before sending to ASR
-                match sample.source {
-                    AudioSource::Microphone => {
-                        mic_channels = sample.channels;
-                        mic_buffer.extend_from_slice(&sample.data);
+                // Downmix and resample each packet as it arrives; the resampler carries
+                // its position across packets so no audio is lost at packet boundaries
+                let (chunk_state, resampler, buffer) = match sample.source {
+                    AudioSource::Microphone => (&mut mic_chunk_state, &mut mic_resampler, &mut mic_buffer),
+                    AudioSource::SystemAudio => (&mut system_chunk_state, &mut system_resampler, &mut system_buffer),
+                };
+                let mono = resampler.process(&stereo_to_mono(&sample.data, sample.channels), sample.sample_rate);
+
+                // Wake word spotting sees the raw mic stream ahead of ASR
+                if sample.source == AudioSource::Microphone {
+                    spot_wake_word(&app_handle, &mono, true);
+                }
+
+                // An OS-muted mic produces digital silence; stop transcribing it until it comes back
+                match mute_watch.observe(&sample.source, AdaptiveChunkState::calculate_rms(&mono), std::time::Instant::now()) {
+                    Some(MuteEvent::Muted) => {
+                        println!("[Audio] Microphone appears muted; pausing mic transcription");
+                        let state: tauri::State<AppState> = app_handle.state();
+                        state.mic_muted.store(true, std::sync::atomic::Ordering::SeqCst);
+                        let _ = app_handle.emit("mic-muted", serde_json::json!({ "muted": true }));
                     }
-                    AudioSource::SystemAudio => {
-                        system_channels = sample.channels;
-                        system_buffer.extend_from_slice(&sample.data);
+                    Some(MuteEvent::Unmuted) => {
+                        println!("[Audio] Microphone audio resumed");
+                        let state: tauri::State<AppState> = app_handle.state();
+                        state.mic_muted.store(false, std::sync::atomic::Ordering::SeqCst);
+                        let _ = app_handle.emit("mic-muted", serde_json::json!({ "muted": false }));
+                    }
+                    None => {}
+                }
+
+                // Auto-calibrate each source's thresholds from its own first second of audio
+                if chunk_state.calibration.is_some() {
+                    if let Some(noise_floor) = chunk_state.observe(&mono, TARGET_SAMPLE_RATE) {
+                        println!("[Audio] Calibrated {}: noise floor {:.5}, silence < {:.5}, speech > {:.5}",
+                            source_str, noise_floor,
+                            chunk_state.config.silence_threshold, chunk_state.config.speech_threshold);
                     }
                 }
 
+                buffer.extend_from_slice(&mono);
+
                 // ============================================================
                 // ADAPTIVE CHUNKING: Use energy-based chunk sizing
                 // - During speech: smaller chunks (50ms) for responsiveness
@@ -1542,21 +4690,19 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
                 // ============================================================
 
                 // Process microphone with adaptive chunking
-                if !mic_buffer.is_empty() {
-                    let mono_samples = stereo_to_mono(&mic_buffer, mic_channels);
-                    if mic_chunk_state.should_emit(&mono_samples) {
-                        let _ = asr_tx_clone.send((mono_samples, sample.sample_rate, "microphone".to_string()));
-                        mic_buffer.clear();
-                    }
+                if !mic_buffer.is_empty() && mic_chunk_state.should_emit(&mic_buffer) {
+                    let mono_samples = std::mem::take(&mut mic_buffer);
+                    let duration_ms = mic_clock.advance(mono_samples.len(), TARGET_SAMPLE_RATE);
+                    let overlap = mic_overlap.next(&mono_samples, TARGET_SAMPLE_RATE, mic_chunk_state.config.overlap_ms);
+                    let _ = asr_tx_clone.send((mono_samples, overlap, TARGET_SAMPLE_RATE, "microphone".to_string(), duration_ms));
                 }
 
                 // Process system audio with adaptive chunking
-                if !system_buffer.is_empty() {
-                    let mono_samples = stereo_to_mono(&system_buffer, system_channels);
-                    if system_chunk_state.should_emit(&mono_samples) {
-                        let _ = asr_tx_clone.send((mono_samples, sample.sample_rate, "system".to_string()));
-                        system_buffer.clear();
-                    }
+                if !system_buffer.is_empty() && system_chunk_state.should_emit(&system_buffer) {
+                    let mono_samples = std::mem::take(&mut system_buffer);
+                    let duration_ms = system_clock.advance(mono_samples.len(), TARGET_SAMPLE_RATE);
+                    let overlap = system_overlap.next(&mono_samples, TARGET_SAMPLE_RATE, system_chunk_state.config.overlap_ms);
+                    let _ = asr_tx_clone.send((mono_samples, overlap, TARGET_SAMPLE_RATE, "system".to_string(), duration_ms));
                 }
 
                 // Emit audio level updates for visualization (throttled)
@@ -1578,10 +4724,60 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
 
                     last_level_emit = std::time::Instant::now();
                 }
+
+                let state: tauri::State<AppState> = app_handle.state();
+                let paused = state.recording_paused.load(std::sync::atomic::Ordering::SeqCst);
+                if duration_watch.exceeded(paused, now_ms()) {
+                    // stop_recording keeps the diarization buffers, so ending the meeting still diarizes it
+                    println!("[MaxDuration] Recording reached its {} minute limit, stopping", max_duration_ms.unwrap_or(0) / 60_000);
+                    let meeting_id = state.current_meeting_id.lock().clone();
+                    let _ = app_handle.emit("max-duration-reached", serde_json::json!({
+                        "meeting_id": meeting_id,
+                        "limit_ms": max_duration_ms,
+                    }));
+                    if let Err(e) = stop_recording(state, app_handle.clone()) {
+                        eprintln!("[MaxDuration] Failed to stop recording: {}", e);
+                    }
+                    break;
+                }
+
+                let speaking = mic_chunk_state.in_speech || system_chunk_state.in_speech;
+                let cancelled = state.auto_stop_cancel.swap(false, std::sync::atomic::Ordering::SeqCst);
+                match silence_watch.update(speaking, cancelled) {
+                    Some(SilenceEvent::Warn) => {
+                        println!("[AutoStop] Silence limit reached, stopping in {}s unless cancelled", AUTO_STOP_GRACE_MS / 1000);
+                        let _ = app_handle.emit("auto-stop-warning", serde_json::json!({
+                            "silent_ms": auto_stop_limit_ms,
+                            "stop_in_ms": AUTO_STOP_GRACE_MS,
+                        }));
+                    }
+                    Some(SilenceEvent::Reset) => {
+                        println!("[AutoStop] Warning cleared ({})", if cancelled { "cancelled" } else { "speech resumed" });
+                        let _ = app_handle.emit("auto-stop-cancelled", ());
+                    }
+                    Some(SilenceEvent::Stop) => {
+                        println!("[AutoStop] Stopping recording after prolonged silence");
+                        let meeting_id = state.current_meeting_id.lock().clone();
+                        match stop_recording(state, app_handle.clone()) {
+                            Ok(()) => {
+                                let _ = app_handle.emit("auto-stopped", serde_json::json!({ "meeting_id": meeting_id }));
+                            }
+                            Err(e) => eprintln!("[AutoStop] Failed to stop recording: {}", e),
+                        }
+                        break;
+                    }
+                    None => {}
+                }
             }
         });
     });
 
+    // Realtime suggestions run on their own worker; the ASR thread only posts windows
+    let (suggestion_tx, suggestion_rx) = watch::channel(SuggestionWindow::default());
+    spawn_suggestion_worker(app.clone(), suggestion_rx);
+    let (alert_tx, alert_rx) = std::sync::mpsc::channel::<AlertSegment>();
+    spawn_alert_worker(app.clone(), alert_rx);
+
     // Spawn ASR processing thread
     let app_handle2 = app.clone();
     std::thread::spawn(move || {
@@ -1594,7 +4790,24 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
         let mut sample_count = 0u64;
         let mut mic_chunk_count = 0u64;
         let mut system_chunk_count = 0u64;
-        while let Ok((samples, sample_rate, source)) = asr_rx.recv() {
+        let mut transcript_seq = 0u64;
+        let mut last_suggestion_at: Option<std::time::Instant> = None;
+        let mut mic_audio_ms = 0u64;
+        let mut system_audio_ms = 0u64;
+        loop {
+            let (samples, overlap, sample_rate, source, chunk_ms) = match asr_rx.recv_timeout(SEGMENT_BATCH_MAX_AGE) {
+                Ok(chunk) => chunk,
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => {
+                    // No audio for a while; don't leave queued segments past their deadline
+                    let state: tauri::State<AppState> = app_handle2.state();
+                    state.pending_segments.lock().close_stale_turns(now_ms());
+                    if state.pending_segments.lock().is_due() {
+                        rt.block_on(flush_pending_segments(&state));
+                    }
+                    continue;
+                }
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => break,
+            };
             sample_count += 1;
 
             // Calculate RMS level for debugging
@@ -1606,8 +4819,10 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
 
             if source == "microphone" {
                 mic_chunk_count += 1;
+                mic_audio_ms += chunk_ms;
             } else {
                 system_chunk_count += 1;
+                system_audio_ms += chunk_ms;
                 // Log more frequently for system audio to debug
                 if system_chunk_count % 20 == 0 || system_chunk_count <= 5 {
                     println!("[ASR] SYSTEM audio chunk #{}: {} samples at {}Hz, RMS={:.6} ({}dB)",
@@ -1626,32 +4841,49 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
 
             // Buffer ALL audio for post-meeting diarization (before ASR processing)
             // This allows speaker identification across all audio sources
+            // Buffers are kept at TARGET_SAMPLE_RATE so diarization and WAV export see a known rate
+            let samples_16k = asr::resample(&samples, sample_rate, TARGET_SAMPLE_RATE);
             if source == "microphone" {
                 let mut buffer = state.mic_audio_buffer.lock();
-                buffer.extend_from_slice(&samples);
+                buffer.extend_from_slice(&samples_16k);
             } else {
                 let mut buffer = state.system_audio_buffer.lock();
-                buffer.extend_from_slice(&samples);
+                buffer.extend_from_slice(&samples_16k);
+            }
+
+            // Queued segments and stale turns are written on schedule even while paused or muted
+            state.pending_segments.lock().close_stale_turns(now_ms());
+            if state.pending_segments.lock().is_due() {
+                rt.block_on(flush_pending_segments(&state));
+            }
+
+            // Paused or muted audio still counts toward the clocks and diarization buffers
+            // above, so timestamps stay aligned when transcription picks up again
+            let paused = state.recording_paused.load(std::sync::atomic::Ordering::SeqCst);
+            let muted = source == "microphone" && state.mic_muted.load(std::sync::atomic::Ordering::SeqCst);
+            if paused || muted {
+                continue;
             }
 
             let mut asr_guard = state.asr_engine.write();
             if let Some(ref mut engine) = *asr_guard {
                 let result = if source == "microphone" {
-                    engine.process_microphone(&samples, sample_rate)
+                    engine.process_microphone(&samples, &overlap, sample_rate)
                 } else {
-                    engine.process_system(&samples, sample_rate)
+                    engine.process_system(&samples, &overlap, sample_rate)
                 };
 
                 if let Some(mut transcription) = result {
-                    // Run Smart Turn analysis on the audio chunk
-                    let turn_guard = state.smart_turn_engine.read();
-                    if let Some(ref turn_engine) = *turn_guard {
-                        if let Ok(turn_result) = turn_engine.predict(&samples) {
-                            transcription.is_turn_complete = turn_result.is_complete;
-                            transcription.turn_confidence = turn_result.probability;
+                    // Run Smart Turn analysis on the audio chunk (interim results are never turn ends)
+                    if transcription.is_final {
+                        let turn_guard = state.smart_turn_engine.read();
+                        if let Some(ref turn_engine) = *turn_guard {
+                            if let Ok(turn_result) = turn_engine.predict(&samples) {
+                                transcription.is_turn_complete = turn_result.is_complete;
+                                transcription.turn_confidence = turn_result.probability;
+                            }
                         }
                     }
-                    drop(turn_guard);
 
                     // Format emotion and events for logging
                     let emotion_str = format!("{:?}", transcription.emotion);
@@ -1664,9 +4896,11 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
                             source, transcription.source);
                     }
 
-                    println!("[ASR] TRANSCRIPTION: \"{}\" (source: {}, lang: {}, emotion: {}, turn_done: {} ({:.2}))",
-                        transcription.text, transcription.source, transcription.language,
-                        emotion_str, transcription.is_turn_complete, transcription.turn_confidence);
+                    if transcription.is_final {
+                        println!("[ASR] TRANSCRIPTION: \"{}\" (source: {}, lang: {}, emotion: {}, turn_done: {} ({:.2}))",
+                            transcription.text, transcription.source, transcription.language,
+                            emotion_str, transcription.is_turn_complete, transcription.turn_confidence);
+                    }
 
                     // Create TranscriptionEvent for channel streaming
                     let event = TranscriptionEvent::Transcription {
@@ -1679,6 +4913,7 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
                         audio_events: events_str.clone(),
                         is_turn_complete: transcription.is_turn_complete,
                         turn_confidence: transcription.turn_confidence,
+                        word_timings: transcription.word_timings.clone(),
                     };
 
                     // Send via Channel if subscribed
@@ -1687,7 +4922,9 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
                         if let Some(ref channel) = *channel_guard {
                             match channel.send(event.clone()) {
                                 Ok(_) => {
-                                    println!("[Channel] Sent transcription event");
+                                    if transcription.is_final {
+                                        println!("[Channel] Sent transcription event");
+                                    }
                                     Some(true)
                                 }
                                 Err(e) => {
@@ -1712,72 +4949,68 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
                         "audio_events": events_str,
                         "is_turn_complete": transcription.is_turn_complete,
                         "turn_confidence": transcription.turn_confidence,
+                        "word_timings": transcription.word_timings,
                     }));
 
-                    if channel_result.is_none() {
+                    if channel_result.is_none() && transcription.is_final {
                         println!("[Transcription] Sent via emit (no channel subscribed)");
                     }
 
                     // Track recent transcripts for LLM suggestions
                     if transcription.is_final && !transcription.text.trim().is_empty() {
-                        let speaker = if source == "microphone" { "You" } else { "Guest" };
+                        let speaker = state.speaker_labels.read().for_source(&source).to_string();
                         let formatted = format!("{}: {}", speaker, transcription.text);
+                        transcript_seq += 1;
 
-                        let should_generate_suggestions = {
+                        {
                             let mut recent = state.recent_transcripts.lock();
                             recent.push(formatted);
                             // Keep only last 10 transcripts
                             if recent.len() > 10 {
                                 recent.remove(0);
                             }
-                            // Generate suggestions:
-                            // - On FIRST transcript (instant feedback)
-                            // - When turn completes (natural conversation break)
-                            // - Every 3 transcripts (more responsive than 5)
-                            recent.len() == 1 || transcription.is_turn_complete || recent.len() % 3 == 0
-                        };
+                        }
 
-                        // Generate and emit real-time suggestions asynchronously
-                        if should_generate_suggestions {
-                            let app_handle3 = app_handle2.clone();
-                            let state_for_suggestions: tauri::State<AppState> = app_handle2.state();
-                            let llm = {
-                                let guard = state_for_suggestions.llm_assistant.read();
-                                guard.clone()
-                            };
-                            let recent_transcripts = state_for_suggestions.recent_transcripts.lock().clone();
-                            let meeting_context = state_for_suggestions.current_meeting_context.lock().clone();
-                            let kb = state_for_suggestions.knowledge_base.clone();
-
-                            if let Some(assistant) = llm {
-                                if !recent_transcripts.is_empty() {
-                                    // Spawn async task for suggestion generation
-                                    std::thread::spawn(move || {
-                                        let rt = tokio::runtime::Builder::new_current_thread()
-                                            .enable_all()
-                                            .build()
-                                            .unwrap();
-
-                                        rt.block_on(async {
-                                            match assistant.generate_realtime_suggestions(&recent_transcripts, meeting_context.as_deref(), kb).await {
-                                                Ok(suggestion) => {
-                                                    // Only emit if there's actual content
-                                                    if suggestion.insight.is_some() || suggestion.question.is_some() || suggestion.related_info.is_some() {
-                                                        let _ = app_handle3.emit("realtime-suggestion", serde_json::json!({
-                                                            "insight": suggestion.insight,
-                                                            "question": suggestion.question,
-                                                            "related_info": suggestion.related_info,
-                                                        }));
-                                                        println!("[Suggestions] Emitted real-time suggestion");
-                                                    }
-                                                }
-                                                Err(e) => {
-                                                    eprintln!("[Suggestions] Error generating: {}", e);
-                                                }
-                                            }
-                                        });
-                                    });
+                        // Cadence comes from settings and may change mid-recording
+                        let cadence = *state.suggestion_cadence.read();
+                        let since_last_ms = last_suggestion_at.map(|at| at.elapsed().as_millis() as u64);
+                        let suggest = cadence.should_suggest(transcript_seq, transcription.is_turn_complete, since_last_ms);
+                        if suggest {
+                            last_suggestion_at = Some(std::time::Instant::now());
+                        }
+
+                        // Hand the latest window to the worker: for a suggestion, and on
+                        // completed turns for live action item detection
+                        let is_turn_complete = transcription.is_turn_complete;
+                        if suggest || is_turn_complete {
+                            let transcripts = state.recent_transcripts.lock().clone();
+                            suggestion_tx.send_modify(|window| {
+                                window.seq = transcript_seq;
+                                window.transcripts = transcripts;
+                                if suggest {
+                                    window.last_suggest_seq = transcript_seq;
+                                }
+                                if is_turn_complete {
+                                    window.last_turn_seq = transcript_seq;
                                 }
+                            });
+                        }
+                    }
+
+                    // Keyword alerts are a few regex scans; entity alerts go to the alert
+                    // worker so GLiNER never runs while the ASR lock is held
+                    if transcription.is_final && !transcription.text.trim().is_empty() {
+                        let matcher = state.meeting_alerts.read().clone();
+                        if !matcher.is_empty() {
+                            let segment = AlertSegment {
+                                meeting_id: state.current_meeting_id.lock().clone(),
+                                speaker: state.speaker_labels.read().for_source(&source).to_string(),
+                                text: transcription.text.clone(),
+                                timestamp_ms: transcription.timestamp_ms,
+                            };
+                            emit_alerts(&app_handle2, &segment, matcher.keyword_matches(&segment.text));
+                            if matcher.wants_entities(&segment.text) {
+                                let _ = alert_tx.send(segment);
                             }
                         }
                     }
@@ -1787,44 +5020,46 @@ fn start_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resu
                         let meeting_id = state.current_meeting_id.lock().clone();
 
                         if let Some(meeting_id) = meeting_id {
-                            let kb = state.knowledge_base.clone();
                             let text = transcription.text.clone();
-                            let speaker = if source == "microphone" { "You" } else { "Guest" }.to_string();
+                            let speaker = state.speaker_labels.read().for_source(&source).to_string();
                             let timestamp = transcription.timestamp_ms;
-                            let emotion = emotion_str.clone();
+                            // Fall back to the chunk length if the recognizer didn't report one
+                            let duration_ms = if transcription.duration_ms > 0 { transcription.duration_ms } else { chunk_ms };
+                            let word_timings = transcription.word_timings.clone();
+                            let language = Some(transcription.language.clone());
+                            let emotion = transcription.emotion.label().map(str::to_string);
                             let is_turn_complete = transcription.is_turn_complete;
 
-                            println!("[KB] Saving segment: speaker={}, text_len={}, emotion={}, turn_done={}",
-                                speaker, text.len(), emotion, is_turn_complete);
-
-                            // Run async KB operation
-                            rt.block_on(async {
-                                let kb_guard = kb.read().await;
-                                if let Some(ref kb) = *kb_guard {
-                                    match kb.add_segment(
-                                        &meeting_id,
-                                        &speaker,
-                                        &text,
-                                        timestamp,
-                                        timestamp + 1000, // Approximate end time
-                                    ).await {
-                                        Ok(segment_id) => {
-                                            println!("[KB] Segment saved successfully: {}", segment_id);
-                                        }
-                                        Err(e) => {
-                                            eprintln!("[KB] ERROR saving segment: {}", e);
-                                        }
-                                    }
-                                } else {
-                                    eprintln!("[KB] Knowledge base not available in save loop");
-                                }
-                            });
+                            println!("[KB] Queueing segment: speaker={}, text_len={}, emotion={}, turn_done={}",
+                                speaker, text.len(), emotion_str, is_turn_complete);
+
+                            // Merged into the speaker's turn, then written in batches below
+                            // once the ASR lock is released
+                            state.pending_segments.lock().push_turn(PendingSegment {
+                                meeting_id,
+                                speaker,
+                                text,
+                                start_ms: timestamp,
+                                end_ms: timestamp + duration_ms,
+                                word_timings,
+                                language,
+                                emotion,
+                            }, is_turn_complete);
                         }
                     }
 
                 }
             }
+            drop(asr_guard);
         }
+
+        // Recording stopped: write whatever is still queued
+        let state: tauri::State<AppState> = app_handle2.state();
+        state.pending_segments.lock().close_turns();
+        rt.block_on(flush_pending_segments(&state));
+
+        println!("[ASR] Processed {}ms microphone and {}ms system audio",
+            mic_audio_ms, system_audio_ms);
     });
 
     // Emit recording-started event
@@ -1841,184 +5076,1420 @@ fn stop_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Resul
     }
 
     // Stop audio capture
-    let mut capture = state.audio_capture.lock();
-    capture.stop();
+    state.audio_capture.lock().stop();
 
     // Clear sender
     *state.audio_sender.lock() = None;
 
-    // Note: Don't clear recording_start_time here - end_meeting uses it for diarization
-    // It will be cleared in end_meeting
+    // Suggestions for this recording are no longer useful
+    if let Some(token) = state.suggestion_request.lock().take() {
+        token.cancel();
+    }
+
+    // Note: Don't clear recording_start_time here - end_meeting uses it for diarization
+    // It will be cleared in end_meeting
+
+    state.is_recording.store(false, std::sync::atomic::Ordering::SeqCst);
+    state.recording_paused.store(false, std::sync::atomic::Ordering::SeqCst);
+    state.mic_muted.store(false, std::sync::atomic::Ordering::SeqCst);
+
+    // Emit recording-stopped event
+    let _ = app.emit("recording-stopped", ());
+
+    if let Err(e) = start_wake_word_listener(&state, &app) {
+        eprintln!("[WakeWord] Failed to resume listening: {}", e);
+    }
+
+    println!("Recording stopped");
+    Ok(())
+}
+
+/// How often the wake word listener checks whether it should stop
+const WAKE_WORD_POLL: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Run the wake word spotter over 16kHz mono mic audio; emits `wake-word-detected` on a hit
+fn spot_wake_word(app: &tauri::AppHandle, samples: &[f32], recording: bool) -> bool {
+    let state = app.state::<AppState>();
+    let heard = state.wake_word_engine.lock().as_mut().and_then(|engine| engine.process(samples));
+    let Some(phrase) = heard else {
+        return false;
+    };
+    println!("[WakeWord] Heard '{}' (recording: {})", phrase, recording);
+    let _ = app.emit("wake-word-detected", serde_json::json!({
+        "phrase": phrase,
+        "recording": recording,
+    }));
+    true
+}
+
+/// Open the mic and listen for the wake word until stopped or, with auto-start, until heard
+/// No-op while recording (the recording capture spots it instead) or when the wake word is off.
+fn start_wake_word_listener(state: &AppState, app: &tauri::AppHandle) -> Result<(), String> {
+    if state.is_recording.load(std::sync::atomic::Ordering::SeqCst) {
+        return Ok(());
+    }
+    let auto_start = match state.wake_word_engine.lock().as_ref() {
+        Some(engine) => engine.config().auto_start,
+        None => return Ok(()),
+    };
+    let mut listener = state.wake_word_listener.lock();
+    if listener.is_some() {
+        return Ok(());
+    }
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<AudioSample>();
+    state.audio_capture.lock().start(tx)?;
+
+    let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let app = app.clone();
+    let thread = std::thread::spawn(move || {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to create tokio runtime for wake word");
+
+        let mut resampler = asr::StreamResampler::new(TARGET_SAMPLE_RATE);
+        let heard = rt.block_on(async {
+            while !thread_stop.load(std::sync::atomic::Ordering::SeqCst) {
+                let sample = match tokio::time::timeout(WAKE_WORD_POLL, rx.recv()).await {
+                    Ok(Some(sample)) => sample,
+                    Ok(None) => break,  // Capture stopped
+                    Err(_) => continue,
+                };
+                if sample.source != AudioSource::Microphone {
+                    continue;
+                }
+                let mono = resampler.process(&stereo_to_mono(&sample.data, sample.channels), sample.sample_rate);
+                if spot_wake_word(&app, &mono, false) && auto_start {
+                    return true;
+                }
+            }
+            false
+        });
+
+        if heard && !thread_stop.load(std::sync::atomic::Ordering::SeqCst) {
+            // Hand the mic over to the recording; our own handle is dropped, not joined
+            let state = app.state::<AppState>();
+            state.wake_word_listener.lock().take();
+            state.audio_capture.lock().stop();
+            println!("[WakeWord] Auto-starting recording");
+            if let Err(e) = start_recording(state, app.clone()) {
+                eprintln!("[WakeWord] Auto-start failed: {}", e);
+            }
+        }
+    });
+
+    *listener = Some(WakeWordListener { stop, thread });
+    println!("[WakeWord] Listener started (auto-start: {})", auto_start);
+    Ok(())
+}
+
+/// Stop the between-recordings listener, releasing the mic
+fn stop_wake_word_listener(state: &AppState) {
+    let Some(listener) = state.wake_word_listener.lock().take() else {
+        return;
+    };
+    listener.stop.store(true, std::sync::atomic::Ordering::SeqCst);
+    state.audio_capture.lock().stop();
+    if listener.thread.thread().id() != std::thread::current().id() {
+        let _ = listener.thread.join();
+    }
+    println!("[WakeWord] Listener stopped");
+}
+
+// Keep recording after an auto-stop-warning; the silence timer starts over
+#[tauri::command]
+fn cancel_auto_stop(state: tauri::State<AppState>) {
+    state.auto_stop_cancel.store(true, std::sync::atomic::Ordering::SeqCst);
+}
+
+#[tauri::command]
+fn is_recording(state: tauri::State<AppState>) -> bool {
+    state.is_recording.load(std::sync::atomic::Ordering::SeqCst)
+}
+
+// Recording status: idle, recording, paused (by the user) or muted (mic muted at the OS level)
+#[tauri::command]
+fn get_recording_state(state: tauri::State<AppState>) -> RecordingState {
+    use std::sync::atomic::Ordering;
+    if !state.is_recording.load(Ordering::SeqCst) {
+        RecordingState::Idle
+    } else if state.recording_paused.load(Ordering::SeqCst) {
+        RecordingState::Paused
+    } else if state.mic_muted.load(Ordering::SeqCst) {
+        RecordingState::Muted
+    } else {
+        RecordingState::Recording
+    }
+}
+
+// Pause transcription and KB writes without ending the recording
+#[tauri::command]
+fn pause_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Result<(), String> {
+    if !state.is_recording.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err("Not recording".to_string());
+    }
+    state.recording_paused.store(true, std::sync::atomic::Ordering::SeqCst);
+    let _ = app.emit("recording-paused", ());
+    println!("Recording paused");
+    Ok(())
+}
+
+// Resume transcription after pause_recording
+#[tauri::command]
+fn resume_recording(state: tauri::State<AppState>, app: tauri::AppHandle) -> Result<(), String> {
+    if !state.is_recording.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err("Not recording".to_string());
+    }
+    state.recording_paused.store(false, std::sync::atomic::Ordering::SeqCst);
+    let _ = app.emit("recording-resumed", ());
+    println!("Recording resumed");
+    Ok(())
+}
+
+/// Windows hidden from screen recordings and shares while protection is on
+const PROTECTED_WINDOWS: [&str; 2] = ["main", "suggestions-overlay"];
+
+/// Set content protection on every open protected window
+fn protect_windows(app: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    for label in PROTECTED_WINDOWS {
+        if let Some(window) = app.get_webview_window(label) {
+            window.set_content_protected(enabled).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Apply the saved `screen_share_protection` to the open windows and ones opened later
+fn apply_screen_share_settings(state: &AppState, app: &tauri::AppHandle) {
+    let enabled = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| s.screen_share_protection)
+            .unwrap_or_else(user_store::default_screen_share_protection)
+    };
+    state.screen_share_protection.store(enabled, std::sync::atomic::Ordering::SeqCst);
+    // Linux compositors don't support excluding a window from capture
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    {
+        if let Err(e) = protect_windows(app, enabled) {
+            eprintln!("[Windows] Failed to set screen share protection: {}", e);
+        }
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let _ = app;
+}
+
+// Hide the app windows from screen recordings and shares (or show them again), and save the choice
+#[tauri::command]
+fn set_screen_share_protection(
+    state: tauri::State<AppState>,
+    app: tauri::AppHandle,
+    enabled: bool,
+) -> Result<(), String> {
+    protect_windows(&app, enabled)?;
+    state.screen_share_protection.store(enabled, std::sync::atomic::Ordering::SeqCst);
+    {
+        let store_guard = state.user_store.lock();
+        if let Some(store) = store_guard.as_ref() {
+            store.set_setting_bool("screen_share_protection", enabled)?;
+        }
+    }
+    println!("Screen share protection: {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+#[tauri::command]
+fn check_models_status() -> Vec<ModelStatus> {
+    get_models_status()
+}
+
+#[tauri::command]
+fn are_models_ready() -> bool {
+    all_models_installed()
+}
+
+#[tauri::command]
+async fn download_models(state: tauri::State<'_, AppState>, app: tauri::AppHandle) -> Result<(), String> {
+    ensure_online(&state)?;
+    download_all_models(app).await
+}
+
+#[tauri::command]
+fn get_models_path() -> String {
+    get_models_dir().to_string_lossy().to_string()
+}
+
+// Per-model disk usage and the total
+#[tauri::command]
+fn get_models_disk_usage() -> ModelsDiskUsage {
+    models::get_models_disk_usage()
+}
+
+/// Name of the loaded engine that uses `model_id`, if any
+async fn engine_using_model(state: &AppState, model_id: &str) -> Option<&'static str> {
+    let loaded = match model_id {
+        "silero-vad" | "sensevoice" => state.asr_engine.read().is_some().then_some("ASR"),
+        "smart-turn-v3" => state.smart_turn_engine.read().is_some().then_some("Smart Turn"),
+        "gliner-model" | "gliner-tokenizer" => state.entity_engine.read().is_some().then_some("entity"),
+        "embedding-model" | "embedding-model-data" | "embedding-tokenizer" => {
+            state.embedding_engine.read().is_some().then_some("embedding")
+        }
+        "speaker-segmentation" | "speaker-embedding" => state.diarization_engine.read().is_some().then_some("diarization"),
+        "ocr-detection" | "ocr-recognition" => state.ocr_engine.read().is_some().then_some("OCR"),
+        WAKE_WORD_MODEL_ID => state.wake_word_engine.lock().is_some().then_some("wake word"),
+        _ => None,
+    };
+    if loaded.is_some() {
+        return loaded;
+    }
+    // The knowledge base keeps its own handles to the entity and embedding engines
+    let kb_models = ["gliner-model", "gliner-tokenizer", "embedding-model", "embedding-model-data", "embedding-tokenizer"];
+    if kb_models.contains(&model_id) && state.knowledge_base.read().await.is_some() {
+        return Some("knowledge base");
+    }
+    None
+}
+
+/// Drop the engine that has `model_id` loaded so its files can be removed
+/// The knowledge base keeps its own entity and embedding handles, so those models stay in use while it is open.
+async fn unload_model_engine(state: &AppState, model_id: &str) -> Result<(), String> {
+    if engine_using_model(state, model_id).await.is_none() {
+        return Ok(());
+    }
+    if state.is_recording.load(std::sync::atomic::Ordering::SeqCst)
+        || state.importing_audio.load(std::sync::atomic::Ordering::SeqCst)
+    {
+        return Err("Stop recording or importing before unloading a model".to_string());
+    }
+    match model_id {
+        "silero-vad" | "sensevoice" => *state.asr_engine.write() = None,
+        "smart-turn-v3" => *state.smart_turn_engine.write() = None,
+        "gliner-model" | "gliner-tokenizer" => *state.entity_engine.write() = None,
+        "embedding-model" | "embedding-model-data" | "embedding-tokenizer" => *state.embedding_engine.write() = None,
+        "speaker-segmentation" | "speaker-embedding" => *state.diarization_engine.write() = None,
+        "ocr-detection" | "ocr-recognition" => *state.ocr_engine.write() = None,
+        WAKE_WORD_MODEL_ID => *state.wake_word_engine.lock() = None,
+        _ => {}
+    }
+    match engine_using_model(state, model_id).await {
+        Some(engine) => Err(format!("Model '{}' is still in use by the {} engine", model_id, engine)),
+        None => Ok(()),
+    }
+}
+
+// Unload the engine using a model without deleting its files
+#[tauri::command]
+async fn unload_model(state: tauri::State<'_, AppState>, model_id: String) -> Result<(), String> {
+    unload_model_engine(&state, &model_id).await
+}
+
+// Delete a downloaded model to free disk space; returns the bytes freed
+// Unloads the engine using it first, and emits `models-ready` when a required model goes missing.
+#[tauri::command]
+async fn delete_model(state: tauri::State<'_, AppState>, app: tauri::AppHandle, model_id: String) -> Result<u64, String> {
+    unload_model_engine(&state, &model_id).await?;
+    let freed = models::delete_model(&model_id)?;
+    if models::get_required_models().iter().any(|m| m.id == model_id) {
+        let _ = app.emit("models-ready", all_models_installed());
+    }
+    Ok(freed)
+}
+
+// ==================== AUDIO & DIARIZATION DIAGNOSTICS ====================
+
+/// Check audio capture capabilities
+#[tauri::command]
+fn get_audio_capabilities() -> AudioCapabilities {
+    check_audio_capabilities()
+}
+
+// Report offline mode and whether the LLM endpoint is local
+#[tauri::command]
+fn get_network_status(state: tauri::State<AppState>) -> NetworkStatus {
+    let llm_url = state.llm_assistant.read().as_ref().map(|a| a.api_url().to_string());
+    NetworkStatus {
+        offline_mode: offline_mode(&state),
+        llm_local: llm_url.as_deref().map(is_local_url).unwrap_or(false),
+        llm_url,
+    }
+}
+
+// Get the adaptive chunking thresholds for both audio sources
+#[tauri::command]
+fn get_audio_chunk_configs(state: tauri::State<AppState>) -> SourceChunkConfigs {
+    state.adaptive_chunk_configs.read().clone()
+}
+
+// Replace one source's chunking thresholds; applies from the next recording
+#[tauri::command]
+fn set_audio_chunk_config(
+    state: tauri::State<AppState>,
+    source: String,
+    config: AdaptiveChunkConfig,
+) -> Result<SourceChunkConfigs, String> {
+    let source = parse_audio_source(&source)?;
+    if config.silence_threshold >= config.speech_threshold {
+        return Err("silence_threshold must be below speech_threshold".to_string());
+    }
+    if config.overlap_ms > MAX_CHUNK_OVERLAP_MS {
+        return Err(format!("overlap_ms must be at most {}", MAX_CHUNK_OVERLAP_MS));
+    }
+    let mut configs = state.adaptive_chunk_configs.write();
+    configs.set(&source, config);
+    Ok(configs.clone())
+}
+
+/// Default length of ambient audio sampled by `calibrate_audio_levels`
+const DEFAULT_CALIBRATION_MS: u64 = 2000;
+
+// Sample ambient noise on both sources and set each one's thresholds from it
+// Sources that produced no audio keep their current config. Turns off per-recording
+// auto-calibration for calibrated sources so the measured thresholds are used as is.
+#[tauri::command]
+async fn calibrate_audio_levels(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    duration_ms: Option<u64>,
+) -> Result<SourceChunkConfigs, String> {
+    if state.is_recording.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err("Cannot calibrate while recording".to_string());
+    }
+
+    let duration = std::time::Duration::from_millis(duration_ms.unwrap_or(DEFAULT_CALIBRATION_MS));
+    stop_wake_word_listener(&state);
+    let (tx, mut rx) = mpsc::unbounded_channel::<AudioSample>();
+    if let Err(e) = state.audio_capture.lock().start(tx) {
+        let _ = start_wake_word_listener(&state, &app);
+        return Err(e);
+    }
+    println!("[Audio] Calibrating levels for {:?}...", duration);
+
+    let mut mic = NoiseFloorEstimator::default();
+    let mut system = NoiseFloorEstimator::default();
+    let deadline = tokio::time::Instant::now() + duration;
+    while let Ok(Some(sample)) = tokio::time::timeout_at(deadline, rx.recv()).await {
+        let mono = stereo_to_mono(&sample.data, sample.channels);
+        match sample.source {
+            AudioSource::Microphone => mic.observe(&mono),
+            AudioSource::SystemAudio => system.observe(&mono),
+        }
+    }
+    state.audio_capture.lock().stop();
+    if let Err(e) = start_wake_word_listener(&state, &app) {
+        eprintln!("[WakeWord] Failed to resume listening: {}", e);
+    }
+
+    let mut configs = state.adaptive_chunk_configs.write();
+    for (source, estimator) in [(AudioSource::Microphone, &mic), (AudioSource::SystemAudio, &system)] {
+        let Some(noise_floor) = estimator.noise_floor() else {
+            println!("[Audio] No {:?} audio captured, keeping its thresholds", source);
+            continue;
+        };
+        let calibrated = AdaptiveChunkConfig {
+            auto_calibrate: false,
+            ..configs.for_source(&source).calibrated(noise_floor)
+        };
+        println!("[Audio] {:?} noise floor {:.5}: silence < {:.5}, speech > {:.5}",
+            source, noise_floor, calibrated.silence_threshold, calibrated.speech_threshold);
+        configs.set(&source, calibrated);
+    }
+    Ok(configs.clone())
+}
+
+// Manually override the min/max speaker count used by diarization (None clears a bound)
+#[tauri::command]
+fn set_diarization_speaker_bounds(
+    state: tauri::State<AppState>,
+    min_speakers: Option<i32>,
+    max_speakers: Option<i32>,
+) -> Result<(), String> {
+    if let (Some(min), Some(max)) = (min_speakers, max_speakers) {
+        if min > max {
+            return Err("min_speakers cannot be greater than max_speakers".to_string());
+        }
+    }
+    if min_speakers.map(|n| n < 1).unwrap_or(false) || max_speakers.map(|n| n < 1).unwrap_or(false) {
+        return Err("Speaker bounds must be at least 1".to_string());
+    }
+
+    let mut diar_guard = state.diarization_engine.write();
+    let engine = diar_guard.as_mut()
+        .ok_or("Diarization engine not initialized")?;
+    engine.set_speaker_bounds(min_speakers, max_speakers);
+
+    println!("[Diarization] Speaker bounds set to min={:?}, max={:?}", min_speakers, max_speakers);
+    Ok(())
+}
+
+/// Check if diarization engine is initialized and ready
+#[tauri::command]
+fn get_diarization_status(state: tauri::State<AppState>) -> serde_json::Value {
+    let diar_guard = state.diarization_engine.read();
+    let is_initialized = diar_guard.is_some() && diar_guard.as_ref().map(|e| e.is_initialized()).unwrap_or(false);
+
+    // Check if models are downloaded
+    let models_dir = get_models_dir();
+    let segmentation_exists = models_dir.join("sherpa-onnx-pyannote-segmentation-3-0").join("model.onnx").exists();
+    let embedding_exists = models_dir.join("3dspeaker_speech_eres2net_base_sv_zh-cn_3dspeaker_16k.onnx").exists();
+
+    let last_meeting = state.last_diarization.lock().clone();
+
+    serde_json::json!({
+        "is_initialized": is_initialized,
+        "segmentation_model_exists": segmentation_exists,
+        "embedding_model_exists": embedding_exists,
+        "models_dir": models_dir.to_string_lossy(),
+        "ready": is_initialized && segmentation_exists && embedding_exists,
+        "average_confidence": last_meeting.as_ref().and_then(|q| q.average_confidence),
+        "last_meeting": last_meeting,
+    })
+}
+
+/// How long `get_system_diagnostics` waits on the database and LLM endpoint
+const DIAGNOSTIC_PING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Outcome of one diagnostic check; a failure here doesn't affect the others
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DiagnosticCheck {
+    pub ok: bool,
+    pub error: Option<String>,
+    pub latency_ms: Option<u64>,
+}
+
+impl DiagnosticCheck {
+    fn from_result<E: Into<String>>(result: Result<(), E>, started: std::time::Instant) -> Self {
+        let latency_ms = Some(started.elapsed().as_millis() as u64);
+        match result {
+            Ok(()) => Self { ok: true, error: None, latency_ms },
+            Err(e) => Self { ok: false, error: Some(e.into()), latency_ms },
+        }
+    }
+
+    fn failed(error: impl Into<String>) -> Self {
+        Self { ok: false, error: Some(error.into()), latency_ms: None }
+    }
+}
+
+/// Which engines are loaded in AppState
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EngineDiagnostics {
+    pub asr: bool,
+    pub smart_turn: bool,
+    pub entities: bool,
+    pub embeddings: bool,
+    pub diarization: bool,
+    pub llm: bool,
+    pub knowledge_base: bool,
+}
+
+/// Space left on the filesystem holding one of the app's directories
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DiskSpace {
+    pub name: String,
+    pub path: String,
+    pub free_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub error: Option<String>,
+}
+
+/// Everything `get_system_diagnostics` checks, for a single health screen
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SystemDiagnostics {
+    pub engines: EngineDiagnostics,
+    pub models: Vec<ModelStatus>,
+    pub models_ready: bool,
+    pub audio: Option<AudioCapabilities>,
+    pub audio_error: Option<String>,
+    pub database: DiagnosticCheck,
+    pub llm_endpoint: DiagnosticCheck,
+    pub llm_url: Option<String>,
+    pub disk: Vec<DiskSpace>,
+}
+
+/// The mounted disk `path` lives on: the one with the longest matching mount point
+fn disk_for_path<'a>(disks: &'a sysinfo::Disks, path: &std::path::Path) -> Option<&'a sysinfo::Disk> {
+    disks.list().iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+}
+
+fn disk_space(disks: &sysinfo::Disks, name: &str, path: Result<std::path::PathBuf, String>) -> DiskSpace {
+    let path = match path {
+        Ok(path) => path,
+        Err(e) => return DiskSpace { name: name.to_string(), path: String::new(), free_bytes: None, total_bytes: None, error: Some(e) },
+    };
+    // Resolve symlinks so the path lines up with the mount points
+    let resolved = path.canonicalize().unwrap_or_else(|_| path.clone());
+    let disk = disk_for_path(disks, &resolved);
+    DiskSpace {
+        name: name.to_string(),
+        path: path.to_string_lossy().to_string(),
+        free_bytes: disk.map(|d| d.available_space()),
+        total_bytes: disk.map(|d| d.total_space()),
+        error: disk.is_none().then(|| format!("No mounted disk found for {:?}", path)),
+    }
+}
+
+// Aggregate engine, model, audio, database, LLM and disk checks for a health screen
+// Each check runs on its own, so one failing (e.g. an unreachable LLM) still reports the rest.
+#[tauri::command]
+async fn get_system_diagnostics(state: tauri::State<'_, AppState>) -> Result<SystemDiagnostics, String> {
+    let knowledge_base = state.knowledge_base.read().await.is_some();
+    let engines = EngineDiagnostics {
+        asr: state.asr_engine.read().is_some(),
+        smart_turn: state.smart_turn_engine.read().is_some(),
+        entities: state.entity_engine.read().is_some(),
+        embeddings: state.embedding_engine.read().is_some(),
+        diarization: state.diarization_engine.read().as_ref().map(|e| e.is_initialized()).unwrap_or(false),
+        llm: state.llm_assistant.read().is_some(),
+        knowledge_base,
+    };
+
+    let (audio, audio_error) = match std::panic::catch_unwind(check_audio_capabilities) {
+        Ok(caps) => (Some(caps), None),
+        Err(_) => (None, Some("Audio capability check panicked".to_string())),
+    };
+
+    let database = {
+        let started = std::time::Instant::now();
+        let kb_guard = state.knowledge_base.read().await;
+        match kb_guard.as_ref() {
+            Some(kb) => match tokio::time::timeout(DIAGNOSTIC_PING_TIMEOUT, kb.ping()).await {
+                Ok(result) => DiagnosticCheck::from_result(result, started),
+                Err(_) => DiagnosticCheck::failed("Database ping timed out"),
+            },
+            None => DiagnosticCheck::failed("Knowledge base not initialized"),
+        }
+    };
+
+    let assistant = state.llm_assistant.read().clone();
+    let llm_url = assistant.as_ref().map(|a| a.api_url().to_string());
+    let llm_endpoint = match assistant {
+        Some(assistant) => match ensure_llm_endpoint_allowed(&state, assistant.api_url()) {
+            Ok(()) => {
+                let started = std::time::Instant::now();
+                match tokio::time::timeout(DIAGNOSTIC_PING_TIMEOUT, assistant.test_connection()).await {
+                    Ok(result) => DiagnosticCheck::from_result(result, started),
+                    Err(_) => DiagnosticCheck::failed("LLM endpoint ping timed out"),
+                }
+            }
+            Err(e) => DiagnosticCheck::failed(e),
+        },
+        None => DiagnosticCheck::failed("LLM not initialized"),
+    };
+
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    let disk = vec![
+        disk_space(&disks, "models", Ok(get_models_dir())),
+        disk_space(&disks, "knowledge_base", Ok(knowledge_base_dir())),
+        disk_space(&disks, "user_store", user_store_dir()),
+    ];
+
+    Ok(SystemDiagnostics {
+        engines,
+        models: get_models_status(),
+        models_ready: all_models_installed(),
+        audio,
+        audio_error,
+        database,
+        llm_endpoint,
+        llm_url,
+        disk,
+    })
+}
+
+// ==================== SCREENSHOT COMMANDS ====================
+
+/// Capture a screenshot of the primary screen
+#[tauri::command]
+fn take_screenshot() -> Result<ScreenshotResult, String> {
+    capture_screen()
+}
+
+/// Capture screenshot and analyze with LLM
+#[tauri::command]
+async fn analyze_screenshot(
+    state: tauri::State<'_, AppState>,
+    question: Option<String>,
+) -> Result<String, String> {
+    // Get the LLM assistant (clone the Arc to release the lock before await)
+    let assistant = {
+        let llm_guard = state.llm_assistant.read();
+        llm_guard.as_ref()
+            .ok_or("LLM not initialized. Please configure your LLM endpoint in Settings.")?
+            .clone()
+    };
+
+    // Build the prompt with the image
+    let prompt = question.unwrap_or_else(|| {
+        "I just captured my screen during a meeting. Please analyze this screenshot and tell me:\n\
+         1. What is being discussed or shown?\n\
+         2. Any important information visible (data, charts, text)?\n\
+         3. Any action items or key points I should note?".to_string()
+    });
+
+    // Text-only models can't see the image, so read the screen with OCR and ask about the text
+    if !assistant.supports_vision() {
+        println!("[Screenshot] Model is not vision-capable, falling back to OCR");
+        let screen_text = ocr_screen(&state).await?;
+        if screen_text.is_empty() {
+            return Err("No readable text found on screen".to_string());
+        }
+
+        let ocr_question = format!(
+            "{}\n\nThe model can't view images, so here is the text read from my screen via OCR \
+             (layout may be lost):\n\n{}",
+            prompt, screen_text
+        );
+        let response = assistant
+            .ask(&ocr_question, state.knowledge_base.clone())
+            .await
+            .map_err(|e| format!("LLM analysis failed: {}", e))?;
+
+        println!("[Screenshot] OCR-based analysis complete ({} chars)", response.len());
+        return Ok(response);
+    }
+
+    // Capture the screen
+    let screenshot = capture_screen()?;
+
+    // Create a message with the image for vision models
+    // Format as a data URL for the LLM
+    let image_data_url = format!("data:image/png;base64,{}", screenshot.base64_data);
+
+    // For vision-capable LLMs, we send the image as part of the message
+    // The rig-core library handles multimodal messages
+    let full_prompt = format!(
+        "{}\n\n[Screenshot attached: {}x{} image]",
+        prompt, screenshot.width, screenshot.height
+    );
+
+    // Use the assistant to analyze
+    // Note: This requires a vision-capable model (GPT-4V, Claude 3, etc.)
+    let response = assistant
+        .ask_with_image(&full_prompt, &image_data_url)
+        .await
+        .map_err(|e| format!("LLM analysis failed: {}", e))?;
+
+    println!("[Screenshot] LLM analysis complete ({} chars)", response.len());
+
+    Ok(response)
+}
+
+/// The shared OCR engine, loading it on first use
+async fn loaded_ocr_engine(state: &AppState) -> Result<Arc<ScreenOcr>, String> {
+    let existing = state.ocr_engine.read().clone();
+    if let Some(engine) = existing {
+        return Ok(engine);
+    }
+
+    if OCR_MODEL_IDS.iter().filter_map(|id| find_model(id)).any(|m| !is_model_installed(&m)) {
+        return Err("OCR models are not downloaded; run download_ocr_models first".to_string());
+    }
+
+    let models_dir = get_models_dir();
+    let engine = tokio::task::spawn_blocking(move || ScreenOcr::new(&models_dir))
+        .await
+        .map_err(|e| format!("OCR initialization failed: {}", e))??;
+    let engine = Arc::new(engine);
+    *state.ocr_engine.write() = Some(engine.clone());
+    Ok(engine)
+}
+
+/// Capture the screen and run OCR on it, loading the OCR engine on first use
+async fn ocr_screen(state: &AppState) -> Result<String, String> {
+    let engine = loaded_ocr_engine(state).await?;
+
+    let (pixels, width, height) = capture_screen_pixels()?;
+    let text = tokio::task::spawn_blocking(move || engine.extract_text(&pixels, width, height))
+        .await
+        .map_err(|e| format!("OCR failed: {}", e))??;
+
+    println!("[Screenshot] OCR extracted {} chars", text.len());
+    Ok(text)
+}
+
+/// Capture the screen and return the text read from it via OCR
+#[tauri::command]
+async fn extract_text_from_screenshot(
+    state: tauri::State<'_, AppState>,
+) -> Result<String, String> {
+    ocr_screen(&state).await
+}
+
+// ==================== USER STORE COMMANDS ====================
+
+// Initialize the user store (SQLite)
+#[tauri::command]
+fn initialize_user_store(state: tauri::State<AppState>) -> Result<(), String> {
+    let mut store_guard = state.user_store.lock();
+
+    if store_guard.is_some() {
+        return Ok(()); // Already initialized
+    }
+
+    let data_dir = user_store_dir()?;
+
+    let store = UserStore::new(&data_dir)?;
+    *store_guard = Some(store);
+
+    println!("User store initialized");
+    Ok(())
+}
+
+// Get user settings
+#[tauri::command]
+fn get_user_settings(state: tauri::State<AppState>) -> Result<UserSettings, String> {
+    let store_guard = state.user_store.lock();
+    let store = store_guard.as_ref().ok_or("User store not initialized")?;
+    store.get_settings()
+}
+
+// Update user settings
+#[tauri::command]
+async fn update_user_settings(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    mut settings: UserSettings,
+) -> Result<(), String> {
+    // Reject bad patterns before they are saved
+    Redactor::from_config(&settings.redaction_patterns)?;
+    GenerationSettings::from_config(&settings.llm_generation_params)?;
+    RetrievalConfig::from_config(&settings.retrieval_params)?;
+    ModelPricing::from_config(&settings.llm_pricing)?;
+    AlertMatcher::from_config(&settings.meeting_alerts)?;
+    LlmEndpoint::list_from_config(&settings.llm_fallbacks)?;
+    validate_auto_stop_silence_minutes(settings.auto_stop_silence_minutes)?;
+    validate_max_recording_minutes(settings.max_recording_minutes)?;
+    validate_retention_days(settings.retention_days)?;
+    validate_suggestion_min_interval_secs(settings.suggestion_min_interval_secs)?;
+    EntityConfig::from_settings(
+        settings.entity_min_confidence,
+        &settings.entity_labels,
+        &settings.entity_label_kinds,
+    )?;
+    RelationConfig::from_settings(settings.relation_min_confidence, &settings.relation_whitelist)?;
+    ChunkStrategy::parse(&settings.chunk_strategy)?;
+    SpeakerLabels::new(&settings.mic_speaker_label, &settings.remote_speaker_label)?;
+    WakeWordConfig {
+        phrase: settings.wake_word_phrase.clone(),
+        sensitivity: settings.wake_word_sensitivity,
+        auto_start: settings.wake_word_auto_start,
+    }.validate()?;
+    {
+        let store_guard = state.user_store.lock();
+        let store = store_guard.as_ref().ok_or("User store not initialized")?;
+        // The embedding model only changes through reembed_all
+        settings.embedding_model = store.get_settings()?.embedding_model;
+        store.update_settings(&settings)?;
+    }
+    apply_saved_settings(&state, &app).await?;
+    apply_offline_mode(state, app);
+    Ok(())
+}
+
+/// Push every saved setting out to the engines and the knowledge base
+/// (LLM endpoint settings are applied separately by `initialize_llm`)
+async fn apply_saved_settings(state: &AppState, app: &tauri::AppHandle) -> Result<(), String> {
+    apply_entity_settings(state)?;
+    apply_inference_settings(state);
+    apply_redaction_settings(state).await;
+    apply_relation_settings(state).await;
+    apply_chunking_settings(state).await;
+    apply_generation_settings(state);
+    apply_retrieval_config(state);
+    apply_prompt_template(state);
+    apply_alert_settings(state);
+    apply_suggestion_settings(state);
+    apply_screen_share_settings(state, app);
+    apply_wake_word_settings(state, app)
+}
+
+// Set a single setting
+#[tauri::command]
+async fn set_user_setting(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    if key == "redaction_patterns" {
+        Redactor::from_config(&value)?;
+    }
+    if key == "llm_generation_params" {
+        GenerationSettings::from_config(&value)?;
+    }
+    if key == "retrieval_params" {
+        RetrievalConfig::from_config(&value)?;
+    }
+    if key == "llm_pricing" {
+        ModelPricing::from_config(&value)?;
+    }
+    if key == "meeting_alerts" {
+        AlertMatcher::from_config(&value)?;
+    }
+    if key == "llm_fallbacks" {
+        LlmEndpoint::list_from_config(&value)?;
+    }
+    if key == "chunk_strategy" {
+        ChunkStrategy::parse(&value)?;
+    }
+    if key.starts_with("entity_") {
+        validate_entity_setting(&state, &key, &value)?;
+    }
+    if key.starts_with("relation_") {
+        validate_relation_setting(&state, &key, &value)?;
+    }
+    if key.starts_with("wake_word_") {
+        validate_wake_word_setting(&key, &value)?;
+    }
+    if key == "embedding_model" {
+        return Err("Use reembed_all to switch embedding models".to_string());
+    }
+    if key == "auto_stop_silence_minutes" {
+        let minutes = value.trim().parse::<u64>()
+            .map_err(|_| format!("Invalid auto_stop_silence_minutes: {}", value))?;
+        validate_auto_stop_silence_minutes(minutes)?;
+    }
+    if key == "retention_days" {
+        let days = value.trim().parse::<u64>()
+            .map_err(|_| format!("Invalid retention_days: {}", value))?;
+        validate_retention_days(days)?;
+    }
+    if key == "suggestion_every_n" || key == "suggestion_min_interval_secs" {
+        let n = value.trim().parse::<u64>()
+            .map_err(|_| format!("Invalid {}: {}", key, value))?;
+        if key == "suggestion_min_interval_secs" {
+            validate_suggestion_min_interval_secs(n)?;
+        }
+    }
+    if key == "resume_window_minutes" || key == "max_recording_minutes" {
+        let minutes = value.trim().parse::<u64>()
+            .map_err(|_| format!("Invalid {}: {}", key, value))?;
+        if key == "max_recording_minutes" {
+            validate_max_recording_minutes(minutes)?;
+        }
+    }
+    if key.ends_with("_speaker_label") {
+        let current = configured_speaker_labels(&state);
+        match key.as_str() {
+            "mic_speaker_label" => SpeakerLabels::new(&value, &current.remote)?,
+            _ => SpeakerLabels::new(&current.mic, &value)?,
+        };
+    }
+    if key == "inference_concurrency" {
+        let limit = value.trim().parse::<usize>()
+            .map_err(|_| format!("Invalid inference_concurrency: {}", value))?;
+        if !(1..=inference_gate::MAX_INFERENCE_CONCURRENCY).contains(&limit) {
+            return Err(format!("inference_concurrency must be between 1 and {}", inference_gate::MAX_INFERENCE_CONCURRENCY));
+        }
+    }
+    {
+        let store_guard = state.user_store.lock();
+        let store = store_guard.as_ref().ok_or("User store not initialized")?;
+        store.set_setting(&key, &value)?;
+    }
+    if key == "redaction_patterns" {
+        apply_redaction_settings(&state).await;
+    }
+    if key.starts_with("entity_") {
+        apply_entity_settings(&state)?;
+    }
+    if key.starts_with("relation_") {
+        apply_relation_settings(&state).await;
+    }
+    if key == "chunk_strategy" {
+        apply_chunking_settings(&state).await;
+    }
+    if key == "llm_generation_params" {
+        apply_generation_settings(&state);
+    }
+    if key == "retrieval_params" {
+        apply_retrieval_config(&state);
+    }
+    if key == "active_prompt_template" {
+        apply_prompt_template(&state);
+    }
+    if key == "meeting_alerts" {
+        apply_alert_settings(&state);
+    }
+    if key.starts_with("suggestion_") {
+        apply_suggestion_settings(&state);
+    }
+    if key == "inference_concurrency" {
+        apply_inference_settings(&state);
+    }
+    if key.starts_with("wake_word_") {
+        apply_wake_word_settings(&state, &app)?;
+    }
+    Ok(())
+}
+
+/// Check a single `wake_word_*` text setting before it is saved
+fn validate_wake_word_setting(key: &str, value: &str) -> Result<(), String> {
+    match key {
+        "wake_word_phrase" if value.trim().is_empty() => {
+            Err("Wake word phrase cannot be empty".to_string())
+        }
+        "wake_word_sensitivity" => {
+            let sensitivity = value.trim().parse::<f32>()
+                .map_err(|_| format!("Invalid wake_word_sensitivity: {}", value))?;
+            if !(0.0..=1.0).contains(&sensitivity) {
+                return Err("wake_word_sensitivity must be between 0 and 1".to_string());
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Wake word config from the saved `wake_word_*` settings; None while disabled
+fn configured_wake_word(state: &AppState) -> Option<WakeWordConfig> {
+    let settings = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref().and_then(|s| s.get_settings().ok())
+    }?;
+    settings.wake_word_enabled.then(|| WakeWordConfig {
+        phrase: settings.wake_word_phrase,
+        sensitivity: settings.wake_word_sensitivity,
+        auto_start: settings.wake_word_auto_start,
+    })
+}
 
-    state.is_recording.store(false, std::sync::atomic::Ordering::SeqCst);
+/// Load or unload the wake word spotter to match the settings, then (re)start listening
+fn apply_wake_word_settings(state: &AppState, app: &tauri::AppHandle) -> Result<(), String> {
+    let config = configured_wake_word(state);
+    let current = state.wake_word_engine.lock().as_ref().map(|e| e.config().clone());
+    if current == config {
+        return start_wake_word_listener(state, app);
+    }
 
-    // Emit recording-stopped event
-    let _ = app.emit("recording-stopped", ());
+    stop_wake_word_listener(state);
+    let engine = match config {
+        Some(config) => match WakeWordEngine::new(&get_models_dir(), config) {
+            Ok(engine) => Some(engine),
+            Err(e) => {
+                *state.wake_word_engine.lock() = None;
+                return Err(e);
+            }
+        },
+        None => None,
+    };
+    let enabled = engine.is_some();
+    *state.wake_word_engine.lock() = engine;
+    println!("[WakeWord] {}", if enabled { "Enabled" } else { "Disabled" });
+    start_wake_word_listener(state, app)
+}
 
-    println!("Recording stopped");
-    Ok(())
+// Load the wake word spotter from the saved settings and start listening (no-op while disabled)
+#[tauri::command]
+fn initialize_wake_word(state: tauri::State<AppState>, app: tauri::AppHandle) -> Result<(), String> {
+    apply_wake_word_settings(&state, &app)
 }
 
+// Download the optional wake word model (not part of download_models)
 #[tauri::command]
-fn is_recording(state: tauri::State<AppState>) -> bool {
-    state.is_recording.load(std::sync::atomic::Ordering::SeqCst)
+async fn download_wake_word_model(state: tauri::State<'_, AppState>, app: tauri::AppHandle) -> Result<(), String> {
+    ensure_online(&state)?;
+    let model = find_model(WAKE_WORD_MODEL_ID).ok_or("Wake word model is not listed")?;
+    if is_model_installed(&model) {
+        return Ok(());
+    }
+    download_model(app, model).await
 }
 
+// Download the OCR models used to read screenshots when the LLM can't take images
 #[tauri::command]
-fn set_screen_share_protection(window: tauri::Window, enabled: bool) -> Result<(), String> {
-    window.set_content_protected(enabled).map_err(|e| e.to_string())?;
-    println!("Screen share protection: {}", if enabled { "enabled" } else { "disabled" });
+async fn download_ocr_models(state: tauri::State<'_, AppState>, app: tauri::AppHandle) -> Result<(), String> {
+    ensure_online(&state)?;
+    for id in OCR_MODEL_IDS {
+        let model = find_model(id).ok_or_else(|| format!("OCR model {} is not listed", id))?;
+        if !is_model_installed(&model) {
+            download_model(app.clone(), model).await?;
+        }
+    }
     Ok(())
 }
 
-#[tauri::command]
-fn check_models_status() -> Vec<ModelStatus> {
-    get_models_status()
+/// Entity config built from the saved `entity_*` settings (built-in labels if unset)
+fn configured_entity_config(state: &AppState) -> Result<EntityConfig, String> {
+    let settings = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref().and_then(|s| s.get_settings().ok())
+    };
+    match settings {
+        Some(s) => EntityConfig::from_settings(s.entity_min_confidence, &s.entity_labels, &s.entity_label_kinds),
+        None => Ok(EntityConfig::default()),
+    }
 }
 
-#[tauri::command]
-fn are_models_ready() -> bool {
-    all_models_installed()
+/// Check that changing one `entity_*` setting still yields a valid config
+fn validate_entity_setting(state: &AppState, key: &str, value: &str) -> Result<(), String> {
+    let mut settings = {
+        let store_guard = state.user_store.lock();
+        let store = store_guard.as_ref().ok_or("User store not initialized")?;
+        store.get_settings()?
+    };
+    match key {
+        "entity_min_confidence" => {
+            settings.entity_min_confidence = value.trim().parse()
+                .map_err(|_| format!("Invalid entity_min_confidence: {}", value))?;
+        }
+        "entity_labels" => settings.entity_labels = value.to_string(),
+        "entity_label_kinds" => settings.entity_label_kinds = value.to_string(),
+        _ => {}
+    }
+    EntityConfig::from_settings(settings.entity_min_confidence, &settings.entity_labels, &settings.entity_label_kinds)?;
+    Ok(())
 }
 
-#[tauri::command]
-async fn download_models(app: tauri::AppHandle) -> Result<(), String> {
-    download_all_models(app).await
+/// Push the saved entity settings into the running entity engine
+fn apply_entity_settings(state: &AppState) -> Result<(), String> {
+    let config = configured_entity_config(state)?;
+    if let Some(engine) = state.entity_engine.read().as_ref() {
+        engine.set_config(config);
+    }
+    Ok(())
 }
 
+// Get the entity labels, threshold and graph mapping in use
 #[tauri::command]
-fn get_models_path() -> String {
-    get_models_dir().to_string_lossy().to_string()
+fn get_entity_config(state: tauri::State<AppState>) -> Result<EntityConfig, String> {
+    match state.entity_engine.read().as_ref() {
+        Some(engine) => Ok(engine.config()),
+        None => configured_entity_config(&state),
+    }
 }
 
-// ==================== AUDIO & DIARIZATION DIAGNOSTICS ====================
-
-/// Check audio capture capabilities
+// Replace the GLiNER label set, e.g. to add domain labels like "ticket_id" or "repo"
+// `kinds` maps custom labels to how they are stored in the graph (person, topic, action_item, decision)
 #[tauri::command]
-fn get_audio_capabilities() -> AudioCapabilities {
-    check_audio_capabilities()
+fn set_entity_labels(
+    state: tauri::State<AppState>,
+    labels: Vec<String>,
+    kinds: Option<std::collections::HashMap<String, EntityKind>>,
+) -> Result<EntityConfig, String> {
+    let min_confidence = configured_entity_config(&state)
+        .map(|c| c.min_confidence)
+        .unwrap_or(entities::DEFAULT_MIN_CONFIDENCE);
+    let kinds = kinds.unwrap_or_default();
+    let config = EntityConfig::new(min_confidence, &labels, kinds.clone())?;
+
+    {
+        let store_guard = state.user_store.lock();
+        let store = store_guard.as_ref().ok_or("User store not initialized")?;
+        let labels_json = serde_json::to_string(&config.labels)
+            .map_err(|e| format!("Failed to serialize entity labels: {}", e))?;
+        let kinds_json = serde_json::to_string(&kinds)
+            .map_err(|e| format!("Failed to serialize entity label kinds: {}", e))?;
+        store.set_setting("entity_labels", &labels_json)?;
+        store.set_setting("entity_label_kinds", &kinds_json)?;
+    }
+
+    if let Some(engine) = state.entity_engine.read().as_ref() {
+        engine.set_config(config.clone());
+    }
+    Ok(config)
 }
 
-/// Check if diarization engine is initialized and ready
-#[tauri::command]
-fn get_diarization_status(state: tauri::State<AppState>) -> serde_json::Value {
-    let diar_guard = state.diarization_engine.read();
-    let is_initialized = diar_guard.is_some() && diar_guard.as_ref().map(|e| e.is_initialized()).unwrap_or(false);
+/// Relation config built from the saved `relation_*` settings (keeps everything >= 0.5 if unset)
+fn configured_relation_config(state: &AppState) -> Result<RelationConfig, String> {
+    let settings = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref().and_then(|s| s.get_settings().ok())
+    };
+    match settings {
+        Some(s) => RelationConfig::from_settings(s.relation_min_confidence, &s.relation_whitelist),
+        None => Ok(RelationConfig::default()),
+    }
+}
 
-    // Check if models are downloaded
-    let models_dir = get_models_dir();
-    let segmentation_exists = models_dir.join("sherpa-onnx-pyannote-segmentation-3-0").join("model.onnx").exists();
-    let embedding_exists = models_dir.join("3dspeaker_speech_eres2net_base_sv_zh-cn_3dspeaker_16k.onnx").exists();
+/// Check that changing one `relation_*` setting still yields a valid config
+fn validate_relation_setting(state: &AppState, key: &str, value: &str) -> Result<(), String> {
+    let mut settings = {
+        let store_guard = state.user_store.lock();
+        let store = store_guard.as_ref().ok_or("User store not initialized")?;
+        store.get_settings()?
+    };
+    match key {
+        "relation_min_confidence" => {
+            settings.relation_min_confidence = value.trim().parse()
+                .map_err(|_| format!("Invalid relation_min_confidence: {}", value))?;
+        }
+        "relation_whitelist" => settings.relation_whitelist = value.to_string(),
+        _ => {}
+    }
+    RelationConfig::from_settings(settings.relation_min_confidence, &settings.relation_whitelist)?;
+    Ok(())
+}
 
-    serde_json::json!({
-        "is_initialized": is_initialized,
-        "segmentation_model_exists": segmentation_exists,
-        "embedding_model_exists": embedding_exists,
-        "models_dir": models_dir.to_string_lossy(),
-        "ready": is_initialized && segmentation_exists && embedding_exists,
-    })
+/// Push the saved relation settings into the knowledge base
+async fn apply_relation_settings(state: &AppState) {
+    let config = match configured_relation_config(state) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("[KB] Invalid relation settings, keeping defaults: {}", e);
+            RelationConfig::default()
+        }
+    };
+
+    let kb_guard = state.knowledge_base.read().await;
+    if let Some(kb) = kb_guard.as_ref() {
+        println!("[KB] Relation threshold {:.2}, whitelist: {:?}", config.min_confidence, config.allowed_relations);
+        kb.set_relation_config(config);
+    }
 }
 
-// ==================== SCREENSHOT COMMANDS ====================
+/// Tell the knowledge base which chunking strategy the settings ask for
+async fn apply_chunking_settings(state: &AppState) {
+    let strategy = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .and_then(|s| ChunkStrategy::parse(&s.chunk_strategy).ok())
+            .unwrap_or_default()
+    };
 
-/// Capture a screenshot of the primary screen
+    let kb_guard = state.knowledge_base.read().await;
+    if let Some(kb) = kb_guard.as_ref() {
+        println!("[KB] Chunking knowledge sources with {:?}", strategy);
+        kb.set_chunk_strategy(strategy);
+    }
+}
+
+// Get the relationship threshold and relation whitelist in use
 #[tauri::command]
-fn take_screenshot() -> Result<ScreenshotResult, String> {
-    capture_screen()
+fn get_relation_config(state: tauri::State<AppState>) -> Result<RelationConfig, String> {
+    configured_relation_config(&state)
 }
 
-/// Capture screenshot and analyze with LLM
+// Set the relationship threshold and whitelist (empty list keeps every relation type)
+// Applies to relations extracted from now on; existing edges are left as they are.
 #[tauri::command]
-async fn analyze_screenshot(
+async fn set_relation_config(
     state: tauri::State<'_, AppState>,
-    question: Option<String>,
-) -> Result<String, String> {
-    // Capture the screen
-    let screenshot = capture_screen()?;
+    min_confidence: f32,
+    allowed_relations: Vec<String>,
+) -> Result<RelationConfig, String> {
+    let config = RelationConfig::new(min_confidence, &allowed_relations)?;
 
-    // Get the LLM assistant (clone the Arc to release the lock before await)
-    let assistant = {
-        let llm_guard = state.llm_assistant.read();
-        llm_guard.as_ref()
-            .ok_or("LLM not initialized. Please configure your LLM endpoint in Settings.")?
-            .clone()
-    };
+    {
+        let store_guard = state.user_store.lock();
+        let store = store_guard.as_ref().ok_or("User store not initialized")?;
+        let whitelist_json = serde_json::to_string(&config.allowed_relations)
+            .map_err(|e| format!("Failed to serialize relation whitelist: {}", e))?;
+        store.set_setting("relation_min_confidence", &config.min_confidence.to_string())?;
+        store.set_setting("relation_whitelist", &whitelist_json)?;
+    }
 
-    // Build the prompt with the image
-    let prompt = question.unwrap_or_else(|| {
-        "I just captured my screen during a meeting. Please analyze this screenshot and tell me:\n\
-         1. What is being discussed or shown?\n\
-         2. Any important information visible (data, charts, text)?\n\
-         3. Any action items or key points I should note?".to_string()
+    apply_relation_settings(&state).await;
+    Ok(config)
+}
+
+/// Recompile the saved `meeting_alerts`; a bad setting leaves alerts off
+fn apply_alert_settings(state: &AppState) {
+    let alerts = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| s.meeting_alerts)
+            .unwrap_or_default()
+    };
+    let matcher = AlertMatcher::from_config(&alerts).unwrap_or_else(|e| {
+        eprintln!("[Alerts] {}", e);
+        AlertMatcher::default()
     });
+    *state.meeting_alerts.write() = Arc::new(matcher);
+}
 
-    // Create a message with the image for vision models
-    // Format as a data URL for the LLM
-    let image_data_url = format!("data:image/png;base64,{}", screenshot.base64_data);
+/// Saved `inference_concurrency`: model passes each ONNX engine runs at once
+fn configured_inference_concurrency(state: &AppState) -> usize {
+    let store_guard = state.user_store.lock();
+    store_guard.as_ref()
+        .and_then(|s| s.get_settings().ok())
+        .map(|s| s.inference_concurrency as usize)
+        .unwrap_or(inference_gate::DEFAULT_INFERENCE_CONCURRENCY)
+}
 
-    // For vision-capable LLMs, we send the image as part of the message
-    // The rig-core library handles multimodal messages
-    let full_prompt = format!(
-        "{}\n\n[Screenshot attached: {}x{} image]",
-        prompt, screenshot.width, screenshot.height
-    );
+/// Mic and remote speaker labels from settings, or "You"/"Guest" if they're unset or clash
+fn configured_speaker_labels(state: &AppState) -> SpeakerLabels {
+    let store_guard = state.user_store.lock();
+    store_guard.as_ref()
+        .and_then(|s| s.get_settings().ok())
+        .and_then(|s| SpeakerLabels::new(&s.mic_speaker_label, &s.remote_speaker_label).ok())
+        .unwrap_or_default()
+}
 
-    // Use the assistant to analyze
-    // Note: This requires a vision-capable model (GPT-4V, Claude 3, etc.)
-    let response = assistant
-        .ask_with_image(&full_prompt, &image_data_url)
-        .await
-        .map_err(|e| format!("LLM analysis failed: {}", e))?;
+/// Push the inference concurrency limit into the loaded embedding and entity engines
+fn apply_inference_settings(state: &AppState) {
+    let limit = configured_inference_concurrency(state);
+    if let Some(engine) = state.embedding_engine.read().as_ref() {
+        engine.set_max_concurrency(limit);
+    }
+    if let Some(engine) = state.entity_engine.read().as_ref() {
+        engine.set_max_concurrency(limit);
+    }
+    println!("[Inference] Up to {} concurrent pass(es) per engine", limit);
+}
 
-    println!("[Screenshot] LLM analysis complete ({} chars)", response.len());
+/// Longest `suggestion_min_interval_secs` accepted (an hour); 0 means no limit
+const SUGGESTION_MIN_INTERVAL_SECS_LIMIT: u64 = 60 * 60;
 
-    Ok(response)
+fn validate_suggestion_min_interval_secs(secs: u64) -> Result<(), String> {
+    if secs > SUGGESTION_MIN_INTERVAL_SECS_LIMIT {
+        return Err(format!("suggestion_min_interval_secs must be between 0 and {}", SUGGESTION_MIN_INTERVAL_SECS_LIMIT));
+    }
+    Ok(())
 }
 
-// ==================== USER STORE COMMANDS ====================
+/// Load the realtime suggestion cadence from the saved settings
+fn apply_suggestion_settings(state: &AppState) {
+    let cadence = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| SuggestionCadence {
+                enabled: s.suggestions_enabled,
+                every_n: s.suggestion_every_n,
+                on_turn_complete: s.suggest_on_turn_complete,
+                min_interval_ms: s.suggestion_min_interval_secs.saturating_mul(1000),
+            })
+            .unwrap_or_default()
+    };
+    *state.suggestion_cadence.write() = cadence;
+}
 
-// Initialize the user store (SQLite)
-#[tauri::command]
-fn initialize_user_store(state: tauri::State<AppState>) -> Result<(), String> {
-    let mut store_guard = state.user_store.lock();
+/// Redactor built from the saved `redaction_patterns` (built-in rules if unset)
+fn configured_redactor(state: &AppState) -> Result<Redactor, String> {
+    let patterns = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| s.redaction_patterns)
+            .unwrap_or_default()
+    };
+    Redactor::from_config(&patterns)
+}
 
-    if store_guard.is_some() {
-        return Ok(()); // Already initialized
+/// Turn store-time redaction in the knowledge base on or off to match settings
+async fn apply_redaction_settings(state: &AppState) {
+    let enabled = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref()
+            .and_then(|s| s.get_settings().ok())
+            .map(|s| s.redact_on_store)
+            .unwrap_or(false)
+    };
+
+    let redactor = if enabled {
+        match configured_redactor(state) {
+            Ok(redactor) => Some(redactor),
+            Err(e) => {
+                eprintln!("[Redaction] Disabled, invalid patterns: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let kb_guard = state.knowledge_base.read().await;
+    if let Some(kb) = kb_guard.as_ref() {
+        kb.set_redactor(redactor);
+        println!("[Redaction] Redact on store: {}", enabled);
     }
+}
 
-    let data_dir = dirs::data_dir()
-        .ok_or("Could not find data directory")?
-        .join("second-brain");
+// Scrub PII from an existing meeting's transcript, returning the number of redactions
+#[tauri::command]
+async fn redact_meeting(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+) -> Result<usize, SecondBrainError> {
+    let redactor = configured_redactor(&state)?;
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    let store = UserStore::new(&data_dir)?;
-    *store_guard = Some(store);
+    kb.redact_meeting(meeting_id.as_str(), &redactor).await
+}
 
-    println!("User store initialized");
-    Ok(())
+/// Bookmark the current moment of the recording in progress and notify the UI
+async fn bookmark_current_moment(
+    state: &AppState,
+    app: &tauri::AppHandle,
+    note: Option<String>,
+) -> Result<MeetingBookmark, String> {
+    if !state.is_recording.load(std::sync::atomic::Ordering::SeqCst) {
+        return Err("Not recording".to_string());
+    }
+    let meeting_id = state.current_meeting_id.lock().clone()
+        .ok_or("No meeting in progress")?;
+    let recording_start = state.recording_start_time.lock()
+        .ok_or("Recording start time unknown")?;
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+
+    let bookmark = {
+        let kb_guard = state.knowledge_base.read().await;
+        let kb = kb_guard.as_ref()
+            .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+        kb.add_bookmark(&meeting_id, now.saturating_sub(recording_start), now, note).await?
+    };
+
+    println!("[Bookmark] Added at {}ms in meeting {}", bookmark.offset_ms, meeting_id);
+    let _ = app.emit("bookmark-added", &bookmark);
+    Ok(bookmark)
 }
 
-// Get user settings
+// Bookmark the current moment of the recording, with an optional note
 #[tauri::command]
-fn get_user_settings(state: tauri::State<AppState>) -> Result<UserSettings, String> {
-    let store_guard = state.user_store.lock();
-    let store = store_guard.as_ref().ok_or("User store not initialized")?;
-    store.get_settings()
+async fn add_bookmark(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    note: Option<String>,
+) -> Result<MeetingBookmark, String> {
+    bookmark_current_moment(&state, &app, note).await
 }
 
-// Update user settings
+// Get a meeting's bookmarks
 #[tauri::command]
-fn update_user_settings(state: tauri::State<AppState>, settings: UserSettings) -> Result<(), String> {
-    let store_guard = state.user_store.lock();
-    let store = store_guard.as_ref().ok_or("User store not initialized")?;
-    store.update_settings(&settings)
+async fn get_meeting_bookmarks(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+) -> Result<Vec<MeetingBookmark>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_meeting_bookmarks(meeting_id.as_str()).await
 }
 
-// Set a single setting
+// Export a meeting as Markdown; PII is redacted unless `redact` is false
+// `mode` lays out the transcript: "chronological" (default), "by_speaker" or "dialogue"
 #[tauri::command]
-fn set_user_setting(state: tauri::State<AppState>, key: String, value: String) -> Result<(), String> {
-    let store_guard = state.user_store.lock();
-    let store = store_guard.as_ref().ok_or("User store not initialized")?;
-    store.set_setting(&key, &value)
+async fn export_meeting_markdown(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+    redact: Option<bool>,
+    mode: Option<String>,
+) -> Result<String, SecondBrainError> {
+    let mode = TranscriptExportMode::parse(mode.as_deref().unwrap_or_default())?;
+    let redactor = if redact.unwrap_or(true) {
+        Some(configured_redactor(&state)?)
+    } else {
+        None
+    };
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref()
+        .ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.export_meeting_markdown(meeting_id.as_str(), redactor.as_ref(), mode).await
 }
 
 // Create a note
@@ -2061,6 +6532,38 @@ fn delete_note(state: tauri::State<AppState>, id: i64) -> Result<(), String> {
     store.delete_note(id)
 }
 
+// Full-text search over notes
+#[tauri::command]
+fn search_notes(state: tauri::State<AppState>, query: String, limit: Option<usize>) -> Result<Vec<Note>, String> {
+    let store_guard = state.user_store.lock();
+    let store = store_guard.as_ref().ok_or("User store not initialized")?;
+    store.search_notes(&query, limit.unwrap_or(20))
+}
+
+// Link a note to a meeting so it shows up in the meeting view
+#[tauri::command]
+fn link_note_to_meeting(state: tauri::State<AppState>, note_id: i64, meeting_id: MeetingId) -> Result<(), String> {
+    let store_guard = state.user_store.lock();
+    let store = store_guard.as_ref().ok_or("User store not initialized")?;
+    store.link_note_to_meeting(note_id, meeting_id.as_str())
+}
+
+// Remove a note's link to a meeting
+#[tauri::command]
+fn unlink_note_from_meeting(state: tauri::State<AppState>, note_id: i64, meeting_id: MeetingId) -> Result<(), String> {
+    let store_guard = state.user_store.lock();
+    let store = store_guard.as_ref().ok_or("User store not initialized")?;
+    store.unlink_note_from_meeting(note_id, meeting_id.as_str())
+}
+
+// Get notes linked to a meeting
+#[tauri::command]
+fn get_notes_for_meeting(state: tauri::State<AppState>, meeting_id: MeetingId) -> Result<Vec<Note>, String> {
+    let store_guard = state.user_store.lock();
+    let store = store_guard.as_ref().ok_or("User store not initialized")?;
+    store.get_notes_for_meeting(meeting_id.as_str())
+}
+
 // Get integrations
 #[tauri::command]
 fn get_integrations(state: tauri::State<AppState>) -> Result<Vec<Integration>, String> {
@@ -2127,24 +6630,81 @@ fn set_app_state(state: tauri::State<AppState>, key: String, value: String) -> R
 
 // ==================== Web Crawler Commands ====================
 
-// Search the web using DuckDuckGo
+/// Search backends in fallback order, from user settings (DuckDuckGo if unavailable)
+fn search_backends(state: &AppState) -> Vec<SearchBackend> {
+    let store_guard = state.user_store.lock();
+    match store_guard.as_ref().and_then(|store| store.get_settings().ok()) {
+        Some(settings) => SearchBackend::chain(
+            &settings.search_backend,
+            &settings.searxng_url,
+            &settings.brave_api_key,
+        ),
+        None => vec![SearchBackend::DuckDuckGo],
+    }
+}
+
+// Search the web using the configured backends, falling back in order
 #[tauri::command]
 async fn search_web(
+    state: tauri::State<'_, AppState>,
     query: String,
     limit: Option<usize>,
 ) -> Result<Vec<WebSearchResult>, String> {
+    ensure_online(&state)?;
     // Create a new crawler for each request (stateless)
-    let crawler = WebCrawler::new();
+    let crawler = WebCrawler::new().with_backends(search_backends(&state));
     crawler.search(&query, limit.unwrap_or(10)).await
 }
 
+// Run a probe query against one search backend without falling back
+#[tauri::command]
+async fn test_search_backend(
+    state: tauri::State<'_, AppState>,
+    backend: String,
+    base_url: Option<String>,
+    api_key: Option<String>,
+) -> Result<Vec<WebSearchResult>, String> {
+    ensure_online(&state)?;
+    let backend = SearchBackend::from_settings(
+        &backend,
+        base_url.as_deref().unwrap_or(""),
+        api_key.as_deref().unwrap_or(""),
+    ).ok_or_else(|| format!("Search backend '{}' is unknown or missing its URL / API key", backend))?;
+
+    let crawler = WebCrawler::new();
+    let results = crawler.search_with(&backend, "second brain", 3).await?;
+    if results.is_empty() {
+        return Err(format!("{} returned no results", backend.name()));
+    }
+    Ok(results)
+}
+
+/// Crawler honoring the user's robots.txt and crawl limit settings, sharing the rate limiter
+fn page_crawler(state: &AppState) -> WebCrawler {
+    let settings = {
+        let store_guard = state.user_store.lock();
+        store_guard.as_ref().and_then(|store| store.get_settings().ok())
+    };
+
+    let mut config = CrawlerConfig::default();
+    if let Some(settings) = settings {
+        config.respect_robots_txt = settings.respect_robots;
+        config.timeout_secs = settings.crawl_timeout_secs;
+        config.max_bytes = settings.crawl_max_bytes as usize;
+    }
+    WebCrawler::with_config(config).with_politeness(state.crawl_politeness.clone())
+}
+
 // Crawl a single URL and return content
+// Errors starting with web_crawler::ROBOTS_BLOCKED_ERROR mean robots.txt disallowed the page
 #[tauri::command]
 async fn crawl_url(
+    state: tauri::State<'_, AppState>,
     url: String,
 ) -> Result<CrawledPage, String> {
+    ensure_online(&state)?;
     // Create a new crawler for each request (stateless)
-    let crawler = WebCrawler::new();
+    let crawler = page_crawler(&state);
     crawler.crawl_url(&url).await
 }
 
@@ -2152,33 +6712,44 @@ async fn crawl_url(
 #[tauri::command]
 async fn crawl_and_store(
     state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
     url: String,
     tags: Vec<String>,
-) -> Result<String, String> {
+) -> Result<String, SecondBrainError> {
+    ensure_online(&state)?;
     // Create a new crawler for each request (stateless)
-    let crawler = WebCrawler::new();
+    let crawler = page_crawler(&state);
     let crawled = crawler.crawl_url(&url).await?;
 
     // Then store in knowledge base
     let kb_guard = state.knowledge_base.read().await;
-    let kb = kb_guard.as_ref().ok_or("Knowledge base not initialized")?;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.add_knowledge_source(
+    kb.add_knowledge_source_with_progress(
         &crawled.url,
         &crawled.title,
         &crawled.markdown,
         "url",
         tags,
+        &|progress| emit_ingestion_progress(&app, progress),
     ).await
 }
 
+/// Result of a document upload; `pdf` reports pages extracted vs skipped
+#[derive(Debug, Clone, serde::Serialize)]
+struct DocumentUpload {
+    source_id: String,
+    pdf: Option<pdf_text::PdfExtractionReport>,
+}
+
 // Upload and process a document (PDF, TXT, MD)
 #[tauri::command]
 async fn upload_document(
     state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
     file_path: String,
     tags: Vec<String>,
-) -> Result<String, String> {
+) -> Result<DocumentUpload, String> {
     use std::fs;
     use std::path::Path;
 
@@ -2193,14 +6764,15 @@ async fn upload_document(
         .to_lowercase();
 
     // Read file content based on type
-    let content = match extension.as_str() {
+    let (content, pdf) = match extension.as_str() {
         "txt" | "md" | "markdown" => {
-            fs::read_to_string(&file_path)
-                .map_err(|e| format!("Failed to read file: {}", e))?
+            let content = fs::read_to_string(&file_path)
+                .map_err(|e| format!("Failed to read file: {}", e))?;
+            (content, None)
         }
         "pdf" => {
-            // Use pdf-extract crate for PDF parsing
-            extract_pdf_text(&file_path)?
+            let (content, report) = extract_pdf_text(&state, &file_path).await?;
+            (content, Some(report))
         }
         _ => return Err(format!("Unsupported file type: {}", extension)),
     };
@@ -2209,24 +6781,189 @@ async fn upload_document(
 
     // Store in knowledge base
     let kb_guard = state.knowledge_base.read().await;
-    let kb = kb_guard.as_ref().ok_or("Knowledge base not initialized")?;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.add_knowledge_source(
+    let source_id = kb.add_knowledge_source_with_progress(
         &format!("file://{}", file_path),
         &file_name,
         &content,
         source_type,
         tags,
+        &|progress| emit_ingestion_progress(&app, progress),
+    ).await?;
+
+    Ok(DocumentUpload { source_id, pdf })
+}
+
+/// Forward chunk embedding progress to the frontend as `ingestion-progress` and to the progress channel
+fn emit_ingestion_progress(app: &tauri::AppHandle, progress: &IngestionProgress) {
+    let _ = app.emit("ingestion-progress", progress);
+    send_progress(app, ProgressEvent::new("ingestion", progress.embedded_chunks as u64, progress.total_chunks as u64, Some(progress.title.clone())));
+}
+
+// Rebuild a knowledge source's chunks from its stored content (e.g. after switching embedding models)
+#[tauri::command]
+async fn reindex_source(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    source_id: String,
+) -> Result<usize, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.reindex_knowledge_source(&source_id, &|progress| emit_ingestion_progress(&app, progress)).await
+}
+
+// Re-crawl a web source and re-embed it if the page changed
+#[tauri::command]
+async fn refresh_source(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    source_id: String,
+) -> Result<SourceRefresh, SecondBrainError> {
+    ensure_online(&state).map_err(SecondBrainError::Network)?;
+    refresh_source_from_web(&state, &app, &source_id).await
+}
+
+// Turn auto-refresh on (every `hours` hours) or off (None) for a web source
+#[tauri::command]
+async fn set_source_refresh_interval(
+    state: tauri::State<'_, AppState>,
+    source_id: String,
+    hours: Option<u32>,
+) -> Result<(), SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.set_source_refresh_interval(&source_id, hours).await
+}
+
+/// Fetch a source's URL again and hand the page to the knowledge base
+/// The KB lock isn't held during the crawl, which can take a while on a slow host.
+async fn refresh_source_from_web(
+    state: &AppState,
+    app: &tauri::AppHandle,
+    source_id: &str,
+) -> Result<SourceRefresh, SecondBrainError> {
+    let source = {
+        let kb_guard = state.knowledge_base.read().await;
+        let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+        kb.get_knowledge_source(source_id).await?
+            .ok_or_else(|| SecondBrainError::NotFound(format!("Knowledge source not found: {}", source_id)))?
+    };
+    if !source.is_refreshable() {
+        return Err(SecondBrainError::InvalidInput(format!("{} is not a web page and can't be refreshed", source.url)));
+    }
+
+    let crawled = page_crawler(state).crawl_url(&source.url).await;
+
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+    let crawled = match crawled {
+        Ok(crawled) => crawled,
+        Err(e) => {
+            // Still counts as a check, so auto-refresh doesn't hammer a broken page
+            kb.mark_source_refreshed(source_id).await?;
+            return Err(SecondBrainError::Network(format!("Failed to fetch {}: {}", source.url, e)));
+        }
+    };
+
+    kb.refresh_knowledge_source(
+        source_id,
+        &crawled.title,
+        &crawled.markdown,
+        &|progress| emit_ingestion_progress(app, progress),
     ).await
 }
 
-// Extract text from PDF using pdf-extract
-fn extract_pdf_text(file_path: &str) -> Result<String, String> {
-    let bytes = std::fs::read(file_path)
-        .map_err(|e| format!("Failed to read PDF: {}", e))?;
+/// How often the auto-refresh loop looks for sources whose interval has passed
+const SOURCE_REFRESH_POLL: std::time::Duration = std::time::Duration::from_secs(10 * 60);
+
+/// Start the background loop that re-crawls sources with an auto-refresh interval
+/// Emits `source-refreshed` for each source whose content changed. Skips rounds in offline mode.
+fn start_source_refresh_loop(state: &AppState, app: &tauri::AppHandle) {
+    if state.source_refresh_started.swap(true, std::sync::atomic::Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(SOURCE_REFRESH_POLL).await;
+            let state = app.state::<AppState>();
+            if offline_mode(&state) {
+                continue;
+            }
+
+            let due = {
+                let kb_guard = state.knowledge_base.read().await;
+                let Some(kb) = kb_guard.as_ref() else { continue };
+                match kb.sources_due_for_refresh().await {
+                    Ok(due) => due,
+                    Err(e) => {
+                        eprintln!("[KB Refresh] Failed to list due sources: {}", e);
+                        continue;
+                    }
+                }
+            };
+
+            for source in due {
+                let Some(source_id) = source.id.as_ref().map(|t| t.to_string()) else { continue };
+                match refresh_source_from_web(&state, &app, &source_id).await {
+                    Ok(refresh) if refresh.changed => {
+                        println!("[KB Refresh] {} changed, {} chunks", source.url, refresh.chunk_count);
+                        let _ = app.emit("source-refreshed", &refresh);
+                    }
+                    Ok(_) => {}
+                    Err(e) => eprintln!("[KB Refresh] {}: {}", source.url, e),
+                }
+            }
+        }
+    });
+}
+
+// Extract text from PDF page by page, skipping broken pages and OCRing scanned ones
+async fn extract_pdf_text(
+    state: &AppState,
+    file_path: &str,
+) -> Result<(String, pdf_text::PdfExtractionReport), String> {
+    let bytes = Arc::new(std::fs::read(file_path)
+        .map_err(|e| format!("Failed to read PDF: {}", e))?);
+
+    let parse_bytes = bytes.clone();
+    let mut extraction = tokio::task::spawn_blocking(move || pdf_text::extract_pdf_text(&parse_bytes))
+        .await
+        .map_err(|e| format!("PDF extraction failed: {}", e))??;
+
+    if extraction.needs_ocr() {
+        match loaded_ocr_engine(state).await {
+            Ok(engine) => {
+                extraction = tokio::task::spawn_blocking(move || {
+                    pdf_text::ocr_empty_pages(&bytes, &mut extraction, &engine);
+                    extraction
+                })
+                .await
+                .map_err(|e| format!("PDF OCR failed: {}", e))?;
+            }
+            // Without the OCR models, near-empty pages just stay skipped
+            Err(e) => eprintln!("[Upload] OCR unavailable for scanned pages: {}", e),
+        }
+    }
+
+    let report = extraction.report();
+    let content = extraction.text();
+    if content.trim().is_empty() {
+        return Err(format!(
+            "No text could be extracted from the PDF ({} pages skipped)",
+            report.pages_skipped
+        ));
+    }
 
-    pdf_extract::extract_text_from_mem(&bytes)
-        .map_err(|e| format!("Failed to extract PDF text: {}", e))
+    println!(
+        "[Upload] PDF: {} of {} pages extracted ({} via OCR), {} skipped, {} warnings",
+        report.pages_extracted + report.pages_ocr, report.pages_total,
+        report.pages_ocr, report.pages_skipped, report.warnings.len()
+    );
+    Ok((content, report))
 }
 
 // Get all knowledge sources
@@ -2234,9 +6971,9 @@ fn extract_pdf_text(file_path: &str) -> Result<String, String> {
 async fn get_knowledge_sources(
     state: tauri::State<'_, AppState>,
     tags: Option<Vec<String>>,
-) -> Result<Vec<KnowledgeSource>, String> {
+) -> Result<Vec<KnowledgeSource>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
-    let kb = kb_guard.as_ref().ok_or("Knowledge base not initialized")?;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.get_knowledge_sources(tags).await
 }
@@ -2246,26 +6983,96 @@ async fn get_knowledge_sources(
 async fn delete_knowledge_source(
     state: tauri::State<'_, AppState>,
     source_id: String,
-) -> Result<(), String> {
+) -> Result<(), SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
-    let kb = kb_guard.as_ref().ok_or("Knowledge base not initialized")?;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.delete_knowledge_source(&source_id).await
 }
 
+// Toggle whether a knowledge source is always included in Graph-RAG context
+#[tauri::command]
+async fn toggle_source_pinned(
+    state: tauri::State<'_, AppState>,
+    source_id: String,
+) -> Result<bool, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.toggle_source_pinned(&source_id).await
+}
+
+// List knowledge sources unused (no meeting links, no search hits) for `days` days
+#[tauri::command]
+async fn get_stale_sources(
+    state: tauri::State<'_, AppState>,
+    days: u64,
+) -> Result<Vec<KnowledgeSource>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_stale_sources(days).await
+}
+
+// Delete several knowledge sources at once
+#[tauri::command]
+async fn bulk_delete_sources(
+    state: tauri::State<'_, AppState>,
+    source_ids: Vec<String>,
+) -> Result<BulkDeleteResult, String> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    Ok(kb.bulk_delete_sources(&source_ids).await)
+}
+
 // Update tags for a knowledge source
 #[tauri::command]
 async fn update_source_tags(
     state: tauri::State<'_, AppState>,
     source_id: String,
     tags: Vec<String>,
-) -> Result<(), String> {
+) -> Result<(), SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
-    let kb = kb_guard.as_ref().ok_or("Knowledge base not initialized")?;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.update_source_tags(&source_id, tags).await
 }
 
+// Source and chunk counts per knowledge source tag
+#[tauri::command]
+async fn get_tag_stats(state: tauri::State<'_, AppState>) -> Result<Vec<TagStats>, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_tag_stats().await
+}
+
+// Rename a tag on every knowledge source, returning how many sources changed
+#[tauri::command]
+async fn rename_tag(
+    state: tauri::State<'_, AppState>,
+    old: String,
+    new: String,
+) -> Result<usize, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.rename_tag(&old, &new).await
+}
+
+// Remove a tag from every knowledge source, returning how many sources changed
+#[tauri::command]
+async fn delete_tag(
+    state: tauri::State<'_, AppState>,
+    tag: String,
+) -> Result<usize, SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.delete_tag(&tag).await
+}
+
 // Search knowledge chunks
 #[tauri::command]
 async fn search_knowledge_chunks(
@@ -2273,9 +7080,9 @@ async fn search_knowledge_chunks(
     query: String,
     limit: Option<usize>,
     tags: Option<Vec<String>>,
-) -> Result<Vec<KnowledgeSearchResult>, String> {
+) -> Result<Vec<KnowledgeSearchResult>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
-    let kb = kb_guard.as_ref().ok_or("Knowledge base not initialized")?;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.search_knowledge(&query, limit.unwrap_or(10), tags).await
 }
@@ -2284,9 +7091,9 @@ async fn search_knowledge_chunks(
 #[tauri::command]
 async fn cleanup_orphaned_chunks(
     state: tauri::State<'_, AppState>,
-) -> Result<usize, String> {
+) -> Result<usize, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
-    let kb = kb_guard.as_ref().ok_or("Knowledge base not initialized")?;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
     kb.cleanup_orphaned_chunks().await
 }
@@ -2295,36 +7102,271 @@ async fn cleanup_orphaned_chunks(
 #[tauri::command]
 async fn link_knowledge_to_meeting(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
+    meeting_id: MeetingId,
     source_id: String,
-) -> Result<(), String> {
+) -> Result<(), SecondBrainError> {
+    let kb_guard = state.knowledge_base.read().await;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.link_knowledge_to_meeting(meeting_id.as_str(), &source_id, "user").await
+}
+
+// Auto-link knowledge sources to a meeting by transcript similarity
+#[tauri::command]
+async fn auto_link_knowledge(
+    state: tauri::State<'_, AppState>,
+    meeting_id: MeetingId,
+    threshold: Option<f32>,
+) -> Result<Vec<MeetingKnowledge>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
-    let kb = kb_guard.as_ref().ok_or("Knowledge base not initialized")?;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
 
-    kb.link_knowledge_to_meeting(&meeting_id, &source_id, "user").await
+    kb.auto_link_knowledge(meeting_id.as_str(), threshold.unwrap_or(DEFAULT_AUTO_LINK_THRESHOLD)).await
 }
 
 // Get knowledge sources linked to a meeting
 #[tauri::command]
 async fn get_meeting_knowledge(
     state: tauri::State<'_, AppState>,
-    meeting_id: String,
-) -> Result<Vec<KnowledgeSource>, String> {
+    meeting_id: MeetingId,
+) -> Result<Vec<KnowledgeSource>, SecondBrainError> {
     let kb_guard = state.knowledge_base.read().await;
-    let kb = kb_guard.as_ref().ok_or("Knowledge base not initialized")?;
+    let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+
+    kb.get_meeting_knowledge(meeting_id.as_str()).await
+}
+
+// ==================== Backup Commands ====================
 
-    kb.get_meeting_knowledge(&meeting_id).await
+/// Refuse backup/restore while a meeting could still be writing segments
+fn ensure_no_active_meeting(state: &AppState) -> Result<(), String> {
+    if state.is_recording.load(std::sync::atomic::Ordering::SeqCst) || has_any_session(state) {
+        return Err("Cannot back up or restore while a meeting is in progress or paused. End the meeting first.".to_string());
+    }
+    Ok(())
+}
+
+/// Open the knowledge base, retrying while RocksDB releases the lock from a previous handle
+async fn reopen_knowledge_base(
+    data_dir: &std::path::PathBuf,
+    embedding_engine: Arc<EmbeddingEngine>,
+    entity_engine: Arc<EntityEngine>,
+) -> Result<KnowledgeBase, String> {
+    let mut last_error = String::new();
+    for _ in 0..10 {
+        match KnowledgeBase::new(data_dir, embedding_engine.clone(), entity_engine.clone()).await {
+            Ok(kb) => return Ok(kb),
+            Err(e) => {
+                last_error = e.to_string();
+                tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+// Back up the knowledge base and user store into a single zip
+#[tauri::command]
+async fn backup_knowledge_base(
+    state: tauri::State<'_, AppState>,
+    dest_path: String,
+) -> Result<BackupManifest, String> {
+    ensure_no_active_meeting(&state)?;
+
+    let data_dir = knowledge_base_dir();
+    let snapshot_path = data_dir.join("user_store.backup.db");
+    let has_snapshot = {
+        let store_guard = state.user_store.lock();
+        match store_guard.as_ref() {
+            Some(store) => {
+                store.backup_to(&snapshot_path)?;
+                true
+            }
+            None => false,
+        }
+    };
+
+    // Close SurrealDB so RocksDB flushes everything to disk before copying,
+    // holding the write lock so nothing else touches the KB in the meantime
+    let mut kb_guard = state.knowledge_base.write().await;
+    let was_open = kb_guard.take().is_some();
+    if was_open {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    let result = backup::create_backup(
+        &data_dir.join("knowledge.db"),
+        if has_snapshot { Some(snapshot_path.as_path()) } else { None },
+        std::path::Path::new(&dest_path),
+    );
+
+    if was_open {
+        let embedding_engine = state.embedding_engine.read().clone()
+            .ok_or("Embedding engine not initialized")?;
+        let entity_engine = state.entity_engine.read().clone()
+            .ok_or("Entity engine not initialized")?;
+        *kb_guard = Some(reopen_knowledge_base(&data_dir, embedding_engine, entity_engine).await?);
+    }
+    drop(kb_guard);
+
+    let _ = std::fs::remove_file(&snapshot_path);
+    result
+}
+
+// Validate a backup and stage it for restore; it is applied on the next app start
+#[tauri::command]
+async fn restore_knowledge_base(
+    state: tauri::State<'_, AppState>,
+    src_path: String,
+) -> Result<BackupManifest, String> {
+    ensure_no_active_meeting(&state)?;
+
+    backup::stage_restore(std::path::Path::new(&src_path), &knowledge_base_dir())
+}
+
+// Export the knowledge base to a standalone SQLite file for use in other tools
+// A read-only copy; use backup_knowledge_base for something that can be restored
+#[tauri::command]
+async fn export_to_sqlite(
+    state: tauri::State<'_, AppState>,
+    dest_path: String,
+) -> Result<SqliteExportStats, SecondBrainError> {
+    let snapshot = {
+        let kb_guard = state.knowledge_base.read().await;
+        let kb = kb_guard.as_ref().ok_or_else(|| SecondBrainError::not_initialized("Knowledge base"))?;
+        kb.export_snapshot().await?
+    };
+
+    let stats = tokio::task::spawn_blocking(move || sqlite_export::export_to_path(std::path::Path::new(&dest_path), &snapshot))
+        .await
+        .map_err(|e| SecondBrainError::Other(format!("SQLite export task failed: {}", e)))??;
+    Ok(stats)
+}
+
+// ==================== Profile Commands ====================
+
+// List data profiles, marking the active one
+#[tauri::command]
+fn list_profiles() -> Vec<profiles::ProfileInfo> {
+    profiles::load().list()
+}
+
+// Create a data profile, optionally stored in a custom directory (e.g. an external drive)
+#[tauri::command]
+fn create_profile(name: String, data_dir: Option<String>) -> Result<Vec<profiles::ProfileInfo>, String> {
+    let mut config = profiles::load();
+    let data_dir = data_dir
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+        .map(std::path::PathBuf::from);
+    config.create(name.trim(), data_dir)?;
+    config.save()?;
+    println!("[Profiles] Created profile {}", name.trim());
+    Ok(config.list())
+}
+
+// Delete an inactive data profile and its data
+#[tauri::command]
+fn delete_profile(name: String) -> Result<Vec<profiles::ProfileInfo>, String> {
+    let mut config = profiles::load();
+    let dir = config.remove(&name)?;
+    config.save()?;
+    profiles::delete_profile_data(&dir)?;
+    println!("[Profiles] Deleted profile {} ({:?})", name, dir);
+    Ok(config.list())
+}
+
+// Switch to another data profile, closing and reopening the knowledge base and user store
+// Emits `profile-changed` so the frontend can reload settings and data
+#[tauri::command]
+async fn set_active_profile(
+    state: tauri::State<'_, AppState>,
+    app: tauri::AppHandle,
+    name: String,
+) -> Result<(), String> {
+    // A parked meeting resumed after the switch would write into the new profile's knowledge base
+    if state.is_recording.load(std::sync::atomic::Ordering::SeqCst) || has_any_session(&state) {
+        return Err("Cannot switch profiles while a meeting is in progress or paused. End the meeting first.".to_string());
+    }
+
+    let mut config = profiles::load();
+    if config.active() == name {
+        return Ok(());
+    }
+    config.set_active(&name)?;
+
+    // Open the new profile's stores alongside the current ones; on any failure
+    // nothing has changed and the current profile stays fully usable
+    let store_was_open = state.user_store.lock().is_some();
+    let new_store = if store_was_open {
+        Some(UserStore::new(&config.user_store_dir()?)?)
+    } else {
+        None
+    };
+    let mut kb_guard = state.knowledge_base.write().await;
+    let new_kb = if kb_guard.is_some() {
+        let embedding_engine = state.embedding_engine.read().clone()
+            .ok_or("Embedding engine not initialized")?;
+        let entity_engine = state.entity_engine.read().clone()
+            .ok_or("Entity engine not initialized")?;
+        let data_dir = config.knowledge_base_dir();
+        std::fs::create_dir_all(&data_dir)
+            .map_err(|e| format!("Failed to create profile directory: {}", e))?;
+        Some(reopen_knowledge_base(&data_dir, embedding_engine, entity_engine).await?)
+    } else {
+        None
+    };
+
+    config.save()?;
+
+    if let Some(store) = new_store {
+        *state.user_store.lock() = Some(store);
+    }
+    if let Some(kb) = new_kb {
+        *kb_guard = Some(kb);
+    }
+    drop(kb_guard);
+
+    // The new profile brings its own settings and LLM endpoint
+    if let Err(e) = apply_saved_settings(&state, &app).await {
+        eprintln!("[Profiles] Failed to apply settings for profile {}: {}", name, e);
+    }
+    if let Err(e) = initialize_llm(state.clone(), app.clone(), None, None, None, None, None) {
+        // Don't keep talking to the previous profile's endpoint
+        *state.llm_assistant.write() = None;
+        eprintln!("[Profiles] LLM not configured for profile {}: {}", name, e);
+    }
+
+    println!("[Profiles] Switched to profile {}", name);
+    let _ = app.emit("profile-changed", serde_json::json!({ "name": name }));
+    Ok(())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    let builder = tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
-        .manage(AppState::default())
+        .manage(AppState::default());
+    // Windows opened later (the suggestions overlay) follow the current protection setting
+    #[cfg(any(target_os = "macos", target_os = "windows"))]
+    let builder = builder.on_page_load(|webview, _payload| {
+        if PROTECTED_WINDOWS.contains(&webview.label()) {
+            let enabled = webview.state::<AppState>().screen_share_protection.load(std::sync::atomic::Ordering::SeqCst);
+            let _ = webview.window().set_content_protected(enabled);
+        }
+    });
+    builder
         .setup(|app| {
+            // Swap in a staged backup restore before any database is opened
+            match user_store_dir().and_then(|store_dir| backup::apply_pending_restore(&knowledge_base_dir(), &store_dir)) {
+                Ok(true) => println!("[Startup] Restored knowledge base from backup"),
+                Ok(false) => {}
+                Err(e) => eprintln!("[Startup] Failed to apply staged restore: {}", e),
+            }
+
             // Create tray menu
             let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
             let start = MenuItem::with_id(app, "start", "Start Recording", true, None::<&str>)?;
@@ -2333,14 +7375,12 @@ pub fn run() {
 
             let menu = Menu::with_items(app, &[&show, &start, &stop, &quit])?;
 
-            // Enable screen share protection - window won't appear in screen recordings/shares
-            if let Some(window) = app.get_webview_window("main") {
-                #[cfg(target_os = "macos")]
-                {
-                    let _ = window.set_content_protected(true);
-                    println!("Screen share protection enabled");
-                }
+            // Screen share protection - windows won't appear in screen recordings/shares
+            // Open the user store now so the saved choice applies before the window shows
+            if let Err(e) = initialize_user_store(app.state::<AppState>()) {
+                eprintln!("[Startup] Failed to open user store: {}", e);
             }
+            apply_screen_share_settings(&app.state::<AppState>(), app.handle());
 
             // Register global shortcuts
             use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
@@ -2381,7 +7421,30 @@ pub fn run() {
                 }
             })?;
 
-            println!("Global shortcuts registered: {} (screenshot), {} (toggle recording)", screenshot_shortcut, record_shortcut);
+            // Bookmark shortcut: Cmd+Shift+B (macOS) / Ctrl+Shift+B (Windows)
+            #[cfg(target_os = "macos")]
+            let bookmark_shortcut = "Command+Shift+B";
+            #[cfg(not(target_os = "macos"))]
+            let bookmark_shortcut = "Ctrl+Shift+B";
+
+            let shortcut: Shortcut = bookmark_shortcut.parse().unwrap();
+            let bookmark_app = app_handle.clone();
+
+            app.global_shortcut().on_shortcut(shortcut, move |_app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    println!("[Hotkey] Bookmark shortcut triggered");
+                    let app = bookmark_app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let state = app.state::<AppState>();
+                        if let Err(e) = bookmark_current_moment(&state, &app, None).await {
+                            eprintln!("[Hotkey] Bookmark failed: {}", e);
+                        }
+                    });
+                }
+            })?;
+
+            println!("Global shortcuts registered: {} (screenshot), {} (toggle recording), {} (bookmark)",
+                screenshot_shortcut, record_shortcut, bookmark_shortcut);
 
             // Build tray icon
             let _tray = TrayIconBuilder::new()
@@ -2431,39 +7494,99 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             initialize_asr,
             initialize_smart_turn,
+            initialize_wake_word,
             initialize_entities,
             initialize_embeddings,
+            embed_texts_batch,
+            get_embedding_cache_stats,
+            set_embedding_cache_capacity,
+            get_embedding_status,
+            find_broken_embeddings,
+            run_kb_query,
+            reembed_all,
+            backfill_missing_embeddings,
+            reprocess_entities,
+            reprocess_all_entities,
             initialize_diarization,
             initialize_knowledge_base,
             initialize_llm,
             extract_entities,
             extract_entities_batch,
             start_meeting,
+            resume_meeting,
+            import_audio_file,
             end_meeting,
+            save_recording,
             add_transcript_segment,
+            set_segment_dedup_config,
             search_knowledge,
+            search_meetings,
+            search_within_meeting,
             get_action_items,
             get_decisions,
             // Meeting query commands
             get_meetings,
+            set_meeting_tags,
+            toggle_meeting_favorite,
+            set_meeting_private,
+            preview_retention_purge,
+            get_all_meeting_tags,
             get_meeting,
             get_meeting_segments,
+            get_segment_at_time,
+            get_next_segment,
             get_meeting_action_items,
             get_meeting_decisions,
             get_meeting_topics,
             get_meeting_people,
+            get_meeting_graph,
+            export_graph,
             get_meeting_stats,
+            get_meeting_sentiment,
+            get_analytics,
+            get_meeting_speaker_stats,
+            edit_transcript_segment,
+            rename_meeting_speaker,
             delete_meeting,
+            restore_meeting,
+            purge_meeting,
+            get_trashed_meetings,
             get_all_action_items,
+            export_action_items,
             get_all_decisions,
             get_knowledge_stats,
             update_action_item_status,
+            set_action_item_assignee,
+            set_action_item_deadline,
+            get_unassigned_action_items,
+            reassign_action_item,
             get_current_meeting_id,
+            pause_session,
+            resume_session,
+            list_sessions,
             // LLM commands
+            get_prompt_templates,
+            set_prompt_template,
+            set_active_prompt_template,
+            delete_prompt_template,
+            test_llm_connection,
+            list_llm_models,
+            get_model_context_length,
+            get_usage_stats,
             ask_assistant,
+            ask_assistant_with_sources,
+            ask_about_person,
+            cancel_assistant_request,
+            catch_up_summary,
             summarize_meeting,
+            summarize_meeting_sections,
+            get_meeting_language_breakdown,
+            summarize_segment_range,
             suggest_questions,
             ask_meeting_question,
+            link_meetings,
+            compare_meetings,
+            get_related_meetings,
             get_realtime_suggestions,
             clear_recent_transcripts,
             set_meeting_context,
@@ -2472,29 +7595,59 @@ pub fn run() {
             start_recording,
             stop_recording,
             is_recording,
+            get_recording_state,
+            pause_recording,
+            resume_recording,
+            cancel_auto_stop,
             subscribe_transcription,
             unsubscribe_transcription,
+            subscribe_progress,
+            unsubscribe_progress,
             set_screen_share_protection,
             check_models_status,
             are_models_ready,
             download_models,
+            download_wake_word_model,
+            download_ocr_models,
             get_models_path,
+            get_models_disk_usage,
+            unload_model,
+            delete_model,
             // Audio & diarization diagnostics
             get_audio_capabilities,
+            get_network_status,
+            get_audio_chunk_configs,
+            set_audio_chunk_config,
+            calibrate_audio_levels,
             get_diarization_status,
+            get_system_diagnostics,
+            set_diarization_speaker_bounds,
             // Screenshot commands
             take_screenshot,
             analyze_screenshot,
+            extract_text_from_screenshot,
             // User store commands
             initialize_user_store,
             get_user_settings,
             update_user_settings,
             set_user_setting,
+            get_entity_config,
+            get_relation_config,
+            set_relation_config,
+            set_entity_labels,
+            redact_meeting,
+            export_meeting_markdown,
+            add_bookmark,
+            get_meeting_bookmarks,
             create_note,
             get_notes,
             update_note,
             toggle_note_pin,
             delete_note,
+            search_notes,
+            link_note_to_meeting,
+            unlink_note_from_meeting,
+            get_notes_for_meeting,
             get_integrations,
             upsert_integration,
             disconnect_integration,
@@ -2505,24 +7658,232 @@ pub fn run() {
             set_app_state,
             // Web crawler commands
             search_web,
+            test_search_backend,
             crawl_url,
             crawl_and_store,
             upload_document,
+            reindex_source,
+            refresh_source,
+            set_source_refresh_interval,
             get_knowledge_sources,
             delete_knowledge_source,
+            get_stale_sources,
+            bulk_delete_sources,
+            toggle_source_pinned,
             update_source_tags,
+            get_tag_stats,
+            rename_tag,
+            delete_tag,
             search_knowledge_chunks,
             cleanup_orphaned_chunks,
             link_knowledge_to_meeting,
+            auto_link_knowledge,
             get_meeting_knowledge,
+            // Backup commands
+            backup_knowledge_base,
+            restore_knowledge_base,
+            export_to_sqlite,
+            list_profiles,
+            create_profile,
+            delete_profile,
+            set_active_profile,
             // Agent queue commands
             initialize_agent_queue,
             get_queue_stats,
             queue_ask_question,
             queue_realtime_suggestions,
             queue_meeting_highlights,
-            queue_entity_extraction
+            queue_entity_extraction,
+            shutdown_agent_queue
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                let state = app.state::<AppState>();
+                tauri::async_runtime::block_on(async {
+                    if let Err(e) = drain_agent_queue(&state).await {
+                        eprintln!("[AgentQueue] Shutdown failed: {}", e);
+                    }
+                    // Dropping the knowledge base closes RocksDB and flushes its WAL
+                    *state.knowledge_base.write().await = None;
+                });
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60_000;
+
+    #[test]
+    fn test_duration_watch_stops_at_the_limit() {
+        let mut watch = DurationWatch::new(Some(10 * MINUTE), 0, 0);
+        assert!(!watch.exceeded(false, 9 * MINUTE));
+        assert!(watch.exceeded(false, 10 * MINUTE));
+    }
+
+    #[test]
+    fn test_duration_watch_without_limit_never_stops() {
+        let mut watch = DurationWatch::new(None, 0, 0);
+        assert!(!watch.exceeded(false, u64::MAX));
+    }
+
+    #[test]
+    fn test_duration_watch_skips_paused_time() {
+        let mut watch = DurationWatch::new(Some(10 * MINUTE), 0, 0);
+        assert!(!watch.exceeded(false, 4 * MINUTE));
+        // Paused for 30 minutes: still 4 minutes of active time throughout
+        assert!(!watch.exceeded(true, 4 * MINUTE));
+        assert!(!watch.exceeded(true, 34 * MINUTE));
+        // Resumed: 6 more active minutes reach the limit
+        assert!(!watch.exceeded(false, 35 * MINUTE));
+        assert!(!watch.exceeded(false, 39 * MINUTE));
+        assert!(watch.exceeded(false, 40 * MINUTE));
+    }
+
+    #[test]
+    fn test_duration_watch_counts_a_resumed_meetings_earlier_time() {
+        let mut watch = DurationWatch::new(Some(10 * MINUTE), 0, 8 * MINUTE);
+        assert!(!watch.exceeded(false, MINUTE));
+        assert!(watch.exceeded(false, 2 * MINUTE));
+    }
+
+    fn pending(speaker: &str, text: &str, start_ms: u64, end_ms: u64) -> PendingSegment {
+        PendingSegment {
+            meeting_id: "weekly".to_string(),
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            start_ms,
+            end_ms,
+            word_timings: vec![(text.to_string(), 0, end_ms - start_ms)],
+            language: None,
+            emotion: None,
+        }
+    }
+
+    fn merging_batch() -> SegmentBatch {
+        SegmentBatch { merge_turns: true, ..Default::default() }
+    }
+
+    #[test]
+    fn test_push_turn_queues_chunks_as_is_without_merging() {
+        let mut batch = SegmentBatch::default();
+        batch.push_turn(pending("Ana", "Hello", 0, 1_000), false);
+        batch.push_turn(pending("Ana", "there", 1_000, 2_000), false);
+        assert_eq!(batch.take().len(), 2);
+    }
+
+    #[test]
+    fn test_push_turn_merges_a_speakers_chunks_until_the_turn_completes() {
+        let mut batch = merging_batch();
+        batch.push_turn(pending("Ana", "Hello", 0, 1_000), false);
+        batch.push_turn(pending("Ben", "Hi", 1_000, 1_500), false);
+        batch.push_turn(pending("Ana", "there", 1_500, 2_000), true);
+
+        let queued = batch.take();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].text, "Hello there");
+        assert_eq!((queued[0].start_ms, queued[0].end_ms), (0, 2_000));
+        // The second chunk's word timings move to the turn's clock
+        assert_eq!(queued[0].word_timings[1], ("there".to_string(), 1_500, 2_000));
+
+        // Ben's turn is still open until it completes or goes stale
+        batch.close_turns();
+        assert_eq!(batch.take().into_iter().map(|s| s.text).collect::<Vec<_>>(), vec!["Hi".to_string()]);
+    }
+
+    #[test]
+    fn test_close_stale_turns_queues_quiet_and_long_turns() {
+        let mut batch = merging_batch();
+        batch.push_turn(pending("Ana", "Still talking", 0, 1_000), false);
+        batch.push_turn(pending("Ben", "Done", 0, 500), false);
+
+        // Ben has been quiet for TURN_MERGE_IDLE_MS; Ana spoke more recently
+        batch.close_stale_turns(500 + TURN_MERGE_IDLE_MS);
+        assert_eq!(batch.take().into_iter().map(|s| s.text).collect::<Vec<_>>(), vec!["Done".to_string()]);
+
+        // A turn that keeps going is cut at TURN_MERGE_MAX_MS
+        for start in (1_000..TURN_MERGE_MAX_MS).step_by(1_000) {
+            batch.push_turn(pending("Ana", "more", start, start + 1_000), false);
+        }
+        assert!(batch.take().is_empty());
+        batch.close_stale_turns(TURN_MERGE_MAX_MS);
+        let queued = batch.take();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(queued[0].end_ms, TURN_MERGE_MAX_MS);
+    }
+
+    fn after(start: std::time::Instant, ms: u64) -> std::time::Instant {
+        start + std::time::Duration::from_millis(ms)
+    }
+
+    #[test]
+    fn test_mute_watch_flags_a_silent_mic_while_system_audio_plays() {
+        let start = std::time::Instant::now();
+        let mut watch = MuteWatch::new();
+        assert!(watch.observe(&AudioSource::SystemAudio, 0.1, start).is_none());
+        assert!(watch.observe(&AudioSource::Microphone, 0.0, start).is_none());
+        assert!(watch.observe(&AudioSource::SystemAudio, 0.1, after(start, MIC_MUTE_AFTER_MS)).is_none());
+        assert!(matches!(watch.observe(&AudioSource::Microphone, 0.0, after(start, MIC_MUTE_AFTER_MS)), Some(MuteEvent::Muted)));
+        // Reported once, then again only after the mic comes back
+        assert!(watch.observe(&AudioSource::Microphone, 0.0, after(start, MIC_MUTE_AFTER_MS + 100)).is_none());
+        assert!(matches!(watch.observe(&AudioSource::Microphone, 0.05, after(start, MIC_MUTE_AFTER_MS + 200)), Some(MuteEvent::Unmuted)));
+        assert!(watch.observe(&AudioSource::Microphone, 0.05, after(start, MIC_MUTE_AFTER_MS + 300)).is_none());
+    }
+
+    #[test]
+    fn test_mute_watch_ignores_silence_on_both_sides() {
+        let start = std::time::Instant::now();
+        let mut watch = MuteWatch::new();
+        assert!(watch.observe(&AudioSource::SystemAudio, 0.0, start).is_none());
+        assert!(watch.observe(&AudioSource::Microphone, 0.0, start).is_none());
+        assert!(watch.observe(&AudioSource::Microphone, 0.0, after(start, 10 * MIC_MUTE_AFTER_MS)).is_none());
+    }
+
+    #[test]
+    fn test_mute_watch_waits_for_a_long_enough_silence() {
+        let start = std::time::Instant::now();
+        let mut watch = MuteWatch::new();
+        assert!(watch.observe(&AudioSource::SystemAudio, 0.1, start).is_none());
+        assert!(watch.observe(&AudioSource::Microphone, 0.0, start).is_none());
+        // A word on the mic restarts the silence
+        assert!(watch.observe(&AudioSource::Microphone, 0.05, after(start, 2_000)).is_none());
+        assert!(watch.observe(&AudioSource::SystemAudio, 0.1, after(start, 2_500)).is_none());
+        assert!(watch.observe(&AudioSource::Microphone, 0.0, after(start, 2_500)).is_none());
+        assert!(watch.observe(&AudioSource::Microphone, 0.0, after(start, MIC_MUTE_AFTER_MS)).is_none());
+    }
+
+    #[test]
+    fn test_max_recording_minutes_is_range_checked() {
+        assert!(validate_max_recording_minutes(0).is_ok());
+        assert!(validate_max_recording_minutes(MAX_RECORDING_MINUTES_LIMIT).is_ok());
+        assert!(validate_max_recording_minutes(MAX_RECORDING_MINUTES_LIMIT + 1).is_err());
+        assert!(validate_max_recording_minutes(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_auto_stop_silence_minutes_is_range_checked() {
+        assert!(validate_auto_stop_silence_minutes(0).is_err());
+        assert!(validate_auto_stop_silence_minutes(1).is_ok());
+        assert!(validate_auto_stop_silence_minutes(AUTO_STOP_SILENCE_MINUTES_LIMIT).is_ok());
+        assert!(validate_auto_stop_silence_minutes(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_retention_days_is_range_checked() {
+        assert!(validate_retention_days(0).is_ok());
+        assert!(validate_retention_days(RETENTION_DAYS_LIMIT).is_ok());
+        assert!(validate_retention_days(RETENTION_DAYS_LIMIT + 1).is_err());
+        assert!(validate_retention_days(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_suggestion_min_interval_secs_is_range_checked() {
+        assert!(validate_suggestion_min_interval_secs(0).is_ok());
+        assert!(validate_suggestion_min_interval_secs(SUGGESTION_MIN_INTERVAL_SECS_LIMIT).is_ok());
+        assert!(validate_suggestion_min_interval_secs(u64::MAX).is_err());
+    }
 }
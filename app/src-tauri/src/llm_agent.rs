@@ -1,9 +1,11 @@
-use crate::knowledge_base::KnowledgeBase;
+use crate::error::SecondBrainError;
+use crate::knowledge_base::{KnowledgeBase, PersonDossier, RetrievalConfig, normalize_record_id};
+use crate::usage::{LlmUsage, TokenUsage};
 use crate::web_crawler::WebCrawler;
 use rig::{
-    completion::{AssistantContent, CompletionModel, Prompt, ToolDefinition},
-    providers::openai,
-    tool::Tool,
+    completion::{AssistantContent, CompletionError, CompletionModel, ToolDefinition},
+    providers::{anthropic, openai},
+    tool::{Tool, ToolSet},
 };
 
 /// Extract text from AssistantContent and strip thinking tags
@@ -19,35 +21,164 @@ fn extract_text(content: &AssistantContent) -> String {
 }
 
 /// Extract JSON object from a response that might contain other text
+///
+/// Strips reasoning tags and markdown code fences, then returns the first
+/// balanced `{...}` (or everything from the first `{` if the object was cut off).
 fn extract_json_from_response(response: &str) -> String {
-    // First strip thinking tags
-    let cleaned = strip_thinking_tags(response);
+    // First strip thinking tags and ```json fences
+    let cleaned = strip_code_fences(&strip_thinking_tags(response));
 
     // Try to find JSON object in the response
     if let Some(start) = cleaned.find('{') {
-        // Find matching closing brace
+        // Find matching closing brace, ignoring braces inside strings
         let mut depth = 0;
-        let mut end = start;
+        let mut in_string = false;
+        let mut escaped = false;
         for (i, c) in cleaned[start..].char_indices() {
+            if in_string {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
             match c {
+                '"' => in_string = true,
                 '{' => depth += 1,
                 '}' => {
                     depth -= 1;
                     if depth == 0 {
-                        end = start + i + 1;
-                        break;
+                        return cleaned[start..start + i + 1].to_string();
                     }
                 }
                 _ => {}
             }
         }
-        if end > start {
-            return cleaned[start..end].to_string();
-        }
+        // Unbalanced: the response was truncated, leave it to parse_lenient_json to repair
+        return cleaned[start..].to_string();
     }
     cleaned
 }
 
+/// Return the contents of the first markdown code block, or the text unchanged if there is none
+fn strip_code_fences(text: &str) -> String {
+    let Some(open) = text.find("```") else {
+        return text.to_string();
+    };
+    // Skip the language tag on the opening fence line
+    let body_start = text[open..].find('\n').map(|i| open + i + 1).unwrap_or(text.len());
+    let body = &text[body_start..];
+    match body.find("```") {
+        Some(close) => body[..close].trim().to_string(),
+        None => body.trim().to_string(),
+    }
+}
+
+/// Parse JSON from an LLM, repairing trailing commas, raw newlines in strings and truncation
+fn parse_lenient_json<T: serde::de::DeserializeOwned>(json_str: &str) -> Result<T, serde_json::Error> {
+    match serde_json::from_str(json_str) {
+        Ok(value) => Ok(value),
+        Err(e) => {
+            let repaired = repair_json(json_str).ok_or(e)?;
+            serde_json::from_str(&repaired)
+        }
+    }
+}
+
+/// Best-effort repair of almost-JSON; returns None if no candidate parses
+fn repair_json(text: &str) -> Option<String> {
+    let mut out = String::with_capacity(text.len());
+    let mut closers: Vec<char> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    // Output length and open containers before the most recent top-level-safe comma
+    let mut last_comma: Option<(usize, Vec<char>)> = None;
+
+    for c in text.trim().chars() {
+        if in_string {
+            match c {
+                _ if escaped => {
+                    escaped = false;
+                    out.push(c);
+                }
+                '\\' => {
+                    escaped = true;
+                    out.push(c);
+                }
+                '"' => {
+                    in_string = false;
+                    out.push(c);
+                }
+                '\n' => out.push_str("\\n"),
+                '\r' => {}
+                '\t' => out.push_str("\\t"),
+                _ => out.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            '{' => {
+                closers.push('}');
+                out.push(c);
+            }
+            '[' => {
+                closers.push(']');
+                out.push(c);
+            }
+            '}' | ']' => {
+                trim_trailing_comma(&mut out);
+                closers.pop();
+                out.push(c);
+            }
+            ',' => {
+                last_comma = Some((out.len(), closers.clone()));
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+
+    let parses = |candidate: &str| serde_json::from_str::<serde_json::Value>(candidate).is_ok();
+    let close = |mut candidate: String, closers: &[char]| {
+        trim_trailing_comma(&mut candidate);
+        candidate.extend(closers.iter().rev());
+        candidate
+    };
+
+    // Close whatever was left open where the text stopped
+    let mut tail = out.clone();
+    if in_string {
+        if escaped {
+            tail.pop();
+        }
+        tail.push('"');
+    }
+    let candidate = close(tail, &closers);
+    if parses(&candidate) {
+        return Some(candidate);
+    }
+
+    // Otherwise drop the incomplete last member and close at the previous comma
+    let (len, closers) = last_comma?;
+    out.truncate(len);
+    let candidate = close(out, &closers);
+    parses(&candidate).then_some(candidate)
+}
+
+fn trim_trailing_comma(text: &mut String) {
+    let trimmed_len = text.trim_end().len();
+    text.truncate(trimmed_len);
+    if text.ends_with(',') {
+        text.pop();
+    }
+}
+
 /// Strip <think>...</think> and similar reasoning tags from LLM responses
 /// Some models (like Qwen, DeepSeek) output thinking process in these tags
 fn strip_thinking_tags(response: &str) -> String {
@@ -98,8 +229,29 @@ fn strip_thinking_tags(response: &str) -> String {
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
+use tokio_util::sync::CancellationToken;
+
+/// Error returned by requests aborted through their cancellation token
+pub const REQUEST_CANCELLED: &str = "Request cancelled";
+
+/// Race an LLM request against a cancellation token
+/// Dropping the request future aborts any in-flight HTTP call at its next await point.
+pub async fn run_cancellable<T, E, F>(token: &CancellationToken, request: F) -> Result<T, E>
+where
+    F: Future<Output = Result<T, E>>,
+    E: From<&'static str>,
+{
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(E::from(REQUEST_CANCELLED)),
+        result = request => result,
+    }
+}
 
 /// Custom error type for tool operations
 #[derive(Debug)]
@@ -125,6 +277,12 @@ impl From<&str> for ToolError {
     }
 }
 
+impl From<SecondBrainError> for ToolError {
+    fn from(e: SecondBrainError) -> Self {
+        ToolError(e.to_string())
+    }
+}
+
 /// Real-time suggestion generated during a meeting
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RealtimeSuggestion {
@@ -136,6 +294,42 @@ pub struct RealtimeSuggestion {
     pub related_info: Option<String>,
 }
 
+/// When realtime suggestions are requested during a recording (the `suggestion*` settings)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SuggestionCadence {
+    pub enabled: bool,
+    /// Suggest on every nth final transcript (0 = never by count)
+    pub every_n: u64,
+    /// Suggest whenever a speaker finishes a turn
+    pub on_turn_complete: bool,
+    /// Minimum gap between suggestion requests
+    pub min_interval_ms: u64,
+}
+
+impl Default for SuggestionCadence {
+    fn default() -> Self {
+        Self { enabled: true, every_n: 3, on_turn_complete: true, min_interval_ms: 0 }
+    }
+}
+
+impl SuggestionCadence {
+    /// Whether the `seq`th final transcript of a recording (1-based) should request a suggestion
+    ///
+    /// The first transcript always does, for instant feedback; `since_last_ms` is the time
+    /// since the previous request, if there was one.
+    pub fn should_suggest(&self, seq: u64, turn_complete: bool, since_last_ms: Option<u64>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        if since_last_ms.is_some_and(|ms| ms < self.min_interval_ms) {
+            return false;
+        }
+        seq == 1
+            || (self.on_turn_complete && turn_complete)
+            || (self.every_n > 0 && seq % self.every_n == 0)
+    }
+}
+
 /// Action item extracted from meeting
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ExtractedActionItem {
@@ -144,8 +338,68 @@ pub struct ExtractedActionItem {
     pub deadline: Option<String>,
 }
 
+/// A meeting segment or knowledge chunk that was included in an answer's prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Source {
+    pub kind: String,               // "meeting" or "document"
+    pub id: String,                 // Meeting id or knowledge source id
+    pub title: String,
+    pub url: Option<String>,        // Knowledge source URL (documents only)
+    pub segment_id: Option<String>, // Transcript segment or knowledge chunk id
+    pub start_ms: Option<u64>,      // Segment start within the meeting
+    pub excerpt: String,            // Text as it appeared in the prompt
+    pub similarity: Option<f32>,
+}
+
+impl Source {
+    fn meeting(meeting: &crate::knowledge_base::Meeting, segment: Option<&crate::knowledge_base::TranscriptSegment>, excerpt: String) -> Self {
+        Self {
+            kind: "meeting".to_string(),
+            id: meeting.id.as_ref()
+                .map(|t| normalize_record_id("meeting", &t.to_string()))
+                .unwrap_or_default(),
+            title: meeting.title.clone(),
+            url: None,
+            segment_id: segment.and_then(|s| s.id.as_ref()).map(|t| t.to_string()),
+            start_ms: segment.map(|s| s.start_ms),
+            excerpt,
+            similarity: None,
+        }
+    }
+
+    fn document(result: &crate::knowledge_base::KnowledgeSearchResult, excerpt: String) -> Self {
+        Self {
+            kind: "document".to_string(),
+            id: result.chunk.source_id.clone(),
+            title: result.source_title.clone(),
+            url: (!result.source_url.is_empty()).then(|| result.source_url.clone()),
+            segment_id: result.chunk.id.as_ref().map(|t| t.to_string()),
+            start_ms: None,
+            excerpt,
+            similarity: Some(result.similarity),
+        }
+    }
+}
+
+/// Assistant answer plus the sources its prompt was built from
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnswerWithSources {
+    pub answer: String,
+    pub sources: Vec<Source>,
+}
+
+/// A meeting summary plus, for transcripts too long for one prompt, the summary of each
+/// part it was built from (in transcript order; empty when summarized in one pass)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeetingSummary {
+    pub summary: String,
+    pub sections: Vec<String>,
+}
+
 /// Highlights and structured data extracted from meeting after it ends
+/// Missing fields default to empty so partial model output still parses.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MeetingHighlights {
     /// 2-3 sentence summary
     pub summary: Option<String>,
@@ -161,6 +415,90 @@ pub struct MeetingHighlights {
     pub follow_ups: Vec<String>,
 }
 
+/// One entry in a catch-up briefing section
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CatchUpItem {
+    pub text: String,
+    #[serde(default)]
+    pub meeting_title: Option<String>,
+    /// Assignee/deadline for actions, why it matters for discussions
+    #[serde(default)]
+    pub detail: Option<String>,
+}
+
+/// Digest of everything that happened since a given time, split into sections
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CatchUpSummary {
+    #[serde(default)]
+    pub since: u64,
+    #[serde(default)]
+    pub meeting_count: usize,
+    /// Short prioritized briefing; None when the LLM could not be used
+    #[serde(default)]
+    pub overview: Option<String>,
+    #[serde(default)]
+    pub new_decisions: Vec<CatchUpItem>,
+    #[serde(default)]
+    pub pending_actions: Vec<CatchUpItem>,
+    #[serde(default)]
+    pub notable_discussions: Vec<CatchUpItem>,
+}
+
+/// One side of a meeting comparison: what was said and what came out of it
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeetingDigest {
+    pub meeting_id: String,
+    pub title: String,
+    pub start_time: u64,
+    pub summary: Option<String>,
+    pub decisions: Vec<String>,
+    /// "task (assignee, status)" lines
+    pub action_items: Vec<String>,
+    pub key_topics: Vec<String>,
+    /// "Speaker: text" lines
+    pub transcript: Vec<String>,
+}
+
+/// What changed from an earlier meeting to a later one on the same topic
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeetingDiff {
+    pub earlier_meeting_id: String,
+    pub later_meeting_id: String,
+    /// 1-3 sentence overview of what's new
+    pub overview: Option<String>,
+    pub new_decisions: Vec<String>,
+    /// Commitments whose owner, deadline or scope changed
+    pub changed_commitments: Vec<String>,
+    /// Earlier open items that were closed or reported done
+    pub resolved_items: Vec<String>,
+    /// Earlier open items still unresolved
+    pub still_open_items: Vec<String>,
+    pub new_people: Vec<String>,
+    pub new_topics: Vec<String>,
+}
+
+/// Transcript characters sent per meeting in `compare_meetings`
+const COMPARE_TRANSCRIPT_CHARS: usize = 12_000;
+
+impl CatchUpSummary {
+    pub fn is_empty(&self) -> bool {
+        self.meeting_count == 0
+            && self.new_decisions.is_empty()
+            && self.pending_actions.is_empty()
+            && self.notable_discussions.is_empty()
+    }
+}
+
+/// Whether an action item belongs to the user: unassigned, "you"/"me", or one of their names
+pub fn is_assigned_to_me(assignee: Option<&str>, my_names: &[String]) -> bool {
+    let Some(assignee) = assignee.map(|a| a.trim().to_lowercase()).filter(|a| !a.is_empty() && a != "null") else {
+        return true;
+    };
+    ["you", "me", "myself"].contains(&assignee.as_str())
+        || my_names.iter().any(|n| n.trim().to_lowercase() == assignee)
+}
+
 /// Tool arguments for searching transcripts
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct SearchTranscriptsArgs {
@@ -214,7 +552,7 @@ impl Tool for SearchTranscriptsTool {
         let kb_guard = self.kb.read().await;
         let kb = kb_guard.as_ref().ok_or(ToolError::from("Knowledge base not initialized"))?;
 
-        let results = kb.search_similar(&args.query, args.limit).await.map_err(ToolError::from)?;
+        let results = kb.search_similar(&args.query, args.limit, None).await.map_err(ToolError::from)?;
 
         if results.is_empty() {
             return Ok("No relevant meeting segments found.".to_string());
@@ -553,162 +891,1254 @@ impl Tool for SearchKnowledgeTool {
     }
 }
 
-/// The LLM-powered meeting assistant
-#[derive(Clone)]
-pub struct MeetingAssistant {
-    client: openai::Client,
-    model: String,
+/// LLM provider backing the assistant
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LlmProvider {
+    /// Any OpenAI-compatible endpoint (OpenAI, LM Studio, vLLM, ...)
+    #[default]
+    OpenAI,
+    /// Native Ollama API (/api/chat, /api/tags)
+    Ollama,
+    /// Anthropic Messages API
+    Anthropic,
 }
 
-impl MeetingAssistant {
-    /// Create a new meeting assistant
-    ///
-    /// # Arguments
-    /// * `api_url` - The OpenAI-compatible API URL (e.g., "https://lmstudio.subh-dev.xyz/llm/v1")
-    /// * `model` - The model name (e.g., "openai/gpt-oss-20b")
-    /// * `api_key` - The API key (can be empty for local servers like LM Studio/Ollama)
-    pub fn new(api_url: &str, model: &str, api_key: &str) -> Self {
-        // from_url signature is (api_key, base_url)
-        // Use provided key or fallback to dummy for local servers
-        let key = if api_key.trim().is_empty() { "not-needed" } else { api_key };
-        let client = openai::Client::from_url(key, api_url);
+impl LlmProvider {
+    /// Parse a provider name from settings; unknown or empty values fall back to OpenAI
+    pub fn parse(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "ollama" => Self::Ollama,
+            "anthropic" | "claude" => Self::Anthropic,
+            _ => Self::OpenAI,
+        }
+    }
 
-        Self {
-            client,
-            model: model.to_string(),
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OpenAI => "openai",
+            Self::Ollama => "ollama",
+            Self::Anthropic => "anthropic",
         }
     }
 
-    /// Ask a question using Graph-RAG (Graph + Retrieval Augmented Generation)
-    /// Combines entity extraction, graph traversal, temporal awareness, and vector search
-    pub async fn ask(
-        &self,
-        question: &str,
-        kb: Arc<RwLock<Option<KnowledgeBase>>>,
-    ) -> Result<String, String> {
-        println!("[Graph-RAG] Asking question: {}", question);
+    /// Endpoint used when the user hasn't configured one
+    pub fn default_url(&self) -> Option<&'static str> {
+        match self {
+            Self::OpenAI => None,
+            Self::Ollama => Some("http://localhost:11434"),
+            Self::Anthropic => Some("https://api.anthropic.com"),
+        }
+    }
+}
 
-        // Step 1: Use Graph-RAG to get comprehensive context
-        let context = {
-            let kb_guard = kb.read().await;
-            if let Some(kb_ref) = kb_guard.as_ref() {
-                println!("[Graph-RAG] Knowledge base found, running Graph-RAG query...");
+/// Kind of completion a call makes; each has its own generation params
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmTask {
+    /// Free-form answers to user questions (ask, ask_about_*, screenshots)
+    Answer,
+    /// Meeting summaries
+    Summary,
+    /// Structured JSON extraction at meeting end
+    Extraction,
+    /// Realtime suggestions during a meeting
+    Suggestions,
+    /// Commitment detection on short transcript windows
+    Commitments,
+    /// Catch-up digests
+    CatchUp,
+    /// Brainstormed follow-up questions
+    Questions,
+}
 
-                match kb_ref.graph_rag_query(question, 5).await {
-                    Ok(graph_context) => {
-                        // Build rich context from Graph-RAG results
-                        let mut context_parts = Vec::new();
+impl LlmTask {
+    pub const ALL: [LlmTask; 7] = [
+        LlmTask::Answer,
+        LlmTask::Summary,
+        LlmTask::Extraction,
+        LlmTask::Suggestions,
+        LlmTask::Commitments,
+        LlmTask::CatchUp,
+        LlmTask::Questions,
+    ];
+
+    /// Built-in params: low temperature for JSON output, higher for brainstorming
+    pub fn default_params(&self) -> GenerationParams {
+        let params = |temperature: f64, max_tokens: Option<u64>| GenerationParams {
+            temperature: Some(temperature),
+            max_tokens,
+            top_p: None,
+        };
+        match self {
+            Self::Answer => params(0.4, None),
+            Self::Summary => params(0.3, None),
+            Self::Extraction => params(0.1, None),
+            Self::Suggestions => params(0.3, None),
+            Self::Commitments => params(0.1, Some(400)),
+            Self::CatchUp => params(0.3, Some(1500)),
+            Self::Questions => params(0.8, None),
+        }
+    }
 
-                        // Add temporal context if detected
-                        if let Some(ref temporal) = graph_context.temporal_context {
-                            context_parts.push(format!(
-                                "## Temporal Reference Detected\nTime reference: {}\n",
-                                temporal.time_reference
-                            ));
-                        }
+    /// What `{question}` becomes in a prompt template for calls without a user question
+    pub fn instruction(&self) -> &'static str {
+        match self {
+            Self::Answer => "Answer the user's question",
+            Self::Summary => "Summarize the meeting",
+            Self::Extraction => "Extract the requested information as JSON",
+            Self::Suggestions => "Suggest what to say or look up next",
+            Self::Commitments => "Detect commitments made in the conversation",
+            Self::CatchUp => "Brief the user on what they missed",
+            Self::Questions => "Suggest questions to ask",
+        }
+    }
 
-                        // Add extracted query entities
-                        if !graph_context.query_entities.is_empty() {
-                            let entities_str: Vec<String> = graph_context.query_entities
-                                .iter()
-                                .map(|e| format!("{} ({})", e.text, e.label))
-                                .collect();
-                            context_parts.push(format!(
-                                "## Entities Mentioned in Query\n{}\n",
-                                entities_str.join(", ")
-                            ));
-                        }
+    /// Name used for the task in settings and usage stats
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Answer => "answer",
+            Self::Summary => "summary",
+            Self::Extraction => "extraction",
+            Self::Suggestions => "suggestions",
+            Self::Commitments => "commitments",
+            Self::CatchUp => "catch_up",
+            Self::Questions => "questions",
+        }
+    }
+}
 
-                        // Add related meetings
-                        if !graph_context.related_meetings.is_empty() {
-                            let meetings_str: Vec<String> = graph_context.related_meetings
-                                .iter()
-                                .take(3)
-                                .map(|m| {
-                                    let segments_preview: Vec<String> = m.relevant_segments
-                                        .iter()
-                                        .take(2)
-                                        .map(|s| format!("  - {}: \"{}...\"", s.speaker, &s.text[..s.text.len().min(100)]))
-                                        .collect();
-                                    format!(
-                                        "**{}** ({} days ago)\n{}",
-                                        m.meeting.title,
-                                        m.days_ago,
-                                        segments_preview.join("\n")
-                                    )
-                                })
-                                .collect();
-                            context_parts.push(format!(
-                                "## Related Meetings\n{}\n",
-                                meetings_str.join("\n\n")
-                            ));
-                        }
+/// Sampling params for one completion; None leaves the server default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GenerationParams {
+    #[serde(default)]
+    pub temperature: Option<f64>,
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+    #[serde(default)]
+    pub top_p: Option<f64>,
+}
 
-                        // Add related people with their topics
-                        if !graph_context.related_people.is_empty() {
-                            let people_str: Vec<String> = graph_context.related_people
-                                .iter()
-                                .map(|p| {
-                                    let topics = if p.recent_topics.is_empty() {
-                                        "No topics recorded".to_string()
-                                    } else {
-                                        p.recent_topics.join(", ")
-                                    };
-                                    format!("- **{}** (last seen {} days ago): discusses {}", p.name, p.last_seen_days_ago, topics)
-                                })
-                                .collect();
-                            context_parts.push(format!(
-                                "## Related People\n{}\n",
-                                people_str.join("\n")
-                            ));
-                        }
+impl GenerationParams {
+    /// Use this value's fields where set, falling back to `base`
+    pub fn or(self, base: GenerationParams) -> GenerationParams {
+        GenerationParams {
+            temperature: self.temperature.or(base.temperature),
+            max_tokens: self.max_tokens.or(base.max_tokens),
+            top_p: self.top_p.or(base.top_p),
+        }
+    }
 
-                        // Add related topics
-                        if !graph_context.related_topics.is_empty() {
-                            let topics_str: Vec<String> = graph_context.related_topics
-                                .iter()
-                                .map(|t| {
-                                    let people = if t.related_people.is_empty() {
-                                        "various participants".to_string()
-                                    } else {
-                                        t.related_people.join(", ")
-                                    };
-                                    format!("- **{}**: mentioned {} times, last {} days ago (discussed by: {})",
-                                        t.name, t.mention_count, t.last_mentioned_days_ago, people)
-                                })
-                                .collect();
-                            context_parts.push(format!(
-                                "## Related Topics\n{}\n",
-                                topics_str.join("\n")
-                            ));
-                        }
+    fn validate(&self, task: LlmTask) -> Result<(), String> {
+        if let Some(t) = self.temperature {
+            if !(0.0..=2.0).contains(&t) {
+                return Err(format!("Invalid temperature {} for {:?} (expected 0-2)", t, task));
+            }
+        }
+        if let Some(p) = self.top_p {
+            if p <= 0.0 || p > 1.0 {
+                return Err(format!("Invalid top_p {} for {:?} (expected 0-1)", p, task));
+            }
+        }
+        if self.max_tokens == Some(0) {
+            return Err(format!("Invalid max_tokens 0 for {:?}", task));
+        }
+        Ok(())
+    }
+}
 
-                        // Add open action items (if relevant)
-                        if !graph_context.open_actions.is_empty() {
-                            let actions_str: Vec<String> = graph_context.open_actions
-                                .iter()
-                                .take(5)
-                                .map(|a| {
-                                    let assignee = a.assignee.as_deref().unwrap_or("Unassigned");
-                                    format!("- {} (assigned to: {})", a.text, assignee)
-                                })
-                                .collect();
-                            context_parts.push(format!(
-                                "## Open Action Items\n{}\n",
-                                actions_str.join("\n")
-                            ));
-                        }
+/// Per-task generation params from the `llm_generation_params` setting
+///
+/// The setting is a JSON object keyed by task name, e.g.
+/// `{"extraction": {"temperature": 0.0}, "questions": {"top_p": 0.9}}`;
+/// missing tasks and fields keep their built-in defaults.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GenerationSettings {
+    overrides: HashMap<LlmTask, GenerationParams>,
+}
 
-                        // Add recent decisions
-                        if !graph_context.recent_decisions.is_empty() {
-                            let decisions_str: Vec<String> = graph_context.recent_decisions
-                                .iter()
-                                .take(5)
-                                .map(|d| format!("- {}", d.text))
-                                .collect();
-                            context_parts.push(format!(
-                                "## Recent Decisions\n{}\n",
-                                decisions_str.join("\n")
+impl GenerationSettings {
+    pub fn from_config(params_json: &str) -> Result<Self, String> {
+        if params_json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let overrides: HashMap<LlmTask, GenerationParams> = serde_json::from_str(params_json)
+            .map_err(|e| format!("Invalid llm_generation_params setting: {}", e))?;
+        for (task, params) in &overrides {
+            params.validate(*task)?;
+        }
+        Ok(Self { overrides })
+    }
+
+    pub fn params(&self, task: LlmTask) -> GenerationParams {
+        let defaults = task.default_params();
+        match self.overrides.get(&task) {
+            Some(params) => params.or(defaults),
+            None => defaults,
+        }
+    }
+}
+
+/// Placeholder a prompt template must contain: replaced by the method's built-in prompt
+pub const TEMPLATE_CONTEXT: &str = "{context}";
+/// Optional placeholder: the user's question, or the task for prompts without one
+pub const TEMPLATE_QUESTION: &str = "{question}";
+
+/// Reject templates that would drop the built-in prompt or leave unknown placeholders
+/// in it (a template without `{context}` sends the model nothing to work from).
+pub fn validate_prompt_template(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("Prompt template is empty".to_string());
+    }
+    if !template.contains(TEMPLATE_CONTEXT) {
+        return Err(format!("Prompt template must contain the {} placeholder", TEMPLATE_CONTEXT));
+    }
+    let placeholder = regex::Regex::new(r"\{([A-Za-z_]+)\}").expect("valid placeholder regex");
+    for caps in placeholder.captures_iter(template) {
+        let name = &caps[0];
+        if name != TEMPLATE_CONTEXT && name != TEMPLATE_QUESTION {
+            return Err(format!("Unknown placeholder {} (supported: {}, {})", name, TEMPLATE_CONTEXT, TEMPLATE_QUESTION));
+        }
+    }
+    Ok(())
+}
+
+/// Fill a template in one pass, so braces inside the context or question are left alone
+pub fn render_prompt_template(template: &str, context: &str, question: &str) -> String {
+    let mut out = String::with_capacity(template.len() + context.len() + question.len());
+    let mut rest = template;
+    loop {
+        let next = [(TEMPLATE_CONTEXT, context), (TEMPLATE_QUESTION, question)]
+            .into_iter()
+            .filter_map(|(placeholder, value)| rest.find(placeholder).map(|i| (i, placeholder, value)))
+            .min_by_key(|(i, _, _)| *i);
+        match next {
+            Some((i, placeholder, value)) => {
+                out.push_str(&rest[..i]);
+                out.push_str(value);
+                rest = &rest[i + placeholder.len()..];
+            }
+            None => {
+                out.push_str(rest);
+                return out;
+            }
+        }
+    }
+}
+
+/// Whether an endpoint URL points at this machine (localhost, loopback or unspecified address)
+pub fn is_local_url(url: &str) -> bool {
+    let url = url.trim();
+    let with_scheme = if url.contains("://") { url.to_string() } else { format!("http://{}", url) };
+    let Ok(parsed) = reqwest::Url::parse(&with_scheme) else {
+        return false;
+    };
+    let Some(host) = parsed.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']').to_lowercase();
+    match host.parse::<std::net::IpAddr>() {
+        Ok(ip) => ip.is_loopback() || ip.is_unspecified(),
+        Err(_) => host == "localhost" || host.ends_with(".localhost"),
+    }
+}
+
+/// Anthropic requires max_tokens on every request
+const ANTHROPIC_DEFAULT_MAX_TOKENS: u64 = 4096;
+const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+
+/// Strip trailing slashes and an OpenAI-style `/v1` suffix so native APIs get their root URL
+fn native_base_url(api_url: &str) -> String {
+    let trimmed = api_url.trim().trim_end_matches('/');
+    trimmed.strip_suffix("/v1").unwrap_or(trimmed).to_string()
+}
+
+/// Context length assumed when the server doesn't report one
+pub const DEFAULT_CONTEXT_LENGTH: usize = 8192;
+/// Context length of current Claude models
+const ANTHROPIC_CONTEXT_LENGTH: usize = 200_000;
+/// Tokens kept free for the prompt's instructions and the question
+const PROMPT_OVERHEAD_TOKENS: usize = 700;
+/// Answer length reserved when the answer task has no max_tokens
+const DEFAULT_ANSWER_TOKENS: usize = 1024;
+/// Smallest transcript window a long meeting is split into for summarizing
+const MIN_SUMMARY_WINDOW_TOKENS: usize = 512;
+
+/// Rough token count (about 4 characters per token for English text)
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Join context sections in order until `budget` tokens are used
+/// The section that overflows keeps as many whole lines as fit; later sections are dropped.
+pub fn trim_to_token_budget(parts: &[String], budget: usize) -> String {
+    let mut kept: Vec<String> = Vec::new();
+    let mut used = 0;
+    for part in parts {
+        let cost = estimate_tokens(part) + 1;
+        if used + cost <= budget {
+            kept.push(part.clone());
+            used += cost;
+            continue;
+        }
+
+        let mut partial = String::new();
+        for line in part.lines() {
+            let line_cost = estimate_tokens(line) + 1;
+            if used + line_cost > budget {
+                break;
+            }
+            partial.push_str(line);
+            partial.push('\n');
+            used += line_cost;
+        }
+        // A lone section heading is no use to the model
+        if partial.lines().count() > 1 {
+            kept.push(partial);
+        }
+        break;
+    }
+    kept.join("\n")
+}
+
+/// Label part summaries "Part 1: ...", "Part 2: ..." so the model keeps them in order
+fn numbered_parts(parts: &[String]) -> Vec<String> {
+    parts.iter()
+        .enumerate()
+        .map(|(i, part)| format!("Part {}:\n{}", i + 1, part.trim()))
+        .collect()
+}
+
+/// Pack parts in order into windows of at most `budget` tokens, joined by blank lines
+/// A part too long for a window on its own is cut into window-sized pieces.
+pub fn split_into_windows(parts: &[String], budget: usize) -> Vec<String> {
+    let max_chars = budget.max(1) * 4;
+    let mut windows = Vec::new();
+    let mut current = String::new();
+    let mut used = 0;
+    for part in parts {
+        let pieces: Vec<String> = if estimate_tokens(part) > budget {
+            let chars: Vec<char> = part.chars().collect();
+            chars.chunks(max_chars).map(|c| c.iter().collect()).collect()
+        } else {
+            vec![part.clone()]
+        };
+        for piece in pieces {
+            let cost = estimate_tokens(&piece) + 1;
+            if !current.is_empty() && used + cost > budget {
+                windows.push(std::mem::take(&mut current));
+                used = 0;
+            }
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&piece);
+            used += cost;
+        }
+    }
+    if !current.is_empty() {
+        windows.push(current);
+    }
+    windows
+}
+
+/// Minimal client for Ollama's native chat API
+#[derive(Clone)]
+struct OllamaClient {
+    base_url: String,
+    http: reqwest::Client,
+    context_lengths: Arc<parking_lot::Mutex<std::collections::HashMap<String, Option<usize>>>>,  // /api/show lookups per model
+}
+
+impl OllamaClient {
+    fn new(api_url: &str) -> Self {
+        Self {
+            base_url: native_base_url(api_url),
+            http: reqwest::Client::new(),
+            context_lengths: Arc::default(),
+        }
+    }
+
+    async fn chat(
+        &self,
+        model: &str,
+        preamble: Option<&str>,
+        prompt: &str,
+        params: GenerationParams,
+    ) -> Result<(String, Option<TokenUsage>), SecondBrainError> {
+        let mut messages = Vec::new();
+        if let Some(system) = preamble {
+            messages.push(json!({"role": "system", "content": system}));
+        }
+        messages.push(json!({"role": "user", "content": prompt}));
+
+        let mut options = serde_json::Map::new();
+        if let Some(t) = params.temperature {
+            options.insert("temperature".to_string(), json!(t));
+        }
+        if let Some(n) = params.max_tokens {
+            options.insert("num_predict".to_string(), json!(n));
+        }
+        if let Some(p) = params.top_p {
+            options.insert("top_p".to_string(), json!(p));
+        }
+
+        let body = json!({
+            "model": model,
+            "messages": messages,
+            "stream": false,
+            "options": options,
+        });
+
+        let response = self.http
+            .post(format!("{}/api/chat", self.base_url))
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| request_error("Ollama request failed", e))?;
+
+        let status = response.status();
+        let text = response.text().await
+            .map_err(|e| request_error("Failed to read Ollama response", e))?;
+        if !status.is_success() {
+            return Err(status_error(format!("Ollama returned {}: {}", status, text), status.as_u16()));
+        }
+
+        let value: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| SecondBrainError::Llm(format!("Invalid Ollama response: {}", e)))?;
+        let content = value["message"]["content"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| SecondBrainError::Llm("Ollama response missing message content".to_string()))?;
+        let usage = match (value["prompt_eval_count"].as_u64(), value["eval_count"].as_u64()) {
+            (Some(prompt_tokens), Some(completion_tokens)) => Some(TokenUsage { prompt_tokens, completion_tokens }),
+            _ => None,
+        };
+        Ok((content, usage))
+    }
+
+    /// Context window the model runs with: `num_ctx` if the modelfile sets it, else the trained length
+    /// Looked up once per model; failed lookups are retried on the next call.
+    async fn context_length(&self, model: &str) -> Result<Option<usize>, SecondBrainError> {
+        if let Some(known) = self.context_lengths.lock().get(model) {
+            return Ok(*known);
+        }
+        let request = self.http
+            .post(format!("{}/api/show", self.base_url))
+            .header("Content-Type", "application/json")
+            .body(json!({"model": model}).to_string());
+        let value = fetch_json(request).await?;
+
+        let num_ctx = value["parameters"].as_str().and_then(|params| {
+            params.lines()
+                .filter_map(|line| line.trim().strip_prefix("num_ctx"))
+                .find_map(|n| n.trim().parse::<usize>().ok())
+        });
+        let trained = value["model_info"].as_object().and_then(|info| {
+            info.iter()
+                .find(|(key, _)| key.ends_with(".context_length"))
+                .and_then(|(_, n)| n.as_u64())
+                .map(|n| n as usize)
+        });
+        let context_length = num_ctx.or(trained);
+        self.context_lengths.lock().insert(model.to_string(), context_length);
+        Ok(context_length)
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>, SecondBrainError> {
+        let value = fetch_json(self.http.get(format!("{}/api/tags", self.base_url))).await?;
+        Ok(value["models"]
+            .as_array()
+            .map(|models| {
+                models.iter()
+                    .filter_map(|m| m["name"].as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+/// Send a request and parse the body as JSON, surfacing non-2xx responses as errors
+async fn fetch_json(request: reqwest::RequestBuilder) -> Result<serde_json::Value, SecondBrainError> {
+    let response = request.send().await
+        .map_err(|e| request_error("Request failed", e))?;
+    let status = response.status();
+    let text = response.text().await
+        .map_err(|e| request_error("Failed to read response", e))?;
+    if !status.is_success() {
+        return Err(status_error(format!("Server returned {}: {}", status, text), status.as_u16()));
+    }
+    serde_json::from_str(&text).map_err(|e| SecondBrainError::Llm(format!("Invalid JSON response: {}", e)))
+}
+
+/// Tool calls allowed per agent-driven realtime suggestion
+const SUGGESTION_TOOL_CALL_BUDGET: usize = 2;
+/// Deadline for an agent-driven suggestion before falling back to manual context
+const SUGGESTION_AGENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(6);
+/// Characters of each tool result fed back to the model
+const SUGGESTION_TOOL_OUTPUT_CHARS: usize = 1500;
+
+/// Tools available to agent-driven realtime suggestions; the web search is left out in offline mode
+fn suggestion_tools(kb: Arc<RwLock<Option<KnowledgeBase>>>, offline: bool) -> ToolSet {
+    let mut tools = ToolSet::default();
+    tools.add_tool(SearchTranscriptsTool { kb: kb.clone() });
+    tools.add_tool(SearchKnowledgeTool { kb });
+    if !offline {
+        tools.add_tool(WebSearchTool);
+    }
+    tools
+}
+
+/// Parse a suggestion JSON response, falling back to its first line as the insight
+fn parse_suggestion(response_text: &str) -> RealtimeSuggestion {
+    let json_str = extract_json_from_response(response_text);
+    parse_lenient_json::<RealtimeSuggestion>(&json_str).unwrap_or_else(|_| RealtimeSuggestion {
+        insight: Some(response_text.lines().next().unwrap_or("").to_string()),
+        question: None,
+        related_info: None,
+    })
+}
+
+/// Context section for pinned sources, labeled so the model treats it as authoritative
+fn format_pinned_section(chunks: &[crate::knowledge_base::KnowledgeSearchResult]) -> String {
+    let entries: Vec<String> = chunks.iter()
+        .map(|r| format!("### {}\n> {}\n", r.source_title, r.chunk.text.replace("\n", "\n> ")))
+        .collect();
+    format!(
+        "## Pinned Reference (authoritative - pinned by the user, always applies)\n{}\n",
+        entries.join("\n")
+    )
+}
+
+/// First `max` characters of `text`
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((i, _)) => format!("{}...", &text[..i]),
+        None => text.to_string(),
+    }
+}
+
+/// Name of a detected language code for prompts; unknown codes are passed through
+fn language_name(code: &str) -> &str {
+    match code {
+        "en" => "English",
+        "zh" => "Chinese",
+        "yue" => "Cantonese",
+        "ja" => "Japanese",
+        "ko" => "Korean",
+        "de" => "German",
+        "fr" => "French",
+        "es" => "Spanish",
+        other => other,
+    }
+}
+
+/// Token counts a provider's raw completion response reports
+trait ReportsUsage {
+    fn token_usage(&self) -> Option<TokenUsage>;
+}
+
+impl ReportsUsage for openai::CompletionResponse {
+    fn token_usage(&self) -> Option<TokenUsage> {
+        self.usage.as_ref().map(|u| TokenUsage {
+            prompt_tokens: u.prompt_tokens as u64,
+            completion_tokens: u.total_tokens.saturating_sub(u.prompt_tokens) as u64,
+        })
+    }
+}
+
+impl ReportsUsage for anthropic::completion::CompletionResponse {
+    fn token_usage(&self) -> Option<TokenUsage> {
+        Some(TokenUsage {
+            prompt_tokens: self.usage.input_tokens,
+            completion_tokens: self.usage.output_tokens,
+        })
+    }
+}
+
+/// What the model produced for a tool-enabled completion
+enum ModelTurn {
+    Text(String),
+    ToolCall { name: String, arguments: serde_json::Value },
+}
+
+/// Like `rig_complete`, offering `tools` and returning the first tool call if the model made one
+async fn rig_complete_with_tools<M>(
+    model: M,
+    prompt: String,
+    tools: Vec<ToolDefinition>,
+    params: GenerationParams,
+) -> Result<(ModelTurn, Option<TokenUsage>), SecondBrainError>
+where
+    M: CompletionModel,
+    M::Response: ReportsUsage,
+{
+    let mut request = model.completion_request(prompt).tools(tools);
+    if let Some(t) = params.temperature {
+        request = request.temperature(t);
+    }
+    if let Some(n) = params.max_tokens {
+        request = request.max_tokens(n);
+    }
+    if let Some(p) = params.top_p {
+        request = request.additional_params(json!({"top_p": p}));
+    }
+
+    let response = request.send().await.map_err(completion_error)?;
+    let tool_call = response.choice.iter().find_map(|content| match content {
+        AssistantContent::ToolCall(call) => Some(ModelTurn::ToolCall {
+            name: call.function.name.clone(),
+            arguments: call.function.arguments.clone(),
+        }),
+        AssistantContent::Text(_) => None,
+    });
+    let turn = tool_call.unwrap_or_else(|| ModelTurn::Text(extract_text(&response.choice.first())));
+    Ok((turn, response.raw_response.token_usage()))
+}
+
+/// Run a single completion through any rig provider model, with the token usage it reported
+async fn rig_complete<M>(
+    model: M,
+    preamble: Option<&str>,
+    prompt: String,
+    params: GenerationParams,
+) -> Result<(String, Option<TokenUsage>), SecondBrainError>
+where
+    M: CompletionModel,
+    M::Response: ReportsUsage,
+{
+    let mut request = model.completion_request(prompt);
+    if let Some(p) = preamble {
+        request = request.preamble(p.to_string());
+    }
+    if let Some(t) = params.temperature {
+        request = request.temperature(t);
+    }
+    if let Some(n) = params.max_tokens {
+        request = request.max_tokens(n);
+    }
+    if let Some(p) = params.top_p {
+        request = request.additional_params(json!({"top_p": p}));
+    }
+
+    let response = request.send().await.map_err(completion_error)?;
+    Ok((extract_text(&response.choice.first()), response.raw_response.token_usage()))
+}
+
+#[derive(Clone)]
+enum LlmBackend {
+    OpenAI(openai::Client),
+    Ollama(OllamaClient),
+    Anthropic(anthropic::Client),
+}
+
+/// One endpoint in the assistant's failover chain, as saved in `llm_fallbacks`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlmEndpoint {
+    #[serde(default)]
+    pub provider: LlmProvider,
+    #[serde(default)]
+    pub url: String,  // Empty = the provider's default URL
+    pub model: String,
+    #[serde(default)]
+    pub api_key: String,
+}
+
+impl LlmEndpoint {
+    /// Parse the `llm_fallbacks` setting (JSON list; empty = no fallbacks)
+    pub fn list_from_config(endpoints_json: &str) -> Result<Vec<Self>, String> {
+        if endpoints_json.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        let endpoints: Vec<Self> = serde_json::from_str(endpoints_json)
+            .map_err(|e| format!("Invalid llm_fallbacks setting: {}", e))?;
+        for endpoint in &endpoints {
+            if endpoint.model.trim().is_empty() {
+                return Err("Every fallback endpoint needs a model".to_string());
+            }
+            if endpoint.url.trim().is_empty() && endpoint.provider.default_url().is_none() {
+                return Err(format!("Fallback endpoint for {} needs a URL", endpoint.model));
+            }
+        }
+        Ok(endpoints)
+    }
+}
+
+/// Result of pinging one endpoint of the failover chain
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointHealth {
+    pub provider: LlmProvider,
+    pub url: String,
+    pub model: String,
+    pub ok: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    pub active: bool,  // The endpoint requests currently go to
+}
+
+/// Whether a request error means the endpoint is unreachable or down (worth failing over),
+/// as opposed to a problem with the request itself
+pub fn is_connection_error(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout() || error.status().is_some_and(|s| is_unavailable_status(s.as_u16()))
+}
+
+/// Bad gateway, service unavailable and gateway timeout: the server is down, not the request wrong
+fn is_unavailable_status(status: u16) -> bool {
+    matches!(status, 502..=504)
+}
+
+/// Classify a failed request: `Network` when the endpoint couldn't be reached, `Llm` otherwise
+fn request_error(context: &str, error: reqwest::Error) -> SecondBrainError {
+    let message = format!("{}: {}", context, error);
+    if is_connection_error(&error) {
+        SecondBrainError::Network(message)
+    } else {
+        SecondBrainError::Llm(message)
+    }
+}
+
+/// Classify an error response by its HTTP status
+fn status_error(message: String, status: u16) -> SecondBrainError {
+    if is_unavailable_status(status) {
+        SecondBrainError::Network(message)
+    } else {
+        SecondBrainError::Llm(message)
+    }
+}
+
+/// Classify a rig completion error; rig's HTTP client is its own reqwest version,
+/// so the kind and status are checked on the error it carries
+fn completion_error(error: CompletionError) -> SecondBrainError {
+    match error {
+        CompletionError::HttpError(e)
+            if e.is_connect() || e.is_timeout() || e.status().is_some_and(|s| is_unavailable_status(s.as_u16())) =>
+        {
+            SecondBrainError::Network(CompletionError::HttpError(e).to_string())
+        }
+        e => SecondBrainError::Llm(e.to_string()),
+    }
+}
+
+/// A provider client for one endpoint
+#[derive(Clone)]
+struct EndpointClient {
+    backend: LlmBackend,
+    provider: LlmProvider,
+    api_url: String,
+    api_key: String,
+    model: String,
+}
+
+impl EndpointClient {
+    fn new(provider: LlmProvider, api_url: &str, model: &str, api_key: &str) -> Self {
+        let api_url = if api_url.trim().is_empty() {
+            provider.default_url().unwrap_or_default().to_string()
+        } else {
+            api_url.trim().to_string()
+        };
+
+        let backend = match provider {
+            LlmProvider::OpenAI => {
+                // from_url signature is (api_key, base_url)
+                // Use provided key or fallback to dummy for local servers
+                let key = if api_key.trim().is_empty() { "not-needed" } else { api_key };
+                LlmBackend::OpenAI(openai::Client::from_url(key, &api_url))
+            }
+            LlmProvider::Ollama => LlmBackend::Ollama(OllamaClient::new(&api_url)),
+            LlmProvider::Anthropic => {
+                let base_url = native_base_url(&api_url);
+                LlmBackend::Anthropic(
+                    anthropic::ClientBuilder::new(api_key)
+                        .base_url(&base_url)
+                        .build(),
+                )
+            }
+        };
+
+        Self {
+            backend,
+            provider,
+            api_url,
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+        }
+    }
+
+    /// Run a single prompt and return the cleaned text with the token usage the server reported
+    async fn complete(
+        &self,
+        preamble: Option<&str>,
+        prompt: String,
+        params: GenerationParams,
+    ) -> Result<(String, Option<TokenUsage>), SecondBrainError> {
+        let result = match &self.backend {
+            LlmBackend::OpenAI(client) => {
+                rig_complete(client.completion_model(&self.model), preamble, prompt, params).await
+            }
+            LlmBackend::Anthropic(client) => {
+                let params = GenerationParams {
+                    max_tokens: Some(params.max_tokens.unwrap_or(ANTHROPIC_DEFAULT_MAX_TOKENS)),
+                    ..params
+                };
+                rig_complete(client.completion_model(&self.model), preamble, prompt, params).await
+            }
+            LlmBackend::Ollama(client) => client.chat(&self.model, preamble, &prompt, params).await
+                .map(|(text, usage)| (strip_thinking_tags(&text), usage)),
+        };
+        result
+    }
+
+    /// Like `complete`, offering `tools`; only rig-backed providers support them
+    async fn complete_with_tools(
+        &self,
+        prompt: String,
+        tools: Vec<ToolDefinition>,
+        params: GenerationParams,
+    ) -> Result<(ModelTurn, Option<TokenUsage>), SecondBrainError> {
+        let result = match &self.backend {
+            LlmBackend::OpenAI(client) => {
+                rig_complete_with_tools(client.completion_model(&self.model), prompt, tools, params).await
+            }
+            LlmBackend::Anthropic(client) => {
+                let params = GenerationParams {
+                    max_tokens: Some(params.max_tokens.unwrap_or(ANTHROPIC_DEFAULT_MAX_TOKENS)),
+                    ..params
+                };
+                rig_complete_with_tools(client.completion_model(&self.model), prompt, tools, params).await
+            }
+            LlmBackend::Ollama(_) => Err(SecondBrainError::Llm("Tool calling is not supported with the Ollama provider".to_string())),
+        };
+        result
+    }
+
+    /// Models the endpoint offers
+    async fn list_models(&self) -> Result<Vec<String>, SecondBrainError> {
+        let http = reqwest::Client::new();
+        let value = match &self.backend {
+            LlmBackend::Ollama(client) => return client.list_models().await,
+            LlmBackend::OpenAI(_) => {
+                let mut request = http.get(format!("{}/models", self.api_url.trim_end_matches('/')));
+                if !self.api_key.trim().is_empty() {
+                    request = request.bearer_auth(&self.api_key);
+                }
+                fetch_json(request).await?
+            }
+            LlmBackend::Anthropic(_) => {
+                let request = http
+                    .get(format!("{}/v1/models", native_base_url(&self.api_url)))
+                    .header("x-api-key", &self.api_key)
+                    .header("anthropic-version", ANTHROPIC_API_VERSION);
+                fetch_json(request).await?
+            }
+        };
+
+        Ok(value["data"]
+            .as_array()
+            .map(|models| {
+                models.iter()
+                    .filter_map(|m| m["id"].as_str().map(|s| s.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// One-token completion to check the endpoint and model
+    async fn ping(&self) -> Result<(), SecondBrainError> {
+        let params = GenerationParams { temperature: Some(0.0), max_tokens: Some(1), top_p: None };
+        self.complete(None, "ping".to_string(), params)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.context(format!("{} connection test failed", self.provider.as_str())))
+    }
+}
+
+/// Receives the token usage of every completion the assistant makes
+pub type UsageCallback = Arc<dyn Fn(LlmUsage) + Send + Sync>;
+
+/// The LLM-powered meeting assistant
+#[derive(Clone)]
+pub struct MeetingAssistant {
+    endpoints: Vec<EndpointClient>,  // Primary first, then fallbacks in the order to try them
+    active: Arc<AtomicUsize>,        // Endpoint that last answered; shared with clones
+    generation: GenerationSettings,
+    prompt_template: Option<String>,  // Active user template wrapping every prompt
+    retrieval: RetrievalConfig,
+    on_usage: Option<UsageCallback>,
+}
+
+impl MeetingAssistant {
+    /// Create a new meeting assistant
+    ///
+    /// # Arguments
+    /// * `provider` - Which API flavour to speak (OpenAI-compatible, Ollama, Anthropic)
+    /// * `api_url` - The API URL (e.g., "https://lmstudio.subh-dev.xyz/llm/v1", "http://localhost:11434")
+    /// * `model` - The model name (e.g., "openai/gpt-oss-20b")
+    /// * `api_key` - The API key (can be empty for local servers like LM Studio/Ollama)
+    pub fn new(provider: LlmProvider, api_url: &str, model: &str, api_key: &str) -> Self {
+        Self {
+            endpoints: vec![EndpointClient::new(provider, api_url, model, api_key)],
+            active: Arc::new(AtomicUsize::new(0)),
+            generation: GenerationSettings::default(),
+            prompt_template: None,
+            retrieval: RetrievalConfig::default(),
+            on_usage: None,
+        }
+    }
+
+    /// Fall back to these endpoints, in order, when the ones before them can't be reached
+    pub fn with_fallbacks(mut self, fallbacks: &[LlmEndpoint]) -> Self {
+        self.endpoints.extend(fallbacks.iter()
+            .map(|e| EndpointClient::new(e.provider, &e.url, &e.model, &e.api_key)));
+        self
+    }
+
+    /// Use per-task generation params instead of the built-in defaults
+    pub fn with_generation_settings(mut self, generation: GenerationSettings) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Wrap every prompt in a user template (None = built-in prompts as is)
+    pub fn with_prompt_template(mut self, template: Option<String>) -> Self {
+        self.prompt_template = template;
+        self
+    }
+
+    /// Use configured Graph-RAG retrieval counts instead of the defaults
+    pub fn with_retrieval_config(mut self, retrieval: RetrievalConfig) -> Self {
+        self.retrieval = retrieval;
+        self
+    }
+
+    /// Report each completion's token usage to `callback`
+    pub fn with_usage_callback(mut self, callback: UsageCallback) -> Self {
+        self.on_usage = Some(callback);
+        self
+    }
+
+    /// Endpoint that last answered (the primary until a failover happens)
+    fn endpoint(&self) -> &EndpointClient {
+        let index = self.active.load(Ordering::Relaxed).min(self.endpoints.len() - 1);
+        &self.endpoints[index]
+    }
+
+    pub fn provider(&self) -> LlmProvider {
+        self.endpoint().provider
+    }
+
+    /// Context length of the active model in tokens
+    /// Uses the configured value if set, otherwise what the server reports; falls back to
+    /// `DEFAULT_CONTEXT_LENGTH` for OpenAI-compatible servers, which don't expose it.
+    pub async fn context_length(&self) -> usize {
+        if let Some(n) = self.retrieval.context_length {
+            return n;
+        }
+        let endpoint = self.endpoint();
+        match &endpoint.backend {
+            LlmBackend::Ollama(client) => match client.context_length(&endpoint.model).await {
+                Ok(Some(n)) => n,
+                Ok(None) => DEFAULT_CONTEXT_LENGTH,
+                Err(e) => {
+                    eprintln!("[LLM] Couldn't read context length for {}: {}", endpoint.model, e);
+                    DEFAULT_CONTEXT_LENGTH
+                }
+            },
+            LlmBackend::Anthropic(_) => ANTHROPIC_CONTEXT_LENGTH,
+            LlmBackend::OpenAI(_) => DEFAULT_CONTEXT_LENGTH,
+        }
+    }
+
+    /// Tokens of retrieved context that fit next to the prompt and the answer
+    fn context_token_budget(&self, context_length: usize) -> usize {
+        self.task_token_budget(LlmTask::Answer, context_length)
+    }
+
+    /// Tokens of input that fit next to the prompt and `task`'s output
+    fn task_token_budget(&self, task: LlmTask, context_length: usize) -> usize {
+        let answer_tokens = self.generation.params(task).max_tokens
+            .map(|n| n as usize)
+            .unwrap_or(DEFAULT_ANSWER_TOKENS);
+        context_length.saturating_sub(PROMPT_OVERHEAD_TOKENS + answer_tokens)
+    }
+
+    pub fn api_url(&self) -> &str {
+        &self.endpoint().api_url
+    }
+
+    /// URLs of every endpoint in the failover chain, primary first
+    pub fn api_urls(&self) -> Vec<&str> {
+        self.endpoints.iter().map(|e| e.api_url.as_str()).collect()
+    }
+
+    /// Run `request` against the endpoint that last answered, then the others in order,
+    /// moving on only when an endpoint can't be reached
+    /// Each attempt is a whole completion, so a response never mixes two endpoints.
+    async fn with_failover<T, F, Fut>(&self, request: F) -> Result<T, SecondBrainError>
+    where
+        F: Fn(EndpointClient) -> Fut,
+        Fut: Future<Output = Result<T, SecondBrainError>>,
+    {
+        let active = self.active.load(Ordering::Relaxed).min(self.endpoints.len() - 1);
+        let order = std::iter::once(active).chain((0..self.endpoints.len()).filter(|&i| i != active));
+
+        let mut last_error = None;
+        for index in order {
+            let endpoint = self.endpoints[index].clone();
+            let url = endpoint.api_url.clone();
+            match request(endpoint).await {
+                Ok(value) => {
+                    if self.active.swap(index, Ordering::Relaxed) != index {
+                        println!("[LLM] Now using endpoint {} ({})", index, url);
+                    }
+                    return Ok(value);
+                }
+                Err(e @ SecondBrainError::Network(_)) if self.endpoints.len() > 1 => {
+                    eprintln!("[LLM] Endpoint {} unreachable: {}", url, e);
+                    last_error = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error.unwrap_or_else(|| SecondBrainError::NotInitialized("No LLM endpoint configured".to_string())))
+    }
+
+    /// Run a task's prompt with its configured generation params
+    async fn complete(&self, task: LlmTask, preamble: Option<&str>, prompt: String) -> Result<String, SecondBrainError> {
+        self.complete_for(task, preamble, prompt, task.instruction()).await
+    }
+
+    /// Same as `complete`, filling the template's `{question}` with the user's question
+    async fn complete_for(
+        &self,
+        task: LlmTask,
+        preamble: Option<&str>,
+        prompt: String,
+        question: &str,
+    ) -> Result<String, SecondBrainError> {
+        let prompt = match self.prompt_template.as_deref() {
+            Some(template) => render_prompt_template(template, &prompt, question),
+            None => prompt,
+        };
+        // Kept only for estimating usage when the server doesn't report it
+        let sent = format!("{}{}", preamble.unwrap_or_default(), prompt);
+        let (text, usage) = self.complete_with(preamble, prompt, self.generation.params(task)).await?;
+        self.record_usage(task, usage.unwrap_or_else(|| TokenUsage::estimate(&sent, &text)));
+        Ok(text)
+    }
+
+    /// Pass one completion's token usage to the usage callback
+    fn record_usage(&self, task: LlmTask, usage: TokenUsage) {
+        if let Some(callback) = &self.on_usage {
+            callback(LlmUsage { task, model: self.endpoint().model.clone(), usage });
+        }
+    }
+
+    /// Run a single prompt against the configured endpoints and return the cleaned text
+    /// with the token usage the server reported
+    async fn complete_with(
+        &self,
+        preamble: Option<&str>,
+        prompt: String,
+        params: GenerationParams,
+    ) -> Result<(String, Option<TokenUsage>), SecondBrainError> {
+        self.with_failover(|endpoint| {
+            let prompt = prompt.clone();
+            async move { endpoint.complete(preamble, prompt, params).await }
+        }).await
+    }
+
+    /// Check the primary endpoint is reachable and accepts the key by listing its models,
+    /// without spending a completion
+    pub async fn test_connection(&self) -> Result<(), SecondBrainError> {
+        let endpoint = &self.endpoints[0];
+        endpoint.list_models()
+            .await
+            .map(|_| ())
+            .map_err(|e| e.context(format!("{} connection test failed", endpoint.provider.as_str())))
+    }
+
+    /// Ping every endpoint in the failover chain
+    pub async fn check_endpoints(&self) -> Vec<EndpointHealth> {
+        let active = self.active.load(Ordering::Relaxed);
+        let mut health = Vec::with_capacity(self.endpoints.len());
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            let started = std::time::Instant::now();
+            let result = endpoint.ping().await;
+            health.push(EndpointHealth {
+                provider: endpoint.provider,
+                url: endpoint.api_url.clone(),
+                model: endpoint.model.clone(),
+                ok: result.is_ok(),
+                latency_ms: started.elapsed().as_millis() as u64,
+                error: result.err().map(String::from),
+                active: index == active,
+            });
+        }
+        health
+    }
+
+    /// List the models the active endpoint offers
+    pub async fn list_models(&self) -> Result<Vec<String>, SecondBrainError> {
+        self.endpoint().list_models().await
+    }
+
+    /// Ask a question using Graph-RAG (Graph + Retrieval Augmented Generation)
+    /// Combines entity extraction, graph traversal, temporal awareness, and vector search
+    pub async fn ask(
+        &self,
+        question: &str,
+        kb: Arc<RwLock<Option<KnowledgeBase>>>,
+    ) -> Result<String, SecondBrainError> {
+        self.ask_with_sources(question, kb).await.map(|a| a.answer)
+    }
+
+    /// Same as `ask`, also returning the meetings and documents included in the prompt
+    pub async fn ask_with_sources(
+        &self,
+        question: &str,
+        kb: Arc<RwLock<Option<KnowledgeBase>>>,
+    ) -> Result<AnswerWithSources, SecondBrainError> {
+        println!("[Graph-RAG] Asking question: {}", question);
+
+        // Size retrieval to the model's context window
+        let context_length = self.context_length().await;
+        let retrieval = self.retrieval.fit_to_context(context_length);
+        let token_budget = self.context_token_budget(context_length);
+
+        // Step 1: Use Graph-RAG to get comprehensive context
+        let mut sources = Vec::new();
+        let context = {
+            let kb_guard = kb.read().await;
+            if let Some(kb_ref) = kb_guard.as_ref() {
+                println!("[Graph-RAG] Knowledge base found, running Graph-RAG query...");
+
+                match kb_ref.graph_rag_query(question, &retrieval).await {
+                    Ok(graph_context) => {
+                        // Build rich context from Graph-RAG results
+                        let mut context_parts = Vec::new();
+
+                        // Pinned sources go first so they survive trimming to the token budget
+                        if !graph_context.pinned_chunks.is_empty() {
+                            sources.extend(graph_context.pinned_chunks.iter().map(|r| Source::document(r, r.chunk.text.clone())));
+                            context_parts.push(format_pinned_section(&graph_context.pinned_chunks));
+                        }
+
+                        // Add temporal context if detected
+                        if let Some(ref temporal) = graph_context.temporal_context {
+                            context_parts.push(format!(
+                                "## Temporal Reference Detected\nTime reference: {}\n",
+                                temporal.time_reference
+                            ));
+                        }
+
+                        // Add extracted query entities
+                        if !graph_context.query_entities.is_empty() {
+                            let entities_str: Vec<String> = graph_context.query_entities
+                                .iter()
+                                .map(|e| format!("{} ({})", e.text, e.label))
+                                .collect();
+                            context_parts.push(format!(
+                                "## Entities Mentioned in Query\n{}\n",
+                                entities_str.join(", ")
+                            ));
+                        }
+
+                        // Add related meetings
+                        if !graph_context.related_meetings.is_empty() {
+                            let meetings_str: Vec<String> = graph_context.related_meetings
+                                .iter()
+                                .map(|m| {
+                                    if m.relevant_segments.is_empty() {
+                                        sources.push(Source::meeting(&m.meeting, None, String::new()));
+                                    }
+                                    let segments_preview: Vec<String> = m.relevant_segments
+                                        .iter()
+                                        .map(|s| {
+                                            let excerpt = &s.text[..s.text.len().min(100)];
+                                            sources.push(Source::meeting(&m.meeting, Some(s), excerpt.to_string()));
+                                            format!("  - {}: \"{}...\"", s.speaker, excerpt)
+                                        })
+                                        .collect();
+                                    format!(
+                                        "**{}** ({} days ago)\n{}",
+                                        m.meeting.title,
+                                        m.days_ago,
+                                        segments_preview.join("\n")
+                                    )
+                                })
+                                .collect();
+                            context_parts.push(format!(
+                                "## Related Meetings\n{}\n",
+                                meetings_str.join("\n\n")
+                            ));
+                        }
+
+                        // Add related people with their topics
+                        if !graph_context.related_people.is_empty() {
+                            let people_str: Vec<String> = graph_context.related_people
+                                .iter()
+                                .map(|p| {
+                                    let topics = if p.recent_topics.is_empty() {
+                                        "No topics recorded".to_string()
+                                    } else {
+                                        p.recent_topics.join(", ")
+                                    };
+                                    format!("- **{}** (last seen {} days ago): discusses {}", p.name, p.last_seen_days_ago, topics)
+                                })
+                                .collect();
+                            context_parts.push(format!(
+                                "## Related People\n{}\n",
+                                people_str.join("\n")
+                            ));
+                        }
+
+                        // Add related topics
+                        if !graph_context.related_topics.is_empty() {
+                            let topics_str: Vec<String> = graph_context.related_topics
+                                .iter()
+                                .map(|t| {
+                                    let people = if t.related_people.is_empty() {
+                                        "various participants".to_string()
+                                    } else {
+                                        t.related_people.join(", ")
+                                    };
+                                    format!("- **{}**: mentioned {} times, last {} days ago (discussed by: {})",
+                                        t.name, t.mention_count, t.last_mentioned_days_ago, people)
+                                })
+                                .collect();
+                            context_parts.push(format!(
+                                "## Related Topics\n{}\n",
+                                topics_str.join("\n")
+                            ));
+                        }
+
+                        // Add open action items (if relevant)
+                        if !graph_context.open_actions.is_empty() {
+                            let actions_str: Vec<String> = graph_context.open_actions
+                                .iter()
+                                .take(5)
+                                .map(|a| {
+                                    let assignee = a.assignee.as_deref().unwrap_or("Unassigned");
+                                    format!("- {} (assigned to: {})", a.text, assignee)
+                                })
+                                .collect();
+                            context_parts.push(format!(
+                                "## Open Action Items\n{}\n",
+                                actions_str.join("\n")
+                            ));
+                        }
+
+                        // Add recent decisions
+                        if !graph_context.recent_decisions.is_empty() {
+                            let decisions_str: Vec<String> = graph_context.recent_decisions
+                                .iter()
+                                .take(5)
+                                .map(|d| format!("- {}", d.text))
+                                .collect();
+                            context_parts.push(format!(
+                                "## Recent Decisions\n{}\n",
+                                decisions_str.join("\n")
                             ));
                         }
 
@@ -724,6 +2154,7 @@ impl MeetingAssistant {
                                     } else {
                                         r.chunk.text.clone()
                                     };
+                                    sources.push(Source::document(r, excerpt.clone()));
                                     format!(
                                         "### {} ({:.0}% similarity)\nURL: {}\n> {}\n",
                                         r.source_title,
@@ -739,16 +2170,26 @@ impl MeetingAssistant {
                             ));
                         }
 
-                        context_parts.join("\n")
+                        let context = trim_to_token_budget(&context_parts, token_budget);
+                        println!("[Graph-RAG] Context: ~{} tokens (budget {} of {})",
+                            estimate_tokens(&context), token_budget, context_length);
+                        context
                     }
                     Err(e) => {
                         println!("[Graph-RAG] Error: {}", e);
-                        // Fall back to simple vector search
-                        let results = kb_ref.search_knowledge(question, 5, None).await.unwrap_or_default();
-                        if results.is_empty() {
-                            String::new()
-                        } else {
-                            results.iter()
+                        // Fall back to simple vector search, still leading with pinned sources
+                        let pinned = kb_ref.search_pinned_knowledge(question, retrieval.pinned_chunks_per_source).await.unwrap_or_default();
+                        let results: Vec<_> = kb_ref.search_knowledge(question, retrieval.max_chunks, None).await.unwrap_or_default()
+                            .into_iter()
+                            .filter(|r| !pinned.iter().any(|p| p.chunk.id == r.chunk.id))
+                            .collect();
+                        sources.extend(pinned.iter().chain(&results).map(|r| Source::document(r, r.chunk.text.clone())));
+                        let mut context_parts = Vec::new();
+                        if !pinned.is_empty() {
+                            context_parts.push(format_pinned_section(&pinned));
+                        }
+                        if !results.is_empty() {
+                            context_parts.push(results.iter()
                                 .map(|r| format!(
                                     "Source: {} ({})\n{}\n",
                                     r.source_title,
@@ -756,8 +2197,9 @@ impl MeetingAssistant {
                                     r.chunk.text
                                 ))
                                 .collect::<Vec<_>>()
-                                .join("\n---\n")
+                                .join("\n---\n"));
                         }
+                        trim_to_token_budget(&context_parts, token_budget)
                     }
                 }
             } else {
@@ -769,7 +2211,10 @@ impl MeetingAssistant {
         // Step 2: Build prompt with rich Graph-RAG context
         let prompt = if context.is_empty() {
             println!("[Graph-RAG] No context found, sending empty KB response");
-            return Ok("I couldn't find any relevant information in your knowledge base to answer this question.\n\n**Possible reasons:**\n- Your knowledge base might be empty. Try adding some content first (web pages, documents, or text).\n- The question might not match any stored content. Try rephrasing or adding more relevant content.\n\n**To add content:**\n1. Go to the \"Add Source\" tab\n2. Add a URL to crawl, or upload a document\n3. Then try asking your question again!".to_string());
+            return Ok(AnswerWithSources {
+                answer: "I couldn't find any relevant information in your knowledge base to answer this question.\n\n**Possible reasons:**\n- Your knowledge base might be empty. Try adding some content first (web pages, documents, or text).\n- The question might not match any stored content. Try rephrasing or adding more relevant content.\n\n**To add content:**\n1. Go to the \"Add Source\" tab\n2. Add a URL to crawl, or upload a document\n3. Then try asking your question again!".to_string(),
+                sources: vec![],
+            });
         } else {
             format!(
                 r#"You are Second Brain, a personal AI assistant with access to the user's meeting history, knowledge base, and documents.
@@ -798,6 +2243,10 @@ RESPONSE GUIDELINES:
 - If action items exist, list them with assignees: "- [ ] Task (Owner)"
 - Acknowledge gaps: "I found X, but couldn't find Y"
 
+**Pinned Reference:**
+- The "Pinned Reference" section is background the user pinned as authoritative (glossaries, rosters, project briefs)
+- Treat it as ground truth and prefer it over other sources when they conflict
+
 **IMPORTANT - Document Attribution:**
 - The "Potentially Relevant Documents" section contains documents retrieved by similarity search
 - These documents were NOT mentioned or discussed in meetings - they are just topically similar
@@ -816,101 +2265,253 @@ ANSWER:"#,
             )
         };
 
-        // Step 3: Get response from LLM
-        let model = self.client.completion_model(&self.model);
-
-        let response = model.completion_request(prompt)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to get response: {}", e))?;
+        // Step 3: Get response from LLM
+        let answer = self.complete_for(LlmTask::Answer, None, prompt, question)
+            .await
+            .map_err(|e| e.context("Failed to get response"))?;
+
+        println!("[Graph-RAG] Answer built from {} sources", sources.len());
+        Ok(AnswerWithSources { answer, sources })
+    }
+
+    /// Ask a question about a specific meeting
+    pub async fn ask_about_meeting(
+        &self,
+        question: &str,
+        meeting_title: &str,
+        transcript: &[String],  // Segments as "Speaker: text"
+        action_items: &[String],
+        decisions: &[String],
+        linked_meetings: &[String],  // Summaries of earlier meetings this one follows up on
+    ) -> Result<String, SecondBrainError> {
+        // Build meeting context
+        let transcript_text = if transcript.is_empty() {
+            "No transcript available.".to_string()
+        } else {
+            transcript.join("\n")
+        };
+
+        let actions_text = if action_items.is_empty() {
+            "None recorded.".to_string()
+        } else {
+            action_items.iter()
+                .map(|a| format!("- {}", a))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let decisions_text = if decisions.is_empty() {
+            "None recorded.".to_string()
+        } else {
+            decisions.iter()
+                .map(|d| format!("- {}", d))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let linked_text = if linked_meetings.is_empty() {
+            String::new()
+        } else {
+            format!("\nLINKED PRIOR MEETINGS (background only):\n{}\n",
+                linked_meetings.iter().map(|m| format!("- {}", m)).collect::<Vec<_>>().join("\n"))
+        };
+
+        let prompt = format!(
+            r#"You are Second Brain, answering a question about a specific meeting.
+
+MEETING: {}
+
+TRANSCRIPT:
+{}
+
+ACTION ITEMS:
+{}
+
+DECISIONS:
+{}
+{}
+USER QUESTION: {}
+
+INSTRUCTIONS:
+- Answer based ONLY on this meeting's content, using linked prior meetings only as background
+- Be concise and direct
+- Quote specific parts of the transcript when relevant
+- If the answer isn't in this meeting, say so clearly
+- Use **bold** for speaker names and key terms
+
+ANSWER:"#,
+            meeting_title,
+            transcript_text,
+            actions_text,
+            decisions_text,
+            linked_text,
+            question
+        );
+
+        self.complete_for(LlmTask::Answer, None, prompt, question)
+            .await
+            .map_err(|e| e.context("Failed to get response"))
+    }
+
+    /// Answer a question about one person from their cross-meeting dossier
+    ///
+    /// Every fact in the prompt is tagged with the meeting it came from ([M1], [M2]...)
+    /// so the answer can cite them; those meetings are returned as sources.
+    pub async fn ask_about_person(
+        &self,
+        question: &str,
+        dossier: &PersonDossier,
+    ) -> Result<AnswerWithSources, SecondBrainError> {
+        if dossier.is_empty() {
+            return Ok(AnswerWithSources {
+                answer: format!("I don't have anything recorded about {} yet.", dossier.name),
+                sources: Vec::new(),
+            });
+        }
+
+        // Reference tags for each cited meeting, newest first
+        let meeting_refs: std::collections::HashMap<String, (String, &crate::knowledge_base::Meeting)> = dossier.meetings.iter()
+            .enumerate()
+            .filter_map(|(i, m)| {
+                let id = m.id.as_ref().map(|t| normalize_record_id("meeting", &t.to_string()))?;
+                Some((id, (format!("M{}", i + 1), m)))
+            })
+            .collect();
+        let tag = |meeting_id: Option<&str>| -> String {
+            meeting_id
+                .and_then(|id| meeting_refs.get(&normalize_record_id("meeting", id)))
+                .map(|(tag, _)| format!(" [{}]", tag))
+                .unwrap_or_default()
+        };
+
+        let mut sources = Vec::new();
+        let mut cited_meetings = std::collections::HashSet::new();
 
-        Ok(extract_text(&response.choice.first()))
-    }
+        let meetings_text = dossier.meetings.iter()
+            .enumerate()
+            .map(|(i, m)| format!("[M{}] {}", i + 1, m.title))
+            .collect::<Vec<_>>()
+            .join("\n");
 
-    /// Ask a question about a specific meeting
-    pub async fn ask_about_meeting(
-        &self,
-        question: &str,
-        meeting_title: &str,
-        transcript: &[String],  // Segments as "Speaker: text"
-        action_items: &[String],
-        decisions: &[String],
-    ) -> Result<String, String> {
-        // Build meeting context
-        let transcript_text = if transcript.is_empty() {
-            "No transcript available.".to_string()
+        let segments_text = if dossier.segments.is_empty() {
+            "None recorded.".to_string()
         } else {
-            transcript.join("\n")
+            dossier.segments.iter()
+                .map(|s| {
+                    let excerpt: String = s.text.chars().take(300).collect();
+                    if let Some((_, meeting)) = meeting_refs.get(&normalize_record_id("meeting", &s.meeting_id)) {
+                        sources.push(Source::meeting(meeting, Some(s), excerpt.clone()));
+                        cited_meetings.insert(s.meeting_id.clone());
+                    }
+                    format!("- {}: \"{}\"{}", s.speaker, excerpt, tag(Some(&s.meeting_id)))
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
         };
 
-        let actions_text = if action_items.is_empty() {
+        let relations_text = if dossier.relations.is_empty() {
             "None recorded.".to_string()
         } else {
-            action_items.iter()
-                .map(|a| format!("- {}", a))
+            dossier.relations.iter()
+                .map(|r| {
+                    let fact = if r.outgoing {
+                        format!("{} {} {} ({})", dossier.name, r.relation, r.other, r.other_type)
+                    } else {
+                        format!("{} ({}) {} {}", r.other, r.other_type, r.relation, dossier.name)
+                    };
+                    format!("- {}{}", fact, tag(r.meeting_id.as_deref()))
+                })
                 .collect::<Vec<_>>()
                 .join("\n")
         };
 
-        let decisions_text = if decisions.is_empty() {
-            "None recorded.".to_string()
+        let actions_text = if dossier.open_actions.is_empty() {
+            "None.".to_string()
         } else {
-            decisions.iter()
-                .map(|d| format!("- {}", d))
+            dossier.open_actions.iter()
+                .map(|a| {
+                    let deadline = a.deadline.as_ref().map(|d| format!(" (due {})", d)).unwrap_or_default();
+                    format!("- {}{}{}", a.text, deadline, tag(Some(&a.meeting_id)))
+                })
                 .collect::<Vec<_>>()
                 .join("\n")
         };
 
+        // Meetings cited only through relations or actions still count as sources
+        for m in &dossier.meetings {
+            let id = m.id.as_ref().map(|t| normalize_record_id("meeting", &t.to_string())).unwrap_or_default();
+            if !cited_meetings.contains(&id) {
+                sources.push(Source::meeting(m, None, String::new()));
+            }
+        }
+
+        let aliases = if dossier.aliases.is_empty() {
+            String::new()
+        } else {
+            format!(" (also known as {})", dossier.aliases.join(", "))
+        };
+        let topics = if dossier.topics.is_empty() { "None recorded.".to_string() } else { dossier.topics.join(", ") };
+        let people = if dossier.related_people.is_empty() { "None recorded.".to_string() } else { dossier.related_people.join(", ") };
+
         let prompt = format!(
-            r#"You are Second Brain, answering a question about a specific meeting.
+            r#"You are Second Brain, answering a question about one person across all of the user's meetings.
 
-MEETING: {}
+PERSON: {}{}
 
-TRANSCRIPT:
+MEETINGS:
 {}
 
-ACTION ITEMS:
+WHAT THEY SAID OR WHAT WAS SAID ABOUT THEM (newest first):
 {}
 
-DECISIONS:
+RELATIONSHIPS:
+{}
+
+TOPICS THEY ARE INVOLVED IN: {}
+
+PEOPLE THEY MEET WITH: {}
+
+OPEN ACTION ITEMS ASSIGNED TO THEM:
 {}
 
 USER QUESTION: {}
 
 INSTRUCTIONS:
-- Answer based ONLY on this meeting's content
-- Be concise and direct
-- Quote specific parts of the transcript when relevant
-- If the answer isn't in this meeting, say so clearly
-- Use **bold** for speaker names and key terms
+- Answer only about {} and only from the information above
+- Cite the meeting each fact came from with its tag, e.g. [M2]
+- Be concise and direct; use **bold** for names and key terms
+- If the information isn't here, say so clearly
 
 ANSWER:"#,
-            meeting_title,
-            transcript_text,
+            dossier.name,
+            aliases,
+            meetings_text,
+            segments_text,
+            relations_text,
+            topics,
+            people,
             actions_text,
-            decisions_text,
-            question
+            question,
+            dossier.name,
         );
 
-        let model = self.client.completion_model(&self.model);
-        let response = model.completion_request(prompt)
-            .send()
+        let answer = self.complete_for(LlmTask::Answer, None, prompt, question)
             .await
-            .map_err(|e| format!("Failed to get response: {}", e))?;
+            .map_err(|e| e.context("Failed to get response"))?;
 
-        Ok(extract_text(&response.choice.first()))
+        println!("[LLM] Person answer for {} built from {} sources", dossier.name, sources.len());
+        Ok(AnswerWithSources { answer, sources })
     }
 
-    /// Generate a meeting summary
+    /// Generate a meeting summary, written in `language` (a detected language code) when given
+    /// Transcripts that don't fit the model's context are summarized window by window, then
+    /// the window summaries are combined (repeatedly, if even they don't fit).
     pub async fn summarize_meeting(
         &self,
         segments: &[String],
-    ) -> Result<String, String> {
-        let combined = segments.join("\n\n");
-
-        let agent = self.client
-            .agent(&self.model)
-            .preamble(r#"
+        language: Option<&str>,
+    ) -> Result<MeetingSummary, SecondBrainError> {
+        let mut preamble = r#"
 You are a meeting summarizer. Given a transcript, create a concise summary that includes:
 
 1. **Key Topics Discussed** - Main subjects covered
@@ -919,15 +2520,71 @@ You are a meeting summarizer. Given a transcript, create a concise summary that
 4. **Open Questions** - Unresolved issues that need follow-up
 
 Be concise but comprehensive. Use bullet points for clarity.
-            "#)
-            .temperature(0.3)
-            .build();
+            "#.to_string();
+        if let Some(language) = language {
+            preamble.push_str(&format!(
+                "\nThe meeting was held mostly in {}. Write the summary in {}, keeping names and quoted terms as spoken.\n",
+                language_name(language),
+                language_name(language),
+            ));
+        }
+
+        let budget = self.task_token_budget(LlmTask::Summary, self.context_length().await)
+            .max(MIN_SUMMARY_WINDOW_TOKENS);
+        let windows = split_into_windows(segments, budget);
+        if windows.len() <= 1 {
+            let prompt = format!("Summarize this meeting transcript:\n\n{}", windows.concat());
+            let summary = self.complete(LlmTask::Summary, Some(&preamble), prompt)
+                .await
+                .map_err(|e| e.context("Failed to generate summary"))?;
+            return Ok(MeetingSummary { summary, sections: Vec::new() });
+        }
 
-        let prompt = format!("Summarize this meeting transcript:\n\n{}", combined);
-        let response = agent.prompt(prompt)
+        println!("[Summary] Transcript too long for one prompt, summarizing {} parts of up to {} tokens",
+            windows.len(), budget);
+        let mut sections = Vec::with_capacity(windows.len());
+        for (i, window) in windows.iter().enumerate() {
+            let prompt = format!(
+                "This is part {} of {} of a long meeting transcript. Summarize this part:\n\n{}",
+                i + 1,
+                windows.len(),
+                window,
+            );
+            let section = self.complete(LlmTask::Summary, Some(&preamble), prompt)
+                .await
+                .map_err(|e| e.context(format!("Failed to summarize part {} of the meeting", i + 1)))?;
+            sections.push(section);
+        }
+
+        // Merge neighbouring summaries until they fit one prompt; stop if a round doesn't shrink them
+        let mut partials = sections.clone();
+        loop {
+            let groups = split_into_windows(&numbered_parts(&partials), budget);
+            if groups.len() <= 1 || groups.len() >= partials.len() {
+                break;
+            }
+            println!("[Summary] Merging {} part summaries into {}", partials.len(), groups.len());
+            let mut merged = Vec::with_capacity(groups.len());
+            for group in groups {
+                let prompt = format!(
+                    "These are summaries of consecutive parts of one meeting, in order. Merge them into a single summary:\n\n{}",
+                    group,
+                );
+                merged.push(self.complete(LlmTask::Summary, Some(&preamble), prompt)
+                    .await
+                    .map_err(|e| e.context("Failed to merge part summaries"))?);
+            }
+            partials = merged;
+        }
+
+        let prompt = format!(
+            "These are summaries of consecutive parts of one long meeting, in order. Combine them into a single summary of the whole meeting:\n\n{}",
+            trim_to_token_budget(&numbered_parts(&partials), budget),
+        );
+        let summary = self.complete(LlmTask::Summary, Some(&preamble), prompt)
             .await
-            .map_err(|e| format!("Failed to generate summary: {}", e))?;
-        Ok(strip_thinking_tags(&response))
+            .map_err(|e| e.context("Failed to generate summary"))?;
+        Ok(MeetingSummary { summary, sections })
     }
 
     /// Process meeting after it ends - extract highlights, action items, decisions
@@ -935,7 +2592,7 @@ Be concise but comprehensive. Use bullet points for clarity.
         &self,
         segments: &[String],
         meeting_title: &str,
-    ) -> Result<MeetingHighlights, String> {
+    ) -> Result<MeetingHighlights, SecondBrainError> {
         if segments.is_empty() {
             return Ok(MeetingHighlights::default());
         }
@@ -969,13 +2626,9 @@ Start your response with {{ and end with }}. No explanations."#,
             combined
         );
 
-        let model = self.client.completion_model(&self.model);
-        let response = model.completion_request(prompt)
-            .send()
+        let response_text = self.complete(LlmTask::Extraction, None, prompt)
             .await
-            .map_err(|e| format!("Failed to process meeting: {}", e))?;
-
-        let response_text = extract_text(&response.choice.first());
+            .map_err(|e| e.context("Failed to process meeting"))?;
 
         // Extract JSON from response (handles LLMs that add text around JSON)
         let json_str = extract_json_from_response(&response_text);
@@ -983,7 +2636,7 @@ Start your response with {{ and end with }}. No explanations."#,
         println!("[MeetingHighlights] Extracted JSON: {}", &json_str[..json_str.len().min(200)]);
 
         // Parse JSON response
-        match serde_json::from_str::<MeetingHighlights>(&json_str) {
+        match parse_lenient_json::<MeetingHighlights>(&json_str) {
             Ok(highlights) => {
                 println!("[MeetingHighlights] Successfully parsed: {} topics, {} action items, {} decisions",
                     highlights.key_topics.len(),
@@ -1037,7 +2690,7 @@ Start your response with {{ and end with }}. No explanations."#,
         recent_transcript: &[String],  // Last few segments as "Speaker: text"
         meeting_context: Option<&str>,  // Optional meeting agenda/linked docs
         kb: Arc<RwLock<Option<KnowledgeBase>>>,
-    ) -> Result<RealtimeSuggestion, String> {
+    ) -> Result<RealtimeSuggestion, SecondBrainError> {
         let start = std::time::Instant::now();
 
         if recent_transcript.is_empty() {
@@ -1052,7 +2705,8 @@ Start your response with {{ and end with }}. No explanations."#,
             if let Some(kb_ref) = kb_guard.as_ref() {
                 // Use the last transcript segment as the query for context retrieval
                 let query = recent_transcript.last().map(|s| s.as_str()).unwrap_or("");
-                match kb_ref.graph_rag_query(query, 3).await {
+                let retrieval = RetrievalConfig { max_chunks: 3, ..self.retrieval.clone() };
+                match kb_ref.graph_rag_query(query, &retrieval).await {
                     Ok(ctx) => {
                         println!("[Realtime] Graph-RAG completed in {:?}", start.elapsed());
                         Some(ctx)
@@ -1124,6 +2778,15 @@ Start your response with {{ and end with }}. No explanations."#,
                 kb_context.push_str(&format!("RECENT DECISIONS:\n{}\n", decisions.join("\n")));
             }
 
+            // Pinned reference material (authoritative)
+            if !ctx.pinned_chunks.is_empty() {
+                let pinned: Vec<String> = ctx.pinned_chunks.iter()
+                    .take(2)
+                    .map(|r| format!("{}: {}", r.source_title, truncate_chars(&r.chunk.text, 150)))
+                    .collect();
+                kb_context.push_str(&format!("PINNED REFERENCE (authoritative):\n{}\n", pinned.join("\n")));
+            }
+
             // Similar knowledge chunks (documents)
             if !ctx.similar_chunks.is_empty() {
                 let docs: Vec<String> = ctx.similar_chunks.iter()
@@ -1166,37 +2829,347 @@ Be conversational and helpful, like a knowledgeable colleague whispering useful
 
         // Step 4: Get LLM response
         let llm_start = std::time::Instant::now();
-        let model = self.client.completion_model(&self.model);
-        let response = model.completion_request(prompt)
-            .send()
+        let response_text = self.complete(LlmTask::Suggestions, None, prompt)
             .await
-            .map_err(|e| format!("Failed to get suggestions: {}", e))?;
-
-        let response_text = extract_text(&response.choice.first());
+            .map_err(|e| e.context("Failed to get suggestions"))?;
         println!("[Realtime] LLM response in {:?}, total: {:?}", llm_start.elapsed(), start.elapsed());
 
-        // Parse JSON response
-        let json_str = extract_json_from_response(&response_text);
+        Ok(parse_suggestion(&response_text))
+    }
+
+    /// Realtime suggestions where the model may search transcripts, the knowledge base
+    /// or the web (not in offline mode) before answering
+    ///
+    /// At most `SUGGESTION_TOOL_CALL_BUDGET` tool calls are made and the whole run is
+    /// bounded by `SUGGESTION_AGENT_TIMEOUT`; on timeout or any error it falls back to
+    /// `generate_realtime_suggestions` so a suggestion still arrives in real time.
+    pub async fn generate_agent_suggestions(
+        &self,
+        recent_transcript: &[String],
+        meeting_context: Option<&str>,
+        kb: Arc<RwLock<Option<KnowledgeBase>>>,
+        offline: bool,
+    ) -> Result<RealtimeSuggestion, SecondBrainError> {
+        if recent_transcript.is_empty() {
+            return Ok(RealtimeSuggestion::default());
+        }
 
-        match serde_json::from_str::<RealtimeSuggestion>(&json_str) {
-            Ok(suggestion) => Ok(suggestion),
+        let start = std::time::Instant::now();
+        let run = self.agent_suggestions(recent_transcript, meeting_context, kb.clone(), offline);
+        match tokio::time::timeout(SUGGESTION_AGENT_TIMEOUT, run).await {
+            Ok(Ok(suggestion)) => {
+                println!("[Realtime] Agent suggestion in {:?}", start.elapsed());
+                Ok(suggestion)
+            }
+            Ok(Err(e)) => {
+                eprintln!("[Realtime] Agent suggestion failed ({}), using manual context", e);
+                self.generate_realtime_suggestions(recent_transcript, meeting_context, kb).await
+            }
             Err(_) => {
-                // Fallback: use response as insight
-                Ok(RealtimeSuggestion {
-                    insight: Some(response_text.lines().next().unwrap_or("").to_string()),
-                    question: None,
-                    related_info: None,
+                eprintln!("[Realtime] Agent suggestion timed out after {:?}, using manual context", SUGGESTION_AGENT_TIMEOUT);
+                self.generate_realtime_suggestions(recent_transcript, meeting_context, kb).await
+            }
+        }
+    }
+
+    async fn agent_suggestions(
+        &self,
+        recent_transcript: &[String],
+        meeting_context: Option<&str>,
+        kb: Arc<RwLock<Option<KnowledgeBase>>>,
+        offline: bool,
+    ) -> Result<RealtimeSuggestion, SecondBrainError> {
+        let tools = suggestion_tools(kb, offline);
+        let definitions = tools.get_tool_definitions().await
+            .map_err(|e| SecondBrainError::Other(format!("Failed to load tool definitions: {}", e)))?;
+
+        let agenda = meeting_context
+            .map(|ctx| format!("MEETING AGENDA:\n{}\n\n", ctx))
+            .unwrap_or_default();
+        let mut tool_results: Vec<String> = Vec::new();
+
+        for call in 0..=SUGGESTION_TOOL_CALL_BUDGET {
+            let budget_left = SUGGESTION_TOOL_CALL_BUDGET - call;
+            let findings = if tool_results.is_empty() {
+                String::new()
+            } else {
+                format!("SEARCH RESULTS:\n{}\n\n", tool_results.join("\n\n"))
+            };
+            let prompt = format!(
+                r#"You are a helpful meeting assistant listening to a live conversation.
+{}{}CURRENT CONVERSATION:
+{}
+
+{}Then respond with a JSON object:
+{{
+  "insight": "One helpful observation connecting the discussion to past context or facts (1-2 sentences, conversational tone)",
+  "question": "A question they could ask to clarify or advance the discussion (or null)",
+  "related_info": "Brief mention of relevant context you found (or null)"
+}}"#,
+                agenda,
+                findings,
+                recent_transcript.join("\n"),
+                if budget_left > 0 {
+                    format!("If the discussion references something you need to look up, you may call one tool ({} call(s) left). Only search when it clearly helps.\n", budget_left)
+                } else {
+                    "Do not call any more tools.\n".to_string()
+                },
+            );
+
+            let tools_offered = if budget_left > 0 { definitions.clone() } else { Vec::new() };
+            match self.complete_with_tools(LlmTask::Suggestions, prompt, tools_offered).await? {
+                ModelTurn::Text(text) => return Ok(parse_suggestion(&text)),
+                ModelTurn::ToolCall { name, arguments } => {
+                    println!("[Realtime] Agent calling {} ({} left)", name, budget_left - 1);
+                    let output = tools.call(&name, arguments.to_string()).await
+                        .unwrap_or_else(|e| format!("Tool failed: {}", e));
+                    tool_results.push(format!("{} {}:\n{}", name, arguments, truncate_chars(&output, SUGGESTION_TOOL_OUTPUT_CHARS)));
+                }
+            }
+        }
+
+        Err(SecondBrainError::Llm("Tool call budget exhausted without an answer".to_string()))
+    }
+
+    /// One completion that may come back as a tool call instead of text
+    /// Only rig-backed providers support tools; Ollama errors so callers fall back.
+    async fn complete_with_tools(
+        &self,
+        task: LlmTask,
+        prompt: String,
+        tools: Vec<ToolDefinition>,
+    ) -> Result<ModelTurn, SecondBrainError> {
+        let params = self.generation.params(task);
+        let sent = prompt.clone();
+        let (turn, usage) = self.with_failover(|endpoint| {
+            let prompt = prompt.clone();
+            let tools = tools.clone();
+            async move { endpoint.complete_with_tools(prompt, tools, params).await }
+        }).await?;
+        let usage = usage.unwrap_or_else(|| match &turn {
+            ModelTurn::Text(text) => TokenUsage::estimate(&sent, text),
+            ModelTurn::ToolCall { arguments, .. } => TokenUsage::estimate(&sent, &arguments.to_string()),
+        });
+        self.record_usage(task, usage);
+        Ok(turn)
+    }
+
+    /// Detect explicit commitments ("I'll send the deck by Friday") in the recent transcript
+    /// `known_tasks` are the meeting's stored action items, so the model can skip repeats.
+    pub async fn detect_commitments(
+        &self,
+        recent_transcripts: &[String],
+        known_tasks: &[String],
+    ) -> Result<Vec<ExtractedActionItem>, SecondBrainError> {
+        if recent_transcripts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        #[derive(Deserialize)]
+        struct Commitments {
+            #[serde(default)]
+            action_items: Vec<ExtractedActionItem>,
+        }
+
+        let known = if known_tasks.is_empty() {
+            "None".to_string()
+        } else {
+            known_tasks.iter().map(|t| format!("- {}", t)).collect::<Vec<_>>().join("\n")
+        };
+
+        let prompt = format!(
+            r#"Find explicit commitments in this live meeting transcript: someone promising or being asked to do a specific task.
+
+TRANSCRIPT (most recent last, "Speaker: text"):
+{}
+
+ALREADY RECORDED (do not repeat these):
+{}
+
+Rules:
+- Only include concrete tasks someone agreed to do ("I'll send the deck by Friday", "Can you book the room? Sure").
+- Ignore ideas, opinions, and vague intentions ("we should think about...").
+- assignee is the person who will do it: the speaker label when they commit themselves, or the name they address. null if unclear.
+- deadline is the stated due time in the speaker's words ("Friday", "end of month"), or null.
+
+Return ONLY JSON, no other text:
+{{"action_items": [{{"task": "short imperative description", "assignee": "name or null", "deadline": "deadline or null"}}]}}
+Return {{"action_items": []}} if there are none."#,
+            recent_transcripts.join("\n"),
+            known
+        );
+
+        let response_text = self.complete(LlmTask::Commitments, None, prompt)
+            .await
+            .map_err(|e| e.context("Failed to detect commitments"))?;
+
+        let json_str = extract_json_from_response(&response_text);
+        let commitments = parse_lenient_json::<Commitments>(&json_str)
+            .map_err(|e| SecondBrainError::Llm(format!("Failed to parse commitments: {}", e)))?;
+
+        Ok(commitments.action_items.into_iter()
+            .filter(|item| !item.task.trim().is_empty())
+            .map(|item| ExtractedActionItem {
+                task: item.task.trim().to_string(),
+                assignee: item.assignee.filter(|a| !a.trim().is_empty() && a != "null"),
+                deadline: item.deadline.filter(|d| !d.trim().is_empty() && d != "null"),
+            })
+            .collect())
+    }
+
+    /// Turn gathered meetings, decisions and actions into a prioritized catch-up briefing
+    ///
+    /// `material` holds every item found since `material.since`; the LLM reorders and
+    /// trims each section and writes the overview. If the response can't be parsed the
+    /// unprioritized material is returned as is.
+    pub async fn catch_up_summary(&self, material: CatchUpSummary) -> Result<CatchUpSummary, SecondBrainError> {
+        if material.is_empty() {
+            return Ok(CatchUpSummary {
+                overview: Some("Nothing new since then: no meetings, decisions or open action items.".to_string()),
+                ..material
+            });
+        }
+
+        let format_items = |items: &[CatchUpItem]| -> String {
+            if items.is_empty() {
+                return "None".to_string();
+            }
+            items.iter()
+                .map(|i| {
+                    let mut line = format!("- {}", i.text);
+                    if let Some(ref title) = i.meeting_title {
+                        line.push_str(&format!(" [meeting: {}]", title));
+                    }
+                    if let Some(ref detail) = i.detail {
+                        line.push_str(&format!(" ({})", detail));
+                    }
+                    line
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let prompt = format!(
+            r#"The user is catching up after time away. Brief them on what happened since they left.
+
+MEETINGS ({} total, with summaries):
+{}
+
+NEW DECISIONS:
+{}
+
+OPEN ACTION ITEMS ASSIGNED TO THE USER (or unassigned):
+{}
+
+Rules:
+- Order every list by importance to the user, most important first.
+- Keep decisions and actions close to their original wording; keep meeting_title as given.
+- notable_discussions: at most 5 meetings worth knowing about, with detail saying why it matters.
+- overview: 2-4 sentences, the things they must know first.
+- Use only the information above.
+
+Return ONLY JSON, no other text:
+{{"overview": "...", "new_decisions": [{{"text": "...", "meeting_title": "..."}}], "pending_actions": [{{"text": "...", "meeting_title": "...", "detail": "assignee/deadline"}}], "notable_discussions": [{{"text": "meeting summary", "meeting_title": "...", "detail": "why it matters"}}]}}"#,
+            material.meeting_count,
+            format_items(&material.notable_discussions),
+            format_items(&material.new_decisions),
+            format_items(&material.pending_actions),
+        );
+
+        let response_text = self.complete(LlmTask::CatchUp, None, prompt)
+            .await
+            .map_err(|e| e.context("Failed to build catch-up summary"))?;
+
+        let json_str = extract_json_from_response(&response_text);
+        match parse_lenient_json::<CatchUpSummary>(&json_str) {
+            Ok(briefing) => {
+                println!("[CatchUp] Briefing: {} decisions, {} actions, {} discussions",
+                    briefing.new_decisions.len(),
+                    briefing.pending_actions.len(),
+                    briefing.notable_discussions.len());
+                Ok(CatchUpSummary {
+                    since: material.since,
+                    meeting_count: material.meeting_count,
+                    ..briefing
                 })
             }
+            Err(e) => {
+                eprintln!("[CatchUp] Could not parse briefing ({}), returning unprioritized sections", e);
+                Ok(material)
+            }
         }
     }
 
+    /// Diff two meetings on the same topic: new decisions, changed commitments,
+    /// resolved vs still-open items and new people/topics
+    pub async fn compare_meetings(&self, earlier: &MeetingDigest, later: &MeetingDigest) -> Result<MeetingDiff, SecondBrainError> {
+        let format_list = |items: &[String]| -> String {
+            if items.is_empty() {
+                return "None".to_string();
+            }
+            items.iter().map(|i| format!("- {}", i)).collect::<Vec<_>>().join("\n")
+        };
+        let format_meeting = |label: &str, m: &MeetingDigest| -> String {
+            format!(
+                "{} MEETING: {}\nSUMMARY: {}\nKEY TOPICS: {}\nDECISIONS:\n{}\nACTION ITEMS:\n{}\nTRANSCRIPT:\n{}",
+                label,
+                m.title,
+                m.summary.as_deref().unwrap_or("None"),
+                if m.key_topics.is_empty() { "None".to_string() } else { m.key_topics.join(", ") },
+                format_list(&m.decisions),
+                format_list(&m.action_items),
+                truncate_chars(&m.transcript.join("\n"), COMPARE_TRANSCRIPT_CHARS),
+            )
+        };
+
+        let prompt = format!(
+            r#"Compare two instances of a recurring meeting and report what changed from the earlier one to the later one.
+
+{}
+
+{}
+
+Rules:
+- new_decisions: decisions made in the later meeting that were not already made earlier.
+- changed_commitments: commitments from earlier whose owner, deadline or scope changed later.
+- resolved_items: earlier action items or open questions the later meeting closed or reported done.
+- still_open_items: earlier action items or open questions that are still unresolved.
+- new_people / new_topics: people and topics in the later meeting that the earlier one did not mention.
+- overview: 1-3 sentences on what is new since last time.
+- Use only the information above; leave a list empty if nothing applies.
+
+Return ONLY JSON, no other text:
+{{"overview": "...", "new_decisions": ["..."], "changed_commitments": ["..."], "resolved_items": ["..."], "still_open_items": ["..."], "new_people": ["..."], "new_topics": ["..."]}}"#,
+            format_meeting("EARLIER", earlier),
+            format_meeting("LATER", later),
+        );
+
+        let response_text = self.complete(LlmTask::Extraction, None, prompt)
+            .await
+            .map_err(|e| e.context("Failed to compare meetings"))?;
+
+        let json_str = extract_json_from_response(&response_text);
+        let diff = parse_lenient_json::<MeetingDiff>(&json_str)
+            .map_err(|e| SecondBrainError::Llm(format!("Could not parse meeting comparison: {}", e)))?;
+        println!("[Compare] {} new decisions, {} changed, {} resolved, {} still open",
+            diff.new_decisions.len(),
+            diff.changed_commitments.len(),
+            diff.resolved_items.len(),
+            diff.still_open_items.len());
+
+        Ok(MeetingDiff {
+            earlier_meeting_id: earlier.meeting_id.clone(),
+            later_meeting_id: later.meeting_id.clone(),
+            ..diff
+        })
+    }
+
     /// Suggest questions to ask based on the current discussion
     pub async fn suggest_questions(
         &self,
         current_topic: &str,
         kb: Arc<RwLock<Option<KnowledgeBase>>>,
-    ) -> Result<Vec<String>, String> {
+    ) -> Result<Vec<String>, SecondBrainError> {
         // Get relevant context from knowledge base
         let context = {
             let kb_guard = kb.read().await;
@@ -1241,14 +3214,9 @@ Return ONLY a numbered list of questions, nothing else."#,
             )
         };
 
-        let model = self.client.completion_model(&self.model);
-        let response_result = model
-            .completion_request(prompt)
-            .send()
+        let response = self.complete(LlmTask::Questions, None, prompt)
             .await
-            .map_err(|e| format!("Failed to generate questions: {}", e))?;
-
-        let response = extract_text(&response_result.choice.first());
+            .map_err(|e| e.context("Failed to generate questions"))?;
 
         // Parse numbered list
         let questions: Vec<String> = response
@@ -1270,13 +3238,18 @@ Return ONLY a numbered list of questions, nothing else."#,
         Ok(questions)
     }
 
+    /// Whether the configured model is expected to understand images
+    pub fn supports_vision(&self) -> bool {
+        model_supports_vision(&self.endpoint().model)
+    }
+
     /// Ask a question with an image (for screenshot analysis)
     /// Requires a vision-capable model (GPT-4V, Claude 3, LLaVA, etc.)
     pub async fn ask_with_image(
         &self,
         question: &str,
         image_data_url: &str,
-    ) -> Result<String, String> {
+    ) -> Result<String, SecondBrainError> {
         // For OpenAI-compatible APIs with vision support, we need to send the image
         // as part of a chat completion request with image_url content
         //
@@ -1312,15 +3285,10 @@ Be concise but thorough in your analysis."#,
         // Try to use the completion API
         // Note: For full vision support, you may need to use a raw HTTP request
         // to the vision endpoint with the proper multimodal format
-        let model = self.client.completion_model(&self.model);
 
         // For now, we'll try to send the image data URL in the prompt
         // Some local models (LLaVA) can handle this format
-        let full_prompt = if self.model.contains("llava")
-            || self.model.contains("vision")
-            || self.model.contains("gpt-4")
-            || self.model.contains("claude")
-        {
+        let full_prompt = if self.supports_vision() {
             // For vision models, include the actual image data
             format!(
                 "{}\n\n<image src=\"{}\" />",
@@ -1344,23 +3312,257 @@ Please respond helpfully."#,
             )
         };
 
-        let response = model
-            .completion_request(full_prompt)
-            .send()
+        self.complete_for(LlmTask::Answer, None, full_prompt, question)
             .await
-            .map_err(|e| format!("Failed to analyze image: {}", e))?;
-
-        Ok(extract_text(&response.choice.first()))
+            .map_err(|e| e.context("Failed to analyze image"))
     }
 }
 
+/// Best-effort guess from the model name; there is no portable capability API
+pub fn model_supports_vision(model: &str) -> bool {
+    let model = model.to_lowercase();
+    const VISION_MARKERS: &[&str] = &[
+        "llava", "vision", "gpt-4o", "gpt-4-turbo", "gpt-4.1", "claude-3", "claude-sonnet",
+        "claude-opus", "claude-haiku", "-vl", "pixtral", "minicpm-v", "moondream", "gemma3",
+    ];
+    VISION_MARKERS.iter().any(|marker| model.contains(marker))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_model_supports_vision() {
+        assert!(model_supports_vision("gpt-4o-mini"));
+        assert!(model_supports_vision("llama3.2-vision:11b"));
+        assert!(model_supports_vision("Qwen2.5-VL-7B"));
+        assert!(!model_supports_vision("llama3.1:8b"));
+        assert!(!model_supports_vision("gpt-3.5-turbo"));
+    }
+
+    #[test]
+    fn test_suggestion_cadence() {
+        let cadence = SuggestionCadence::default();
+        let fired: Vec<u64> = (1..=7).filter(|&seq| cadence.should_suggest(seq, false, Some(10_000))).collect();
+        assert_eq!(fired, vec![1, 3, 6]);
+        assert!(cadence.should_suggest(4, true, None));
+
+        let quiet = SuggestionCadence { every_n: 0, on_turn_complete: false, min_interval_ms: 30_000, ..cadence };
+        assert!(quiet.should_suggest(1, false, None));
+        assert!(!quiet.should_suggest(3, true, None));
+
+        let throttled = SuggestionCadence { min_interval_ms: 30_000, ..cadence };
+        assert!(!throttled.should_suggest(3, true, Some(5_000)));
+        assert!(throttled.should_suggest(3, false, Some(30_000)));
+
+        let off = SuggestionCadence { enabled: false, ..cadence };
+        assert!(!off.should_suggest(1, true, None));
+    }
+
+    #[test]
+    fn test_is_assigned_to_me() {
+        let names = vec!["Dana Lee".to_string()];
+        assert!(is_assigned_to_me(None, &names));
+        assert!(is_assigned_to_me(Some("null"), &names));
+        assert!(is_assigned_to_me(Some("You"), &names));
+        assert!(is_assigned_to_me(Some(" dana lee "), &names));
+        assert!(!is_assigned_to_me(Some("Sam"), &names));
+    }
+
+    #[test]
+    fn test_trim_to_token_budget_keeps_whole_lines() {
+        let parts = vec![
+            "## Entities\nAcme (org)\n".to_string(),
+            "## Related Meetings\n**Kickoff** (2 days ago)\n  - Dana: \"We ship in May\"\n".to_string(),
+            "## Documents\nA long document excerpt\n".to_string(),
+        ];
+        assert_eq!(trim_to_token_budget(&parts, 10_000), parts.join("\n"));
+
+        let budget = estimate_tokens(&parts[0]) + 1 + estimate_tokens("## Related Meetings") + estimate_tokens("**Kickoff** (2 days ago)") + 2;
+        let trimmed = trim_to_token_budget(&parts, budget);
+        assert!(trimmed.contains("**Kickoff**"));
+        assert!(!trimmed.contains("We ship in May"));
+        assert!(!trimmed.contains("## Documents"));
+
+        // Only a heading would fit: drop the section entirely
+        assert_eq!(trim_to_token_budget(&parts, estimate_tokens(&parts[0]) + 2), parts[0]);
+    }
+
+    #[test]
+    fn test_split_into_windows_packs_in_order() {
+        let parts: Vec<String> = (0..6).map(|i| format!("Speaker {}: {}", i, "word ".repeat(10))).collect();
+        let per_part = estimate_tokens(&parts[0]) + 1;
+
+        let windows = split_into_windows(&parts, per_part * 2);
+        assert_eq!(windows.len(), 3);
+        assert!(windows[0].starts_with("Speaker 0") && windows[0].contains("Speaker 1"));
+        assert!(windows[2].ends_with(parts[5].as_str()));
+        assert!(windows.iter().all(|w| estimate_tokens(w) <= per_part * 2));
+
+        assert_eq!(split_into_windows(&parts, 10_000), vec![parts.join("\n\n")]);
+        assert!(split_into_windows(&[], 100).is_empty());
+    }
+
+    #[test]
+    fn test_split_into_windows_cuts_oversized_parts() {
+        let long = "x".repeat(1000);
+        let windows = split_into_windows(std::slice::from_ref(&long), 50);
+        assert_eq!(windows.len(), 5);
+        assert_eq!(windows.concat(), long);
+    }
+
+    #[test]
+    fn test_generation_settings_override_defaults() {
+        let settings = GenerationSettings::from_config(
+            r#"{"extraction": {"temperature": 0.0}, "questions": {"top_p": 0.9, "max_tokens": 300}}"#,
+        ).unwrap();
+
+        let extraction = settings.params(LlmTask::Extraction);
+        assert_eq!(extraction.temperature, Some(0.0));
+        assert_eq!(extraction.max_tokens, None);
+
+        let questions = settings.params(LlmTask::Questions);
+        assert_eq!(questions.temperature, LlmTask::Questions.default_params().temperature);
+        assert_eq!(questions.top_p, Some(0.9));
+        assert_eq!(questions.max_tokens, Some(300));
+
+        assert_eq!(settings.params(LlmTask::Commitments), LlmTask::Commitments.default_params());
+        assert_eq!(GenerationSettings::from_config("").unwrap(), GenerationSettings::default());
+    }
+
+    #[test]
+    fn test_generation_settings_rejects_bad_params() {
+        assert!(GenerationSettings::from_config("not json").is_err());
+        assert!(GenerationSettings::from_config(r#"{"brainstorm": {}}"#).is_err());
+        assert!(GenerationSettings::from_config(r#"{"answer": {"temperature": 3.0}}"#).is_err());
+        assert!(GenerationSettings::from_config(r#"{"answer": {"top_p": 0.0}}"#).is_err());
+        assert!(GenerationSettings::from_config(r#"{"answer": {"max_tokens": 0}}"#).is_err());
+    }
+
+    #[test]
+    fn test_llm_fallbacks_config() {
+        let endpoints = LlmEndpoint::list_from_config(
+            r#"[{"provider": "ollama", "model": "llama3"}, {"url": "https://api.openai.com/v1", "model": "gpt-4o", "api_key": "sk-test"}]"#,
+        ).unwrap();
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!(endpoints[0].provider, LlmProvider::Ollama);
+        assert_eq!(endpoints[1].provider, LlmProvider::OpenAI);
+
+        assert!(LlmEndpoint::list_from_config("").unwrap().is_empty());
+        assert!(LlmEndpoint::list_from_config(r#"[{"model": ""}]"#).is_err());
+        // OpenAI-compatible endpoints have no default URL
+        assert!(LlmEndpoint::list_from_config(r#"[{"model": "gpt-4o"}]"#).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_is_connection_error() {
+        // Nothing listens on port 1
+        let refused = reqwest::Client::new().get("http://127.0.0.1:1/v1/models").send().await.unwrap_err();
+        assert!(is_connection_error(&refused));
+        assert!(matches!(request_error("Request failed", refused), SecondBrainError::Network(_)));
+
+        assert!(matches!(status_error("Server returned 503".to_string(), 503), SecondBrainError::Network(_)));
+        assert!(matches!(status_error("Server returned 404".to_string(), 404), SecondBrainError::Llm(_)));
+        assert!(matches!(
+            completion_error(CompletionError::ProviderError("invalid model 'gpt-5'".to_string())),
+            SecondBrainError::Llm(_)
+        ));
+    }
+
+    fn parse_highlights(response: &str) -> MeetingHighlights {
+        parse_lenient_json(&extract_json_from_response(response)).unwrap()
+    }
+
+    #[test]
+    fn test_json_in_markdown_fence() {
+        let highlights = parse_highlights(
+            "```json\n{\"summary\": \"Planned the launch\", \"key_topics\": [\"launch\"]}\n```",
+        );
+        assert_eq!(highlights.summary.as_deref(), Some("Planned the launch"));
+        assert_eq!(highlights.key_topics, vec!["launch"]);
+    }
+
+    #[test]
+    fn test_json_after_leading_prose_with_trailing_commas() {
+        let highlights = parse_highlights(
+            "Here is the JSON:\n{\"summary\": \"Budget {draft} review\", \"decisions\": [\"Cut travel\",],}\nLet me know!",
+        );
+        assert_eq!(highlights.summary.as_deref(), Some("Budget {draft} review"));
+        assert_eq!(highlights.decisions, vec!["Cut travel"]);
+    }
+
+    #[test]
+    fn test_truncated_json_is_closed() {
+        let highlights = parse_highlights(
+            "<think>long reasoning</think>{\"summary\": \"Hiring sync\", \"key_topics\": [\"hiring\", \"onboard",
+        );
+        assert_eq!(highlights.summary.as_deref(), Some("Hiring sync"));
+        assert_eq!(highlights.key_topics, vec!["hiring", "onboard"]);
+
+        // A half-written key is dropped back to the last complete member
+        let highlights = parse_highlights("{\"summary\": \"Hiring sync\", \"key_to");
+        assert_eq!(highlights.summary.as_deref(), Some("Hiring sync"));
+        assert!(highlights.key_topics.is_empty());
+    }
+
+    #[test]
+    fn test_raw_newlines_in_strings_repaired() {
+        let highlights = parse_highlights("{\"summary\": \"Line one\nline two\"}");
+        assert_eq!(highlights.summary.as_deref(), Some("Line one\nline two"));
+    }
+
+    #[test]
+    fn test_unrepairable_json_errors() {
+        assert!(parse_lenient_json::<MeetingHighlights>("no json here").is_err());
+    }
+
+    #[test]
+    fn test_is_local_url() {
+        assert!(is_local_url("http://localhost:11434"));
+        assert!(is_local_url("http://127.0.0.1:1234/v1"));
+        assert!(is_local_url("http://[::1]:8080"));
+        assert!(is_local_url("localhost:1234"));
+        assert!(!is_local_url("https://api.anthropic.com"));
+        assert!(!is_local_url("https://lmstudio.example.com/llm/v1"));
+        assert!(!is_local_url("http://192.168.1.20:11434"));
+        assert!(!is_local_url(""));
+    }
+
     #[test]
     fn test_search_args_default() {
         let args: SearchTranscriptsArgs = serde_json::from_str(r#"{"query": "test"}"#).unwrap();
         assert_eq!(args.limit, 5);
     }
+
+    #[test]
+    fn test_parse_suggestion_falls_back_to_first_line() {
+        let parsed = parse_suggestion("```json\n{\"insight\": \"Ask about Q3\", \"question\": null}\n```");
+        assert_eq!(parsed.insight.as_deref(), Some("Ask about Q3"));
+
+        let fallback = parse_suggestion("They already agreed on this last week.\nMore text");
+        assert_eq!(fallback.insight.as_deref(), Some("They already agreed on this last week."));
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("héllo wörld", 5), "héllo...");
+        assert_eq!(truncate_chars("short", 10), "short");
+    }
+
+    #[test]
+    fn test_prompt_template_validation_and_render() {
+        assert!(validate_prompt_template("Be terse.\n\n{context}").is_ok());
+        assert!(validate_prompt_template("Answer in Spanish: {question}\n{context}").is_ok());
+        assert!(validate_prompt_template("Answer {question} tersely").is_err());
+        assert!(validate_prompt_template("{context} for {user}").is_err());
+        assert!(validate_prompt_template("  ").is_err());
+
+        let rendered = render_prompt_template(
+            "Q: {question}\n{context}\nAlways answer in Spanish.",
+            "Transcript says {question} literally",
+            "What was decided?",
+        );
+        assert_eq!(rendered, "Q: What was decided?\nTranscript says {question} literally\nAlways answer in Spanish.");
+    }
 }
@@ -36,6 +36,22 @@ pub struct ModelStatus {
     pub size_bytes: u64,
 }
 
+/// On-disk size of one model
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelDiskUsage {
+    pub id: String,
+    pub name: String,
+    pub installed: bool,
+    pub bytes: u64,
+}
+
+/// Per-model sizes plus the total, for freeing disk space
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelsDiskUsage {
+    pub models: Vec<ModelDiskUsage>,
+    pub total_bytes: u64,
+}
+
 /// Get the models directory path
 pub fn get_models_dir() -> PathBuf {
     let data_dir = dirs::data_local_dir()
@@ -150,6 +166,55 @@ pub fn get_required_models() -> Vec<ModelInfo> {
     ]
 }
 
+/// Id of the optional wake word (keyword spotting) model
+pub const WAKE_WORD_MODEL_ID: &str = "wake-word-kws";
+/// Directory the wake word (keyword spotting) model extracts to
+pub const WAKE_WORD_MODEL_DIR: &str = "sherpa-onnx-kws-zipformer-gigaspeech-3.3M-2024-01-01";
+
+/// Ids of the OCR models, downloaded on demand for screenshot text extraction
+pub const OCR_MODEL_IDS: &[&str] = &["ocr-detection", "ocr-recognition"];
+
+/// Models only needed by opt-in features; not part of `all_models_installed`
+pub fn get_optional_models() -> Vec<ModelInfo> {
+    vec![
+        // OCR text detection model (ocrs ~2.5MB), used when the LLM can't read screenshots
+        ModelInfo {
+            id: "ocr-detection".to_string(),
+            name: "OCR Text Detection".to_string(),
+            url: "https://ocrs-models.s3-accelerate.amazonaws.com/text-detection.rten".to_string(),
+            size_bytes: 2_500_000,
+            filename: "ocr-text-detection.rten".to_string(),
+            is_archive: false,
+        },
+        // OCR text recognition model (ocrs ~10MB)
+        ModelInfo {
+            id: "ocr-recognition".to_string(),
+            name: "OCR Text Recognition".to_string(),
+            url: "https://ocrs-models.s3-accelerate.amazonaws.com/text-recognition.rten".to_string(),
+            size_bytes: 10_000_000,
+            filename: "ocr-text-recognition.rten".to_string(),
+            is_archive: false,
+        },
+        // Streaming zipformer keyword spotter (English, ~3.3M params) for the wake word
+        ModelInfo {
+            id: WAKE_WORD_MODEL_ID.to_string(),
+            name: "Wake Word Spotter (Zipformer KWS)".to_string(),
+            url: format!("https://github.com/k2-fsa/sherpa-onnx/releases/download/kws-models/{}.tar.bz2", WAKE_WORD_MODEL_DIR),
+            size_bytes: 14_000_000,
+            filename: format!("{}.tar.bz2", WAKE_WORD_MODEL_DIR),
+            is_archive: true,
+        },
+    ]
+}
+
+/// Look up a required or optional model by id
+pub fn find_model(model_id: &str) -> Option<ModelInfo> {
+    get_required_models()
+        .into_iter()
+        .chain(get_optional_models())
+        .find(|m| m.id == model_id)
+}
+
 /// Check if a model is installed
 pub fn is_model_installed(model: &ModelInfo) -> bool {
     let models_dir = get_models_dir();
@@ -167,6 +232,10 @@ pub fn is_model_installed(model: &ModelInfo) -> bool {
                 // Pyannote segmentation model
                 models_dir.join("sherpa-onnx-pyannote-segmentation-3-0").join("model.onnx").exists()
             }
+            "wake-word-kws" => {
+                // Zipformer transducer files plus the token table keywords are encoded with
+                models_dir.join(WAKE_WORD_MODEL_DIR).join("tokens.txt").exists()
+            }
             _ => false,
         }
     } else {
@@ -192,6 +261,90 @@ pub fn all_models_installed() -> bool {
     get_required_models().iter().all(|m| is_model_installed(m))
 }
 
+/// Files and directories a model occupies once installed
+/// Archives live in the directory they extract to (named after the archive), plus
+/// any leftover archive or partial download.
+fn model_paths(model: &ModelInfo) -> Vec<PathBuf> {
+    let models_dir = get_models_dir();
+    let mut paths = vec![
+        models_dir.join(&model.filename),
+        models_dir.join(format!("{}.tmp", model.filename)),
+    ];
+    if model.is_archive {
+        if let Some(dir) = model.filename.strip_suffix(".tar.bz2") {
+            paths.push(models_dir.join(dir));
+        }
+    }
+    paths
+}
+
+/// Total size of a file, or of everything under a directory (0 if missing)
+pub fn path_size(path: &std::path::Path) -> std::io::Result<u64> {
+    if !path.exists() {
+        return Ok(0);
+    }
+    let meta = std::fs::metadata(path)?;
+    if !meta.is_dir() {
+        return Ok(meta.len());
+    }
+    let mut total = 0;
+    for entry in std::fs::read_dir(path)? {
+        total += path_size(&entry?.path())?;
+    }
+    Ok(total)
+}
+
+/// Bytes each model actually uses on disk
+pub fn get_models_disk_usage() -> ModelsDiskUsage {
+    let models: Vec<ModelDiskUsage> = get_required_models()
+        .into_iter()
+        .chain(get_optional_models())
+        .map(|model| ModelDiskUsage {
+            bytes: model_paths(&model).iter().map(|p| path_size(p).unwrap_or(0)).sum(),
+            installed: is_model_installed(&model),
+            id: model.id,
+            name: model.name,
+        })
+        .collect();
+    let total_bytes = models.iter().map(|m| m.bytes).sum();
+    ModelsDiskUsage { models, total_bytes }
+}
+
+/// Remove a model's files; returns the bytes freed
+/// Callers must make sure no engine still has the model loaded.
+pub fn delete_model(model_id: &str) -> Result<u64, String> {
+    let model = find_model(model_id)
+        .ok_or_else(|| format!("Unknown model: {}", model_id))?;
+
+    let mut freed = 0;
+    for path in model_paths(&model) {
+        if !path.exists() {
+            continue;
+        }
+        freed += path_size(&path).unwrap_or(0);
+        let removed = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else {
+            std::fs::remove_file(&path)
+        };
+        removed.map_err(|e| format!("Failed to delete {}: {}", path.display(), e))?;
+    }
+
+    println!("Deleted model {} ({} bytes freed)", model.name, freed);
+    Ok(freed)
+}
+
+/// Emit `download-progress` and forward it to the progress channel
+fn emit_download_progress(app: &AppHandle, progress: DownloadProgress) {
+    crate::send_progress(app, crate::ProgressEvent::new(
+        "download_models",
+        progress.downloaded_bytes,
+        progress.total_bytes,
+        Some(format!("{}: {}", progress.model_name, progress.status)),
+    ));
+    let _ = app.emit("download-progress", progress);
+}
+
 /// Download a model with progress reporting
 pub async fn download_model(
     app: AppHandle,
@@ -210,7 +363,7 @@ pub async fn download_model(
     let total_size = response.content_length().unwrap_or(model.size_bytes);
 
     // Emit initial progress
-    let _ = app.emit("download-progress", DownloadProgress {
+    emit_download_progress(&app, DownloadProgress {
         model_id: model.id.clone(),
         model_name: model.name.clone(),
         downloaded_bytes: 0,
@@ -237,7 +390,7 @@ pub async fn download_model(
 
         // Emit progress every ~1%
         if (progress as u32) % 1 == 0 {
-            let _ = app.emit("download-progress", DownloadProgress {
+            emit_download_progress(&app, DownloadProgress {
                 model_id: model.id.clone(),
                 model_name: model.name.clone(),
                 downloaded_bytes: downloaded,
@@ -252,7 +405,7 @@ pub async fn download_model(
 
     // Handle archive extraction
     if model.is_archive {
-        let _ = app.emit("download-progress", DownloadProgress {
+        emit_download_progress(&app, DownloadProgress {
             model_id: model.id.clone(),
             model_name: model.name.clone(),
             downloaded_bytes: total_size,
@@ -271,7 +424,7 @@ pub async fn download_model(
     }
 
     // Emit completion
-    let _ = app.emit("download-progress", DownloadProgress {
+    emit_download_progress(&app, DownloadProgress {
         model_id: model.id.clone(),
         model_name: model.name.clone(),
         downloaded_bytes: total_size,
@@ -0,0 +1,74 @@
+//! Screen text extraction (OCR) module using ocrs
+//!
+//! Fallback for screenshot analysis when the configured LLM cannot read images:
+//! the screen is run through text detection + recognition models and the
+//! resulting text is handed to the regular text-only `ask` flow.
+
+use ocrs::{ImageSource, OcrEngine, OcrEngineParams};
+use rten::Model;
+use std::path::Path;
+
+/// Detection model filename in the models directory
+pub const OCR_DETECTION_MODEL: &str = "ocr-text-detection.rten";
+/// Recognition model filename in the models directory
+pub const OCR_RECOGNITION_MODEL: &str = "ocr-text-recognition.rten";
+
+/// Text extraction engine for screenshots
+pub struct ScreenOcr {
+    engine: OcrEngine,
+}
+
+impl ScreenOcr {
+    /// Load the OCR engine
+    ///
+    /// # Arguments
+    /// * `models_dir` - Directory containing the detection and recognition .rten models
+    pub fn new(models_dir: &Path) -> Result<Self, String> {
+        let detection_path = models_dir.join(OCR_DETECTION_MODEL);
+        let recognition_path = models_dir.join(OCR_RECOGNITION_MODEL);
+
+        if !detection_path.exists() {
+            return Err(format!("OCR detection model not found at {:?}", detection_path));
+        }
+        if !recognition_path.exists() {
+            return Err(format!("OCR recognition model not found at {:?}", recognition_path));
+        }
+
+        let detection_model = Model::load_file(&detection_path)
+            .map_err(|e| format!("Failed to load OCR detection model: {}", e))?;
+        let recognition_model = Model::load_file(&recognition_path)
+            .map_err(|e| format!("Failed to load OCR recognition model: {}", e))?;
+
+        let engine = OcrEngine::new(OcrEngineParams {
+            detection_model: Some(detection_model),
+            recognition_model: Some(recognition_model),
+            ..Default::default()
+        })
+        .map_err(|e| format!("Failed to initialize OCR engine: {}", e))?;
+
+        println!("OCR engine initialized");
+        Ok(Self { engine })
+    }
+
+    /// Extract text from raw RGB/RGBA pixels (row-major, `width * height * channels` bytes)
+    /// This is CPU-heavy; call it from a blocking task.
+    pub fn extract_text(&self, pixels: &[u8], width: u32, height: u32) -> Result<String, String> {
+        let source = ImageSource::from_bytes(pixels, (width, height))
+            .map_err(|e| format!("Invalid screenshot image: {}", e))?;
+        let input = self.engine.prepare_input(source)
+            .map_err(|e| format!("Failed to prepare image for OCR: {}", e))?;
+        let text = self.engine.get_text(&input)
+            .map_err(|e| format!("OCR failed: {}", e))?;
+
+        Ok(clean_ocr_text(&text))
+    }
+}
+
+/// Trim lines and drop blank / single-character noise lines that OCR picks up from icons
+fn clean_ocr_text(text: &str) -> String {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| line.chars().filter(|c| c.is_alphanumeric()).count() > 1)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
@@ -0,0 +1,256 @@
+//! Page-tolerant PDF text extraction
+//!
+//! Each page is extracted on its own, so a malformed page (or a parser panic)
+//! only loses that page instead of the whole document. Pages that come back
+//! with next to no text, as in scanned PDFs, can then be run through the
+//! screenshot OCR engine on the images they embed.
+
+use serde::Serialize;
+use std::io::Read;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::ocr::ScreenOcr;
+
+/// Pages with fewer letters/digits than this count as empty (likely scanned)
+const MIN_PAGE_CHARS: usize = 20;
+
+/// How a page's text was obtained
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PageStatus {
+    Text,
+    Ocr,
+    Skipped,  // Failed to parse, or no text even after OCR
+}
+
+#[derive(Debug, Clone)]
+struct PdfPage {
+    number: u32,
+    text: String,
+    status: PageStatus,
+}
+
+/// Pages extracted vs skipped, returned to the uploader
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PdfExtractionReport {
+    pub pages_total: usize,
+    pub pages_extracted: usize,
+    pub pages_ocr: usize,
+    pub pages_skipped: usize,
+    pub warnings: Vec<String>,
+}
+
+/// Per-page text of a PDF
+#[derive(Debug, Clone)]
+pub struct PdfExtraction {
+    pages: Vec<PdfPage>,
+    warnings: Vec<String>,
+}
+
+impl PdfExtraction {
+    /// Good pages joined in order
+    pub fn text(&self) -> String {
+        self.pages.iter()
+            .filter(|p| p.status != PageStatus::Skipped)
+            .map(|p| p.text.trim())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Pages whose text is near-empty and worth an OCR pass
+    pub fn needs_ocr(&self) -> bool {
+        self.pages.iter().any(|p| is_near_empty(&p.text))
+    }
+
+    pub fn report(&self) -> PdfExtractionReport {
+        let count = |status: PageStatus| self.pages.iter().filter(|p| p.status == status).count();
+        PdfExtractionReport {
+            pages_total: self.pages.len(),
+            pages_extracted: count(PageStatus::Text),
+            pages_ocr: count(PageStatus::Ocr),
+            pages_skipped: count(PageStatus::Skipped),
+            warnings: self.warnings.clone(),
+        }
+    }
+}
+
+fn is_near_empty(text: &str) -> bool {
+    text.chars().filter(|c| c.is_alphanumeric()).count() < MIN_PAGE_CHARS
+}
+
+/// Extract each page's text, skipping pages that fail
+pub fn extract_pdf_text(bytes: &[u8]) -> Result<PdfExtraction, String> {
+    let doc = match lopdf::Document::load_mem(bytes) {
+        Ok(doc) => doc,
+        Err(e) => {
+            // The structure can't be read page by page; whole-document extraction is the last resort
+            let text = catch_unwind(|| pdf_extract::extract_text_from_mem(bytes))
+                .map_err(|_| format!("Failed to parse PDF: {}", e))?
+                .map_err(|e| format!("Failed to extract PDF text: {}", e))?;
+            return Ok(PdfExtraction {
+                pages: vec![PdfPage { number: 1, text, status: PageStatus::Text }],
+                warnings: vec![format!("PDF structure unreadable ({}); extracted as a single page", e)],
+            });
+        }
+    };
+
+    let mut pages = Vec::new();
+    let mut warnings = Vec::new();
+    for number in doc.get_pages().into_keys() {
+        match extract_page(&doc, number) {
+            Ok(text) => {
+                let status = if is_near_empty(&text) { PageStatus::Skipped } else { PageStatus::Text };
+                pages.push(PdfPage { number, text, status });
+            }
+            Err(e) => {
+                warnings.push(format!("Page {}: {}", number, e));
+                pages.push(PdfPage { number, text: String::new(), status: PageStatus::Skipped });
+            }
+        }
+    }
+
+    if pages.is_empty() {
+        return Err("PDF has no pages".to_string());
+    }
+    Ok(PdfExtraction { pages, warnings })
+}
+
+/// pdf-extract's layout-aware text, falling back to lopdf's simpler extractor
+fn extract_page(doc: &lopdf::Document, number: u32) -> Result<String, String> {
+    let primary = catch_unwind(AssertUnwindSafe(|| {
+        let mut text = String::new();
+        let mut output = pdf_extract::PlainTextOutput::new(&mut text);
+        pdf_extract::output_doc_page(doc, &mut output, number).map(|_| text)
+    }));
+    let primary_error = match primary {
+        Ok(Ok(text)) => return Ok(text),
+        Ok(Err(e)) => e.to_string(),
+        Err(_) => "parser panicked".to_string(),
+    };
+
+    catch_unwind(AssertUnwindSafe(|| doc.extract_text(&[number])))
+        .map_err(|_| format!("{}; fallback parser panicked", primary_error))?
+        .map_err(|e| format!("{}; fallback failed: {}", primary_error, e))
+}
+
+/// OCR the embedded images of near-empty pages (scanned documents)
+/// This is CPU-heavy; call it from a blocking task.
+pub fn ocr_empty_pages(bytes: &[u8], extraction: &mut PdfExtraction, ocr: &ScreenOcr) {
+    let doc = match lopdf::Document::load_mem(bytes) {
+        Ok(doc) => doc,
+        Err(e) => {
+            extraction.warnings.push(format!("OCR skipped, PDF structure unreadable: {}", e));
+            return;
+        }
+    };
+    let page_ids = doc.get_pages();
+
+    for page in extraction.pages.iter_mut().filter(|p| is_near_empty(&p.text)) {
+        let Some(&page_id) = page_ids.get(&page.number) else { continue };
+        let images = match doc.get_page_images(page_id) {
+            Ok(images) => images,
+            Err(e) => {
+                extraction.warnings.push(format!("Page {}: can't read images: {}", page.number, e));
+                continue;
+            }
+        };
+
+        let mut texts = Vec::new();
+        for image in &images {
+            let Some((pixels, width, height)) = decode_image(image) else {
+                extraction.warnings.push(format!("Page {}: unsupported image encoding {:?}", page.number, image.filters));
+                continue;
+            };
+            match ocr.extract_text(&pixels, width, height) {
+                Ok(text) if !text.trim().is_empty() => texts.push(text),
+                Ok(_) => {}
+                Err(e) => extraction.warnings.push(format!("Page {}: {}", page.number, e)),
+            }
+        }
+
+        let text = texts.join("\n");
+        if !is_near_empty(&text) {
+            page.text = text;
+            page.status = PageStatus::Ocr;
+        }
+    }
+}
+
+/// RGB pixels of a JPEG or 8-bit Flate-compressed image XObject
+fn decode_image(image: &lopdf::xobject::PdfImage) -> Option<(Vec<u8>, u32, u32)> {
+    let filters = image.filters.clone().unwrap_or_default();
+    match filters.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["DCTDecode"] => {
+            let decoded = image::load_from_memory(image.content).ok()?.to_rgb8();
+            let (width, height) = decoded.dimensions();
+            Some((decoded.into_raw(), width, height))
+        }
+        ["FlateDecode"] | [] => {
+            if image.bits_per_component != Some(8) {
+                return None;
+            }
+            let raw = if filters.is_empty() {
+                image.content.to_vec()
+            } else {
+                let mut raw = Vec::new();
+                flate2::read::ZlibDecoder::new(image.content).read_to_end(&mut raw).ok()?;
+                raw
+            };
+            let (width, height) = (u32::try_from(image.width).ok()?, u32::try_from(image.height).ok()?);
+            let pixels = width as usize * height as usize;
+            match image.color_space.as_deref() {
+                Some("DeviceRGB") if raw.len() >= pixels * 3 => Some((raw[..pixels * 3].to_vec(), width, height)),
+                Some("DeviceGray") if raw.len() >= pixels => Some((gray_to_rgb(&raw[..pixels]), width, height)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn gray_to_rgb(gray: &[u8]) -> Vec<u8> {
+    gray.iter().flat_map(|&v| [v, v, v]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(number: u32, text: &str, status: PageStatus) -> PdfPage {
+        PdfPage { number, text: text.to_string(), status }
+    }
+
+    #[test]
+    fn test_text_and_report_skip_bad_pages() {
+        let extraction = PdfExtraction {
+            pages: vec![
+                page(1, "Quarterly results were strong across regions. ", PageStatus::Text),
+                page(2, "", PageStatus::Skipped),
+                page(3, "Scanned appendix read back through OCR", PageStatus::Ocr),
+            ],
+            warnings: vec!["Page 2: parser panicked".to_string()],
+        };
+
+        assert_eq!(
+            extraction.text(),
+            "Quarterly results were strong across regions.\n\nScanned appendix read back through OCR"
+        );
+        let report = extraction.report();
+        assert_eq!(report.pages_total, 3);
+        assert_eq!(report.pages_extracted, 1);
+        assert_eq!(report.pages_ocr, 1);
+        assert_eq!(report.pages_skipped, 1);
+        assert!(extraction.needs_ocr());
+    }
+
+    #[test]
+    fn test_near_empty_ignores_whitespace_and_symbols() {
+        assert!(is_near_empty("  \n 12 \u{c} -- ..."));
+        assert!(!is_near_empty("This page has a real sentence on it."));
+    }
+
+    #[test]
+    fn test_gray_to_rgb() {
+        assert_eq!(gray_to_rgb(&[0, 128]), vec![0, 0, 0, 128, 128, 128]);
+    }
+}
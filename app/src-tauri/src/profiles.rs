@@ -0,0 +1,280 @@
+//! Data profiles
+//!
+//! Each profile has its own knowledge base, user store and recordings. The
+//! built-in `default` profile keeps the original locations so existing data
+//! stays where it is; other profiles live under `second-brain/profiles/<name>`
+//! or a directory of the user's choosing (e.g. an external drive). Models are
+//! shared by all profiles.
+//!
+//! The profile list and the active profile are kept in `profiles.json` at the
+//! top of the app's data directory, read at startup.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+const CONFIG_FILE: &str = "profiles.json";
+const PROFILES_DIR: &str = "profiles";
+const MAX_NAME_LEN: usize = 40;
+/// What a profile directory holds that belongs to us; deleting an external profile removes only these
+const PROFILE_DATA: &[&str] = &["knowledge.db", "user_store.db", "recordings", "restore_staging"];
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfileEntry {
+    /// Custom location; None = `profiles/<name>` under the app data directory
+    #[serde(default)]
+    pub data_dir: Option<PathBuf>,
+}
+
+/// Contents of `profiles.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProfilesConfig {
+    #[serde(default)]
+    active: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, ProfileEntry>,
+}
+
+/// A profile as shown in the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct ProfileInfo {
+    pub name: String,
+    pub data_dir: String,
+    pub active: bool,
+}
+
+/// Top-level app data directory (holds the default profile's knowledge base and `profiles.json`)
+pub fn root_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("second-brain")
+}
+
+/// Where the default profile's user store has always lived
+fn legacy_user_store_dir() -> Result<PathBuf, String> {
+    Ok(dirs::data_dir()
+        .ok_or("Could not find data directory")?
+        .join("second-brain"))
+}
+
+/// Profile names double as directory names, so keep them to a safe character set
+pub fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > MAX_NAME_LEN {
+        return Err(format!("Profile name must be 1-{} characters", MAX_NAME_LEN));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Invalid profile name '{}': use letters, digits, '-' and '_'", name));
+    }
+    Ok(())
+}
+
+/// Read `profiles.json`; missing or unreadable config means the default profile
+pub fn load() -> ProfilesConfig {
+    let path = root_dir().join(CONFIG_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            eprintln!("[Profiles] Ignoring invalid {:?}: {}", path, e);
+            ProfilesConfig::default()
+        }),
+        Err(_) => ProfilesConfig::default(),
+    }
+}
+
+impl ProfilesConfig {
+    pub fn save(&self) -> Result<(), String> {
+        let root = root_dir();
+        std::fs::create_dir_all(&root)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+        std::fs::write(root.join(CONFIG_FILE), json)
+            .map_err(|e| format!("Failed to write profiles config: {}", e))
+    }
+
+    /// Name of the active profile (the default one unless another was picked and still exists)
+    pub fn active(&self) -> &str {
+        match self.active.as_deref() {
+            Some(name) if self.profiles.contains_key(name) => name,
+            _ => DEFAULT_PROFILE,
+        }
+    }
+
+    fn exists(&self, name: &str) -> bool {
+        name == DEFAULT_PROFILE || self.profiles.contains_key(name)
+    }
+
+    /// Data directory of a profile (None for the default one, which keeps the legacy layout)
+    fn profile_dir(&self, name: &str) -> Option<PathBuf> {
+        if name == DEFAULT_PROFILE {
+            return None;
+        }
+        let entry = self.profiles.get(name).cloned().unwrap_or_default();
+        Some(entry.data_dir.unwrap_or_else(|| root_dir().join(PROFILES_DIR).join(name)))
+    }
+
+    /// Knowledge base (and recordings) directory of the active profile
+    pub fn knowledge_base_dir(&self) -> PathBuf {
+        self.profile_dir(self.active()).unwrap_or_else(root_dir)
+    }
+
+    /// User store directory of the active profile
+    pub fn user_store_dir(&self) -> Result<PathBuf, String> {
+        match self.profile_dir(self.active()) {
+            Some(dir) => Ok(dir),
+            None => legacy_user_store_dir(),
+        }
+    }
+
+    pub fn list(&self) -> Vec<ProfileInfo> {
+        let active = self.active();
+        std::iter::once(DEFAULT_PROFILE)
+            .chain(self.profiles.keys().map(String::as_str))
+            .map(|name| ProfileInfo {
+                name: name.to_string(),
+                data_dir: self.profile_dir(name).unwrap_or_else(root_dir).to_string_lossy().to_string(),
+                active: name == active,
+            })
+            .collect()
+    }
+
+    pub fn create(&mut self, name: &str, data_dir: Option<PathBuf>) -> Result<(), String> {
+        validate_name(name)?;
+        if self.exists(name) {
+            return Err(format!("Profile '{}' already exists", name));
+        }
+        if let Some(dir) = &data_dir {
+            if !dir.is_absolute() {
+                return Err(format!("Profile directory must be an absolute path: {:?}", dir));
+            }
+        }
+        self.profiles.insert(name.to_string(), ProfileEntry { data_dir });
+        Ok(())
+    }
+
+    /// Switch the active profile; takes effect for stores opened afterwards
+    pub fn set_active(&mut self, name: &str) -> Result<(), String> {
+        if !self.exists(name) {
+            return Err(format!("Profile not found: {}", name));
+        }
+        self.active = (name != DEFAULT_PROFILE).then(|| name.to_string());
+        Ok(())
+    }
+
+    /// Remove a profile from the config, returning its directory for the caller to clean up
+    pub fn remove(&mut self, name: &str) -> Result<PathBuf, String> {
+        if name == DEFAULT_PROFILE {
+            return Err("The default profile can't be deleted".to_string());
+        }
+        if name == self.active() {
+            return Err("Switch to another profile before deleting this one".to_string());
+        }
+        let dir = self.profile_dir(name).ok_or_else(|| format!("Profile not found: {}", name))?;
+        self.profiles.remove(name).ok_or_else(|| format!("Profile not found: {}", name))?;
+        Ok(dir)
+    }
+}
+
+/// Whether `dir` is a profile directory directly inside `profiles_root`
+/// Compares resolved paths, so `..` components and symlinks can't point it elsewhere.
+fn is_managed_dir(dir: &Path, profiles_root: &Path) -> bool {
+    let (Ok(dir), Ok(profiles_root)) = (dir.canonicalize(), profiles_root.canonicalize()) else {
+        return false;
+    };
+    dir.parent() == Some(profiles_root.as_path())
+}
+
+/// Delete a removed profile's data
+/// Managed directories go entirely; a custom directory may hold other files, so only ours are removed.
+pub fn delete_profile_data(dir: &Path) -> Result<(), String> {
+    if !dir.exists() {
+        return Ok(());
+    }
+    if is_managed_dir(dir, &root_dir().join(PROFILES_DIR)) {
+        return std::fs::remove_dir_all(dir)
+            .map_err(|e| format!("Failed to delete profile data: {}", e));
+    }
+    for entry in PROFILE_DATA {
+        let path = dir.join(entry);
+        let result = if path.is_dir() {
+            std::fs::remove_dir_all(&path)
+        } else if path.exists() {
+            std::fs::remove_file(&path)
+        } else {
+            continue;
+        };
+        result.map_err(|e| format!("Failed to delete {:?}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("client_a-2024").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../etc").is_err());
+        assert!(validate_name("my profile").is_err());
+        assert!(validate_name(&"x".repeat(MAX_NAME_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_profile_dirs() {
+        let mut config = ProfilesConfig::default();
+        assert_eq!(config.active(), DEFAULT_PROFILE);
+        assert_eq!(config.knowledge_base_dir(), root_dir());
+
+        config.create("work", None).unwrap();
+        let external = std::env::temp_dir().join("second-brain-external");
+        config.create("archive", Some(external.clone())).unwrap();
+        config.set_active("work").unwrap();
+        assert_eq!(config.knowledge_base_dir(), root_dir().join("profiles").join("work"));
+        assert_eq!(config.user_store_dir().unwrap(), config.knowledge_base_dir());
+
+        config.set_active("archive").unwrap();
+        assert_eq!(config.knowledge_base_dir(), external);
+
+        let names: Vec<String> = config.list().into_iter().map(|p| p.name).collect();
+        assert_eq!(names, vec!["default", "archive", "work"]);
+    }
+
+    #[test]
+    fn test_only_profile_dirs_are_managed() {
+        let root = std::env::temp_dir().join(format!("second-brain-profiles-test-{}", std::process::id()));
+        let profiles = root.join(PROFILES_DIR);
+        let work = profiles.join("work");
+        std::fs::create_dir_all(work.join("recordings")).unwrap();
+
+        assert!(is_managed_dir(&work, &profiles));
+        assert!(!is_managed_dir(&profiles, &profiles));
+        assert!(!is_managed_dir(&work.join("recordings"), &profiles));
+        assert!(!is_managed_dir(&work.join("..").join(".."), &profiles));
+        assert!(!is_managed_dir(&profiles.join("missing"), &profiles));
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_create_and_remove_rules() {
+        let mut config = ProfilesConfig::default();
+        config.create("work", None).unwrap();
+        assert!(config.create("work", None).is_err());
+        assert!(config.create("default", None).is_err());
+        assert!(config.create("rel", Some(PathBuf::from("relative/dir"))).is_err());
+        assert!(config.set_active("missing").is_err());
+
+        config.set_active("work").unwrap();
+        assert!(config.remove("work").is_err());
+        assert!(config.remove(DEFAULT_PROFILE).is_err());
+
+        config.set_active(DEFAULT_PROFILE).unwrap();
+        assert_eq!(config.remove("work").unwrap(), root_dir().join("profiles").join("work"));
+        assert!(config.remove("work").is_err());
+        assert_eq!(config.active(), DEFAULT_PROFILE);
+    }
+}
@@ -0,0 +1,167 @@
+//! PII redaction for transcripts
+//!
+//! Regex rules scrub emails, phone numbers, SSNs and card numbers; person names
+//! found by the entity engine are passed in and replaced as whole words.
+//! Rules are configurable through the `redaction_patterns` setting (a JSON
+//! list of `RedactionPattern`); an empty setting uses `default_patterns()`.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Placeholder used for redacted person names
+pub const NAME_PLACEHOLDER: &str = "[NAME]";
+
+/// A configurable redaction rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedactionPattern {
+    pub name: String,
+    pub pattern: String,
+    /// Replacement text; defaults to `[<NAME>]`
+    #[serde(default)]
+    pub replacement: Option<String>,
+}
+
+/// Redacted text plus how many spans were replaced
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RedactionResult {
+    pub text: String,
+    pub count: usize,
+}
+
+/// Built-in rules, applied in order (SSNs and cards before the looser phone rule)
+pub fn default_patterns() -> Vec<RedactionPattern> {
+    let rule = |name: &str, pattern: &str| RedactionPattern {
+        name: name.to_string(),
+        pattern: pattern.to_string(),
+        replacement: None,
+    };
+    vec![
+        rule("email", r"(?i)\b[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}\b"),
+        rule("ssn", r"\b\d{3}-\d{2}-\d{4}\b"),
+        rule("card", r"\b\d{4}[ -]?\d{4}[ -]?\d{4}[ -]?\d{1,4}\b"),
+        rule("phone", r"(?:\+\d{1,3}[\s.-]?)?(?:\(\d{3}\)|\b\d{3})[\s.-]?\d{3}[\s.-]?\d{4}\b"),
+    ]
+}
+
+/// Compiled redaction rules
+pub struct Redactor {
+    rules: Vec<(Regex, String)>,
+}
+
+impl Redactor {
+    pub fn new(patterns: &[RedactionPattern]) -> Result<Self, String> {
+        let rules = patterns.iter()
+            .map(|p| {
+                let regex = Regex::new(&p.pattern)
+                    .map_err(|e| format!("Invalid redaction pattern '{}': {}", p.name, e))?;
+                let replacement = p.replacement.clone()
+                    .unwrap_or_else(|| format!("[{}]", p.name.to_uppercase()));
+                Ok((regex, replacement))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Build from the `redaction_patterns` setting (JSON list; empty = defaults)
+    pub fn from_config(patterns_json: &str) -> Result<Self, String> {
+        if patterns_json.trim().is_empty() {
+            return Self::new(&default_patterns());
+        }
+        let patterns: Vec<RedactionPattern> = serde_json::from_str(patterns_json)
+            .map_err(|e| format!("Invalid redaction_patterns setting: {}", e))?;
+        Self::new(&patterns)
+    }
+
+    /// Redact `text`, also replacing any of `person_names` that appear as whole words
+    pub fn redact(&self, text: &str, person_names: &[String]) -> RedactionResult {
+        let mut text = text.to_string();
+        let mut count = 0;
+
+        for (regex, replacement) in &self.rules {
+            let matches = regex.find_iter(&text).count();
+            if matches > 0 {
+                count += matches;
+                text = regex.replace_all(&text, regex::NoExpand(replacement.as_str())).into_owned();
+            }
+        }
+
+        // Longest names first so "Alice Smith" wins over "Alice"
+        let mut names: Vec<&str> = person_names.iter()
+            .map(|n| n.trim())
+            .filter(|n| n.chars().count() > 1)
+            .collect();
+        names.sort_by_key(|n| std::cmp::Reverse(n.len()));
+        names.dedup();
+
+        for name in names {
+            let Ok(regex) = Regex::new(&format!(r"(?i)\b{}\b", regex::escape(name))) else {
+                continue;
+            };
+            let matches = regex.find_iter(&text).count();
+            if matches > 0 {
+                count += matches;
+                text = regex.replace_all(&text, NAME_PLACEHOLDER).into_owned();
+            }
+        }
+
+        RedactionResult { text, count }
+    }
+}
+
+/// Redact `text` with the default rules
+pub fn redact_text(text: &str, person_names: &[String]) -> RedactionResult {
+    match Redactor::new(&default_patterns()) {
+        Ok(redactor) => redactor.redact(text, person_names),
+        Err(_) => RedactionResult { text: text.to_string(), count: 0 },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacts_contact_details() {
+        let result = redact_text(
+            "Mail me at jane.doe@example.com or call (555) 123-4567, or +1 555.987.6543",
+            &[],
+        );
+        assert_eq!(result.text, "Mail me at [EMAIL] or call [PHONE], or [PHONE]");
+        assert_eq!(result.count, 3);
+    }
+
+    #[test]
+    fn test_redacts_ssn_and_card() {
+        let result = redact_text("SSN 123-45-6789, card 4111 1111 1111 1111", &[]);
+        assert_eq!(result.text, "SSN [SSN], card [CARD]");
+        assert_eq!(result.count, 2);
+    }
+
+    #[test]
+    fn test_redacts_person_names_as_whole_words() {
+        let names = vec!["Alice".to_string(), "Alice Smith".to_string()];
+        let result = redact_text("alice smith said Alice will ask Alicea", &names);
+        assert_eq!(result.text, "[NAME] said [NAME] will ask Alicea");
+        assert_eq!(result.count, 2);
+    }
+
+    #[test]
+    fn test_custom_patterns_from_config() {
+        let redactor = Redactor::from_config(
+            r#"[{"name": "ticket", "pattern": "JIRA-\\d+", "replacement": "[TICKET]"}]"#,
+        ).unwrap();
+        let result = redactor.redact("Fixed in JIRA-42, mail bob@example.com", &[]);
+        assert_eq!(result.text, "Fixed in [TICKET], mail bob@example.com");
+        assert_eq!(result.count, 1);
+
+        assert!(Redactor::from_config("not json").is_err());
+        assert!(Redactor::from_config(r#"[{"name": "bad", "pattern": "("}]"#).is_err());
+    }
+
+    #[test]
+    fn test_clean_text_untouched() {
+        let result = redact_text("We agreed to ship the beta on Friday", &[]);
+        assert_eq!(result.text, "We agreed to ship the beta on Friday");
+        assert_eq!(result.count, 0);
+    }
+}
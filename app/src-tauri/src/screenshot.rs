@@ -16,8 +16,8 @@ pub struct ScreenshotResult {
     pub format: String,
 }
 
-/// Capture the primary screen as a PNG image
-pub fn capture_screen() -> Result<ScreenshotResult, String> {
+/// Capture the primary screen as raw image data
+fn capture_primary_screen() -> Result<screenshots::image::RgbaImage, String> {
     // Get all screens
     let screens = Screen::all().map_err(|e| format!("Failed to get screens: {}", e))?;
 
@@ -25,9 +25,21 @@ pub fn capture_screen() -> Result<ScreenshotResult, String> {
     let screen = screens.first().ok_or("No screens found")?;
 
     // Capture the screen
-    let image = screen
+    screen
         .capture()
-        .map_err(|e| format!("Failed to capture screen: {}", e))?;
+        .map_err(|e| format!("Failed to capture screen: {}", e))
+}
+
+/// Capture the primary screen as RGBA pixels, returned as (pixels, width, height)
+pub fn capture_screen_pixels() -> Result<(Vec<u8>, u32, u32), String> {
+    let image = capture_primary_screen()?;
+    let (width, height) = (image.width(), image.height());
+    Ok((image.into_raw(), width, height))
+}
+
+/// Capture the primary screen as a PNG image
+pub fn capture_screen() -> Result<ScreenshotResult, String> {
+    let image = capture_primary_screen()?;
 
     let width = image.width();
     let height = image.height();
@@ -1,11 +1,64 @@
 //! Speaker Diarization module using sherpa-rs
 //!
 //! Uses Pyannote segmentation + 3D-Speaker embeddings for identifying
-//! different speakers in system audio.
+//! different speakers in system audio. Each segment is re-embedded after
+//! clustering and scored by how much closer it sits to its own speaker's
+//! centroid than to the nearest other speaker. Spans where two speakers'
+//! segments overlap are reported as crosstalk.
 
 use sherpa_rs::diarize::{Diarize, DiarizeConfig};
+use sherpa_rs::speaker_id::{EmbeddingExtractor, ExtractorConfig};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+/// Segments shorter than this are too short to embed reliably
+const MIN_SCORED_SEGMENT_MS: u64 = 400;
+/// Only the first part of long segments is embedded for scoring
+const MAX_SCORED_SEGMENT_MS: u64 = 10_000;
+/// Centroid similarity margin that counts as a fully confident label
+const FULL_CONFIDENCE_MARGIN: f32 = 0.3;
+/// Shorter overlaps are boundary jitter between turns, not crosstalk
+const MIN_OVERLAP_MS: u64 = 250;
+
+/// Speaker name for microphone segments when none is configured
+pub const DEFAULT_MIC_SPEAKER: &str = "You";
+/// Speaker name for system audio segments until diarization tells the speakers apart
+pub const DEFAULT_REMOTE_SPEAKER: &str = "Guest";
+
+/// Names given to live transcript segments by audio source
+///
+/// Diarization only relabels `remote` segments in separate-source mode, so the two
+/// labels must differ or the user's own segments would be renamed too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeakerLabels {
+    pub mic: String,
+    pub remote: String,
+}
+
+impl SpeakerLabels {
+    pub fn new(mic: &str, remote: &str) -> Result<Self, String> {
+        let (mic, remote) = (mic.trim(), remote.trim());
+        if mic.is_empty() || remote.is_empty() {
+            return Err("Speaker labels cannot be empty".to_string());
+        }
+        if mic.eq_ignore_ascii_case(remote) {
+            return Err(format!("Microphone and remote speaker labels must differ (both are '{}')", mic));
+        }
+        Ok(Self { mic: mic.to_string(), remote: remote.to_string() })
+    }
+
+    /// Label for a segment captured from `source` ("microphone" or "system")
+    pub fn for_source(&self, source: &str) -> &str {
+        if source == "microphone" { &self.mic } else { &self.remote }
+    }
+}
+
+impl Default for SpeakerLabels {
+    fn default() -> Self {
+        Self { mic: DEFAULT_MIC_SPEAKER.to_string(), remote: DEFAULT_REMOTE_SPEAKER.to_string() }
+    }
+}
+
 /// Diarization result with speaker-labeled segments
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct DiarizedSegment {
@@ -13,6 +66,69 @@ pub struct DiarizedSegment {
     pub end_ms: u64,
     pub speaker_id: i32,
     pub speaker_label: String,  // "Speaker 1", "Speaker 2", etc.
+    pub confidence: Option<f32>,  // 0-1 cluster margin; None if the segment couldn't be scored
+}
+
+/// A span where two or more speakers talk at once (crosstalk)
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct OverlapRegion {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub speakers: Vec<i32>,  // Speaker IDs active in the span, ascending
+}
+
+/// Output of `SpeakerDiarizationEngine::process`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DiarizationOutput {
+    pub segments: Vec<DiarizedSegment>,
+    pub overlap_regions: Vec<OverlapRegion>,
+}
+
+/// Diarization quality for the most recently processed meeting
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DiarizationQuality {
+    pub meeting_id: String,
+    pub segment_count: usize,
+    pub speaker_count: usize,
+    pub average_confidence: Option<f32>,
+    pub low_confidence_segments: usize,
+    pub overlap_regions: usize,
+    pub overlap_ms: u64,
+}
+
+/// Labels below this confidence are worth a manual check
+pub const LOW_CONFIDENCE_THRESHOLD: f32 = 0.5;
+
+impl DiarizationQuality {
+    pub fn from_output(meeting_id: &str, output: &DiarizationOutput) -> Self {
+        Self {
+            overlap_regions: output.overlap_regions.len(),
+            overlap_ms: output.overlap_regions.iter().map(|r| r.end_ms - r.start_ms).sum(),
+            ..Self::from_segments(meeting_id, &output.segments)
+        }
+    }
+
+    pub fn from_segments(meeting_id: &str, segments: &[DiarizedSegment]) -> Self {
+        let scores: Vec<f32> = segments.iter().filter_map(|s| s.confidence).collect();
+        let average_confidence = if scores.is_empty() {
+            None
+        } else {
+            Some(scores.iter().sum::<f32>() / scores.len() as f32)
+        };
+
+        Self {
+            meeting_id: meeting_id.to_string(),
+            segment_count: segments.len(),
+            speaker_count: segments.iter()
+                .map(|s| s.speaker_id)
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            average_confidence,
+            low_confidence_segments: scores.iter().filter(|c| **c < LOW_CONFIDENCE_THRESHOLD).count(),
+            overlap_regions: 0,
+            overlap_ms: 0,
+        }
+    }
 }
 
 /// Speaker diarization engine configuration
@@ -20,6 +136,8 @@ pub struct SpeakerDiarizationConfig {
     pub models_dir: PathBuf,
     pub num_speakers: Option<i32>,  // None = auto-detect
     pub threshold: f32,             // Clustering threshold (default 0.5)
+    pub min_speakers: Option<i32>,  // Manual lower bound on detected speakers
+    pub max_speakers: Option<i32>,  // Manual upper bound on detected speakers
 }
 
 impl Default for SpeakerDiarizationConfig {
@@ -33,6 +151,8 @@ impl Default for SpeakerDiarizationConfig {
             models_dir,
             num_speakers: None,  // Auto-detect
             threshold: 0.5,
+            min_speakers: None,
+            max_speakers: None,
         }
     }
 }
@@ -41,6 +161,8 @@ impl Default for SpeakerDiarizationConfig {
 pub struct SpeakerDiarizationEngine {
     config: SpeakerDiarizationConfig,
     diarizer: Option<Diarize>,
+    extractor: Option<EmbeddingExtractor>,    // Scores segments; None disables confidence
+    model_paths: Option<(PathBuf, PathBuf)>,  // (segmentation, embedding)
 }
 
 impl SpeakerDiarizationEngine {
@@ -49,6 +171,8 @@ impl SpeakerDiarizationEngine {
         Self {
             config,
             diarizer: None,
+            extractor: None,
+            model_paths: None,
         }
     }
 
@@ -77,8 +201,33 @@ impl SpeakerDiarizationEngine {
             ));
         }
 
+        let extractor_config = ExtractorConfig {
+            model: embedding_model.to_string_lossy().to_string(),
+            provider: None,
+            num_threads: None,
+            debug: false,
+        };
+        self.extractor = match EmbeddingExtractor::new(extractor_config) {
+            Ok(extractor) => Some(extractor),
+            Err(e) => {
+                eprintln!("[Diarization] Speaker confidence unavailable: {:?}", e);
+                None
+            }
+        };
+
+        self.model_paths = Some((segmentation_model, embedding_model));
+        self.diarizer = Some(self.build_diarizer(self.config.num_speakers)?);
+        println!("Speaker diarization engine initialized");
+        Ok(())
+    }
+
+    /// Build a diarizer with a fixed cluster count (None = threshold-based auto-detect)
+    fn build_diarizer(&self, num_clusters: Option<i32>) -> Result<Diarize, String> {
+        let (segmentation_model, embedding_model) = self.model_paths.clone()
+            .ok_or("Diarization engine not initialized")?;
+
         let diarize_config = DiarizeConfig {
-            num_clusters: self.config.num_speakers,
+            num_clusters,
             threshold: Some(self.config.threshold),
             min_duration_on: Some(0.0),
             min_duration_off: Some(0.5),
@@ -86,12 +235,19 @@ impl SpeakerDiarizationEngine {
             debug: false,
         };
 
-        let diarizer = Diarize::new(segmentation_model, embedding_model, diarize_config)
-            .map_err(|e| format!("Failed to initialize diarizer: {:?}", e))?;
+        Diarize::new(segmentation_model, embedding_model, diarize_config)
+            .map_err(|e| format!("Failed to initialize diarizer: {:?}", e))
+    }
 
-        self.diarizer = Some(diarizer);
-        println!("Speaker diarization engine initialized");
-        Ok(())
+    /// Set manual speaker count bounds; these take precedence over meeting participant counts
+    pub fn set_speaker_bounds(&mut self, min_speakers: Option<i32>, max_speakers: Option<i32>) {
+        self.config.min_speakers = min_speakers;
+        self.config.max_speakers = max_speakers;
+    }
+
+    /// Current manual speaker count bounds as (min, max)
+    pub fn speaker_bounds(&self) -> (Option<i32>, Option<i32>) {
+        (self.config.min_speakers, self.config.max_speakers)
     }
 
     /// Process audio samples and return speaker-labeled segments
@@ -99,47 +255,214 @@ impl SpeakerDiarizationEngine {
     /// # Arguments
     /// * `samples` - Audio samples at 16kHz mono
     /// * `sample_rate` - Sample rate (will resample if not 16kHz)
+    /// * `min_speakers` / `max_speakers` - If auto-detection lands outside these bounds,
+    ///   clustering is re-run with the cluster count clamped into range
     ///
     /// # Returns
-    /// Vector of diarized segments with speaker IDs
-    pub fn process(&mut self, samples: Vec<f32>, sample_rate: u32) -> Result<Vec<DiarizedSegment>, String> {
-        let diarizer = self.diarizer.as_mut()
-            .ok_or("Diarization engine not initialized")?;
-
+    /// Diarized segments with speaker IDs, plus the spans where speakers overlap
+    pub fn process(
+        &mut self,
+        samples: Vec<f32>,
+        sample_rate: u32,
+        min_speakers: Option<i32>,
+        max_speakers: Option<i32>,
+    ) -> Result<DiarizationOutput, String> {
         // Resample to 16kHz if needed
-        let samples_16k = if sample_rate != 16000 {
+        let mut samples_16k = if sample_rate != 16000 {
             resample(&samples, sample_rate, 16000)
         } else {
             samples
         };
 
+        // A second, clamped pass is only possible when auto-detecting
+        let has_bounds = self.config.num_speakers.is_none()
+            && (min_speakers.is_some() || max_speakers.is_some());
+        // sherpa takes the samples by value: only copy them while a later pass or scoring needs them
+        let scoring = self.extractor.is_some();
+
         // Run diarization
-        let segments = diarizer.compute(samples_16k, None)
+        let diarizer = self.diarizer.as_mut()
+            .ok_or("Diarization engine not initialized")?;
+        let first_pass = if has_bounds || scoring { samples_16k.clone() } else { std::mem::take(&mut samples_16k) };
+        let mut segments = diarizer.compute(first_pass, None)
             .map_err(|e| format!("Diarization failed: {:?}", e))?;
 
+        if has_bounds {
+            let detected = segments.iter()
+                .map(|s| s.speaker)
+                .collect::<std::collections::HashSet<_>>()
+                .len() as i32;
+
+            let clamped = match (min_speakers, max_speakers) {
+                (_, Some(max)) if detected > max => Some(max.max(1)),
+                (Some(min), _) if detected < min => Some(min),
+                _ => None,
+            };
+
+            if let Some(num_clusters) = clamped {
+                println!("[Diarization] Detected {} speakers, re-clustering with {}", detected, num_clusters);
+                let mut clamped_diarizer = self.build_diarizer(Some(num_clusters))?;
+                let second_pass = if scoring { samples_16k.clone() } else { std::mem::take(&mut samples_16k) };
+                segments = clamped_diarizer.compute(second_pass, None)
+                    .map_err(|e| format!("Diarization failed: {:?}", e))?;
+            }
+        }
+
         // Convert to our format with labels
-        let diarized: Vec<DiarizedSegment> = segments
+        let mut diarized: Vec<DiarizedSegment> = segments
             .into_iter()
             .map(|seg| DiarizedSegment {
                 start_ms: (seg.start * 1000.0) as u64,
                 end_ms: (seg.end * 1000.0) as u64,
                 speaker_id: seg.speaker,
                 speaker_label: format!("Speaker {}", seg.speaker + 1),
+                confidence: None,
             })
             .collect();
 
-        println!("[Diarization] Found {} segments with {} unique speakers",
+        self.score_segments(&samples_16k, &mut diarized);
+        let overlap_regions = find_overlap_regions(&diarized);
+
+        println!("[Diarization] Found {} segments with {} unique speakers, {} crosstalk regions",
             diarized.len(),
-            diarized.iter().map(|s| s.speaker_id).collect::<std::collections::HashSet<_>>().len()
+            diarized.iter().map(|s| s.speaker_id).collect::<std::collections::HashSet<_>>().len(),
+            overlap_regions.len()
         );
 
-        Ok(diarized)
+        Ok(DiarizationOutput { segments: diarized, overlap_regions })
     }
 
     /// Check if the engine is initialized
     pub fn is_initialized(&self) -> bool {
         self.diarizer.is_some()
     }
+
+    /// Fill in `confidence` from each segment's margin between its own and the nearest other centroid
+    fn score_segments(&mut self, samples_16k: &[f32], segments: &mut [DiarizedSegment]) {
+        let Some(extractor) = self.extractor.as_mut() else {
+            return;
+        };
+
+        let mut embeddings: Vec<Option<Vec<f32>>> = Vec::with_capacity(segments.len());
+        for seg in segments.iter() {
+            let duration_ms = seg.end_ms.saturating_sub(seg.start_ms);
+            if duration_ms < MIN_SCORED_SEGMENT_MS {
+                embeddings.push(None);
+                continue;
+            }
+            let start = (seg.start_ms * 16) as usize;
+            let end = ((seg.start_ms + duration_ms.min(MAX_SCORED_SEGMENT_MS)) * 16) as usize;
+            let end = end.min(samples_16k.len());
+            if start >= end {
+                embeddings.push(None);
+                continue;
+            }
+            let embedding = extractor.compute_speaker_embedding(samples_16k[start..end].to_vec(), 16000)
+                .ok()
+                .map(|e| normalize(&e));
+            embeddings.push(embedding);
+        }
+
+        let labeled: Vec<(i32, &[f32])> = segments.iter()
+            .zip(&embeddings)
+            .filter_map(|(seg, e)| e.as_deref().map(|e| (seg.speaker_id, e)))
+            .collect();
+        let centroids = speaker_centroids(&labeled);
+
+        for (seg, embedding) in segments.iter_mut().zip(&embeddings) {
+            seg.confidence = embedding.as_deref()
+                .and_then(|e| segment_confidence(e, seg.speaker_id, &centroids));
+        }
+    }
+}
+
+fn normalize(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter().map(|x| x / norm).collect()
+    } else {
+        v.to_vec()
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Unit-length mean embedding per speaker
+fn speaker_centroids(embeddings: &[(i32, &[f32])]) -> HashMap<i32, Vec<f32>> {
+    let mut sums: HashMap<i32, Vec<f32>> = HashMap::new();
+    for (speaker, embedding) in embeddings {
+        let sum = sums.entry(*speaker).or_insert_with(|| vec![0.0; embedding.len()]);
+        if sum.len() == embedding.len() {
+            for (s, x) in sum.iter_mut().zip(embedding.iter()) {
+                *s += x;
+            }
+        }
+    }
+    sums.into_iter().map(|(speaker, sum)| (speaker, normalize(&sum))).collect()
+}
+
+/// Confidence that `embedding` belongs to `speaker` rather than the closest other speaker
+///
+/// With a single speaker there is nothing to confuse it with, so the similarity
+/// to its own centroid is used directly.
+fn segment_confidence(embedding: &[f32], speaker: i32, centroids: &HashMap<i32, Vec<f32>>) -> Option<f32> {
+    let own = dot(embedding, centroids.get(&speaker)?);
+    let nearest_other = centroids.iter()
+        .filter(|(id, _)| **id != speaker)
+        .map(|(_, centroid)| dot(embedding, centroid))
+        .fold(None, |best: Option<f32>, sim| Some(best.map_or(sim, |b| b.max(sim))));
+
+    let confidence = match nearest_other {
+        Some(other) => (own - other) / FULL_CONFIDENCE_MARGIN,
+        None => own,
+    };
+    Some(confidence.clamp(0.0, 1.0))
+}
+
+/// Spans where segments of different speakers overlap, merged and sorted by start
+pub fn find_overlap_regions(segments: &[DiarizedSegment]) -> Vec<OverlapRegion> {
+    let mut sorted: Vec<&DiarizedSegment> = segments.iter().collect();
+    sorted.sort_by_key(|s| s.start_ms);
+
+    let mut overlaps: Vec<OverlapRegion> = Vec::new();
+    for (i, a) in sorted.iter().enumerate() {
+        for b in &sorted[i + 1..] {
+            if b.start_ms >= a.end_ms {
+                break;
+            }
+            if a.speaker_id == b.speaker_id {
+                continue;
+            }
+            let end_ms = a.end_ms.min(b.end_ms);
+            if end_ms.saturating_sub(b.start_ms) >= MIN_OVERLAP_MS {
+                overlaps.push(OverlapRegion {
+                    start_ms: b.start_ms,
+                    end_ms,
+                    speakers: vec![a.speaker_id.min(b.speaker_id), a.speaker_id.max(b.speaker_id)],
+                });
+            }
+        }
+    }
+
+    overlaps.sort_by_key(|r| r.start_ms);
+    let mut merged: Vec<OverlapRegion> = Vec::new();
+    for region in overlaps {
+        match merged.last_mut() {
+            Some(last) if region.start_ms <= last.end_ms => {
+                last.end_ms = last.end_ms.max(region.end_ms);
+                for speaker in region.speakers {
+                    if !last.speakers.contains(&speaker) {
+                        last.speakers.push(speaker);
+                    }
+                }
+                last.speakers.sort_unstable();
+            }
+            _ => merged.push(region),
+        }
+    }
+    merged
 }
 
 /// Simple linear resampling
@@ -172,10 +495,11 @@ fn resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
 pub fn relabel_speakers(
     segments: &mut Vec<(u64, u64, String, String)>,  // (start_ms, end_ms, original_speaker, text)
     diarization: &[DiarizedSegment],
+    remote_label: &str,
 ) {
     for (start_ms, end_ms, speaker, _text) in segments.iter_mut() {
-        // Only relabel "Guest" speakers
-        if speaker != "Guest" {
+        // Only relabel remote speakers
+        if speaker != remote_label {
             continue;
         }
 
@@ -189,3 +513,78 @@ pub fn relabel_speakers(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confidence_reflects_cluster_margin() {
+        let a = [1.0, 0.0];
+        let b = [0.0, 1.0];
+        let centroids = speaker_centroids(&[(0, &a[..]), (1, &b[..])]);
+
+        // Right on speaker 0's centroid
+        assert_eq!(segment_confidence(&a, 0, &centroids), Some(1.0));
+        // Halfway between the two speakers
+        let between = normalize(&[1.0, 1.0]);
+        assert_eq!(segment_confidence(&between, 0, &centroids), Some(0.0));
+        // Closer to speaker 1 than to its own label
+        assert_eq!(segment_confidence(&b, 0, &centroids), Some(0.0));
+        // Unknown speaker
+        assert_eq!(segment_confidence(&a, 7, &centroids), None);
+    }
+
+    fn timed(speaker_id: i32, start_ms: u64, end_ms: u64) -> DiarizedSegment {
+        DiarizedSegment {
+            start_ms,
+            end_ms,
+            speaker_id,
+            speaker_label: format!("Speaker {}", speaker_id + 1),
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn test_speaker_labels_must_be_distinct() {
+        let labels = SpeakerLabels::new(" Dana ", "Remote").unwrap();
+        assert_eq!(labels.for_source("microphone"), "Dana");
+        assert_eq!(labels.for_source("system"), "Remote");
+        assert!(SpeakerLabels::new("", "Guest").is_err());
+        assert!(SpeakerLabels::new("guest", "Guest").is_err());
+    }
+
+    #[test]
+    fn test_overlap_regions_between_speakers() {
+        let segments = vec![
+            timed(0, 0, 5000),
+            timed(1, 4000, 8000),      // Talks over speaker 0 for 1s
+            timed(2, 4500, 6000),      // Joins in, merged into the same region
+            timed(0, 7900, 10_000),    // 100ms boundary jitter, ignored
+            timed(1, 12_000, 13_000),
+            timed(1, 12_500, 14_000),  // Same speaker, not crosstalk
+        ];
+        let regions = find_overlap_regions(&segments);
+
+        assert_eq!(regions, vec![OverlapRegion { start_ms: 4000, end_ms: 6000, speakers: vec![0, 1, 2] }]);
+        assert!(find_overlap_regions(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_quality_averages_scored_segments() {
+        let seg = |speaker_id, confidence| DiarizedSegment {
+            start_ms: 0,
+            end_ms: 1000,
+            speaker_id,
+            speaker_label: format!("Speaker {}", speaker_id + 1),
+            confidence,
+        };
+        let segments = vec![seg(0, Some(0.9)), seg(1, Some(0.3)), seg(1, None)];
+        let quality = DiarizationQuality::from_segments("abc", &segments);
+
+        assert_eq!(quality.segment_count, 3);
+        assert_eq!(quality.speaker_count, 2);
+        assert!((quality.average_confidence.unwrap() - 0.6).abs() < 1e-6);
+        assert_eq!(quality.low_confidence_segments, 1);
+    }
+}
@@ -0,0 +1,512 @@
+//! Portable SQLite export of the knowledge base
+//!
+//! Copies meetings, transcripts, action items, decisions, people, topics and knowledge
+//! sources into a plain, indexed SQLite file that standard tools can query or migrate
+//! from. This is a one-way snapshot for interoperability; backups (see `backup`) are
+//! what restores read. Ids are bare SurrealDB record ids, list fields are JSON arrays,
+//! and embeddings are little-endian f32 BLOBs (NULL when a row has none).
+
+use crate::knowledge_base::{
+    normalize_record_id, ActionItem, Decision, KnowledgeChunk, KnowledgeSource, Meeting, Person, Topic,
+    TranscriptSegment,
+};
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use surrealdb::sql::Thing;
+
+/// Bump when a table or column of the exported file changes
+pub const SQLITE_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Knowledge base rows to export
+#[derive(Debug, Clone, Default)]
+pub struct KbSnapshot {
+    pub meetings: Vec<Meeting>,
+    pub segments: Vec<TranscriptSegment>,
+    pub action_items: Vec<ActionItem>,
+    pub decisions: Vec<Decision>,
+    pub people: Vec<Person>,
+    pub topics: Vec<Topic>,
+    pub sources: Vec<KnowledgeSource>,
+    pub chunks: Vec<KnowledgeChunk>,
+}
+
+/// Rows written per table
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SqliteExportStats {
+    pub schema_version: u32,
+    pub meetings: usize,
+    pub segments: usize,
+    pub action_items: usize,
+    pub decisions: usize,
+    pub people: usize,
+    pub topics: usize,
+    pub sources: usize,
+    pub chunks: usize,
+}
+
+const SCHEMA: &str = r#"
+    CREATE TABLE export_meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+
+    CREATE TABLE meetings (
+        id TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        start_time INTEGER NOT NULL,
+        end_time INTEGER,
+        participants TEXT NOT NULL,
+        summary TEXT,
+        tags TEXT NOT NULL,
+        favorite INTEGER NOT NULL,
+        private INTEGER NOT NULL,
+        dominant_language TEXT
+    );
+    CREATE INDEX idx_meetings_start_time ON meetings(start_time);
+
+    CREATE TABLE segments (
+        id TEXT PRIMARY KEY,
+        meeting_id TEXT NOT NULL REFERENCES meetings(id),
+        speaker TEXT NOT NULL,
+        text TEXT NOT NULL,
+        start_ms INTEGER NOT NULL,
+        end_ms INTEGER NOT NULL,
+        speaker_confidence REAL,
+        overlapped INTEGER NOT NULL,
+        language TEXT,
+        emotion TEXT,
+        embedding BLOB
+    );
+    CREATE INDEX idx_segments_meeting ON segments(meeting_id, start_ms);
+    CREATE INDEX idx_segments_speaker ON segments(speaker);
+
+    CREATE TABLE action_items (
+        id TEXT PRIMARY KEY,
+        meeting_id TEXT NOT NULL REFERENCES meetings(id),
+        text TEXT NOT NULL,
+        assignee TEXT,
+        deadline TEXT,
+        status TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+    );
+    CREATE INDEX idx_action_items_meeting ON action_items(meeting_id);
+    CREATE INDEX idx_action_items_status ON action_items(status);
+    CREATE INDEX idx_action_items_assignee ON action_items(assignee);
+
+    CREATE TABLE decisions (
+        id TEXT PRIMARY KEY,
+        meeting_id TEXT NOT NULL REFERENCES meetings(id),
+        text TEXT NOT NULL,
+        participants TEXT NOT NULL,
+        created_at INTEGER NOT NULL
+    );
+    CREATE INDEX idx_decisions_meeting ON decisions(meeting_id);
+
+    CREATE TABLE people (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        aliases TEXT NOT NULL,
+        first_seen INTEGER NOT NULL,
+        last_seen INTEGER NOT NULL
+    );
+    CREATE INDEX idx_people_name ON people(name COLLATE NOCASE);
+
+    CREATE TABLE topics (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        mention_count INTEGER NOT NULL,
+        last_mentioned INTEGER NOT NULL,
+        embedding BLOB
+    );
+    CREATE INDEX idx_topics_name ON topics(name COLLATE NOCASE);
+
+    CREATE TABLE knowledge_sources (
+        id TEXT PRIMARY KEY,
+        url TEXT NOT NULL,
+        title TEXT NOT NULL,
+        source_type TEXT NOT NULL,
+        raw_content TEXT NOT NULL,
+        tags TEXT NOT NULL,
+        pinned INTEGER NOT NULL,
+        created_at INTEGER NOT NULL,
+        last_updated INTEGER NOT NULL
+    );
+    CREATE INDEX idx_knowledge_sources_type ON knowledge_sources(source_type);
+
+    CREATE TABLE knowledge_chunks (
+        id TEXT PRIMARY KEY,
+        source_id TEXT NOT NULL REFERENCES knowledge_sources(id),
+        chunk_index INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        embedding BLOB
+    );
+    CREATE INDEX idx_knowledge_chunks_source ON knowledge_chunks(source_id, chunk_index);
+"#;
+
+/// Write `snapshot` to a new SQLite file at `path`, replacing any file already there
+///
+/// The export is built in a temp file next to `path` and only renamed over it once
+/// complete, so a failed export leaves the previous file in place.
+pub fn export_to_path(path: &Path, snapshot: &KbSnapshot) -> Result<SqliteExportStats, String> {
+    let temp_path = temp_export_path(path)?;
+    // Left behind by an interrupted export
+    if temp_path.exists() {
+        std::fs::remove_file(&temp_path).map_err(|e| format!("Failed to remove {:?}: {}", temp_path, e))?;
+    }
+
+    let stats = write_new_database(&temp_path, snapshot)
+        .and_then(|stats| {
+            std::fs::rename(&temp_path, path)
+                .map_err(|e| format!("Failed to replace {:?}: {}", path, e))?;
+            Ok(stats)
+        })
+        .inspect_err(|_| {
+            std::fs::remove_file(&temp_path).ok();
+        })?;
+    println!("[Export] Wrote SQLite export to {:?}: {:?}", path, stats);
+    Ok(stats)
+}
+
+/// Sibling of `path` the export is written to before it replaces `path`
+fn temp_export_path(path: &Path) -> Result<std::path::PathBuf, String> {
+    let file_name = path.file_name()
+        .ok_or_else(|| format!("Invalid export path: {:?}", path))?;
+    Ok(path.with_file_name(format!("{}.tmp", file_name.to_string_lossy())))
+}
+
+fn write_new_database(path: &Path, snapshot: &KbSnapshot) -> Result<SqliteExportStats, String> {
+    let mut conn = Connection::open(path).map_err(|e| format!("Failed to create export database: {}", e))?;
+    let stats = write_snapshot(&mut conn, snapshot)?;
+    conn.close().map_err(|(_, e)| format!("Failed to close export database: {}", e))?;
+    Ok(stats)
+}
+
+/// Create the export schema on an empty connection and fill it in one transaction
+pub fn write_snapshot(conn: &mut Connection, snapshot: &KbSnapshot) -> Result<SqliteExportStats, String> {
+    let tx = conn.transaction().map_err(|e| format!("Failed to start export: {}", e))?;
+    tx.execute_batch(SCHEMA).map_err(|e| format!("Failed to create export schema: {}", e))?;
+
+    let exported_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    for (key, value) in [
+        ("schema_version", SQLITE_EXPORT_SCHEMA_VERSION.to_string()),
+        ("app_version", env!("CARGO_PKG_VERSION").to_string()),
+        ("exported_at", exported_at.to_string()),
+        ("embedding_format", "f32le".to_string()),
+    ] {
+        tx.execute("INSERT INTO export_meta (key, value) VALUES (?1, ?2)", params![key, value])
+            .map_err(|e| format!("Failed to write export metadata: {}", e))?;
+    }
+
+    let mut stats = SqliteExportStats { schema_version: SQLITE_EXPORT_SCHEMA_VERSION, ..Default::default() };
+    {
+        let mut insert = prepare(&tx, "INSERT OR REPLACE INTO meetings VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)")?;
+        for m in &snapshot.meetings {
+            insert.execute(params![
+                record_id("meeting", &m.id),
+                m.title,
+                m.start_time as i64,
+                m.end_time.map(|t| t as i64),
+                json_list(&m.participants),
+                m.summary,
+                json_list(&m.tags),
+                m.favorite,
+                m.private,
+                m.dominant_language,
+            ]).map_err(|e| format!("Failed to export meeting: {}", e))?;
+            stats.meetings += 1;
+        }
+
+        let mut insert = prepare(&tx, "INSERT OR REPLACE INTO segments VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)")?;
+        for s in &snapshot.segments {
+            insert.execute(params![
+                record_id("segment", &s.id),
+                normalize_record_id("meeting", &s.meeting_id),
+                s.speaker,
+                s.text,
+                s.start_ms as i64,
+                s.end_ms as i64,
+                s.speaker_confidence,
+                s.overlapped,
+                s.language,
+                s.emotion,
+                embedding_blob(&s.embedding),
+            ]).map_err(|e| format!("Failed to export segment: {}", e))?;
+            stats.segments += 1;
+        }
+
+        let mut insert = prepare(&tx, "INSERT OR REPLACE INTO action_items VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)")?;
+        for a in &snapshot.action_items {
+            insert.execute(params![
+                record_id("action_item", &a.id),
+                normalize_record_id("meeting", &a.meeting_id),
+                a.text,
+                a.assignee,
+                a.deadline,
+                a.status,
+                a.created_at as i64,
+            ]).map_err(|e| format!("Failed to export action item: {}", e))?;
+            stats.action_items += 1;
+        }
+
+        let mut insert = prepare(&tx, "INSERT OR REPLACE INTO decisions VALUES (?1, ?2, ?3, ?4, ?5)")?;
+        for d in &snapshot.decisions {
+            insert.execute(params![
+                record_id("decision", &d.id),
+                normalize_record_id("meeting", &d.meeting_id),
+                d.text,
+                json_list(&d.participants),
+                d.created_at as i64,
+            ]).map_err(|e| format!("Failed to export decision: {}", e))?;
+            stats.decisions += 1;
+        }
+
+        let mut insert = prepare(&tx, "INSERT OR REPLACE INTO people VALUES (?1, ?2, ?3, ?4, ?5)")?;
+        for p in &snapshot.people {
+            insert.execute(params![
+                record_id("person", &p.id),
+                p.name,
+                json_list(&p.aliases),
+                p.first_seen as i64,
+                p.last_seen as i64,
+            ]).map_err(|e| format!("Failed to export person: {}", e))?;
+            stats.people += 1;
+        }
+
+        let mut insert = prepare(&tx, "INSERT OR REPLACE INTO topics VALUES (?1, ?2, ?3, ?4, ?5)")?;
+        for t in &snapshot.topics {
+            insert.execute(params![
+                record_id("topic", &t.id),
+                t.name,
+                t.mention_count,
+                t.last_mentioned as i64,
+                embedding_blob(&t.embedding),
+            ]).map_err(|e| format!("Failed to export topic: {}", e))?;
+            stats.topics += 1;
+        }
+
+        let mut insert = prepare(&tx, "INSERT OR REPLACE INTO knowledge_sources VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)")?;
+        for s in &snapshot.sources {
+            insert.execute(params![
+                record_id("knowledge_source", &s.id),
+                s.url,
+                s.title,
+                s.source_type,
+                s.raw_content,
+                json_list(&s.tags),
+                s.pinned,
+                s.created_at as i64,
+                s.last_updated as i64,
+            ]).map_err(|e| format!("Failed to export knowledge source: {}", e))?;
+            stats.sources += 1;
+        }
+
+        let mut insert = prepare(&tx, "INSERT OR REPLACE INTO knowledge_chunks VALUES (?1, ?2, ?3, ?4, ?5)")?;
+        for c in &snapshot.chunks {
+            insert.execute(params![
+                record_id("knowledge_chunk", &c.id),
+                normalize_record_id("knowledge_source", &c.source_id),
+                c.chunk_index,
+                c.text,
+                embedding_blob(&c.embedding),
+            ]).map_err(|e| format!("Failed to export knowledge chunk: {}", e))?;
+            stats.chunks += 1;
+        }
+    }
+
+    tx.commit().map_err(|e| format!("Failed to finish export: {}", e))?;
+    Ok(stats)
+}
+
+fn prepare<'a>(tx: &'a rusqlite::Transaction, sql: &str) -> Result<rusqlite::Statement<'a>, String> {
+    tx.prepare(sql).map_err(|e| format!("Failed to prepare export statement: {}", e))
+}
+
+fn record_id(table: &str, id: &Option<Thing>) -> String {
+    id.as_ref()
+        .map(|t| normalize_record_id(table, &t.to_string()))
+        .unwrap_or_default()
+}
+
+fn json_list(values: &[String]) -> String {
+    serde_json::to_string(values).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Little-endian f32 bytes, or None so rows without an embedding store NULL
+fn embedding_blob(embedding: &[f32]) -> Option<Vec<u8>> {
+    if embedding.is_empty() {
+        return None;
+    }
+    Some(embedding.iter().flat_map(|v| v.to_le_bytes()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn thing(table: &str, id: &str) -> Option<Thing> {
+        Some(Thing::from((table, id)))
+    }
+
+    fn sample_snapshot() -> KbSnapshot {
+        KbSnapshot {
+            meetings: vec![Meeting {
+                id: thing("meeting", "m1"),
+                title: "Roadmap review".to_string(),
+                start_time: 1_700_000_000_000,
+                end_time: Some(1_700_000_600_000),
+                participants: vec!["Alice".to_string(), "Bob".to_string()],
+                summary: Some("Agreed on Q3 scope".to_string()),
+                tags: vec!["planning".to_string()],
+                favorite: true,
+                private: false,
+                dominant_language: Some("en".to_string()),
+                deleted_at: None,
+            }],
+            segments: vec![TranscriptSegment {
+                id: thing("segment", "s1"),
+                meeting_id: "meeting:m1".to_string(),
+                speaker: "Alice".to_string(),
+                text: "Let's ship search first".to_string(),
+                start_ms: 1_700_000_001_000,
+                end_ms: 1_700_000_003_000,
+                embedding: vec![0.25, -1.5, 3.0],
+                word_timings: Vec::new(),
+                speaker_confidence: Some(0.9),
+                overlapped: false,
+                language: Some("en".to_string()),
+                emotion: None,
+                needs_embedding: false,
+            }],
+            action_items: vec![ActionItem {
+                id: thing("action_item", "a1"),
+                meeting_id: "m1".to_string(),
+                text: "Draft the search spec".to_string(),
+                assignee: Some("Bob".to_string()),
+                deadline: Some("Friday".to_string()),
+                status: "open".to_string(),
+                created_at: 1_700_000_100_000,
+            }],
+            decisions: vec![Decision {
+                id: thing("decision", "d1"),
+                meeting_id: "m1".to_string(),
+                text: "Search ships before sync".to_string(),
+                participants: vec!["Alice".to_string()],
+                created_at: 1_700_000_200_000,
+            }],
+            people: vec![Person {
+                id: thing("person", "alice"),
+                name: "Alice".to_string(),
+                aliases: vec!["Ali".to_string()],
+                first_seen: 1_700_000_000_000,
+                last_seen: 1_700_000_600_000,
+            }],
+            topics: vec![Topic {
+                id: thing("topic", "search"),
+                name: "search".to_string(),
+                embedding: Vec::new(),
+                mention_count: 3,
+                last_mentioned: 1_700_000_500_000,
+            }],
+            sources: vec![KnowledgeSource {
+                id: thing("knowledge_source", "k1"),
+                url: "https://example.com/spec".to_string(),
+                title: "Spec".to_string(),
+                source_type: "url".to_string(),
+                raw_content: "Search spec".to_string(),
+                tags: vec!["docs".to_string()],
+                created_at: 1_700_000_000_000,
+                last_updated: 1_700_000_000_000,
+                last_accessed: None,
+                pinned: true,
+                refresh_interval_hours: None,
+                last_refreshed: None,
+            }],
+            chunks: vec![KnowledgeChunk {
+                id: thing("knowledge_chunk", "c1"),
+                source_id: "knowledge_source:k1".to_string(),
+                text: "Search spec".to_string(),
+                chunk_index: 0,
+                embedding: vec![1.0, 2.0],
+                needs_embedding: false,
+            }],
+        }
+    }
+
+    fn decode_embedding(blob: &[u8]) -> Vec<f32> {
+        blob.chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_through_sqlite() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let stats = write_snapshot(&mut conn, &sample_snapshot()).unwrap();
+        assert_eq!(stats.schema_version, SQLITE_EXPORT_SCHEMA_VERSION);
+        assert_eq!((stats.meetings, stats.segments, stats.action_items, stats.decisions), (1, 1, 1, 1));
+        assert_eq!((stats.people, stats.topics, stats.sources, stats.chunks), (1, 1, 1, 1));
+
+        let version: String = conn
+            .query_row("SELECT value FROM export_meta WHERE key = 'schema_version'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(version, SQLITE_EXPORT_SCHEMA_VERSION.to_string());
+
+        let (title, participants, favorite): (String, String, bool) = conn
+            .query_row("SELECT title, participants, favorite FROM meetings WHERE id = 'm1'", [], |r| {
+                Ok((r.get(0)?, r.get(1)?, r.get(2)?))
+            })
+            .unwrap();
+        assert_eq!(title, "Roadmap review");
+        assert_eq!(serde_json::from_str::<Vec<String>>(&participants).unwrap(), vec!["Alice", "Bob"]);
+        assert!(favorite);
+
+        // Segment, action item and decision ids all join back to the bare meeting id
+        let joined: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM meetings m
+                 JOIN segments s ON s.meeting_id = m.id
+                 JOIN action_items a ON a.meeting_id = m.id
+                 JOIN decisions d ON d.meeting_id = m.id",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(joined, 1);
+
+        let (text, blob): (String, Vec<u8>) = conn
+            .query_row("SELECT text, embedding FROM segments WHERE id = 's1'", [], |r| Ok((r.get(0)?, r.get(1)?)))
+            .unwrap();
+        assert_eq!(text, "Let's ship search first");
+        assert_eq!(decode_embedding(&blob), vec![0.25, -1.5, 3.0]);
+
+        let topic_embedding: Option<Vec<u8>> = conn
+            .query_row("SELECT embedding FROM topics WHERE id = 'search'", [], |r| r.get(0))
+            .unwrap();
+        assert!(topic_embedding.is_none());
+
+        let chunk_source: String = conn
+            .query_row("SELECT source_id FROM knowledge_chunks WHERE id = 'c1'", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(chunk_source, "k1");
+    }
+
+    #[test]
+    fn test_export_replaces_existing_file() {
+        let path = std::env::temp_dir().join(format!("second_brain_export_test_{}.db", std::process::id()));
+        std::fs::write(&path, b"not a database").unwrap();
+
+        let stats = export_to_path(&path, &sample_snapshot()).unwrap();
+        assert_eq!(stats.meetings, 1);
+        let conn = Connection::open(&path).unwrap();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM knowledge_sources", [], |r| r.get(0)).unwrap();
+        assert_eq!(count, 1);
+        assert!(!temp_export_path(&path).unwrap().exists());
+
+        drop(conn);
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,202 @@
+//! LLM token accounting
+//!
+//! Every completion reports its prompt/completion token counts (estimated from
+//! text length when the server doesn't return them). The user store keeps
+//! totals per day, task and model in its `usage` table. Cost is estimated from
+//! the `llm_pricing` setting, a JSON map of model name to USD per million
+//! tokens, e.g. `{"gpt-4o": {"prompt": 2.5, "completion": 10.0}}`; models
+//! without a price (local servers) count as free.
+
+use crate::llm_agent::{estimate_tokens, LlmTask};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Token counts for one completion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Rough counts from the text sent and received, for servers that don't report usage
+    pub fn estimate(prompt: &str, completion: &str) -> Self {
+        Self {
+            prompt_tokens: estimate_tokens(prompt) as u64,
+            completion_tokens: estimate_tokens(completion) as u64,
+        }
+    }
+}
+
+/// One completion's usage, as passed to the assistant's usage callback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmUsage {
+    pub task: LlmTask,
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+/// USD per million tokens for one model
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    #[serde(default)]
+    pub prompt: f64,
+    #[serde(default)]
+    pub completion: f64,
+}
+
+/// Per-model prices from the `llm_pricing` setting
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModelPricing {
+    prices: HashMap<String, ModelPrice>,
+}
+
+impl ModelPricing {
+    pub fn from_config(pricing_json: &str) -> Result<Self, String> {
+        if pricing_json.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let prices: HashMap<String, ModelPrice> = serde_json::from_str(pricing_json)
+            .map_err(|e| format!("Invalid llm_pricing setting: {}", e))?;
+        for (model, price) in &prices {
+            if !(price.prompt >= 0.0 && price.completion >= 0.0) {
+                return Err(format!("Invalid price for {} (expected non-negative USD per million tokens)", model));
+            }
+        }
+        Ok(Self { prices })
+    }
+
+    /// Price for `model`, matching the name case-insensitively
+    pub fn price(&self, model: &str) -> Option<ModelPrice> {
+        self.prices.get(model).copied().or_else(|| {
+            self.prices.iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(model))
+                .map(|(_, price)| *price)
+        })
+    }
+
+    /// Estimated USD cost of `usage` on `model` (0 when the model has no price)
+    pub fn cost(&self, model: &str, usage: TokenUsage) -> f64 {
+        self.price(model)
+            .map(|p| (usage.prompt_tokens as f64 * p.prompt + usage.completion_tokens as f64 * p.completion) / 1_000_000.0)
+            .unwrap_or(0.0)
+    }
+}
+
+/// Stored totals for one day, task and model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageRow {
+    pub day: String,
+    pub task: String,
+    pub model: String,
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+/// Request count, tokens and estimated cost
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub requests: u64,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+    pub estimated_cost: f64,
+}
+
+impl UsageTotals {
+    /// Count one completion
+    pub fn add(&mut self, usage: TokenUsage, cost: f64) {
+        self.requests += 1;
+        self.prompt_tokens += usage.prompt_tokens;
+        self.completion_tokens += usage.completion_tokens;
+        self.estimated_cost += cost;
+    }
+
+    fn add_row(&mut self, row: &UsageRow, cost: f64) {
+        self.requests += row.requests;
+        self.prompt_tokens += row.prompt_tokens;
+        self.completion_tokens += row.completion_tokens;
+        self.estimated_cost += cost;
+    }
+}
+
+/// Usage since a date, broken down by task, day and model
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UsageStats {
+    pub since: Option<String>,
+    pub total: UsageTotals,
+    pub by_task: BTreeMap<String, UsageTotals>,
+    pub by_day: BTreeMap<String, UsageTotals>,
+    pub by_model: BTreeMap<String, UsageTotals>,
+    /// Models used without a configured price (their cost is counted as 0)
+    pub unpriced_models: Vec<String>,
+    /// Completions since the app started
+    pub session: UsageTotals,
+}
+
+impl UsageStats {
+    pub fn from_rows(since: Option<String>, rows: &[UsageRow], pricing: &ModelPricing) -> Self {
+        let mut stats = UsageStats { since, ..Default::default() };
+        for row in rows {
+            let usage = TokenUsage { prompt_tokens: row.prompt_tokens, completion_tokens: row.completion_tokens };
+            let cost = pricing.cost(&row.model, usage);
+            stats.total.add_row(row, cost);
+            stats.by_task.entry(row.task.clone()).or_default().add_row(row, cost);
+            stats.by_day.entry(row.day.clone()).or_default().add_row(row, cost);
+            stats.by_model.entry(row.model.clone()).or_default().add_row(row, cost);
+            if pricing.price(&row.model).is_none() && !stats.unpriced_models.contains(&row.model) {
+                stats.unpriced_models.push(row.model.clone());
+            }
+        }
+        stats.unpriced_models.sort();
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(day: &str, task: &str, model: &str, prompt_tokens: u64, completion_tokens: u64) -> UsageRow {
+        UsageRow {
+            day: day.to_string(),
+            task: task.to_string(),
+            model: model.to_string(),
+            requests: 2,
+            prompt_tokens,
+            completion_tokens,
+        }
+    }
+
+    #[test]
+    fn test_pricing_config() {
+        let pricing = ModelPricing::from_config(r#"{"GPT-4o": {"prompt": 2.5, "completion": 10.0}}"#).unwrap();
+        assert_eq!(pricing.price("gpt-4o").map(|p| p.prompt), Some(2.5));
+        let usage = TokenUsage { prompt_tokens: 1_000_000, completion_tokens: 100_000 };
+        assert!((pricing.cost("gpt-4o", usage) - 3.5).abs() < 1e-9);
+        assert_eq!(pricing.cost("llama3", usage), 0.0);
+
+        assert!(ModelPricing::from_config("").unwrap().price("gpt-4o").is_none());
+        assert!(ModelPricing::from_config(r#"{"gpt-4o": {"prompt": -1}}"#).is_err());
+        assert!(ModelPricing::from_config("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn test_usage_stats_from_rows() {
+        let pricing = ModelPricing::from_config(r#"{"gpt-4o": {"prompt": 1.0, "completion": 2.0}}"#).unwrap();
+        let rows = vec![
+            row("2026-10-01", "suggestions", "gpt-4o", 500_000, 100_000),
+            row("2026-10-01", "summary", "llama3", 2_000, 300),
+            row("2026-10-02", "suggestions", "gpt-4o", 500_000, 400_000),
+        ];
+        let stats = UsageStats::from_rows(Some("2026-10-01".to_string()), &rows, &pricing);
+
+        assert_eq!(stats.total.requests, 6);
+        assert_eq!(stats.total.prompt_tokens, 1_002_000);
+        assert!((stats.total.estimated_cost - 2.0).abs() < 1e-9);
+        assert_eq!(stats.by_task["suggestions"].requests, 4);
+        assert_eq!(stats.by_day.len(), 2);
+        assert_eq!(stats.by_model["llama3"].estimated_cost, 0.0);
+        assert_eq!(stats.unpriced_models, vec!["llama3".to_string()]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::usage::{LlmUsage, UsageRow};
 use rusqlite::{Connection, params};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -10,9 +11,97 @@ pub struct UserSettings {
     pub llm_url: String,                  // LLM API endpoint
     pub llm_model: String,                // Model name
     pub llm_api_key: String,              // API key for LLM (optional for local servers)
+    #[serde(default = "default_llm_provider")]
+    pub llm_provider: String,             // "openai", "ollama", "anthropic"
     pub auto_record: bool,                // Auto-start recording on meeting
     pub notifications_enabled: bool,
+    #[serde(default)]
+    pub auto_save_recording: bool,        // Write meeting audio to WAV on end_meeting
     pub language: String,                 // "en", "es", etc.
+    #[serde(default = "default_search_backend")]
+    pub search_backend: String,           // "duckduckgo", "searxng", "brave"
+    #[serde(default)]
+    pub searxng_url: String,              // Base URL of a SearXNG instance
+    #[serde(default)]
+    pub brave_api_key: String,            // Brave Search API subscription token
+    #[serde(default = "default_true")]
+    pub respect_robots: bool,             // Skip URLs disallowed by robots.txt when crawling
+    #[serde(default = "default_crawl_timeout_secs")]
+    pub crawl_timeout_secs: u64,          // Abort page fetches after this long
+    #[serde(default = "default_crawl_max_bytes")]
+    pub crawl_max_bytes: u64,             // Refuse pages larger than this
+    #[serde(default)]
+    pub redact_on_store: bool,            // Scrub PII from transcript segments before saving
+    #[serde(default)]
+    pub redaction_patterns: String,       // JSON list of custom redaction rules ("" = built-in)
+    #[serde(default = "default_entity_min_confidence")]
+    pub entity_min_confidence: f32,       // Drop GLiNER spans scored below this
+    #[serde(default)]
+    pub entity_labels: String,            // JSON list of GLiNER labels ("" = built-in)
+    #[serde(default)]
+    pub entity_label_kinds: String,       // JSON map of label -> graph kind for custom labels
+    #[serde(default = "default_resume_window_minutes")]
+    pub resume_window_minutes: u64,       // Meetings ended longer ago than this cannot be resumed
+    #[serde(default)]
+    pub embedding_model: String,          // Embedding model id ("" = built-in default)
+    #[serde(default)]
+    pub llm_generation_params: String,    // JSON map of task -> LLM params ("" = built-in)
+    #[serde(default)]
+    pub auto_stop_enabled: bool,          // Stop recording after a long stretch of silence
+    #[serde(default = "default_auto_stop_silence_minutes")]
+    pub auto_stop_silence_minutes: u64,   // Silence that triggers the auto-stop warning
+    #[serde(default)]
+    pub offline_mode: bool,               // Refuse all network calls; LLM must be local
+    #[serde(default)]
+    pub developer_mode: bool,             // Enables developer tools such as raw KB queries
+    #[serde(default)]
+    pub agent_suggestions: bool,          // Let the LLM search (tools) for realtime suggestions
+    #[serde(default = "default_entity_min_confidence")]
+    pub relation_min_confidence: f32,     // Drop extracted relationships scored below this
+    #[serde(default)]
+    pub relation_whitelist: String,       // JSON list of relation types to store ("" = all)
+    #[serde(default)]
+    pub active_prompt_template: String,   // Name of the prompt template in use ("" = built-in)
+    #[serde(default)]
+    pub wake_word_enabled: bool,          // Listen for the wake phrase while not recording
+    #[serde(default = "default_wake_word_phrase")]
+    pub wake_word_phrase: String,         // Phrase the keyword spotter listens for
+    #[serde(default = "default_wake_word_sensitivity")]
+    pub wake_word_sensitivity: f32,       // Keyword spotter sensitivity (0-1, higher fires more easily)
+    #[serde(default)]
+    pub wake_word_auto_start: bool,       // Start recording when the wake phrase is heard
+    #[serde(default)]
+    pub retrieval_params: String,         // JSON Graph-RAG retrieval counts ("" = defaults)
+    #[serde(default)]
+    pub llm_pricing: String,              // JSON map of model -> USD per million tokens ("" = free)
+    #[serde(default)]
+    pub retention_days: u64,              // Delete meetings older than this on startup (0 = keep forever)
+    #[serde(default)]
+    pub merge_turns: bool,                // Store one segment per completed speaker turn
+    #[serde(default)]
+    pub meeting_alerts: String,           // JSON list of keywords/alerts to watch transcripts for
+    #[serde(default)]
+    pub llm_fallbacks: String,            // JSON list of endpoints tried when the main LLM is unreachable
+    #[serde(default = "default_true")]
+    pub suggestions_enabled: bool,        // Generate realtime suggestions while recording
+    #[serde(default = "default_suggestion_every_n")]
+    pub suggestion_every_n: u64,          // Suggest on every nth final transcript (0 = never by count)
+    #[serde(default = "default_true")]
+    pub suggest_on_turn_complete: bool,   // Suggest whenever a speaker finishes a turn
+    #[serde(default)]
+    pub suggestion_min_interval_secs: u64, // Minimum gap between suggestions (0 = no limit)
+    #[serde(default = "default_inference_concurrency")]
+    pub inference_concurrency: u64,       // Embedding/entity model passes allowed to run at once (per engine)
+    #[serde(default = "default_mic_speaker_label")]
+    pub mic_speaker_label: String,        // Speaker name for microphone segments
+    #[serde(default = "default_remote_speaker_label")]
+    pub remote_speaker_label: String,     // Speaker name for system audio until diarization relabels it
+    #[serde(default = "default_chunk_strategy")]
+    pub chunk_strategy: String,           // fixed_size, by_heading or semantic
+    #[serde(default = "default_screen_share_protection")]
+    pub screen_share_protection: bool,    // Hide app windows from screen recordings and shares
+    #[serde(default = "default_max_recording_minutes")]
+    pub max_recording_minutes: u64,       // Stop recording after this many minutes of active recording (0 = no limit)
     pub created_at: String,
     pub updated_at: String,
 }
@@ -25,15 +114,134 @@ impl Default for UserSettings {
             llm_url: String::new(),  // Empty by default - user must configure
             llm_model: String::new(),
             llm_api_key: String::new(),  // Empty for local servers
+            llm_provider: default_llm_provider(),
             auto_record: false,
             notifications_enabled: true,
+            auto_save_recording: false,
             language: "en".to_string(),
+            search_backend: default_search_backend(),
+            searxng_url: String::new(),
+            brave_api_key: String::new(),
+            respect_robots: true,
+            crawl_timeout_secs: default_crawl_timeout_secs(),
+            crawl_max_bytes: default_crawl_max_bytes(),
+            redact_on_store: false,
+            redaction_patterns: String::new(),
+            entity_min_confidence: default_entity_min_confidence(),
+            entity_labels: String::new(),
+            entity_label_kinds: String::new(),
+            resume_window_minutes: default_resume_window_minutes(),
+            embedding_model: String::new(),
+            llm_generation_params: String::new(),
+            auto_stop_enabled: false,
+            auto_stop_silence_minutes: default_auto_stop_silence_minutes(),
+            offline_mode: false,
+            developer_mode: false,
+            agent_suggestions: false,
+            relation_min_confidence: default_entity_min_confidence(),
+            relation_whitelist: String::new(),
+            active_prompt_template: String::new(),
+            wake_word_enabled: false,
+            wake_word_phrase: default_wake_word_phrase(),
+            wake_word_sensitivity: default_wake_word_sensitivity(),
+            wake_word_auto_start: false,
+            retrieval_params: String::new(),
+            llm_pricing: String::new(),
+            retention_days: 0,
+            merge_turns: false,
+            meeting_alerts: String::new(),
+            llm_fallbacks: String::new(),
+            suggestions_enabled: true,
+            suggestion_every_n: default_suggestion_every_n(),
+            suggest_on_turn_complete: true,
+            suggestion_min_interval_secs: 0,
+            inference_concurrency: default_inference_concurrency(),
+            mic_speaker_label: default_mic_speaker_label(),
+            remote_speaker_label: default_remote_speaker_label(),
+            chunk_strategy: default_chunk_strategy(),
+            screen_share_protection: default_screen_share_protection(),
+            max_recording_minutes: default_max_recording_minutes(),
             created_at: String::new(),
             updated_at: String::new(),
         }
     }
 }
 
+fn default_llm_provider() -> String {
+    "openai".to_string()
+}
+
+fn default_search_backend() -> String {
+    "duckduckgo".to_string()
+}
+
+pub(crate) fn default_true() -> bool {
+    true
+}
+
+/// Screen share protection is on by default only where it works reliably (macOS)
+pub fn default_screen_share_protection() -> bool {
+    cfg!(target_os = "macos")
+}
+
+/// `default_screen_share_protection` as a SQL literal for column defaults
+#[cfg(target_os = "macos")]
+const SCREEN_SHARE_PROTECTION_SQL_DEFAULT: &str = "1";
+#[cfg(not(target_os = "macos"))]
+const SCREEN_SHARE_PROTECTION_SQL_DEFAULT: &str = "0";
+
+fn default_crawl_timeout_secs() -> u64 {
+    30
+}
+
+fn default_crawl_max_bytes() -> u64 {
+    5 * 1024 * 1024
+}
+
+fn default_entity_min_confidence() -> f32 {
+    crate::entities::DEFAULT_MIN_CONFIDENCE
+}
+
+fn default_resume_window_minutes() -> u64 {
+    60
+}
+
+fn default_auto_stop_silence_minutes() -> u64 {
+    10
+}
+
+fn default_suggestion_every_n() -> u64 {
+    3
+}
+
+fn default_inference_concurrency() -> u64 {
+    crate::inference_gate::DEFAULT_INFERENCE_CONCURRENCY as u64
+}
+
+fn default_mic_speaker_label() -> String {
+    crate::speaker_diarization::DEFAULT_MIC_SPEAKER.to_string()
+}
+
+fn default_remote_speaker_label() -> String {
+    crate::speaker_diarization::DEFAULT_REMOTE_SPEAKER.to_string()
+}
+
+fn default_chunk_strategy() -> String {
+    "fixed_size".to_string()
+}
+
+fn default_max_recording_minutes() -> u64 {
+    720
+}
+
+fn default_wake_word_phrase() -> String {
+    crate::wake_word::DEFAULT_PHRASE.to_string()
+}
+
+fn default_wake_word_sensitivity() -> f32 {
+    crate::wake_word::DEFAULT_SENSITIVITY
+}
+
 /// Quick note (not tied to meetings)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Note {
@@ -67,6 +275,39 @@ pub struct SavedSearch {
     pub created_at: String,
 }
 
+/// User-written prompt template wrapping the assistant's built-in prompts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub template: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+/// Map an `id, content, tags, pinned, created_at, updated_at` row to a Note
+fn note_from_row(row: &rusqlite::Row) -> rusqlite::Result<Note> {
+    let tags_json: String = row.get(2)?;
+    Ok(Note {
+        id: row.get(0)?,
+        content: row.get(1)?,
+        tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+        pinned: row.get::<_, i32>(3)? != 0,
+        created_at: row.get(4)?,
+        updated_at: row.get(5)?,
+    })
+}
+
+/// Turn free text into an FTS5 query: every word must match, as a prefix
+/// Words are quoted so user input can't trip FTS5 syntax (quotes, AND/OR, colons).
+fn fts_match_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query.split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
 /// The user data store backed by SQLite
 pub struct UserStore {
     conn: Connection,
@@ -95,7 +336,7 @@ impl UserStore {
 
     /// Initialize database schema
     fn init_schema(&self) -> Result<(), String> {
-        self.conn.execute_batch(r#"
+        self.conn.execute_batch(&format!(r#"
             -- User settings (singleton table)
             CREATE TABLE IF NOT EXISTS settings (
                 id INTEGER PRIMARY KEY CHECK (id = 1),
@@ -103,9 +344,53 @@ impl UserStore {
                 llm_url TEXT NOT NULL DEFAULT '',
                 llm_model TEXT NOT NULL DEFAULT '',
                 llm_api_key TEXT NOT NULL DEFAULT '',
+                llm_provider TEXT NOT NULL DEFAULT 'openai',
                 auto_record INTEGER NOT NULL DEFAULT 0,
                 notifications_enabled INTEGER NOT NULL DEFAULT 1,
+                auto_save_recording INTEGER NOT NULL DEFAULT 0,
                 language TEXT NOT NULL DEFAULT 'en',
+                search_backend TEXT NOT NULL DEFAULT 'duckduckgo',
+                searxng_url TEXT NOT NULL DEFAULT '',
+                brave_api_key TEXT NOT NULL DEFAULT '',
+                respect_robots INTEGER NOT NULL DEFAULT 1,
+                crawl_timeout_secs INTEGER NOT NULL DEFAULT 30,
+                crawl_max_bytes INTEGER NOT NULL DEFAULT 5242880,
+                redact_on_store INTEGER NOT NULL DEFAULT 0,
+                redaction_patterns TEXT NOT NULL DEFAULT '',
+                entity_min_confidence REAL NOT NULL DEFAULT 0.5,
+                entity_labels TEXT NOT NULL DEFAULT '',
+                entity_label_kinds TEXT NOT NULL DEFAULT '',
+                resume_window_minutes INTEGER NOT NULL DEFAULT 60,
+                embedding_model TEXT NOT NULL DEFAULT '',
+                llm_generation_params TEXT NOT NULL DEFAULT '',
+                auto_stop_enabled INTEGER NOT NULL DEFAULT 0,
+                auto_stop_silence_minutes INTEGER NOT NULL DEFAULT 10,
+                offline_mode INTEGER NOT NULL DEFAULT 0,
+                developer_mode INTEGER NOT NULL DEFAULT 0,
+                agent_suggestions INTEGER NOT NULL DEFAULT 0,
+                relation_min_confidence REAL NOT NULL DEFAULT 0.5,
+                relation_whitelist TEXT NOT NULL DEFAULT '',
+                active_prompt_template TEXT NOT NULL DEFAULT '',
+                wake_word_enabled INTEGER NOT NULL DEFAULT 0,
+                wake_word_phrase TEXT NOT NULL DEFAULT 'hey second brain',
+                wake_word_sensitivity REAL NOT NULL DEFAULT 0.5,
+                wake_word_auto_start INTEGER NOT NULL DEFAULT 0,
+                retrieval_params TEXT NOT NULL DEFAULT '',
+                llm_pricing TEXT NOT NULL DEFAULT '',
+                retention_days INTEGER NOT NULL DEFAULT 0,
+                merge_turns INTEGER NOT NULL DEFAULT 0,
+                meeting_alerts TEXT NOT NULL DEFAULT '',
+                llm_fallbacks TEXT NOT NULL DEFAULT '',
+                suggestions_enabled INTEGER NOT NULL DEFAULT 1,
+                suggestion_every_n INTEGER NOT NULL DEFAULT 3,
+                suggest_on_turn_complete INTEGER NOT NULL DEFAULT 1,
+                suggestion_min_interval_secs INTEGER NOT NULL DEFAULT 0,
+                inference_concurrency INTEGER NOT NULL DEFAULT 1,
+                mic_speaker_label TEXT NOT NULL DEFAULT 'You',
+                remote_speaker_label TEXT NOT NULL DEFAULT 'Guest',
+                chunk_strategy TEXT NOT NULL DEFAULT 'fixed_size',
+                screen_share_protection INTEGER NOT NULL DEFAULT {SCREEN_SHARE_PROTECTION_SQL_DEFAULT},
+                max_recording_minutes INTEGER NOT NULL DEFAULT 720,
                 created_at TEXT NOT NULL DEFAULT (datetime('now')),
                 updated_at TEXT NOT NULL DEFAULT (datetime('now'))
             );
@@ -126,6 +411,33 @@ impl UserStore {
                 updated_at TEXT NOT NULL DEFAULT (datetime('now'))
             );
 
+            -- Notes linked to meetings (meeting_id is the bare knowledge base id)
+            CREATE TABLE IF NOT EXISTS note_meeting (
+                note_id INTEGER NOT NULL,
+                meeting_id TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                PRIMARY KEY (note_id, meeting_id)
+            );
+
+            -- Full-text index over note content (kept in sync by triggers)
+            CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+                content,
+                content = 'notes',
+                content_rowid = 'id'
+            );
+
+            CREATE TRIGGER IF NOT EXISTS notes_fts_insert AFTER INSERT ON notes BEGIN
+                INSERT INTO notes_fts(rowid, content) VALUES (new.id, new.content);
+            END;
+            CREATE TRIGGER IF NOT EXISTS notes_fts_delete AFTER DELETE ON notes BEGIN
+                INSERT INTO notes_fts(notes_fts, rowid, content) VALUES ('delete', old.id, old.content);
+                DELETE FROM note_meeting WHERE note_id = old.id;
+            END;
+            CREATE TRIGGER IF NOT EXISTS notes_fts_update AFTER UPDATE OF content ON notes BEGIN
+                INSERT INTO notes_fts(notes_fts, rowid, content) VALUES ('delete', old.id, old.content);
+                INSERT INTO notes_fts(rowid, content) VALUES (new.id, new.content);
+            END;
+
             -- Integrations/connected tools
             CREATE TABLE IF NOT EXISTS integrations (
                 id TEXT PRIMARY KEY,
@@ -146,6 +458,24 @@ impl UserStore {
                 created_at TEXT NOT NULL DEFAULT (datetime('now'))
             );
 
+            -- Named prompt templates (the active one is picked by settings.active_prompt_template)
+            CREATE TABLE IF NOT EXISTS prompt_templates (
+                name TEXT PRIMARY KEY,
+                template TEXT NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            );
+
+            -- LLM token usage, accumulated per day, task and model
+            CREATE TABLE IF NOT EXISTS usage (
+                day TEXT NOT NULL,
+                task TEXT NOT NULL,
+                model TEXT NOT NULL,
+                requests INTEGER NOT NULL DEFAULT 0,
+                prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                completion_tokens INTEGER NOT NULL DEFAULT 0,
+                PRIMARY KEY (day, task, model)
+            );
+
             -- App state (key-value for misc stuff)
             CREATE TABLE IF NOT EXISTS app_state (
                 key TEXT PRIMARY KEY,
@@ -155,7 +485,8 @@ impl UserStore {
             -- Create indexes
             CREATE INDEX IF NOT EXISTS idx_notes_pinned ON notes(pinned);
             CREATE INDEX IF NOT EXISTS idx_notes_created ON notes(created_at DESC);
-        "#).map_err(|e| format!("Failed to create schema: {}", e))?;
+            CREATE INDEX IF NOT EXISTS idx_note_meeting_meeting ON note_meeting(meeting_id);
+        "#)).map_err(|e| format!("Failed to create schema: {}", e))?;
 
         // Run migrations for existing databases
         self.run_migrations()?;
@@ -171,6 +502,268 @@ impl UserStore {
             [],
         ); // Ignore error if column already exists
 
+        // Add llm_provider column (existing configs keep the OpenAI-compatible default)
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN llm_provider TEXT NOT NULL DEFAULT 'openai'",
+            [],
+        );
+
+        // Add auto_save_recording column
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN auto_save_recording INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Add web search backend columns
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN search_backend TEXT NOT NULL DEFAULT 'duckduckgo'",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN searxng_url TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN brave_api_key TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Add respect_robots column (crawler politeness is on unless turned off)
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN respect_robots INTEGER NOT NULL DEFAULT 1",
+            [],
+        );
+
+        // Add crawl limits
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN crawl_timeout_secs INTEGER NOT NULL DEFAULT 30",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN crawl_max_bytes INTEGER NOT NULL DEFAULT 5242880",
+            [],
+        );
+
+        // Add PII redaction settings
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN redact_on_store INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN redaction_patterns TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Add entity extraction settings
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN entity_min_confidence REAL NOT NULL DEFAULT 0.5",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN entity_labels TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN entity_label_kinds TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Add meeting resume window
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN resume_window_minutes INTEGER NOT NULL DEFAULT 60",
+            [],
+        );
+
+        // Add embedding model selection
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN embedding_model TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Add per-task LLM generation params
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN llm_generation_params TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Add silence auto-stop toggle
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN auto_stop_enabled INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Add silence auto-stop threshold
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN auto_stop_silence_minutes INTEGER NOT NULL DEFAULT 10",
+            [],
+        );
+
+        // Add offline mode
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN offline_mode INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Add developer mode
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN developer_mode INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Add agent-driven realtime suggestions
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN agent_suggestions INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Relationship confidence threshold
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN relation_min_confidence REAL NOT NULL DEFAULT 0.5",
+            [],
+        );
+
+        // Relation type whitelist (empty keeps every type)
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN relation_whitelist TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Active prompt template
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN active_prompt_template TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Wake word listening, off by default
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN wake_word_enabled INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Wake phrase for keyword spotting
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN wake_word_phrase TEXT NOT NULL DEFAULT 'hey second brain'",
+            [],
+        );
+
+        // Keyword spotter sensitivity
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN wake_word_sensitivity REAL NOT NULL DEFAULT 0.5",
+            [],
+        );
+
+        // Auto-start recording on the wake phrase
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN wake_word_auto_start INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Graph-RAG retrieval counts (JSON; empty = defaults)
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN retrieval_params TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Per-model LLM prices for usage cost estimates
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN llm_pricing TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Meeting retention policy (0 = keep forever)
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN retention_days INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Merge ASR chunks into whole speaker turns (on unless turned off)
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN merge_turns INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Keyword alerts on live transcripts
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN meeting_alerts TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Fallback LLM endpoints, tried in order when the main one is down
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN llm_fallbacks TEXT NOT NULL DEFAULT ''",
+            [],
+        );
+
+        // Realtime suggestion cadence (defaults match the old hardcoded trigger)
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN suggestions_enabled INTEGER NOT NULL DEFAULT 1",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN suggestion_every_n INTEGER NOT NULL DEFAULT 3",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN suggest_on_turn_complete INTEGER NOT NULL DEFAULT 1",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN suggestion_min_interval_secs INTEGER NOT NULL DEFAULT 0",
+            [],
+        );
+
+        // Concurrent ONNX passes allowed per engine
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN inference_concurrency INTEGER NOT NULL DEFAULT 1",
+            [],
+        );
+
+        // Speaker labels for the microphone and remote audio
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN mic_speaker_label TEXT NOT NULL DEFAULT 'You'",
+            [],
+        );
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN remote_speaker_label TEXT NOT NULL DEFAULT 'Guest'",
+            [],
+        );
+
+        // How knowledge sources are split into chunks
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN chunk_strategy TEXT NOT NULL DEFAULT 'fixed_size'",
+            [],
+        );
+
+        // Whether app windows are hidden from screen recordings and shares
+        let _ = self.conn.execute(
+            &format!("ALTER TABLE settings ADD COLUMN screen_share_protection INTEGER NOT NULL DEFAULT {SCREEN_SHARE_PROTECTION_SQL_DEFAULT}"),
+            [],
+        );
+
+        // Hard cap on how long one recording may run
+        let _ = self.conn.execute(
+            "ALTER TABLE settings ADD COLUMN max_recording_minutes INTEGER NOT NULL DEFAULT 720",
+            [],
+        );
+
+        // Index notes written before the full-text table existed
+        if self.get_state("notes_fts_indexed")?.is_none() {
+            self.conn.execute("INSERT INTO notes_fts(notes_fts) VALUES ('rebuild')", [])
+                .map_err(|e| format!("Failed to index notes: {}", e))?;
+            self.set_state("notes_fts_indexed", "1")?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a consistent snapshot of the database to `dest` (used for backups)
+    pub fn backup_to(&self, dest: &std::path::Path) -> Result<(), String> {
+        if dest.exists() {
+            std::fs::remove_file(dest)
+                .map_err(|e| format!("Failed to replace snapshot: {}", e))?;
+        }
+        self.conn.execute("VACUUM INTO ?1", params![dest.to_string_lossy()])
+            .map_err(|e| format!("Failed to snapshot user store: {}", e))?;
         Ok(())
     }
 
@@ -179,7 +772,7 @@ impl UserStore {
     /// Get user settings
     pub fn get_settings(&self) -> Result<UserSettings, String> {
         let mut stmt = self.conn
-            .prepare("SELECT id, theme, llm_url, llm_model, COALESCE(llm_api_key, '') as llm_api_key, auto_record, notifications_enabled, language, created_at, updated_at FROM settings WHERE id = 1")
+            .prepare(&format!("SELECT id, theme, llm_url, llm_model, COALESCE(llm_api_key, '') as llm_api_key, auto_record, notifications_enabled, language, created_at, updated_at, COALESCE(llm_provider, 'openai') as llm_provider, COALESCE(auto_save_recording, 0) as auto_save_recording, COALESCE(search_backend, 'duckduckgo') as search_backend, COALESCE(searxng_url, '') as searxng_url, COALESCE(brave_api_key, '') as brave_api_key, COALESCE(respect_robots, 1) as respect_robots, COALESCE(crawl_timeout_secs, 30) as crawl_timeout_secs, COALESCE(crawl_max_bytes, 5242880) as crawl_max_bytes, COALESCE(redact_on_store, 0) as redact_on_store, COALESCE(redaction_patterns, '') as redaction_patterns, COALESCE(entity_min_confidence, 0.5) as entity_min_confidence, COALESCE(entity_labels, '') as entity_labels, COALESCE(entity_label_kinds, '') as entity_label_kinds, COALESCE(resume_window_minutes, 60) as resume_window_minutes, COALESCE(embedding_model, '') as embedding_model, COALESCE(llm_generation_params, '') as llm_generation_params, COALESCE(auto_stop_enabled, 0) as auto_stop_enabled, COALESCE(auto_stop_silence_minutes, 10) as auto_stop_silence_minutes, COALESCE(offline_mode, 0) as offline_mode, COALESCE(developer_mode, 0) as developer_mode, COALESCE(agent_suggestions, 0) as agent_suggestions, COALESCE(relation_min_confidence, 0.5) as relation_min_confidence, COALESCE(relation_whitelist, '') as relation_whitelist, COALESCE(active_prompt_template, '') as active_prompt_template, COALESCE(wake_word_enabled, 0) as wake_word_enabled, COALESCE(wake_word_phrase, 'hey second brain') as wake_word_phrase, COALESCE(wake_word_sensitivity, 0.5) as wake_word_sensitivity, COALESCE(wake_word_auto_start, 0) as wake_word_auto_start, COALESCE(retrieval_params, '') as retrieval_params, COALESCE(llm_pricing, '') as llm_pricing, COALESCE(retention_days, 0) as retention_days, COALESCE(merge_turns, 0) as merge_turns, COALESCE(meeting_alerts, '') as meeting_alerts, COALESCE(llm_fallbacks, '') as llm_fallbacks, COALESCE(suggestions_enabled, 1) as suggestions_enabled, COALESCE(suggestion_every_n, 3) as suggestion_every_n, COALESCE(suggest_on_turn_complete, 1) as suggest_on_turn_complete, COALESCE(suggestion_min_interval_secs, 0) as suggestion_min_interval_secs, COALESCE(inference_concurrency, 1) as inference_concurrency, COALESCE(mic_speaker_label, 'You') as mic_speaker_label, COALESCE(remote_speaker_label, 'Guest') as remote_speaker_label, COALESCE(chunk_strategy, 'fixed_size') as chunk_strategy, COALESCE(screen_share_protection, {SCREEN_SHARE_PROTECTION_SQL_DEFAULT}) as screen_share_protection, COALESCE(max_recording_minutes, 720) as max_recording_minutes FROM settings WHERE id = 1"))
             .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
         let settings = stmt.query_row([], |row| {
@@ -189,9 +782,53 @@ impl UserStore {
                 llm_url: row.get(2)?,
                 llm_model: row.get(3)?,
                 llm_api_key: row.get(4)?,
+                llm_provider: row.get(10)?,
                 auto_record: row.get::<_, i32>(5)? != 0,
                 notifications_enabled: row.get::<_, i32>(6)? != 0,
+                auto_save_recording: row.get::<_, i32>(11)? != 0,
                 language: row.get(7)?,
+                search_backend: row.get(12)?,
+                searxng_url: row.get(13)?,
+                brave_api_key: row.get(14)?,
+                respect_robots: row.get::<_, i32>(15)? != 0,
+                crawl_timeout_secs: row.get::<_, i64>(16)?.max(1) as u64,
+                crawl_max_bytes: row.get::<_, i64>(17)?.max(1) as u64,
+                redact_on_store: row.get::<_, i32>(18)? != 0,
+                redaction_patterns: row.get(19)?,
+                entity_min_confidence: row.get::<_, f64>(20)? as f32,
+                entity_labels: row.get(21)?,
+                entity_label_kinds: row.get(22)?,
+                resume_window_minutes: row.get::<_, i64>(23)?.max(0) as u64,
+                embedding_model: row.get(24)?,
+                llm_generation_params: row.get(25)?,
+                auto_stop_enabled: row.get::<_, i32>(26)? != 0,
+                auto_stop_silence_minutes: u64::try_from(row.get::<_, i64>(27)?).unwrap_or_else(|_| default_auto_stop_silence_minutes()),
+                offline_mode: row.get::<_, i32>(28)? != 0,
+                developer_mode: row.get::<_, i32>(29)? != 0,
+                agent_suggestions: row.get::<_, i32>(30)? != 0,
+                relation_min_confidence: row.get::<_, f64>(31)? as f32,
+                relation_whitelist: row.get(32)?,
+                active_prompt_template: row.get(33)?,
+                wake_word_enabled: row.get::<_, i32>(34)? != 0,
+                wake_word_phrase: row.get(35)?,
+                wake_word_sensitivity: row.get::<_, f64>(36)? as f32,
+                wake_word_auto_start: row.get::<_, i32>(37)? != 0,
+                retrieval_params: row.get(38)?,
+                llm_pricing: row.get(39)?,
+                retention_days: u64::try_from(row.get::<_, i64>(40)?).unwrap_or(0),
+                merge_turns: row.get::<_, i32>(41)? != 0,
+                meeting_alerts: row.get(42)?,
+                llm_fallbacks: row.get(43)?,
+                suggestions_enabled: row.get::<_, i32>(44)? != 0,
+                suggestion_every_n: u64::try_from(row.get::<_, i64>(45)?).unwrap_or_else(|_| default_suggestion_every_n()),
+                suggest_on_turn_complete: row.get::<_, i32>(46)? != 0,
+                suggestion_min_interval_secs: u64::try_from(row.get::<_, i64>(47)?).unwrap_or(0),
+                inference_concurrency: row.get::<_, i64>(48)?.max(1) as u64,
+                mic_speaker_label: row.get(49)?,
+                remote_speaker_label: row.get(50)?,
+                chunk_strategy: row.get(51)?,
+                screen_share_protection: row.get::<_, i32>(52)? != 0,
+                max_recording_minutes: u64::try_from(row.get::<_, i64>(53)?).unwrap_or_else(|_| default_max_recording_minutes()),
                 created_at: row.get(8)?,
                 updated_at: row.get(9)?,
             })
@@ -202,8 +839,18 @@ impl UserStore {
 
     /// Update user settings
     pub fn update_settings(&self, settings: &UserSettings) -> Result<(), String> {
+        let auto_stop_silence_minutes = i64::try_from(settings.auto_stop_silence_minutes)
+            .map_err(|_| format!("auto_stop_silence_minutes is out of range: {}", settings.auto_stop_silence_minutes))?;
+        let retention_days = i64::try_from(settings.retention_days)
+            .map_err(|_| format!("retention_days is out of range: {}", settings.retention_days))?;
+        let max_recording_minutes = i64::try_from(settings.max_recording_minutes)
+            .map_err(|_| format!("max_recording_minutes is out of range: {}", settings.max_recording_minutes))?;
+        let suggestion_every_n = i64::try_from(settings.suggestion_every_n)
+            .map_err(|_| format!("suggestion_every_n is out of range: {}", settings.suggestion_every_n))?;
+        let suggestion_min_interval_secs = i64::try_from(settings.suggestion_min_interval_secs)
+            .map_err(|_| format!("suggestion_min_interval_secs is out of range: {}", settings.suggestion_min_interval_secs))?;
         self.conn.execute(
-            "UPDATE settings SET theme = ?1, llm_url = ?2, llm_model = ?3, llm_api_key = ?4, auto_record = ?5, notifications_enabled = ?6, language = ?7, updated_at = datetime('now') WHERE id = 1",
+            "UPDATE settings SET theme = ?1, llm_url = ?2, llm_model = ?3, llm_api_key = ?4, auto_record = ?5, notifications_enabled = ?6, language = ?7, llm_provider = ?8, auto_save_recording = ?9, search_backend = ?10, searxng_url = ?11, brave_api_key = ?12, respect_robots = ?13, crawl_timeout_secs = ?14, crawl_max_bytes = ?15, redact_on_store = ?16, redaction_patterns = ?17, entity_min_confidence = ?18, entity_labels = ?19, entity_label_kinds = ?20, resume_window_minutes = ?21, embedding_model = ?22, llm_generation_params = ?23, auto_stop_enabled = ?24, auto_stop_silence_minutes = ?25, offline_mode = ?26, developer_mode = ?27, agent_suggestions = ?28, relation_min_confidence = ?29, relation_whitelist = ?30, active_prompt_template = ?31, wake_word_enabled = ?32, wake_word_phrase = ?33, wake_word_sensitivity = ?34, wake_word_auto_start = ?35, retrieval_params = ?36, llm_pricing = ?37, retention_days = ?38, merge_turns = ?39, meeting_alerts = ?40, llm_fallbacks = ?41, suggestions_enabled = ?42, suggestion_every_n = ?43, suggest_on_turn_complete = ?44, suggestion_min_interval_secs = ?45, inference_concurrency = ?46, mic_speaker_label = ?47, remote_speaker_label = ?48, chunk_strategy = ?49, screen_share_protection = ?50, max_recording_minutes = ?51, updated_at = datetime('now') WHERE id = 1",
             params![
                 settings.theme,
                 settings.llm_url,
@@ -212,6 +859,50 @@ impl UserStore {
                 settings.auto_record as i32,
                 settings.notifications_enabled as i32,
                 settings.language,
+                settings.llm_provider,
+                settings.auto_save_recording as i32,
+                settings.search_backend,
+                settings.searxng_url,
+                settings.brave_api_key,
+                settings.respect_robots as i32,
+                settings.crawl_timeout_secs as i64,
+                settings.crawl_max_bytes as i64,
+                settings.redact_on_store as i32,
+                settings.redaction_patterns,
+                settings.entity_min_confidence as f64,
+                settings.entity_labels,
+                settings.entity_label_kinds,
+                settings.resume_window_minutes as i64,
+                settings.embedding_model,
+                settings.llm_generation_params,
+                settings.auto_stop_enabled as i32,
+                auto_stop_silence_minutes,
+                settings.offline_mode as i32,
+                settings.developer_mode as i32,
+                settings.agent_suggestions as i32,
+                settings.relation_min_confidence as f64,
+                settings.relation_whitelist,
+                settings.active_prompt_template,
+                settings.wake_word_enabled as i32,
+                settings.wake_word_phrase,
+                settings.wake_word_sensitivity as f64,
+                settings.wake_word_auto_start as i32,
+                settings.retrieval_params,
+                settings.llm_pricing,
+                retention_days,
+                settings.merge_turns as i32,
+                settings.meeting_alerts,
+                settings.llm_fallbacks,
+                settings.suggestions_enabled as i32,
+                suggestion_every_n,
+                settings.suggest_on_turn_complete as i32,
+                suggestion_min_interval_secs,
+                settings.inference_concurrency as i64,
+                settings.mic_speaker_label,
+                settings.remote_speaker_label,
+                settings.chunk_strategy,
+                settings.screen_share_protection as i32,
+                max_recording_minutes,
             ],
         ).map_err(|e| format!("Failed to update settings: {}", e))?;
 
@@ -220,7 +911,7 @@ impl UserStore {
 
     /// Update a single setting
     pub fn set_setting(&self, key: &str, value: &str) -> Result<(), String> {
-        let valid_keys = ["theme", "llm_url", "llm_model", "llm_api_key", "language"];
+        let valid_keys = ["theme", "llm_url", "llm_model", "llm_api_key", "llm_provider", "language", "search_backend", "searxng_url", "brave_api_key", "crawl_timeout_secs", "crawl_max_bytes", "redaction_patterns", "entity_min_confidence", "entity_labels", "entity_label_kinds", "resume_window_minutes", "embedding_model", "llm_generation_params", "auto_stop_silence_minutes", "relation_min_confidence", "relation_whitelist", "active_prompt_template", "wake_word_phrase", "wake_word_sensitivity", "retrieval_params", "llm_pricing", "retention_days", "meeting_alerts", "llm_fallbacks", "suggestion_every_n", "suggestion_min_interval_secs", "inference_concurrency", "mic_speaker_label", "remote_speaker_label", "chunk_strategy", "max_recording_minutes"];
         if !valid_keys.contains(&key) {
             return Err(format!("Invalid setting key: {}", key));
         }
@@ -234,7 +925,7 @@ impl UserStore {
 
     /// Update a boolean setting
     pub fn set_setting_bool(&self, key: &str, value: bool) -> Result<(), String> {
-        let valid_keys = ["auto_record", "notifications_enabled"];
+        let valid_keys = ["auto_record", "notifications_enabled", "auto_save_recording", "respect_robots", "redact_on_store", "auto_stop_enabled", "offline_mode", "developer_mode", "agent_suggestions", "wake_word_enabled", "wake_word_auto_start", "merge_turns", "suggestions_enabled", "suggest_on_turn_complete", "screen_share_protection"];
         if !valid_keys.contains(&key) {
             return Err(format!("Invalid boolean setting key: {}", key));
         }
@@ -341,6 +1032,72 @@ impl UserStore {
         Ok(())
     }
 
+    /// Full-text search over note content, best matches first
+    pub fn search_notes(&self, query: &str, limit: usize) -> Result<Vec<Note>, String> {
+        let Some(fts_query) = fts_match_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let mut stmt = self.conn
+            .prepare(
+                "SELECT n.id, n.content, n.tags, n.pinned, n.created_at, n.updated_at
+                 FROM notes_fts JOIN notes n ON n.id = notes_fts.rowid
+                 WHERE notes_fts MATCH ?1
+                 ORDER BY bm25(notes_fts)
+                 LIMIT ?2",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let notes = stmt.query_map(params![fts_query, limit as i64], note_from_row)
+            .map_err(|e| format!("Failed to search notes: {}", e))?;
+
+        notes.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect notes: {}", e))
+    }
+
+    /// Link a note to a meeting (no-op if already linked)
+    pub fn link_note_to_meeting(&self, note_id: i64, meeting_id: &str) -> Result<(), String> {
+        self.get_note(note_id)?;
+        self.conn.execute(
+            "INSERT OR IGNORE INTO note_meeting (note_id, meeting_id) VALUES (?1, ?2)",
+            params![note_id, meeting_id],
+        ).map_err(|e| format!("Failed to link note: {}", e))?;
+        Ok(())
+    }
+
+    /// Remove every note link to a meeting (the notes themselves are kept)
+    pub fn unlink_meeting_notes(&self, meeting_id: &str) -> Result<usize, String> {
+        self.conn.execute("DELETE FROM note_meeting WHERE meeting_id = ?1", params![meeting_id])
+            .map_err(|e| format!("Failed to unlink meeting notes: {}", e))
+    }
+
+    /// Remove a note's link to a meeting
+    pub fn unlink_note_from_meeting(&self, note_id: i64, meeting_id: &str) -> Result<(), String> {
+        self.conn.execute(
+            "DELETE FROM note_meeting WHERE note_id = ?1 AND meeting_id = ?2",
+            params![note_id, meeting_id],
+        ).map_err(|e| format!("Failed to unlink note: {}", e))?;
+        Ok(())
+    }
+
+    /// Notes linked to a meeting, pinned first then newest
+    pub fn get_notes_for_meeting(&self, meeting_id: &str) -> Result<Vec<Note>, String> {
+        let mut stmt = self.conn
+            .prepare(
+                "SELECT n.id, n.content, n.tags, n.pinned, n.created_at, n.updated_at
+                 FROM note_meeting nm JOIN notes n ON n.id = nm.note_id
+                 WHERE nm.meeting_id = ?1
+                 ORDER BY n.pinned DESC, n.created_at DESC",
+            )
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let notes = stmt.query_map(params![meeting_id], note_from_row)
+            .map_err(|e| format!("Failed to query meeting notes: {}", e))?;
+
+        notes.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect notes: {}", e))
+    }
+
     // ==================== INTEGRATIONS ====================
 
     /// Get all integrations
@@ -459,6 +1216,109 @@ impl UserStore {
         Ok(())
     }
 
+    // ==================== PROMPT TEMPLATES ====================
+
+    /// Create or replace a named prompt template
+    pub fn save_prompt_template(&self, name: &str, template: &str) -> Result<PromptTemplate, String> {
+        self.conn.execute(
+            "INSERT INTO prompt_templates (name, template) VALUES (?1, ?2)
+             ON CONFLICT(name) DO UPDATE SET template = excluded.template, updated_at = datetime('now')",
+            params![name, template],
+        ).map_err(|e| format!("Failed to save prompt template: {}", e))?;
+
+        self.get_prompt_template(name)?
+            .ok_or_else(|| format!("Prompt template '{}' not found after saving", name))
+    }
+
+    /// Get a prompt template by name
+    pub fn get_prompt_template(&self, name: &str) -> Result<Option<PromptTemplate>, String> {
+        let mut stmt = self.conn
+            .prepare("SELECT name, template, updated_at FROM prompt_templates WHERE name = ?1")
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let result = stmt.query_row(params![name], |row| {
+            Ok(PromptTemplate {
+                name: row.get(0)?,
+                template: row.get(1)?,
+                updated_at: row.get(2)?,
+            })
+        });
+
+        match result {
+            Ok(template) => Ok(Some(template)),
+            Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+            Err(e) => Err(format!("Failed to get prompt template: {}", e)),
+        }
+    }
+
+    /// Get all prompt templates, by name
+    pub fn get_prompt_templates(&self) -> Result<Vec<PromptTemplate>, String> {
+        let mut stmt = self.conn
+            .prepare("SELECT name, template, updated_at FROM prompt_templates ORDER BY name")
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let templates = stmt.query_map([], |row| {
+            Ok(PromptTemplate {
+                name: row.get(0)?,
+                template: row.get(1)?,
+                updated_at: row.get(2)?,
+            })
+        }).map_err(|e| format!("Failed to query prompt templates: {}", e))?;
+
+        templates.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect prompt templates: {}", e))
+    }
+
+    /// Delete a prompt template
+    pub fn delete_prompt_template(&self, name: &str) -> Result<(), String> {
+        self.conn.execute("DELETE FROM prompt_templates WHERE name = ?1", params![name])
+            .map_err(|e| format!("Failed to delete prompt template: {}", e))?;
+        Ok(())
+    }
+
+    // ==================== LLM USAGE ====================
+
+    /// Add one completion's tokens to today's totals for its task and model
+    pub fn record_usage(&self, usage: &LlmUsage) -> Result<(), String> {
+        self.conn.execute(
+            "INSERT INTO usage (day, task, model, requests, prompt_tokens, completion_tokens)
+             VALUES (date('now', 'localtime'), ?1, ?2, 1, ?3, ?4)
+             ON CONFLICT(day, task, model) DO UPDATE SET
+                requests = requests + 1,
+                prompt_tokens = prompt_tokens + excluded.prompt_tokens,
+                completion_tokens = completion_tokens + excluded.completion_tokens",
+            params![
+                usage.task.as_str(),
+                usage.model,
+                usage.usage.prompt_tokens as i64,
+                usage.usage.completion_tokens as i64,
+            ],
+        ).map_err(|e| format!("Failed to record usage: {}", e))?;
+
+        Ok(())
+    }
+
+    /// Stored usage totals from `since` (YYYY-MM-DD, inclusive; None = all time), oldest first
+    pub fn get_usage(&self, since: Option<&str>) -> Result<Vec<UsageRow>, String> {
+        let mut stmt = self.conn
+            .prepare("SELECT day, task, model, requests, prompt_tokens, completion_tokens FROM usage WHERE ?1 IS NULL OR day >= ?1 ORDER BY day, task, model")
+            .map_err(|e| format!("Failed to prepare query: {}", e))?;
+
+        let rows = stmt.query_map(params![since], |row| {
+            Ok(UsageRow {
+                day: row.get(0)?,
+                task: row.get(1)?,
+                model: row.get(2)?,
+                requests: row.get::<_, i64>(3)?.max(0) as u64,
+                prompt_tokens: row.get::<_, i64>(4)?.max(0) as u64,
+                completion_tokens: row.get::<_, i64>(5)?.max(0) as u64,
+            })
+        }).map_err(|e| format!("Failed to query usage: {}", e))?;
+
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Failed to collect usage: {}", e))
+    }
+
     // ==================== APP STATE (Key-Value) ====================
 
     /// Get app state value
@@ -536,6 +1396,42 @@ mod tests {
         store.delete_note(note.id).unwrap();
     }
 
+    #[test]
+    fn test_fts_match_query() {
+        assert_eq!(fts_match_query("budget q3").as_deref(), Some("\"budget\"* \"q3\"*"));
+        assert_eq!(fts_match_query("say \"hi\" OR"), Some("\"say\"* \"hi\"* \"OR\"*".to_string()));
+        assert_eq!(fts_match_query("  \"\" "), None);
+    }
+
+    #[test]
+    fn test_note_search_and_meeting_links() {
+        let dir = temp_dir().join("second-brain-note-search-test");
+        let store = UserStore::new(&dir).unwrap();
+
+        let note = store.create_note("Follow up on the quarterly budget review", &[]).unwrap();
+        let other = store.create_note("Lunch ideas", &[]).unwrap();
+
+        let found = store.search_notes("budg", 10).unwrap();
+        assert!(found.iter().any(|n| n.id == note.id));
+        assert!(!found.iter().any(|n| n.id == other.id));
+
+        // Index follows edits
+        store.update_note(other.id, "Budget spreadsheet link", &[]).unwrap();
+        assert!(store.search_notes("budget", 10).unwrap().iter().any(|n| n.id == other.id));
+
+        store.link_note_to_meeting(note.id, "meeting-abc").unwrap();
+        store.link_note_to_meeting(note.id, "meeting-abc").unwrap();
+        let linked = store.get_notes_for_meeting("meeting-abc").unwrap();
+        assert_eq!(linked.iter().filter(|n| n.id == note.id).count(), 1);
+
+        // Deleting a note drops it from search and its meeting links
+        store.delete_note(note.id).unwrap();
+        assert!(!store.search_notes("quarterly", 10).unwrap().iter().any(|n| n.id == note.id));
+        assert!(!store.get_notes_for_meeting("meeting-abc").unwrap().iter().any(|n| n.id == note.id));
+
+        store.delete_note(other.id).unwrap();
+    }
+
     #[test]
     fn test_app_state() {
         let dir = temp_dir();
@@ -550,4 +1446,44 @@ mod tests {
         let missing = store.get_state("nonexistent").unwrap();
         assert_eq!(missing, None);
     }
+
+    #[test]
+    fn test_prompt_templates() {
+        let dir = temp_dir().join("second-brain-prompt-template-test");
+        let store = UserStore::new(&dir).unwrap();
+
+        store.save_prompt_template("terse", "Be terse.\n\n{context}").unwrap();
+        let updated = store.save_prompt_template("terse", "Be very terse.\n\n{context}").unwrap();
+        assert_eq!(updated.template, "Be very terse.\n\n{context}");
+        assert_eq!(store.get_prompt_templates().unwrap().iter().filter(|t| t.name == "terse").count(), 1);
+
+        store.delete_prompt_template("terse").unwrap();
+        assert!(store.get_prompt_template("terse").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_usage_accumulates_per_day_and_task() {
+        use crate::llm_agent::LlmTask;
+        use crate::usage::TokenUsage;
+
+        let dir = temp_dir().join("second-brain-usage-test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let store = UserStore::new(&dir).unwrap();
+
+        let usage = |task, prompt_tokens, completion_tokens| LlmUsage {
+            task,
+            model: "gpt-4o".to_string(),
+            usage: TokenUsage { prompt_tokens, completion_tokens },
+        };
+        store.record_usage(&usage(LlmTask::Suggestions, 100, 20)).unwrap();
+        store.record_usage(&usage(LlmTask::Suggestions, 50, 10)).unwrap();
+        store.record_usage(&usage(LlmTask::Summary, 1000, 200)).unwrap();
+
+        let rows = store.get_usage(None).unwrap();
+        assert_eq!(rows.len(), 2);
+        let suggestions = rows.iter().find(|r| r.task == "suggestions").unwrap();
+        assert_eq!((suggestions.requests, suggestions.prompt_tokens, suggestions.completion_tokens), (2, 150, 30));
+
+        assert!(store.get_usage(Some("9999-01-01")).unwrap().is_empty());
+    }
 }
@@ -0,0 +1,333 @@
+//! Wake word - hands-free recording start
+//!
+//! Spots a configured phrase in the raw microphone stream with a small
+//! streaming zipformer keyword-spotting model (sherpa-onnx KWS).
+//!
+//! Input: 16kHz mono audio, fed continuously in small packets
+//! Output: the phrase, once each time it is heard
+
+use sherpa_rs::sherpa_rs_sys as sys;
+use std::collections::HashSet;
+use std::ffi::{CStr, CString};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::models::WAKE_WORD_MODEL_DIR;
+
+/// Phrase used until the user picks one
+pub const DEFAULT_PHRASE: &str = "hey second brain";
+/// Middle of the range; maps to sherpa-onnx's default keyword threshold
+pub const DEFAULT_SENSITIVITY: f32 = 0.5;
+
+/// Ignore repeats of the phrase for this long after a detection
+const DETECTION_COOLDOWN: Duration = Duration::from_secs(3);
+/// Marks the start of a word in the model's BPE token table
+const WORD_START: char = '▁';
+/// Keywords file written next to the model for the spotter to read
+const KEYWORDS_FILE: &str = "wake-word-keywords.txt";
+
+/// Wake word settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct WakeWordConfig {
+    pub phrase: String,
+    /// 0.0-1.0; higher fires more easily (and more often by mistake)
+    pub sensitivity: f32,
+    /// Start recording on detection instead of only emitting the event
+    pub auto_start: bool,
+}
+
+impl WakeWordConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.phrase.trim().is_empty() {
+            return Err("Wake word phrase cannot be empty".to_string());
+        }
+        if !(0.0..=1.0).contains(&self.sensitivity) {
+            return Err(format!("Wake word sensitivity must be between 0 and 1, got {}", self.sensitivity));
+        }
+        Ok(())
+    }
+}
+
+/// sherpa-onnx keyword threshold for a sensitivity (lower threshold = more sensitive)
+pub fn keywords_threshold(sensitivity: f32) -> f32 {
+    (0.5 * (1.0 - sensitivity.clamp(0.0, 1.0))).max(0.05)
+}
+
+/// Spell `phrase` with the model's BPE tokens as a keywords-file line
+///
+/// Uses greedy longest-match per word, which gives the same pieces as the model's
+/// BPE for ordinary English words. Fails if a word can't be spelled at all.
+pub fn encode_phrase(phrase: &str, vocab: &HashSet<String>) -> Result<String, String> {
+    let mut pieces: Vec<String> = Vec::new();
+    let mut words: Vec<String> = Vec::new();
+
+    for raw in phrase.split_whitespace() {
+        let word: String = raw.chars()
+            .filter(|c| c.is_alphanumeric() || *c == '\'')
+            .collect::<String>()
+            .to_uppercase();
+        if word.is_empty() {
+            continue;
+        }
+
+        let chars: Vec<char> = std::iter::once(WORD_START).chain(word.chars()).collect();
+        let mut start = 0;
+        while start < chars.len() {
+            let piece = (start + 1..=chars.len())
+                .rev()
+                .map(|end| chars[start..end].iter().collect::<String>())
+                .find(|piece| vocab.contains(piece))
+                .ok_or_else(|| format!("Wake phrase word '{}' can't be spelled with the model's tokens", raw))?;
+            start += piece.chars().count();
+            pieces.push(piece);
+        }
+        words.push(word);
+    }
+
+    if pieces.is_empty() {
+        return Err("Wake word phrase cannot be empty".to_string());
+    }
+    // "@" sets the name the spotter reports back
+    Ok(format!("{} @{}", pieces.join(" "), words.join("_")))
+}
+
+/// Tokens from a sherpa-onnx tokens.txt ("<token> <id>" per line)
+fn load_vocab(tokens_path: &Path) -> Result<HashSet<String>, String> {
+    let content = std::fs::read_to_string(tokens_path)
+        .map_err(|e| format!("Failed to read {:?}: {}", tokens_path, e))?;
+    Ok(content.lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_string)
+        .collect())
+}
+
+/// First `<prefix>*.onnx` in the model directory, preferring the int8 build
+fn find_model_file(dir: &Path, prefix: &str) -> Result<String, String> {
+    let mut candidates: Vec<String> = std::fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {:?}: {}", dir, e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with(prefix) && name.ends_with(".onnx"))
+        .collect();
+    candidates.sort_by_key(|name| (!name.contains(".int8."), name.clone()));
+    candidates.into_iter()
+        .next()
+        .map(|name| dir.join(name).to_string_lossy().to_string())
+        .ok_or_else(|| format!("Wake word model is missing its {} file", prefix))
+}
+
+/// Model and keyword files for the spotter
+struct SpotterFiles {
+    encoder: String,
+    decoder: String,
+    joiner: String,
+    tokens: String,
+    keywords: String,
+}
+
+/// sherpa-onnx keyword spotter with one long-lived online stream
+///
+/// sherpa-rs' `KeywordSpot::extract_keyword` marks its only stream finished on
+/// every call, so it can't spot in a continuous mic feed; this keeps the stream
+/// open and resets it after each detection instead.
+struct KeywordStream {
+    spotter: *const sys::SherpaOnnxKeywordSpotter,
+    stream: *const sys::SherpaOnnxOnlineStream,
+}
+
+// The raw handles are only touched through &mut self
+unsafe impl Send for KeywordStream {}
+
+impl KeywordStream {
+    fn new(files: &SpotterFiles, threshold: f32) -> Result<Self, String> {
+        let cstring = |s: &str| CString::new(s).map_err(|e| format!("Invalid wake word model path: {}", e));
+        let encoder = cstring(&files.encoder)?;
+        let decoder = cstring(&files.decoder)?;
+        let joiner = cstring(&files.joiner)?;
+        let tokens = cstring(&files.tokens)?;
+        let keywords = cstring(&files.keywords)?;
+        let provider = cstring("cpu")?;
+
+        // Same layout sherpa-rs builds; unused model kinds stay zeroed
+        let config = unsafe {
+            sys::SherpaOnnxKeywordSpotterConfig {
+                feat_config: sys::SherpaOnnxFeatureConfig { sample_rate: 16000, feature_dim: 80 },
+                keywords_buf: std::mem::zeroed(),
+                keywords_buf_size: 0,
+                keywords_file: keywords.as_ptr(),
+                max_active_paths: 4,
+                keywords_score: 3.0,
+                keywords_threshold: threshold,
+                num_trailing_blanks: 1,
+                model_config: sys::SherpaOnnxOnlineModelConfig {
+                    transducer: sys::SherpaOnnxOnlineTransducerModelConfig {
+                        encoder: encoder.as_ptr(),
+                        decoder: decoder.as_ptr(),
+                        joiner: joiner.as_ptr(),
+                    },
+                    num_threads: 1,  // Runs next to capture; keep it cheap
+                    provider: provider.as_ptr(),
+                    debug: 0,
+                    tokens: tokens.as_ptr(),
+                    paraformer: std::mem::zeroed(),
+                    zipformer2_ctc: std::mem::zeroed(),
+                    model_type: std::mem::zeroed(),
+                    modeling_unit: std::mem::zeroed(),
+                    bpe_vocab: std::mem::zeroed(),
+                    tokens_buf: std::mem::zeroed(),
+                    tokens_buf_size: std::mem::zeroed(),
+                    nemo_ctc: std::mem::zeroed(),
+                },
+            }
+        };
+
+        let spotter = unsafe { sys::SherpaOnnxCreateKeywordSpotter(&config) };
+        if spotter.is_null() {
+            return Err("Keyword spotter init error: failed to create spotter".to_string());
+        }
+        let stream = unsafe { sys::SherpaOnnxCreateKeywordStream(spotter) };
+        if stream.is_null() {
+            unsafe { sys::SherpaOnnxDestroyKeywordSpotter(spotter) };
+            return Err("Keyword spotter init error: failed to create stream".to_string());
+        }
+        Ok(Self { spotter, stream })
+    }
+
+    /// Append samples and decode what's ready; returns a keyword when one just fired
+    fn accept(&mut self, samples: &[f32]) -> Option<String> {
+        unsafe {
+            sys::SherpaOnnxOnlineStreamAcceptWaveform(self.stream, 16000, samples.as_ptr(), samples.len() as i32);
+
+            let mut keyword = None;
+            while sys::SherpaOnnxIsKeywordStreamReady(self.spotter, self.stream) == 1 {
+                sys::SherpaOnnxDecodeKeywordStream(self.spotter, self.stream);
+                let result = sys::SherpaOnnxGetKeywordResult(self.spotter, self.stream);
+                if result.is_null() {
+                    continue;
+                }
+                let found = if (*result).keyword.is_null() {
+                    String::new()
+                } else {
+                    CStr::from_ptr((*result).keyword).to_string_lossy().into_owned()
+                };
+                sys::SherpaOnnxDestroyKeywordResult(result);
+                if !found.trim().is_empty() {
+                    // Start over so the same utterance isn't reported again
+                    sys::SherpaOnnxResetKeywordStream(self.spotter, self.stream);
+                    keyword = Some(found);
+                }
+            }
+            keyword
+        }
+    }
+}
+
+impl Drop for KeywordStream {
+    fn drop(&mut self) {
+        unsafe {
+            sys::SherpaOnnxDestroyOnlineStream(self.stream);
+            sys::SherpaOnnxDestroyKeywordSpotter(self.spotter);
+        }
+    }
+}
+
+/// Keyword spotter for a single wake phrase
+pub struct WakeWordEngine {
+    spotter: KeywordStream,
+    config: WakeWordConfig,
+    last_detection: Option<Instant>,
+}
+
+impl WakeWordEngine {
+    pub fn new(models_dir: &Path, config: WakeWordConfig) -> Result<Self, String> {
+        config.validate()?;
+
+        let model_dir = models_dir.join(WAKE_WORD_MODEL_DIR);
+        let tokens_path = model_dir.join("tokens.txt");
+        if !tokens_path.exists() {
+            return Err(format!("Wake word model not found: {:?}", model_dir));
+        }
+
+        let keywords = encode_phrase(&config.phrase, &load_vocab(&tokens_path)?)?;
+        let keywords_path = model_dir.join(KEYWORDS_FILE);
+        std::fs::write(&keywords_path, format!("{}\n", keywords))
+            .map_err(|e| format!("Failed to write wake word keywords: {}", e))?;
+
+        let files = SpotterFiles {
+            encoder: find_model_file(&model_dir, "encoder")?,
+            decoder: find_model_file(&model_dir, "decoder")?,
+            joiner: find_model_file(&model_dir, "joiner")?,
+            tokens: tokens_path.to_string_lossy().to_string(),
+            keywords: keywords_path.to_string_lossy().to_string(),
+        };
+        let spotter = KeywordStream::new(&files, keywords_threshold(config.sensitivity))?;
+
+        println!("[WakeWord] Listening for '{}' ({})", config.phrase, keywords);
+        Ok(Self {
+            spotter,
+            config,
+            last_detection: None,
+        })
+    }
+
+    pub fn config(&self) -> &WakeWordConfig {
+        &self.config
+    }
+
+    /// Feed 16kHz mono mic samples; returns the phrase when it was just heard
+    pub fn process(&mut self, samples: &[f32]) -> Option<String> {
+        if samples.is_empty() {
+            return None;
+        }
+        self.spotter.accept(samples)?;
+
+        // One utterance of the phrase can be reported over several packets
+        if self.last_detection.is_some_and(|t| t.elapsed() < DETECTION_COOLDOWN) {
+            return None;
+        }
+        self.last_detection = Some(Instant::now());
+        Some(self.config.phrase.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vocab(tokens: &[&str]) -> HashSet<String> {
+        tokens.iter().map(|t| t.to_string()).collect()
+    }
+
+    #[test]
+    fn test_encode_phrase_uses_longest_pieces() {
+        let vocab = vocab(&["▁HE", "▁H", "Y", "E", "▁SECOND", "▁BRA", "IN", "▁B", "R", "A", "I", "N"]);
+        let line = encode_phrase("Hey, second  brain!", &vocab).unwrap();
+        assert_eq!(line, "▁HE Y ▁SECOND ▁BRA IN @HEY_SECOND_BRAIN");
+    }
+
+    #[test]
+    fn test_encode_phrase_rejects_unspellable_and_empty() {
+        let vocab = vocab(&["▁HE", "Y"]);
+        assert!(encode_phrase("hey you", &vocab).is_err());
+        assert!(encode_phrase("  ?! ", &vocab).is_err());
+    }
+
+    #[test]
+    fn test_sensitivity_maps_to_threshold() {
+        assert!((keywords_threshold(DEFAULT_SENSITIVITY) - 0.25).abs() < f32::EPSILON);
+        assert!(keywords_threshold(1.0) < keywords_threshold(0.2));
+        assert!((keywords_threshold(5.0) - 0.05).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let config = WakeWordConfig {
+            phrase: DEFAULT_PHRASE.to_string(),
+            sensitivity: DEFAULT_SENSITIVITY,
+            auto_start: false,
+        };
+        assert!(config.validate().is_ok());
+        assert!(WakeWordConfig { phrase: " ".to_string(), ..config.clone() }.validate().is_err());
+        assert!(WakeWordConfig { sensitivity: 1.5, ..config }.validate().is_err());
+    }
+}
@@ -1,10 +1,25 @@
 //! Web crawler module for searching and fetching web content.
 //!
-//! Uses spider crate for web crawling and duckduckgo_search for web search.
+//! Uses spider crate for web crawling and a configurable search backend
+//! (DuckDuckGo by default, or SearXNG / Brave) for web search.
 //! Converts web pages to markdown for storage in the knowledge base.
 
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+const BRAVE_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+
+/// Prefix of the error returned when robots.txt disallows a URL
+pub const ROBOTS_BLOCKED_ERROR: &str = "Blocked by robots.txt";
+
+/// How long a fetched robots.txt is trusted before re-fetching
+const ROBOTS_CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// User-agent token matched against robots.txt groups
+const ROBOTS_AGENT_TOKEN: &str = "secondbrain";
 
 /// Result from a web search
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -12,6 +27,56 @@ pub struct SearchResult {
     pub title: String,
     pub url: String,
     pub snippet: String,
+    /// Backend that produced this result ("duckduckgo", "searxng", "brave")
+    #[serde(default)]
+    pub backend: String,
+}
+
+/// A web search provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SearchBackend {
+    DuckDuckGo,
+    SearXNG { base_url: String },
+    Brave { api_key: String },
+}
+
+impl SearchBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SearchBackend::DuckDuckGo => "duckduckgo",
+            SearchBackend::SearXNG { .. } => "searxng",
+            SearchBackend::Brave { .. } => "brave",
+        }
+    }
+
+    /// Build a backend from its settings name, or None if it is unknown or not configured
+    pub fn from_settings(name: &str, searxng_url: &str, brave_api_key: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "duckduckgo" | "ddg" | "" => Some(SearchBackend::DuckDuckGo),
+            "searxng" if !searxng_url.trim().is_empty() => Some(SearchBackend::SearXNG {
+                base_url: searxng_url.trim().trim_end_matches('/').to_string(),
+            }),
+            "brave" if !brave_api_key.trim().is_empty() => Some(SearchBackend::Brave {
+                api_key: brave_api_key.trim().to_string(),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Fallback order: the selected backend first, then every other configured one.
+    /// DuckDuckGo needs no configuration, so it is always the last resort.
+    pub fn chain(primary: &str, searxng_url: &str, brave_api_key: &str) -> Vec<Self> {
+        let mut chain = Vec::new();
+        for name in [primary, "searxng", "brave", "duckduckgo"] {
+            if let Some(backend) = Self::from_settings(name, searxng_url, brave_api_key) {
+                if !chain.contains(&backend) {
+                    chain.push(backend);
+                }
+            }
+        }
+        chain
+    }
 }
 
 /// A crawled web page with content
@@ -29,10 +94,18 @@ pub struct CrawledPage {
 pub struct CrawlerConfig {
     /// User agent to use for requests
     pub user_agent: String,
-    /// Request timeout in seconds
+    /// Page fetch timeout in seconds (covers connecting and reading the body)
     pub timeout_secs: u64,
+    /// Largest page body accepted, in bytes; bigger pages are refused
+    pub max_bytes: usize,
+    /// Timeout for web search requests, kept shorter than page fetches
+    pub search_timeout_secs: u64,
     /// Whether to respect robots.txt
     pub respect_robots_txt: bool,
+    /// Sustained requests per second allowed to a single host
+    pub requests_per_second: f64,
+    /// Requests allowed to a host in a burst before spacing kicks in
+    pub burst: u32,
 }
 
 impl Default for CrawlerConfig {
@@ -40,14 +113,194 @@ impl Default for CrawlerConfig {
         Self {
             user_agent: "SecondBrain/1.0 (Meeting Assistant)".to_string(),
             timeout_secs: 30,
+            max_bytes: 5 * 1024 * 1024,
+            search_timeout_secs: 10,
             respect_robots_txt: true,
+            requests_per_second: 1.0,
+            burst: 2,
+        }
+    }
+}
+
+/// Parsed robots.txt rules that apply to this crawler
+#[derive(Debug, Clone, Default)]
+struct RobotsRules {
+    /// (is_allow, path pattern)
+    rules: Vec<(bool, String)>,
+    crawl_delay: Option<f64>,
+}
+
+impl RobotsRules {
+    /// Parse robots.txt, keeping the group for our agent token, else the `*` group
+    fn parse(body: &str) -> Self {
+        let mut ours = RobotsRules::default();
+        let mut wildcard = RobotsRules::default();
+        let mut found_ours = false;
+
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            if key == "user-agent" {
+                // A user-agent line after rules starts a new group
+                if in_rules {
+                    group_agents.clear();
+                    in_rules = false;
+                }
+                group_agents.push(value.to_lowercase());
+                continue;
+            }
+
+            in_rules = true;
+            let is_ours = group_agents.iter().any(|a| a == ROBOTS_AGENT_TOKEN);
+            let is_wildcard = group_agents.iter().any(|a| a == "*");
+            if is_ours {
+                found_ours = true;
+            }
+            let targets: Vec<&mut RobotsRules> = match (is_ours, is_wildcard) {
+                (true, true) => vec![&mut ours, &mut wildcard],
+                (true, false) => vec![&mut ours],
+                (false, true) => vec![&mut wildcard],
+                (false, false) => continue,
+            };
+
+            for target in targets {
+                match key.as_str() {
+                    // An empty Disallow means allow everything, so it adds no rule
+                    "disallow" if !value.is_empty() => target.rules.push((false, value.to_string())),
+                    "allow" if !value.is_empty() => target.rules.push((true, value.to_string())),
+                    "crawl-delay" => target.crawl_delay = value.parse().ok(),
+                    _ => {}
+                }
+            }
+        }
+
+        if found_ours { ours } else { wildcard }
+    }
+
+    /// Longest matching pattern wins; Allow wins ties
+    fn is_allowed(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            if robots_pattern_matches(pattern, path) {
+                let len = pattern.len();
+                best = match best {
+                    Some((best_len, best_allow)) if best_len > len || (best_len == len && best_allow) => {
+                        Some((best_len, best_allow))
+                    }
+                    _ => Some((len, *allow)),
+                };
+            }
         }
+        best.map(|(_, allow)| allow).unwrap_or(true)
+    }
+}
+
+/// Match a robots.txt path pattern supporting `*` wildcards and a trailing `$` anchor
+fn robots_pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let last = parts.len() - 1;
+    let mut pos = 0;
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            if !path.starts_with(part) {
+                return false;
+            }
+            pos = part.len();
+        } else if anchored && i == last {
+            // The final segment of an anchored pattern must end the path
+            return path.len() >= pos + part.len() && path.ends_with(part);
+        } else if let Some(found) = path[pos..].find(part) {
+            pos += found + part.len();
+        } else {
+            return false;
+        }
+    }
+
+    !anchored || pos == path.len()
+}
+
+/// Token bucket spacing requests to one host
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Shared robots.txt cache and per-host rate limiter
+///
+/// Crawlers are created per request, so this lives in app state and is handed
+/// to each crawler to keep politeness consistent across commands.
+#[derive(Debug, Default)]
+pub struct CrawlPoliteness {
+    robots: Mutex<HashMap<String, (Instant, RobotsRules)>>,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+}
+
+impl CrawlPoliteness {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve a request slot for `host`, returning how long the caller must wait
+    fn reserve(&self, host: &str, rate: f64, burst: u32, min_interval: Option<f64>) -> Duration {
+        // A robots Crawl-delay lowers the allowed rate for that host
+        let rate = match min_interval {
+            Some(delay) if delay > 0.0 => rate.min(1.0 / delay),
+            _ => rate,
+        }.max(0.01);
+        let capacity = burst.max(1) as f64;
+
+        let mut buckets = self.buckets.lock();
+        let now = Instant::now();
+        let bucket = buckets.entry(host.to_string()).or_insert(TokenBucket {
+            tokens: capacity,
+            last_refill: now,
+        });
+
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.last_refill = now;
+
+        // Take the token now (possibly going negative) so concurrent callers queue up behind us
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / rate)
+        }
+    }
+
+    fn cached_robots(&self, host: &str) -> Option<RobotsRules> {
+        let cache = self.robots.lock();
+        cache.get(host)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < ROBOTS_CACHE_TTL)
+            .map(|(_, rules)| rules.clone())
+    }
+
+    fn store_robots(&self, host: &str, rules: RobotsRules) {
+        self.robots.lock().insert(host.to_string(), (Instant::now(), rules));
     }
 }
 
 /// Web crawler for searching and fetching content
 pub struct WebCrawler {
     config: CrawlerConfig,
+    /// Search backends in fallback order
+    backends: Vec<SearchBackend>,
+    politeness: Arc<CrawlPoliteness>,
 }
 
 impl WebCrawler {
@@ -58,75 +311,195 @@ impl WebCrawler {
 
     /// Create a new web crawler with custom config
     pub fn with_config(config: CrawlerConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            backends: vec![SearchBackend::DuckDuckGo],
+            politeness: Arc::new(CrawlPoliteness::new()),
+        }
     }
 
-    /// Search DuckDuckGo and return results
-    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
-        use duckduckgo_search::DuckDuckGoSearch;
+    /// Share a robots.txt cache and rate limiter with other crawlers
+    pub fn with_politeness(mut self, politeness: Arc<CrawlPoliteness>) -> Self {
+        self.politeness = politeness;
+        self
+    }
 
+    /// Replace the search backends (tried in order)
+    pub fn with_backends(mut self, backends: Vec<SearchBackend>) -> Self {
+        if !backends.is_empty() {
+            self.backends = backends;
+        }
+        self
+    }
+
+    /// Search the web, falling back to the next backend when one fails or finds nothing
+    pub async fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
         println!("[WebSearch] Searching for: {}", query);
 
-        let search = DuckDuckGoSearch::new();
+        for backend in &self.backends {
+            match self.search_with(backend, query, limit).await {
+                Ok(results) if !results.is_empty() => {
+                    println!("[WebSearch] Returning {} results from {}", results.len(), backend.name());
+                    return Ok(results);
+                }
+                Ok(_) => println!("[WebSearch] {} returned no results", backend.name()),
+                Err(e) => eprintln!("[WebSearch] {} search failed: {}", backend.name(), e),
+            }
+        }
 
-        // DuckDuckGoSearch::search takes &str
-        let results = match search.search(query).await {
-            Ok(r) => r,
-            Err(e) => {
-                eprintln!("[WebSearch] DuckDuckGo search failed: {}", e);
-                // Return empty results instead of error - search is best-effort
-                return Ok(vec![]);
+        // Return empty results instead of error - search is best-effort
+        Ok(vec![])
+    }
+
+    /// Search a single backend, surfacing its errors (no fallback)
+    pub async fn search_with(
+        &self,
+        backend: &SearchBackend,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>, String> {
+        let results = match backend {
+            SearchBackend::DuckDuckGo => {
+                // The DuckDuckGo crate manages its own client, so bound it from outside
+                let timeout = Duration::from_secs(self.config.search_timeout_secs);
+                tokio::time::timeout(timeout, self.search_duckduckgo(query)).await
+                    .map_err(|_| format!("DuckDuckGo search timed out after {}s", timeout.as_secs()))??
             }
+            SearchBackend::SearXNG { base_url } => self.search_searxng(base_url, query).await?,
+            SearchBackend::Brave { api_key } => self.search_brave(api_key, query, limit).await?,
         };
 
+        Ok(results
+            .into_iter()
+            .filter(|r| !r.title.is_empty() && !r.url.is_empty())
+            .take(limit)
+            .collect())
+    }
+
+    async fn search_duckduckgo(&self, query: &str) -> Result<Vec<SearchResult>, String> {
+        use duckduckgo_search::DuckDuckGoSearch;
+
+        let search = DuckDuckGoSearch::new();
+
+        // DuckDuckGoSearch::search takes &str
+        let results = search.search(query).await
+            .map_err(|e| format!("DuckDuckGo search failed: {}", e))?;
+
         println!("[WebSearch] Got {} raw results", results.len());
 
-        // Convert to our SearchResult type, taking only 'limit' results
-        let search_results: Vec<SearchResult> = results
+        Ok(results
             .into_iter()
-            .take(limit)
-            .filter(|(title, url)| !title.is_empty() && !url.is_empty())
             .map(|(title, url)| SearchResult {
                 title,
                 url,
                 snippet: String::new(), // DuckDuckGo crate doesn't provide snippets
+                backend: "duckduckgo".to_string(),
             })
-            .collect();
+            .collect())
+    }
 
-        println!("[WebSearch] Returning {} filtered results", search_results.len());
+    async fn search_searxng(&self, base_url: &str, query: &str) -> Result<Vec<SearchResult>, String> {
+        let url = format!("{}/search", base_url.trim_end_matches('/'));
+        let request = self.http_client(self.config.search_timeout_secs)?
+            .get(&url)
+            .query(&[("q", query), ("format", "json")]);
+        let body = fetch_json(request).await?;
+
+        let results = body.get("results")
+            .and_then(|r| r.as_array())
+            .ok_or("SearXNG response has no results (is the json format enabled?)")?;
+
+        Ok(results
+            .iter()
+            .map(|r| SearchResult {
+                title: json_str(r, "title"),
+                url: json_str(r, "url"),
+                snippet: json_str(r, "content"),
+                backend: "searxng".to_string(),
+            })
+            .collect())
+    }
 
-        Ok(search_results)
+    async fn search_brave(&self, api_key: &str, query: &str, limit: usize) -> Result<Vec<SearchResult>, String> {
+        let count = limit.clamp(1, 20).to_string();
+        let request = self.http_client(self.config.search_timeout_secs)?
+            .get(BRAVE_SEARCH_URL)
+            .header("Accept", "application/json")
+            .header("X-Subscription-Token", api_key)
+            .query(&[("q", query), ("count", count.as_str())]);
+        let body = fetch_json(request).await?;
+
+        let results = body.get("web")
+            .and_then(|w| w.get("results"))
+            .and_then(|r| r.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(results
+            .iter()
+            .map(|r| SearchResult {
+                title: json_str(r, "title"),
+                url: json_str(r, "url"),
+                snippet: json_str(r, "description"),
+                backend: "brave".to_string(),
+            })
+            .collect())
     }
 
-    /// Crawl a single URL and return its content
-    pub async fn crawl_url(&self, url: &str) -> Result<CrawledPage, String> {
-        // Use reqwest directly for simpler single-page fetching
-        let client = reqwest::Client::builder()
+    fn http_client(&self, timeout_secs: u64) -> Result<reqwest::Client, String> {
+        reqwest::Client::builder()
             .user_agent(&self.config.user_agent)
-            .timeout(Duration::from_secs(self.config.timeout_secs))
+            .timeout(Duration::from_secs(timeout_secs))
             .build()
-            .map_err(|e| format!("Failed to create client: {}", e))?;
+            .map_err(|e| format!("Failed to create client: {}", e))
+    }
 
-        let response = client
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to fetch URL: {}", e))?;
+    /// Crawl a single URL and return its content
+    pub async fn crawl_url(&self, url: &str) -> Result<CrawledPage, String> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+        let host = parsed.host_str()
+            .ok_or_else(|| format!("Invalid URL {}: missing host", url))?
+            .to_lowercase();
 
-        if !response.status().is_success() {
-            return Err(format!("HTTP error: {}", response.status()));
+        // Use reqwest directly for simpler single-page fetching
+        let client = self.http_client(self.config.timeout_secs)?;
+
+        let mut crawl_delay = None;
+        if self.config.respect_robots_txt {
+            let rules = self.robots_rules(&client, &parsed, &host).await;
+            let path = match parsed.query() {
+                Some(q) => format!("{}?{}", parsed.path(), q),
+                None => parsed.path().to_string(),
+            };
+            if !rules.is_allowed(&path) {
+                return Err(format!("{}: {} disallows {}", ROBOTS_BLOCKED_ERROR, host, path));
+            }
+            crawl_delay = rules.crawl_delay;
         }
 
-        let html = response
-            .text()
-            .await
-            .map_err(|e| format!("Failed to read response: {}", e))?;
+        let wait = self.politeness.reserve(&host, self.config.requests_per_second, self.config.burst, crawl_delay);
+        if !wait.is_zero() {
+            println!("[WebCrawler] Rate limiting {} for {:?}", host, wait);
+            tokio::time::sleep(wait).await;
+        }
+
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let html = tokio::time::timeout(timeout, self.fetch_page(&client, url)).await
+            .map_err(|_| format!("Network error fetching {}: timed out after {}s", url, timeout.as_secs()))??;
 
-        // Extract title from HTML
-        let title = extract_title(&html).unwrap_or_else(|| url.to_string());
+        // Markdown conversion is CPU-bound on large pages, keep it off the async workers
+        let (title, markdown, html) = tokio::task::spawn_blocking({
+            let url = url.to_string();
+            move || {
+                // Extract title from HTML
+                let title = extract_title(&html).unwrap_or(url);
 
-        // Convert HTML to markdown
-        let markdown = html_to_markdown(&html);
+                // Convert HTML to markdown
+                let markdown = html_to_markdown(&html);
+                (title, markdown, html)
+            }
+        }).await.map_err(|e| format!("Failed to convert page: {}", e))?;
 
         let crawled_at = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -142,6 +515,65 @@ impl WebCrawler {
         })
     }
 
+    /// Download a page body, refusing anything larger than `max_bytes`
+    async fn fetch_page(&self, client: &reqwest::Client, url: &str) -> Result<String, String> {
+        let max_bytes = self.config.max_bytes;
+
+        let mut response = client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| format!("Network error fetching {}: {}", url, e))?;
+
+        if !response.status().is_success() {
+            return Err(format!("HTTP error: {}", response.status()));
+        }
+
+        if let Some(length) = response.content_length() {
+            if length as usize > max_bytes {
+                return Err(format_too_large(url, length as usize, max_bytes));
+            }
+        }
+
+        // Content-Length can be missing or wrong, so enforce the cap while reading
+        let mut body: Vec<u8> = Vec::new();
+        while let Some(chunk) = response.chunk().await
+            .map_err(|e| format!("Network error reading {}: {}", url, e))?
+        {
+            if body.len() + chunk.len() > max_bytes {
+                return Err(format_too_large(url, body.len() + chunk.len(), max_bytes));
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        Ok(String::from_utf8_lossy(&body).into_owned())
+    }
+
+    /// Fetch (or reuse cached) robots.txt rules for a host
+    /// A missing or unreachable robots.txt allows everything, matching common crawler practice.
+    async fn robots_rules(&self, client: &reqwest::Client, url: &reqwest::Url, host: &str) -> RobotsRules {
+        if let Some(rules) = self.politeness.cached_robots(host) {
+            return rules;
+        }
+
+        let Ok(robots_url) = url.join("/robots.txt") else {
+            return RobotsRules::default();
+        };
+        let rules = match client.get(robots_url.clone()).send().await {
+            Ok(response) if response.status().is_success() => {
+                RobotsRules::parse(&response.text().await.unwrap_or_default())
+            }
+            Ok(_) => RobotsRules::default(),
+            Err(e) => {
+                eprintln!("[WebCrawler] Failed to fetch {}: {}", robots_url, e);
+                RobotsRules::default()
+            }
+        };
+
+        self.politeness.store_robots(host, rules.clone());
+        rules
+    }
+
     /// Crawl multiple URLs concurrently
     pub async fn crawl_urls(&self, urls: Vec<&str>) -> Vec<Result<CrawledPage, String>> {
         let mut results = Vec::with_capacity(urls.len());
@@ -166,6 +598,32 @@ impl Default for WebCrawler {
     }
 }
 
+fn format_too_large(url: &str, size: usize, max_bytes: usize) -> String {
+    format!(
+        "Page too large: {} is at least {} KB, over the {} KB crawl limit",
+        url,
+        size / 1024,
+        max_bytes / 1024
+    )
+}
+
+/// Send a request and parse the body as JSON, surfacing HTTP errors
+async fn fetch_json(request: reqwest::RequestBuilder) -> Result<serde_json::Value, String> {
+    let response = request.send().await
+        .map_err(|e| format!("Request failed: {}", e))?;
+    let status = response.status();
+    let text = response.text().await
+        .map_err(|e| format!("Failed to read response: {}", e))?;
+    if !status.is_success() {
+        return Err(format!("HTTP error {}: {}", status, text));
+    }
+    serde_json::from_str(&text).map_err(|e| format!("Invalid JSON response: {}", e))
+}
+
+fn json_str(value: &serde_json::Value, key: &str) -> String {
+    value.get(key).and_then(|v| v.as_str()).unwrap_or_default().to_string()
+}
+
 /// Extract title from HTML
 fn extract_title(html: &str) -> Option<String> {
     // Simple regex-free title extraction
@@ -420,4 +878,59 @@ mod tests {
         assert!(md.contains("Before"));
         assert!(md.contains("After"));
     }
+
+    #[test]
+    fn test_search_backend_chain_defaults_to_duckduckgo() {
+        let chain = SearchBackend::chain("duckduckgo", "", "");
+        assert_eq!(chain, vec![SearchBackend::DuckDuckGo]);
+
+        // Unconfigured selections fall back to DuckDuckGo rather than failing
+        let chain = SearchBackend::chain("searxng", "", "");
+        assert_eq!(chain, vec![SearchBackend::DuckDuckGo]);
+    }
+
+    #[test]
+    fn test_search_backend_chain_order() {
+        let chain = SearchBackend::chain("brave", "http://localhost:8888/", "key");
+        assert_eq!(chain, vec![
+            SearchBackend::Brave { api_key: "key".to_string() },
+            SearchBackend::SearXNG { base_url: "http://localhost:8888".to_string() },
+            SearchBackend::DuckDuckGo,
+        ]);
+    }
+
+    #[test]
+    fn test_robots_prefers_specific_agent_group() {
+        let robots = "User-agent: *\nDisallow: /\n\nUser-agent: SecondBrain\nDisallow: /private\n";
+        let rules = RobotsRules::parse(robots);
+        assert!(rules.is_allowed("/docs/page"));
+        assert!(!rules.is_allowed("/private/notes"));
+    }
+
+    #[test]
+    fn test_robots_longest_match_and_wildcards() {
+        let robots = "User-agent: *\nDisallow: /docs\nAllow: /docs/public\nDisallow: /*.pdf$\nCrawl-delay: 5\n";
+        let rules = RobotsRules::parse(robots);
+        assert!(!rules.is_allowed("/docs/secret"));
+        assert!(rules.is_allowed("/docs/public/intro"));
+        assert!(!rules.is_allowed("/files/report.pdf"));
+        assert!(rules.is_allowed("/files/report.pdf.html"));
+        assert_eq!(rules.crawl_delay, Some(5.0));
+    }
+
+    #[test]
+    fn test_robots_empty_disallow_allows_all() {
+        let rules = RobotsRules::parse("User-agent: *\nDisallow:\n");
+        assert!(rules.is_allowed("/anything"));
+    }
+
+    #[test]
+    fn test_rate_limiter_spaces_requests_per_host() {
+        let politeness = CrawlPoliteness::new();
+        assert!(politeness.reserve("example.com", 1.0, 2, None).is_zero());
+        assert!(politeness.reserve("example.com", 1.0, 2, None).is_zero());
+        assert!(!politeness.reserve("example.com", 1.0, 2, None).is_zero());
+        // Other hosts have their own bucket
+        assert!(politeness.reserve("other.org", 1.0, 2, None).is_zero());
+    }
 }
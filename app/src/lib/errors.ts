@@ -0,0 +1,37 @@
+/**
+ * Command errors
+ *
+ * Migrated commands reject with a `{ kind, message }` object (SecondBrainError on
+ * the Rust side); the rest still reject with a plain string.
+ */
+
+export type ErrorKind =
+  | 'not_initialized'
+  | 'model_missing'
+  | 'network'
+  | 'db'
+  | 'llm'
+  | 'invalid_input'
+  | 'not_found'
+  | 'other';
+
+export interface CommandError {
+  kind: ErrorKind;
+  message: string;
+}
+
+function isCommandError(error: unknown): error is CommandError {
+  return typeof error === 'object' && error !== null && 'kind' in error && 'message' in error;
+}
+
+/** Human-readable message for anything a command rejected with */
+export function errorMessage(error: unknown): string {
+  if (isCommandError(error)) return error.message;
+  if (error instanceof Error) return error.message;
+  return String(error);
+}
+
+/** The error's kind, or `other` for plain string errors */
+export function errorKind(error: unknown): ErrorKind {
+  return isCommandError(error) ? error.kind : 'other';
+}